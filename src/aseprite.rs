@@ -0,0 +1,236 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::freshness::{is_fresh, mark_fresh};
+use crate::i18n::t;
+use crate::manifest::ToolRequirement;
+use crate::tools::require_tool;
+use image::RgbaImage;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+pub const ASEPRITE_EXTENSIONS: &[&str] = &["aseprite", "ase"];
+// the sheet and data aseprite exported for every file, reused until the file changes
+pub const ASEPRITE_CACHE_DIR: &str = ".bonsai/cache/aseprite";
+
+/// Whether `path` is an Aseprite file the atlas is packed from.
+pub fn is_aseprite(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ASEPRITE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// How an animation steps through its frames, the direction of its Aseprite tag.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationDirection {
+    #[default]
+    Forward,
+    Reverse,
+    #[serde(rename = "pingpong")]
+    PingPong,
+    #[serde(rename = "pingpong_reverse")]
+    PingPongReverse,
+}
+
+impl AnimationDirection {
+    pub fn odin_name(self) -> &'static str {
+        match self {
+            AnimationDirection::Forward => "forward",
+            AnimationDirection::Reverse => "reverse",
+            AnimationDirection::PingPong => "ping_pong",
+            AnimationDirection::PingPongReverse => "ping_pong_reverse",
+        }
+    }
+}
+
+/// An animation drawn in Aseprite: a tag, or all frames of a file without tags.
+pub struct Animation {
+    pub name: String,
+    // sprite key and duration in milliseconds of every frame, in file order
+    pub frames: Vec<(String, u32)>,
+    pub direction: AnimationDirection,
+    // times the animation plays, 0 loops forever
+    pub repeat: u32,
+}
+
+/// The frames of one Aseprite file and the animations its tags make of them.
+pub struct AsepriteSheet {
+    pub frames: Vec<(RgbaImage, u32)>,
+    tags: Vec<FrameTag>,
+}
+
+impl AsepriteSheet {
+    /// The animations of the file, named `<stem>_<tag>` or `<stem>` for a file without tags,
+    /// with `key(index)` naming the sprite of each frame.
+    pub fn animations(&self, stem: &str, key: impl Fn(usize) -> String) -> Vec<Animation> {
+        let frames = |from: usize, to: usize| {
+            (from..=to.min(self.frames.len().saturating_sub(1)))
+                .map(|index| (key(index), self.frames[index].1))
+                .collect()
+        };
+        if self.tags.is_empty() {
+            // a single frame without tags is a still image
+            if self.frames.len() < 2 {
+                return Vec::new();
+            }
+            return vec![Animation {
+                name: stem.to_string(),
+                frames: frames(0, self.frames.len().saturating_sub(1)),
+                direction: AnimationDirection::Forward,
+                repeat: 0,
+            }];
+        }
+        self.tags
+            .iter()
+            .map(|tag| Animation {
+                name: format!("{}_{}", stem, tag.name),
+                frames: frames(tag.from, tag.to),
+                direction: tag.direction,
+                repeat: tag.repeat.as_deref().and_then(|r| r.parse().ok()).unwrap_or(0),
+            })
+            .collect()
+    }
+}
+
+// the parts of aseprite's `--format json-array --list-tags` data the atlas needs
+#[derive(Deserialize)]
+struct SheetData {
+    frames: Vec<SheetFrame>,
+    meta: SheetMeta,
+}
+
+#[derive(Deserialize)]
+struct SheetFrame {
+    frame: Rect,
+    duration: u32,
+}
+
+#[derive(Deserialize)]
+struct Rect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct SheetMeta {
+    #[serde(default, rename = "frameTags")]
+    frame_tags: Vec<FrameTag>,
+}
+
+#[derive(Deserialize)]
+struct FrameTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: AnimationDirection,
+    // written as a string, and only by aseprite 1.3 and later
+    repeat: Option<String>,
+}
+
+/// Exports Aseprite files through the aseprite CLI, found like any other `[tools]` entry.
+/// The program is only looked up once a file has to be exported.
+pub struct AsepriteExporter<'a> {
+    tools: &'a BTreeMap<String, ToolRequirement>,
+    program: Option<String>,
+}
+
+impl<'a> AsepriteExporter<'a> {
+    pub fn new(tools: &'a BTreeMap<String, ToolRequirement>) -> Self {
+        Self {
+            tools,
+            program: None,
+        }
+    }
+
+    /// The frames and tags of the Aseprite file at `path`, `logical` names its cached export.
+    pub fn load(&mut self, path: &Path, logical: &str, ui: &Ui) -> Result<AsepriteSheet, CustomError> {
+        let cache = Path::new(ASEPRITE_CACHE_DIR).join(logical);
+        let sheet_path = cache.with_extension("png");
+        let data_path = cache.with_extension("json");
+        let sources = [path.to_path_buf()];
+
+        if !is_fresh(&sources, &data_path)? || !sheet_path.is_file() {
+            self.export(path, &sheet_path, &data_path, ui)?;
+            mark_fresh(&sources, &data_path)?;
+        }
+
+        let data: SheetData = serde_json::from_str(&fs::read_to_string(&data_path)?)
+            .map_err(|e| {
+                CustomError::BuildError(t!("Invalid aseprite data for {}: {}", path.display(), e))
+            })?;
+        let sheet = image::open(&sheet_path)
+            .map_err(|e| {
+                CustomError::BuildError(t!("Failed to load {}: {}", format!("{:?}", sheet_path), e))
+            })?
+            .to_rgba8();
+
+        let mut frames = Vec::new();
+        for frame in &data.frames {
+            let rect = &frame.frame;
+            if rect.x + rect.w > sheet.width() || rect.y + rect.h > sheet.height() {
+                return Err(CustomError::BuildError(t!(
+                    "Aseprite frame {}x{} at {},{} is outside the sheet exported from {}",
+                    rect.w,
+                    rect.h,
+                    rect.x,
+                    rect.y,
+                    path.display()
+                )));
+            }
+            let image = image::imageops::crop_imm(&sheet, rect.x, rect.y, rect.w, rect.h).to_image();
+            frames.push((image, frame.duration));
+        }
+
+        Ok(AsepriteSheet {
+            frames,
+            tags: data.meta.frame_tags,
+        })
+    }
+
+    fn export(&mut self, path: &Path, sheet_path: &Path, data_path: &Path, ui: &Ui) -> Result<(), CustomError> {
+        let program = match &self.program {
+            Some(program) => program.clone(),
+            None => {
+                let program = require_tool(self.tools, "aseprite")?;
+                self.program = Some(program.clone());
+                program
+            }
+        };
+        if let Some(parent) = sheet_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        ui.cancel.check()?;
+        ui.status(&t!("Exporting {}...", path.display()));
+        let mut command = Command::new(&program);
+        command
+            .arg("--batch")
+            .arg(path)
+            .arg("--sheet")
+            .arg(sheet_path)
+            .arg("--data")
+            .arg(data_path)
+            .args(["--format", "json-array", "--list-tags"]);
+        let output = ui.cancel.output(&mut command, &ui.limits).map_err(|e| match e {
+            CustomError::Cancelled => CustomError::Cancelled,
+            e => CustomError::ProcessError(t!("Failed to start aseprite: {}", e)),
+        })?;
+        if !output.status.success() || !sheet_path.is_file() || !data_path.is_file() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // a half-written export would look up to date next time
+            let _ = fs::remove_file(data_path);
+            return Err(CustomError::BuildError(t!(
+                "aseprite failed to export {}: {}",
+                path.display(),
+                stderr.trim()
+            )));
+        }
+        Ok(())
+    }
+}
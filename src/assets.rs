@@ -1,11 +1,22 @@
 use crate::error::CustomError;
-use std::collections::{BTreeSet, HashMap};
+use crate::freshness::{is_fresh, mark_fresh, settings_stamp, write_if_changed};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use texture_packer::TexturePacker;
+use crate::aseprite::{ASEPRITE_CACHE_DIR, Animation};
+use crate::atlas_layout::Layout;
 use serde::Serialize;
-use crate::packer::{pack_font, GlyphMetrics};
+use crate::i18n::{t, tr};
+use crate::packer::{atlas_pages, bake_font, pack_font, page_names, BakedFont, BakedGlyph, GlyphMetrics};
 use crate::Ui;
+use crate::manifest::{AppOptions, AtlasOptions, ColorSpace, FontBakeOptions, FontMode};
+use crate::path_guard::is_contained;
+use crate::paths::ProjectPaths;
+use crate::pixel_ops::Trim;
+use crate::tilemap::{LayerContent, load_maps};
+use crate::version::VersionInfo;
+use colored::Colorize;
 use fontdue::FontSettings;
 
 enum AssetSearchMode<'a> {
@@ -22,24 +33,44 @@ pub struct SpriteMetadata {
     pub size_x: f32,
     pub size_y: f32,
     pub frames: i32,
+    pub page: i32,
+    // transparent pixels trimmed off the left, top, right and bottom of the image
+    pub trim: [f32; 4],
+    pub rotated: bool,
 }
 
 //sprite
 const SPRITE_OUTPUT_DIR: &str = "bonsai/generated/sprite.odin";
 const SPRITE_BINARY_DIR: &str = ".bonsai/cache/sprites/sprites.bin";
+// sprites.bin starts with the magic, then the version, the entry size and the entry count as u32s
+const SPRITE_BINARY_MAGIC: &[u8; 4] = b"BSPR";
+const SPRITE_BINARY_VERSION: u32 = 1;
+const SPRITE_BINARY_HEADER_SIZE: usize = 16;
+const SPRITE_BINARY_STRIDE: usize = 52; // 13 x f32
+const ATLAS_OUTPUT_DIR: &str = "bonsai/core/render/atlas";
+const ATLAS_STATS_PATH: &str = ".bonsai/cache/atlas_stats.json";
 //font
-const FONT_SRC_DIR: &str = "assets/fonts";
 const FONT_OUT_DIR: &str = "bonsai/generated/font.odin";
 const FONT_DATA_OUT_DIR: &str = ".bonsai/cache/fonts";
 const ADDITIONAL_FONT_ENUM: &str = "PixelCode";
 const ADDITIONAL_FONT_FILENAME: &str = "bonsai/core/render/PixelCode_9.ttf";
 const ADDITIONAL_FONT_DIR: &str = "bonsai/core/render";
+// fonts baked by [assets.fonts]: their atlases ship with the engine's, the metrics are cached
+const BAKED_FONT_DIR: &str = "bonsai/core/render/atlas/fonts";
+const BAKED_FONT_CACHE_DIR: &str = ".bonsai/cache/fonts/baked";
+const FONT_ATLAS_OUT_DIR: &str = "bonsai/generated/font_atlas.odin";
+const DEFAULT_MSDF_SIZE: u32 = 64;
+const DEFAULT_MSDF_RANGE: u32 = 8;
 //audio
-const AUDIO_SRC_DIR: &str = "assets/audio";
 const AUDIO_OUT_DIR: &str = "bonsai/generated/audio.odin";
+//app
+const APP_OUT_DIR: &str = "bonsai/generated/app.odin";
+const DEFAULT_APP_WIDTH: u32 = 1280;
+const DEFAULT_APP_HEIGHT: u32 = 720;
+//map
+const MAP_OUT_DIR: &str = "bonsai/generated/map.odin";
 //scene
-const SCENE_REG_OUT_DIR: &str = "source/game/scenes/generated_registry.odin";
-const SCENE_SRC_DIR: &str = "source/game/scenes";
+pub const SCENE_REGISTRY_FILE: &str = "generated_registry.odin";
 const SCENE_OUT_DIR: &str = "bonsai/generated/scene.odin";
 
 fn clean_key_suffix(mut clean_key: String) -> Result<String, CustomError> {
@@ -66,16 +97,19 @@ pub fn parse_font_stem(stem: &str) -> (String, Option<u8>) {
     (stem.to_string(), None)
 }
 
+// a fresh atlas is only reused while the metadata generated with it is still there
+pub fn sprite_metadata_exists() -> bool {
+    Path::new(SPRITE_OUTPUT_DIR).exists() && Path::new(SPRITE_BINARY_DIR).exists()
+}
+
 //this is separated from generate_asset_metadata, since there's a lot of "custom" logic here
 pub fn generate_sprite_metadata(
-    packer: &TexturePacker<image::RgbaImage, String>,
-    width: u32,
-    height: u32,
-    extruded_sprites: &BTreeSet<String>,
+    layout: &Layout,
+    extruded_sprites: &BTreeMap<String, u32>,
+    trimmed_sprites: &BTreeMap<String, Trim>,
+    animations: &[Animation],
+    options: &AtlasOptions,
 ) -> Result<Vec<u8>, CustomError> {
-    let mut sorted_frames: Vec<_> = packer.get_frames().iter().collect();
-    sorted_frames.sort_by_key(|(key, _frame)| *key);
-    
     let mut odin_code = String::new();
 
     odin_code.push_str("// NOTE: Machine generated by bonsai CLI.\n");
@@ -95,7 +129,8 @@ pub fn generate_sprite_metadata(
 
     odin_code.push_str("\n\n");
 
-    odin_code.push_str("import \"bonsai:core/gmath\"\n\n");
+    odin_code.push_str("import \"bonsai:core/gmath\"\n");
+    odin_code.push_str("import \"core:encoding/endian\"\n\n");
 
     odin_code.push_str("// @ref\n");
     odin_code.push_str("// Struct assigned to every sprite present on the atlas.\n");
@@ -103,6 +138,11 @@ pub fn generate_sprite_metadata(
     odin_code.push_str("\tuv:           gmath.Vector4,\n");
     odin_code.push_str("\tsize:         gmath.Vector2,\n");
     odin_code.push_str("\tframes:       int,\n");
+    odin_code.push_str("\tpage:         int,\n");
+    odin_code.push_str("\t// transparent pixels trimmed off the left, top, right and bottom of the image\n");
+    odin_code.push_str("\ttrim:         gmath.Vector4,\n");
+    odin_code.push_str("\t// stored turned 90 degrees clockwise in the atlas\n");
+    odin_code.push_str("\trotated:      bool,\n");
     odin_code.push_str("}\n\n");
 
     odin_code.push_str("// @ref\n");
@@ -110,27 +150,31 @@ pub fn generate_sprite_metadata(
     odin_code.push_str("SpriteName :: enum u32 {\n");
     odin_code.push_str("\tnil,\n");
 
+    let mut sprite_names = vec!["nil".to_string()];
     let mut metadata_array = Vec::new();
-    
+
     metadata_array.push(SpriteMetadata {
         u0: 0.0, v0: 0.0, u1: 0.0, v1: 0.0,
-        size_x: 0.0, size_y: 0.0, frames: 1,
+        size_x: 0.0, size_y: 0.0, frames: 1, page: 0,
+        trim: [0.0; 4], rotated: false,
     });
 
-    for (key, frame) in sorted_frames {
+    for (key, (page, frame)) in layout.frames() {
         let mut clean_key = key.replace("-", "_").replace(" ", "_");
         clean_key = clean_key_suffix(clean_key)?;
 
         odin_code.push_str(&format!("\t{},\n", clean_key));
+        sprite_names.push(clean_key);
 
-        let mut x = frame.frame.x;
-        let mut y = frame.frame.y;
-        let mut w = frame.frame.w;
-        let mut h = frame.frame.h;
+        let (width, height) = (layout.pages[page].width, layout.pages[page].height);
+        let mut x = frame.x;
+        let mut y = frame.y;
+        let mut w = frame.w;
+        let mut h = frame.h;
 
-        if extruded_sprites.contains(key) {
-            x += 1; y += 1;
-            w -= 2; h -= 2;
+        if let Some(&amount) = extruded_sprites.get(key) {
+            x += amount; y += amount;
+            w -= amount * 2; h -= amount * 2;
         }
 
         let u0 = x as f32 / width as f32;
@@ -138,6 +182,15 @@ pub fn generate_sprite_metadata(
         let u1 = (x + w) as f32 / width as f32;
         let v1 = (y + h) as f32 / height as f32;
 
+        // the size of the image itself: turned back upright, with its trimmed borders
+        let rotated = layout.pages[page].rotated.contains(key);
+        if rotated {
+            (w, h) = (h, w);
+        }
+        let cut = trimmed_sprites.get(key).copied().unwrap_or_default();
+        w += cut.left + cut.right;
+        h += cut.top + cut.bottom;
+
         let mut frames_count = 1;
         let normalized_key = key.replace("-", "_").replace(" ", "_");
         if let Some(last_underscore) = normalized_key.rfind('_') {
@@ -155,15 +208,67 @@ pub fn generate_sprite_metadata(
             size_x: w as f32,
             size_y: h as f32,
             frames: frames_count,
+            page: page as i32,
+            trim: [cut.left as f32, cut.top as f32, cut.right as f32, cut.bottom as f32],
+            rotated,
         });
     }
     odin_code.push_str("}\n\n");
 
-    let output_path = Path::new(SPRITE_OUTPUT_DIR);
-    fs::create_dir_all(output_path.parent().unwrap())?;
-    fs::write(output_path, odin_code)?;
+    let premultiplied = options.premultiply_alpha.unwrap_or(false);
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Number of atlas textures. One is `atlas.png`, more are `atlas_0.png`, `atlas_1.png`, ... followed by the `atlas_<group>.png` textures of `[assets.atlas.groups]`.\n");
+    odin_code.push_str(&format!("ATLAS_PAGE_COUNT :: {}\n\n", layout.pages.len()));
+    let page_names = page_names(layout);
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// File name of every atlas texture, indexed by `SpriteData.page`.\n");
+    odin_code.push_str("ATLAS_PAGES :: [ATLAS_PAGE_COUNT]string{\n");
+    for name in &page_names {
+        odin_code.push_str(&format!("\t\"{}\",\n", name));
+    }
+    odin_code.push_str("}\n\n");
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// `[assets.atlas.groups]` name of every atlas texture, empty for the textures of ungrouped sprites.\n");
+    odin_code.push_str("ATLAS_PAGE_GROUPS :: [ATLAS_PAGE_COUNT]string{\n");
+    for page in &layout.pages {
+        odin_code.push_str(&format!("\t\"{}\",\n", page.group.as_deref().unwrap_or("")));
+    }
+    odin_code.push_str("}\n\n");
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// True when the atlas colors are sRGB encoded, so it should be sampled through an sRGB texture format.\n");
+    odin_code.push_str(&format!(
+        "ATLAS_SRGB :: {}\n\n",
+        options.color_space.unwrap_or_default() == ColorSpace::Srgb
+    ));
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// True when the atlas colors are premultiplied by alpha, so it should be blended with `ONE, ONE_MINUS_SRC_ALPHA`.\n");
+    odin_code.push_str(&format!("ATLAS_PREMULTIPLIED_ALPHA :: {}\n\n", premultiplied));
+    push_sprite_reader(&mut odin_code);
+    push_animation_metadata(&mut odin_code, animations)?;
+
+    // same data as the runtime sprites.bin, baked in so it can be indexed at compile time
+    if options.uv_constants.unwrap_or(false) {
+        odin_code.push_str("// @ref\n");
+        odin_code.push_str("// Atlas UV, size and frame count of every sprite, known at compile time.\n");
+        odin_code.push_str("@(rodata)\n");
+        odin_code.push_str("SPRITE_DATA := [SpriteName]SpriteData {\n");
+        for (name, meta) in sprite_names.iter().zip(&metadata_array) {
+            odin_code.push_str(&format!(
+                "\t.{} = {{uv = {{{:?}, {:?}, {:?}, {:?}}}, size = {{{:?}, {:?}}}, frames = {}, page = {}, trim = {{{:?}, {:?}, {:?}, {:?}}}, rotated = {}}},\n",
+                name, meta.u0, meta.v0, meta.u1, meta.v1, meta.size_x, meta.size_y, meta.frames, meta.page,
+                meta.trim[0], meta.trim[1], meta.trim[2], meta.trim[3], meta.rotated
+            ));
+        }
+        odin_code.push_str("}\n");
+    }
+
+    write_if_changed(Path::new(SPRITE_OUTPUT_DIR), odin_code)?;
 
-    let mut bin_data = Vec::with_capacity(metadata_array.len() * 28); // 7 x f32
+    let mut bin_data = Vec::with_capacity(SPRITE_BINARY_HEADER_SIZE + metadata_array.len() * SPRITE_BINARY_STRIDE);
+    bin_data.extend_from_slice(SPRITE_BINARY_MAGIC);
+    bin_data.extend_from_slice(&SPRITE_BINARY_VERSION.to_le_bytes());
+    bin_data.extend_from_slice(&(SPRITE_BINARY_STRIDE as u32).to_le_bytes());
+    bin_data.extend_from_slice(&(metadata_array.len() as u32).to_le_bytes());
     for meta in &metadata_array {
         bin_data.extend_from_slice(&meta.u0.to_le_bytes());
         bin_data.extend_from_slice(&meta.v0.to_le_bytes());
@@ -173,19 +278,22 @@ pub fn generate_sprite_metadata(
         bin_data.extend_from_slice(&meta.size_y.to_le_bytes());
         let frames_f32 = meta.frames as f32;
         bin_data.extend_from_slice(&frames_f32.to_le_bytes());
+        let page_f32 = meta.page as f32;
+        bin_data.extend_from_slice(&page_f32.to_le_bytes());
+        for cut in meta.trim {
+            bin_data.extend_from_slice(&cut.to_le_bytes());
+        }
+        let rotated_f32 = if meta.rotated { 1.0f32 } else { 0.0 };
+        bin_data.extend_from_slice(&rotated_f32.to_le_bytes());
     }
 
-    let bin_path = Path::new(SPRITE_BINARY_DIR);
-    if let Some(parent) = bin_path.parent() { fs::create_dir_all(parent)?; }
-    fs::write(bin_path, &bin_data)?;
+    write_if_changed(Path::new(SPRITE_BINARY_DIR), &bin_data)?;
 
     Ok(bin_data)
 }
 
 //like above, too custom to abstract out
-fn generate_scene_registry_metadata(scene_src: &str, scene_out: &str) -> Result<(), CustomError> {
-    let scene_dir = Path::new(scene_src);
-    let registry_file = Path::new(scene_out);
+fn generate_scene_registry_metadata(scene_dir: &Path, registry_file: &Path) -> Result<(), CustomError> {
 
     let mut entries = vec![];
     if scene_dir.exists() {
@@ -231,11 +339,154 @@ fn generate_scene_registry_metadata(scene_src: &str, scene_out: &str) -> Result<
     }
     odin_code.push_str("}");
 
-    if let Some(parent) = registry_file.parent() {
-        fs::create_dir_all(parent)?;
+    write_if_changed(registry_file, odin_code)?;
+
+    Ok(())
+}
+
+// an odin identifier from an Aseprite file and tag name, or a map name
+fn odin_identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+// the `SpriteName` entry of a sprite key
+fn sprite_identifier(key: &str) -> Result<String, CustomError> {
+    clean_key_suffix(key.replace("-", "_").replace(" ", "_"))
+}
+
+// the sprites.bin header constants and a proc that reads the file, so the engine's loader always
+// matches the format this bonsai writes
+fn push_sprite_reader(odin_code: &mut String) {
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// First bytes of `sprites.bin`.\n");
+    odin_code.push_str(&format!(
+        "SPRITE_BIN_MAGIC :: \"{}\"\n\n",
+        String::from_utf8_lossy(SPRITE_BINARY_MAGIC)
+    ));
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Format version of `sprites.bin`, raised whenever its layout changes.\n");
+    odin_code.push_str(&format!("SPRITE_BIN_VERSION :: {}\n\n", SPRITE_BINARY_VERSION));
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Size of the `sprites.bin` header: the magic, then the version, the entry size and the entry count as little-endian `u32`s.\n");
+    odin_code.push_str(&format!("SPRITE_BIN_HEADER_SIZE :: {}\n\n", SPRITE_BINARY_HEADER_SIZE));
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Size of one `sprites.bin` entry in bytes, 13 little-endian `f32`s.\n");
+    odin_code.push_str(&format!("SPRITE_BIN_STRIDE :: {}\n\n", SPRITE_BINARY_STRIDE));
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Reads `sprites.bin` (or the metadata of a hot reloaded atlas) into `sprites`. Returns false, leaving `sprites` untouched,\n");
+    odin_code.push_str("// when the data has no valid header, was written in another format version or is cut short.\n");
+    odin_code.push_str("read_sprite_data :: proc(data: []u8, sprites: ^[SpriteName]SpriteData) -> bool {\n");
+    odin_code.push_str("\tif len(data) < SPRITE_BIN_HEADER_SIZE || string(data[:4]) != SPRITE_BIN_MAGIC do return false\n");
+    odin_code.push_str("\tversion, _ := endian.get_u32(data[4:], .Little)\n");
+    odin_code.push_str("\tstride, _ := endian.get_u32(data[8:], .Little)\n");
+    odin_code.push_str("\tcount, _ := endian.get_u32(data[12:], .Little)\n");
+    odin_code.push_str("\tif version != SPRITE_BIN_VERSION || stride < SPRITE_BIN_STRIDE do return false\n");
+    odin_code.push_str("\tif len(data) < SPRITE_BIN_HEADER_SIZE + int(count) * int(stride) do return false\n\n");
+    odin_code.push_str("\tfield :: proc(entry: []u8, index: int) -> f32 {\n");
+    odin_code.push_str("\t\tvalue, _ := endian.get_f32(entry[index * 4:], .Little)\n");
+    odin_code.push_str("\t\treturn value\n");
+    odin_code.push_str("\t}\n");
+    odin_code.push_str("\t// a hot reloaded atlas may list sprites this build has no name for yet\n");
+    odin_code.push_str("\tfor i in 0 ..< min(int(count), len(sprites^)) {\n");
+    odin_code.push_str("\t\tentry := data[SPRITE_BIN_HEADER_SIZE + i * int(stride):]\n");
+    odin_code.push_str("\t\tsprites[SpriteName(i)] = SpriteData {\n");
+    odin_code.push_str("\t\t\tuv      = {field(entry, 0), field(entry, 1), field(entry, 2), field(entry, 3)},\n");
+    odin_code.push_str("\t\t\tsize    = {field(entry, 4), field(entry, 5)},\n");
+    odin_code.push_str("\t\t\tframes  = int(field(entry, 6)),\n");
+    odin_code.push_str("\t\t\tpage    = int(field(entry, 7)),\n");
+    odin_code.push_str("\t\t\ttrim    = {field(entry, 8), field(entry, 9), field(entry, 10), field(entry, 11)},\n");
+    odin_code.push_str("\t\t\trotated = field(entry, 12) != 0,\n");
+    odin_code.push_str("\t\t}\n");
+    odin_code.push_str("\t}\n");
+    odin_code.push_str("\treturn true\n");
+    odin_code.push_str("}\n\n");
+}
+
+// the Aseprite animations: every frame in one table, and where each animation's frames are in it
+fn push_animation_metadata(odin_code: &mut String, animations: &[Animation]) -> Result<(), CustomError> {
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// How an animation steps through its frames, the direction of its Aseprite tag.\n");
+    odin_code.push_str("AnimationDirection :: enum u8 {\n");
+    for direction in ["forward", "reverse", "ping_pong", "ping_pong_reverse"] {
+        odin_code.push_str(&format!("\t{},\n", direction));
+    }
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// One frame of an animation and how long it is shown, in seconds.\n");
+    odin_code.push_str("AnimationFrame :: struct {\n");
+    odin_code.push_str("\tsprite:       SpriteName,\n");
+    odin_code.push_str("\tduration:     f32,\n");
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Frames `first` to `first + count - 1` of [`ANIMATION_FRAMES`](#animation_frames), played in `direction`.\n");
+    odin_code.push_str("AnimationData :: struct {\n");
+    odin_code.push_str("\tfirst:        int,\n");
+    odin_code.push_str("\tcount:        int,\n");
+    odin_code.push_str("\tdirection:    AnimationDirection,\n");
+    odin_code.push_str("\t// times the animation plays, 0 loops forever\n");
+    odin_code.push_str("\trepeat:       int,\n");
+    odin_code.push_str("}\n\n");
+
+    let mut names = vec!["nil".to_string()];
+    for animation in animations {
+        let name = odin_identifier(&animation.name);
+        if names.contains(&name) {
+            return Err(CustomError::BuildError(t!(
+                "Two Aseprite animations are named '{}'. Rename one of the files or tags",
+                name
+            )));
+        }
+        names.push(name);
+    }
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Enum containing every Aseprite animation: `<file>_<tag>` for each tag, `<file>` for a file without tags.\n");
+    odin_code.push_str("AnimationName :: enum u32 {\n");
+    for name in &names {
+        odin_code.push_str(&format!("\t{},\n", name));
     }
-    fs::write(registry_file, odin_code)?;
+    odin_code.push_str("}\n\n");
 
+    let frame_count: usize = animations.iter().map(|a| a.frames.len()).sum();
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Frames of every animation, each animation's in file order.\n");
+    odin_code.push_str("@(rodata)\n");
+    odin_code.push_str(&format!("ANIMATION_FRAMES := [{}]AnimationFrame {{\n", frame_count));
+    for animation in animations {
+        for (key, duration_ms) in &animation.frames {
+            let sprite = sprite_identifier(key)?;
+            odin_code.push_str(&format!(
+                "\t{{sprite = .{}, duration = {:?}}},\n",
+                sprite,
+                *duration_ms as f32 / 1000.0
+            ));
+        }
+    }
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Where the frames of every animation are in `ANIMATION_FRAMES`, and how they are played.\n");
+    odin_code.push_str("@(rodata)\n");
+    odin_code.push_str("ANIMATION_DATA := [AnimationName]AnimationData {\n");
+    odin_code.push_str("\t.nil = {},\n");
+    let mut first = 0;
+    for (name, animation) in names.iter().skip(1).zip(animations) {
+        odin_code.push_str(&format!(
+            "\t.{} = {{first = {}, count = {}, direction = .{}, repeat = {}}},\n",
+            name,
+            first,
+            animation.frames.len(),
+            animation.direction.odin_name(),
+            animation.repeat
+        ));
+        first += animation.frames.len();
+    }
+    odin_code.push_str("}\n\n");
     Ok(())
 }
 
@@ -261,7 +512,8 @@ pub fn generate_empty_sprite_metadata() -> Result<(), CustomError> {
 
     odin_code.push_str("\n\n");
 
-    odin_code.push_str("import \"bonsai:core/gmath\"\n\n");
+    odin_code.push_str("import \"bonsai:core/gmath\"\n");
+    odin_code.push_str("import \"core:encoding/endian\"\n\n");
 
     odin_code.push_str("// @ref\n");
     odin_code.push_str("// Struct assigned to every sprite present on the atlas.\n");
@@ -269,18 +521,22 @@ pub fn generate_empty_sprite_metadata() -> Result<(), CustomError> {
     odin_code.push_str("\tuv:           gmath.Vector4,\n");
     odin_code.push_str("\tsize:         gmath.Vector2,\n");
     odin_code.push_str("\tframes:       int,\n");
+    odin_code.push_str("\tpage:         int,\n");
+    odin_code.push_str("\t// transparent pixels trimmed off the left, top, right and bottom of the image\n");
+    odin_code.push_str("\ttrim:         gmath.Vector4,\n");
+    odin_code.push_str("\t// stored turned 90 degrees clockwise in the atlas\n");
+    odin_code.push_str("\trotated:      bool,\n");
     odin_code.push_str("}\n\n");
 
     odin_code.push_str("// @ref\n");
     odin_code.push_str("// Enum containing all sprite file names.\n");
     odin_code.push_str("SpriteName :: enum u32 {\n");
     odin_code.push_str("\tnil,\n");
-    odin_code.push_str("}\n");
-
-    let output_path = Path::new(SPRITE_OUTPUT_DIR);
+    odin_code.push_str("}\n\n");
+    push_sprite_reader(&mut odin_code);
+    push_animation_metadata(&mut odin_code, &[])?;
 
-    fs::create_dir_all(output_path.parent().unwrap())?;
-    fs::write(output_path, odin_code)?;
+    write_if_changed(Path::new(SPRITE_OUTPUT_DIR), odin_code)?;
 
     Ok(())
 }
@@ -336,41 +592,62 @@ pub fn generate_font_metadata(
     Ok(bin_data)
 }
 
-fn build_fonts(font_assets_dir: &Path, font_output_dir: &Path, ui: &Ui) {
+// fonts baked by `[assets.fonts]` are left to bake_configured_fonts
+fn build_fonts(
+    font_assets_dir: &Path,
+    font_output_dir: &Path,
+    baked: &BTreeMap<String, FontBakeOptions>,
+    ui: &Ui,
+) {
     if font_assets_dir.exists() {
         if let Ok(entries) = std::fs::read_dir(&font_assets_dir) {
             for entry in entries.flatten() {
                 let path = entry.path();
-                if let Some(ext) = path.extension() {
-                    if ext == "ttf" || ext == "otf" {
-                        let raw_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown");
-                        let clean_stem = raw_stem.replace("-", "_").replace(" ", "_");
-                        let (final_name, native_size_opt) = parse_font_stem(&clean_stem);
-
-                        let is_pixel = native_size_opt.is_some();
-                        let native_size = native_size_opt.unwrap_or(0);
-
-                        if let Err(e) = pack_font(&path, &final_name, is_pixel, native_size, &font_output_dir, ui) {
-                            ui.error(&format!("Failed to pack font at build time: {}", e));
-                        }
+                if is_font_file(&path) && !baked.contains_key(&font_stem(&path).0) {
+                    if entry.file_type().is_ok_and(|t| t.is_symlink())
+                        && !is_contained(&path, &[Path::new("."), font_assets_dir])
+                    {
+                        ui.message(&t!(
+                            "{} Skipped '{}': it links outside the project",
+                            "[WARNING]".yellow(),
+                            path.display()
+                        ));
+                        continue;
+                    }
+                    if let Err(e) = pack_font_file(&path, font_output_dir, ui) {
+                        ui.error(&t!("Failed to pack font at build time: {}", e));
                     }
                 }
             }
         }
     } else if ui.verbose {
-        ui.log("No fonts directory found, skipping build-time font packing.");
+        ui.log(tr("No fonts directory found, skipping build-time font packing."));
     }
 }
 
+fn is_font_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "ttf" || ext == "otf")
+}
+
+fn pack_font_file(path: &Path, font_output_dir: &Path, ui: &Ui) -> Result<(), CustomError> {
+    let (final_name, native_size_opt) = font_stem(path);
+
+    let is_pixel = native_size_opt.is_some();
+    let native_size = native_size_opt.unwrap_or(0);
+
+    pack_font(path, &final_name, is_pixel, native_size, font_output_dir, ui)?;
+    Ok(())
+}
+
 pub fn detect_native_size(font_bytes: &[u8], font_name: &str, ui: &Ui) {
     if ui.verbose {
-        ui.status(&format!("Analyzing {} to find true pixel size...", font_name));
+        ui.status(&t!("Analyzing {} to find true pixel size...", font_name));
     }
 
     let font = match fontdue::Font::from_bytes(font_bytes, FontSettings::default()) {
         Ok(f) => f,
         Err(_) => {
-            ui.error("Failed to parse font for size detection.");
+            ui.error(tr("Failed to parse font for size detection."));
             return;
         }
     };
@@ -394,58 +671,782 @@ pub fn detect_native_size(font_bytes: &[u8], font_name: &str, ui: &Ui) {
         if !is_blurry {
             found_sizes.push(test_size);
             if ui.verbose {
-                ui.log(&format!("Size for font {} found: {}px", font_name, test_size));
+                ui.log(&t!("Size for font {} found: {}px", font_name, test_size));
             }
         }
     }
 
     if found_sizes.is_empty() {
-        ui.log(&format!("No perfect pixel size found for {}.", font_name));
+        ui.log(&t!("No perfect pixel size found for {}.", font_name));
     } else {
-        ui.log(&format!("Recommendation: Rename your file to {}_{}.ttf/otf", font_name, found_sizes[0]));
+        ui.log(&t!("Recommendation: Rename your file to {}_{}.ttf/otf", font_name, found_sizes[0]));
     }
 }
 
-pub fn generate_assets(ui: &Ui) -> Result<(), CustomError> {
-    build_fonts(Path::new(FONT_SRC_DIR), Path::new(FONT_DATA_OUT_DIR), ui);
-    build_fonts(Path::new(ADDITIONAL_FONT_DIR), Path::new(FONT_DATA_OUT_DIR), ui);
+fn odin_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+//window and app settings from the [app] manifest section, read by the framework at startup
+pub fn generate_app_metadata(
+    app: &AppOptions,
+    project_name: &str,
+    versions: &VersionInfo,
+    web_packages: &[String],
+) -> Result<(), CustomError> {
+    let orientation = match app.orientation.as_deref().unwrap_or("any") {
+        "any" => "Any",
+        "landscape" => "Landscape",
+        "portrait" => "Portrait",
+        other => {
+            return Err(CustomError::ValidationError(t!(
+                "Invalid [app] orientation '{}' (expected any, landscape or portrait)",
+                other
+            )));
+        }
+    };
+
+    if let Some(icon) = &app.icon
+        && !Path::new(icon).exists()
+    {
+        return Err(CustomError::ValidationError(t!(
+            "App icon not found: {}",
+            icon
+        )));
+    }
+
+    let save_dir_name = app.save_dir_name.as_deref().unwrap_or(project_name);
+    if save_dir_name.is_empty()
+        || save_dir_name.starts_with('.')
+        || save_dir_name.contains(['/', '\\', ':'])
+    {
+        return Err(CustomError::ValidationError(t!(
+            "Invalid [app] save_dir_name '{}' (expected a single directory name)",
+            save_dir_name
+        )));
+    }
+
+    let mut odin_code = String::new();
+
+    odin_code.push_str("// NOTE: Machine generated by bonsai CLI.\n");
+    odin_code.push_str("package generated\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Window orientation requested by the `[app]` manifest section.\n");
+    odin_code.push_str("AppOrientation :: enum {\n\tAny,\n\tLandscape,\n\tPortrait,\n}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Application settings from the `[app]` section of `bonsai.toml`.\n");
+    odin_code.push_str(&format!(
+        "APP_TITLE :: {}\n",
+        odin_string(app.title.as_deref().unwrap_or(project_name))
+    ));
+    odin_code.push_str(&format!(
+        "APP_WIDTH :: {}\n",
+        app.width.unwrap_or(DEFAULT_APP_WIDTH)
+    ));
+    odin_code.push_str(&format!(
+        "APP_HEIGHT :: {}\n",
+        app.height.unwrap_or(DEFAULT_APP_HEIGHT)
+    ));
+    odin_code.push_str(&format!("APP_VSYNC :: {}\n", app.vsync.unwrap_or(true)));
+    odin_code.push_str(&format!(
+        "APP_ICON :: {}\n",
+        odin_string(app.icon.as_deref().unwrap_or(""))
+    ));
+    odin_code.push_str(&format!(
+        "APP_ORIENTATION :: AppOrientation.{}\n",
+        orientation
+    ));
+    odin_code.push_str(&format!(
+        "APP_SAVE_DIR_NAME :: {}\n\n",
+        odin_string(save_dir_name)
+    ));
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Versions this build was made with, also stored in `version.json` next to the build.\n");
+    odin_code.push_str(&format!("APP_VERSION :: {}\n", odin_string(&versions.project)));
+    odin_code.push_str(&format!(
+        "BONSAI_ENGINE_VERSION :: {}\n",
+        odin_string(&versions.engine)
+    ));
+    odin_code.push_str(&format!(
+        "BONSAI_CLI_VERSION :: {}\n\n",
+        odin_string(&versions.cli)
+    ));
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Asset packages from `[web.packages]`. On the web their files exist only after `bonsaiLoadPackage(name)` resolves.\n");
+    odin_code.push_str("WEB_PACKAGES :: [?]string{");
+    for name in web_packages {
+        odin_code.push_str(&format!("{}, ", odin_string(name)));
+    }
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Platform save directory: the `APP_SAVE_ROOT_ENV` environment variable joined with `APP_SAVE_SUBPATH`.\n");
+    odin_code.push_str("// On the web `APP_SAVE_SUBPATH` is an absolute path, mount it with IDBFS to persist it.\n");
+    for (condition, root_env, subpath) in [
+        ("when ODIN_OS == .Windows", "APPDATA", save_dir_name.to_string()),
+        (
+            "} else when ODIN_OS == .Darwin",
+            "HOME",
+            format!("Library/Application Support/{}", save_dir_name),
+        ),
+        (
+            "} else when ODIN_OS == .JS",
+            "",
+            format!("/saves/{}", save_dir_name),
+        ),
+        ("} else", "HOME", format!(".local/share/{}", save_dir_name)),
+    ] {
+        odin_code.push_str(&format!("{} {{\n", condition));
+        odin_code.push_str(&format!("\tAPP_SAVE_ROOT_ENV :: {}\n", odin_string(root_env)));
+        odin_code.push_str(&format!("\tAPP_SAVE_SUBPATH :: {}\n", odin_string(&subpath)));
+    }
+    odin_code.push_str("}\n");
+
+    write_if_changed(Path::new(APP_OUT_DIR), odin_code)?;
+
+    Ok(())
+}
+
+pub fn clean_assets(ui: &Ui) -> Result<(), CustomError> {
+    for page in atlas_pages(Path::new(ATLAS_OUTPUT_DIR)) {
+        fs::remove_file(&page)?;
+        if ui.verbose {
+            ui.log(&t!("Removed {}", page.display()));
+        }
+    }
+    for file in [
+        ATLAS_STATS_PATH,
+        SPRITE_BINARY_DIR,
+        SPRITE_OUTPUT_DIR,
+        FONT_OUT_DIR,
+        FONT_ATLAS_OUT_DIR,
+        AUDIO_OUT_DIR,
+        SCENE_OUT_DIR,
+        MAP_OUT_DIR,
+        APP_OUT_DIR,
+    ] {
+        if Path::new(file).exists() {
+            fs::remove_file(file)?;
+            if ui.verbose {
+                ui.log(&t!("Removed {}", file));
+            }
+        }
+    }
+
+    let font_cache = Path::new(FONT_DATA_OUT_DIR);
+    if font_cache.exists() {
+        fs::remove_dir_all(font_cache)?;
+    }
+    let baked_fonts = Path::new(BAKED_FONT_DIR);
+    if baked_fonts.exists() {
+        fs::remove_dir_all(baked_fonts)?;
+    }
+    let aseprite_cache = Path::new(ASEPRITE_CACHE_DIR);
+    if aseprite_cache.exists() {
+        fs::remove_dir_all(aseprite_cache)?;
+    }
+
+    ui.log(tr("Cleaned generated assets."));
+    Ok(())
+}
+
+pub fn generate_font_assets(
+    paths: &ProjectPaths,
+    fonts: &BTreeMap<String, FontBakeOptions>,
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    build_fonts(&paths.fonts(), Path::new(FONT_DATA_OUT_DIR), fonts, ui);
+    build_fonts(Path::new(ADDITIONAL_FONT_DIR), Path::new(FONT_DATA_OUT_DIR), fonts, ui);
+    generate_font_enum(paths)?;
+    let atlases = bake_configured_fonts(paths, fonts, ui)?;
+    generate_font_atlas_metadata(&atlases)?;
+    Ok(())
+}
+
+/// Packs only the `changed` fonts that still exist and regenerates the font metadata, fonts
+/// `[assets.fonts]` bakes are rebaked where their file or settings changed.
+/// Returns whether the metadata changed, i.e. fonts were added, removed, renamed or rebaked.
+pub fn refresh_font_assets(
+    paths: &ProjectPaths,
+    fonts: &BTreeMap<String, FontBakeOptions>,
+    changed: &[PathBuf],
+    ui: &Ui,
+) -> Result<bool, CustomError> {
+    for path in changed.iter().filter(|p| is_font_file(p) && p.is_file()) {
+        if !fonts.contains_key(&font_stem(path).0) {
+            pack_font_file(path, Path::new(FONT_DATA_OUT_DIR), ui)?;
+        }
+    }
+    let enum_changed = generate_font_enum(paths)?;
+    let atlases = bake_configured_fonts(paths, fonts, ui)?;
+    Ok(generate_font_atlas_metadata(&atlases)? || enum_changed)
+}
+
+// one `[assets.fonts]` atlas, as written to font_atlas.odin
+struct FontAtlas {
+    name: String,
+    size: u32,
+    mode: FontMode,
+    distance_range: u32,
+    texture: String,
+    font: BakedFont,
+}
+
+/// The font files `[assets.fonts]` bakes, by FontName. Every entry has to match a font.
+pub fn baked_font_files(
+    paths: &ProjectPaths,
+    fonts: &BTreeMap<String, FontBakeOptions>,
+) -> Result<BTreeMap<String, PathBuf>, CustomError> {
+    let mut files = BTreeMap::new();
+    if fonts.is_empty() {
+        return Ok(files);
+    }
+    for dir in [paths.fonts(), PathBuf::from(ADDITIONAL_FONT_DIR)] {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            if !is_font_file(&path) {
+                continue;
+            }
+            let (name, _) = font_stem(&path);
+            if fonts.contains_key(&name) {
+                files.entry(name).or_insert(path);
+            }
+        }
+    }
+    if let Some(name) = fonts.keys().find(|name| !files.contains_key(*name)) {
+        return Err(CustomError::ValidationError(t!(
+            "[assets.fonts] '{}' matches no font in {}",
+            name,
+            paths.fonts().display()
+        )));
+    }
+    Ok(files)
+}
+
+// the FontName of a font file and the pixel size its name ends in
+fn font_stem(path: &Path) -> (String, Option<u8>) {
+    let raw_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown");
+    parse_font_stem(&raw_stem.replace("-", "_").replace(" ", "_"))
+}
+
+// "32-126", "0x400-0x4ff" and "U+20AC" style ranges plus `chars`, printable ASCII by default
+fn font_chars(name: &str, options: &FontBakeOptions) -> Result<BTreeSet<char>, CustomError> {
+    if options.ranges.is_empty() && options.chars.is_none() {
+        return Ok((' '..='~').collect());
+    }
+    let codepoint = |text: &str| {
+        let text = text.trim();
+        if let Some(hex) = text.strip_prefix("U+").or_else(|| text.strip_prefix("0x")) {
+            u32::from_str_radix(hex, 16).ok()
+        } else {
+            text.parse().ok()
+        }
+    };
+    let mut chars = BTreeSet::new();
+    for range in &options.ranges {
+        let (start, end) = range.split_once('-').unwrap_or((range, range));
+        match (codepoint(start), codepoint(end)) {
+            (Some(start), Some(end)) if start <= end => {
+                chars.extend((start..=end).filter_map(char::from_u32));
+            }
+            _ => {
+                return Err(CustomError::ValidationError(t!(
+                    "[assets.fonts] {} has an invalid range '{}'",
+                    name,
+                    range
+                )));
+            }
+        }
+    }
+    chars.extend(options.chars.iter().flat_map(|c| c.chars()));
+    Ok(chars)
+}
+
+fn bake_configured_fonts(
+    paths: &ProjectPaths,
+    fonts: &BTreeMap<String, FontBakeOptions>,
+    ui: &Ui,
+) -> Result<Vec<FontAtlas>, CustomError> {
+    let files = baked_font_files(paths, fonts)?;
+    let mut atlases = Vec::new();
+
+    for (name, path) in &files {
+        let options = &fonts[name];
+        let (_, native_size) = font_stem(path);
+        let mode = options.mode.unwrap_or_default();
+        let sizes = match (mode, options.sizes.as_slice()) {
+            (FontMode::Msdf, [_, _, ..]) => {
+                return Err(CustomError::ValidationError(t!(
+                    "[assets.fonts] {} is an msdf font and takes a single size",
+                    name
+                )));
+            }
+            (FontMode::Msdf, []) => vec![DEFAULT_MSDF_SIZE],
+            (FontMode::Bitmap, []) => match native_size {
+                Some(size) => vec![size as u32],
+                None => {
+                    return Err(CustomError::ValidationError(t!(
+                        "[assets.fonts] {} needs sizes, its file name doesn't end in a pixel size",
+                        name
+                    )));
+                }
+            },
+            (_, sizes) => sizes.to_vec(),
+        };
+        let distance_range = options.distance_range.unwrap_or(DEFAULT_MSDF_RANGE);
+        if sizes.contains(&0) || distance_range == 0 {
+            return Err(CustomError::ValidationError(t!(
+                "[assets.fonts] {} sizes and distance_range must be greater than 0",
+                name
+            )));
+        }
+        // pixel fonts stay sharp, anything else is smoothed
+        let antialias = options.antialias.unwrap_or(native_size.is_none());
+        let chars = font_chars(name, options)?;
+
+        let settings = serde_json::to_string(options).map_err(|e| CustomError::BuildError(e.to_string()))?;
+        let inputs = [path.clone(), settings_stamp(&format!("font_{}", name), &settings)?];
+        let mut font_bytes = None;
+
+        for size in sizes {
+            let stem = format!("{}_{}", name, size);
+            let texture = Path::new(BAKED_FONT_DIR).join(format!("{}.png", stem));
+            let cache = Path::new(BAKED_FONT_CACHE_DIR).join(format!("{}.json", stem));
+
+            let cached = if is_fresh(&inputs, &cache)? && texture.is_file() {
+                serde_json::from_str(&fs::read_to_string(&cache)?).ok()
+            } else {
+                None
+            };
+            let font = match cached {
+                Some(font) => font,
+                None => {
+                    ui.cancel.check()?;
+                    ui.status(&t!("Baking font: {} ({}px, {})...", name, size, mode.name()));
+                    if font_bytes.is_none() {
+                        font_bytes = Some(fs::read(path)?);
+                    }
+                    let bytes = font_bytes.as_deref().unwrap_or_default();
+                    let (image, font) = bake_font(bytes, &chars, mode, size, antialias, distance_range)
+                        .map_err(|e| CustomError::BuildError(t!("Failed to bake font {} at {}px: {}", name, size, e)))?;
+
+                    fs::create_dir_all(BAKED_FONT_DIR)?;
+                    fs::create_dir_all(BAKED_FONT_CACHE_DIR)?;
+                    image.save(&texture).map_err(|_| {
+                        CustomError::BuildError(t!("Failed to save font atlas: {}", stem))
+                    })?;
+                    let json = serde_json::to_string(&font).map_err(|e| CustomError::BuildError(e.to_string()))?;
+                    fs::write(&cache, json)?;
+                    mark_fresh(&inputs, &cache)?;
+                    font
+                }
+            };
+            if font.glyphs.len() < chars.len() && ui.verbose {
+                ui.log(&t!(
+                    "Font {} has no glyph for {} of the {} baked characters",
+                    name,
+                    chars.len() - font.glyphs.len(),
+                    chars.len()
+                ));
+            }
+
+            atlases.push(FontAtlas {
+                name: name.clone(),
+                size,
+                mode,
+                distance_range,
+                texture: texture.to_string_lossy().replace('\\', "/"),
+                font,
+            });
+        }
+    }
+
+    // atlases of fonts or sizes no longer configured
+    if let Ok(entries) = fs::read_dir(BAKED_FONT_DIR) {
+        for path in entries.flatten().map(|e| e.path()) {
+            if !atlases.iter().any(|atlas| Path::new(&atlas.texture) == path) {
+                fs::remove_file(&path)?;
+                if ui.verbose {
+                    ui.log(&t!("Removed {}", path.display()));
+                }
+            }
+        }
+    }
+
+    Ok(atlases)
+}
+
+fn generate_font_atlas_metadata(atlases: &[FontAtlas]) -> Result<bool, CustomError> {
+    let mut odin_code = String::new();
+    odin_code.push_str("// NOTE: Machine generated by bonsai CLI.\n");
+    odin_code.push_str("package generated\n\n");
+    odin_code.push_str("import \"bonsai:core/gmath\"\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// How a baked font stores its glyphs: coverage bitmaps, or multi-channel signed distance fields that scale to any size.\n");
+    odin_code.push_str("FontAtlasMode :: enum u8 {\n\tbitmap,\n\tmsdf,\n}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// A baked glyph. `offset` places the glyph's bottom left corner relative to the pen on the baseline, in pixels with y pointing up.\n");
+    odin_code.push_str("// Glyphs without pixels, like the space, have a zero `size` and only advance the pen.\n");
+    odin_code.push_str("FontGlyph :: struct {\n");
+    odin_code.push_str("\tcodepoint: rune,\n");
+    odin_code.push_str("\tuv:        gmath.Vector4,\n");
+    odin_code.push_str("\tsize:      gmath.Vector2,\n");
+    odin_code.push_str("\toffset:    gmath.Vector2,\n");
+    odin_code.push_str("\tadvance:   f32,\n");
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// A font baked by `[assets.fonts]` at one size. Its glyphs are `first_glyph` to `first_glyph + glyph_count - 1` of\n");
+    odin_code.push_str("// [`FONT_GLYPHS`](#font_glyphs), sorted by codepoint. Metrics are in pixels at `size`.\n");
+    odin_code.push_str("FontAtlas :: struct {\n");
+    odin_code.push_str("\tfont:           FontName,\n");
+    odin_code.push_str("\tsize:           int,\n");
+    odin_code.push_str("\tmode:           FontAtlasMode,\n");
+    odin_code.push_str("\t// distance field range in pixels, msdf only\n");
+    odin_code.push_str("\tdistance_range: f32,\n");
+    odin_code.push_str("\ttexture:        string,\n");
+    odin_code.push_str("\ttexture_size:   gmath.Vector2,\n");
+    odin_code.push_str("\tline_height:    f32,\n");
+    odin_code.push_str("\tascent:         f32,\n");
+    odin_code.push_str("\tdescent:        f32,\n");
+    odin_code.push_str("\tfirst_glyph:    int,\n");
+    odin_code.push_str("\tglyph_count:    int,\n");
+    odin_code.push_str("}\n\n");
+
+    let glyph_count: usize = atlases.iter().map(|atlas| atlas.font.glyphs.len()).sum();
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// The glyphs of every baked font.\n");
+    odin_code.push_str(&format!("FONT_GLYPHS := [{}]FontGlyph {{\n", glyph_count));
+    for atlas in atlases {
+        let (width, height) = (atlas.font.width as f32, atlas.font.height as f32);
+        let mut glyphs: Vec<&BakedGlyph> = atlas.font.glyphs.iter().collect();
+        glyphs.sort_by_key(|glyph| glyph.codepoint);
+        for glyph in glyphs {
+            let [x, y, w, h] = glyph.rect.map(|v| v as f32);
+            odin_code.push_str(&format!(
+                "\t{{codepoint = {}, uv = {{{}, {}, {}, {}}}, size = {{{}, {}}}, offset = {{{}, {}}}, advance = {}}},\n",
+                glyph.codepoint,
+                x / width,
+                y / height,
+                (x + w) / width,
+                (y + h) / height,
+                w,
+                h,
+                glyph.x_offset,
+                glyph.y_offset,
+                glyph.advance
+            ));
+        }
+    }
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Every font and size `[assets.fonts]` bakes.\n");
+    odin_code.push_str(&format!("FONT_ATLASES := [{}]FontAtlas {{\n", atlases.len()));
+    let mut first_glyph = 0;
+    for atlas in atlases {
+        odin_code.push_str(&format!(
+            "\t{{font = .{}, size = {}, mode = .{}, distance_range = {}, texture = {}, texture_size = {{{}, {}}}, line_height = {}, ascent = {}, descent = {}, first_glyph = {}, glyph_count = {}}},\n",
+            atlas.name,
+            atlas.size,
+            atlas.mode.name(),
+            if atlas.mode == FontMode::Msdf { atlas.distance_range } else { 0 },
+            odin_string(&atlas.texture),
+            atlas.font.width,
+            atlas.font.height,
+            atlas.font.line_height,
+            atlas.font.ascent,
+            atlas.font.descent,
+            first_glyph,
+            atlas.font.glyphs.len()
+        ));
+        first_glyph += atlas.font.glyphs.len();
+    }
+    odin_code.push_str("}\n");
+
+    write_if_changed(Path::new(FONT_ATLAS_OUT_DIR), odin_code)
+}
+
+fn generate_font_enum(paths: &ProjectPaths) -> Result<bool, CustomError> {
     generate_asset_metadata(
-        FONT_SRC_DIR,
+        &paths.fonts(),
         FONT_OUT_DIR,
         ADDITIONAL_FONT_ENUM,
         ADDITIONAL_FONT_FILENAME,
         AssetSearchMode::ByExtension("ttf"),
         "Font",
-    )?;
+        paths,
+    )
+}
+
+pub fn generate_audio_assets(paths: &ProjectPaths) -> Result<(), CustomError> {
+    refresh_audio_assets(paths)?;
+    Ok(())
+}
+
+/// Regenerates the audio metadata, returning whether it changed. WAV files are used as they
+/// are, so an edited sound needs nothing else.
+pub fn refresh_audio_assets(paths: &ProjectPaths) -> Result<bool, CustomError> {
     generate_asset_metadata(
-        AUDIO_SRC_DIR,
+        &paths.audio(),
         AUDIO_OUT_DIR,
         "",
         "",
         AssetSearchMode::ByExtension("wav"),
         "Audio",
-    )?;
+        paths,
+    )
+}
+
+pub fn generate_scene_assets(paths: &ProjectPaths) -> Result<(), CustomError> {
     generate_asset_metadata(
-        SCENE_SRC_DIR,
+        &paths.scenes(),
         SCENE_OUT_DIR,
         "",
         "",
         AssetSearchMode::Directories,
         "Scene",
+        paths,
     )?;
-    generate_scene_registry_metadata(SCENE_SRC_DIR, SCENE_REG_OUT_DIR)?;
+    generate_scene_registry_metadata(&paths.scenes(), &paths.scenes().join(SCENE_REGISTRY_FILE))?;
+    Ok(())
+}
+
+/// Generates the layers, tiles and objects of every Tiled and LDtk map in `assets/maps`.
+/// Tiles refer to `SpriteName`s, the atlas packs the tileset images the maps use.
+pub fn generate_map_assets(paths: &ProjectPaths) -> Result<(), CustomError> {
+    let maps = load_maps(&paths.maps())?;
+
+    let mut odin_code = String::new();
+    odin_code.push_str("// NOTE: Machine generated by bonsai CLI.\n");
+    odin_code.push_str("package generated\n\n");
+    odin_code.push_str("import \"bonsai:core/gmath\"\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Whether a layer's `first` and `count` select tiles in [`MAP_TILES`](#map_tiles) or objects in [`MAP_OBJECTS`](#map_objects).\n");
+    odin_code.push_str("MapLayerKind :: enum u8 {\n\ttiles,\n\tobjects,\n}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// A placed tile, `cell` counts tiles from the layer's top left corner.\n");
+    odin_code.push_str("MapTile :: struct {\n");
+    odin_code.push_str("\tsprite:        SpriteName,\n");
+    odin_code.push_str("\tcell:          [2]int,\n");
+    odin_code.push_str("\tflip_x:        bool,\n");
+    odin_code.push_str("\tflip_y:        bool,\n");
+    odin_code.push_str("\t// flipped along the top left to bottom right diagonal, applied before flip_x and flip_y\n");
+    odin_code.push_str("\tflip_diagonal: bool,\n");
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// The shape of a Tiled object, LDtk entities are rectangles.\n");
+    odin_code.push_str("MapObjectShape :: enum u8 {\n");
+    for shape in ["rectangle", "ellipse", "point", "polygon", "polyline"] {
+        odin_code.push_str(&format!("\t{},\n", shape));
+    }
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// A Tiled object or LDtk entity, `class` is the Tiled class or the LDtk entity identifier. Positions are the top left corner in pixels,\n");
+    odin_code.push_str("// with y pointing down like in the editor. Polygon points in [`MAP_POINTS`](#map_points) are relative to the position.\n");
+    odin_code.push_str("MapObject :: struct {\n");
+    odin_code.push_str("\tname:           string,\n");
+    odin_code.push_str("\tclass:          string,\n");
+    odin_code.push_str("\tposition:       gmath.Vector2,\n");
+    odin_code.push_str("\tsize:           gmath.Vector2,\n");
+    odin_code.push_str("\t// degrees clockwise\n");
+    odin_code.push_str("\trotation:       f32,\n");
+    odin_code.push_str("\t// the tile of a Tiled tile object\n");
+    odin_code.push_str("\tsprite:         SpriteName,\n");
+    odin_code.push_str("\tshape:          MapObjectShape,\n");
+    odin_code.push_str("\tfirst_point:    int,\n");
+    odin_code.push_str("\tpoint_count:    int,\n");
+    odin_code.push_str("\tfirst_property: int,\n");
+    odin_code.push_str("\tproperty_count: int,\n");
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// A custom property of a Tiled object or a field of an LDtk entity, written as text.\n");
+    odin_code.push_str("MapProperty :: struct {\n");
+    odin_code.push_str("\tname:  string,\n");
+    odin_code.push_str("\tvalue: string,\n");
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// One layer of a map. Layers inside Tiled groups are named `<group>/<layer>`.\n");
+    odin_code.push_str("MapLayer :: struct {\n");
+    odin_code.push_str("\tname:      string,\n");
+    odin_code.push_str("\tkind:      MapLayerKind,\n");
+    odin_code.push_str("\tvisible:   bool,\n");
+    odin_code.push_str("\toffset:    gmath.Vector2,\n");
+    odin_code.push_str("\ttile_size: gmath.Vector2,\n");
+    odin_code.push_str("\tfirst:     int,\n");
+    odin_code.push_str("\tcount:     int,\n");
+    odin_code.push_str("}\n\n");
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Layers `first_layer` to `first_layer + layer_count - 1` of [`MAP_LAYERS`](#map_layers), the bottom layer first. Sizes are in pixels.\n");
+    odin_code.push_str("MapData :: struct {\n");
+    odin_code.push_str("\tsize:        gmath.Vector2,\n");
+    odin_code.push_str("\ttile_size:   gmath.Vector2,\n");
+    odin_code.push_str("\tfirst_layer: int,\n");
+    odin_code.push_str("\tlayer_count: int,\n");
+    odin_code.push_str("}\n\n");
+
+    let mut names = vec!["nil".to_string()];
+    for map in &maps {
+        let name = odin_identifier(&map.name);
+        if names.contains(&name) {
+            return Err(CustomError::BuildError(t!(
+                "Two maps are named '{}'. Rename one of the files or levels",
+                name
+            )));
+        }
+        names.push(name);
+    }
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Enum containing every map: `<file>` for a Tiled map, `<file>_<level>` for each level of an LDtk project.\n");
+    odin_code.push_str("MapName :: enum u32 {\n");
+    for name in &names {
+        odin_code.push_str(&format!("\t{},\n", name));
+    }
+    odin_code.push_str("}\n\n");
+
+    let mut tiles = String::new();
+    let mut objects = String::new();
+    let mut points = String::new();
+    let mut properties = String::new();
+    let mut layers = String::new();
+    let mut data = String::new();
+    let (mut tile_count, mut object_count, mut point_count, mut property_count, mut layer_count) = (0, 0, 0, 0, 0);
+
+    for (name, map) in names.iter().skip(1).zip(&maps) {
+        data.push_str(&format!(
+            "\t.{} = {{size = {{{:?}, {:?}}}, tile_size = {{{:?}, {:?}}}, first_layer = {}, layer_count = {}}},\n",
+            name,
+            map.width,
+            map.height,
+            map.tile_width as f32,
+            map.tile_height as f32,
+            layer_count,
+            map.layers.len()
+        ));
+        for layer in &map.layers {
+            let (kind, first, count) = match &layer.content {
+                LayerContent::Tiles(layer_tiles) => {
+                    for tile in layer_tiles {
+                        tiles.push_str(&format!(
+                            "\t{{sprite = .{}, cell = {{{}, {}}}, flip_x = {}, flip_y = {}, flip_diagonal = {}}},\n",
+                            sprite_identifier(&tile.sprite)?,
+                            tile.x,
+                            tile.y,
+                            tile.flip_x,
+                            tile.flip_y,
+                            tile.flip_diagonal
+                        ));
+                    }
+                    tile_count += layer_tiles.len();
+                    ("tiles", tile_count - layer_tiles.len(), layer_tiles.len())
+                }
+                LayerContent::Objects(layer_objects) => {
+                    for object in layer_objects {
+                        let sprite = match &object.sprite {
+                            Some(key) => sprite_identifier(key)?,
+                            None => "nil".to_string(),
+                        };
+                        objects.push_str(&format!(
+                            "\t{{name = {}, class = {}, position = {{{:?}, {:?}}}, size = {{{:?}, {:?}}}, rotation = {:?}, sprite = .{}, shape = .{}, first_point = {}, point_count = {}, first_property = {}, property_count = {}}},\n",
+                            odin_string(&object.name),
+                            odin_string(&object.class),
+                            object.x,
+                            object.y,
+                            object.width,
+                            object.height,
+                            object.rotation,
+                            sprite,
+                            object.shape.odin_name(),
+                            point_count,
+                            object.points.len(),
+                            property_count,
+                            object.properties.len()
+                        ));
+                        for (x, y) in &object.points {
+                            points.push_str(&format!("\t{{{:?}, {:?}}},\n", x, y));
+                        }
+                        for (key, value) in &object.properties {
+                            properties.push_str(&format!(
+                                "\t{{name = {}, value = {}}},\n",
+                                odin_string(key),
+                                odin_string(value)
+                            ));
+                        }
+                        point_count += object.points.len();
+                        property_count += object.properties.len();
+                    }
+                    object_count += layer_objects.len();
+                    ("objects", object_count - layer_objects.len(), layer_objects.len())
+                }
+            };
+            layers.push_str(&format!(
+                "\t{{name = {}, kind = .{}, visible = {}, offset = {{{:?}, {:?}}}, tile_size = {{{:?}, {:?}}}, first = {}, count = {}}},\n",
+                odin_string(&layer.name),
+                kind,
+                layer.visible,
+                layer.offset.0,
+                layer.offset.1,
+                layer.tile_width as f32,
+                layer.tile_height as f32,
+                first,
+                count
+            ));
+            layer_count += 1;
+        }
+    }
+
+    for (comment, table, count, kind, rows) in [
+        ("Tiles of every tile layer, each layer's in draw order.", "MAP_TILES", tile_count, "MapTile", &tiles),
+        ("Objects of every object layer.", "MAP_OBJECTS", object_count, "MapObject", &objects),
+        ("Polygon and polyline points of every object.", "MAP_POINTS", point_count, "gmath.Vector2", &points),
+        ("Properties of every object.", "MAP_PROPERTIES", property_count, "MapProperty", &properties),
+        ("Layers of every map.", "MAP_LAYERS", layer_count, "MapLayer", &layers),
+    ] {
+        odin_code.push_str("// @ref\n");
+        odin_code.push_str(&format!("// {}\n", comment));
+        odin_code.push_str("@(rodata)\n");
+        odin_code.push_str(&format!("{} := [{}]{} {{\n", table, count, kind));
+        odin_code.push_str(rows);
+        odin_code.push_str("}\n\n");
+    }
+
+    odin_code.push_str("// @ref\n");
+    odin_code.push_str("// Size and layers of every map.\n");
+    odin_code.push_str("@(rodata)\n");
+    odin_code.push_str("MAP_DATA := [MapName]MapData {\n");
+    odin_code.push_str("\t.nil = {},\n");
+    odin_code.push_str(&data);
+    odin_code.push_str("}\n");
+
+    write_if_changed(Path::new(MAP_OUT_DIR), odin_code)?;
     Ok(())
 }
 
 fn generate_asset_metadata(
-    asset_src: &str,
+    asset_dir: &Path,
     asset_out: &str,
     additional_asset_enum: &str,
     additional_asset_filename: &str,
     mode: AssetSearchMode,
     enum_name: &str,
-) -> Result<(), CustomError> {
-    let asset_dir = Path::new(asset_src);
+    paths: &ProjectPaths,
+) -> Result<bool, CustomError> {
     let output_file = Path::new(asset_out);
 
     let mut entries: Vec<(String, String, Option<u8>)> = vec![];
@@ -474,7 +1475,9 @@ fn generate_asset_metadata(
 
             if is_match || is_match_second {
                 if let Some(stem_os) = path.file_stem() {
-                    if let (Some(stem), Some(path_str)) = (stem_os.to_str(), path.to_str()) {
+                    if let Some(stem) = stem_os.to_str() {
+                        // builds copy assets to `assets/`, the game loads them by their default-layout path
+                        let path_str = paths.logical(&path);
                         let mut clean_stem = stem.replace("-", "_").replace(" ", "_");
                         let mut size = None;
 
@@ -544,10 +1547,5 @@ fn generate_asset_metadata(
         odin_code.push_str("}\n");
     }
 
-    if let Some(parent) = output_file.parent() {
-        fs::create_dir_all(parent)?;
-    }
-    fs::write(output_file, odin_code)?;
-
-    Ok(())
+    write_if_changed(output_file, odin_code)
 }
@@ -0,0 +1,468 @@
+use crate::manifest::{AtlasPacker, AtlasSort};
+use std::cmp::{Reverse, max};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Where a sprite sits in the atlas, in pixels from the top-left, padding excluded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Rect {
+    fn right(&self) -> u32 {
+        self.x + self.w
+    }
+
+    fn bottom(&self) -> u32 {
+        self.y + self.h
+    }
+
+    fn area(&self) -> u64 {
+        self.w as u64 * self.h as u64
+    }
+
+    fn contains(&self, other: &Rect) -> bool {
+        self.x <= other.x
+            && self.y <= other.y
+            && self.right() >= other.right()
+            && self.bottom() >= other.bottom()
+    }
+
+    fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+}
+
+/// One atlas texture and the sprites packed into it.
+pub struct Page {
+    pub width: u32,
+    pub height: u32,
+    pub frames: BTreeMap<String, Rect>,
+    /// Sprites stored turned 90 degrees clockwise, their rect is the turned one.
+    pub rotated: BTreeSet<String>,
+    /// The `[assets.atlas.groups]` entry the page was packed for, None for ungrouped sprites.
+    pub group: Option<String>,
+}
+
+impl Page {
+    fn new() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            frames: BTreeMap::new(),
+            rotated: BTreeSet::new(),
+            group: None,
+        }
+    }
+}
+
+/// The packed positions of every sprite, over as many pages as they need.
+pub struct Layout {
+    pub pages: Vec<Page>,
+}
+
+impl Layout {
+    /// Every sprite with the index of its page, by name.
+    pub fn frames(&self) -> BTreeMap<&str, (usize, Rect)> {
+        let mut frames = BTreeMap::new();
+        for (index, page) in self.pages.iter().enumerate() {
+            for (key, rect) in &page.frames {
+                frames.insert(key.as_str(), (index, *rect));
+            }
+        }
+        frames
+    }
+
+    pub fn used_pixels(&self) -> u64 {
+        self.pages
+            .iter()
+            .flat_map(|page| page.frames.values())
+            .map(Rect::area)
+            .sum()
+    }
+
+    pub fn total_pixels(&self) -> u64 {
+        self.pages
+            .iter()
+            .map(|page| page.width as u64 * page.height as u64)
+            .sum()
+    }
+
+    pub fn fill_percent(&self) -> f64 {
+        let total = self.total_pixels();
+        if total == 0 {
+            return 0.0;
+        }
+        self.used_pixels() as f64 / total as f64 * 100.0
+    }
+}
+
+// places padded rectangles inside a max_size square, top-left corner or None when full.
+// with `rotate` a turned (h, w) spot is considered too, the bool says it was taken
+trait Bin {
+    fn insert(&mut self, w: u32, h: u32, rotate: bool) -> Option<(u32, u32, bool)>;
+}
+
+/// Packs `sprites` (key, width, height) in the order `sort` gives them.
+/// `padding` goes around the atlas border and between sprites, as it always has.
+/// Sprites that don't fit into a `max_size` page spill into the next one.
+/// `allow_rotation` lets sprites turn 90 degrees where that fits them better.
+/// `Err` holds the key of a sprite too large for an empty page.
+pub fn layout(
+    sprites: &[(String, u32, u32)],
+    packer: AtlasPacker,
+    sort: AtlasSort,
+    max_size: u32,
+    padding: u32,
+    allow_rotation: bool,
+) -> Result<Layout, String> {
+    let mut remaining = sorted_order(sprites, sort);
+    let mut pages: Vec<Page> = Vec::new();
+    while let Some(&first) = remaining.first() {
+        let (page, left) = fill_page(sprites, &remaining, packer, max_size, padding, allow_rotation);
+        if page.frames.is_empty() {
+            return Err(sprites[first].0.clone());
+        }
+        // a later sprite with the same name replaces the earlier one, on any page
+        for earlier in &mut pages {
+            earlier.frames.retain(|key, _| !page.frames.contains_key(key));
+            earlier.rotated.retain(|key| !page.frames.contains_key(key));
+        }
+        pages.push(page);
+        remaining = left;
+    }
+    Ok(Layout { pages })
+}
+
+// packs what fits from `order` into one page, and returns the sprites left over
+fn fill_page(
+    sprites: &[(String, u32, u32)],
+    order: &[usize],
+    packer: AtlasPacker,
+    max_size: u32,
+    padding: u32,
+    rotate: bool,
+) -> (Page, Vec<usize>) {
+    let placement = match packer {
+        AtlasPacker::Skyline => {
+            return place(sprites, order, &mut Skyline::new(max_size), padding, rotate);
+        }
+        AtlasPacker::Maxrects => Placement::MaxRects,
+        AtlasPacker::Guillotine => Placement::Guillotine,
+    };
+
+    // both fill whichever free space scores best, which spreads sprites over the whole
+    // bin, so search for the smallest square bin that still holds the page's sprites
+    let (mut best, left) = place(sprites, order, &mut FreeRects::new(max_size, placement), padding, rotate);
+    let mut spilled = vec![false; sprites.len()];
+    for &index in &left {
+        spilled[index] = true;
+    }
+    let placed: Vec<usize> = order.iter().copied().filter(|&i| !spilled[i]).collect();
+    let fits = |size: u32| {
+        let (page, left) = place(sprites, &placed, &mut FreeRects::new(size, placement), padding, rotate);
+        left.is_empty().then_some(page)
+    };
+    let (mut low, mut high) = (1, max(best.width, best.height));
+    while low < high {
+        let size = low + (high - low) / 2;
+        match fits(size) {
+            Some(page) => {
+                high = size;
+                best = page;
+            }
+            None => low = size + 1,
+        }
+    }
+    (best, left)
+}
+
+fn place(
+    sprites: &[(String, u32, u32)],
+    order: &[usize],
+    bin: &mut dyn Bin,
+    padding: u32,
+    rotate: bool,
+) -> (Page, Vec<usize>) {
+    let mut page = Page::new();
+    let mut left = Vec::new();
+
+    for &index in order {
+        let (key, w, h) = &sprites[index];
+        let Some((x, y, rotated)) = bin.insert(w + padding, h + padding, rotate) else {
+            left.push(index);
+            continue;
+        };
+        let (w, h) = if rotated { (*h, *w) } else { (*w, *h) };
+        let rect = Rect {
+            x: x + padding,
+            y: y + padding,
+            w,
+            h,
+        };
+        if rotated {
+            page.rotated.insert(key.clone());
+        } else {
+            page.rotated.remove(key);
+        }
+        page.width = max(page.width, rect.right() + padding);
+        page.height = max(page.height, rect.bottom() + padding);
+        page.frames.insert(key.clone(), rect);
+    }
+
+    (page, left)
+}
+
+// a stable sort, so equal sprites keep their name order
+fn sorted_order(sprites: &[(String, u32, u32)], sort: AtlasSort) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..sprites.len()).collect();
+    let size = |i: &usize| (sprites[*i].1 as u64, sprites[*i].2 as u64);
+    match sort {
+        AtlasSort::Name => {}
+        AtlasSort::Area => order.sort_by_key(|i| Reverse(size(i).0 * size(i).1)),
+        AtlasSort::Height => order.sort_by_key(|i| Reverse((size(i).1, size(i).0))),
+        AtlasSort::Width => order.sort_by_key(|i| Reverse((size(i).0, size(i).1))),
+        AtlasSort::Perimeter => order.sort_by_key(|i| Reverse(size(i).0 + size(i).1)),
+        AtlasSort::MaxSide => order.sort_by_key(|i| {
+            let (w, h) = size(i);
+            Reverse((max(w, h), w.min(h)))
+        }),
+    }
+    order
+}
+
+struct Segment {
+    x: u32,
+    y: u32,
+    w: u32,
+}
+
+// lowest-then-narrowest skyline, placed exactly like texture_packer does so default atlases don't move
+struct Skyline {
+    size: u32,
+    // sorted by x, covering the full width
+    segments: Vec<Segment>,
+}
+
+impl Skyline {
+    fn new(size: u32) -> Self {
+        Self {
+            size,
+            segments: vec![Segment { x: 0, y: 0, w: size }],
+        }
+    }
+
+    // the y a (w, h) rectangle would rest at when its left edge starts on segment `i`
+    fn fit(&self, mut i: usize, w: u32, h: u32) -> Option<u32> {
+        if self.segments[i].x + w > self.size {
+            return None;
+        }
+        let mut y = 0;
+        let mut width_left = w;
+        loop {
+            y = max(y, self.segments[i].y);
+            if y + h > self.size {
+                return None;
+            }
+            if self.segments[i].w >= width_left {
+                return Some(y);
+            }
+            width_left -= self.segments[i].w;
+            i += 1;
+        }
+    }
+}
+
+impl Skyline {
+    // the segment and y of the lowest, then narrowest spot for a (w, h) rectangle,
+    // with that ranking
+    fn best_fit(&self, w: u32, h: u32) -> Option<(usize, u32, (u32, u32))> {
+        let mut best: Option<(usize, u32, (u32, u32))> = None;
+        for i in 0..self.segments.len() {
+            if let Some(y) = self.fit(i, w, h) {
+                let rank = (y + h, self.segments[i].w);
+                if best.is_none_or(|(_, _, best_rank)| rank < best_rank) {
+                    best = Some((i, y, rank));
+                }
+            }
+        }
+        best
+    }
+}
+
+impl Bin for Skyline {
+    fn insert(&mut self, w: u32, h: u32, rotate: bool) -> Option<(u32, u32, bool)> {
+        let upright = self.best_fit(w, h).map(|fit| (fit, false));
+        let turned = (rotate && w != h).then(|| self.best_fit(h, w).map(|fit| (fit, true))).flatten();
+        // the first of equal spots wins, so upright sprites stay upright on a tie
+        let ((index, y, _), rotated) = [upright, turned]
+            .into_iter()
+            .flatten()
+            .min_by_key(|((_, _, rank), _)| *rank)?;
+        let (w, h) = if rotated { (h, w) } else { (w, h) };
+        let x = self.segments[index].x;
+
+        self.segments.insert(index, Segment { x, y: y + h, w });
+        // trim the segments the new one now covers
+        let i = index + 1;
+        while i < self.segments.len() {
+            let covered_to = self.segments[i - 1].x + self.segments[i - 1].w;
+            if self.segments[i].x >= covered_to {
+                break;
+            }
+            let shrink = covered_to - self.segments[i].x;
+            if self.segments[i].w <= shrink {
+                self.segments.remove(i);
+            } else {
+                self.segments[i].x += shrink;
+                self.segments[i].w -= shrink;
+                break;
+            }
+        }
+        // and join neighbours of equal height
+        let mut i = 1;
+        while i < self.segments.len() {
+            if self.segments[i - 1].y == self.segments[i].y {
+                self.segments[i - 1].w += self.segments[i].w;
+                self.segments.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+
+        Some((x, y, rotated))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Placement {
+    // best short side fit, free rectangles may overlap
+    MaxRects,
+    // best area fit, each cut splits the shorter leftover axis
+    Guillotine,
+}
+
+struct FreeRects {
+    placement: Placement,
+    free: Vec<Rect>,
+}
+
+impl FreeRects {
+    fn new(size: u32, placement: Placement) -> Self {
+        Self {
+            placement,
+            free: vec![Rect {
+                x: 0,
+                y: 0,
+                w: size,
+                h: size,
+            }],
+        }
+    }
+
+    fn score(&self, free: &Rect, w: u32, h: u32) -> (u64, u64) {
+        let (left_w, left_h) = ((free.w - w) as u64, (free.h - h) as u64);
+        match self.placement {
+            Placement::MaxRects => (left_w.min(left_h), left_w.max(left_h)),
+            Placement::Guillotine => (free.area() - w as u64 * h as u64, left_w.min(left_h)),
+        }
+    }
+
+    // maxrects: carve the placed rectangle out of every free one it overlaps
+    fn split_overlapping(&mut self, placed: &Rect) {
+        let mut carved = Vec::new();
+        self.free.retain(|free| {
+            if !free.intersects(placed) {
+                return true;
+            }
+            if placed.x > free.x {
+                carved.push(Rect { w: placed.x - free.x, ..*free });
+            }
+            if placed.right() < free.right() {
+                carved.push(Rect {
+                    x: placed.right(),
+                    w: free.right() - placed.right(),
+                    ..*free
+                });
+            }
+            if placed.y > free.y {
+                carved.push(Rect { h: placed.y - free.y, ..*free });
+            }
+            if placed.bottom() < free.bottom() {
+                carved.push(Rect {
+                    y: placed.bottom(),
+                    h: free.bottom() - placed.bottom(),
+                    ..*free
+                });
+            }
+            false
+        });
+        self.free.extend(carved);
+
+        // drop free rectangles that another one already covers
+        let mut i = 0;
+        while i < self.free.len() {
+            let covered = (0..self.free.len()).any(|j| {
+                j != i
+                    && self.free[j].contains(&self.free[i])
+                    && (self.free[j] != self.free[i] || j < i)
+            });
+            if covered {
+                self.free.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    // guillotine: cut the used free rectangle in two along the shorter leftover axis
+    fn split_guillotine(&mut self, index: usize, w: u32, h: u32) {
+        let free = self.free.remove(index);
+        let (left_w, left_h) = (free.w - w, free.h - h);
+        let (right, below) = if left_w < left_h {
+            (
+                Rect { x: free.x + w, y: free.y, w: left_w, h },
+                Rect { x: free.x, y: free.y + h, w: free.w, h: left_h },
+            )
+        } else {
+            (
+                Rect { x: free.x + w, y: free.y, w: left_w, h: free.h },
+                Rect { x: free.x, y: free.y + h, w, h: left_h },
+            )
+        };
+        self.free.extend([right, below].into_iter().filter(|r| r.w > 0 && r.h > 0));
+    }
+}
+
+impl Bin for FreeRects {
+    fn insert(&mut self, w: u32, h: u32, rotate: bool) -> Option<(u32, u32, bool)> {
+        let best_fit = |w: u32, h: u32| {
+            self.free
+                .iter()
+                .enumerate()
+                .filter(|(_, free)| free.w >= w && free.h >= h)
+                .map(|(index, free)| (index, (self.score(free, w, h), free.y, free.x)))
+                .min_by_key(|(_, rank)| *rank)
+        };
+        let upright = best_fit(w, h).map(|fit| (fit, false));
+        let turned = (rotate && w != h).then(|| best_fit(h, w).map(|fit| (fit, true))).flatten();
+        let ((index, _), rotated) = [upright, turned]
+            .into_iter()
+            .flatten()
+            .min_by_key(|((_, rank), _)| *rank)?;
+        let (w, h) = if rotated { (h, w) } else { (w, h) };
+        let (x, y) = (self.free[index].x, self.free[index].y);
+
+        match self.placement {
+            Placement::MaxRects => self.split_overlapping(&Rect { x, y, w, h }),
+            Placement::Guillotine => self.split_guillotine(index, w, h),
+        }
+        Some((x, y, rotated))
+    }
+}
@@ -0,0 +1,95 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub const BASELINE_PATH: &str = "tests/bench/baseline.json";
+pub const BENCH_OUTPUT_DIR: &str = ".bonsai/test/bench";
+pub const DEFAULT_THRESHOLD_PERCENT: f64 = 10.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FrameStats {
+    pub frames: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+pub type Baselines = BTreeMap<String, FrameStats>;
+
+fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    let index = ((sorted.len() - 1) as f64 * percent / 100.0).round() as usize;
+    sorted[index]
+}
+
+// the game writes one frame time in milliseconds per line
+pub fn read_frame_stats(path: &Path) -> Result<FrameStats, CustomError> {
+    let content = fs::read_to_string(path)?;
+    let mut times: Vec<f64> = content
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect();
+
+    if times.is_empty() {
+        return Err(CustomError::ValidationError(t!("No frame times recorded in {}", format!("{:?}", path))));
+    }
+    times.sort_by(f64::total_cmp);
+
+    Ok(FrameStats {
+        frames: times.len(),
+        mean_ms: times.iter().sum::<f64>() / times.len() as f64,
+        p50_ms: percentile(&times, 50.0),
+        p95_ms: percentile(&times, 95.0),
+        p99_ms: percentile(&times, 99.0),
+        max_ms: times[times.len() - 1],
+    })
+}
+
+pub fn load_baselines() -> Result<Baselines, CustomError> {
+    let path = Path::new(BASELINE_PATH);
+    if !path.exists() {
+        return Ok(Baselines::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| CustomError::ValidationError(t!("Invalid bench baseline: {}", e)))
+}
+
+pub fn save_baselines(baselines: &Baselines) -> Result<(), CustomError> {
+    let path = Path::new(BASELINE_PATH);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let json = serde_json::to_string_pretty(baselines)
+        .map_err(|e| CustomError::ValidationError(t!("Failed to serialize baseline: {}", e)))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// Returns a description of every metric that got slower than the threshold allows.
+pub fn regressions(baseline: &FrameStats, current: &FrameStats, threshold_percent: f64) -> Vec<String> {
+    let limit = 1.0 + threshold_percent / 100.0;
+    [
+        ("mean", baseline.mean_ms, current.mean_ms),
+        ("p95", baseline.p95_ms, current.p95_ms),
+        ("p99", baseline.p99_ms, current.p99_ms),
+    ]
+    .into_iter()
+    .filter(|(_, before, after)| *before > 0.0 && *after > before * limit)
+    .map(|(metric, before, after)| {
+        format!(
+            "{} {:.2}ms -> {:.2}ms (+{:.1}%)",
+            metric,
+            before,
+            after,
+            (after / before - 1.0) * 100.0
+        )
+    })
+    .collect()
+}
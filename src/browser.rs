@@ -0,0 +1,349 @@
+use crate::Ui;
+use crate::config::load_global_config;
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::wsl::{is_wsl, to_windows_path};
+use colored::Colorize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+// browsers that can start with a profile of their own, in the order they are tried
+const ISOLATED_BROWSERS: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "chromium",
+    "chromium-browser",
+    "microsoft-edge",
+    "brave-browser",
+    "firefox",
+];
+const MACOS_BROWSERS: &[&str] = &[
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+    "/Applications/Brave Browser.app/Contents/MacOS/Brave Browser",
+    "/Applications/Firefox.app/Contents/MacOS/firefox",
+];
+// relative to %ProgramFiles%, %ProgramFiles(x86)% and %LOCALAPPDATA%
+const WINDOWS_BROWSERS: &[&str] = &[
+    "Google\\Chrome\\Application\\chrome.exe",
+    "Microsoft\\Edge\\Application\\msedge.exe",
+    "BraveSoftware\\Brave-Browser\\Application\\brave.exe",
+    "Mozilla Firefox\\firefox.exe",
+];
+// how long an opener gets to report failure before it is taken as a running browser
+const OPENER_TIMEOUT: Duration = Duration::from_secs(3);
+const CHROMIUM_NAMES: &[&str] = &["chrome", "chromium", "edge", "brave", "vivaldi", "opera"];
+// a new Firefox profile opens its welcome and default browser prompts over the game otherwise
+const FIREFOX_USER_JS: &str = r#"user_pref("browser.shell.checkDefaultBrowser", false);
+user_pref("browser.aboutwelcome.enabled", false);
+user_pref("browser.startup.homepage_override.mstone", "ignore");
+user_pref("datareporting.policy.dataSubmissionPolicyBypassNotification", true);
+"#;
+
+struct Opener {
+    program: String,
+    args: Vec<String>,
+    // cmd.exe complains when started from a linux working directory, so it runs from a drive mount
+    working_dir: Option<&'static str>,
+}
+
+impl Opener {
+    fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            working_dir: None,
+        }
+    }
+
+    fn spawn(&self) -> bool {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(&self.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        if let Some(dir) = self.working_dir {
+            cmd.current_dir(dir);
+        }
+
+        let Ok(mut child) = cmd.spawn() else {
+            return false;
+        };
+
+        // xdg-open, wslview, open and cmd exit right away and report failure through their
+        // status, a browser started directly keeps running and counts as opened
+        let started = Instant::now();
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) => return status.success(),
+                Ok(None) if started.elapsed() >= OPENER_TIMEOUT => return true,
+                Ok(None) => thread::sleep(Duration::from_millis(50)),
+                Err(_) => return false,
+            }
+        }
+    }
+}
+
+// a .exe started from WSL runs on Windows and can't read linux paths
+fn is_windows_program(program: &str) -> bool {
+    program.to_lowercase().ends_with(".exe")
+}
+
+fn browser_openers(browser: &str, url: &str, windows_url: &str) -> Vec<Opener> {
+    // $BROWSER convention: "%s" marks where the url goes
+    if browser.contains("%s") {
+        let mut parts = browser.split_whitespace();
+        return match parts.next() {
+            Some(program) => {
+                let target = if is_windows_program(program) { windows_url } else { url };
+                let args: Vec<String> = parts.map(|p| p.replace("%s", target)).collect();
+                vec![Opener {
+                    program: program.to_string(),
+                    args,
+                    working_dir: None,
+                }]
+            }
+            None => Vec::new(),
+        };
+    }
+
+    let target = if is_windows_program(browser) { windows_url } else { url };
+    let mut openers = vec![Opener::new(browser, &[target])];
+
+    if !Path::new(browser).exists() {
+        if cfg!(target_os = "macos") {
+            openers.push(Opener::new("open", &["-a", browser, url]));
+        } else if cfg!(windows) {
+            openers.push(Opener::new("cmd", &["/C", "start", "", browser, url]));
+        }
+    }
+
+    openers
+}
+
+fn default_openers(url: &str, windows_url: &str) -> Vec<Opener> {
+    if cfg!(windows) {
+        return vec![Opener::new("cmd", &["/C", "start", "", url])];
+    }
+
+    if cfg!(target_os = "macos") {
+        return vec![Opener::new("open", &[url])];
+    }
+
+    let mut openers = Vec::new();
+
+    if is_wsl() {
+        openers.push(Opener::new("wslview", &[url]));
+        openers.push(Opener {
+            working_dir: Some("/mnt/c"),
+            ..Opener::new("cmd.exe", &["/C", "start", "", windows_url])
+        });
+    }
+
+    for program in ["xdg-open", "sensible-browser", "x-www-browser", "gnome-open"] {
+        openers.push(Opener::new(program, &[url]));
+    }
+
+    openers
+}
+
+pub fn open_browser(url: &str, browser: Option<&str>, ui: &Ui) {
+    let configured = browser
+        .map(|b| b.to_string())
+        .or_else(|| load_global_config().ok().and_then(|c| c.web.browser));
+    // a local file opened from WSL goes to Windows programs as a \\wsl.localhost\ path
+    let windows_url = if is_wsl() && Path::new(url).is_absolute() {
+        to_windows_path(Path::new(url)).unwrap_or_else(|| url.to_string())
+    } else {
+        url.to_string()
+    };
+
+    if let Some(browser) = &configured {
+        if browser_openers(browser, url, &windows_url).iter().any(|o| o.spawn()) {
+            return;
+        }
+
+        ui.message(&t!(
+            "{} Could not start browser '{}'. Falling back to the system default.",
+            "[WARNING]".yellow(),
+            browser
+        ));
+    } else if let Ok(list) = env::var("BROWSER") {
+        let separator = if cfg!(windows) { ';' } else { ':' };
+        for browser in list.split(separator).filter(|b| !b.trim().is_empty()) {
+            if browser_openers(browser.trim(), url, &windows_url).iter().any(|o| o.spawn()) {
+                return;
+            }
+        }
+    }
+
+    for opener in default_openers(url, &windows_url) {
+        if opener.spawn() {
+            if ui.verbose {
+                ui.log(&t!("Opened browser with '{}'.", opener.program));
+            }
+            return;
+        }
+    }
+
+    ui.message(&t!(
+        "{} No browser opener available (tried --browser, $BROWSER and the system defaults). Open {} manually.",
+        "[WARNING]".yellow(),
+        url
+    ));
+}
+
+enum Engine {
+    Chromium,
+    Firefox,
+}
+
+fn engine(program: &Path) -> Option<Engine> {
+    let name = program.file_stem()?.to_string_lossy().to_lowercase();
+    if name.contains("firefox") {
+        Some(Engine::Firefox)
+    } else if CHROMIUM_NAMES.iter().any(|n| name.contains(n)) {
+        Some(Engine::Chromium)
+    } else {
+        None
+    }
+}
+
+// a path is taken as is, a bare name is looked up in PATH
+fn find_program(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let file = if cfg!(windows) && path.extension().is_none() {
+        format!("{}.exe", name)
+    } else {
+        name.to_string()
+    };
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(&file))
+        .find(|candidate| candidate.is_file())
+}
+
+fn installed_browser() -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if cfg!(target_os = "macos") {
+        candidates.extend(MACOS_BROWSERS.iter().map(PathBuf::from));
+    }
+    if cfg!(windows) {
+        for var in ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"] {
+            if let Some(root) = env::var_os(var) {
+                candidates.extend(WINDOWS_BROWSERS.iter().map(|b| Path::new(&root).join(b)));
+            }
+        }
+    }
+    candidates
+        .into_iter()
+        .find(|path| path.is_file())
+        .or_else(|| ISOLATED_BROWSERS.iter().find_map(|name| find_program(name)))
+}
+
+/// A browser started by [`open_isolated_browser`]. Closing it deletes its profile.
+pub struct IsolatedBrowser {
+    pub program: PathBuf,
+    child: Child,
+    // removed when dropped, after the browser is gone
+    _profile: TempDir,
+}
+
+impl IsolatedBrowser {
+    pub fn close(mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Starts a Chromium based browser or Firefox on `url` with an empty temporary profile and the
+/// devtools open, so no cached wasm, service worker or extension from other sessions is involved.
+/// Uses `browser` or `[web] browser` when set, otherwise the first supported browser installed.
+pub fn open_isolated_browser(
+    url: &str,
+    browser: Option<&str>,
+    ui: &Ui,
+) -> Result<IsolatedBrowser, CustomError> {
+    let configured = browser
+        .map(|b| b.to_string())
+        .or_else(|| load_global_config().ok().and_then(|c| c.web.browser));
+
+    let program = match &configured {
+        Some(name) => find_program(name).ok_or_else(|| {
+            CustomError::ValidationError(t!("Browser '{}' not found", name))
+        })?,
+        None => installed_browser().ok_or_else(|| {
+            CustomError::ValidationError(
+                t!("No Chrome, Chromium, Edge, Brave or Firefox found for a separate profile, pass its path with --browser")
+            )
+        })?,
+    };
+
+    let profile = tempfile::Builder::new().prefix("bonsai-browser-").tempdir()?;
+    // a Windows browser started from WSL needs its profile as a Windows path
+    let windows_profile = if is_wsl() && is_windows_program(&program.to_string_lossy()) {
+        to_windows_path(profile.path())
+    } else {
+        None
+    };
+    let profile_dir =
+        windows_profile.unwrap_or_else(|| profile.path().to_string_lossy().to_string());
+    let args: Vec<String> = match engine(&program) {
+        Some(Engine::Chromium) => vec![
+            format!("--user-data-dir={}", profile_dir),
+            "--no-first-run".to_string(),
+            "--no-default-browser-check".to_string(),
+            "--disable-extensions".to_string(),
+            "--auto-open-devtools-for-tabs".to_string(),
+            "--new-window".to_string(),
+            url.to_string(),
+        ],
+        Some(Engine::Firefox) => {
+            fs::write(profile.path().join("user.js"), FIREFOX_USER_JS)?;
+            vec![
+                "-profile".to_string(),
+                profile_dir,
+                "-no-remote".to_string(),
+                "-new-instance".to_string(),
+                "-devtools".to_string(),
+                url.to_string(),
+            ]
+        }
+        None => {
+            return Err(CustomError::ValidationError(t!(
+                "'{}' is not a Chromium based browser or Firefox, which a separate profile needs",
+                program.display()
+            )));
+        }
+    };
+
+    let child = Command::new(&program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            CustomError::ProcessError(t!("Failed to start {}: {}", program.display(), e))
+        })?;
+
+    ui.log(&t!(
+        "Opened {} with a temporary profile in {}.",
+        program.display(),
+        profile.path().display()
+    ));
+    Ok(IsolatedBrowser {
+        program,
+        child,
+        _profile: profile,
+    })
+}
@@ -1,129 +1,586 @@
 use crate::Ui;
-use crate::assets::generate_assets;
+use crate::assets::{
+    baked_font_files, clean_assets, generate_app_metadata, generate_audio_assets, generate_font_assets, generate_map_assets, generate_scene_assets,
+    refresh_audio_assets, refresh_font_assets,
+};
+use crate::emsdk::resolve_emsdk;
 use crate::error::CustomError;
-use crate::manifest::{Manifest, update_manifest};
+use crate::freshness::{self, is_fresh, mark_fresh, settings_stamp, write_if_changed};
+use crate::i18n::{t, tr};
+use crate::imports::check_imports;
+use crate::lockfile::verify_locked_systems;
+use crate::recovery::{self, Outcome};
+use crate::limits::{spawn_limited, timeout, validate_limits};
+use crate::manifest::{
+    Manifest, ShaderOptions, load_manifest, update_manifest, warn_deprecated_sections,
+};
 use crate::packer::pack_atlas;
+use crate::paths::ProjectPaths;
+use crate::profile::{BuildProfile, resolve_profile};
 use crate::shdc::get_or_install_shdc;
-use crate::sokol;
+use crate::sokol::{self, clean_sokol};
+use crate::staging::{AssetFilter, link_dir, stage_files, unlink_dir};
+use crate::system_config::{load_system_configs, system_odin_flags};
+use crate::tools::require_tools;
+use crate::version::{resolve_versions, web_version_tags, write_version_file};
+use crate::watch::ASSET_STAGES;
+use crate::web_bundle::{WEB_EMBED_STAGE_DIR, WEB_EXTRA_PRELOAD_STAGE_DIR, stage_web_bundles};
+use crate::web_chunks::{WEB_CORE_DATA, chunk_web_data, clean_web_chunks, prepare_chunked_shell};
+use crate::web_packages::{
+    StagedPackage, WEB_PACKAGE_EMCC_FLAGS, WEB_PACKAGES_STAGE_DIR, stage_web_packages, web_package_names,
+    web_package_patterns, web_package_script, write_web_packages_manifest,
+};
+use indicatif::HumanBytes;
 use colored::Colorize;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::{env, fs};
+use std::fs;
 use walkdir::WalkDir;
 
 // general
+// runtime name of the assets directory inside a build
 const ASSETS_DIR: &str = "assets";
 const ATLAS_DIR: &str = "bonsai/core/render/atlas";
 const BONSAI_DIR: &str = "./bonsai";
-const GAME_DIR: &str = "./source/game";
+const WEB_PRELOAD_STAGE_DIR: &str = ".bonsai/cache/web_preload";
 const SHADERS_CACHE_DIR: &str = ".bonsai/cache/shaders";
 const SHADERS_INCLUDE_SRC: &str = "bonsai/shaders/include";
 const SHADERS_CORE_VS_NAME: &str = "shader_vs_core/shader_vs_core.glsl";
 const SHADERS_CORE_FS_NAME: &str = "shader_fs_core/shader_fs_core.glsl";
 const SHADERS_HEADER_NAME: &str = "shader_header/shader_header.glsl";
 const SHADERS_UTILS_NAME: &str = "shader_utils/shader_utils.glsl";
-const SHADERS_BONSAI_SRC: &str = "bonsai/shaders/shader.glsl";
-const SHADERS_BONSAI_OUT: &str = "bonsai/shaders/shader.odin";
-const SHADERS_GAME_SRC: &str = "source/game/shaders";
-const SOURCE_DIR: &str = "source";
+const SHADERS_BONSAI_DIR: &str = "bonsai/shaders";
+const SHADERS_SLANG_STAMP: &str = ".bonsai/cache/shader_slang";
+const SHDC_LANGUAGES: &[&str] = &[
+    "glsl410",
+    "glsl430",
+    "glsl300es",
+    "glsl310es",
+    "hlsl4",
+    "hlsl5",
+    "metal_macos",
+    "metal_ios",
+    "metal_sim",
+    "wgsl",
+    "spirv_vk",
+];
 // build
-const BUILD_SRC: &str = "build";
-const BUILD_DESKTOP_DIR: &str = "build/desktop";
-const BUILD_WEB_DIR: &str = "build/web";
 const DESKTOP_BINARY_NAME: &str = if cfg!(windows) {
     "game_desktop.exe"
 } else {
     "game_desktop.bin"
 };
-const WEB_BINARY_NAME: &str = "game.wasm.o";
+// kept out of the build directory, and between builds so linking alone can rerun
+const WEB_OBJECT_PATH: &str = ".bonsai/cache/web/game.wasm.o";
 const UTILS_DIR: &str = "utils";
-// manifest
-const MANIFEST_NAME: &str = "bonsai.toml";
+const LINK_TARGETS: &[&str] = &["desktop", "windows", "linux", "macos", "web"];
+// manifest sections each stage reads besides its input files, editing any other field
+// leaves the stage up to date; [app] and [project] reach compile through bonsai/generated
+const MANIFEST_STAGE_SECTIONS: &[(&str, &[&str])] = &[
+    ("compile", &["systems", "profile", "target", "paths"]),
+    (
+        "link",
+        &[
+            "build.web_libs",
+            "web.initial_memory_mb",
+            "web.packages",
+            "web.preload",
+            "web.embed",
+            "web.chunk_mb",
+            "target",
+        ],
+    ),
+];
+// file types the odin compiler reads: sources and prebuilt libraries
+const COMPILE_INPUT_EXTENSIONS: &[&str] = &["odin", "a", "lib", "o"];
 // emscripten
+const DEFAULT_INITIAL_MEMORY_MB: u32 = 64;
 const EMSCRIPTEN_FLAGS: &str = "-sWASM_BIGINT \
 -sWARN_ON_UNDEFINED_SYMBOLS=0 \
 -sALLOW_MEMORY_GROWTH \
--sINITIAL_MEMORY=67108864 \
 -sMAX_WEBGL_VERSION=2 \
--sASSERTIONS \
---shell-file bonsai/core/platform/web/index.html \
---preload-file bonsai/core/render/atlas \
---preload-file assets/audio \
---preload-file assets/fonts \
---preload-file bonsai/core/render/PixelCode_9.ttf \
---preload-file .bonsai/cache/sprites/sprites.bin \
---preload-file .bonsai/cache/fonts";
+-sASSERTIONS";
+const WEB_SHELL_SRC: &str = "bonsai/core/platform/web/index.html";
+const WEB_SHELL_STAGED: &str = ".bonsai/cache/web/index.html";
+const WEB_PRELOAD_ENGINE_PATHS: &[&str] = &[
+    "bonsai/core/render/atlas",
+    "bonsai/core/render/PixelCode_9.ttf",
+    ".bonsai/cache/sprites/sprites.bin",
+    ".bonsai/cache/fonts",
+];
+
+/// Files preloaded into the web build, as (source path, path in the virtual filesystem).
+pub fn web_preload_paths(paths: &ProjectPaths) -> Vec<(PathBuf, String)> {
+    let mut preload: Vec<(PathBuf, String)> = WEB_PRELOAD_ENGINE_PATHS
+        .iter()
+        .map(|p| (PathBuf::from(p), p.to_string()))
+        .collect();
+    preload.push((paths.audio(), format!("{}/audio", ASSETS_DIR)));
+    preload.push((paths.fonts(), format!("{}/fonts", ASSETS_DIR)));
+    preload
+}
 
 pub struct BuildResult {
     pub executable_path: PathBuf,
 }
 
-fn prepare_resources(ui: &Ui) -> Result<(), CustomError> {
+// stages whose outputs are written in place, in the order they run
+const PARTIAL_OUTPUT_STAGES: &[&str] = &["assets", "shaders", "sokol", "compile", "copy", "link"];
+
+// ui.stage that also moves the incomplete marker along
+fn enter_stage(id: &str, percent: u8, ui: &Ui) -> Result<(), CustomError> {
+    recovery::enter_stage(id)?;
+    ui.stage(id, percent);
+    Ok(())
+}
+
+fn remove_path(path: &Path, ui: &Ui) -> Result<(), CustomError> {
+    unlink_dir(path)?;
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else if path.exists() {
+        fs::remove_file(path)?;
+    } else {
+        return Ok(());
+    }
+    if ui.verbose {
+        ui.log(&t!("Removed {}", path.display()));
+    }
+    Ok(())
+}
+
+// outputs a build that stopped during `stage` of `target` may have left half-written,
+// every stage and target when the marker didn't say
+fn remove_partial_outputs(target: Option<&str>, stage: Option<&str>, ui: &Ui) -> Result<(), CustomError> {
+    let paths = ProjectPaths::load()?;
+    let stages = match stage {
+        Some(stage) => vec![stage],
+        None => PARTIAL_OUTPUT_STAGES.to_vec(),
+    };
+    let desktop = target.is_none_or(|t| t == "desktop");
+    let web = target.is_none_or(|t| t == "web");
+
+    for stage in stages {
+        match stage {
+            // shaders compile during the assets stage
+            "assets" => {
+                clean_assets(ui)?;
+                clean_shaders(ui)?;
+            }
+            "shaders" => clean_shaders(ui)?,
+            "sokol" => clean_sokol(ui)?,
+            "compile" => {
+                if desktop {
+                    remove_path(&paths.build_target("desktop").join(DESKTOP_BINARY_NAME), ui)?;
+                }
+                if web {
+                    remove_path(Path::new(WEB_OBJECT_PATH), ui)?;
+                }
+            }
+            "copy" => {
+                if desktop {
+                    remove_path(&paths.build_target("desktop").join(ASSETS_DIR), ui)?;
+                }
+                if web {
+                    remove_path(&paths.build_target("web").join(ASSETS_DIR), ui)?;
+                    clean_web_staging(ui)?;
+                }
+            }
+            "link" if web => {
+                for name in ["index.html", "index.js", "index.wasm", "index.data"] {
+                    remove_path(&paths.build_target("web").join(name), ui)?;
+                }
+                clean_web_chunks(&paths.build_target("web"))?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Cleans up after a build that left [`recovery::INCOMPLETE_MARKER`] behind, so none of its
+/// half-written outputs look up to date. Call with the project lock held.
+pub fn recover_incomplete_build(ui: &Ui) -> Result<(), CustomError> {
+    let Some(marker) = recovery::incomplete_build() else {
+        return Ok(());
+    };
+
+    match &marker {
+        Ok(build) => {
+            let stage = if build.stage.is_empty() { "start" } else { build.stage.as_str() };
+            let message = format!(
+                "The {} build started {} (pid {}) {} during '{}', removing what it may have left half-written.",
+                build.target,
+                build.started,
+                build.pid,
+                match build.outcome {
+                    Outcome::Running => "stopped",
+                    Outcome::Failed => "failed",
+                    Outcome::Cancelled => "was cancelled",
+                },
+                stage
+            );
+            // a failed build is expected after a compile error, a vanished one is not
+            if build.outcome == Outcome::Running {
+                ui.message(&format!("{} {}", "[WARNING]".yellow(), message));
+            } else {
+                ui.log(&message);
+            }
+            remove_partial_outputs(Some(&build.target), Some(stage), ui)?;
+        }
+        Err(e) => {
+            ui.message(&t!(
+                "{} {}. Removing the outputs of every build stage.",
+                "[WARNING]".yellow(),
+                e
+            ));
+            remove_partial_outputs(None, None, ui)?;
+        }
+    }
+    recovery::finish()
+}
+
+// the ui every tool of a build is spawned through, under the manifest's [limits]
+fn limited_ui(manifest: &Manifest, ui: &Ui) -> Result<Ui, CustomError> {
+    validate_limits(&manifest.limits)?;
+    Ok(ui.with_limits(manifest.limits))
+}
+
+// runs `build` between the incomplete marker's begin and finish, cleaning up first if the
+// previous build never finished
+fn tracked_build<T>(
+    target: &str,
+    ui: &Ui,
+    build: impl FnOnce() -> Result<T, CustomError>,
+) -> Result<T, CustomError> {
+    recover_incomplete_build(ui)?;
+    recovery::begin(target)?;
+    let result = build();
+    match &result {
+        Ok(_) => recovery::finish()?,
+        Err(e) => recovery::fail(e),
+    }
+    result
+}
+
+fn prepare_resources(is_web_target: bool, ui: &Ui) -> Result<(), CustomError> {
     if ui.verbose {
-        ui.status("Running pre-build tasks...");
+        ui.status(tr("Running pre-build tasks..."));
     }
+    enter_stage("prepare", 0, ui)?;
     check_dependencies()?;
+    let manifest = load_manifest(Path::new("."))?;
+    freshness::init(manifest.build.freshness, ui);
+    require_tools(&manifest.tools)?;
     run_utils(ui)?;
     update_manifest(Path::new("."), ui)?;
-    pack_atlas(Path::new(ASSETS_DIR), Path::new(ATLAS_DIR), ui)?;
-    generate_assets(ui)?;
-    compile_shaders(ui)?;
+    verify_locked_systems(ui)?;
+    let manifest = load_manifest(Path::new("."))?;
+    warn_deprecated_sections(&manifest, ui);
+    let languages = shader_languages(is_web_target, &manifest.shaders)?;
+    let shaders_dir = ProjectPaths::new(&manifest.paths)?.shaders;
+    // shaders share no inputs or outputs with the asset steps, so they compile alongside them
+    enter_stage("assets", 10, ui)?;
+    let (assets, shaders) = rayon::join(
+        || build_assets(&manifest, ui),
+        || compile_shaders(&languages, &manifest.shaders.programs, &shaders_dir, false, ui),
+    );
+    join_errors(vec![assets, shaders], ui)
+}
+
+// every parallel step runs to the end, so one build reports all of them that failed
+fn join_errors(results: Vec<Result<(), CustomError>>, ui: &Ui) -> Result<(), CustomError> {
+    let mut errors: Vec<CustomError> = results.into_iter().filter_map(Result::err).collect();
+    if errors.iter().any(|e| matches!(e, CustomError::Cancelled)) {
+        return Err(CustomError::Cancelled);
+    }
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        failed => {
+            for error in &errors {
+                ui.error(&error.to_string());
+            }
+            Err(CustomError::BuildError(t!("{} asset steps failed", failed)))
+        }
+    }
+}
+
+// atlas, fonts, audio and generated metadata, without touching odin or sokol
+pub fn build_assets(manifest: &Manifest, ui: &Ui) -> Result<(), CustomError> {
+    build_asset_stages(manifest, ASSET_STAGES, ui)
+}
+
+// runs the asset pipeline stages listed in `stages`, skipping the rest
+pub fn build_asset_stages(manifest: &Manifest, stages: &[&str], ui: &Ui) -> Result<(), CustomError> {
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let results = ASSET_STAGES
+        .par_iter()
+        .filter(|s| stages.contains(s))
+        .map(|stage| build_asset_stage(manifest, &paths, stage, ui))
+        .collect();
+    join_errors(results, ui)
+}
+
+fn build_asset_stage(manifest: &Manifest, paths: &ProjectPaths, stage: &'static str, ui: &Ui) -> Result<(), CustomError> {
+    ui.cancel.check()?;
+    // only the atlas is reused when its inputs are unchanged, nothing is packed then
+    let mut cached = false;
+    match stage {
+        "sprites" => {
+            cached = pack_atlas(
+                &paths.assets,
+                Path::new(ATLAS_DIR),
+                &manifest.assets.atlas,
+                &manifest.tools,
+                ui,
+            )?
+            .is_none();
+        }
+        "fonts" => generate_font_assets(paths, &manifest.assets.fonts, ui)?,
+        "audio" => generate_audio_assets(paths)?,
+        "scenes" => generate_scene_assets(paths)?,
+        "maps" => generate_map_assets(paths)?,
+        "app" => generate_app_metadata(
+            &manifest.app,
+            &manifest.project.name,
+            &resolve_versions(manifest)?,
+            &web_package_names(&manifest.web)?,
+        )?,
+        _ => unreachable!(),
+    }
+    let node = match stage {
+        "sprites" => "atlas",
+        "app" => "metadata",
+        other => other,
+    };
+    ui.graph.record(node, cached);
     Ok(())
 }
 
-fn get_c_libraries() -> Vec<String> {
-    vec![
-        "bonsai/libs/sokol/app/sokol_app_wasm_gl_release.a".to_string(),
-        "bonsai/libs/sokol/glue/sokol_glue_wasm_gl_release.a".to_string(),
-        "bonsai/libs/sokol/gfx/sokol_gfx_wasm_gl_release.a".to_string(),
-        "bonsai/libs/sokol/shape/sokol_shape_wasm_gl_release.a".to_string(),
-        "bonsai/libs/sokol/log/sokol_log_wasm_gl_release.a".to_string(),
-        "bonsai/libs/sokol/gl/sokol_gl_wasm_gl_release.a".to_string(),
-        "bonsai/libs/sokol/audio/sokol_audio_wasm_gl_release.a".to_string(),
-        "bonsai/libs/stb/lib/stb_image_wasm.o".to_string(),
-        "bonsai/libs/stb/lib/stb_image_write_wasm.o".to_string(),
-        "bonsai/libs/stb/lib/stb_rect_pack_wasm.o".to_string(),
-        "bonsai/libs/stb/lib/stb_truetype_wasm.o".to_string(),
-    ]
-    .iter()
-    .map(|s| s.to_string())
-    .collect()
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// the shell is copied so the [app] title and versions can be injected without touching the engine sources
+fn stage_web_shell(manifest: &Manifest, packages: &[StagedPackage]) -> Result<PathBuf, CustomError> {
+    let mut shell = fs::read_to_string(WEB_SHELL_SRC)?;
+    let title = manifest.app.title.as_deref().unwrap_or(&manifest.project.name);
+
+    if let (Some(start), Some(end)) = (shell.find("<title>"), shell.find("</title>"))
+        && start < end
+    {
+        shell.replace_range(
+            start + "<title>".len()..end,
+            &html_escape(title),
+        );
+    }
+
+    if let Some(head_end) = shell.find("</head>") {
+        shell.insert_str(head_end, &web_version_tags(&resolve_versions(manifest)?));
+    }
+
+    if let Some(chunk_mb) = manifest.web.chunk_mb {
+        prepare_chunked_shell(&mut shell, chunk_mb)?;
+    }
+
+    if !packages.is_empty()
+        && let Some(body_end) = shell.rfind("</body>")
+    {
+        let packages: Vec<_> = packages.iter().map(|p| p.package.clone()).collect();
+        shell.insert_str(body_end, &web_package_script(&packages)?);
+    }
+
+    let staged = Path::new(WEB_SHELL_STAGED);
+    write_if_changed(staged, shell)?;
+
+    Ok(staged.to_path_buf())
+}
+
+fn get_c_libraries(is_debug: bool) -> Vec<String> {
+    let mut libraries = sokol::wasm_libraries(is_debug);
+    libraries.extend(
+        [
+            "bonsai/libs/stb/lib/stb_image_wasm.o",
+            "bonsai/libs/stb/lib/stb_image_write_wasm.o",
+            "bonsai/libs/stb/lib/stb_rect_pack_wasm.o",
+            "bonsai/libs/stb/lib/stb_truetype_wasm.o",
+        ]
+        .iter()
+        .map(|s| s.to_string()),
+    );
+    libraries
 }
 
-fn compile_shaders(ui: &Ui) -> Result<(), CustomError> {
+// link entries from [target.desktop] plus the current OS, or [target.web]
+fn native_link_flags(manifest: &Manifest, is_web_target: bool) -> Result<Vec<String>, CustomError> {
+    for name in manifest.target.keys() {
+        if !LINK_TARGETS.contains(&name.as_str()) {
+            return Err(CustomError::ValidationError(t!(
+                "Unknown manifest section [target.{}]. Expected one of: {}",
+                name,
+                LINK_TARGETS.join(", ")
+            )));
+        }
+    }
+
+    let os = std::env::consts::OS;
+    let sections: &[&str] = if is_web_target {
+        &["web"]
+    } else {
+        &["desktop", os]
+    };
+
+    let mut flags = Vec::new();
+    for options in sections.iter().filter_map(|s| manifest.target.get(*s)) {
+        for lib in &options.libs {
+            if !Path::new(lib).exists() {
+                return Err(CustomError::ValidationError(t!(
+                    "Native library not found: {}",
+                    lib
+                )));
+            }
+            flags.push(if lib.contains(' ') {
+                format!("\"{}\"", lib)
+            } else {
+                lib.clone()
+            });
+        }
+        for lib in &options.system_libs {
+            flags.push(if os == "windows" && !is_web_target {
+                format!("{}.lib", lib)
+            } else {
+                format!("-l{}", lib)
+            });
+        }
+        if os == "macos" && !is_web_target {
+            for framework in &options.frameworks {
+                flags.push(format!("-framework {}", framework));
+            }
+        }
+        flags.extend(options.linker_flags.iter().cloned());
+    }
+
+    Ok(flags)
+}
+
+// shared libraries from [target.desktop] and the current OS ship next to the binary
+fn copy_runtime_libs(manifest: &Manifest, out_dir: &Path) -> Result<(), CustomError> {
+    let os = std::env::consts::OS;
+    for options in ["desktop", os].iter().filter_map(|s| manifest.target.get(*s)) {
+        for lib in &options.runtime_libs {
+            let src = Path::new(lib);
+            if !src.exists() {
+                return Err(CustomError::ValidationError(t!(
+                    "Runtime library not found: {}",
+                    lib
+                )));
+            }
+            if let Some(name) = src.file_name() {
+                fs::copy(src, out_dir.join(name))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn target_languages(target: &str) -> Result<&'static [&'static str], CustomError> {
+    match target {
+        "web" => Ok(&["glsl300es"]),
+        "windows" => Ok(&["hlsl4", "glsl430"]),
+        "macos" => Ok(&["metal_macos", "glsl430"]),
+        "linux" => Ok(&["glsl430"]),
+        _ => Err(CustomError::ValidationError(t!(
+            "Unknown shader target '{}' in [shaders] (expected windows, macos, linux or web)",
+            target
+        ))),
+    }
+}
+
+// sokol-shdc language list for the current build target plus any extra platforms from the manifest
+fn shader_languages(is_web_target: bool, options: &ShaderOptions) -> Result<String, CustomError> {
+    if !options.slang.is_empty() {
+        if let Some(unknown) = options
+            .slang
+            .iter()
+            .find(|l| !SHDC_LANGUAGES.contains(&l.as_str()))
+        {
+            return Err(CustomError::ValidationError(t!(
+                "Unknown shader language '{}' in [shaders] slang",
+                unknown
+            )));
+        }
+        return Ok(options.slang.join(":"));
+    }
+
+    let current = if is_web_target {
+        "web"
+    } else {
+        std::env::consts::OS
+    };
+
+    let mut languages: Vec<&str> = Vec::new();
+    for target in std::iter::once(current).chain(options.targets.iter().map(|t| t.as_str())) {
+        for language in target_languages(target)? {
+            if !languages.contains(language) {
+                languages.push(language);
+            }
+        }
+    }
+
+    Ok(languages.join(":"))
+}
+
+// recompiles changed shaders only, without the rest of the build pipeline
+pub fn compile_shaders_only(is_web_target: bool, force: bool, ui: &Ui) -> Result<(), CustomError> {
+    let manifest = load_manifest(Path::new("."))?;
+    compile_shaders(
+        &shader_languages(is_web_target, &manifest.shaders)?,
+        &manifest.shaders.programs,
+        &ProjectPaths::new(&manifest.paths)?.shaders,
+        force,
+        ui,
+    )
+}
+
+fn compile_shaders(
+    shader_format: &str,
+    programs: &BTreeMap<String, String>,
+    game_shaders_dir: &Path,
+    force: bool,
+    ui: &Ui,
+) -> Result<(), CustomError> {
     let shdc_path = get_or_install_shdc(ui);
     let shdc_str = shdc_path.to_string_lossy();
 
+    // outputs compiled for a different language set must be rebuilt
+    let stamp_path = Path::new(SHADERS_SLANG_STAMP);
+    let forced_by = match fs::read_to_string(stamp_path).ok() {
+        _ if force => Some("a full shader rebuild was requested".to_string()),
+        None => Some("no shader languages were recorded (first build, --clean or --force-shaders)".to_string()),
+        Some(previous) if previous != shader_format => Some(format!(
+            "the shader languages changed from '{}' to '{}'",
+            previous, shader_format
+        )),
+        Some(_) => None,
+    };
+
     let cache_dir = Path::new(SHADERS_CACHE_DIR);
     if cache_dir.exists() {
         fs::remove_dir_all(cache_dir).map_err(|e| CustomError::IoError(e))?;
     }
     fs::create_dir_all(cache_dir).map_err(|e| CustomError::IoError(e))?;
 
-    let include_dir = Path::new(SHADERS_INCLUDE_SRC);
-    fs::copy(
-        include_dir.join(SHADERS_CORE_VS_NAME),
-        cache_dir.join("shader_vs_core.glsl"),
-    )?;
-    fs::copy(
-        include_dir.join(SHADERS_CORE_FS_NAME),
-        cache_dir.join("shader_fs_core.glsl"),
-    )?;
-    fs::copy(
-        include_dir.join(SHADERS_UTILS_NAME),
-        cache_dir.join("shader_utils.glsl"),
-    )?;
-    fs::copy(
-        include_dir.join(SHADERS_HEADER_NAME),
-        cache_dir.join("shader_header.glsl"),
-    )?;
-
-    let shader_format = if cfg!(target_os = "windows") {
-        "glsl300es:hlsl4:glsl430"
-    } else {
-        "metal_macos:glsl300es:hlsl4:glsl430"
-    };
+    let core_includes = core_shader_includes();
+    for (name, path) in &core_includes {
+        fs::copy(path, cache_dir.join(name))?;
+    }
 
     let compile_shader_cached = |src_path: &Path,
                                  out_path: &Path,
@@ -137,11 +594,11 @@ fn compile_shaders(ui: &Ui) -> Result<(), CustomError> {
 
         fs::copy(src_path, &cached_path).map_err(|e| CustomError::IoError(e))?;
 
-        ui.status(&format!("Compiling shader: {}", src_path.to_string_lossy()));
+        ui.cancel.check()?;
+        ui.status(&t!("Compiling shader: {}", src_path.to_string_lossy()));
 
-        run_with_prefix(
-            &shdc_str,
-            &[
+        let mut command = Command::new(shdc_str.as_ref());
+        command.args([
                 "-i",
                 cached_path_str,
                 "-o",
@@ -150,94 +607,374 @@ fn compile_shaders(ui: &Ui) -> Result<(), CustomError> {
                 shader_format,
                 "-f",
                 "sokol_odin",
-            ],
-            log_prefix,
-            color,
-            ui,
-        )
-    };
+            ]);
+        let output = match ui.cancel.output(&mut command, &ui.limits) {
+            Ok(output) => output,
+            Err(CustomError::Cancelled) => {
+                // a half-written output would look up to date next time
+                let _ = fs::remove_file(out_path);
+                return Err(CustomError::Cancelled);
+            }
+            Err(e) => {
+                return Err(CustomError::ProcessError(t!(
+                    "Failed to start sokol-shdc: {}",
+                    e
+                )));
+            }
+        };
+
+        // diagnostics refer to the cached copy, point them back at the source file
+        let src_display = src_path.to_string_lossy();
+        let diagnostics = [output.stdout.as_slice(), output.stderr.as_slice()]
+            .iter()
+            .flat_map(|bytes| String::from_utf8_lossy(bytes).lines().map(str::to_string).collect::<Vec<_>>())
+            .map(|line| line.replace(cached_path_str, &src_display))
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+
+        for line in &diagnostics {
+            let text = format!("{} {}", log_prefix, line);
+            if line.contains("error") {
+                ui.error(&format!("{}", text.color(colored::Color::Red)));
+            } else if line.contains("warning") {
+                ui.message(&format!("{}", text.color(colored::Color::Yellow)));
+            } else if ui.verbose {
+                ui.message(&format!("{}", text.color(color)));
+            }
+        }
 
-    if !should_skip(Path::new(SHADERS_BONSAI_SRC), Path::new(SHADERS_BONSAI_OUT))? {
-        compile_shader_cached(
-            Path::new(SHADERS_BONSAI_SRC),
-            Path::new(SHADERS_BONSAI_OUT),
-            "[CORE SHDC]",
-            colored::Color::Cyan,
-        )?;
-    } else if ui.verbose {
-        ui.log("Core shader compilation skipped (already compiled).");
-    }
+        if !output.status.success() {
+            return Err(CustomError::BuildError(t!(
+                "Shader compilation failed for {}",
+                src_display
+            )));
+        }
 
-    let walker = WalkDir::new(SHADERS_GAME_SRC).into_iter();
+        Ok(())
+    };
 
-    for entry in walker.filter_map(|e| e.ok()) {
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
+    for core_shader in core_shaders() {
+        let output_path = core_shader.with_extension("odin");
+        let core_sources = shader_sources(&core_shader, &core_includes, None)?;
+        let core_cached = match &forced_by {
+            Some(why) => {
+                freshness::mark_forced(&output_path, why);
+                false
+            }
+            None => is_fresh(&core_sources, &output_path)?,
+        };
+        ui.metrics.cache(core_cached);
+        ui.graph.record("shaders", core_cached);
+
+        if !core_cached {
+            compile_shader_cached(&core_shader, &output_path, "[CORE SHDC]", colored::Color::Cyan)?;
+            mark_fresh(&core_sources, &output_path)?;
+        } else if ui.verbose {
+            ui.log(&t!(
+                "Core shader compilation skipped for {} (already compiled).",
+                core_shader.display()
+            ));
         }
+    }
 
-        match path.extension().and_then(|s| s.to_str()) {
-            Some("glsl") | Some("vert") | Some("frag") => {}
-            _ => continue,
+    let walker = WalkDir::new(game_shaders_dir).into_iter();
+
+    let mut game_shaders: Vec<PathBuf> = walker
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|s| s.to_str()),
+                Some("glsl") | Some("vert") | Some("frag")
+            )
+        })
+        .collect();
+
+    // registered programs living outside the game shader directory
+    for program_path in programs.values() {
+        let path = PathBuf::from(program_path);
+        if !path.starts_with(game_shaders_dir) {
+            if !path.is_file() {
+                return Err(CustomError::ValidationError(t!(
+                    "Shader registered in [shaders.programs] not found: {}",
+                    program_path
+                )));
+            }
+            game_shaders.push(path);
         }
+    }
 
+    let mut shader_inputs = Vec::new();
+    for path in &game_shaders {
+        shader_inputs.push((path, shader_sources(path, &core_includes, None)?));
+    }
+    // files other shaders @include are compiled as part of them, not on their own
+    let included: BTreeSet<&PathBuf> = shader_inputs
+        .iter()
+        .flat_map(|(_, sources)| sources.iter().skip(1))
+        .collect();
+
+    for (path, sources) in &shader_inputs {
+        if included.contains(path) {
+            continue;
+        }
         let output_path = path.with_extension("odin");
-        if should_skip(path, &output_path)? {
+        let cached = match &forced_by {
+            Some(why) => {
+                freshness::mark_forced(&output_path, why);
+                false
+            }
+            None => is_fresh(sources, &output_path)?,
+        };
+        ui.metrics.cache(cached);
+        ui.graph.record("shaders", cached);
+        if cached {
             continue;
         }
 
+        // includes with the same name may live next to other shaders, so stage this one's last
+        shader_sources(path, &core_includes, Some(cache_dir))?;
         compile_shader_cached(
             path,
             &output_path,
             "[GAME SHDC]",
             colored::Color::BrightBlue,
         )?;
+        mark_fresh(sources, &output_path)?;
     }
 
+    fs::write(stamp_path, shader_format)?;
+
     Ok(())
 }
 
+/// The engine's shader programs: every `.glsl` directly in `bonsai/shaders`, each compiled to
+/// an `.odin` file beside it. The include directory only holds files they pull in.
+pub fn core_shaders() -> Vec<PathBuf> {
+    let mut shaders: Vec<PathBuf> = fs::read_dir(SHADERS_BONSAI_DIR)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("glsl"))
+        .collect();
+    shaders.sort();
+    shaders
+}
+
+// the engine's shared shader files, by the name shaders @include them with
+fn core_shader_includes() -> BTreeMap<String, PathBuf> {
+    let include_dir = Path::new(SHADERS_INCLUDE_SRC);
+    [
+        SHADERS_CORE_VS_NAME,
+        SHADERS_CORE_FS_NAME,
+        SHADERS_UTILS_NAME,
+        SHADERS_HEADER_NAME,
+    ]
+    .iter()
+    .map(|relative| {
+        let path = include_dir.join(relative);
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        (name, path)
+    })
+    .collect()
+}
+
+/// `shader` and every file it pulls in through `@include`, recursively. sokol-shdc resolves
+/// includes next to the compiled copy in the cache, so engine includes are found by name and
+/// any other include is looked up beside the source, and copied into `stage_dir` when given.
+fn shader_sources(
+    shader: &Path,
+    core_includes: &BTreeMap<String, PathBuf>,
+    stage_dir: Option<&Path>,
+) -> Result<Vec<PathBuf>, CustomError> {
+    let mut sources = vec![shader.to_path_buf()];
+    let mut pending = vec![(shader.to_path_buf(), shader.parent().unwrap_or(Path::new("")).to_path_buf())];
+    let mut seen: BTreeSet<PathBuf> = BTreeSet::new();
+    seen.insert(shader.to_path_buf());
+
+    while let Some((file, base_dir)) = pending.pop() {
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for line in content.lines() {
+            let Some(name) = line.trim().strip_prefix("@include") else {
+                continue;
+            };
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            let local = base_dir.join(name);
+            let path = if let Some(core) = core_includes.get(name) {
+                core.clone()
+            } else if local.is_file() {
+                if let Some(stage_dir) = stage_dir {
+                    let staged = stage_dir.join(name);
+                    if let Some(parent) = staged.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::copy(&local, &staged)?;
+                }
+                local
+            } else {
+                // sokol-shdc reports the missing file itself
+                continue;
+            };
+            if seen.insert(path.clone()) {
+                sources.push(path.clone());
+                pending.push((path, base_dir.clone()));
+            }
+        }
+    }
+    Ok(sources)
+}
+
 fn to_emcc_path(path: &Path) -> String {
     path.to_str().unwrap_or("").replace("\\", "/")
 }
 
+/// Stamp of the manifest sections `stage` reads (see [`MANIFEST_STAGE_SECTIONS`]) and the exact
+/// command it runs, which also carries what it takes from system.toml and the build config.
+fn stage_settings(manifest: &Manifest, stage: &str, command: &str) -> Result<PathBuf, CustomError> {
+    let serialize_error =
+        |e: serde_json::Error| CustomError::ValidationError(t!("Failed to serialize manifest: {}", e));
+    let manifest = serde_json::to_value(manifest).map_err(serialize_error)?;
+
+    let mut settings = serde_json::Map::new();
+    for section in MANIFEST_STAGE_SECTIONS
+        .iter()
+        .filter(|(name, _)| *name == stage)
+        .flat_map(|(_, sections)| sections.iter())
+    {
+        let value = manifest.pointer(&format!("/{}", section.replace('.', "/")));
+        settings.insert(section.to_string(), value.cloned().unwrap_or_default());
+    }
+    settings.insert("command".to_string(), command.into());
+
+    settings_stamp(stage, &serde_json::to_string_pretty(&settings).map_err(serialize_error)?)
+}
+
+// odin sources and prebuilt libraries under the directories the compiler reads
+fn compile_inputs(paths: &ProjectPaths) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for root in [paths.source.clone(), PathBuf::from(BONSAI_DIR), paths.shaders.clone()] {
+        if !roots.iter().any(|r| root.starts_with(r)) {
+            roots.push(root);
+        }
+    }
+
+    roots
+        .iter()
+        // linked systems are symlinks into another checkout
+        .flat_map(|root| WalkDir::new(root).follow_links(true))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| COMPILE_INPUT_EXTENSIONS.contains(&ext))
+        })
+        .collect()
+}
+
 fn compile_project(
     is_web_target: bool,
-    config: &str,
+    profile: &BuildProfile,
     clean: bool,
     ui: &Ui,
 ) -> Result<PathBuf, CustomError> {
-    let is_debug = config == "debug";
+    let manifest = load_manifest(Path::new("."))?;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let is_debug = profile.links_debug_libraries();
+
+    // odin only says "cannot find package", resolve imports first to say why
+    let systems = load_system_configs();
+    for system in &systems {
+        if let Some(error) = &system.error {
+            ui.message(&t!(
+                "{} Ignoring the system.toml of '{}': {}",
+                "[WARNING]".yellow(),
+                system.name,
+                error
+            ));
+        }
+    }
+    let import_issues = check_imports(&manifest, &systems);
+    if !import_issues.is_empty() {
+        for issue in &import_issues {
+            ui.error(&format!(
+                "{}",
+                format!("[IMPORT] {}({}): {}", issue.file.display(), issue.line, issue.message)
+                    .red()
+            ));
+        }
+        return Err(CustomError::BuildError(t!(
+            "{} unresolved package import(s)",
+            import_issues.len()
+        )));
+    }
+
+    enter_stage("sokol", 40, ui)?;
     sokol::compile_sokol(is_web_target, is_debug, clean, ui)?;
+    enter_stage("compile", 55, ui)?;
 
-    let (out_dir_str, binary_name) = if is_web_target {
-        (BUILD_WEB_DIR, WEB_BINARY_NAME)
+    let out_path = if is_web_target {
+        PathBuf::from(WEB_OBJECT_PATH)
     } else {
-        (BUILD_DESKTOP_DIR, DESKTOP_BINARY_NAME)
+        paths.build_target("desktop").join(DESKTOP_BINARY_NAME)
     };
-
-    let out_dir = Path::new(out_dir_str);
-    let out_path = out_dir.join(binary_name);
     let out_clean_str = to_emcc_path(&out_path);
     let out_clean_path = Path::new(&out_clean_str).to_path_buf();
 
-    if !out_dir.exists() {
+    if let Some(out_dir) = out_path.parent()
+        && !out_dir.exists()
+    {
         fs::create_dir_all(out_dir).map_err(CustomError::IoError)?;
     }
 
-    let mut args = vec!["build", SOURCE_DIR, "-vet", "-strict-style"];
+    let mut vetted_roots = vec![paths.source.clone(), PathBuf::from("bonsai")];
+    if !paths.shaders.starts_with(&paths.source) {
+        vetted_roots.push(paths.shaders.clone());
+    }
+    let system_flags = system_odin_flags(&systems, &vetted_roots)?;
+    if !system_flags.unvetted.is_empty() {
+        ui.log(&t!(
+            "Not vetting systems: {}.",
+            system_flags.unvetted.join(", ")
+        ));
+    }
+    if !system_flags.loose_style.is_empty() {
+        ui.log(&t!(
+            "Building without -strict-style for systems: {}.",
+            system_flags.loose_style.join(", ")
+        ));
+    }
+
+    let source_dir = to_emcc_path(&paths.source);
+    let mut args = vec!["build", source_dir.as_str(), "-vet"];
+    if system_flags.loose_style.is_empty() {
+        args.push("-strict-style");
+    }
+    let vet_packages_flag = system_flags
+        .vet_packages
+        .as_ref()
+        .map(|packages| format!("-vet-packages:{}", packages));
+    if let Some(flag) = &vet_packages_flag {
+        args.push(flag);
+    }
+    args.extend(system_flags.defines.iter().map(|s| s.as_str()));
 
     if is_web_target {
         args.push("-target:js_wasm32");
         args.push("-build-mode:obj");
     }
 
-    if config == "debug" {
-        args.push("-debug");
-    } else {
-        args.push("-o:speed");
-        args.push("-no-bounds-check");
-    }
+    let profile_args = profile.odin_args();
+    ui.log(&t!("Profile '{}': {}.", profile.name, profile_args.join(" ")));
+    args.extend(profile_args.iter().map(|s| s.as_str()));
 
     let out_flag = format!("-out:{}", out_clean_path.to_string_lossy());
     args.push(&out_flag);
@@ -245,9 +982,29 @@ fn compile_project(
     let bonsai_collection_flag = format!("-collection:bonsai={}", BONSAI_DIR);
     args.push(&bonsai_collection_flag);
 
-    let game_collection_flag = format!("-collection:game={}", GAME_DIR);
+    let game_collection_flag = format!("-collection:game={}", to_emcc_path(&paths.game()));
     args.push(&game_collection_flag);
 
+    let link_flags = native_link_flags(&manifest, is_web_target)?;
+    let extra_linker_flag = format!("-extra-linker-flags:{}", link_flags.join(" "));
+    if !is_web_target && !link_flags.is_empty() {
+        args.push(&extra_linker_flag);
+    }
+
+    let mut inputs = compile_inputs(&paths);
+    inputs.extend(link_flags.iter().map(PathBuf::from).filter(|path| path.is_file()));
+    inputs.push(stage_settings(&manifest, "compile", &args.join(" "))?);
+    if clean {
+        freshness::mark_forced(&out_clean_path, "--clean");
+    }
+    let cached = !clean && is_fresh(&inputs, &out_clean_path)?;
+    ui.metrics.cache(cached);
+    ui.graph.record("compile", cached);
+    if cached {
+        ui.log(tr("Compile skipped, sources and compile settings are unchanged."));
+        return Ok(out_clean_path);
+    }
+
     run_with_prefix(
         "odin",
         &args.iter().map(|s| s.as_ref()).collect::<Vec<&str>>(),
@@ -255,41 +1012,144 @@ fn compile_project(
         colored::Color::Blue,
         ui,
     )?;
+    mark_fresh(&inputs, &out_clean_path)?;
 
     Ok(out_clean_path)
 }
 
 pub fn build_desktop(config: &str, clean: bool, ui: &Ui) -> Result<BuildResult, CustomError> {
-    prepare_resources(ui)?;
+    tracked_build("desktop", ui, || desktop_stages(config, clean, ui))
+}
+
+fn desktop_stages(config: &str, clean: bool, ui: &Ui) -> Result<BuildResult, CustomError> {
+    let manifest = load_manifest(Path::new("."))?;
+    let ui = &limited_ui(&manifest, ui)?;
+    let profile = resolve_profile(&manifest, config)?;
+    prepare_resources(false, ui)?;
 
-    let binary_path = compile_project(false, config, clean, ui)?;
+    let binary_path = compile_project(false, &profile, clean, ui)?;
 
-    ui.status("Copying assets...");
+    enter_stage("copy", 90, ui)?;
+    ui.status(tr("Copying assets..."));
     let out_dir = binary_path.parent().unwrap();
-    let assets_src = Path::new(ASSETS_DIR);
-    let assets_dest = out_dir.join(ASSETS_DIR);
+    let manifest = load_manifest(Path::new("."))?;
+    place_desktop_assets(&manifest, profile.link_assets, out_dir, ui)?;
 
-    if assets_src.exists() {
-        copy_dir_recursive(assets_src, &assets_dest)?;
-    }
+    copy_runtime_libs(&manifest, out_dir)?;
+    write_version_file(out_dir, &resolve_versions(&manifest)?)?;
 
+    enter_stage("done", 100, ui)?;
     Ok(BuildResult {
         executable_path: binary_path,
     })
 }
 
+/// Reruns only the font packing and audio metadata for the `changed` files of a desktop
+/// build and refreshes the assets next to `executable`, so the running game can be restarted
+/// without the rest of the pipeline. Returns false when the generated metadata changed, the
+/// game then has to be recompiled with a full build.
+pub fn refresh_desktop_assets(
+    config: &str,
+    executable: &Path,
+    changed: &[PathBuf],
+    ui: &Ui,
+) -> Result<bool, CustomError> {
+    tracked_build("desktop", ui, || {
+        let manifest = load_manifest(Path::new("."))?;
+        let ui = &limited_ui(&manifest, ui)?;
+        let profile = resolve_profile(&manifest, config)?;
+        freshness::init(manifest.build.freshness, ui);
+        let paths = ProjectPaths::new(&manifest.paths)?;
+
+        enter_stage("assets", 10, ui)?;
+        let mut metadata_changed = false;
+        let has_extension = |extensions: &[&str]| {
+            changed.iter().any(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| extensions.contains(&ext))
+            })
+        };
+        if has_extension(&["ttf", "otf"]) {
+            metadata_changed |= refresh_font_assets(&paths, &manifest.assets.fonts, changed, ui)?;
+            ui.graph.record("fonts", false);
+        }
+        if has_extension(&["wav"]) {
+            metadata_changed |= refresh_audio_assets(&paths)?;
+            ui.graph.record("audio", false);
+        }
+        if metadata_changed {
+            ui.log(tr("Asset names changed, the game needs to be recompiled."));
+            return Ok(false);
+        }
+
+        enter_stage("copy", 90, ui)?;
+        let out_dir = executable.parent().unwrap_or(Path::new("."));
+        place_desktop_assets(&manifest, profile.link_assets, out_dir, ui)?;
+
+        enter_stage("done", 100, ui)?;
+        Ok(true)
+    })
+}
+
+// copies the assets next to the desktop binary, or links them with [profile] link_assets
+fn place_desktop_assets(manifest: &Manifest, link_assets: bool, out_dir: &Path, ui: &Ui) -> Result<(), CustomError> {
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let assets_dest = out_dir.join(ASSETS_DIR);
+
+    if link_assets && paths.assets.exists() {
+        link_dir(&paths.assets, &assets_dest)?;
+        ui.graph.record("copy", true);
+        if ui.verbose {
+            ui.log(tr("Linked build assets to the source assets directory."));
+        }
+    } else {
+        unlink_dir(&assets_dest)?;
+        let filter = AssetFilter::new(&asset_excludes(manifest, &paths)?)?.with_paths(&paths);
+
+        let copied = stage_files(&paths.assets, &assets_dest, &filter)?;
+        ui.graph.record("copy", copied == 0);
+        if ui.verbose {
+            ui.log(&t!("Copied {} changed asset files.", copied));
+        }
+    }
+    Ok(())
+}
+
+// [assets] exclude plus the files of fonts [assets.fonts] bakes, builds only need their atlases
+fn asset_excludes(manifest: &Manifest, paths: &ProjectPaths) -> Result<Vec<String>, CustomError> {
+    let mut excludes = manifest.assets.exclude.clone();
+    for file in baked_font_files(paths, &manifest.assets.fonts)?.values() {
+        excludes.push(globset::escape(&paths.logical(file)));
+    }
+    Ok(excludes)
+}
+
 pub fn build_web(config: &str, clean: bool, ui: &Ui) -> Result<(), CustomError> {
-    prepare_resources(ui)?;
+    tracked_build("web", ui, || web_stages(config, clean, ui))
+}
+
+fn web_stages(config: &str, clean: bool, ui: &Ui) -> Result<(), CustomError> {
+    let manifest = load_manifest(Path::new("."))?;
+    let ui = &limited_ui(&manifest, ui)?;
+    let profile = resolve_profile(&manifest, config)?;
+    let emsdk_path = resolve_emsdk(ui)?;
 
-    let object_file = compile_project(true, config, clean, ui)?;
+    prepare_resources(true, ui)?;
 
-    ui.status("Copying runtime files...");
-    let out_dir = object_file.parent().unwrap();
+    let object_file = compile_project(true, &profile, clean, ui)?;
+
+    enter_stage("copy", 80, ui)?;
+    ui.status(tr("Copying runtime files..."));
+    let manifest = load_manifest(Path::new("."))?;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let out_dir = &paths.build_target("web");
+    fs::create_dir_all(out_dir)?;
 
     let odin_root_out = Command::new("odin")
         .arg("root")
         .output()
-        .map_err(|_| CustomError::BuildError("Could not find 'odin' to get root path".into()))?;
+        .map_err(|_| CustomError::BuildError(t!("Could not find 'odin' to get root path")))?;
 
     let odin_root_str = String::from_utf8_lossy(&odin_root_out.stdout)
         .trim()
@@ -301,33 +1161,114 @@ pub fn build_web(config: &str, clean: bool, ui: &Ui) -> Result<(), CustomError>
 
     fs::copy(&odin_js_src, &odin_js_dest).map_err(|e| CustomError::IoError(e))?;
 
-    let assets_src = Path::new(ASSETS_DIR);
-    let assets_dest = out_dir.join(ASSETS_DIR);
-    if assets_src.exists() {
-        copy_dir_recursive(assets_src, &assets_dest).map_err(CustomError::IoError)?;
+    let filter = AssetFilter::new(&asset_excludes(&manifest, &paths)?)?.with_paths(&paths);
+    // packaged assets are fetched on demand, keep them out of the core bundle and the copied assets
+    let core_filter = AssetFilter::new(
+        &asset_excludes(&manifest, &paths)?
+            .into_iter()
+            .chain(web_package_patterns(&manifest.web))
+            .collect::<Vec<_>>(),
+    )?
+    .with_paths(&paths);
+
+    unlink_dir(&out_dir.join(ASSETS_DIR))?;
+    stage_files(&paths.assets, &out_dir.join(ASSETS_DIR), &core_filter)?;
+
+    // checked before anything is packaged or linked
+    let (extra_preload, embedded) = stage_web_bundles(&manifest.web, &paths, &filter)?;
+    let default_preload_filter = AssetFilter::new(
+        &asset_excludes(&manifest, &paths)?
+            .into_iter()
+            .chain(web_package_patterns(&manifest.web))
+            .chain(manifest.web.embed.iter().cloned())
+            .collect::<Vec<_>>(),
+    )?
+    .with_paths(&paths);
+
+    // stage preloaded files so excluded assets never end up in the .data bundle
+    let preload_stage = Path::new(WEB_PRELOAD_STAGE_DIR);
+    let mut preload_flags = web_preload_paths(&paths)
+        .par_iter()
+        .map(|(src, mount)| -> Result<String, CustomError> {
+            let staged = preload_stage.join(mount);
+            let copied = stage_files(src, &staged, &default_preload_filter)?;
+            ui.graph.record("preload", copied == 0);
+            Ok(format!("--preload-file {}@{}", to_emcc_path(&staged), mount))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    preload_flags.extend(extra_preload.emcc_flag("--preload-file", to_emcc_path));
+    preload_flags.extend(embedded.emcc_flag("--embed-file", to_emcc_path));
+    let preload_flags = preload_flags.join(" ");
+
+    let preload_bytes: u64 = WalkDir::new(preload_stage)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum::<u64>()
+        + extra_preload.bytes;
+    ui.log(&t!("Core preload bundle: {}.", HumanBytes(preload_bytes)));
+    if embedded.files > 0 {
+        ui.log(&t!(
+            "Embedded into the wasm: {} files, {}.",
+            embedded.files,
+            HumanBytes(embedded.bytes)
+        ));
+    }
+    if let Some(budget_mb) = manifest.web.preload_budget_mb
+        && preload_bytes > budget_mb as u64 * 1024 * 1024
+    {
+        ui.message(&t!(
+            "{} Core preload bundle is over the {} MB budget, move assets into [web.packages].",
+            "[WARNING]".yellow(),
+            budget_mb
+        ));
     }
 
-    ui.status("Linking with Emscripten...");
-    let emsdk_path = get_emsdk_path()?;
+    let packages = stage_web_packages(&manifest.web, &paths, &filter)?;
+    for staged in &packages {
+        let package = &staged.package;
+        if package.files == 0 {
+            ui.message(&t!(
+                "{} Web package '{}' matches no files.",
+                "[WARNING]".yellow(),
+                package.name
+            ));
+            continue;
+        }
 
-    let mut libraries = get_c_libraries();
-    libraries.insert(0, object_file.to_string_lossy().to_string());
+        ui.graph.record("packages", false);
+        ui.status(&t!("Packaging '{}'...", package.name));
+        let packager_cmd = format!(
+            "file_packager {} --preload {}@/ --js-output={}",
+            to_emcc_path(&out_dir.join(format!("{}.data", package.name))),
+            to_emcc_path(&staged.stage_dir),
+            to_emcc_path(&out_dir.join(format!("{}.js", package.name))),
+        );
+        run_in_emsdk(&packager_cmd, &emsdk_path, ui)?;
+        ui.log(&t!(
+            "Web package '{}': {} files, {}.",
+            package.name,
+            package.files,
+            HumanBytes(package.bytes)
+        ));
+    }
 
-    let manifest_content =
-        fs::read_to_string(MANIFEST_NAME).map_err(|e| CustomError::IoError(e))?;
+    enter_stage("link", 85, ui)?;
 
-    let manifest: Manifest = toml_edit::de::from_str(&manifest_content)
-        .map_err(|e| CustomError::ValidationError(format!("Invalid manifest: {}", e)))?;
+    let mut libraries = get_c_libraries(profile.links_debug_libraries());
+    libraries.insert(0, object_file.to_string_lossy().to_string());
 
     if !manifest.build.web_libs.is_empty() {
-        ui.message(&format!(
+        ui.message(&t!(
             "  + Adding {} external libraries from manifest.",
             manifest.build.web_libs.len()
         ));
-        for lib in manifest.build.web_libs {
+        for lib in &manifest.build.web_libs {
             let lib_path = Path::new(&lib);
             if !lib_path.exists() {
-                return Err(CustomError::ValidationError(format!(
+                return Err(CustomError::ValidationError(t!(
                     "External library not found: {}",
                     lib
                 )));
@@ -336,31 +1277,110 @@ pub fn build_web(config: &str, clean: bool, ui: &Ui) -> Result<(), CustomError>
         }
     }
 
+    libraries.extend(native_link_flags(&manifest, true)?);
+
     let libs_str = libraries.join(" ");
+    let shell_file = stage_web_shell(&manifest, &packages)?;
     let out_html = to_emcc_path(out_dir.join("index.html").as_path());
 
-    let emcc_cmd = format!("emcc -o {} {} {} -g", out_html, libs_str, EMSCRIPTEN_FLAGS);
+    let initial_memory = manifest
+        .web
+        .initial_memory_mb
+        .unwrap_or(DEFAULT_INITIAL_MEMORY_MB) as u64
+        * 1024
+        * 1024;
 
-    run_in_emsdk(&emcc_cmd, &emsdk_path)?;
+    let package_flags = if packages.is_empty() {
+        ""
+    } else {
+        WEB_PACKAGE_EMCC_FLAGS
+    };
 
-    let binary_path = Path::new(BUILD_WEB_DIR).join(WEB_BINARY_NAME);
-    let _ = fs::remove_file(binary_path);
+    // saves persist in IndexedDB through IDBFS
+    let emcc_cmd = format!(
+        "emcc -o {} {} {} {} -lidbfs.js --shell-file {} {} -sINITIAL_MEMORY={} {}",
+        out_html,
+        libs_str,
+        EMSCRIPTEN_FLAGS,
+        package_flags,
+        to_emcc_path(&shell_file),
+        preload_flags,
+        initial_memory,
+        profile.emcc_args().join(" ")
+    );
+
+    let outputs = ["index.html", "index.js", "index.wasm"].map(|name| out_dir.join(name));
+    let mut inputs = vec![shell_file.clone()];
+    inputs.extend(libraries.iter().map(PathBuf::from).filter(|path| path.is_file()));
+    inputs.extend(
+        WalkDir::new(preload_stage)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path()),
+    );
+    inputs.extend(extra_preload.staged_files());
+    inputs.extend(embedded.staged_files());
+    inputs.push(stage_settings(&manifest, "link", &emcc_cmd)?);
+    let mut cached = !clean;
+    for output in &outputs {
+        if clean {
+            freshness::mark_forced(output, "--clean");
+        } else {
+            // every output is checked, so the rebuild log explains each of them
+            cached = is_fresh(&inputs, output)? && cached;
+        }
+    }
+    ui.metrics.cache(cached);
+    ui.graph.record("link", cached);
 
-    ui.success("Web build created in build/web.");
+    if cached {
+        ui.log(tr("Link skipped, its inputs and link settings are unchanged."));
+    } else {
+        ui.status(tr("Linking with Emscripten..."));
+        run_in_emsdk(&emcc_cmd, &emsdk_path, ui)?;
+        for output in &outputs {
+            mark_fresh(&inputs, output)?;
+        }
+    }
+
+    // index.data comes from the link and the package bundles from file_packager, both are in place now
+    let data: Vec<String> = std::iter::once(WEB_CORE_DATA.to_string())
+        .chain(packages.iter().filter_map(|p| p.package.data.clone()))
+        .collect();
+    let chunked = chunk_web_data(out_dir, &data, manifest.web.chunk_mb)?;
+    if !chunked.is_empty() {
+        ui.log(&t!(
+            "Split {} data bundles into {} chunks.",
+            chunked.len(),
+            chunked.values().map(|c| c.chunks.len()).sum::<usize>()
+        ));
+    }
+
+    write_version_file(out_dir, &resolve_versions(&manifest)?)?;
+    write_web_packages_manifest(
+        out_dir,
+        &packages.into_iter().map(|p| p.package).collect::<Vec<_>>(),
+    )?;
+
+    enter_stage("done", 100, ui)?;
+    ui.success(&t!("Web build created in {}.", out_dir.display()));
     Ok(())
 }
 
 pub fn clean_build(ui: &Ui) -> Result<(), CustomError> {
-    let build_dir = Path::new(BUILD_SRC);
+    let build_dir = ProjectPaths::load()?.build;
     if build_dir.exists() {
-        fs::remove_dir_all(build_dir)?;
-        ui.log("Cleaned build directory.");
+        fs::remove_dir_all(&build_dir)?;
+        ui.log(tr("Cleaned build directory."));
     }
 
-    let shader_output = Path::new(SHADERS_BONSAI_OUT);
-    if shader_output.exists() {
-        fs::remove_file(shader_output)?;
-        ui.log("Cleaned shader output.");
+    for shader in core_shaders() {
+        let shader_output = shader.with_extension("odin");
+        if shader_output.exists() {
+            fs::remove_file(&shader_output)?;
+            ui.log(&t!("Cleaned shader output {}.", shader_output.display()));
+        }
     }
 
     let _ = fs::remove_dir_all("utils/__pycache__"); // python scripts
@@ -369,65 +1389,47 @@ pub fn clean_build(ui: &Ui) -> Result<(), CustomError> {
     Ok(())
 }
 
-fn copy_dir_recursive(src: &Path, dest: &Path) -> std::io::Result<()> {
-    if !dest.exists() {
-        fs::create_dir_all(dest)?;
-    }
-
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let path = entry.path();
-        let dest_path = dest.join(entry.file_name());
-
-        if path.is_dir() {
-            copy_dir_recursive(&path, &dest_path)?;
-        } else {
-            let should_copy = if dest_path.exists() {
-                let src_meta = fs::metadata(&path)?;
-                let dest_meta = fs::metadata(&dest_path)?;
-                src_meta.modified()? > dest_meta.modified()?
-            } else {
-                true
-            };
-
-            if should_copy {
-                fs::copy(&path, &dest_path)?;
-            }
+/// Removes compiled shaders and the shader cache, the next build compiles every shader.
+pub fn clean_shaders(ui: &Ui) -> Result<(), CustomError> {
+    for shader in core_shaders() {
+        let shader_output = shader.with_extension("odin");
+        if shader_output.exists() {
+            fs::remove_file(shader_output)?;
         }
     }
-    Ok(())
-}
-
-fn get_emsdk_path() -> Result<PathBuf, CustomError> {
-    if let Ok(path) = env::var("EMSDK") {
-        let path = PathBuf::from(path);
-        if path.exists() {
-            return Ok(path);
-        }
+    // without the stamp, game shaders are recompiled even when their outputs look fresh
+    if Path::new(SHADERS_SLANG_STAMP).exists() {
+        fs::remove_file(SHADERS_SLANG_STAMP)?;
+    }
+    let cache_dir = Path::new(SHADERS_CACHE_DIR);
+    if cache_dir.exists() {
+        fs::remove_dir_all(cache_dir)?;
     }
 
-    if let Ok(home) = env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
-        let home_path = PathBuf::from(home);
-
-        let common_paths = ["repos/emsdk", "emsdk", "tools/emsdk", ".emsdk"]; // a little bit hacky, but might help someone
+    ui.log(tr("Cleaned shader outputs."));
+    Ok(())
+}
 
-        for sub_path in common_paths {
-            let attempt = home_path.join(sub_path);
-            if attempt.join("emsdk_env.sh").exists() || attempt.join("emsdk_env.bat").exists() {
-                return Ok(attempt);
-            }
+/// Removes the files staged for web builds: preloaded assets, packages and the page shell.
+pub fn clean_web_staging(ui: &Ui) -> Result<(), CustomError> {
+    let shell_dir = Path::new(WEB_SHELL_STAGED).parent().unwrap_or(Path::new(WEB_SHELL_STAGED));
+    for dir in [
+        Path::new(WEB_PRELOAD_STAGE_DIR),
+        Path::new(WEB_EXTRA_PRELOAD_STAGE_DIR),
+        Path::new(WEB_EMBED_STAGE_DIR),
+        Path::new(WEB_PACKAGES_STAGE_DIR),
+        shell_dir,
+    ] {
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
         }
     }
 
-    Err(CustomError::BuildError(
-        "Could not find Emscripten SDK.\n\
-        Please install it (https://emscripten.org/docs/getting_started/downloads.html)\n\
-        and set the 'EMSDK' environment variable to its installation folder"
-            .to_string(),
-    ))
+    ui.log(tr("Cleaned staged web files."));
+    Ok(())
 }
 
-fn run_in_emsdk(cmd: &str, emsdk_path: &Path) -> Result<(), CustomError> {
+fn run_in_emsdk(cmd: &str, emsdk_path: &Path, ui: &Ui) -> Result<(), CustomError> {
     let clean_emsdk_path = to_emcc_path(emsdk_path);
 
     let (shell, flag, command_string) = if cfg!(target_os = "windows") {
@@ -444,19 +1446,41 @@ fn run_in_emsdk(cmd: &str, emsdk_path: &Path) -> Result<(), CustomError> {
         )
     };
 
-    let status = Command::new(shell)
+    // stderr carries the linker errors, route it through the ui so watch mode can show them
+    let mut command = Command::new(shell);
+    command
         .env("EMSDK_QUIET", "1")
         .arg(flag)
         .arg(command_string)
         .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .status()
-        .map_err(|e| {
-            CustomError::ProcessError(format!("Failed to run Emscripten command: {}", e))
-        })?;
+        .stderr(Stdio::piped());
+    let mut child = spawn_limited(&mut command, &ui.limits).map_err(|e| {
+        CustomError::ProcessError(t!("Failed to run Emscripten command: {}", e))
+    })?;
+
+    let stderr = child.stderr.take();
+    let ui_clone = ui.clone();
+    let stderr_thread = std::thread::spawn(move || {
+        let Some(stderr) = stderr else { return };
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            let text = format!("[EMCC] {}", line);
+            if line.contains("warning") {
+                ui_clone.message(&format!("{}", text.yellow()));
+            } else {
+                ui_clone.error(&format!("{}", text.red()));
+            }
+        }
+    });
+    let status = ui.cancel.wait(&mut child, timeout(&ui.limits)).map_err(|e| match e {
+        CustomError::IoError(e) => {
+            CustomError::ProcessError(t!("Failed to run Emscripten command: {}", e))
+        }
+        e => e,
+    })?;
+    let _ = stderr_thread.join();
 
     if !status.success() {
-        return Err(CustomError::BuildError(format!(
+        return Err(CustomError::BuildError(t!(
             "Emscripten command failed: {}",
             cmd
         )));
@@ -472,7 +1496,7 @@ fn run_utils(ui: &Ui) -> Result<(), CustomError> {
     }
 
     if ui.verbose {
-        ui.status("Scanning for utility scripts...");
+        ui.status(tr("Scanning for utility scripts..."));
     }
 
     visit_utility_dir(utils_path, ui)?;
@@ -493,7 +1517,7 @@ fn visit_utility_dir(dir: &Path, ui: &Ui) -> Result<(), CustomError> {
         if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
             let path_str = path
                 .to_str()
-                .ok_or(CustomError::ValidationError("Invalid UTF-8 path".into()))?;
+                .ok_or(CustomError::ValidationError(t!("Invalid UTF-8 path")))?;
             match ext {
                 "py" => {
                     run_with_prefix(
@@ -514,12 +1538,8 @@ fn visit_utility_dir(dir: &Path, ui: &Ui) -> Result<(), CustomError> {
                     })?;
                 }
                 "rs" => {
-                    ui.message(&format!(
-                        "{} Running Rust script: {:?}",
-                        "[RUST]".bright_red(),
-                        path_str
-                    ));
-                    run_rust_script(&path)?;
+                    ui.message(&t!("{} Running Rust script: {}", "[RUST]".bright_red(), format!("{:?}", path_str)));
+                    run_rust_script(&path, ui)?;
                 }
                 "odin" => {
                     run_with_prefix(
@@ -538,7 +1558,7 @@ fn visit_utility_dir(dir: &Path, ui: &Ui) -> Result<(), CustomError> {
     Ok(())
 }
 
-fn run_rust_script(path: &Path) -> Result<(), CustomError> {
+fn run_rust_script(path: &Path, ui: &Ui) -> Result<(), CustomError> {
     let file_stem = path.file_stem().unwrap().to_str().unwrap();
 
     let out_name = if cfg!(windows) {
@@ -548,24 +1568,19 @@ fn run_rust_script(path: &Path) -> Result<(), CustomError> {
     };
     let out_path = path.parent().unwrap().join(&out_name);
 
-    let status = Command::new("rustc")
-        .arg(path)
-        .arg("-o")
-        .arg(&out_path)
-        .status()
-        .map_err(|e| CustomError::ProcessError(format!("Failed to compile rust script: {}", e)))?;
+    let mut rustc = spawn_limited(Command::new("rustc").arg(path).arg("-o").arg(&out_path), &ui.limits)
+        .map_err(|e| CustomError::ProcessError(t!("Failed to compile rust script: {}", e)))?;
+    let status = ui.cancel.wait(&mut rustc, timeout(&ui.limits))?;
 
     if !status.success() {
-        return Err(CustomError::BuildError(format!(
-            "Failed to compile {:?}",
-            path
-        )));
+        return Err(CustomError::BuildError(t!("Failed to compile {}", format!("{:?}", path))));
     }
 
-    let status = Command::new(&out_path)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .status();
+    let mut script = Command::new(&out_path);
+    script.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+    let status = spawn_limited(&mut script, &ui.limits)
+        .map_err(CustomError::IoError)
+        .and_then(|mut child| ui.cancel.wait(&mut child, timeout(&ui.limits)));
 
     let _ = fs::remove_file(&out_path);
 
@@ -573,11 +1588,8 @@ fn run_rust_script(path: &Path) -> Result<(), CustomError> {
         let _ = fs::remove_file(out_path.with_extension("pdb"));
     }
 
-    if !status.map_err(CustomError::IoError)?.success() {
-        return Err(CustomError::BuildError(format!(
-            "Rust script {:?} failed",
-            path
-        )));
+    if !status?.success() {
+        return Err(CustomError::BuildError(t!("Rust script {} failed", format!("{:?}", path))));
     }
 
     Ok(())
@@ -586,14 +1598,14 @@ fn run_rust_script(path: &Path) -> Result<(), CustomError> {
 fn check_dependencies() -> Result<(), CustomError> {
     if Command::new("odin").arg("version").output().is_err() {
         return Err(CustomError::BuildError(
-            "Odin compiler not found in PATH. Please install it from https://odin-lang.org/docs/install".to_string()
+            t!("Odin compiler not found in PATH. Please install it from https://odin-lang.org/docs/install")
         ));
     }
 
     Ok(())
 }
 
-fn run_with_prefix(
+pub fn run_with_prefix(
     cmd: &str,
     args: &[&str],
     prefix: &str,
@@ -601,19 +1613,17 @@ fn run_with_prefix(
     ui: &Ui,
 ) -> Result<(), CustomError> {
     if ui.verbose {
-        ui.message(&format!(
+        ui.message(&t!(
             "{} Running script with arguments: {}...",
             prefix,
             args.join(" ")
         ));
     }
 
-    let mut child = Command::new(cmd)
-        .args(args)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| CustomError::ProcessError(format!("Failed to start {}: {}", cmd, e)))?;
+    let mut command = Command::new(cmd);
+    command.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = spawn_limited(&mut command, &ui.limits)
+        .map_err(|e| CustomError::ProcessError(t!("Failed to start {}: {}", cmd, e)))?;
 
     let stdout = child.stdout.take().unwrap();
     let stderr = child.stderr.take().unwrap();
@@ -642,30 +1652,22 @@ fn run_with_prefix(
         }
     });
 
-    let status = child
-        .wait()
-        .map_err(|e| CustomError::ProcessError(format!("Failed to wait for {}: {}", cmd, e)))?;
-
-    stdout_thread.join().unwrap();
-    stderr_thread.join().unwrap();
+    let status = ui.cancel.wait(&mut child, timeout(&ui.limits));
 
-    if !status.success() {
-        return Err(CustomError::BuildError(format!("{} failed", cmd)));
+    // a tool stopped early may have left processes behind that still hold the pipes
+    if status.is_ok() {
+        stdout_thread.join().unwrap();
+        stderr_thread.join().unwrap();
     }
 
-    Ok(())
-}
+    let status = status.map_err(|e| match e {
+        CustomError::IoError(e) => CustomError::ProcessError(t!("Failed to wait for {}: {}", cmd, e)),
+        e => e,
+    })?;
 
-fn should_skip(src: &Path, out: &Path) -> Result<bool, CustomError> {
-    if !out.exists() {
-        return Ok(false);
+    if !status.success() {
+        return Err(CustomError::BuildError(t!("{} failed", cmd)));
     }
 
-    let src_meta = fs::metadata(src)?;
-    let out_meta = fs::metadata(out)?;
-
-    let src_time = src_meta.modified()?;
-    let out_time = out_meta.modified()?;
-
-    Ok(src_time <= out_time)
+    Ok(())
 }
@@ -0,0 +1,250 @@
+use crate::error::CustomError;
+use crate::history::BuildEntry;
+use crate::i18n::{t, tr};
+use indicatif::HumanBytes;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+const BUILD_INFO_FILE: &str = "build.json";
+
+pub struct SizeChange {
+    pub name: String,
+    pub before: Option<u64>,
+    pub after: Option<u64>,
+}
+
+pub struct BuildDiff {
+    pub binaries: Vec<SizeChange>,
+    pub wasm_sections: Vec<SizeChange>,
+    pub files: Vec<SizeChange>,
+    pub shaders: Vec<(String, &'static str)>,
+    pub total_before: u64,
+    pub total_after: u64,
+}
+
+impl SizeChange {
+    fn delta(&self) -> i64 {
+        self.after.unwrap_or(0) as i64 - self.before.unwrap_or(0) as i64
+    }
+}
+
+fn is_binary(path: &str) -> bool {
+    path.ends_with(".wasm") || path.ends_with(".exe") || path.ends_with("game_desktop.bin")
+}
+
+fn file_sizes(dir: &Path) -> BTreeMap<String, u64> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(dir).ok()?;
+            let name = relative.to_string_lossy().replace('\\', "/");
+            if name == BUILD_INFO_FILE {
+                return None;
+            }
+            Some((name, e.metadata().ok()?.len()))
+        })
+        .collect()
+}
+
+fn read_leb128(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut result = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(result);
+        }
+        shift += 7;
+        if shift > 63 {
+            return None;
+        }
+    }
+}
+
+// section name -> total size, custom sections keyed by their own name
+fn wasm_sections(path: &Path) -> Result<BTreeMap<String, u64>, CustomError> {
+    let bytes = fs::read(path)?;
+    if bytes.len() < 8 || &bytes[0..4] != b"\0asm" {
+        return Err(CustomError::ValidationError(t!(
+            "Not a wasm module: {}",
+            path.display()
+        )));
+    }
+
+    let mut sections = BTreeMap::new();
+    let mut pos = 8;
+    while pos < bytes.len() {
+        let id = bytes[pos];
+        pos += 1;
+        let Some(size) = read_leb128(&bytes, &mut pos) else {
+            break;
+        };
+        let start = pos;
+
+        let name = match id {
+            0 => {
+                let mut name_pos = pos;
+                let len = read_leb128(&bytes, &mut name_pos).unwrap_or(0) as usize;
+                let raw = bytes.get(name_pos..name_pos + len).unwrap_or_default();
+                format!("custom:{}", String::from_utf8_lossy(raw))
+            }
+            1 => "type".to_string(),
+            2 => "import".to_string(),
+            3 => "function".to_string(),
+            4 => "table".to_string(),
+            5 => "memory".to_string(),
+            6 => "global".to_string(),
+            7 => "export".to_string(),
+            8 => "start".to_string(),
+            9 => "element".to_string(),
+            10 => "code".to_string(),
+            11 => "data".to_string(),
+            12 => "datacount".to_string(),
+            13 => "tag".to_string(),
+            other => format!("unknown({})", other),
+        };
+
+        *sections.entry(name).or_insert(0) += size;
+        pos = start + size as usize;
+    }
+
+    Ok(sections)
+}
+
+fn compare(before: &BTreeMap<String, u64>, after: &BTreeMap<String, u64>) -> Vec<SizeChange> {
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    let mut changes: Vec<SizeChange> = names
+        .into_iter()
+        .map(|name| SizeChange {
+            name: name.clone(),
+            before: before.get(name).copied(),
+            after: after.get(name).copied(),
+        })
+        .filter(|c| c.before != c.after)
+        .collect();
+    changes.sort_by_key(|c| std::cmp::Reverse(c.delta().abs()));
+    changes
+}
+
+pub fn diff_builds(a: &BuildEntry, b: &BuildEntry) -> Result<BuildDiff, CustomError> {
+    let before = file_sizes(&a.path);
+    let after = file_sizes(&b.path);
+
+    let (bin_before, files_before): (BTreeMap<_, _>, BTreeMap<_, _>) =
+        before.iter().map(|(k, v)| (k.clone(), *v)).partition(|(k, _)| is_binary(k));
+    let (bin_after, files_after): (BTreeMap<_, _>, BTreeMap<_, _>) =
+        after.iter().map(|(k, v)| (k.clone(), *v)).partition(|(k, _)| is_binary(k));
+
+    let mut wasm_sections_diff = Vec::new();
+    for name in bin_before.keys().filter(|n| n.ends_with(".wasm")) {
+        if bin_after.contains_key(name) {
+            let sections_before = wasm_sections(&a.path.join(name))?;
+            let sections_after = wasm_sections(&b.path.join(name))?;
+            wasm_sections_diff.extend(compare(&sections_before, &sections_after));
+        }
+    }
+
+    let shader_names: BTreeSet<&String> =
+        a.info.shaders.keys().chain(b.info.shaders.keys()).collect();
+    let shaders = shader_names
+        .into_iter()
+        .filter_map(|name| {
+            match (a.info.shaders.get(name), b.info.shaders.get(name)) {
+                (None, Some(_)) => Some((name.clone(), "added")),
+                (Some(_), None) => Some((name.clone(), "removed")),
+                (Some(x), Some(y)) if x != y => Some((name.clone(), "changed")),
+                _ => None,
+            }
+        })
+        .collect();
+
+    Ok(BuildDiff {
+        binaries: compare(&bin_before, &bin_after),
+        wasm_sections: wasm_sections_diff,
+        files: compare(&files_before, &files_after),
+        shaders,
+        total_before: before.values().sum(),
+        total_after: after.values().sum(),
+    })
+}
+
+fn format_size(size: Option<u64>) -> String {
+    size.map(|s| HumanBytes(s).to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn format_delta(delta: i64) -> String {
+    let sign = if delta >= 0 { "+" } else { "-" };
+    format!("{}{}", sign, HumanBytes(delta.unsigned_abs()))
+}
+
+fn print_table(title: &str, changes: &[SizeChange]) {
+    if changes.is_empty() {
+        return;
+    }
+
+    let width = changes
+        .iter()
+        .map(|c| c.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(title.chars().count());
+
+    println!();
+    println!(
+        "{:<width$}  {:>12}  {:>12}  {:>12}",
+        title,
+        tr("before"),
+        tr("after"),
+        tr("delta"),
+        width = width
+    );
+    for change in changes {
+        println!(
+            "{:<width$}  {:>12}  {:>12}  {:>12}",
+            change.name,
+            format_size(change.before),
+            format_size(change.after),
+            format_delta(change.delta()),
+            width = width
+        );
+    }
+}
+
+pub fn print_diff(a: &BuildEntry, b: &BuildEntry, diff: &BuildDiff) {
+    println!(
+        "{} ({}) -> {} ({})",
+        a.info.id,
+        a.info.commit.as_deref().unwrap_or("-"),
+        b.info.id,
+        b.info.commit.as_deref().unwrap_or("-")
+    );
+    println!(
+        "{}",
+        t!(
+            "Total: {} -> {} ({})",
+            HumanBytes(diff.total_before),
+            HumanBytes(diff.total_after),
+            format_delta(diff.total_after as i64 - diff.total_before as i64)
+        )
+    );
+
+    print_table(tr("binary"), &diff.binaries);
+    print_table(tr("wasm section"), &diff.wasm_sections);
+    print_table(tr("file"), &diff.files);
+
+    if !diff.shaders.is_empty() {
+        println!();
+        println!("{}", tr("shader outputs"));
+        for (name, status) in &diff.shaders {
+            println!("  {:<8} {}", tr(status), name);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+// inputs are never rebuilt, they only start edges
+const INPUTS: &[&str] = &["assets", "source", "manifest", "shader_sources"];
+
+// (from, to, targets); "compile" links the desktop binary itself, web builds link with emcc
+const EDGES: &[(&str, &str, &[&str])] = &[
+    ("assets", "atlas", &["desktop", "web"]),
+    ("manifest", "atlas", &["desktop", "web"]),
+    ("assets", "fonts", &["desktop", "web"]),
+    ("assets", "audio", &["desktop", "web"]),
+    ("source", "scenes", &["desktop", "web"]),
+    ("assets", "maps", &["desktop", "web"]),
+    ("atlas", "metadata", &["desktop", "web"]),
+    ("fonts", "metadata", &["desktop", "web"]),
+    ("audio", "metadata", &["desktop", "web"]),
+    ("scenes", "metadata", &["desktop", "web"]),
+    ("maps", "metadata", &["desktop", "web"]),
+    ("manifest", "metadata", &["desktop", "web"]),
+    ("shader_sources", "shaders", &["desktop", "web"]),
+    ("metadata", "compile", &["desktop", "web"]),
+    ("shaders", "compile", &["desktop", "web"]),
+    ("source", "compile", &["desktop", "web"]),
+    ("sokol", "compile", &["desktop"]),
+    ("manifest", "compile", &["desktop", "web"]),
+    ("assets", "copy", &["desktop"]),
+    ("compile", "link", &["web"]),
+    ("sokol", "link", &["web"]),
+    ("assets", "preload", &["web"]),
+    ("assets", "packages", &["web"]),
+    ("preload", "link", &["web"]),
+    ("packages", "link", &["web"]),
+    ("manifest", "link", &["web"]),
+];
+
+/// `.json` paths get JSON (true), `.dot` and `.gv` paths Graphviz (false).
+pub fn check_graph_path(path: &Path) -> Result<bool, CustomError> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => Ok(true),
+        Some("dot") | Some("gv") => Ok(false),
+        _ => Err(CustomError::ValidationError(t!(
+            "Unknown build graph format for '{}' (expected a .dot, .gv or .json path)",
+            path.display()
+        ))),
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct NodeRecord {
+    hits: u64,
+    misses: u64,
+}
+
+/// Which build graph nodes ran during this invocation, and whether they reused their
+/// previous output. Stages call [`BuildGraph::record`] next to their cache checks.
+#[derive(Default)]
+pub struct BuildGraph {
+    nodes: Mutex<BTreeMap<&'static str, NodeRecord>>,
+}
+
+#[derive(Serialize)]
+struct GraphNode {
+    id: &'static str,
+    // "input", "cached", "partial", "rebuilt" or "skipped"
+    status: &'static str,
+    cache_hits: u64,
+    cache_misses: u64,
+}
+
+#[derive(Serialize)]
+struct GraphEdge {
+    from: &'static str,
+    to: &'static str,
+    cached: bool,
+}
+
+#[derive(Serialize)]
+struct GraphReport {
+    target: String,
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+}
+
+impl BuildGraph {
+    pub fn record(&self, node: &'static str, cached: bool) {
+        if let Ok(mut nodes) = self.nodes.lock() {
+            let record = nodes.entry(node).or_default();
+            if cached {
+                record.hits += 1;
+            } else {
+                record.misses += 1;
+            }
+        }
+    }
+
+    fn report(&self, target: &str) -> GraphReport {
+        let recorded = self.nodes.lock().map(|n| n.clone()).unwrap_or_default();
+        let edges: Vec<&(&str, &str, &[&str])> =
+            EDGES.iter().filter(|(_, _, targets)| targets.contains(&target)).collect();
+
+        let mut ids: Vec<&'static str> = Vec::new();
+        for (from, to, _) in &edges {
+            for id in [*from, *to] {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+
+        let nodes: Vec<GraphNode> = ids
+            .into_iter()
+            .map(|id| {
+                let record = recorded.get(id).copied().unwrap_or_default();
+                let status = match (INPUTS.contains(&id), record.hits, record.misses) {
+                    (true, _, _) => "input",
+                    (false, 0, 0) => "skipped",
+                    (false, _, 0) => "cached",
+                    (false, 0, _) => "rebuilt",
+                    (false, _, _) => "partial",
+                };
+                GraphNode {
+                    id,
+                    status,
+                    cache_hits: record.hits,
+                    cache_misses: record.misses,
+                }
+            })
+            .collect();
+
+        // an edge is cached when its target reused everything it had built from it
+        let edges = edges
+            .into_iter()
+            .map(|(from, to, _)| GraphEdge {
+                from,
+                to,
+                cached: nodes.iter().any(|n| n.id == *to && n.status == "cached"),
+            })
+            .collect();
+
+        GraphReport {
+            target: target.to_string(),
+            nodes,
+            edges,
+        }
+    }
+
+    fn render_dot(report: &GraphReport) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph bonsai_{} {{", report.target);
+        let _ = writeln!(out, "  rankdir=LR;");
+        let _ = writeln!(out, "  node [shape=box, style=filled, fontname=\"Helvetica\"];");
+        for node in &report.nodes {
+            let (shape, color) = match node.status {
+                "input" => ("ellipse", "white"),
+                "cached" => ("box", "palegreen"),
+                "partial" => ("box", "khaki"),
+                "rebuilt" => ("box", "lightsalmon"),
+                _ => ("box", "lightgray"),
+            };
+            let label = if node.status == "input" {
+                node.id.to_string()
+            } else {
+                format!("{}\\n{}", node.id, node.status)
+            };
+            let _ = writeln!(
+                out,
+                "  {} [label=\"{}\", shape={}, fillcolor={}];",
+                node.id, label, shape, color
+            );
+        }
+        for edge in &report.edges {
+            let style = if edge.cached { " [style=dashed, label=\"cached\"]" } else { "" };
+            let _ = writeln!(out, "  {} -> {}{};", edge.from, edge.to, style);
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Writes the graph of the last build for `target` ("desktop" or "web"), in the format
+    /// [`check_graph_path`] picks.
+    pub fn write(&self, path: &Path, target: &str) -> Result<(), CustomError> {
+        let report = self.report(target);
+        let contents = if check_graph_path(path)? {
+            serde_json::to_string_pretty(&report).map_err(|e| {
+                CustomError::ValidationError(t!("Failed to serialize build graph: {}", e))
+            })?
+        } else {
+            Self::render_dot(&report)
+        };
+
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
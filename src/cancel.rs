@@ -0,0 +1,162 @@
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::limits::{ProcessTree, kill_running, spawn_limited, timeout};
+use crate::manifest::LimitsOptions;
+use crate::ui::Ui;
+use std::io::Read;
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+// what a shell reports for a process ended by SIGINT
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+type InterruptHandler = Box<dyn Fn() + Send>;
+static ON_INTERRUPT: Mutex<Option<InterruptHandler>> = Mutex::new(None);
+static HANDLER_INSTALLED: OnceLock<Result<(), String>> = OnceLock::new();
+
+/// Installs the Ctrl+C handler. Tools run in their own process groups and don't see the
+/// terminal's Ctrl+C, so it kills them first, then exits unless [`on_interrupt`] took over.
+pub fn install_interrupt_handler() -> Result<(), CustomError> {
+    HANDLER_INSTALLED
+        .get_or_init(|| {
+            ctrlc::set_handler(|| {
+                kill_running();
+                match ON_INTERRUPT.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+                    Some(handler) => handler(),
+                    None => std::process::exit(INTERRUPTED_EXIT_CODE),
+                }
+            })
+            .map_err(|e| e.to_string())
+        })
+        .clone()
+        .map_err(|e| CustomError::ProcessError(t!("Failed to set Ctrl+C handler: {}", e)))
+}
+
+/// Runs `handler` on Ctrl+C instead of exiting, e.g. to shut a server down cleanly.
+pub fn on_interrupt(handler: impl Fn() + Send + 'static) -> Result<(), CustomError> {
+    *ON_INTERRUPT.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(handler));
+    install_interrupt_handler()
+}
+
+/// Shared flag that tells a running build to stop at the next checkpoint
+/// and kill the external process it is waiting on.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub fn check(&self) -> Result<(), CustomError> {
+        if self.is_cancelled() {
+            Err(CustomError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Waits for `child`, killing it and everything it started if the token is cancelled in the
+    /// meantime or it runs longer than `timeout`.
+    pub fn wait(&self, child: &mut ProcessTree, timeout: Option<Duration>) -> Result<ExitStatus, CustomError> {
+        let started = Instant::now();
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok(status);
+            }
+            if self.is_cancelled() {
+                child.kill();
+                return Err(CustomError::Cancelled);
+            }
+            if let Some(timeout) = timeout
+                && started.elapsed() >= timeout
+            {
+                child.kill();
+                return Err(CustomError::ProcessError(t!(
+                    "Stopped after running longer than [limits] timeout_seconds ({}s)",
+                    timeout.as_secs()
+                )));
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Cancellable replacement for `Command::output`, running `command` under `limits`.
+    pub fn output(&self, command: &mut Command, limits: &LimitsOptions) -> Result<Output, CustomError> {
+        let mut child = spawn_limited(command.stdout(Stdio::piped()).stderr(Stdio::piped()), limits)?;
+
+        // drain the pipes on their own threads so a chatty process can't block on a full pipe
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+
+        let status = self.wait(&mut child, timeout(limits))?;
+
+        Ok(Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        })
+    }
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+/// Runs watch-mode rebuilds on a background thread. Starting a new rebuild
+/// cancels the one in flight, so only the latest state gets built.
+#[derive(Default)]
+pub struct RebuildRunner {
+    current: Option<(CancelToken, JoinHandle<()>)>,
+}
+
+impl RebuildRunner {
+    pub fn is_running(&self) -> bool {
+        self.current
+            .as_ref()
+            .is_some_and(|(_, handle)| !handle.is_finished())
+    }
+
+    pub fn restart<F>(&mut self, ui: &Ui, rebuild: F)
+    where
+        F: FnOnce(&Ui) -> Result<(), CustomError> + Send + 'static,
+    {
+        if let Some((token, handle)) = self.current.take() {
+            if !handle.is_finished() {
+                ui.log(tr("Change detected, cancelling the running rebuild..."));
+                token.cancel();
+            }
+            let _ = handle.join();
+        }
+
+        let token = CancelToken::default();
+        let ui = ui.with_cancel(token.clone());
+        let handle = thread::spawn(move || match rebuild(&ui) {
+            Ok(()) | Err(CustomError::Cancelled) => {}
+            Err(e) => ui.error(&e.to_string()),
+        });
+        self.current = Some((token, handle));
+    }
+}
+
+impl Drop for RebuildRunner {
+    fn drop(&mut self) {
+        if let Some((_, handle)) = self.current.take() {
+            let _ = handle.join();
+        }
+    }
+}
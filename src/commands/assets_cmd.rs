@@ -0,0 +1,248 @@
+use crate::Ui;
+use crate::assets::clean_assets;
+use crate::atlas_layout::Layout;
+use crate::build::build_asset_stages;
+use crate::cancel::RebuildRunner;
+use crate::error::CustomError;
+use crate::freshness;
+use crate::i18n::{t, tr};
+use crate::manifest::{load_manifest, update_manifest, warn_deprecated_sections};
+use crate::packer::compare_atlas_packers;
+use crate::paths::ProjectPaths;
+use crate::tools::require_tools;
+use crate::watch::{ASSET_STAGES, WatchRules};
+use crate::wsl::check_project_location;
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+
+#[derive(Args)]
+pub struct AssetsArgs {
+    #[command(subcommand)]
+    pub command: AssetsCommand,
+}
+
+#[derive(Subcommand)]
+pub enum AssetsCommand {
+    /// Pack the atlas and regenerate font, audio and scene data
+    Build {
+        #[arg(default_value = ".")]
+        dir: String,
+        #[arg(long)]
+        clean: bool,
+        /// Report the atlas size every packer and sort order reaches instead of building
+        #[arg(long, conflicts_with = "clean")]
+        compare: bool,
+    },
+    /// Remove the packed atlas, font caches and generated metadata
+    Clean {
+        #[arg(default_value = ".")]
+        dir: String,
+    },
+    /// Rebuild assets whenever the assets directory changes
+    Watch {
+        #[arg(default_value = ".")]
+        dir: String,
+    },
+}
+
+pub fn assets(args: &AssetsArgs, ui: Ui) -> Result<(), CustomError> {
+    let dir = match &args.command {
+        AssetsCommand::Build { dir, .. }
+        | AssetsCommand::Clean { dir }
+        | AssetsCommand::Watch { dir } => dir,
+    };
+
+    let project_dir = Path::new(dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            dir
+        )));
+    }
+
+    check_project_location(project_dir, &ui);
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    match &args.command {
+        AssetsCommand::Build { compare: true, .. } => compare(&ui)?,
+        AssetsCommand::Build { clean, .. } => {
+            if *clean {
+                clean_assets(&ui)?;
+            }
+            rebuild(&ui)?;
+            ui.success(tr("Assets built successfully."));
+        }
+        AssetsCommand::Clean { .. } => {
+            clean_assets(&ui)?;
+            ui.success(tr("Assets cleaned."));
+        }
+        AssetsCommand::Watch { .. } => watch(&ui)?,
+    }
+
+    Ok(())
+}
+
+fn compare(ui: &Ui) -> Result<(), CustomError> {
+    let manifest = load_manifest(Path::new("."))?;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let comparisons = compare_atlas_packers(&paths.assets, &manifest.assets.atlas, &manifest.tools, ui)?;
+
+    let configured = (
+        manifest.assets.atlas.packer.unwrap_or_default(),
+        manifest.assets.atlas.sort.unwrap_or_default(),
+    );
+    // fewest pages win, then the smallest atlas, then the squarer one on a tie
+    let max_side = |l: &Layout| l.pages.iter().map(|p| p.width.max(p.height)).max().unwrap_or(0);
+    let best = comparisons
+        .iter()
+        .filter_map(|c| c.layout.as_ref().map(|l| (c, l)))
+        .min_by(|(_, a), (_, b)| {
+            a.pages
+                .len()
+                .cmp(&b.pages.len())
+                .then(a.total_pixels().cmp(&b.total_pixels()))
+                .then(max_side(a).cmp(&max_side(b)))
+        })
+        .map(|(c, _)| (c.packer, c.sort));
+
+    println!("{:<12} {:<10} {:>11} {:>7}", "packer", "sort", tr("size"), tr("fill"));
+    for comparison in &comparisons {
+        let key = (comparison.packer, comparison.sort);
+        let (size, fill) = match &comparison.layout {
+            Some(layout) if layout.pages.len() > 1 => (
+                format!("{} pages", layout.pages.len()),
+                format!("{:.1}%", layout.fill_percent()),
+            ),
+            Some(layout) => (
+                format!("{}x{}", layout.pages[0].width, layout.pages[0].height),
+                format!("{:.1}%", layout.fill_percent()),
+            ),
+            None => ("too large".to_string(), "-".to_string()),
+        };
+        let mut marks = Vec::new();
+        if key == configured {
+            marks.push("current");
+        }
+        if Some(key) == best {
+            marks.push("smallest");
+        }
+        let line = format!(
+            "{:<12} {:<10} {:>11} {:>7}  {}",
+            comparison.packer.name(),
+            comparison.sort.name(),
+            size,
+            fill,
+            marks.join(", ")
+        );
+        if Some(key) == best {
+            println!("{}", line.green());
+        } else {
+            println!("{}", line.trim_end());
+        }
+    }
+
+    if let Some((packer, sort)) = best
+        && (packer, sort) != configured
+    {
+        println!(
+            "\n{}",
+            t!(
+                "Set [assets.atlas] packer = \"{}\" and sort = \"{}\" in bonsai.toml to use the smallest layout.",
+                packer.name(),
+                sort.name()
+            )
+        );
+    }
+    Ok(())
+}
+
+fn rebuild(ui: &Ui) -> Result<(), CustomError> {
+    let manifest = load_manifest(Path::new("."))?;
+    warn_deprecated_sections(&manifest, ui);
+    freshness::init(manifest.build.freshness, ui);
+    require_tools(&manifest.tools)?;
+    rebuild_stages(ASSET_STAGES, ui)
+}
+
+fn rebuild_stages(stages: &[&str], ui: &Ui) -> Result<(), CustomError> {
+    update_manifest(Path::new("."), ui)?;
+    let manifest = load_manifest(Path::new("."))?;
+    build_asset_stages(&manifest, stages, ui)
+}
+
+fn watch(ui: &Ui) -> Result<(), CustomError> {
+    let manifest = load_manifest(Path::new("."))?;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let rules = WatchRules::new(&manifest.watch, &paths)?;
+
+    let mut runner = RebuildRunner::default();
+    runner.restart(ui, rebuild);
+    let mut pending = BTreeSet::new();
+
+    let (tx, rx) = mpsc::channel();
+    let mut debouncer = new_debouncer(rules.debounce(), tx)
+        .map_err(|e| CustomError::ProcessError(t!("Failed to create file watcher: {}", e)))?;
+
+    // scene directories feed the generated scene registry
+    for watch_dir in [paths.assets.clone(), paths.scenes()] {
+        if watch_dir.exists() {
+            debouncer
+                .watcher()
+                .watch(&watch_dir, RecursiveMode::Recursive)
+                .map_err(|e| {
+                    CustomError::ProcessError(t!(
+                        "Failed to watch {}: {}",
+                        watch_dir.display(),
+                        e
+                    ))
+                })?;
+        }
+    }
+
+    ui.success(tr("Watching assets for changes (Ctrl+C to stop)..."));
+
+    while let Some(paths) = rules.next_batch(&rx) {
+        let stages: BTreeSet<String> = rules
+            .batch_stages(paths.iter().map(PathBuf::as_path))
+            .into_iter()
+            .filter(|stage| ASSET_STAGES.contains(&stage.as_str()))
+            .collect();
+        if stages.is_empty() {
+            continue;
+        }
+
+        // stages of a cancelled rebuild are carried over into the next one
+        if !runner.is_running() {
+            pending.clear();
+        }
+        pending.extend(stages);
+
+        let stages: Vec<&'static str> = ASSET_STAGES
+            .iter()
+            .copied()
+            .filter(|stage| pending.contains(*stage))
+            .collect();
+        if ui.verbose {
+            ui.log(&t!("Rebuilding asset stages: {}", stages.join(", ")));
+        }
+
+        runner.restart(ui, move |ui| {
+            rebuild_stages(&stages, ui)?;
+            ui.success(tr("Assets up to date."));
+            Ok(())
+        });
+    }
+
+    Ok(())
+}
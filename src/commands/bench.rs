@@ -0,0 +1,163 @@
+use crate::Ui;
+use crate::bench::{
+    BENCH_OUTPUT_DIR, DEFAULT_THRESHOLD_PERCENT, load_baselines, read_frame_stats, regressions,
+    save_baselines,
+};
+use crate::build::build_desktop;
+use crate::error::CustomError;
+use crate::harness::{HarnessOptions, RunOutcome, run_headless};
+use crate::i18n::{t, tr};
+use crate::lock::ProjectLock;
+use crate::manifest::load_manifest;
+use crate::wsl::check_project_location;
+use clap::Args;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+const BENCH_CONFIG: &str = "release";
+const DEFAULT_FRAMES: u32 = 600;
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+#[derive(Args)]
+pub struct BenchArgs {
+    #[arg(default_value = ".")]
+    pub dir: String,
+    /// Frames to run each scene (default: [bench] frames or 600)
+    #[arg(long)]
+    pub frames: Option<u32>,
+    /// Allowed slowdown in percent before a scene fails (default: [bench] threshold_percent or 10)
+    #[arg(long)]
+    pub threshold: Option<f64>,
+    /// Store the results as the new baseline
+    #[arg(long)]
+    pub save_baseline: bool,
+    /// Wait for another bonsai build in this project to finish instead of failing
+    #[arg(long)]
+    pub wait: bool,
+}
+
+pub fn bench(args: &BenchArgs, ui: Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(&args.dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    check_project_location(project_dir, &ui);
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    let manifest = load_manifest(Path::new("."))?;
+    let options = &manifest.bench;
+    if options.scenes.is_empty() {
+        return Err(CustomError::ValidationError(
+            t!("No benchmark scenes. Add them to [bench] scenes in bonsai.toml")
+        ));
+    }
+
+    let frames = args.frames.or(options.frames).unwrap_or(DEFAULT_FRAMES);
+    let threshold = args
+        .threshold
+        .or(options.threshold_percent)
+        .unwrap_or(DEFAULT_THRESHOLD_PERCENT);
+    let timeout = Duration::from_secs(DEFAULT_TIMEOUT_SECS);
+
+    let _lock = ProjectLock::acquire(args.wait, &ui)?;
+
+    ui.status(tr("Building for desktop (release)..."));
+    let build_result = build_desktop(BENCH_CONFIG, false, &ui)?;
+
+    let output_dir = std::path::absolute(BENCH_OUTPUT_DIR)?;
+    fs::create_dir_all(&output_dir)?;
+
+    let mut baselines = load_baselines()?;
+    let mut failures = 0;
+
+    for scene in &options.scenes {
+        let stats_path = output_dir.join(format!("{}.txt", scene));
+        let _ = fs::remove_file(&stats_path);
+
+        ui.status(&t!("Benchmarking {}...", scene));
+        let harness = HarnessOptions {
+            headless: true,
+            exit_after_frames: Some(frames),
+            scene: Some(scene.clone()),
+            frame_stats: Some(stats_path.clone()),
+            ..Default::default()
+        };
+
+        match run_headless(&build_result.executable_path, &harness, timeout)? {
+            RunOutcome::Passed => {}
+            RunOutcome::TimedOut => {
+                failures += 1;
+                ui.error(&t!("{}: did not exit within {}s", scene, timeout.as_secs()));
+                continue;
+            }
+            RunOutcome::Crashed(status) => {
+                failures += 1;
+                ui.error(&t!("{}: game exited with {}", scene, status));
+                continue;
+            }
+            RunOutcome::Desync => unreachable!("benchmarks run without a replay"),
+        }
+
+        let stats = read_frame_stats(&stats_path)?;
+        let ms = |value: f64| format!("{:>7.2}", value);
+        println!(
+            "{:<24} {}",
+            scene,
+            t!(
+                "mean {}ms  p50 {}ms  p95 {}ms  p99 {}ms  max {}ms  ({} frames)",
+                ms(stats.mean_ms),
+                ms(stats.p50_ms),
+                ms(stats.p95_ms),
+                ms(stats.p99_ms),
+                ms(stats.max_ms),
+                stats.frames
+            )
+        );
+
+        if args.save_baseline {
+            baselines.insert(scene.clone(), stats);
+            continue;
+        }
+
+        match baselines.get(scene) {
+            Some(baseline) => {
+                let slower = regressions(baseline, &stats, threshold);
+                if !slower.is_empty() {
+                    failures += 1;
+                    ui.error(&t!("{}: regressed: {}", scene, slower.join(", ")));
+                }
+            }
+            None => ui.log(&t!(
+                "{}: no baseline yet. Run 'bonsai bench --save-baseline' to store one.",
+                scene
+            )),
+        }
+    }
+
+    if args.save_baseline {
+        save_baselines(&baselines)?;
+        ui.success(tr("Benchmark baseline saved."));
+        return Ok(());
+    }
+
+    if failures > 0 {
+        return Err(CustomError::ProcessError(t!(
+            "{} benchmark scene(s) failed or regressed by more than {}%",
+            failures, threshold
+        )));
+    }
+
+    ui.success(tr("No benchmark regressions."));
+    Ok(())
+}
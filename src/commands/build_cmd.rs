@@ -1,8 +1,21 @@
 use crate::Ui;
-use crate::build::{build_desktop, build_web, clean_build};
+use crate::build::{build_desktop, build_web, clean_build, clean_shaders};
+use crate::build_graph::check_graph_path;
 use crate::error::CustomError;
+use crate::freshness::clear_cache;
+use crate::history::{DEFAULT_KEEP_BUILDS, new_build_id, snapshot_build};
+use crate::host_config::{HostProvider, write_host_config};
+use crate::i18n::{t, tr};
+use crate::lock::ProjectLock;
+use crate::manifest::load_manifest;
+use crate::paths::ProjectPaths;
+use crate::provenance::{BuildInvocation, configured_signing_key, write_provenance};
+use crate::update::write_update_manifest;
+use crate::wsl::check_project_location;
+use chrono::Local;
 use clap::Args;
-use std::path::Path;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
 
 #[derive(Args)]
 pub struct BuildArgs {
@@ -16,12 +29,30 @@ pub struct BuildArgs {
     pub config: String,
     #[arg(long)]
     pub clean: bool,
+    /// Forget the content hashes in .bonsai/cache, so only modification times count until outputs are rebuilt
+    #[arg(long)]
+    pub clean_cache: bool,
+    /// Recompile every shader even when its outputs are newer than its sources and includes
+    #[arg(long)]
+    pub force_shaders: bool,
+    /// Wait for another bonsai build in this project to finish instead of failing
+    #[arg(long)]
+    pub wait: bool,
+    /// Write the hosting provider's header config (COOP/COEP) next to the web build
+    #[arg(long, value_name = "PROVIDER", requires = "web")]
+    pub host_config: Option<HostProvider>,
+    /// Write the build stage graph, with cached edges, to a .dot/.gv (Graphviz) or .json file
+    #[arg(long, value_name = "PATH")]
+    pub emit_graph: Option<PathBuf>,
+    /// Write a provenance file (SLSA, signed when a key is configured) into the build output
+    #[arg(long)]
+    pub provenance: bool,
 }
 
 pub fn build(args: &BuildArgs, ui: Ui) -> Result<(), CustomError> {
     let project_dir = Path::new(&args.dir);
     if !project_dir.exists() {
-        return Err(CustomError::ValidationError(format!(
+        return Err(CustomError::ValidationError(t!(
             "Directory '{}' does not exist",
             args.dir
         )));
@@ -29,36 +60,100 @@ pub fn build(args: &BuildArgs, ui: Ui) -> Result<(), CustomError> {
 
     let manifest_path = project_dir.join("bonsai.toml");
     if !manifest_path.exists() {
-        return Err(CustomError::ValidationError(format!(
+        return Err(CustomError::ValidationError(t!(
             "Not a bonsai project: '{}'. (Missing bonsai.toml)",
             args.dir
         )));
     }
 
+    check_project_location(project_dir, &ui);
+
+    let started = Local::now();
+    // read before building, so a bad key doesn't waste a build
+    let signing_key = if args.provenance {
+        configured_signing_key()?
+    } else {
+        None
+    };
+
     let current_dir = std::env::current_dir()?;
+    // relative to where bonsai was started, not the project
+    let graph_path = match &args.emit_graph {
+        Some(path) => {
+            check_graph_path(path)?;
+            Some(current_dir.join(path))
+        }
+        None => None,
+    };
     std::env::set_current_dir(project_dir)?;
 
     let _cleanup_on_fail = scopeguard::guard((), |_| {
         let _ = std::env::set_current_dir(&current_dir);
     });
 
+    let _lock = ProjectLock::acquire(args.wait, &ui)?;
+
     if ui.verbose {
-        ui.log(&format!("Building project in: '{}'", project_dir.display()));
+        ui.log(&t!("Building project in: '{}'", project_dir.display()));
     }
 
+    if args.clean_cache {
+        clear_cache(&ui)?;
+    }
     if args.clean {
         clean_build(&ui)?;
+    } else if args.force_shaders {
+        clean_shaders(&ui)?;
     }
 
-    if args.web {
-        ui.log(&format!("Building for web ({}).", args.config));
+    let target = if args.web {
+        ui.log(&t!("Building for web ({}).", args.config));
         build_web(&args.config, args.clean, &ui)?;
+        if let Some(provider) = args.host_config {
+            let path = write_host_config(&ProjectPaths::load()?.build_target("web"), provider)?;
+            ui.log(&t!("Wrote host config {}.", path.display()));
+        }
+        "web"
     } else {
-        ui.log(&format!("Building for desktop ({}).", args.config));
+        ui.log(&t!("Building for desktop ({}).", args.config));
         build_desktop(&args.config, args.clean, &ui)?;
+        "desktop"
+    };
+
+    if let Some(path) = &graph_path {
+        ui.graph.write(path, target)?;
+        ui.log(&t!("Wrote build graph to {}.", path.display()));
+    }
+
+    let manifest = load_manifest(Path::new("."))?;
+    let keep = manifest.build.keep_builds.unwrap_or(DEFAULT_KEEP_BUILDS);
+    let output_dir = ProjectPaths::new(&manifest.paths)?.build_target(target);
+    let id = new_build_id(target);
+
+    if write_update_manifest(&output_dir, target, &id, &manifest)? {
+        ui.log(&t!("Wrote update manifest for build {}.", id));
+    }
+    if args.provenance {
+        let invocation = BuildInvocation {
+            id: &id,
+            target,
+            config: &args.config,
+            started,
+        };
+        write_provenance(&output_dir, &invocation, &manifest, signing_key.as_ref())?;
+        if signing_key.is_none() {
+            ui.message(&t!(
+                "{} No signing key configured, the provenance is unsigned.",
+                "[WARNING]".yellow()
+            ));
+        }
+        ui.log(&t!("Wrote provenance for build {}.", id));
+    }
+    if snapshot_build(&output_dir, &id, target, &args.config, keep, &ui)? {
+        ui.log(&t!("Recorded build {}.", id));
     }
 
-    ui.success("Build completed successfully.");
+    ui.success(tr("Build completed successfully."));
 
     Ok(())
 }
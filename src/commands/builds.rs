@@ -0,0 +1,100 @@
+use crate::Ui;
+use crate::browser::open_browser;
+use crate::build_diff::{diff_builds, print_diff};
+use crate::error::CustomError;
+use crate::history::{HISTORY_DIR, find_build, list_builds};
+use crate::i18n::{t, tr};
+use clap::{Args, Subcommand};
+use std::fs;
+use std::path::Path;
+
+#[derive(Args)]
+pub struct BuildsArgs {
+    #[command(subcommand)]
+    pub command: BuildsCommand,
+    #[arg(long, global = true, default_value = ".")]
+    pub dir: String,
+}
+
+#[derive(Subcommand)]
+pub enum BuildsCommand {
+    /// List recorded builds
+    List {
+        #[arg(long, value_parser = ["desktop", "web"])]
+        target: Option<String>,
+    },
+    /// Remove recorded builds, optionally keeping the newest ones
+    Clean {
+        #[arg(long, default_value_t = 0)]
+        keep: usize,
+    },
+    /// Open a recorded build directory
+    Open { id: String },
+    /// Compare the outputs of two recorded builds
+    Diff { from: String, to: String },
+}
+
+pub fn builds(args: &BuildsArgs, ui: Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(&args.dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    match &args.command {
+        BuildsCommand::List { target } => {
+            let builds = list_builds(target.as_deref());
+            if builds.is_empty() {
+                ui.message(tr("No recorded builds. Run 'bonsai build' to create one."));
+            }
+            for build in builds.iter().rev() {
+                ui.message(&format!(
+                    "{}  {:<7}  {:<7}  {}",
+                    build.info.id,
+                    build.info.target,
+                    build.info.config,
+                    build.info.commit.as_deref().unwrap_or("-")
+                ));
+            }
+        }
+        BuildsCommand::Clean { keep } => {
+            let mut removed = 0;
+            for target in ["desktop", "web"] {
+                let mut builds = list_builds(Some(target));
+                let remove_count = builds.len().saturating_sub(*keep);
+                builds.truncate(remove_count);
+                for build in builds {
+                    fs::remove_dir_all(&build.path)?;
+                    removed += 1;
+                }
+                if *keep == 0 {
+                    let _ = fs::remove_dir_all(Path::new(HISTORY_DIR).join(target));
+                }
+            }
+            ui.success(&t!("Removed {} recorded builds.", removed));
+        }
+        BuildsCommand::Open { id } => {
+            let build = find_build(id)?;
+            let path = build.path.canonicalize()?;
+            ui.log(&t!("Opening {}", path.display()));
+            open_browser(&path.to_string_lossy(), None, &ui);
+        }
+        BuildsCommand::Diff { from, to } => {
+            let from = find_build(from)?;
+            let to = find_build(to)?;
+            let diff = diff_builds(&from, &to)?;
+            print_diff(&from, &to, &diff);
+        }
+    }
+
+    Ok(())
+}
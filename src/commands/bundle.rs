@@ -0,0 +1,370 @@
+use crate::Ui;
+use crate::commands::init::REPO_URL;
+use crate::commands::install::{declared_dependencies, extract_name_from_url, resolve_url};
+use crate::config::bundle_cache_dir;
+use crate::error::CustomError;
+use crate::git::{BUNDLE_REPOS_DIR, describe_tag, mirror_name, mirror_repo, run_clone};
+use crate::hash::hash_file;
+use crate::i18n::{t, tr};
+use crate::lockfile::Lockfile;
+use crate::manifest::create_manifest;
+use crate::shdc::{get_or_install_shdc, install_bundled_shdc};
+use crate::sokol::{BUNDLE_SOKOL_DIR, compile_sokol, sources_fingerprint};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use indicatif::HumanBytes;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::env;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const BUNDLE_FORMAT: u32 = 1;
+const BUNDLE_INFO_FILE: &str = "bundle.json";
+const SHDC_DIR: &str = "shdc";
+const SOKOL_LIB_DIR: &str = "bonsai/libs/sokol";
+const SYSTEMS_DIR: &str = "bonsai/systems";
+const LIBRARY_EXTENSIONS: &[&str] = &["a", "lib", "dll"];
+
+#[derive(Args)]
+pub struct BundleArgs {
+    #[command(subcommand)]
+    pub command: BundleCommand,
+}
+
+#[derive(Subcommand)]
+pub enum BundleCommand {
+    /// Write an archive with everything a new project needs to build without internet access
+    Create {
+        /// Archive to write (default: bonsai-bundle-<version>.zip)
+        #[arg(long, short)]
+        output: Option<String>,
+        /// Engine version (tag/branch) the sokol libraries are compiled for
+        #[arg(long, short, default_value = "latest")]
+        version: String,
+        /// System repository to include, in the same form as for `bonsai install`
+        #[arg(long = "system", value_name = "URL")]
+        systems: Vec<String>,
+        /// Also include every system in this project's bonsai.lock
+        #[arg(long)]
+        locked: bool,
+        /// Also include the web (WASM) sokol libraries
+        #[arg(long)]
+        web: bool,
+        /// Leave out the prebuilt sokol libraries
+        #[arg(long)]
+        no_sokol: bool,
+    },
+    /// Prime the local caches from a bundle
+    Use { file: String },
+}
+
+// what a bundle holds, stored as bundle.json at its root
+#[derive(Serialize, Deserialize)]
+struct BundleInfo {
+    format: u32,
+    bonsai_version: String,
+    engine_version: String,
+    // os-arch the sokol-shdc binary and desktop libraries were made for
+    platform: String,
+    repositories: Vec<String>,
+    // sha256 of the bundled sokol-shdc
+    shdc: Option<String>,
+    // sources fingerprint the sokol libraries were compiled from
+    sokol: Option<String>,
+}
+
+pub fn bundle(args: &BundleArgs, ui: Ui) -> Result<(), CustomError> {
+    match &args.command {
+        BundleCommand::Create {
+            output,
+            version,
+            systems,
+            locked,
+            web,
+            no_sokol,
+        } => create(output.as_deref(), version, systems, *locked, *web, *no_sokol, &ui),
+        BundleCommand::Use { file } => use_bundle(Path::new(file), &ui),
+    }
+}
+
+fn platform() -> String {
+    format!("{}-{}", env::consts::OS, env::consts::ARCH)
+}
+
+fn create(
+    output: Option<&str>,
+    version: &str,
+    systems: &[String],
+    locked: bool,
+    web: bool,
+    no_sokol: bool,
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    let staging = TempDir::new()?;
+    let repos_dir = staging.path().join(BUNDLE_REPOS_DIR);
+
+    let engine_mirror = repos_dir.join(mirror_name(REPO_URL));
+    mirror_repo(REPO_URL, &engine_mirror, ui)?;
+    let engine = TempDir::new()?;
+    run_clone(&["clone"], &engine_mirror.to_string_lossy(), engine.path(), version)?;
+    // pin 'latest' to the tag that was actually mirrored
+    let engine_version = if version == "latest" {
+        describe_tag(engine.path()).unwrap_or_else(|| version.to_string())
+    } else {
+        version.to_string()
+    };
+
+    let repositories = mirror_systems(systems, locked, &repos_dir, ui)?;
+
+    ui.status(tr("Adding sokol-shdc..."));
+    let shdc = get_or_install_shdc(ui);
+    let shdc_dir = staging.path().join(SHDC_DIR);
+    fs::create_dir_all(&shdc_dir)?;
+    fs::copy(&shdc, shdc_dir.join(shdc.file_name().unwrap_or_default()))?;
+    let shdc_checksum = hash_file(&shdc)?;
+
+    let sokol = if no_sokol {
+        None
+    } else {
+        Some(prebuild_sokol(engine.path(), staging.path(), web, ui)?)
+    };
+
+    let info = BundleInfo {
+        format: BUNDLE_FORMAT,
+        bonsai_version: env!("CARGO_PKG_VERSION").to_string(),
+        engine_version: engine_version.clone(),
+        platform: platform(),
+        repositories: std::iter::once(REPO_URL.to_string()).chain(repositories).collect(),
+        shdc: Some(shdc_checksum),
+        sokol,
+    };
+    let json = serde_json::to_string_pretty(&info)
+        .map_err(|e| CustomError::BuildError(t!("Failed to write the bundle: {}", e)))?;
+    fs::write(staging.path().join(BUNDLE_INFO_FILE), json)?;
+
+    let output = output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(format!("bonsai-bundle-{}.zip", engine_version)));
+    ui.status(&t!("Writing {}...", output.display()));
+    write_archive(staging.path(), &output)?;
+
+    let size = fs::metadata(&output)?.len();
+    ui.success(&t!(
+        "Bundle written to {} ({} repositories, {}).",
+        output.display(),
+        info.repositories.len(),
+        HumanBytes(size)
+    ));
+    ui.message(&t!(
+        "Prime a machine with 'bonsai bundle use {}', then create projects with 'bonsai init <name> --version {}'.",
+        output.display(),
+        engine_version
+    ));
+    Ok(())
+}
+
+// mirrors the requested systems and every system they depend on, returns their urls
+fn mirror_systems(
+    systems: &[String],
+    locked: bool,
+    repos_dir: &Path,
+    ui: &Ui,
+) -> Result<Vec<String>, CustomError> {
+    let mut queue: Vec<(String, String)> = Vec::new();
+    for system in systems {
+        let url = resolve_url(system);
+        queue.push((extract_name_from_url(&url)?, url));
+    }
+    if locked {
+        for (name, system) in Lockfile::load()?.systems {
+            queue.push((name, system.url));
+        }
+    }
+
+    let mut mirrored = BTreeSet::new();
+    while let Some((name, url)) = queue.pop() {
+        if !mirrored.insert(url.clone()) {
+            continue;
+        }
+        let mirror = repos_dir.join(mirror_name(&url));
+        mirror_repo(&url, &mirror, ui)?;
+
+        // dependencies declared on the default branch are bundled too
+        let checkout = TempDir::new()?;
+        run_clone(&["clone"], &mirror.to_string_lossy(), checkout.path(), "latest")?;
+        let system_dir = checkout.path().join(SYSTEMS_DIR).join(&name);
+        for dependency in declared_dependencies(&system_dir, Some(checkout.path()))? {
+            queue.push((dependency.name, dependency.url));
+        }
+    }
+    Ok(mirrored.into_iter().collect())
+}
+
+// compiles the engine's sokol libraries and copies them to sokol/<fingerprint> in the bundle
+fn prebuild_sokol(engine: &Path, staging: &Path, web: bool, ui: &Ui) -> Result<String, CustomError> {
+    create_manifest(engine, "bundle")?;
+
+    let current_dir = env::current_dir()?;
+    env::set_current_dir(engine)?;
+    let _restore_dir = scopeguard::guard(current_dir, |dir| {
+        let _ = env::set_current_dir(&dir);
+    });
+
+    let targets: &[bool] = if web { &[false, true] } else { &[false] };
+    for &is_web in targets {
+        for is_debug in [true, false] {
+            compile_sokol(is_web, is_debug, false, ui)?;
+        }
+    }
+    let fingerprint = sources_fingerprint()?;
+
+    let destination = staging.join(BUNDLE_SOKOL_DIR).join(&fingerprint);
+    let sokol_dir = Path::new(SOKOL_LIB_DIR);
+    let mut copied = 0;
+    for entry in WalkDir::new(sokol_dir).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let is_library = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| LIBRARY_EXTENSIONS.contains(&ext));
+        if !entry.file_type().is_file() || !is_library {
+            continue;
+        }
+        let target = destination.join(path.strip_prefix(sokol_dir).unwrap_or(path));
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(path, &target)?;
+        copied += 1;
+    }
+    ui.log(&t!("Bundled {} sokol libraries.", copied));
+
+    Ok(fingerprint)
+}
+
+fn archive_error(e: zip::result::ZipError) -> CustomError {
+    CustomError::BuildError(t!("Failed to write the bundle: {}", e))
+}
+
+// written next to `output` and moved into place once complete
+fn write_archive(staging: &Path, output: &Path) -> Result<(), CustomError> {
+    let partial = output.with_extension("partial");
+    let _remove_partial = scopeguard::guard(partial.clone(), |partial| {
+        let _ = fs::remove_file(partial);
+    });
+    let mut zip = ZipWriter::new(File::create(&partial)?);
+
+    for entry in WalkDir::new(staging).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| CustomError::IoError(e.into()))?;
+        let name = entry
+            .path()
+            .strip_prefix(staging)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+        let metadata = entry.metadata().map_err(|e| CustomError::IoError(e.into()))?;
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(metadata.len() >= u32::MAX as u64);
+        #[cfg(unix)]
+        let options = {
+            use std::os::unix::fs::PermissionsExt;
+            options.unix_permissions(metadata.permissions().mode())
+        };
+
+        if entry.file_type().is_dir() {
+            // bare repositories need their empty directories, refs/ above all
+            zip.add_directory(name, options).map_err(archive_error)?;
+        } else if entry.file_type().is_file() {
+            zip.start_file(name, options).map_err(archive_error)?;
+            io::copy(&mut File::open(entry.path())?, &mut zip)?;
+        }
+    }
+    zip.finish().map_err(archive_error)?;
+
+    fs::rename(&partial, output)?;
+    Ok(())
+}
+
+fn use_bundle(file: &Path, ui: &Ui) -> Result<(), CustomError> {
+    let invalid = |e: zip::result::ZipError| {
+        CustomError::ValidationError(t!("Invalid bundle {}: {}", file.display(), e))
+    };
+    let mut archive = ZipArchive::new(File::open(file)?).map_err(invalid)?;
+    let info: BundleInfo = serde_json::from_reader(archive.by_name(BUNDLE_INFO_FILE).map_err(invalid)?)
+        .map_err(|e| {
+            CustomError::ValidationError(t!("Invalid bundle {}: {}", file.display(), e))
+        })?;
+    if info.format != BUNDLE_FORMAT {
+        return Err(CustomError::ValidationError(t!(
+            "{} was made by bonsai {}, which writes a bundle format this version can't read",
+            file.display(),
+            info.bonsai_version
+        )));
+    }
+
+    // unpacked inside the cache so every directory can be renamed into place
+    let cache = bundle_cache_dir()?;
+    fs::create_dir_all(&cache)?;
+    let staging = TempDir::new_in(&cache)?;
+    ui.status(&t!("Unpacking {}...", file.display()));
+    archive.extract(staging.path()).map_err(invalid)?;
+
+    for dir in [BUNDLE_REPOS_DIR, BUNDLE_SOKOL_DIR] {
+        replace_entries(&staging.path().join(dir), &cache.join(dir))?;
+    }
+
+    if let Some(checksum) = &info.shdc {
+        if info.platform == platform() {
+            let shdc = fs::read_dir(staging.path().join(SHDC_DIR))?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .find(|p| p.is_file())
+                .ok_or_else(|| {
+                    CustomError::ValidationError(t!("The bundle {} has no sokol-shdc", file.display()))
+                })?;
+            install_bundled_shdc(&shdc, checksum, ui)?;
+        } else {
+            ui.message(&t!(
+                "{} The bundle was made on {}, its sokol-shdc and desktop libraries don't run on {}.",
+                "[WARNING]".yellow(),
+                info.platform,
+                platform()
+            ));
+        }
+    }
+
+    ui.success(&t!(
+        "Primed the cache with engine {} and {} repositories.",
+        info.engine_version,
+        info.repositories.len()
+    ));
+    ui.message(&t!(
+        "Create projects with 'bonsai init <name> --version {}'.",
+        info.engine_version
+    ));
+    Ok(())
+}
+
+// moves every entry of `from` into `to`, replacing what an earlier bundle left there
+fn replace_entries(from: &Path, to: &Path) -> Result<(), CustomError> {
+    if !from.is_dir() {
+        return Ok(());
+    }
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if target.exists() {
+            fs::remove_dir_all(&target)?;
+        }
+        fs::rename(entry.path(), &target)?;
+    }
+    Ok(())
+}
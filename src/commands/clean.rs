@@ -0,0 +1,72 @@
+use crate::Ui;
+use crate::assets::clean_assets;
+use crate::build::{clean_build, clean_shaders, clean_web_staging};
+use crate::error::CustomError;
+use crate::freshness::clear_cache;
+use crate::i18n::{t, tr};
+use crate::lock::ProjectLock;
+use crate::sokol::clean_sokol;
+use clap::Args;
+use std::path::Path;
+
+#[derive(Args)]
+pub struct CleanArgs {
+    #[arg(default_value = ".")]
+    pub dir: String,
+    /// Remove the compiled sokol libraries
+    #[arg(long)]
+    pub sokol: bool,
+    /// Remove compiled shaders and the shader cache
+    #[arg(long)]
+    pub shaders: bool,
+    /// Remove the packed atlas, font caches, generated asset metadata and staged web files
+    #[arg(long)]
+    pub assets_cache: bool,
+    /// Remove the build directory, every intermediate artifact and the content hash cache
+    #[arg(long, conflicts_with_all = ["sokol", "shaders", "assets_cache"])]
+    pub all: bool,
+    /// Wait for another bonsai build in this project to finish instead of failing
+    #[arg(long)]
+    pub wait: bool,
+}
+
+pub fn clean(args: &CleanArgs, ui: Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(&args.dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    let _lock = ProjectLock::acquire(args.wait, &ui)?;
+
+    // without a selection, clean what `build --clean` does before building
+    let selected = args.sokol || args.shaders || args.assets_cache;
+    if args.all || !selected {
+        clean_build(&ui)?;
+    }
+    if args.all || args.sokol {
+        clean_sokol(&ui)?;
+    }
+    if args.all || args.shaders {
+        clean_shaders(&ui)?;
+    }
+    if args.all || args.assets_cache {
+        clean_assets(&ui)?;
+        clean_web_staging(&ui)?;
+    }
+    if args.all {
+        clear_cache(&ui)?;
+    }
+
+    ui.success(tr("Project cleaned."));
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::manifest::write_manifest;
+use crate::preset::{apply_preset, export_preset, read_preset};
+use clap::{Args, Subcommand};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "bonsai.toml";
+
+#[derive(Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+    #[arg(long, global = true, default_value = ".")]
+    pub dir: String,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Write the [profile], [assets.atlas], [web] and [toolchain] settings to a preset file
+    ExportPreset {
+        path: PathBuf,
+        /// Name recorded in the preset (default: the file name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Overwrite an existing file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Merge the settings of a preset file into bonsai.toml
+    #[command(alias = "import")]
+    ImportPreset {
+        path: PathBuf,
+        /// Only list the settings the preset would change
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub fn config(args: &ConfigArgs, ui: Ui) -> Result<(), CustomError> {
+    let project_root = Path::new(&args.dir);
+    if !project_root.join(MANIFEST_FILE).exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    match &args.command {
+        ConfigCommand::ExportPreset { path, name, force } => {
+            export(project_root, path, name.as_deref(), *force, &ui)
+        }
+        ConfigCommand::ImportPreset { path, dry_run } => import(project_root, path, *dry_run, &ui),
+    }
+}
+
+fn export(project_root: &Path, path: &Path, name: Option<&str>, force: bool, ui: &Ui) -> Result<(), CustomError> {
+    if path.exists() && !force {
+        return Err(CustomError::ValidationError(t!(
+            "{} already exists, pass --force to replace it",
+            path.display()
+        )));
+    }
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "preset".to_string()),
+    };
+
+    let manifest = fs::read_to_string(project_root.join(MANIFEST_FILE))?;
+    let preset = export_preset(&manifest, &name)?;
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, preset)?;
+
+    ui.success(&t!("Exported preset '{}' to {}.", name, path.display()));
+    Ok(())
+}
+
+fn import(project_root: &Path, path: &Path, dry_run: bool, ui: &Ui) -> Result<(), CustomError> {
+    let preset = fs::read_to_string(path).map_err(|e| {
+        CustomError::ValidationError(t!("Could not read preset {}: {}", path.display(), e))
+    })?;
+    let info = read_preset(&preset)?;
+    let version = info.bonsai.as_deref().unwrap_or("?");
+    match &info.source {
+        Some(source) => ui.log(&t!("Preset '{}' from {} (bonsai {})", info.name, source, version)),
+        None => ui.log(&t!("Preset '{}' (bonsai {})", info.name, version)),
+    }
+
+    let manifest = fs::read_to_string(project_root.join(MANIFEST_FILE))?;
+    let (merged, changes) = apply_preset(&manifest, &preset)?;
+    if changes.is_empty() {
+        ui.success(&t!("bonsai.toml already matches preset '{}'.", info.name));
+        return Ok(());
+    }
+
+    for (key, new) in &changes {
+        ui.message(&format!("  ~ {} = {}", key, new));
+    }
+    if dry_run {
+        ui.success(&t!("Preset '{}' would change {} setting(s).", info.name, changes.len()));
+        return Ok(());
+    }
+
+    let journal = std::iter::once(format!("Imported preset '{}'", info.name))
+        .chain(changes.iter().map(|(key, new)| format!("Set {} = {}", key, new)))
+        .collect();
+    write_manifest(project_root, &manifest, &merged, journal)?;
+    ui.success(&t!("Imported preset '{}', {} setting(s) changed.", info.name, changes.len()));
+    ui.message(tr("Run 'bonsai manifest undo' to revert it."));
+    Ok(())
+}
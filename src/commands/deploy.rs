@@ -0,0 +1,127 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::itch::{DEPLOY_TARGETS, deploy_config, deploy_targets, page_url, push_build, validate_itch_options};
+use crate::lock::ProjectLock;
+use crate::manifest::load_manifest;
+use crate::paths::ProjectPaths;
+use crate::profile::resolve_profile;
+use crate::release::build_recorded;
+use crate::tools::require_tool;
+use crate::wsl::check_project_location;
+use clap::{Args, Subcommand};
+use std::path::Path;
+
+#[derive(Args)]
+pub struct DeployArgs {
+    #[command(subcommand)]
+    pub command: DeployCommand,
+}
+
+#[derive(Subcommand)]
+pub enum DeployCommand {
+    /// Build the [deploy.itch] targets and push them to itch.io with butler
+    Itch {
+        #[arg(default_value = ".")]
+        dir: String,
+        /// Target to push, repeatable (default: [deploy.itch] targets, else web and desktop)
+        #[arg(long, value_parser = DEPLOY_TARGETS.to_vec())]
+        target: Vec<String>,
+        /// Build config (default: [deploy.itch] config, else release)
+        #[arg(long, short = 'c')]
+        config: Option<String>,
+        /// Version shown on itch.io (default: the project version)
+        #[arg(long)]
+        user_version: Option<String>,
+        /// Build and print the butler commands without pushing
+        #[arg(long)]
+        dry_run: bool,
+        /// Wait for another bonsai build in this project to finish instead of failing
+        #[arg(long)]
+        wait: bool,
+    },
+}
+
+pub fn deploy(args: &DeployArgs, ui: Ui) -> Result<(), CustomError> {
+    match &args.command {
+        DeployCommand::Itch {
+            dir,
+            target,
+            config,
+            user_version,
+            dry_run,
+            wait,
+        } => deploy_itch(dir, target, config.as_deref(), user_version.as_deref(), *dry_run, *wait, ui),
+    }
+}
+
+fn deploy_itch(
+    dir: &str,
+    requested: &[String],
+    config: Option<&str>,
+    user_version: Option<&str>,
+    dry_run: bool,
+    wait: bool,
+    ui: Ui,
+) -> Result<(), CustomError> {
+    let project_dir = Path::new(dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            dir
+        )));
+    }
+
+    check_project_location(project_dir, &ui);
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    let manifest = load_manifest(Path::new("."))?;
+    let options = manifest.deploy.itch.clone().ok_or_else(|| {
+        CustomError::ValidationError(
+            t!("No [deploy.itch] section in bonsai.toml. Add one with project = \"user/game\"")
+        )
+    })?;
+    validate_itch_options(&options)?;
+
+    let config = config.unwrap_or(deploy_config(&options));
+    let version = user_version.unwrap_or(&manifest.project.version);
+    let targets = deploy_targets(requested, &options);
+
+    if targets.iter().any(|t| t == "desktop")
+        && resolve_profile(&manifest, config)?.link_assets
+    {
+        return Err(CustomError::ValidationError(t!(
+            "[profile.{}] link_assets would push a link instead of the assets, deploy another config",
+            config
+        )));
+    }
+
+    // checked before building, so a missing butler doesn't waste a build
+    let butler = if dry_run {
+        None
+    } else {
+        Some(require_tool(&manifest.tools, "butler")?)
+    };
+
+    let _lock = ProjectLock::acquire(wait, &ui)?;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+
+    for target in &targets {
+        let (output_dir, id) = build_recorded(target, config, &manifest, &paths, &ui)?;
+        push_build(butler.as_deref(), &options, target, &output_dir, &id, version, &ui)?;
+    }
+
+    if dry_run {
+        ui.success(tr("Dry run finished, nothing was pushed."));
+    } else {
+        ui.success(&t!("Deployed to {}.", page_url(&options.project)));
+    }
+
+    Ok(())
+}
@@ -1,19 +1,37 @@
 use crate::Ui;
 use crate::error::CustomError;
-use clap::Args;
+use crate::i18n::{t, tr};
+use crate::manifest::{DocsCategory, DocsOptions, load_manifest};
+use clap::{Args, ValueEnum};
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+// scanned and written without a --dir, --target or [docs] entry
+pub const DEFAULT_DOCS_DIR: &str = "./bonsai";
+pub const DEFAULT_DOCS_TARGET: &str = "./docs";
+
 #[derive(Args)]
 pub struct DocsArgs {
     #[arg(long, short)]
     pub dir: Option<String>,
     #[arg(long, short)]
     pub target: Option<String>,
-    pub trigger: String,
+    /// Also write the navigation files of a site generator
+    #[arg(long, value_enum, value_name = "FLAVOR")]
+    pub site: Option<DocsSite>,
+    /// Comment tag marking documented declarations (default: the [docs.triggers] of bonsai.toml)
+    pub trigger: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum DocsSite {
+    /// SUMMARY.md, with the target directory as the book's src
+    Mdbook,
+    /// sidebars.js, with doc ids relative to the target directory
+    Docusaurus,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -40,11 +58,20 @@ enum ItemKind {
 struct DocItem {
     name: String,
     doc: String,
-    #[allow(dead_code)]
     kind: ItemKind,
     signature: String,
     operator: String,
     language: Language,
+    // `@(require_results)` style attributes above or before the declaration
+    attributes: Vec<String>,
+    // `"c"`, `"contextless"`, ... of a proc
+    calling_convention: Option<String>,
+    where_clause: Option<String>,
+    // procs of an overload set, as written inside `proc { ... }`
+    members: Vec<String>,
+    // body of a proc, shown on the examples page
+    body: Option<String>,
+    category: DocsCategory,
 }
 
 struct PackageData {
@@ -52,29 +79,88 @@ struct PackageData {
     items: Vec<DocItem>,
 }
 
+// generated pages nested by directory; a package page and its subpackages share a node
+#[derive(Default)]
+struct SiteNode {
+    // (path without extension, '/' separated, title)
+    page: Option<(String, String)>,
+    children: BTreeMap<String, SiteNode>,
+}
+
+impl SiteNode {
+    fn insert(&mut self, rel_path: &Path, title: String) {
+        let parts: Vec<String> = rel_path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let mut node = self;
+        for part in &parts {
+            node = node.children.entry(part.clone()).or_default();
+        }
+        node.page = Some((parts.join("/"), title));
+    }
+
+    // the root package page first, the rest alphabetically
+    fn ordered_children(&self) -> impl Iterator<Item = (&String, &SiteNode)> {
+        let index = self.children.iter().filter(|(name, _)| *name == "index");
+        index.chain(self.children.iter().filter(|(name, _)| *name != "index"))
+    }
+}
+
 pub fn docs(args: &DocsArgs, ui: Ui) -> Result<(), CustomError> {
-    let src_dir = args.dir.as_deref().unwrap_or("./bonsai");
+    // a trigger argument documents everything it marks as one set, otherwise [docs] decides
+    let (triggers, options) = match &args.trigger {
+        Some(trigger) => (
+            vec![(trigger.clone(), DocsCategory::Public)],
+            DocsOptions::default(),
+        ),
+        None => {
+            let options = if Path::new("bonsai.toml").exists() {
+                load_manifest(Path::new("."))?.docs
+            } else {
+                DocsOptions::default()
+            };
+            if options.triggers.is_empty() {
+                return Err(CustomError::ValidationError(
+                    t!("No trigger given and no [docs.triggers] in bonsai.toml")
+                ));
+            }
+            let triggers = options
+                .triggers
+                .iter()
+                .map(|(trigger, category)| (trigger.clone(), *category))
+                .collect();
+            (triggers, options)
+        }
+    };
+
+    let src_dir = args.dir.as_deref().or(options.dir.as_deref()).unwrap_or(DEFAULT_DOCS_DIR);
     let src_path = Path::new(src_dir);
-    let out_dir = args.target.as_deref().unwrap_or("./docs");
+    let out_dir = args.target.as_deref().or(options.target.as_deref()).unwrap_or(DEFAULT_DOCS_TARGET);
     let out_path = Path::new(out_dir);
 
-    ui.status(&format!("Scanning for odin files in: {:?}", src_path));
+    ui.status(&t!("Scanning for odin files in: {}", format!("{:?}", src_path)));
 
     let overview_re = Regex::new(r"(?im)//\s*@overview\s*:?\s*((?:.*(?:\n\s*//.*)*))").unwrap();
 
-    let pattern_odin = format!(
-        r"(?im)//\s*{}:?\s*((?:.*(?:\n\s*//.*)*))\n(?:.*\n)*?\s*(\w+)\s*(::|:)\s*(?:(proc|struct|enum|union)|([^\n]+))",
-        regex::escape(args.trigger.as_str())
-    );
-    let re_odin =
-        Regex::new(&pattern_odin).map_err(|e| CustomError::ValidationError(e.to_string()))?;
-
-    let pattern_glsl = format!(
-        r"(?im)//\s*{}:?\s*((?:.*(?:\n\s*//.*)*))",
-        regex::escape(args.trigger.as_str())
-    );
-    let re_glsl =
-        Regex::new(&pattern_glsl).map_err(|e| CustomError::ValidationError(e.to_string()))?;
+    let mut trigger_patterns = Vec::new();
+    for (trigger, category) in &triggers {
+        let pattern_odin = format!(
+            r"(?im)//\s*{}:?\s*((?:.*(?:\n\s*//.*)*))\n((?:.*\n)*?)\s*((?:@\([^)\n]*\)\s*)*)(\w+)\s*(::|:)\s*((?:#\w+\s+)*)(?:(proc|struct|enum|union)|([^\n]+))",
+            regex::escape(trigger)
+        );
+        let re_odin =
+            Regex::new(&pattern_odin).map_err(|e| CustomError::ValidationError(e.to_string()))?;
+
+        let pattern_glsl = format!(
+            r"(?im)//\s*{}:?\s*((?:.*(?:\n\s*//.*)*))",
+            regex::escape(trigger)
+        );
+        let re_glsl =
+            Regex::new(&pattern_glsl).map_err(|e| CustomError::ValidationError(e.to_string()))?;
+
+        trigger_patterns.push((*category, re_odin, re_glsl));
+    }
 
     let mut package_map: HashMap<PathBuf, PackageData> = HashMap::new();
     let mut files_processed = 0;
@@ -95,7 +181,7 @@ pub fn docs(args: &DocsArgs, ui: Ui) -> Result<(), CustomError> {
 
                 if let Some(lang) = language {
                     let content = fs::read_to_string(path).map_err(|_| {
-                        CustomError::ValidationError(format!(
+                        CustomError::ValidationError(t!(
                             "Failed to read file: {}",
                             path.display()
                         ))
@@ -108,12 +194,18 @@ pub fn docs(args: &DocsArgs, ui: Ui) -> Result<(), CustomError> {
                         file_overview = clean_comments(cap.get(1).map_or("", |m| m.as_str()));
                     }
 
-                    match lang {
-                        Language::Odin => {
-                            parse_odin(&content, &re_odin, &mut file_items);
+                    for (category, re_odin, re_glsl) in &trigger_patterns {
+                        let start = file_items.len();
+                        match lang {
+                            Language::Odin => {
+                                parse_odin(&content, re_odin, &mut file_items);
+                            }
+                            Language::Glsl => {
+                                parse_glsl(&content, re_glsl, &mut file_items);
+                            }
                         }
-                        Language::Glsl => {
-                            parse_glsl(&content, &re_glsl, &mut file_items);
+                        for item in &mut file_items[start..] {
+                            item.category = *category;
                         }
                     }
 
@@ -146,22 +238,76 @@ pub fn docs(args: &DocsArgs, ui: Ui) -> Result<(), CustomError> {
             }
             Err(e) => {
                 if ui.verbose {
-                    ui.log(&format!("Skipping entry: {}", e))
+                    ui.log(&t!("Skipping entry: {}", e))
                 }
             }
         }
     }
 
-    ui.status("Writing package pages...");
-
-    for (rel_path, mut data) in package_map {
-        // Sort items alphabetically so the page is readable
+    // Sort items alphabetically so the pages are readable
+    for data in package_map.values_mut() {
         data.items.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    ui.status(tr("Writing package pages..."));
+
+    let has = |category: DocsCategory| triggers.iter().any(|(_, c)| *c == category);
+    let mut pages = 0;
+    if args.trigger.is_some() {
+        pages += write_doc_set(&package_map, out_path, |_| true, args.site, &ui)?;
+    } else {
+        if has(DocsCategory::Public) {
+            pages += write_doc_set(
+                &package_map,
+                &out_path.join("public"),
+                |item| item.category == DocsCategory::Public,
+                args.site,
+                &ui,
+            )?;
+        }
+        if has(DocsCategory::Internal) {
+            pages += write_doc_set(
+                &package_map,
+                &out_path.join("internal"),
+                |item| item.category != DocsCategory::Examples,
+                args.site,
+                &ui,
+            )?;
+        }
+        if has(DocsCategory::Examples) {
+            fs::create_dir_all(out_path)?;
+            write_examples_page(&package_map, &out_path.join("examples.md"), args.site)?;
+            pages += 1;
+        }
+    }
+
+    ui.success(&t!(
+        "Docs generated successfully. Scanned {} files, created {} pages.",
+        files_processed, pages
+    ));
+    Ok(())
+}
+
+/// Writes one page per package with the items `include` picks, plus the site navigation.
+fn write_doc_set(
+    package_map: &HashMap<PathBuf, PackageData>,
+    out_path: &Path,
+    include: impl Fn(&DocItem) -> bool,
+    site: Option<DocsSite>,
+    ui: &Ui,
+) -> Result<usize, CustomError> {
+    let mut site_root = SiteNode::default();
+    let mut pages = 0;
+    for (rel_path, data) in package_map {
+        let items: Vec<&DocItem> = data.items.iter().filter(|item| include(item)).collect();
+        if items.is_empty() && data.overview.is_empty() {
+            continue;
+        }
 
         let file_stem = if rel_path.components().count() == 0 {
             Path::new("index")
         } else {
-            &rel_path
+            rel_path.as_path()
         };
 
         let out_file_path = out_path.join(file_stem).with_extension("md");
@@ -176,27 +322,116 @@ pub fn docs(args: &DocsArgs, ui: Ui) -> Result<(), CustomError> {
             .unwrap_or("Root")
             .to_string();
 
-        write_package_markdown(&title_str, &data.overview, &data.items, &out_file_path)?;
+        write_package_markdown(&title_str, &data.overview, &items, &out_file_path, site)?;
         if ui.verbose {
-            ui.log(&format!("Generated package: {:?}", out_file_path));
+            ui.log(&t!("Generated package: {}", out_file_path.display()));
         }
+        site_root.insert(file_stem, title_str);
+        pages += 1;
     }
 
-    ui.success(&format!(
-        "Docs generated successfully. Scanned {} files, created {} pages.",
-        files_processed,
-        out_path.read_dir()?.count()
-    ));
+    match site {
+        Some(DocsSite::Mdbook) => {
+            let mut summary = String::from("# Summary\n\n");
+            write_mdbook_summary(&site_root, 0, &mut summary);
+            fs::write(out_path.join("SUMMARY.md"), summary)?;
+            ui.log(&t!("Wrote {} for mdBook.", out_path.join("SUMMARY.md").display()));
+        }
+        Some(DocsSite::Docusaurus) => {
+            let sidebar = serde_json::to_string_pretty(&docusaurus_items(&site_root))
+                .map_err(|e| CustomError::ValidationError(e.to_string()))?;
+            fs::write(
+                out_path.join("sidebars.js"),
+                format!("module.exports = {{\n  apiSidebar: {},\n}};\n", sidebar.replace('\n', "\n  ")),
+            )?;
+            ui.log(&t!("Wrote {} for Docusaurus.", out_path.join("sidebars.js").display()));
+        }
+        None => {}
+    }
+
+    Ok(pages)
+}
+
+// every example on one page, grouped by package, with the full body of example procs
+fn write_examples_page(
+    package_map: &HashMap<PathBuf, PackageData>,
+    path: &Path,
+    site: Option<DocsSite>,
+) -> Result<(), CustomError> {
+    let mut content = if site == Some(DocsSite::Mdbook) {
+        "# Examples\n\n".to_string()
+    } else {
+        "---\ntitle: Examples\ndescription: Examples gallery\n---\n\n".to_string()
+    };
+
+    let mut packages: Vec<(&PathBuf, &PackageData)> = package_map.iter().collect();
+    packages.sort_by(|a, b| a.0.cmp(b.0));
+    for (rel_path, data) in packages {
+        let examples: Vec<&DocItem> = data
+            .items
+            .iter()
+            .filter(|item| item.category == DocsCategory::Examples)
+            .collect();
+        if examples.is_empty() {
+            continue;
+        }
+
+        let package = rel_path.to_string_lossy().replace('\\', "/");
+        content.push_str(&format!(
+            "## {}\n\n",
+            if package.is_empty() { "Root" } else { &package }
+        ));
+        for item in examples {
+            content.push_str(&format!("### {}\n\n{}\n\n", item.name, item.doc));
+            let code = match (&item.language, &item.body) {
+                (Language::Odin, Some(body)) => {
+                    format!("```Odin\n{} :: {} {{\n{}\n}}\n```", item.name, item.signature, body)
+                }
+                (Language::Odin, None) => {
+                    format!("```Odin\n{} {} {}\n```", item.name, item.operator, item.signature)
+                }
+                (Language::Glsl, _) => format!("```glsl\n{}\n```", item.signature),
+            };
+            content.push_str(&code);
+            content.push_str("\n\n---\n\n");
+        }
+    }
+
+    fs::write(path, content)
+        .map_err(|_| CustomError::ValidationError(path.display().to_string()))?;
     Ok(())
 }
 
 fn parse_odin(content: &str, re: &Regex, items: &mut Vec<DocItem>) {
     for cap in re.captures_iter(&content) {
         let raw_doc = cap.get(1).map_or("", |m| m.as_str());
-        let name = cap.get(2).map_or("Unknown", |m| m.as_str()).to_string();
-        let operator = cap.get(3).map_or("::", |m| m.as_str()).to_string();
-        let raw_kind_keyword = cap.get(4).map(|m| m.as_str());
-        let raw_value_content = cap.get(5).map(|m| m.as_str());
+        let name = cap.get(4).map_or("Unknown", |m| m.as_str()).to_string();
+        let operator = cap.get(5).map_or("::", |m| m.as_str()).to_string();
+        let directives = cap.get(6).map_or("", |m| m.as_str()).split_whitespace().collect::<Vec<_>>().join(" ");
+        let raw_kind_keyword = cap.get(7).map(|m| m.as_str());
+        let raw_value_content = cap.get(8).map(|m| m.as_str());
+
+        // attribute lines between the comment and the declaration, then ones on its own line
+        let mut attributes: Vec<String> = cap
+            .get(2)
+            .map_or("", |m| m.as_str())
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with('@'))
+            .map(str::to_string)
+            .collect();
+        attributes.extend(
+            cap.get(3)
+                .map_or("", |m| m.as_str())
+                .split_inclusive(')')
+                .map(str::trim)
+                .filter(|attr| !attr.is_empty())
+                .map(str::to_string),
+        );
+        let mut calling_convention = None;
+        let mut where_clause = None;
+        let mut members = Vec::new();
+        let mut body = None;
 
         let doc = raw_doc
             .lines()
@@ -220,7 +455,7 @@ fn parse_odin(content: &str, re: &Regex, items: &mut Vec<DocItem>) {
                 "union" => ItemKind::Union,
                 _ => ItemKind::Unknown,
             };
-            let kind_match = cap.get(4).unwrap();
+            let kind_match = cap.get(7).unwrap();
             let scan_start = kind_match.end();
             let (raw_header, body_start_index) = scan_header(&content, scan_start);
             let header_clean = raw_header.trim();
@@ -232,14 +467,27 @@ fn parse_odin(content: &str, re: &Regex, items: &mut Vec<DocItem>) {
                         let sig_string = if body_start_index > 0 {
                             let body = extract_balanced_block(&content, body_start_index)
                                 .unwrap_or("...".into());
-                            let clean = clean_body_indentation(body);
-                            format!("proc {{\n{}\n}}", clean)
+                            members = overload_members(&body);
+                            let lines: Vec<String> =
+                                members.iter().map(|m| format!("\t{},", m)).collect();
+                            format!("proc {{\n{}\n}}", lines.join("\n"))
                         } else {
                             "proc { ... }".into()
                         };
                         (ItemKind::Overload, sig_string)
                     } else {
-                        (ItemKind::Proc, format!("proc {}", header_clean))
+                        let (header, clause) = split_where_clause(header_clean);
+                        where_clause = clause;
+                        calling_convention = header
+                            .strip_prefix('"')
+                            .and_then(|rest| rest.split('"').next())
+                            .map(str::to_string);
+                        if body_start_index > 0 {
+                            body = extract_balanced_block(content, body_start_index).map(
+                                |block| block.trim_start_matches(['\r', '\n']).trim_end().to_string(),
+                            );
+                        }
+                        (ItemKind::Proc, format!("proc {}", header))
                     }
                 }
                 ItemKind::Struct | ItemKind::Enum | ItemKind::Union => {
@@ -257,7 +505,7 @@ fn parse_odin(content: &str, re: &Regex, items: &mut Vec<DocItem>) {
             }
         } else if let Some(val) = raw_value_content {
             if let Some(brace_offset) = val.find('{') {
-                let val_match = cap.get(5).unwrap();
+                let val_match = cap.get(8).unwrap();
                 let body_start = val_match.start() + brace_offset + 1;
 
                 let sig_string = if let Some(block) = extract_balanced_block(&content, body_start) {
@@ -276,6 +524,12 @@ fn parse_odin(content: &str, re: &Regex, items: &mut Vec<DocItem>) {
             (ItemKind::Unknown, "???".to_string())
         };
 
+        let signature = if directives.is_empty() {
+            signature
+        } else {
+            format!("{} {}", directives, signature)
+        };
+
         items.push(DocItem {
             name,
             doc,
@@ -283,42 +537,145 @@ fn parse_odin(content: &str, re: &Regex, items: &mut Vec<DocItem>) {
             signature,
             operator,
             language: Language::Odin,
+            attributes,
+            calling_convention,
+            where_clause,
+            members,
+            body,
+            category: DocsCategory::Public,
         });
     }
 }
 
+// `a, b, pkg.c,` with comments and blank lines dropped
+fn overload_members(body: &str) -> Vec<String> {
+    body.lines()
+        .map(|line| line.split("//").next().unwrap_or(""))
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|member| !member.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// splits `(x: $T) -> T where intrinsics.type_is_numeric(T)` at the top-level `where`
+fn split_where_clause(header: &str) -> (String, Option<String>) {
+    let mut depth = 0;
+    for (i, c) in header.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            'w' if depth == 0
+                && header[i..].starts_with("where")
+                && header[..i].ends_with(char::is_whitespace)
+                && header[i + 5..].starts_with(char::is_whitespace) =>
+            {
+                let clause = header[i + 5..].split_whitespace().collect::<Vec<_>>().join(" ");
+                return (header[..i].trim_end().to_string(), Some(clause));
+            }
+            _ => {}
+        }
+    }
+    (header.to_string(), None)
+}
+
+// heading anchors as GitHub, mdBook and Docusaurus generate them
+fn anchor(name: &str) -> String {
+    name.to_lowercase()
+}
+
+// directories without a page of their own become mdBook draft chapters
+fn write_mdbook_summary(node: &SiteNode, depth: usize, out: &mut String) {
+    for (name, child) in node.ordered_children() {
+        let indent = "  ".repeat(depth);
+        match &child.page {
+            Some((path, title)) => out.push_str(&format!("{}- [{}]({}.md)\n", indent, title, path)),
+            None => out.push_str(&format!("{}- [{}]()\n", indent, name)),
+        }
+        write_mdbook_summary(child, depth + 1, out);
+    }
+}
+
+fn docusaurus_items(node: &SiteNode) -> Vec<serde_json::Value> {
+    node.ordered_children()
+        .map(|(name, child)| match (&child.page, child.children.is_empty()) {
+            (Some((path, _)), true) => serde_json::Value::String(path.clone()),
+            (page, _) => {
+                let mut category = serde_json::json!({
+                    "type": "category",
+                    "label": page.as_ref().map_or(name.as_str(), |(_, title)| title.as_str()),
+                    "items": docusaurus_items(child),
+                });
+                if let Some((path, _)) = page {
+                    category["link"] = serde_json::json!({ "type": "doc", "id": path });
+                }
+                category
+            }
+        })
+        .collect()
+}
+
 fn write_package_markdown(
     title: &str,
     overview: &str,
-    items: &[DocItem],
-    path: &PathBuf,
+    items: &[&DocItem],
+    path: &Path,
+    site: Option<DocsSite>,
 ) -> Result<(), CustomError> {
-    let mut content = format!(
-        "---\ntitle: {}\ndescription: API Reference for {} package\n---\n\n",
-        title, title
-    );
+    // mdBook prints front matter as text, it takes the title from a heading instead
+    let mut content = if site == Some(DocsSite::Mdbook) {
+        format!("# {}\n\n", title)
+    } else {
+        format!(
+            "---\ntitle: {}\ndescription: API Reference for {} package\n---\n\n",
+            title, title
+        )
+    };
 
     if !overview.is_empty() {
         content.push_str(overview);
         content.push_str("\n\n---\n\n");
     }
 
+    // overload sets on this page, by member
+    let mut overload_sets: HashMap<&str, Vec<&str>> = HashMap::new();
+    for item in items.iter().filter(|i| i.kind == ItemKind::Overload) {
+        for member in &item.members {
+            overload_sets.entry(member).or_default().push(&item.name);
+        }
+    }
+    let link = |name: &str| {
+        if items.iter().any(|i| i.name == name) {
+            format!("[`{}`](#{})", name, anchor(name))
+        } else {
+            format!("`{}`", name)
+        }
+    };
+
     for item in items {
         content.push_str(&format!("## {}\n\n", item.name));
 
-        let private_prefix = if item.name.starts_with('_') {
-            "@(private)\n"
-        } else {
-            ""
-        };
+        let mut attributes = item.attributes.join("\n");
+        if item.name.starts_with('_') && !attributes.contains("private") {
+            attributes.insert_str(0, "@(private)\n");
+        }
+        if !attributes.is_empty() && !attributes.ends_with('\n') {
+            attributes.push('\n');
+        }
 
         match item.language {
             Language::Odin => {
                 let operator = if item.operator == "::" { " ::" } else { ":" };
+                let where_clause = item
+                    .where_clause
+                    .as_ref()
+                    .map(|clause| format!("\n\twhere {}", clause))
+                    .unwrap_or_default();
                 content.push_str(&format!(
-                    "```Odin\n{}{}{} {}\n```\n\n",
-                    private_prefix, item.name, operator, item.signature
+                    "```Odin\n{}{}{} {}{}\n```\n\n",
+                    attributes, item.name, operator, item.signature, where_clause
                 ));
+
             }
             Language::Glsl => {
                 content.push_str(&format!("```glsl\n{}\n```\n\n", item.signature));
@@ -326,6 +683,18 @@ fn write_package_markdown(
         }
 
         content.push_str(&format!("{}\n\n", item.doc));
+
+        if let Some(convention) = &item.calling_convention {
+            content.push_str(&format!("Calling convention: `\"{}\"`\n\n", convention));
+        }
+        if !item.members.is_empty() {
+            let members: Vec<String> = item.members.iter().map(|m| link(m)).collect();
+            content.push_str(&format!("Overloads: {}\n\n", members.join(", ")));
+        }
+        if let Some(sets) = overload_sets.get(item.name.as_str()) {
+            let sets: Vec<String> = sets.iter().map(|s| link(s)).collect();
+            content.push_str(&format!("Part of: {}\n\n", sets.join(", ")));
+        }
         content.push_str("---\n");
     }
 
@@ -435,6 +804,12 @@ fn parse_glsl(content: &str, re: &Regex, items: &mut Vec<DocItem>) {
                 signature: final_signature,
                 operator: "".to_string(),
                 language: Language::Glsl,
+                attributes: Vec::new(),
+                calling_convention: None,
+                where_clause: None,
+                members: Vec::new(),
+                body: None,
+                category: DocsCategory::Public,
             });
         }
     }
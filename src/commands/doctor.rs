@@ -0,0 +1,63 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::manifest::load_manifest;
+use crate::sysinfo::{SystemInfo, collect_system_info, render_system_info};
+use crate::tools::{ToolCheck, check_tools, render_tool_report};
+use clap::Args;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    #[arg(default_value = ".")]
+    pub dir: String,
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct DoctorReport {
+    system: SystemInfo,
+    tools: Vec<ToolCheck>,
+}
+
+pub fn doctor(args: &DoctorArgs, _ui: Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(&args.dir);
+
+    // outside a project there are no [tools] to check, the environment report still helps
+    let tools = if project_dir.join("bonsai.toml").exists() {
+        check_tools(&load_manifest(project_dir)?.tools)?
+    } else {
+        Vec::new()
+    };
+
+    let report = DoctorReport {
+        system: collect_system_info(),
+        tools,
+    };
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&report).map_err(|e| {
+            CustomError::ValidationError(t!("Failed to serialize doctor report: {}", e))
+        })?;
+        println!("{}", json);
+    } else {
+        print!("{}", render_system_info(&report.system));
+        if !report.tools.is_empty() {
+            println!("\n{}", tr("Tools:"));
+            print!("{}", render_tool_report(&report.tools));
+        }
+    }
+
+    let failed = report.tools.iter().filter(|t| !t.ok).count();
+    if failed > 0 {
+        return Err(CustomError::ValidationError(t!(
+            "{} of {} tools from [tools] are missing or outdated",
+            failed,
+            report.tools.len()
+        )));
+    }
+
+    Ok(())
+}
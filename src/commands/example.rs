@@ -0,0 +1,251 @@
+use crate::Ui;
+use crate::commands::init::{REPO_URL, strip_template};
+use crate::commands::run::{self, RunArgs};
+use crate::config::global_dir;
+use crate::error::CustomError;
+use crate::git::clone_repo;
+use crate::i18n::t;
+use crate::manifest::create_manifest;
+use crate::path_guard::{copy_dir_all, report_skipped};
+use clap::{Args, Subcommand};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EXAMPLES_DIR: &str = "examples";
+const CACHE_DIR: &str = "cache/examples";
+// example directories holding these replace the matching sandbox directory,
+// anything else is treated as the contents of source/game
+const PROJECT_DIRS: &[&str] = &["source", "assets"];
+const GAME_DIR: &str = "source/game";
+
+#[derive(Args)]
+pub struct ExampleArgs {
+    #[command(subcommand)]
+    pub command: ExampleCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ExampleCommand {
+    /// List the examples bundled with the engine
+    List {
+        #[arg(long, short, default_value = "latest")]
+        version: String,
+        /// Download the engine again instead of using the cached copy
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Run an example in a sandbox project
+    Run {
+        name: String,
+        #[arg(long, short, default_value = "latest")]
+        version: String,
+        /// Download the engine again instead of using the cached copy
+        #[arg(long)]
+        refresh: bool,
+        #[arg(long)]
+        web: bool,
+        #[arg(long, short = 'c', default_value = "debug")]
+        config: String,
+        #[arg(long, short = 'p')]
+        port: Option<u16>,
+    },
+}
+
+pub fn example(args: &ExampleArgs, ui: Ui) -> Result<(), CustomError> {
+    match &args.command {
+        ExampleCommand::List { version, refresh } => {
+            let engine = engine_checkout(version, *refresh, &ui)?;
+            let examples = list_examples(&engine)?;
+
+            let width = examples.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+            for (name, description) in &examples {
+                match description {
+                    Some(description) => println!("{:<width$}  {}", name, description, width = width),
+                    None => println!("{}", name),
+                }
+            }
+            Ok(())
+        }
+        ExampleCommand::Run {
+            name,
+            version,
+            refresh,
+            web,
+            config,
+            port,
+        } => {
+            let engine = engine_checkout(version, *refresh, &ui)?;
+            let example_dir = engine.join(EXAMPLES_DIR).join(name);
+            if !example_dir.is_dir() {
+                let available = list_examples(&engine)?
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>();
+                return Err(CustomError::ValidationError(t!(
+                    "Unknown example '{}'. Available examples: {}",
+                    name,
+                    available.join(", ")
+                )));
+            }
+
+            let sandbox = prepare_sandbox(&engine, &example_dir, version, name, &ui)?;
+            ui.log(&t!("Running example '{}' from {}.", name, sandbox.display()));
+
+            let run_args = RunArgs {
+                dir: sandbox.to_string_lossy().to_string(),
+                desktop: !web,
+                web: *web,
+                config: config.clone(),
+                clean: false,
+                clean_cache: false,
+                force_shaders: false,
+                port: *port,
+                browser: None,
+                browser_profile: false,
+                watch: false,
+                record: None,
+                replay: None,
+                no_console_bridge: false,
+                memory_report: false,
+                wait: false,
+                capture: false,
+                capture_frame: None,
+            };
+            run::run(&run_args, ui)
+        }
+    }
+}
+
+fn cache_dir(version: &str) -> Result<PathBuf, CustomError> {
+    Ok(global_dir()?.join(CACHE_DIR).join(version))
+}
+
+// one engine clone per version, shared by every sandbox
+pub fn engine_checkout(version: &str, refresh: bool, ui: &Ui) -> Result<PathBuf, CustomError> {
+    let engine = cache_dir(version)?.join("engine");
+
+    if refresh && engine.exists() {
+        fs::remove_dir_all(&engine)?;
+    }
+    if engine.exists() {
+        return Ok(engine);
+    }
+
+    fs::create_dir_all(cache_dir(version)?)?;
+
+    // clone next to the final location so an interrupted download is never mistaken for a cache hit
+    let partial = cache_dir(version)?.join("engine.partial");
+    if partial.exists() {
+        fs::remove_dir_all(&partial)?;
+    }
+    clone_repo(REPO_URL, &partial, version, ui)?;
+    fs::rename(&partial, &engine)?;
+
+    Ok(engine)
+}
+
+fn describe_example(dir: &Path) -> Option<String> {
+    let readme = fs::read_to_string(dir.join("README.md")).ok();
+    if let Some(line) = readme
+        .as_deref()
+        .and_then(|r| r.lines().map(str::trim).find(|l| !l.is_empty() && !l.starts_with('#')))
+    {
+        return Some(line.to_string());
+    }
+
+    // fall back to the leading comment of the example's first Odin file
+    let mut sources = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "odin"))
+        .collect::<Vec<_>>();
+    sources.sort();
+
+    let source = fs::read_to_string(sources.first()?).ok()?;
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|l| l.starts_with("//") || l.is_empty())
+        .find_map(|l| l.strip_prefix("//").map(str::trim).filter(|l| !l.is_empty()))
+        .map(str::to_string)
+}
+
+fn list_examples(engine: &Path) -> Result<Vec<(String, Option<String>)>, CustomError> {
+    let examples_dir = engine.join(EXAMPLES_DIR);
+    if !examples_dir.is_dir() {
+        return Err(CustomError::ValidationError(
+            t!("This engine version has no bundled examples. Try '--version latest --refresh'")
+        ));
+    }
+
+    let mut examples = fs::read_dir(&examples_dir)?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| {
+            let name = e.file_name().to_str()?.to_string();
+            Some((name, describe_example(&e.path())))
+        })
+        .collect::<Vec<_>>();
+    examples.sort();
+
+    Ok(examples)
+}
+
+/// Copies an engine checkout into `destination` as a new project called `name`, without the
+/// bundled examples.
+pub fn copy_engine_project(
+    engine: &Path,
+    destination: &Path,
+    name: &str,
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    report_skipped(destination, &copy_dir_all(engine, destination)?, ui);
+    strip_template(destination)?;
+    let examples_copy = destination.join(EXAMPLES_DIR);
+    if examples_copy.exists() {
+        fs::remove_dir_all(examples_copy)?;
+    }
+    create_manifest(destination, name)
+}
+
+// the sandbox keeps its build cache between runs, only the example sources are refreshed
+fn prepare_sandbox(
+    engine: &Path,
+    example_dir: &Path,
+    version: &str,
+    name: &str,
+    ui: &Ui,
+) -> Result<PathBuf, CustomError> {
+    let sandbox = cache_dir(version)?.join("sandbox").join(name);
+
+    if !sandbox.join("bonsai.toml").exists() {
+        ui.status(&t!("Creating sandbox for example '{}'...", name));
+        if sandbox.exists() {
+            fs::remove_dir_all(&sandbox)?;
+        }
+        copy_engine_project(engine, &sandbox, &format!("example_{}", name), ui)?;
+    }
+
+    let overlays_project = PROJECT_DIRS.iter().any(|dir| example_dir.join(dir).is_dir());
+    if overlays_project {
+        for dir in PROJECT_DIRS {
+            let src = example_dir.join(dir);
+            if src.is_dir() {
+                let dest = sandbox.join(dir);
+                if dest.exists() {
+                    fs::remove_dir_all(&dest)?;
+                }
+                report_skipped(&dest, &copy_dir_all(&src, &dest)?, ui);
+            }
+        }
+    } else {
+        let game_dir = sandbox.join(GAME_DIR);
+        if game_dir.exists() {
+            fs::remove_dir_all(&game_dir)?;
+        }
+        report_skipped(&game_dir, &copy_dir_all(example_dir, &game_dir)?, ui);
+    }
+
+    Ok(sandbox)
+}
@@ -0,0 +1,272 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::rebuild_log::{Reason, RebuildRecord, Status, key, load_log};
+use clap::Args;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// changed inputs listed before the rest are summarized
+const MAX_LISTED_REASONS: usize = 20;
+
+#[derive(Args)]
+pub struct ExplainBuildArgs {
+    /// Output to explain, e.g. build/desktop/game_desktop.bin (default: list every tracked output)
+    pub path: Option<String>,
+    #[arg(long, default_value = ".")]
+    pub dir: String,
+    /// Print the record as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn explain_build(args: &ExplainBuildArgs, _ui: Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(&args.dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    let current_dir = std::env::current_dir()?;
+    let project_root = project_dir.canonicalize()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir.clone(), |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    let log = load_log();
+    if log.is_empty() {
+        return Err(CustomError::ValidationError(
+            t!("Nothing recorded yet, run 'bonsai build' first")
+        ));
+    }
+
+    let Some(path) = &args.path else {
+        for (output, record) in &log {
+            println!(
+                "{:<13}  {:<19}  {}",
+                status_name(record.status),
+                record.rebuilt.as_deref().unwrap_or("-"),
+                output
+            );
+        }
+        return Ok(());
+    };
+
+    let (output, record) = find_record(&log, path, &current_dir, &project_root)?;
+    if args.json {
+        let json = serde_json::json!({
+            "output": output,
+            "status": record.status,
+            "checked": record.checked,
+            "rebuilt": record.rebuilt,
+            "reasons": record.reasons,
+        });
+        let json = serde_json::to_string_pretty(&json).map_err(|e| {
+            CustomError::ValidationError(t!("Failed to serialize rebuild record: {}", e))
+        })?;
+        println!("{}", json);
+    } else {
+        print_explanation(output, record);
+    }
+    Ok(())
+}
+
+fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::UpToDate => tr("up to date"),
+        Status::Rebuilding => tr("unfinished"),
+        Status::Rebuilt => tr("rebuilt"),
+    }
+}
+
+// `path` relative to where the command was run, or to the project, or just a file name
+fn find_record<'a>(
+    log: &'a BTreeMap<String, RebuildRecord>,
+    path: &str,
+    current_dir: &Path,
+    project_root: &Path,
+) -> Result<(&'a String, &'a RebuildRecord), CustomError> {
+    let absolute = current_dir.join(path);
+    let mut candidates = vec![key(Path::new(path))];
+    if let Some(relative) = absolute
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.strip_prefix(project_root).ok().map(Path::to_path_buf))
+    {
+        candidates.insert(0, key(&relative));
+    }
+    if let Some(found) = candidates.iter().find_map(|c| log.get_key_value(c)) {
+        return Ok(found);
+    }
+
+    let file_name = Path::new(path).file_name().map(|n| n.to_string_lossy().to_string());
+    let similar: Vec<&String> = log
+        .keys()
+        .filter(|output| {
+            file_name
+                .as_deref()
+                .is_some_and(|name| Path::new(output.as_str()).file_name().is_some_and(|n| n == name))
+        })
+        .collect();
+    match similar.as_slice() {
+        [only] => Ok(log.get_key_value(*only).unwrap()),
+        [] => Err(CustomError::ValidationError(t!(
+            "No rebuild record for '{}'. Run 'bonsai explain-build' to list the tracked outputs",
+            path
+        ))),
+        several => Err(CustomError::ValidationError(t!(
+            "'{}' matches several outputs: {}",
+            path,
+            several.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+        ))),
+    }
+}
+
+fn print_explanation(output: &str, record: &RebuildRecord) {
+    println!("{}", output);
+    match (record.status, &record.rebuilt) {
+        (Status::UpToDate, Some(rebuilt)) => {
+            println!("{}", t!("  Up to date when last checked at {}.", record.checked));
+            println!("{}", t!("  Last rebuilt at {} because:", rebuilt));
+        }
+        (Status::UpToDate, None) => {
+            println!("{}", t!("  Up to date when last checked at {}.", record.checked));
+            println!("{}", tr("  It hasn't been rebuilt since the rebuild log was started."));
+            return;
+        }
+        (Status::Rebuilding, _) => {
+            println!(
+                "{}",
+                t!(
+                    "  A rebuild started at {} and didn't finish (it failed or was stopped) because:",
+                    record.checked
+                )
+            );
+        }
+        (Status::Rebuilt, rebuilt) => {
+            println!(
+                "{}",
+                t!("  Rebuilt at {} because:", rebuilt.as_deref().unwrap_or(&record.checked))
+            );
+        }
+    }
+
+    for reason in record.reasons.iter().take(MAX_LISTED_REASONS) {
+        print_reason(reason);
+    }
+    if record.reasons.len() > MAX_LISTED_REASONS {
+        println!("{}", t!("  - ... and {} more", record.reasons.len() - MAX_LISTED_REASONS));
+    }
+}
+
+fn print_reason(reason: &Reason) {
+    match reason {
+        Reason::Missing => println!("{}", tr("  - the output didn't exist")),
+        Reason::Forced { why } => println!("{}", t!("  - it was forced: {}", why)),
+        Reason::Newer { input } => println!("{}", t!("  - {} was modified after the output was built", input)),
+        Reason::Changed { input } => println!("{}", t!("  - {} changed (its content hash differs)", input)),
+        Reason::Added { input } => println!("{}", t!("  - {} is a new input", input)),
+        Reason::Removed { input } => println!("{}", t!("  - {} is no longer an input", input)),
+        Reason::OutputChanged => {
+            println!("{}", tr("  - the output was changed or replaced after it was built"))
+        }
+        Reason::Unrecorded => println!(
+            "{}",
+            tr("  - nothing recorded which inputs it was built from (first build with content hashes)")
+        ),
+        Reason::Settings { stage, before, after } => {
+            println!("{}", t!("  - the {} settings changed", stage));
+            match before {
+                Some(before) => {
+                    for line in settings_diff(before, after) {
+                        println!("      {}", line);
+                    }
+                }
+                None => println!("{}", tr("      (the previous settings weren't recorded)")),
+            }
+        }
+    }
+}
+
+// one line per changed setting, commands compared word by word
+fn settings_diff(before: &str, after: &str) -> Vec<String> {
+    let (Ok(before_json), Ok(after_json)) = (
+        serde_json::from_str::<Value>(before),
+        serde_json::from_str::<Value>(after),
+    ) else {
+        return vec![format!("before: {}", before.trim()), format!("after:  {}", after.trim())];
+    };
+
+    let mut old = BTreeMap::new();
+    let mut new = BTreeMap::new();
+    flatten("", &before_json, &mut old);
+    flatten("", &after_json, &mut new);
+
+    let mut lines = Vec::new();
+    for name in old.keys().chain(new.keys().filter(|k| !old.contains_key(*k))) {
+        let (from, to) = (old.get(name), new.get(name));
+        if from == to {
+            continue;
+        }
+        let name = if name.is_empty() { "value" } else { name.as_str() };
+        match (from, to) {
+            (Some(from), Some(to)) if from.contains(' ') || to.contains(' ') => {
+                let from_words: Vec<&str> = from.split_whitespace().collect();
+                let to_words: Vec<&str> = to.split_whitespace().collect();
+                let removed: Vec<&str> = from_words.iter().copied().filter(|w| !to_words.contains(w)).collect();
+                let added: Vec<&str> = to_words.iter().copied().filter(|w| !from_words.contains(w)).collect();
+                let mut parts = Vec::new();
+                if !removed.is_empty() {
+                    parts.push(format!("removed {}", removed.join(" ")));
+                }
+                if !added.is_empty() {
+                    parts.push(format!("added {}", added.join(" ")));
+                }
+                if parts.is_empty() {
+                    parts.push("same words in a different order".to_string());
+                }
+                lines.push(format!("{}: {}", name, parts.join(", ")));
+            }
+            _ => lines.push(format!(
+                "{}: {} -> {}",
+                name,
+                from.map(String::as_str).unwrap_or("(unset)"),
+                to.map(String::as_str).unwrap_or("(unset)")
+            )),
+        }
+    }
+    lines
+}
+
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, String>) {
+    let join = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", prefix, name)
+        }
+    };
+    match value {
+        Value::Object(map) => {
+            for (name, value) in map {
+                flatten(&join(name), value, out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                flatten(&join(&i.to_string()), value, out);
+            }
+        }
+        Value::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        other => {
+            out.insert(prefix.to_string(), other.to_string());
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::generate::vet_generated;
+use crate::i18n::{t, tr};
+use crate::manifest::{load_manifest, update_manifest};
+use crate::wsl::check_project_location;
+use clap::Args;
+use std::path::Path;
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    #[arg(default_value = ".")]
+    pub dir: String,
+    /// Fail if committed generated files are out of date, without changing them
+    #[arg(long, conflicts_with = "write")]
+    pub check: bool,
+    /// Regenerate all generated files
+    #[arg(long)]
+    pub write: bool,
+}
+
+pub fn generate(args: &GenerateArgs, ui: Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(&args.dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    check_project_location(project_dir, &ui);
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    update_manifest(Path::new("."), &ui)?;
+    let manifest = load_manifest(Path::new("."))?;
+
+    ui.status(tr("Regenerating generated files..."));
+    let stale = vet_generated(&manifest, !args.check, &ui)?;
+
+    if args.check {
+        if stale.is_empty() {
+            ui.success(tr("Generated files are up to date."));
+            return Ok(());
+        }
+
+        for path in &stale {
+            println!("{}", t!("stale: {}", path.display()));
+        }
+        return Err(CustomError::ValidationError(t!(
+            "{} generated file(s) are out of date. Run 'bonsai generate --write' and commit the result",
+            stale.len()
+        )));
+    }
+
+    for path in &stale {
+        ui.log(&t!("Updated {}.", path.display()));
+    }
+    ui.success(tr("Generated files refreshed."));
+
+    Ok(())
+}
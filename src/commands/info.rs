@@ -0,0 +1,107 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::manifest::load_manifest;
+use crate::paths::ProjectPaths;
+use crate::version::{VersionInfo, read_version_info, resolve_versions};
+use crate::wsl::check_project_location;
+use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const BUILD_TARGETS: &[&str] = &["desktop", "web"];
+
+#[derive(Args)]
+pub struct InfoArgs {
+    /// Project directory, build directory, executable or web build index.html
+    #[arg(default_value = ".")]
+    pub path: String,
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct ProjectInfoReport {
+    name: String,
+    versions: VersionInfo,
+    builds: BTreeMap<String, VersionInfo>,
+}
+
+fn print_versions(label: &str, versions: &VersionInfo) {
+    println!(
+        "{:<16} {}",
+        format!("{}:", label),
+        t!("engine {}, cli {}, project {}", versions.engine, versions.cli, versions.project)
+    );
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, CustomError> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| CustomError::ValidationError(t!("Failed to serialize info: {}", e)))
+}
+
+pub fn info(args: &InfoArgs, ui: Ui) -> Result<(), CustomError> {
+    let path = Path::new(&args.path);
+    if !path.exists() {
+        return Err(CustomError::ValidationError(t!(
+            "'{}' does not exist",
+            args.path
+        )));
+    }
+
+    // a shipped build: read back what was embedded into it
+    if !path.join("bonsai.toml").exists() {
+        let versions = read_version_info(path).ok_or_else(|| {
+            CustomError::ValidationError(t!(
+                "No version information found at '{}'. Expected a bonsai project or build",
+                args.path
+            ))
+        })?;
+        if args.json {
+            println!("{}", to_json(&versions)?);
+        } else {
+            print_versions("Build", &versions);
+        }
+        return Ok(());
+    }
+
+    check_project_location(path, &ui);
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(path)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    let manifest = load_manifest(Path::new("."))?;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let report = ProjectInfoReport {
+        name: manifest.project.name.clone(),
+        versions: resolve_versions(&manifest)?,
+        builds: BUILD_TARGETS
+            .iter()
+            .filter_map(|target| {
+                let versions = read_version_info(&paths.build_target(target))?;
+                Some((target.to_string(), versions))
+            })
+            .collect(),
+    };
+
+    if args.json {
+        println!("{}", to_json(&report)?);
+        return Ok(());
+    }
+
+    println!("{:<16} {}", tr("Project:"), report.name);
+    print_versions(tr("Current"), &report.versions);
+    for (target, versions) in &report.builds {
+        print_versions(&t!("Build ({})", target), versions);
+    }
+    if report.builds.is_empty() {
+        println!("{}", tr("No builds found."));
+    }
+
+    Ok(())
+}
@@ -1,26 +1,63 @@
 use crate::Ui;
 use crate::error::CustomError;
-use crate::git::clone_repo;
-use crate::manifest::create_manifest;
+use crate::git::{clone_repo, clone_repo_sparse, describe_tag};
+use crate::i18n::{t, tr};
+use crate::manifest::{create_manifest, set_manifest_value};
 use clap::Args;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
-const REPO_URL: &str = "https://github.com/nihiL7331/bonsai-2d.git";
+pub const REPO_URL: &str = "https://github.com/nihiL7331/bonsai-2d.git";
+const RUN_SH_TEMPLATE: &str = r#"#!/bin/sh
+# NOTE: Machine generated by bonsai CLI.
+# Usage: ./run.sh [--web] [--config release] ...
+cd "$(dirname "$0")" || exit 1
+exec bonsai run "$@"
+"#;
+const RUN_BAT_TEMPLATE: &str = "@echo off\r
+rem NOTE: Machine generated by bonsai CLI.\r
+rem Usage: run.bat [--web] [--config release] ...\r
+cd /d \"%~dp0\"\r
+bonsai run %*\r
+";
+const SPARSE_PATHS: &[&str] = &["bonsai", "source", "assets", "utils"];
 
 #[derive(Args)]
 pub struct InitArgs {
-    pub name: String,
-    #[arg(long, short)]
+    #[arg(required_unless_present = "here")]
+    pub name: Option<String>,
+    #[arg(long, short, conflicts_with = "here")]
     pub dir: Option<String>,
     #[arg(long, short, default_value = "latest")]
     pub version: String,
+    #[arg(long)]
+    pub here: bool,
+    #[arg(long, requires = "here")]
+    pub force: bool,
+    #[arg(long)]
+    pub sparse: bool,
+    #[arg(long = "include", value_name = "PATH", requires = "sparse")]
+    pub include: Vec<String>,
 }
 
 pub fn init(args: &InitArgs, ui: Ui) -> Result<(), CustomError> {
-    if args.name.is_empty() {
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None => std::env::current_dir()?
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.to_string())
+            .ok_or_else(|| {
+                CustomError::ValidationError(
+                    t!("Could not determine project name from the current directory")
+                )
+            })?,
+    };
+
+    if name.is_empty() {
         return Err(CustomError::ValidationError(
-            "Project name cannot be empty".to_string(),
+            t!("Project name cannot be empty")
         ));
     }
 
@@ -30,19 +67,20 @@ pub fn init(args: &InitArgs, ui: Ui) -> Result<(), CustomError> {
         &['/', '\0']
     };
 
-    if args.name.chars().any(|c| invalid_chars.contains(&c)) {
-        return Err(CustomError::ValidationError(format!(
-            "Project name contains invalid characters: {:?}",
-            invalid_chars
-        )));
+    if name.chars().any(|c| invalid_chars.contains(&c)) {
+        return Err(CustomError::ValidationError(t!("Project name contains invalid characters: {}", format!("{:?}", invalid_chars))));
+    }
+
+    if args.here {
+        return init_here(args, &name, ui);
     }
 
-    let destination_dir = args.dir.as_deref().unwrap_or(&args.name);
+    let destination_dir = args.dir.as_deref().unwrap_or(&name);
     let destination = Path::new(destination_dir);
 
     let should_cleanup = if destination.exists() {
         if !destination.is_dir() {
-            return Err(CustomError::ValidationError(format!(
+            return Err(CustomError::ValidationError(t!(
                 "'{}' exists but is not a directory",
                 destination.display()
             )));
@@ -54,8 +92,8 @@ pub fn init(args: &InitArgs, ui: Ui) -> Result<(), CustomError> {
             .is_none();
 
         if !is_empty {
-            return Err(CustomError::ValidationError(format!(
-                "Destination '{}' already exists and is not empty",
+            return Err(CustomError::ValidationError(t!(
+                "Destination '{}' already exists and is not empty (use --here from inside it to merge)",
                 destination.display()
             )));
         }
@@ -66,15 +104,116 @@ pub fn init(args: &InitArgs, ui: Ui) -> Result<(), CustomError> {
     };
     let cleanup_on_fail = scopeguard::guard(should_cleanup, |should| {
         if should && destination.exists() {
-            ui.status("Cleaning up partial installation...");
+            ui.status(tr("Cleaning up partial installation..."));
             let _ = fs::remove_dir_all(destination);
         }
     });
 
-    ui.log(&format!("Initializing project '{}'.", args.name));
+    ui.log(&t!("Initializing project '{}'.", name));
+
+    fetch_template(args, destination, &ui)?;
+    let engine_version = template_version(args, destination);
+
+    strip_template(destination)?;
+
+    create_manifest(destination, &name)?;
+    record_engine_version(destination, engine_version.as_deref())?;
+    write_launchers(destination)?;
+
+    ui.success(&t!("Project '{}' initialized successfully.", name));
+
+    scopeguard::ScopeGuard::into_inner(cleanup_on_fail);
+
+    Ok(())
+}
+
+fn init_here(args: &InitArgs, name: &str, ui: Ui) -> Result<(), CustomError> {
+    let destination = Path::new(".");
+
+    ui.log(&t!(
+        "Initializing project '{}' in the current directory.",
+        name
+    ));
+
+    let template = tempfile::TempDir::new()?;
+    fetch_template(args, template.path(), &ui)?;
+    let engine_version = template_version(args, template.path());
+    strip_template(template.path())?;
+    create_manifest(template.path(), name)?;
+    record_engine_version(template.path(), engine_version.as_deref())?;
+    write_launchers(template.path())?;
+
+    ui.status(tr("Merging template files..."));
+    let (copied, skipped) = merge_template(template.path(), destination, args.force)?;
+
+    for path in &skipped {
+        ui.message(&t!("  ~ Skipped existing file: '{}'", path.display()));
+    }
+
+    if !skipped.is_empty() {
+        ui.message(&t!(
+            "  {} file(s) already existed and were left untouched (use --force to overwrite).",
+            skipped.len()
+        ));
+    }
+
+    ui.success(&t!(
+        "Project '{}' initialized successfully ({} files added).",
+        name, copied
+    ));
+
+    Ok(())
+}
+
+fn fetch_template(args: &InitArgs, destination: &Path, ui: &Ui) -> Result<(), CustomError> {
+    if !args.sparse {
+        return clone_repo(REPO_URL, destination, &args.version, ui);
+    }
+
+    let mut paths: Vec<String> = SPARSE_PATHS.iter().map(|p| p.to_string()).collect();
+    for path in &args.include {
+        let path = path.trim_matches('/').to_string();
+        if !path.is_empty() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    clone_repo_sparse(REPO_URL, destination, &args.version, &paths, ui)
+}
+
+// the requested tag, or whatever the cloned template describes itself as for "latest"
+fn template_version(args: &InitArgs, template: &Path) -> Option<String> {
+    if args.version != "latest" {
+        return Some(args.version.clone());
+    }
+    describe_tag(template)
+}
+
+fn record_engine_version(destination: &Path, version: Option<&str>) -> Result<(), CustomError> {
+    match version {
+        Some(version) => set_manifest_value(destination, "toolchain", "engine", version),
+        None => Ok(()),
+    }
+}
+
+fn write_launchers(destination: &Path) -> Result<(), CustomError> {
+    let run_sh = destination.join("run.sh");
+    fs::write(&run_sh, RUN_SH_TEMPLATE)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(&run_sh)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&run_sh, perms)?;
+    }
 
-    clone_repo(REPO_URL, destination, &args.version, &ui)?;
+    fs::write(destination.join("run.bat"), RUN_BAT_TEMPLATE)?;
 
+    Ok(())
+}
+
+pub fn strip_template(destination: &Path) -> Result<(), CustomError> {
     let git_dir = destination.join(".git");
     if git_dir.exists() {
         fs::remove_dir_all(&git_dir)?;
@@ -92,14 +231,44 @@ pub fn init(args: &InitArgs, ui: Ui) -> Result<(), CustomError> {
         fs::remove_file(&gitignore_dir)?;
     }
 
-    create_manifest(destination, &args.name)?;
+    Ok(())
+}
 
-    ui.success(&format!(
-        "Project '{}' initialized successfully.",
-        args.name
-    ));
+fn merge_template(
+    template: &Path,
+    destination: &Path,
+    force: bool,
+) -> Result<(usize, Vec<PathBuf>), CustomError> {
+    let mut copied = 0;
+    let mut skipped = Vec::new();
 
-    scopeguard::ScopeGuard::into_inner(cleanup_on_fail);
+    for entry in WalkDir::new(template).min_depth(1) {
+        let entry = entry.map_err(|e| CustomError::IoError(e.into()))?;
+        let path = entry.path();
+        let relative = path.strip_prefix(template).map_err(|_| {
+            CustomError::ValidationError(t!("Invalid template path: {}", path.display()))
+        })?;
+        let target = destination.join(relative);
 
-    Ok(())
+        if entry.file_type().is_dir() {
+            if target.exists() && !target.is_dir() {
+                return Err(CustomError::ValidationError(t!(
+                    "'{}' exists but is not a directory",
+                    target.display()
+                )));
+            }
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+
+        if target.exists() && !force {
+            skipped.push(relative.to_path_buf());
+            continue;
+        }
+
+        fs::copy(path, &target)?;
+        copied += 1;
+    }
+
+    Ok((copied, skipped))
 }
@@ -1,160 +1,536 @@
 use crate::Ui;
 use crate::error::CustomError;
-use crate::git::clone_repo_to_temp;
-use crate::manifest::update_manifest;
+use crate::git::{clone_repo_at_commit, clone_repo_to_temp, head_commit};
+use crate::i18n::{t, tr};
+use crate::lockfile::{LOCKFILE, LockedSystem, Lockfile, system_checksum};
+use crate::manifest::{set_system_entry, update_manifest};
+use crate::path_guard::{copy_dir_all, is_plain_name, report_skipped};
+use crate::staging::link_dir;
 use clap::Args;
+use colored::Colorize;
+use std::collections::BTreeMap;
 use std::fs;
-use std::io;
 use std::path::Path;
+use tempfile::TempDir;
 use toml_edit::DocumentMut;
 use url::Url;
 
 const SYSTEMS_DIR: &str = "bonsai/systems";
 const MANIFEST_FILE: &str = "bonsai.toml";
+const SYSTEM_MANIFEST: &str = "system.toml";
 
 #[derive(Args)]
 pub struct InstallArgs {
-    pub url: String,
+    /// Without one, installs the systems of bonsai.lock that are missing
+    #[arg(required_if_eq("link", "true"))]
+    pub url: Option<String>,
     #[arg(long, short, default_value = "latest")]
     pub version: String,
     #[arg(long, short)]
     pub name: Option<String>,
+    /// Treat <url> as a local system directory and link it instead of copying
+    #[arg(long, conflicts_with = "version")]
+    pub link: bool,
 }
 
 pub fn install(args: &InstallArgs, ui: Ui) -> Result<(), CustomError> {
     let project_manifest_path = Path::new(MANIFEST_FILE);
     if !project_manifest_path.exists() {
         return Err(CustomError::ValidationError(
-            "Bonsai.toml manifest not found. Are you in a bonsai project?".to_string(),
+            t!("Bonsai.toml manifest not found. Are you in a bonsai project?")
         ));
     }
 
-    let full_url = resolve_url(&args.url);
+    let Some(url) = &args.url else {
+        return install_locked(ui);
+    };
+
+    if args.link {
+        return install_linked(url, args, ui);
+    }
+
+    let full_url = resolve_url(url);
 
     let folder_name = match &args.name {
         Some(n) => n.clone(),
         None => extract_name_from_url(&full_url)?,
     };
 
-    if folder_name.contains('/') || folder_name.contains('\\') {
+    if !is_plain_name(&folder_name) {
         return Err(CustomError::ValidationError(
-            "Invalid system name.".to_string(),
+            t!("Invalid system name.")
         ));
     }
 
     let systems_dir = Path::new(SYSTEMS_DIR);
     if !systems_dir.exists() {
         return Err(CustomError::ValidationError(
-            "source/systems directory not found. Are you in a bonsai project?".to_string(),
+            t!("source/systems directory not found. Are you in a bonsai project?")
         ));
     }
 
     let target_path = systems_dir.join(&folder_name);
     if target_path.exists() {
-        return Err(CustomError::ValidationError(format!(
-            "System '{}' is already installed at {:?}",
-            folder_name, target_path
-        )));
+        return Err(CustomError::ValidationError(t!("System '{}' is already installed at {}", folder_name, format!("{:?}", target_path))));
     }
 
-    ui.status(&format!("Installing system '{}'...", folder_name));
+    ui.status(&t!("Installing system '{}'...", folder_name));
 
-    // 1. clone to temp cache
-    let temp_repo = clone_repo_to_temp(&full_url, &args.version, &ui)?;
-    let repo_path = temp_repo.path();
+    // 1. fetch the system and everything it depends on before touching the project
+    let mut resolver = Resolver::new(&ui)?;
+    resolver.resolve(&folder_name, &full_url, &args.version, None)?;
 
-    // 2. read manifest
-    let manifest_path = repo_path.join("bonsai.toml");
-    if !manifest_path.exists() {
-        return Err(CustomError::ValidationError(
-            "Not a valid Bonsai system".into(),
+    // 2. copy them in, dependencies first
+    let installed = resolver.install()?;
+    if installed.len() > 1 {
+        ui.log(&t!(
+            "Installed dependencies: {}.",
+            installed[..installed.len() - 1].join(", ")
         ));
     }
 
-    // 3. resolve dependencies recursively
-    let manifest_content = fs::read_to_string(&manifest_path)?;
-    let doc = manifest_content.parse::<DocumentMut>()?;
-    if let Some(deps) = doc.get("dependencies").and_then(|d| d.as_table()) {
-        for (dep_name, dep_value) in deps.iter() {
-            let ui_clone = ui.clone();
-            let dep_url = if let Some(table) = dep_value.as_table() {
-                table.get("git").and_then(|v| v.as_str())
-            } else {
-                None
-            };
+    ui.success(&t!("Installed {} successfully.", folder_name));
+    Ok(())
+}
 
-            if let Some(url) = dep_url {
-                ui_clone.status(&format!("Resolving dependency '{}'...", dep_name));
-                let dep_args = InstallArgs {
-                    url: url.to_string(),
-                    name: Some(dep_name.to_string()),
-                    version: "latest".to_string(),
-                };
-                install(&dep_args, ui_clone)?;
+/// Installs every system in bonsai.lock that is missing from bonsai/systems, at its locked commit.
+fn install_locked(ui: Ui) -> Result<(), CustomError> {
+    let lockfile = Lockfile::load()?;
+    if lockfile.systems.is_empty() {
+        return Err(CustomError::ValidationError(t!(
+            "Nothing to install: no system URL given and no {}",
+            LOCKFILE
+        )));
+    }
+
+    let mut installed = 0;
+    for (name, locked) in &lockfile.systems {
+        // dependencies of an earlier entry may have brought it in already
+        if Path::new(SYSTEMS_DIR).join(name).exists() {
+            continue;
+        }
+        let locked_args = InstallArgs {
+            url: Some(locked.url.clone()),
+            name: Some(name.clone()),
+            version: locked.version.clone(),
+            link: false,
+        };
+        install(&locked_args, ui.clone())?;
+        installed += 1;
+    }
+
+    ui.success(&t!(
+        "{} of {} locked systems installed, the rest were present.",
+        installed,
+        lockfile.systems.len()
+    ));
+    Ok(())
+}
+
+/// A `[dependencies]` entry: `name = "user/repo"` or `name = { git = "...", version = "v1" }`.
+pub struct Dependency {
+    pub name: String,
+    pub url: String,
+    pub version: String,
+}
+
+fn parse_dependencies(path: &Path) -> Result<Vec<Dependency>, CustomError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let doc = fs::read_to_string(path)?.parse::<DocumentMut>()?;
+    let Some(deps) = doc.get("dependencies").and_then(|d| d.as_table_like()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut dependencies = Vec::new();
+    for (name, value) in deps.iter() {
+        let (git, version) = if let Some(url) = value.as_str() {
+            (Some(url), None)
+        } else if let Some(table) = value.as_table_like() {
+            (
+                table.get("git").and_then(|v| v.as_str()),
+                table.get("version").and_then(|v| v.as_str()),
+            )
+        } else {
+            (None, None)
+        };
+        let Some(git) = git else {
+            return Err(CustomError::ValidationError(t!(
+                "Dependency '{}' in {} needs a git URL (name = \"user/repo\" or {{ git = \"...\" }})",
+                name,
+                path.display()
+            )));
+        };
+        if !is_plain_name(name) {
+            return Err(CustomError::ValidationError(t!(
+                "Invalid dependency name '{}' in {}",
+                name,
+                path.display()
+            )));
+        }
+        dependencies.push(Dependency {
+            name: name.to_string(),
+            url: resolve_url(git),
+            version: version.unwrap_or("latest").to_string(),
+        });
+    }
+    Ok(dependencies)
+}
+
+/// Dependencies a system declares: `[dependencies]` in its `system.toml`, then the ones in
+/// its repository's `bonsai.toml`, which is where older systems list them.
+pub fn declared_dependencies(
+    system_dir: &Path,
+    repo_dir: Option<&Path>,
+) -> Result<Vec<Dependency>, CustomError> {
+    let mut dependencies = parse_dependencies(&system_dir.join(SYSTEM_MANIFEST))?;
+    if let Some(repo_dir) = repo_dir {
+        for dependency in parse_dependencies(&repo_dir.join(MANIFEST_FILE))? {
+            if !dependencies.iter().any(|d| d.name == dependency.name) {
+                dependencies.push(dependency);
             }
         }
     }
+    Ok(dependencies)
+}
 
-    // 4. copy system to target dir
-    let source_system_path = repo_path.join("bonsai/systems").join(&folder_name);
+/// A system cloned for installation, at the commit that will be locked.
+struct FetchedSystem {
+    name: String,
+    url: String,
+    version: String,
+    commit: String,
+    locked: Option<LockedSystem>,
+    repo: TempDir,
+}
 
-    if !source_system_path.exists() {
-        return Err(CustomError::ValidationError(format!(
-            "The repository does not contain 'bonsai/systems/{}'. Structure mismatch.",
-            folder_name
-        )));
+// who asked for a system at which url and version, for conflict reports
+struct Request {
+    url: String,
+    version: String,
+    required_by: String,
+}
+
+fn spec_line(name: &str, url: &str, version: &str) -> String {
+    format!("{} = {{ git = \"{}\", version = \"{}\" }}", name, url, version)
+}
+
+/// Walks the dependency graph of the systems being installed, cloning each one once.
+/// Cycles and two requirements of the same system that disagree stop it before anything
+/// is copied into the project.
+struct Resolver<'a> {
+    ui: &'a Ui,
+    lockfile: Lockfile,
+    requests: BTreeMap<String, Request>,
+    // systems currently being resolved, to spot cycles
+    stack: Vec<String>,
+    // in install order, every system after its dependencies
+    fetched: Vec<FetchedSystem>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(ui: &'a Ui) -> Result<Self, CustomError> {
+        Ok(Self {
+            ui,
+            lockfile: Lockfile::load()?,
+            requests: BTreeMap::new(),
+            stack: Vec::new(),
+            fetched: Vec::new(),
+        })
+    }
+
+    fn conflict(&self, name: &str, existing: &Request, url: &str, version: &str, by: &str) -> CustomError {
+        CustomError::ValidationError(t!(
+            "Version conflict for system '{}':\n  - {}  ({})\n  + {}  ({})",
+            name,
+            spec_line(name, &existing.url, &existing.version),
+            existing.required_by,
+            spec_line(name, url, version),
+            by
+        ))
     }
 
-    ui.status("Copying system files...");
+    /// `required_by` is the system declaring the dependency, None for the one being installed.
+    fn resolve(
+        &mut self,
+        name: &str,
+        url: &str,
+        version: &str,
+        required_by: Option<&str>,
+    ) -> Result<(), CustomError> {
+        let by = match required_by {
+            Some(system) => t!("required by {}", system),
+            None => tr("requested").to_string(),
+        };
+
+        if let Some(start) = self.stack.iter().position(|n| n == name) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(CustomError::ValidationError(t!(
+                "Dependency cycle between systems: {}",
+                cycle.join(" -> ")
+            )));
+        }
+
+        if let Some(existing) = self.requests.get(name) {
+            if existing.url == url && existing.version == version {
+                return Ok(());
+            }
+            return Err(self.conflict(name, existing, url, version, &by));
+        }
+
+        // already in the project, e.g. installed earlier or linked for development
+        if Path::new(SYSTEMS_DIR).join(name).exists() {
+            if let Some(locked) = self.lockfile.systems.get(name)
+                && (locked.url != url || locked.version != version)
+            {
+                let installed = Request {
+                    url: locked.url.clone(),
+                    version: locked.version.clone(),
+                    required_by: t!("installed, {}", LOCKFILE),
+                };
+                return Err(self.conflict(name, &installed, url, version, &by));
+            }
+            self.requests.insert(
+                name.to_string(),
+                Request {
+                    url: url.to_string(),
+                    version: version.to_string(),
+                    required_by: tr("installed").to_string(),
+                },
+            );
+            return Ok(());
+        }
 
-    copy_dir_all(&source_system_path, &target_path).map_err(|e| CustomError::IoError(e))?;
+        self.requests.insert(
+            name.to_string(),
+            Request {
+                url: url.to_string(),
+                version: version.to_string(),
+                required_by: by,
+            },
+        );
+        if let Some(system) = required_by {
+            self.ui.status(&t!("Resolving dependency '{}' of '{}'...", name, system));
+        }
 
-    // 5. install utils (optional)
-    let source_utils_path = repo_path.join("utils");
-    if source_utils_path.exists() {
-        let project_utils_dir = Path::new("utils");
-        if !project_utils_dir.exists() {
-            fs::create_dir_all(project_utils_dir).map_err(|e| CustomError::IoError(e))?;
+        // clone to temp cache, at the locked commit when bonsai.lock pins this system
+        let locked = self
+            .lockfile
+            .systems
+            .get(name)
+            .filter(|l| l.url == url && l.version == version)
+            .cloned();
+        let repo = match &locked {
+            Some(locked) => clone_repo_at_commit(url, &locked.commit, self.ui)?,
+            None => clone_repo_to_temp(url, version, self.ui)?,
+        };
+        let repo_path = repo.path();
+        let commit = head_commit(repo_path).ok_or_else(|| {
+            CustomError::GitError(t!("Could not read the checked out commit of {}", url))
+        })?;
+
+        if !repo_path.join(MANIFEST_FILE).exists() {
+            return Err(CustomError::ValidationError(t!(
+                "Not a valid Bonsai system: {} has no bonsai.toml",
+                url
+            )));
+        }
+        let system_dir = repo_path.join(SYSTEMS_DIR).join(name);
+        if !system_dir.exists() {
+            return Err(CustomError::ValidationError(t!(
+                "The repository {} does not contain 'bonsai/systems/{}'. Structure mismatch.",
+                url, name
+            )));
         }
 
-        let target_utils_path = project_utils_dir.join(&folder_name);
+        self.stack.push(name.to_string());
+        for dependency in declared_dependencies(&system_dir, Some(repo_path))? {
+            self.resolve(&dependency.name, &dependency.url, &dependency.version, Some(name))?;
+        }
+        self.stack.pop();
+
+        self.fetched.push(FetchedSystem {
+            name: name.to_string(),
+            url: url.to_string(),
+            version: version.to_string(),
+            commit,
+            locked,
+            repo,
+        });
+        Ok(())
+    }
+
+    /// Copies every fetched system (and its utils) into the project and locks them.
+    /// Returns their names in install order.
+    fn install(self) -> Result<Vec<String>, CustomError> {
+        let ui = self.ui;
+        for system in &self.fetched {
+            let target_path = Path::new(SYSTEMS_DIR).join(&system.name);
+            ui.status(&t!("Copying system files of '{}'...", system.name));
+            let source_path = system.repo.path().join(SYSTEMS_DIR).join(&system.name);
+            let skipped = copy_dir_all(&source_path, &target_path).map_err(CustomError::IoError)?;
+            report_skipped(&target_path, &skipped, ui);
+
+            // install utils (optional)
+            let source_utils_path = system.repo.path().join("utils");
+            if source_utils_path.exists() {
+                let project_utils_dir = Path::new("utils");
+                if !project_utils_dir.exists() {
+                    fs::create_dir_all(project_utils_dir).map_err(CustomError::IoError)?;
+                }
+
+                ui.status(&t!(
+                    "Found utilities. Installing to 'utils/{}'",
+                    system.name
+                ));
+                let utils_target = project_utils_dir.join(&system.name);
+                let skipped = copy_dir_all(&source_utils_path, &utils_target).map_err(CustomError::IoError)?;
+                report_skipped(&utils_target, &skipped, ui);
+            }
+        }
+
+        ui.status(tr("Updating manifest..."));
+        update_manifest(Path::new("."), ui)?;
+
+        // after update_manifest, which may add a default system.toml
+        let mut lockfile = Lockfile::load()?;
+        for system in &self.fetched {
+            let entry = LockedSystem {
+                url: system.url.clone(),
+                version: system.version.clone(),
+                commit: system.commit.clone(),
+                checksum: system_checksum(&Path::new(SYSTEMS_DIR).join(&system.name))?,
+            };
+            if let Some(locked) = &system.locked
+                && locked.checksum != entry.checksum
+            {
+                ui.message(&t!(
+                    "{} System '{}' at commit {} does not match the checksum in {}.",
+                    "[WARNING]".yellow(),
+                    system.name,
+                    &entry.commit[..12.min(entry.commit.len())],
+                    LOCKFILE
+                ));
+            }
+            lockfile.systems.insert(system.name.clone(), entry);
+        }
+        lockfile.save()?;
+
+        Ok(self.fetched.into_iter().map(|s| s.name).collect())
+    }
+}
+
+/// Installs what the system at `system_dir` depends on, for systems that arrive without
+/// going through [`install`] (linked or updated ones).
+pub fn install_dependencies(
+    name: &str,
+    system_dir: &Path,
+    repo_dir: Option<&Path>,
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    let dependencies = declared_dependencies(system_dir, repo_dir)?;
+    if dependencies.is_empty() {
+        return Ok(());
+    }
+
+    let mut resolver = Resolver::new(ui)?;
+    // the system itself is in place already, a dependency pointing back at it is a cycle
+    resolver.stack.push(name.to_string());
+    for dependency in dependencies {
+        resolver.resolve(&dependency.name, &dependency.url, &dependency.version, Some(name))?;
+    }
+    let installed = resolver.install()?;
+    if !installed.is_empty() {
+        ui.log(&t!("Installed dependencies of '{}': {}.", name, installed.join(", ")));
+    }
+    Ok(())
+}
 
-        ui.status(&format!(
-            "Found utilities. Installing to 'utils/{}'",
-            folder_name
+/// Links a system checked out elsewhere (its repository root or the system directory itself)
+/// into `bonsai/systems`, so edits there show up in the next build without reinstalling.
+fn install_linked(url: &str, args: &InstallArgs, ui: Ui) -> Result<(), CustomError> {
+    let repo_path = Path::new(url);
+    if !repo_path.is_dir() {
+        return Err(CustomError::ValidationError(t!(
+            "System directory '{}' not found.",
+            url
+        )));
+    }
+
+    let folder_name = match &args.name {
+        Some(n) => n.clone(),
+        None => repo_path
+            .canonicalize()?
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                CustomError::ValidationError(t!("Could not determine system name"))
+            })?,
+    };
+
+    if !is_plain_name(&folder_name) {
+        return Err(CustomError::ValidationError(
+            t!("Invalid system name.")
         ));
+    }
+
+    // a systems repository keeps the package under bonsai/systems/<name>
+    let nested = repo_path.join(SYSTEMS_DIR).join(&folder_name);
+    let source_system_path = if nested.is_dir() {
+        nested
+    } else {
+        repo_path.to_path_buf()
+    };
 
-        copy_dir_all(&source_utils_path, &target_utils_path)
-            .map_err(|e| CustomError::IoError(e))?;
+    let target_path = Path::new(SYSTEMS_DIR).join(&folder_name);
+    if target_path.exists() {
+        return Err(CustomError::ValidationError(t!("System '{}' is already installed at {}", folder_name, format!("{:?}", target_path))));
     }
 
-    ui.status("Updating manifest...");
+    ui.status(&t!("Linking system '{}'...", folder_name));
+
+    install_dependencies(&folder_name, &source_system_path, Some(repo_path), &ui)?;
+
+    link_dir(&source_system_path, &target_path)?;
+
+    // keep the manifest path relative so the project can move with its checkouts
+    let manifest_path = source_system_path.to_string_lossy().replace('\\', "/");
+    set_system_entry(Path::new("."), &folder_name, &manifest_path, true)?;
+
+    ui.status(tr("Updating manifest..."));
     update_manifest(Path::new("."), &ui)?;
 
-    ui.success(&format!("Installed {} successfully.", folder_name));
+    ui.success(&t!(
+        "Linked {} from {} (editable).",
+        folder_name,
+        source_system_path.display()
+    ));
     Ok(())
 }
 
-fn extract_name_from_url(url_str: &str) -> Result<String, CustomError> {
+pub fn extract_name_from_url(url_str: &str) -> Result<String, CustomError> {
     if url_str.starts_with("git@") {
         let last_segment =
             url_str
                 .rsplit(|c| c == '/' || c == ':')
                 .next()
                 .ok_or(CustomError::ValidationError(
-                    "Invalid git SSH format".into(),
+                    t!("Invalid git SSH format")
                 ))?;
 
         if last_segment == url_str {
             return Err(CustomError::ValidationError(
-                "Git SSH URL must contain a separator (: or /)".into(),
+                t!("Git SSH URL must contain a separator (: or /)")
             ));
         }
 
         let name = last_segment.trim_end_matches(".git");
         if name.is_empty() {
             return Err(CustomError::ValidationError(
-                "Could not determine system name".into(),
+                t!("Could not determine system name")
             ));
         }
 
@@ -162,15 +538,15 @@ fn extract_name_from_url(url_str: &str) -> Result<String, CustomError> {
     }
 
     let url = Url::parse(url_str)
-        .map_err(|e| CustomError::ValidationError(format!("Invalid URL: {}", e)))?;
+        .map_err(|e| CustomError::ValidationError(t!("Invalid URL: {}", e)))?;
 
     let segments = url
         .path_segments()
-        .ok_or(CustomError::ValidationError("URL has no path".into()))?;
+        .ok_or(CustomError::ValidationError(t!("URL has no path")))?;
     let last = segments
         .filter(|s| !s.is_empty())
         .last()
-        .ok_or(CustomError::ValidationError("URL has no segments".into()))?;
+        .ok_or(CustomError::ValidationError(t!("URL has no segments")))?;
 
     let name = if last.ends_with(".git") {
         last.trim_end_matches(".git")
@@ -180,30 +556,14 @@ fn extract_name_from_url(url_str: &str) -> Result<String, CustomError> {
 
     if name.is_empty() {
         return Err(CustomError::ValidationError(
-            "Could not determine system name from URL".into(),
+            t!("Could not determine system name from URL")
         ));
     }
 
     Ok(name.to_string())
 }
 
-fn copy_dir_all(src: &Path, dst: &Path) -> io::Result<()> {
-    if !dst.exists() {
-        fs::create_dir_all(dst)?;
-    }
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-        if ty.is_dir() {
-            copy_dir_all(&entry.path(), &dst.join(entry.file_name()))?;
-        } else {
-            fs::copy(entry.path(), dst.join(entry.file_name()))?;
-        }
-    }
-    Ok(())
-}
-
-fn resolve_url(input: &str) -> String {
+pub fn resolve_url(input: &str) -> String {
     if input.starts_with("http") || input.starts_with("git@") {
         input.to_string()
     } else {
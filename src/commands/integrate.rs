@@ -0,0 +1,362 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::manifest::add_target_entries;
+use crate::paths::ProjectPaths;
+use crate::wsl::check_project_location;
+use clap::{Args, ValueEnum};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use ureq::Agent;
+use ureq::tls::{RootCerts, TlsConfig};
+use walkdir::WalkDir;
+
+const INTEGRATIONS_LIB_DIR: &str = "libs";
+const INTEGRATIONS_PACKAGE: &str = "integrations";
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Integration {
+    Steamworks,
+    Discord,
+}
+
+#[derive(Args)]
+pub struct IntegrateArgs {
+    pub sdk_name: Integration,
+    #[arg(default_value = ".")]
+    pub dir: String,
+    /// SDK zip file, extracted SDK directory or download URL
+    #[arg(long)]
+    pub sdk: Option<String>,
+    /// Accept the SDK license without prompting
+    #[arg(long)]
+    pub accept_license: bool,
+}
+
+struct RedistFile {
+    target: &'static str,
+    // path suffix inside the SDK archive
+    source: &'static str,
+    link: bool,
+    // only copied on hosts of this architecture, for SDKs that ship one library per architecture
+    arch: Option<&'static str>,
+}
+
+struct SdkInfo {
+    name: &'static str,
+    license_url: &'static str,
+    download_url: Option<&'static str>,
+    files: &'static [RedistFile],
+    stub: &'static str,
+}
+
+const STEAMWORKS: SdkInfo = SdkInfo {
+    name: "steamworks",
+    license_url: "https://partner.steamgames.com/documentation/sdk_access_agreement",
+    download_url: None,
+    files: &[
+        RedistFile {
+            target: "windows",
+            source: "redistributable_bin/win64/steam_api64.dll",
+            link: false,
+            arch: None,
+        },
+        RedistFile {
+            target: "windows",
+            source: "redistributable_bin/win64/steam_api64.lib",
+            link: true,
+            arch: None,
+        },
+        RedistFile {
+            target: "linux",
+            source: "redistributable_bin/linux64/libsteam_api.so",
+            link: true,
+            arch: None,
+        },
+        RedistFile {
+            target: "macos",
+            source: "redistributable_bin/osx/libsteam_api.dylib",
+            link: true,
+            arch: None,
+        },
+    ],
+    stub: r#"// NOTE: Machine generated by bonsai CLI. Minimal Steamworks loader, extend as needed.
+package steamworks
+
+when ODIN_OS == .Windows {
+	foreign import steam_api "system:steam_api64"
+} else {
+	foreign import steam_api "system:steam_api"
+}
+
+@(default_calling_convention = "c")
+foreign steam_api {
+	SteamAPI_InitFlat :: proc(err_msg: ^[1024]u8) -> i32 ---
+	SteamAPI_Shutdown :: proc() ---
+	SteamAPI_RunCallbacks :: proc() ---
+}
+
+// Returns false when Steam is not running or the app id is unknown.
+init :: proc() -> bool {
+	err_msg: [1024]u8
+	return SteamAPI_InitFlat(&err_msg) == 0
+}
+
+// Call once per frame.
+update :: proc() {
+	SteamAPI_RunCallbacks()
+}
+
+shutdown :: proc() {
+	SteamAPI_Shutdown()
+}
+"#,
+};
+
+const DISCORD: SdkInfo = SdkInfo {
+    name: "discord",
+    license_url: "https://discord.com/developers/docs/legal",
+    download_url: Some("https://dl-game-sdk.discordapp.net/3.2.1/discord_game_sdk.zip"),
+    files: &[
+        RedistFile {
+            target: "windows",
+            source: "lib/x86_64/discord_game_sdk.dll",
+            link: false,
+            arch: None,
+        },
+        RedistFile {
+            target: "windows",
+            source: "lib/x86_64/discord_game_sdk.dll.lib",
+            link: true,
+            arch: None,
+        },
+        RedistFile {
+            target: "linux",
+            source: "lib/x86_64/discord_game_sdk.so",
+            link: true,
+            arch: None,
+        },
+        RedistFile {
+            target: "macos",
+            source: "lib/x86_64/discord_game_sdk.dylib",
+            link: true,
+            arch: Some("x86_64"),
+        },
+        RedistFile {
+            target: "macos",
+            source: "lib/aarch64/discord_game_sdk.dylib",
+            link: true,
+            arch: Some("aarch64"),
+        },
+    ],
+    stub: r#"// NOTE: Machine generated by bonsai CLI. Minimal Discord Game SDK loader, extend as needed.
+package discord
+
+when ODIN_OS == .Windows {
+	foreign import game_sdk "system:discord_game_sdk.dll.lib"
+} else when ODIN_OS == .Darwin {
+	foreign import game_sdk "system:discord_game_sdk.dylib"
+} else {
+	foreign import game_sdk "system:discord_game_sdk.so"
+}
+
+DISCORD_VERSION :: 3
+
+@(default_calling_convention = "c")
+foreign game_sdk {
+	// params is a DiscordCreateParams, result receives an IDiscordCore
+	DiscordCreate :: proc(version: i32, params: rawptr, result: ^rawptr) -> i32 ---
+}
+
+core: rawptr
+
+// Returns false when the Discord client is not running.
+init :: proc(params: rawptr) -> bool {
+	return DiscordCreate(DISCORD_VERSION, params, &core) == 0
+}
+"#,
+};
+
+pub fn integrate(args: &IntegrateArgs, ui: Ui) -> Result<(), CustomError> {
+    let sdk = match args.sdk_name {
+        Integration::Steamworks => &STEAMWORKS,
+        Integration::Discord => &DISCORD,
+    };
+
+    let project_dir = Path::new(&args.dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    check_project_location(project_dir, &ui);
+
+    // local sdk paths are relative to where bonsai was invoked
+    let source = match (&args.sdk, sdk.download_url) {
+        (Some(s), _) if s.starts_with("http://") || s.starts_with("https://") => s.clone(),
+        (Some(s), _) => std::path::absolute(s)?.to_string_lossy().to_string(),
+        (None, Some(url)) => url.to_string(),
+        (None, None) => {
+            return Err(CustomError::ValidationError(t!(
+                "The {} SDK can't be downloaded automatically. Download it from the partner site and pass it with --sdk <zip or directory>",
+                sdk.name
+            )));
+        }
+    };
+
+    ui.message(&t!(
+        "The {} SDK is distributed under its own license: {}",
+        sdk.name, sdk.license_url
+    ));
+    if !args.accept_license && !ui.confirm(tr("Do you accept the SDK license?")) {
+        return Err(CustomError::ValidationError(
+            t!("SDK license not accepted")
+        ));
+    }
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    let temp = TempDir::new()?;
+    let sdk_root = fetch_sdk(&source, temp.path(), &ui)?;
+
+    let lib_root = Path::new(INTEGRATIONS_LIB_DIR).join(sdk.name);
+    let mut installed = 0;
+    for file in sdk.files {
+        if file.arch.is_some_and(|arch| arch != std::env::consts::ARCH) {
+            continue;
+        }
+        let Some(found) = find_in_sdk(&sdk_root, file.source) else {
+            ui.log(&t!("Skipping {} (not in this SDK).", file.source));
+            continue;
+        };
+
+        let dest_dir = lib_root.join(file.target);
+        fs::create_dir_all(&dest_dir)?;
+        let dest = dest_dir.join(found.file_name().unwrap_or_default());
+        fs::copy(&found, &dest)?;
+        installed += 1;
+
+        let dest_str = dest.to_string_lossy().replace('\\', "/");
+        let dir_str = dest_dir.to_string_lossy().replace('\\', "/");
+        let (search_flag, rpath) = match file.target {
+            "windows" => (format!("/LIBPATH:{}", dir_str), None),
+            "linux" => (format!("-L{}", dir_str), Some("-Wl,-rpath,'$ORIGIN'")),
+            _ => (
+                format!("-L{}", dir_str),
+                Some("-Wl,-rpath,@executable_path"),
+            ),
+        };
+
+        if file.link {
+            let mut flags = vec![search_flag];
+            flags.extend(rpath.map(str::to_string));
+            add_target_entries(Path::new("."), file.target, "linker_flags", &flags)?;
+        }
+        // everything except windows import libraries has to ship next to the binary
+        if !dest_str.ends_with(".lib") {
+            add_target_entries(Path::new("."), file.target, "runtime_libs", &[dest_str])?;
+        }
+    }
+
+    if installed == 0 {
+        return Err(CustomError::ValidationError(t!(
+            "No {} redistributables found in '{}'",
+            sdk.name, source
+        )));
+    }
+
+    let stub_dir = ProjectPaths::load()?
+        .game()
+        .join(INTEGRATIONS_PACKAGE)
+        .join(sdk.name);
+    let stub_path = stub_dir.join(format!("{}.odin", sdk.name));
+    if stub_path.exists() {
+        ui.log(&t!(
+            "Keeping existing loader at {}.",
+            stub_path.display()
+        ));
+    } else {
+        fs::create_dir_all(&stub_dir)?;
+        fs::write(&stub_path, sdk.stub)?;
+        ui.log(&t!("Generated loader at {}.", stub_path.display()));
+    }
+
+    ui.success(&t!(
+        "Integrated {} ({} files in {}).",
+        sdk.name,
+        installed,
+        lib_root.display()
+    ));
+    Ok(())
+}
+
+// returns the directory holding the extracted sdk
+fn fetch_sdk(source: &str, temp: &Path, ui: &Ui) -> Result<PathBuf, CustomError> {
+    let archive_path = if source.starts_with("http://") || source.starts_with("https://") {
+        let path = temp.join("sdk.zip");
+        download(source, &path, ui)?;
+        path
+    } else {
+        let path = PathBuf::from(source);
+        if path.is_dir() {
+            return Ok(path);
+        }
+        if !path.exists() {
+            return Err(CustomError::ValidationError(t!(
+                "SDK not found: {}",
+                path.display()
+            )));
+        }
+        path
+    };
+
+    ui.status(tr("Extracting SDK..."));
+    let extract_dir = temp.join("sdk");
+    let mut archive = zip::ZipArchive::new(File::open(&archive_path)?)
+        .map_err(|e| CustomError::ValidationError(t!("Invalid SDK archive: {}", e)))?;
+    archive
+        .extract(&extract_dir)
+        .map_err(|e| CustomError::ValidationError(t!("Failed to extract SDK: {}", e)))?;
+
+    Ok(extract_dir)
+}
+
+fn download(url: &str, dest: &Path, ui: &Ui) -> Result<(), CustomError> {
+    let agent = Agent::config_builder()
+        .tls_config(
+            TlsConfig::builder()
+                .root_certs(RootCerts::PlatformVerifier)
+                .build(),
+        )
+        .build()
+        .new_agent();
+
+    ui.status(&t!("Downloading {}...", url));
+    let response = agent
+        .get(url)
+        .header("User-Agent", "bonsai-cli")
+        .call()
+        .map_err(|e| CustomError::BuildError(t!("Failed to download SDK: {}", e)))?;
+
+    let mut reader = response.into_body().into_reader();
+    let mut file = File::create(dest)?;
+    io::copy(&mut reader, &mut file)?;
+    Ok(())
+}
+
+fn find_in_sdk(root: &Path, suffix: &str) -> Option<PathBuf> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .find(|p| p.to_string_lossy().replace('\\', "/").ends_with(suffix))
+}
@@ -0,0 +1,70 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::lint::{apply_fix, run_lints};
+use crate::manifest::load_manifest;
+use clap::Args;
+use colored::Colorize;
+use std::path::Path;
+
+#[derive(Args)]
+pub struct LintArgs {
+    #[arg(default_value = ".")]
+    pub dir: String,
+    #[arg(long)]
+    pub fix: bool,
+    #[arg(long)]
+    pub skip_systems: bool,
+}
+
+pub fn lint(args: &LintArgs, ui: Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(&args.dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    ui.status(tr("Linting project..."));
+    let manifest = load_manifest(Path::new("."))?;
+    let issues = run_lints(&manifest, !args.skip_systems);
+
+    let mut remaining = 0;
+    for issue in &issues {
+        match (&issue.fix, args.fix) {
+            (Some(fix), true) => {
+                apply_fix(fix, Path::new("bonsai.toml"))?;
+                println!("{} [{}] {}", tr("fixed").green(), issue.rule, issue.message);
+            }
+            (fix, _) => {
+                remaining += 1;
+                let hint = if fix.is_some() { tr(" (fixable with --fix)") } else { "" };
+                println!(
+                    "{} [{}] {}{}",
+                    tr("warning").yellow(),
+                    issue.rule,
+                    issue.message,
+                    hint
+                );
+            }
+        }
+    }
+
+    if remaining > 0 {
+        return Err(CustomError::ValidationError(t!(
+            "{} lint issue(s) found",
+            remaining
+        )));
+    }
+
+    ui.success(tr("No lint issues found."));
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::manifest::write_manifest;
+use crate::manifest_fix::fix_manifest;
+use crate::manifest_history::{ManifestChange, load_history, save_history};
+use clap::{Args, Subcommand};
+use colored::*;
+use std::fs;
+use std::path::Path;
+
+const MANIFEST_FILE: &str = "bonsai.toml";
+
+#[derive(Args)]
+pub struct ManifestArgs {
+    #[command(subcommand)]
+    pub command: ManifestCommand,
+    #[arg(long, global = true, default_value = ".")]
+    pub dir: String,
+}
+
+#[derive(Subcommand)]
+pub enum ManifestCommand {
+    /// Resolve merge conflicts and duplicate [systems] entries, then normalize [systems]
+    Fix {
+        /// Only report problems, exit with an error if the manifest needs fixing
+        #[arg(long)]
+        check: bool,
+    },
+    /// List the changes bonsai made to bonsai.toml, newest first
+    History {
+        /// How many changes to show
+        #[arg(long, short = 'n', default_value_t = 10)]
+        count: usize,
+    },
+    /// Revert the latest change bonsai made to bonsai.toml that isn't undone yet
+    Undo {
+        /// Revert even if bonsai.toml was edited after that change, losing the later edits
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+pub fn manifest(args: &ManifestArgs, ui: Ui) -> Result<(), CustomError> {
+    let manifest_path = Path::new(&args.dir).join(MANIFEST_FILE);
+    if !manifest_path.exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    match &args.command {
+        ManifestCommand::Fix { check } => fix(&manifest_path, *check, &ui),
+        ManifestCommand::History { count } => history(Path::new(&args.dir), *count, &ui),
+        ManifestCommand::Undo { force } => undo(Path::new(&args.dir), *force, &ui),
+    }
+}
+
+fn fix(manifest_path: &Path, check: bool, ui: &Ui) -> Result<(), CustomError> {
+    let original = fs::read_to_string(manifest_path)?;
+
+    let mut conflicts = 0;
+    let fixed = fix_manifest(&original, &mut |conflict| {
+        conflicts += 1;
+        if check {
+            return Ok(0);
+        }
+        let question = format!(
+            "{} System '{}' has diverging sources:",
+            "[WARNING]".yellow(),
+            conflict.name.bold()
+        );
+        ui.choose(&question, &conflict.candidates)
+            .ok_or(CustomError::Cancelled)
+    })?;
+
+    if check {
+        if fixed.text == original {
+            ui.success(tr("Manifest is clean."));
+            return Ok(());
+        }
+        if fixed.moved_atlas && fixed.conflict_markers == 0 && conflicts == 0 && fixed.duplicates.is_empty() {
+            return Err(CustomError::ValidationError(t!(
+                "Manifest needs fixing: [atlas] is deprecated and should be [assets.atlas]. Run 'bonsai manifest fix'"
+            )));
+        }
+        return Err(CustomError::ValidationError(t!(
+            "Manifest needs fixing: {} conflict hunk(s), {} duplicate and {} conflicting system(s). Run 'bonsai manifest fix'",
+            fixed.conflict_markers,
+            fixed.duplicates.len(),
+            conflicts
+        )));
+    }
+
+    if fixed.text == original {
+        ui.success(tr("Manifest is clean, nothing to fix."));
+        return Ok(());
+    }
+
+    let mut changes = Vec::new();
+    if fixed.conflict_markers > 0 {
+        changes.push(format!("Merged {} conflict hunk(s) in [systems]", fixed.conflict_markers));
+    }
+    changes.extend(fixed.duplicates.iter().map(|name| format!("Removed duplicate system entry '{}'", name)));
+    changes.extend(fixed.resolved.iter().map(|(name, value)| format!("Kept {} = {}", name, value)));
+    if fixed.moved_atlas {
+        changes.push("Moved [atlas] to [assets.atlas]".to_string());
+    }
+    let project_root = manifest_path.parent().unwrap_or(Path::new("."));
+    write_manifest(project_root, &original, &fixed.text, changes)?;
+
+    if fixed.conflict_markers > 0 {
+        ui.message(&t!(
+            "  ~ Merged {} conflict hunk(s) in [systems]",
+            fixed.conflict_markers
+        ));
+    }
+    for name in &fixed.duplicates {
+        ui.message(&t!("  - Removed duplicate system entry: '{}'", name));
+    }
+    for (name, value) in &fixed.resolved {
+        ui.message(&t!("  ~ Kept {} = {}", name, value));
+    }
+    if fixed.moved_atlas {
+        ui.message(tr("  ~ Moved [atlas] to [assets.atlas]"));
+    }
+    ui.success(if fixed.is_clean() {
+        tr("Normalized manifest formatting.")
+    } else {
+        tr("Fixed manifest.")
+    });
+    Ok(())
+}
+
+fn print_change(index: usize, change: &ManifestChange) {
+    let undone = if change.undone { tr(" (undone)") } else { "" };
+    println!(
+        "{} {}  {}{}",
+        format!("#{}", index).bold(),
+        change.time,
+        change.command.cyan(),
+        undone
+    );
+    for line in &change.changes {
+        println!("  {}", line);
+    }
+    println!("    {}", t!("at line {}:", change.line).dimmed());
+    for line in &change.before {
+        println!("    {}", format!("- {}", line).red());
+    }
+    for line in &change.after {
+        println!("    {}", format!("+ {}", line).green());
+    }
+}
+
+fn history(project_root: &Path, count: usize, ui: &Ui) -> Result<(), CustomError> {
+    let history = load_history(project_root);
+    if history.is_empty() {
+        ui.success(tr("bonsai hasn't changed the manifest yet."));
+        return Ok(());
+    }
+    for (index, change) in history.iter().enumerate().rev().take(count) {
+        print_change(index + 1, change);
+    }
+    Ok(())
+}
+
+fn undo(project_root: &Path, force: bool, ui: &Ui) -> Result<(), CustomError> {
+    let mut history = load_history(project_root);
+    let Some(index) = history.iter().rposition(|change| !change.undone) else {
+        return Err(CustomError::ValidationError(t!("No manifest change left to undo")));
+    };
+    let change = &history[index];
+
+    let manifest_path = project_root.join(MANIFEST_FILE);
+    let current = fs::read_to_string(&manifest_path)?;
+    if current != change.manifest_after && !force {
+        return Err(CustomError::ValidationError(t!(
+            "bonsai.toml was edited after the change from {} ({}). Run 'bonsai manifest undo --force' to restore the manifest from before it, losing the later edits",
+            change.time,
+            change.command
+        )));
+    }
+
+    fs::write(&manifest_path, &change.manifest_before)?;
+    print_change(index + 1, change);
+    ui.success(&t!("Reverted the change '{}' made at {}.", change.command, change.time));
+    history[index].undone = true;
+    save_history(project_root, &history)
+}
@@ -1,6 +1,31 @@
+pub mod assets_cmd;
+pub mod bench;
 pub mod build_cmd;
+pub mod builds;
+pub mod bundle;
+pub mod clean;
+pub mod config_cmd;
+pub mod deploy;
 pub mod docs;
+pub mod doctor;
+pub mod example;
+pub mod explain_build;
+pub mod generate_cmd;
+pub mod info;
 pub mod init;
 pub mod install;
+pub mod integrate;
+pub mod lint;
+pub mod manifest_cmd;
+pub mod provenance_cmd;
+pub mod release_cmd;
 pub mod remove;
 pub mod run;
+pub mod selftest;
+pub mod serve;
+pub mod shader;
+pub mod sokol_cmd;
+pub mod stats_cmd;
+pub mod sysinfo_cmd;
+pub mod test;
+pub mod update_cmd;
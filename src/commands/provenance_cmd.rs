@@ -0,0 +1,182 @@
+use crate::Ui;
+use crate::config::set_global_value;
+use crate::error::CustomError;
+use crate::hash::to_hex;
+use crate::i18n::{t, tr};
+use crate::provenance::{PROVENANCE_FILE, SIGNING_KEY_ENV, key_id, parse_public_key, verify_provenance};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use ed25519_dalek::SigningKey;
+use rand_core::OsRng;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub struct ProvenanceArgs {
+    #[command(subcommand)]
+    pub command: ProvenanceCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ProvenanceCommand {
+    /// Create an Ed25519 key pair for signing provenance
+    Keygen {
+        /// Where the private key goes, the public key is written next to it as <path>.pub
+        path: PathBuf,
+        /// Make it the signing key in ~/.bonsai/config.toml
+        #[arg(long)]
+        global: bool,
+        /// Overwrite an existing key
+        #[arg(long)]
+        force: bool,
+    },
+    /// Check a build directory against its provenance file
+    Verify {
+        dir: PathBuf,
+        /// Public key (hex, or a .pub file) the provenance must be signed with
+        #[arg(long)]
+        key: Option<String>,
+    },
+}
+
+pub fn provenance(args: &ProvenanceArgs, ui: Ui) -> Result<(), CustomError> {
+    match &args.command {
+        ProvenanceCommand::Keygen { path, global, force } => keygen(path, *global, *force, &ui),
+        ProvenanceCommand::Verify { dir, key } => verify(dir, key.as_deref(), &ui),
+    }
+}
+
+fn keygen(path: &Path, global: bool, force: bool, ui: &Ui) -> Result<(), CustomError> {
+    let public_path = PathBuf::from(format!("{}.pub", path.display()));
+    if !force && (path.exists() || public_path.exists()) {
+        return Err(CustomError::ValidationError(t!(
+            "{} already exists, pass --force to replace it",
+            path.display()
+        )));
+    }
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    let key = SigningKey::generate(&mut OsRng);
+    fs::write(path, format!("{}\n", to_hex(key.as_bytes())))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    let public = to_hex(key.verifying_key().as_bytes());
+    fs::write(&public_path, format!("{}\n", public))?;
+
+    if global {
+        let absolute = std::path::absolute(path)?;
+        set_global_value("provenance", "signing_key", &absolute.to_string_lossy())?;
+        ui.log(tr("Set [provenance] signing_key in ~/.bonsai/config.toml."));
+    }
+
+    ui.message(&t!("Public key: {}", public));
+    ui.message(&t!(
+        "  Share {} with whoever verifies builds. In CI, put the contents of {} in {} instead of the file.",
+        public_path.display(),
+        path.display(),
+        SIGNING_KEY_ENV
+    ));
+    ui.success(&t!("Wrote signing key to {}.", path.display()));
+    Ok(())
+}
+
+fn verify(dir: &Path, key: Option<&str>, ui: &Ui) -> Result<(), CustomError> {
+    let key = key.map(parse_public_key).transpose()?;
+    let verification = verify_provenance(dir, key.as_ref())?;
+
+    let predicate = &verification.statement["predicate"];
+    let parameters = &predicate["buildDefinition"]["externalParameters"];
+    ui.message(&format!(
+        "{} {} {} ({}, {})",
+        "[PROVENANCE]".cyan().bold(),
+        parameters["project"].as_str().unwrap_or("?"),
+        parameters["version"].as_str().unwrap_or("?"),
+        parameters["target"].as_str().unwrap_or("?"),
+        parameters["config"].as_str().unwrap_or("?"),
+    ));
+    ui.message(&t!(
+        "  builder: {}",
+        predicate["runDetails"]["builder"]["id"].as_str().unwrap_or("?")
+    ));
+    for dependency in predicate["buildDefinition"]["resolvedDependencies"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        if let (Some(uri), Some(commit)) = (
+            dependency["uri"].as_str(),
+            dependency["digest"]["gitCommit"].as_str(),
+        ) {
+            let dirty = dependency["annotations"]["dirty"].as_bool() == Some(true);
+            let label = match dependency["name"].as_str() {
+                Some(name) => format!("system {}", name),
+                None => "source".to_string(),
+            };
+            ui.message(&format!(
+                "  {}: {} @ {}{}",
+                label,
+                uri,
+                commit,
+                if dirty { " (uncommitted changes)" } else { "" }
+            ));
+        }
+    }
+
+    if verification.signatures.is_empty() {
+        ui.message(&t!("{} The provenance is not signed.", "[WARNING]".yellow()));
+    }
+    for (keyid, valid) in &verification.signatures {
+        let short = &keyid[..keyid.len().min(16)];
+        match valid {
+            Some(true) => ui.message(&t!("  {} signed by {}", ui.mark(true), short)),
+            Some(false) => ui.message(&t!("  {} bad signature from {}", ui.mark(false), short)),
+            None => ui.message(&t!(
+                "  - signed by {}, not checked{}",
+                short,
+                if key.is_none() { " (pass --key)" } else { " (different key)" }
+            )),
+        }
+    }
+    for name in &verification.mismatched {
+        ui.message(&t!("  {} {} does not match its digest", ui.mark(false), name));
+    }
+    for name in &verification.missing {
+        ui.message(&t!("  {} {} is missing", ui.mark(false), name));
+    }
+    for name in &verification.unlisted {
+        ui.message(&t!("  {} {} is not covered by the provenance", ui.mark(false), name));
+    }
+
+    if !verification.is_valid(key.is_some()) {
+        let reason = match &key {
+            Some(key)
+                if !verification
+                    .signatures
+                    .iter()
+                    .any(|(keyid, valid)| *keyid == key_id(key) && *valid == Some(true)) =>
+            {
+                "no valid signature from the given key"
+            }
+            _ => "the files don't match",
+        };
+        return Err(CustomError::ValidationError(t!(
+            "{} verification failed: {}",
+            dir.join(PROVENANCE_FILE).display(),
+            reason
+        )));
+    }
+
+    ui.success(&t!(
+        "{} files match the provenance{}.",
+        verification.statement["subject"].as_array().map_or(0, Vec::len),
+        if key.is_some() { " and its signature" } else { "" }
+    ));
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use crate::Ui;
+use crate::commands::docs::{DEFAULT_DOCS_DIR, DEFAULT_DOCS_TARGET, DocsArgs, docs};
+use crate::commands::test::{TestArgs, test};
+use crate::error::CustomError;
+use crate::freshness::{is_fresh, mark_fresh, settings_stamp};
+use crate::git::{commit_paths, create_tag, tag_exists, uncommitted_changes};
+use crate::i18n::{t, tr};
+use crate::itch::{deploy_targets, page_url, push_build, validate_itch_options};
+use crate::lock::ProjectLock;
+use crate::manifest::{Manifest, load_manifest};
+use crate::paths::ProjectPaths;
+use crate::profile::resolve_profile;
+use crate::release::{
+    build_recorded, package_build, release_changelog, release_config, release_dir, release_tag,
+    release_targets, validate_release_options, write_checksums,
+};
+use crate::tools::require_tool;
+use crate::wsl::check_project_location;
+use chrono::Local;
+use clap::Args;
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+// the docs are regenerated only when their sources or [docs] changed since this was written
+const DOCS_STAMP: &str = ".bonsai/cache/release/docs.stamp";
+const DOCS_EXTENSIONS: &[&str] = &["odin", "glsl", "vert", "frag"];
+// tests run in the config `bonsai test` uses by default
+const TEST_CONFIG: &str = "debug";
+
+#[derive(Args)]
+pub struct ReleaseArgs {
+    #[arg(default_value = ".")]
+    pub dir: String,
+    /// Test, build, package and generate the docs, but don't touch the changelog, git or deploys
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Release from a working tree with uncommitted changes
+    #[arg(long)]
+    pub allow_dirty: bool,
+    /// Skip `bonsai test`
+    #[arg(long)]
+    pub skip_tests: bool,
+    /// Skip the [release] deploys
+    #[arg(long)]
+    pub no_deploy: bool,
+    /// Wait for another bonsai build in this project to finish instead of failing
+    #[arg(long)]
+    pub wait: bool,
+}
+
+pub fn release(args: &ReleaseArgs, ui: Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(&args.dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    check_project_location(project_dir, &ui);
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    let manifest = load_manifest(Path::new("."))?;
+    validate_release_options(&manifest)?;
+    let options = &manifest.release;
+    let name = &manifest.project.name;
+    let version = &manifest.project.version;
+    let config = release_config(options);
+    let targets = release_targets(options);
+    let tag = release_tag(options, version);
+    let root = Path::new(".");
+
+    // everything that can fail without a build is checked before the first one
+    let changes = uncommitted_changes(root)?;
+    if !changes.is_empty() && !args.allow_dirty {
+        return Err(CustomError::ValidationError(t!(
+            "The working tree has uncommitted changes, commit or stash them first (or pass --allow-dirty):\n{}",
+            changes.join("\n")
+        )));
+    }
+    if let Some(tag) = &tag
+        && tag_exists(root, tag)
+    {
+        return Err(CustomError::ValidationError(t!(
+            "Tag {} already exists, bump [project] version first",
+            tag
+        )));
+    }
+    let changelog = match &options.changelog {
+        Some(path) => {
+            let date = Local::now().format("%Y-%m-%d").to_string();
+            Some((path.as_str(), release_changelog(Path::new(path), version, &date)?))
+        }
+        None => None,
+    };
+    if targets.iter().any(|t| t == "desktop") && resolve_profile(&manifest, config)?.link_assets {
+        return Err(CustomError::ValidationError(t!(
+            "[profile.{}] link_assets would package a link instead of the assets, release another config",
+            config
+        )));
+    }
+
+    let itch = if options.deploy.iter().any(|d| d == "itch") && !args.no_deploy {
+        let itch = manifest.deploy.itch.clone().ok_or_else(|| {
+            CustomError::ValidationError(t!(
+                "[release] deploy lists itch, but bonsai.toml has no [deploy.itch] section"
+            ))
+        })?;
+        validate_itch_options(&itch)?;
+        if let Some(target) = deploy_targets(&[], &itch).into_iter().find(|t| !targets.contains(t)) {
+            return Err(CustomError::ValidationError(t!(
+                "[deploy.itch] pushes {} builds, but [release] targets don't build it",
+                target
+            )));
+        }
+        Some(itch)
+    } else {
+        None
+    };
+    let butler = match &itch {
+        Some(_) if !args.dry_run => Some(require_tool(&manifest.tools, "butler")?),
+        _ => None,
+    };
+
+    if options.test.unwrap_or(true) && !args.skip_tests {
+        run_tests(&manifest, args.wait, &ui)?;
+    }
+
+    let _lock = ProjectLock::acquire(args.wait, &ui)?;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+
+    let mut builds = Vec::new();
+    for target in &targets {
+        let (output_dir, id) = build_recorded(target, config, &manifest, &paths, &ui)?;
+        builds.push((target.clone(), output_dir, id));
+    }
+
+    // packaging reads only the builds and the docs only the sources, so they run side by side
+    let release_dir = release_dir(&paths);
+    let with_docs = options.docs.unwrap_or(!manifest.docs.triggers.is_empty());
+    let (docs_dir, archives) = std::thread::scope(|scope| {
+        let docs = scope.spawn(|| {
+            if with_docs {
+                generate_docs(&manifest, &ui).map(Some)
+            } else {
+                Ok(None)
+            }
+        });
+        let archives: Result<Vec<PathBuf>, CustomError> = builds
+            .par_iter()
+            .map(|(target, output_dir, _)| {
+                let archive = release_dir.join(format!("{}-{}-{}.zip", name, version, target));
+                if package_build(output_dir, &archive)? {
+                    ui.log(&t!("Packaged {}.", archive.display()));
+                } else {
+                    ui.log(&t!("{} is up to date.", archive.display()));
+                }
+                Ok(archive)
+            })
+            .collect();
+        let docs = docs
+            .join()
+            .unwrap_or_else(|_| Err(CustomError::BuildError(t!("Docs generation crashed"))));
+        (docs, archives)
+    });
+    let docs_dir = docs_dir?;
+    let archives = archives?;
+    let checksums = write_checksums(&archives, &release_dir)?;
+    ui.log(&t!("Wrote {}.", checksums.display()));
+
+    if args.dry_run {
+        if let Some((path, _)) = &changelog {
+            ui.log(&t!("Would release the Unreleased section of {}.", path));
+        }
+        if let Some(tag) = &tag {
+            ui.log(&t!("Would tag the release commit as {}.", tag));
+        }
+        if let Some(itch) = &itch {
+            for (target, output_dir, id) in builds.iter().filter(|(t, _, _)| deploy_targets(&[], itch).contains(t)) {
+                push_build(None, itch, target, output_dir, id, version, &ui)?;
+            }
+        }
+        ui.success(&t!(
+            "Dry run finished, packages are in {}. Nothing was committed, tagged or pushed.",
+            release_dir.display()
+        ));
+        return Ok(());
+    }
+
+    let mut release_paths = Vec::new();
+    if let Some((path, text)) = &changelog {
+        fs::write(path, text)?;
+        release_paths.push(*path);
+    }
+    if let Some(docs_dir) = &docs_dir {
+        release_paths.push(docs_dir.as_str());
+    }
+    let message = format!("Release {}", version);
+    if !release_paths.is_empty() && commit_paths(root, &release_paths, &message)? {
+        ui.log(&t!("Committed {}.", release_paths.join(", ")));
+    }
+    if let Some(tag) = &tag {
+        create_tag(root, tag, &format!("{} {}", name, version))?;
+        ui.log(&t!("Tagged {}.", tag));
+    }
+
+    if let Some(itch) = &itch {
+        let pushed = deploy_targets(&[], itch);
+        for (target, output_dir, id) in builds.iter().filter(|(t, _, _)| pushed.contains(t)) {
+            push_build(butler.as_deref(), itch, target, output_dir, id, version, &ui)?;
+        }
+        ui.log(&t!("Deployed to {}.", page_url(&itch.project)));
+    }
+
+    ui.success(&t!("Released {} {}.", name, version));
+    Ok(())
+}
+
+// the smoke run and replays, then the golden images when there are any
+fn run_tests(manifest: &Manifest, wait: bool, ui: &Ui) -> Result<(), CustomError> {
+    let mut args = TestArgs {
+        dir: ".".to_string(),
+        config: TEST_CONFIG.to_string(),
+        replay: Vec::new(),
+        frames: None,
+        timeout: None,
+        golden: false,
+        update_golden: false,
+        wait,
+    };
+    ui.status(tr("Running tests..."));
+    test(&args, ui.clone())?;
+    if !manifest.test.golden_scenes.is_empty() {
+        args.golden = true;
+        test(&args, ui.clone())?;
+    }
+    Ok(())
+}
+
+// `bonsai docs` with the [docs] settings, skipped while no source changed. Returns the output directory.
+fn generate_docs(manifest: &Manifest, ui: &Ui) -> Result<String, CustomError> {
+    let source = manifest.docs.dir.as_deref().unwrap_or(DEFAULT_DOCS_DIR);
+    let target = manifest.docs.target.as_deref().unwrap_or(DEFAULT_DOCS_TARGET);
+
+    let mut inputs: Vec<PathBuf> = WalkDir::new(source)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| DOCS_EXTENSIONS.contains(&e))
+        })
+        .collect();
+    let settings = serde_json::to_string(&manifest.docs)
+        .map_err(|e| CustomError::BuildError(e.to_string()))?;
+    inputs.push(settings_stamp("docs", &settings)?);
+
+    let stamp = Path::new(DOCS_STAMP);
+    if is_fresh(&inputs, stamp)? && Path::new(target).is_dir() {
+        ui.log(tr("Docs are up to date."));
+        return Ok(target.to_string());
+    }
+
+    let args = DocsArgs {
+        dir: None,
+        target: None,
+        site: None,
+        trigger: None,
+    };
+    docs(&args, ui.clone())?;
+
+    if let Some(parent) = stamp.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(stamp, target)?;
+    mark_fresh(&inputs, stamp)?;
+    Ok(target.to_string())
+}
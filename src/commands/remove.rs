@@ -1,6 +1,10 @@
 use crate::Ui;
 use crate::error::CustomError;
-use crate::manifest::update_manifest;
+use crate::i18n::{t, tr};
+use crate::manifest::{System, load_manifest, remove_system_entry, update_manifest};
+use crate::lockfile::Lockfile;
+use crate::path_guard::is_plain_name;
+use crate::staging::unlink_dir;
 use clap::Args;
 use colored::*;
 use std::path::Path;
@@ -16,15 +20,30 @@ pub struct RemoveArgs {
 }
 
 pub fn remove(args: &RemoveArgs, ui: Ui) -> Result<(), CustomError> {
-    if args.name.contains('/') || args.name.contains('\\') {
-        return Err(CustomError::ValidationError("Invalid system name.".into()));
+    if !is_plain_name(&args.name) {
+        return Err(CustomError::ValidationError(t!("Invalid system name.")));
     }
 
     let systems_path = Path::new(SYSTEMS_DIR);
     let target_path = systems_path.join(&args.name);
 
+    let editable = matches!(
+        load_manifest(Path::new("."))?.systems.get(&args.name),
+        Some(System::Path { editable: true, .. })
+    );
+
+    // only the link goes, the linked checkout is the user's working copy
+    if editable {
+        ui.status(&t!("Unlinking system '{}'...", args.name));
+        unlink_dir(&target_path)?;
+        remove_system_entry(Path::new("."), &args.name)?;
+        update_manifest(Path::new("."), &ui)?;
+        ui.success(&t!("Unlinked system '{}'", args.name));
+        return Ok(());
+    }
+
     if !target_path.exists() {
-        return Err(CustomError::ValidationError(format!(
+        return Err(CustomError::ValidationError(t!(
             "System '{}' not found.",
             args.name
         )));
@@ -37,24 +56,29 @@ pub fn remove(args: &RemoveArgs, ui: Ui) -> Result<(), CustomError> {
             args.name.red().bold()
         );
         if !ui.confirm(&question) {
-            ui.error("Operation cancelled.");
+            ui.error(tr("Operation cancelled."));
             return Ok(());
         }
     }
 
-    ui.status(&format!("Removing system '{}'...", args.name));
+    ui.status(&t!("Removing system '{}'...", args.name));
     std::fs::remove_dir_all(&target_path)?;
 
     let utils_path = Path::new(UTILS_DIR);
     let utils_target_path = utils_path.join(&args.name);
 
     if utils_target_path.exists() {
-        ui.status(&format!("Removing system '{}' utility...", args.name));
+        ui.status(&t!("Removing system '{}' utility...", args.name));
         std::fs::remove_dir_all(&utils_target_path)?;
     }
 
     update_manifest(Path::new("."), &ui)?;
 
-    ui.success(&format!("Removed system '{}'", args.name));
+    let mut lockfile = Lockfile::load()?;
+    if lockfile.systems.remove(&args.name).is_some() {
+        lockfile.save()?;
+    }
+
+    ui.success(&t!("Removed system '{}'", args.name));
     Ok(())
 }
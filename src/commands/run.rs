@@ -1,24 +1,111 @@
-use crate::build::{build_desktop, build_web, clean_build};
+use crate::browser::{IsolatedBrowser, open_browser, open_isolated_browser};
+use crate::assets::SCENE_REGISTRY_FILE;
+use crate::build::{BuildResult, build_desktop, build_web, clean_build, clean_shaders, refresh_desktop_assets};
+use crate::cancel::on_interrupt;
 use crate::error::CustomError;
-use crate::ui::Ui;
+use crate::freshness::clear_cache;
+use crate::harness::HarnessOptions;
+use crate::host_config::CROSS_ORIGIN_HEADERS;
+use crate::i18n::{t, tr};
+use crate::lock::ProjectLock;
+use crate::manifest::{load_manifest, Manifest};
+use crate::memory_report::{MEMORY_REPORT_SCRIPT, MEMORY_REPORT_URL, MemoryReport};
+use crate::paths::ProjectPaths;
+use crate::renderdoc::{capture_session, find_renderdoc, list_captures};
+use crate::sysinfo::write_crash_log;
+use crate::watch::WatchRules;
+use crate::ui::{ErrorLog, Ui};
+use crate::wsl::check_project_location;
 use clap::Args;
 use colored::Colorize;
-use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{DebounceEventResult, Debouncer, new_debouncer};
 use rouille::Server;
 use std::net::TcpListener;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Child, Command, ExitStatus, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{mpsc, Arc};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::{Instant, Duration, SystemTime};
 use crate::packer::{pack_atlas, pack_font};
 use crate::assets::parse_font_stem;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::Read;
 
-const ASSETS_DIR: &str = "assets";
 const ATLAS_DIR: &str = "bonsai/core/render/atlas";
 const FONT_DIR: &str = ".bonsai/cache/fonts";
+const DEFAULT_PORT: u16 = 8080;
+const SYSTEMS_DIR: &str = "bonsai/systems";
+// written next to the desktop binary, which loads its assets from `assets/` beside it
+const RELOAD_FILE_NAME: &str = ".bonsai_reload";
+const RELOAD_FILE_ENV: &str = "BONSAI_RELOAD_FILE";
+// forwards console output and uncaught errors to the hot reload websocket, {port} is replaced
+const CONSOLE_BRIDGE_SCRIPT: &str = r#"<script>
+(function () {
+  var queue = [], ws;
+  function format(args) {
+    return Array.prototype.map.call(args, function (arg) {
+      if (arg instanceof Error) return arg.stack || String(arg);
+      if (typeof arg === "object") { try { return JSON.stringify(arg); } catch (e) {} }
+      return String(arg);
+    }).join(" ");
+  }
+  function send(level, args) {
+    var payload = JSON.stringify({ level: level, message: format(args) });
+    if (ws && ws.readyState === 1) ws.send(payload);
+    else if (queue.length < 500) queue.push(payload);
+  }
+  ws = new WebSocket((location.protocol === "https:" ? "wss://" : "ws://") + location.hostname + ":{port}");
+  ws.onopen = function () { while (queue.length) ws.send(queue.shift()); };
+  ["log", "info", "warn", "error", "debug"].forEach(function (level) {
+    var original = console[level];
+    console[level] = function () { send(level, arguments); original.apply(console, arguments); };
+  });
+  window.addEventListener("error", function (e) {
+    send("error", [e.error || (e.message + " (" + e.filename + ":" + e.lineno + ")")]);
+  });
+  window.addEventListener("unhandledrejection", function (e) {
+    send("error", ["Unhandled rejection: ", e.reason]);
+  });
+})();
+</script>
+"#;
+
+// polled by the error overlay while `run --web --watch` serves the build
+const BUILD_STATUS_URL: &str = "/__bonsai/build";
+// covers the page while the last web rebuild is failing and reloads it once one succeeds,
+// {generation} is the build the page was served from
+const ERROR_OVERLAY_SCRIPT: &str = r#"<script>
+(function () {
+  var generation = {generation}, overlay;
+  function show(message) {
+    if (!overlay) {
+      overlay = document.createElement("div");
+      overlay.style.cssText = "position:fixed;inset:0;z-index:2147483647;overflow:auto;margin:0;padding:24px;" +
+        "background:rgba(24,24,24,0.96);color:#f0f0f0;font:13px/1.5 monospace;white-space:pre-wrap";
+      document.documentElement.appendChild(overlay);
+    }
+    overlay.innerHTML = "<div style=\"color:#ff6b6b;font-weight:bold;margin-bottom:12px\">Build failed</div>" +
+      "<div style=\"color:#999;margin-bottom:16px\">Waiting for the next successful build to reload...</div>";
+    overlay.appendChild(document.createTextNode(message));
+  }
+  function poll() {
+    fetch("{url}", { cache: "no-store" }).then(function (response) {
+      return response.json();
+    }).then(function (status) {
+      if (status.error) show(status.error);
+      else if (status.generation !== generation) location.reload();
+      else if (overlay) { overlay.remove(); overlay = null; }
+    }).catch(function () {}).then(function () { setTimeout(poll, 1000); });
+  }
+  poll();
+})();
+</script>
+"#;
+// served for index.html until the first web build succeeds, the overlay says why it hasn't
+const NO_BUILD_PAGE: &str = "<!DOCTYPE html><html><head><meta charset=\"utf-8\"></head><body></body></html>";
 
 #[derive(Args)]
 pub struct RunArgs {
@@ -32,14 +119,46 @@ pub struct RunArgs {
     pub config: String,
     #[arg(long)]
     pub clean: bool,
-    #[arg(long, short = 'p', default_value_t = 8080)]
-    pub port: u16,
+    /// Forget the content hashes in .bonsai/cache, so only modification times count until outputs are rebuilt
+    #[arg(long)]
+    pub clean_cache: bool,
+    /// Recompile every shader even when its outputs are newer than its sources and includes
+    #[arg(long)]
+    pub force_shaders: bool,
+    #[arg(long, short = 'p')]
+    pub port: Option<u16>,
+    #[arg(long, value_name = "NAME|PATH")]
+    pub browser: Option<String>,
+    /// Open the page in a fresh temporary browser profile with the devtools open
+    #[arg(long, requires = "web")]
+    pub browser_profile: bool,
+    #[arg(long)]
+    pub watch: bool,
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["replay", "web"])]
+    pub record: Option<PathBuf>,
+    #[arg(long, value_name = "FILE", conflicts_with = "web")]
+    pub replay: Option<PathBuf>,
+    /// Don't print the browser console in the terminal
+    #[arg(long)]
+    pub no_console_bridge: bool,
+    /// Report wasm heap growth and peak memory when the web server stops
+    #[arg(long, requires = "web")]
+    pub memory_report: bool,
+    /// Wait for another bonsai build in this project to finish instead of failing
+    #[arg(long)]
+    pub wait: bool,
+    /// Launch the desktop build through RenderDoc
+    #[arg(long, conflicts_with = "web")]
+    pub capture: bool,
+    /// Frame the game captures on its own (default: capture on F12/PrintScreen)
+    #[arg(long, value_name = "N", requires = "capture")]
+    pub capture_frame: Option<u32>,
 }
 
 pub fn run(args: &RunArgs, ui: Ui) -> Result<(), CustomError> {
     let project_dir = Path::new(&args.dir);
     if !project_dir.exists() {
-        return Err(CustomError::ValidationError(format!(
+        return Err(CustomError::ValidationError(t!(
             "Directory '{}' does not exist",
             args.dir
         )));
@@ -47,12 +166,30 @@ pub fn run(args: &RunArgs, ui: Ui) -> Result<(), CustomError> {
 
     let manifest_path = project_dir.join("bonsai.toml");
     if !manifest_path.exists() {
-        return Err(CustomError::ValidationError(format!(
+        return Err(CustomError::ValidationError(t!(
             "Not a bonsai project directory: '{}'. (Missing bonsai.toml)",
             args.dir
         )));
     }
 
+    check_project_location(project_dir, &ui);
+
+    // record/replay paths are relative to where bonsai was invoked
+    let harness = HarnessOptions {
+        record: args.record.as_deref().map(std::path::absolute).transpose()?,
+        replay: args.replay.as_deref().map(std::path::absolute).transpose()?,
+        capture_frame: args.capture_frame,
+        ..Default::default()
+    };
+    if let Some(replay) = &harness.replay
+        && !replay.exists()
+    {
+        return Err(CustomError::ValidationError(t!(
+            "Replay file not found: {}",
+            replay.display()
+        )));
+    }
+
     let current_dir = std::env::current_dir()?;
     std::env::set_current_dir(project_dir)?;
 
@@ -60,39 +197,137 @@ pub fn run(args: &RunArgs, ui: Ui) -> Result<(), CustomError> {
         let _ = std::env::set_current_dir(&dir);
     });
 
-    ui.status(&format!("Running project in: {}...", project_dir.display()));
+    ui.status(&t!("Running project in: {}...", project_dir.display()));
 
+    if args.clean_cache {
+        let _lock = ProjectLock::acquire(args.wait, &ui)?;
+        clear_cache(&ui)?;
+    }
     if args.clean {
+        let _lock = ProjectLock::acquire(args.wait, &ui)?;
         clean_build(&ui)?;
+    } else if args.force_shaders {
+        let _lock = ProjectLock::acquire(args.wait, &ui)?;
+        clean_shaders(&ui)?;
     }
 
-    let ws_port = args.port + 1;
-    let watch_dir = project_dir.join(ASSETS_DIR);
-    spawn_hot_reloader(&ui, ws_port, watch_dir, args.web);
+    let manifest = load_manifest(Path::new("."))?;
+    let port = args.port.or(manifest.web.port).unwrap_or(DEFAULT_PORT);
+
+    let ws_port = port + 1;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let rules = WatchRules::new(&manifest.watch, &paths)?;
+    spawn_hot_reloader(
+        &ui,
+        ws_port,
+        paths.clone(),
+        rules,
+        &manifest,
+        args.web,
+        args.watch,
+    );
 
     if args.web {
-        run_web(args, &ui)?;
+        run_web(args, port, &paths.build_target("web"), &ui)?;
     } else {
-        run_desktop(args, &ui)?;
+        run_desktop(args, &harness, &ui)?;
     }
 
     Ok(())
 }
 
-fn spawn_hot_reloader(ui: &Ui, ws_port: u16, target_dir: PathBuf, is_web: bool) {
+// game data files are sent as they are: copied next to the desktop binary or pushed over the websocket
+fn data_reload_path(assets_root: &Path, path: &Path) -> Option<PathBuf> {
+    path.strip_prefix(assets_root).ok().map(Path::to_path_buf)
+}
+
+fn sync_desktop_data(
+    assets_root: &Path,
+    desktop_dir: &Path,
+    changed: &BTreeSet<PathBuf>,
+    sequence: u64,
+) -> std::io::Result<()> {
+    for relative in changed {
+        let src = assets_root.join(relative);
+        let dest = desktop_dir.join("assets").join(relative);
+
+        // with link_assets the build dir already points at the sources
+        if dest.canonicalize().ok() == src.canonicalize().ok() && src.exists() {
+            continue;
+        }
+
+        if src.is_file() {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&src, &dest)?;
+        } else if !src.exists() && dest.is_file() {
+            fs::remove_file(&dest)?;
+        }
+    }
+
+    // touch-file protocol: first line is a sequence number, then one changed asset path per line
+    let mut content = format!("{}\n", sequence);
+    for relative in changed {
+        content.push_str(&format!("assets/{}\n", relative.to_string_lossy().replace('\\', "/")));
+    }
+    fs::write(desktop_dir.join(RELOAD_FILE_NAME), content)
+}
+
+fn data_packet(assets_root: &Path, relative: &Path) -> Vec<u8> {
+    let name = format!("assets/{}", relative.to_string_lossy().replace('\\', "/"));
+    let data = fs::read(assets_root.join(relative)).unwrap_or_default();
+
+    let mut ws_binary = Vec::new();
+    ws_binary.push(2); // packet id
+
+    ws_binary.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    ws_binary.extend_from_slice(name.as_bytes());
+    ws_binary.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    ws_binary.extend_from_slice(&data);
+    ws_binary
+}
+
+// atlas pages after the first, which travels with the sprite metadata in packet 0
+fn atlas_page_packet(page: usize, png: &[u8]) -> Vec<u8> {
+    let mut ws_binary = Vec::new();
+    ws_binary.push(3); // packet id
+
+    ws_binary.extend_from_slice(&(page as u32).to_le_bytes());
+    ws_binary.extend_from_slice(png);
+    ws_binary
+}
+
+fn spawn_hot_reloader(
+    ui: &Ui,
+    ws_port: u16,
+    paths: ProjectPaths,
+    rules: WatchRules,
+    manifest: &Manifest,
+    is_web: bool,
+    watch_data: bool,
+) {
+    let atlas_options = manifest.assets.atlas.clone();
+    let tools = manifest.tools.clone();
+    // baked fonts end up in generated code, only a rebuild picks their changes up
+    let baked_fonts: BTreeSet<String> = manifest.assets.fonts.keys().cloned().collect();
+    let target_dir = paths.assets.clone();
+    let desktop_dir = paths.build_target("desktop");
     if !target_dir.exists() {
-        ui.error(&format!("Watch directory missing: {}", target_dir.display()));
+        ui.error(&t!("Watch directory missing: {}", target_dir.display()));
         return;
     }
 
     let ui_clone = ui.clone();
     let ui_ws_clone = ui.clone();
+    // desktop --watch rebuilds and restarts the game for everything but data
+    let repack = is_web || !watch_data;
 
     let (tx, rx) = mpsc::channel::<Vec<u8>>();
 
     thread::spawn(move || {
         let (debounce_tx, debounce_rx) = mpsc::channel();
-        let mut debouncer = new_debouncer(Duration::from_millis(200), debounce_tx)
+        let mut debouncer = new_debouncer(rules.debounce(), debounce_tx)
             .expect("Failed to create file watcher");
 
         debouncer
@@ -100,104 +335,144 @@ fn spawn_hot_reloader(ui: &Ui, ws_port: u16, target_dir: PathBuf, is_web: bool)
             .watch(&target_dir, RecursiveMode::Recursive)
             .expect("Failed to watch target directory");
 
-        ui_clone.status("Hot reloader waiting for asset changes...");
+        ui_clone.status(tr("Hot reloader waiting for asset changes..."));
 
         let mut last_repack_time_atlas = Instant::now() - Duration::from_secs(10);
         let mut last_repack_time_font = Instant::now() - Duration::from_secs(10);
         let cooldown_duration = Duration::from_millis(1000);
 
         let mut known_mod_times: HashMap<PathBuf, SystemTime> = HashMap::new();
+        let assets_root = target_dir.canonicalize().unwrap_or(target_dir.clone());
+        let mut data_sequence: u64 = 0;
+
+        while let Some(paths) = rules.next_batch(&debounce_rx) {
+            let mut should_repack_atlas = false;
+            let mut should_repack_font = false;
+            let mut changed_font_path = None;
+            let mut changed_data = BTreeSet::new();
+
+            for path in paths {
+                if path.components().any(|c| c.as_os_str() == ".bonsai") {
+                    continue;
+                }
 
-        for result in debounce_rx {
-            if let Ok(events) = result {
-                let mut should_repack_atlas = false;
-                let mut should_repack_font = false;
-                let mut changed_font_path = None;
+                let stages = rules.stages_for(&path);
+                let has_stage = |stage: &str| stages.iter().any(|s| s == stage);
 
-                for event in events {
-                    if event.path.components().any(|c| c.as_os_str() == ".bonsai") {
-                        continue;
-                    }
+                if watch_data
+                    && has_stage("data")
+                    && let Some(relative) = data_reload_path(&assets_root, &path)
+                {
+                    changed_data.insert(relative);
+                    continue;
+                }
 
-                    if let Some(ext) = event.path.extension() {
-                        if ext == "png" {
-                            should_repack_atlas = true;
-                        } else if ext == "ttf" || ext == "otf" {
-                            should_repack_font = true;
-                            changed_font_path = Some(event.path.clone());
-                        }
+                if !repack {
+                    continue;
+                }
+                if has_stage("sprites") {
+                    should_repack_atlas = true;
+                }
+                if has_stage("fonts")
+                    && path
+                        .extension()
+                        .is_some_and(|ext| ext == "ttf" || ext == "otf")
+                {
+                    should_repack_font = true;
+                    changed_font_path = Some(path.clone());
+                }
+            }
+
+            if !changed_data.is_empty() {
+                data_sequence += 1;
+                if is_web {
+                    for relative in &changed_data {
+                        let _ = tx.send(data_packet(&assets_root, relative));
                     }
+                } else if let Err(e) =
+                    sync_desktop_data(&assets_root, &desktop_dir, &changed_data, data_sequence)
+                {
+                    ui_clone.error(&t!("Data reload failed: {}", e));
                 }
+                ui_clone.log(&t!("Reloaded {} data file(s).", changed_data.len()));
+            }
 
-                if should_repack_atlas {
-                    if last_repack_time_atlas.elapsed() >= cooldown_duration {
-                        last_repack_time_atlas = Instant::now();
+            if should_repack_atlas && last_repack_time_atlas.elapsed() >= cooldown_duration {
+                last_repack_time_atlas = Instant::now();
 
-                        ui_clone.status("Repacking atlas...");
-                        let atlas_output_dir = Path::new(ATLAS_DIR);
+                ui_clone.status(tr("Repacking atlas..."));
+                let atlas_output_dir = Path::new(ATLAS_DIR);
 
-                        match pack_atlas(&target_dir, &atlas_output_dir, &ui_clone) {
-                            Ok(Some(payload)) => {
-                                let mut ws_binary = Vec::new();
+                match pack_atlas(&target_dir, atlas_output_dir, &atlas_options, &tools, &ui_clone) {
+                    Ok(Some(payload)) => {
+                        let mut ws_binary = Vec::new();
 
-                                ws_binary.push(0);
+                        ws_binary.push(0);
 
-                                let bin_len = payload.metadata_bin.len() as u32;
+                        let bin_len = payload.metadata_bin.len() as u32;
 
-                                ws_binary.extend_from_slice(&bin_len.to_le_bytes());
-                                ws_binary.extend_from_slice(&payload.metadata_bin);
-                                ws_binary.extend_from_slice(&payload.png_bytes);
+                        ws_binary.extend_from_slice(&bin_len.to_le_bytes());
+                        ws_binary.extend_from_slice(&payload.metadata_bin);
+                        ws_binary.extend_from_slice(&payload.png_bytes);
 
-                                let _ = tx.send(ws_binary);
-                            }
-                            Ok(None) => {}
-                            Err(e) => ui_clone.error(&format!("Packer failed: {}", e)),
+                        let _ = tx.send(ws_binary);
+                        for (index, png) in payload.extra_pages.iter().enumerate() {
+                            let _ = tx.send(atlas_page_packet(index + 1, png));
                         }
                     }
-                } 
-                if should_repack_font {
-                    if let Some(font_path) = changed_font_path {
-                        if let Ok(meta) = std::fs::metadata(&font_path) {
-                            if let Ok(mtime) = meta.modified() {
-                                if known_mod_times.get(&font_path) == Some(&mtime) {
-                                    continue;
-                                }
-                                known_mod_times.insert(font_path.clone(), mtime);
-                            }
-                        }
-                        if last_repack_time_font.elapsed() >= cooldown_duration {
-                            last_repack_time_font = Instant::now();
-
-                            let raw_stem = font_path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown");
-                            let clean_stem = raw_stem.replace("-", "_").replace(" ", "_");
-                            let (final_name, native_size_opt) = parse_font_stem(&clean_stem);
-                            let is_pixel = native_size_opt.is_some();
-                            let native_size = native_size_opt.unwrap_or(0);
+                    Ok(None) => {}
+                    Err(e) => ui_clone.error(&t!("Packer failed: {}", e)),
+                }
+            }
+            if should_repack_font
+                && let Some(font_path) = changed_font_path
+            {
+                if let Ok(meta) = std::fs::metadata(&font_path)
+                    && let Ok(mtime) = meta.modified()
+                {
+                    if known_mod_times.get(&font_path) == Some(&mtime) {
+                        continue;
+                    }
+                    known_mod_times.insert(font_path.clone(), mtime);
+                }
+                if last_repack_time_font.elapsed() >= cooldown_duration {
+                    last_repack_time_font = Instant::now();
+
+                    let raw_stem = font_path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown");
+                    let clean_stem = raw_stem.replace("-", "_").replace(" ", "_");
+                    let (final_name, native_size_opt) = parse_font_stem(&clean_stem);
+                    let is_pixel = native_size_opt.is_some();
+                    let native_size = native_size_opt.unwrap_or(0);
+                    if baked_fonts.contains(&final_name) {
+                        ui_clone.log(&t!(
+                            "Font {} is baked by [assets.fonts], rebuild to see the change.",
+                            final_name
+                        ));
+                        continue;
+                    }
 
-                            ui_clone.status(&format!("Repacking font: {:?}...", font_path.file_name().unwrap()));
-                            let font_output_dir = Path::new(FONT_DIR);
+                    ui_clone.status(&t!("Repacking font: {}...", format!("{:?}", font_path.file_name().unwrap())));
+                    let font_output_dir = Path::new(FONT_DIR);
 
-                            match pack_font(&font_path, &final_name, is_pixel, native_size, &font_output_dir, &ui_clone) {
-                                Ok(Some(payload)) => {
-                                    let mut ws_binary = Vec::new();
+                    match pack_font(&font_path, &final_name, is_pixel, native_size, font_output_dir, &ui_clone) {
+                        Ok(Some(payload)) => {
+                            let mut ws_binary = Vec::new();
 
-                                    ws_binary.push(1); // packet id
+                            ws_binary.push(1); // packet id
 
-                                    let name_bytes = final_name.as_bytes();
-                                    ws_binary.push(name_bytes.len() as u8);
-                                    ws_binary.extend_from_slice(name_bytes);
+                            let name_bytes = final_name.as_bytes();
+                            ws_binary.push(name_bytes.len() as u8);
+                            ws_binary.extend_from_slice(name_bytes);
 
-                                    let bin_len = payload.metadata_bin.len() as u32;
-                                    ws_binary.extend_from_slice(&bin_len.to_le_bytes());
-                                    ws_binary.extend_from_slice(&payload.metadata_bin);
-                                    ws_binary.extend_from_slice(&payload.png_bytes);
+                            let bin_len = payload.metadata_bin.len() as u32;
+                            ws_binary.extend_from_slice(&bin_len.to_le_bytes());
+                            ws_binary.extend_from_slice(&payload.metadata_bin);
+                            ws_binary.extend_from_slice(&payload.png_bytes);
 
-                                    let _ = tx.send(ws_binary);
-                                }
-                                Ok(None) => {}
-                                Err(e) => ui_clone.error(&format!("font packing failed: {}", e)),
-                            }
+                            let _ = tx.send(ws_binary);
                         }
+                        Ok(None) => {}
+                        Err(e) => ui_clone.error(&t!("font packing failed: {}", e)),
                     }
                 }
             }
@@ -211,7 +486,7 @@ fn spawn_hot_reloader(ui: &Ui, ws_port: u16, target_dir: PathBuf, is_web: bool)
 
             server.set_nonblocking(true).expect("Cannot set non-blocking");
 
-            ui_ws_clone.message(&format!(
+            ui_ws_clone.message(&t!(
                 "{} WebSocket hot-reload server running at ws://localhost:{}",
                 "[INFO]".green(),
                 ws_port,
@@ -223,6 +498,8 @@ fn spawn_hot_reloader(ui: &Ui, ws_port: u16, target_dir: PathBuf, is_web: bool)
                 if let Ok((stream, _)) = server.accept() {
                     stream.set_nonblocking(false).unwrap();
                     if let Ok(ws) = tungstenite::accept(stream) {
+                        // non-blocking from here on so console messages can be polled
+                        let _ = ws.get_ref().set_nonblocking(true);
                         clients.push(ws);
                     }
                 }
@@ -230,118 +507,643 @@ fn spawn_hot_reloader(ui: &Ui, ws_port: u16, target_dir: PathBuf, is_web: bool)
                 if let Ok(payload) = rx.try_recv() {
                     clients.retain_mut(|client| {
                         let msg = tungstenite::Message::Binary(payload.clone().into());
-                        client.send(msg).is_ok()
+                        !is_fatal_ws_error(client.send(msg))
                     });
                 }
 
+                clients.retain_mut(|client| {
+                    if is_fatal_ws_error(client.flush()) {
+                        return false;
+                    }
+                    loop {
+                        match client.read() {
+                            Ok(tungstenite::Message::Text(text)) => {
+                                print_browser_console(&text, &ui_ws_clone)
+                            }
+                            Ok(tungstenite::Message::Close(_)) => return false,
+                            Ok(_) => {}
+                            Err(e) => return !is_fatal_ws_error(Err(e)),
+                        }
+                    }
+                });
+
                 thread::sleep(Duration::from_millis(16));
             }
         });
     }
 }
 
-fn run_desktop(args: &RunArgs, ui: &Ui) -> Result<(), CustomError> {
-    ui.status("Building for desktop...");
+// a full send buffer on a non-blocking socket is not a disconnect
+fn is_fatal_ws_error(result: Result<(), tungstenite::Error>) -> bool {
+    match result {
+        Ok(()) => false,
+        Err(tungstenite::Error::Io(e)) => e.kind() != std::io::ErrorKind::WouldBlock,
+        Err(_) => true,
+    }
+}
 
-    let build_result = build_desktop(&args.config, false, ui)?;
+fn print_browser_console(text: &str, ui: &Ui) {
+    let Ok(entry) = serde_json::from_str::<serde_json::Value>(text) else {
+        return;
+    };
+    let level = entry["level"].as_str().unwrap_or("log");
+    let message = entry["message"].as_str().unwrap_or_default();
+
+    let prefix = match level {
+        "error" => "[BROWSER]".red().bold(),
+        "warn" => "[BROWSER]".yellow().bold(),
+        _ => "[BROWSER]".magenta().bold(),
+    };
+    for line in message.lines() {
+        ui.message(&format!("{} {}", prefix, line));
+    }
+}
+
+// the desktop game process, with the RenderDoc capture directory when launched through it
+struct GameSession {
+    child: Child,
+    executable: PathBuf,
+    capture_dir: Option<PathBuf>,
+}
+
+fn build_for_run(args: &RunArgs, ui: &Ui) -> Result<BuildResult, CustomError> {
+    ui.status(tr("Building for desktop..."));
+
+    // the lock only covers the build, the game itself may run alongside other builds
+    let build_result = {
+        let _lock = ProjectLock::acquire(args.wait, ui)?;
+        build_desktop(&args.config, false, ui)?
+    };
 
     if !build_result.executable_path.exists() {
-        return Err(CustomError::BuildError(format!(
+        return Err(CustomError::BuildError(t!(
             "Executable not found at: {}",
             build_result.executable_path.display()
         )));
     }
+    Ok(build_result)
+}
+
+// reruns only the font and audio work for `changed`, false when a full build is needed
+fn refresh_for_run(args: &RunArgs, build_result: &BuildResult, changed: &[PathBuf], ui: &Ui) -> bool {
+    let started = Instant::now();
+    let refreshed = ProjectLock::acquire(args.wait, ui).and_then(|_lock| {
+        refresh_desktop_assets(&args.config, &build_result.executable_path, changed, ui)
+    });
+    match refreshed {
+        Ok(true) => {
+            ui.log(&t!("Refreshed assets in {} ms.", started.elapsed().as_millis()));
+            true
+        }
+        Ok(false) => false,
+        Err(e) => {
+            ui.error(&e.to_string());
+            false
+        }
+    }
+}
 
-    ui.success("Running desktop build...");
+fn launch_desktop(
+    args: &RunArgs,
+    harness: &HarnessOptions,
+    build_result: &BuildResult,
+    renderdoc: Option<&Path>,
+    ui: &Ui,
+) -> Result<GameSession, CustomError> {
+    ui.success(tr("Running desktop build..."));
     println!("");
 
-    let mut child = Command::new(&build_result.executable_path)
+    // renderdoccmd passes its environment on to the game, so the harness variables still apply
+    let capture = match renderdoc {
+        Some(renderdoccmd) => {
+            let executable = std::path::absolute(&build_result.executable_path)?;
+            Some(capture_session(renderdoccmd, &executable)?)
+        }
+        None => None,
+    };
+    let capture_dir = capture.as_ref().map(|c| c.capture_dir.clone());
+
+    let mut command = match capture {
+        Some(session) => {
+            ui.log(match args.capture_frame {
+                Some(_) => "Launching through RenderDoc, the game triggers the capture itself.",
+                None => "Launching through RenderDoc, press F12 or PrintScreen to capture a frame.",
+            });
+            session.command
+        }
+        None => Command::new(&build_result.executable_path),
+    };
+    harness.apply(&mut command);
+    if args.watch {
+        let desktop_dir = build_result.executable_path.parent().unwrap_or(Path::new("."));
+        let reload_file = std::path::absolute(desktop_dir.join(RELOAD_FILE_NAME))?;
+        let _ = fs::remove_file(&reload_file);
+        command.env(RELOAD_FILE_ENV, reload_file);
+    }
+
+    let child = command
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
         .spawn()
-        .map_err(|e| CustomError::ProcessError(format!("Failed to start game: {}", e)))?;
+        .map_err(|e| CustomError::ProcessError(t!("Failed to start game: {}", e)))?;
 
-    let status = child
-        .wait()
-        .map_err(|e| CustomError::ProcessError(format!("Failed to wait for process: {}", e)))?;
+    Ok(GameSession {
+        child,
+        executable: build_result.executable_path.clone(),
+        capture_dir,
+    })
+}
 
+fn finish_session(session: GameSession, status: ExitStatus, ui: &Ui) {
     if !status.success() {
-        ui.error(&format!("Game exited with code: {}", status));
+        ui.error(&t!("Game exited with code: {}", status));
+        match write_crash_log(&session.executable, &status) {
+            Ok(path) => ui.log(&t!("Crash log written to {}", path.display())),
+            Err(e) => ui.error(&t!("Failed to write crash log: {}", e)),
+        }
     }
 
-    Ok(())
+    if let Some(capture_dir) = &session.capture_dir {
+        report_captures(capture_dir, ui);
+    }
 }
 
-fn run_web(args: &RunArgs, ui: &Ui) -> Result<(), CustomError> {
-    ui.status("Building for web...");
+fn report_captures(capture_dir: &Path, ui: &Ui) {
+    let captures = list_captures(capture_dir);
+    if captures.is_empty() {
+        ui.log(tr("No frame was captured."));
+        let _ = fs::remove_dir(capture_dir);
+    }
+    for capture in captures {
+        println!("{}", t!("Capture saved: {}", capture.display()));
+    }
+}
 
-    build_web(&args.config, false, ui)?;
+fn run_desktop(args: &RunArgs, harness: &HarnessOptions, ui: &Ui) -> Result<(), CustomError> {
+    let renderdoc = args.capture.then(find_renderdoc).transpose()?;
 
-    ui.status("Starting web server...");
+    if args.watch {
+        return run_desktop_watched(args, harness, renderdoc.as_deref(), ui);
+    }
 
-    let port = args.port;
-    thread::spawn(move || {
-        thread::sleep(Duration::from_millis(500));
-        let _ = open_browser(port);
-    });
+    let build_result = build_for_run(args, ui)?;
+    let mut session = launch_desktop(args, harness, &build_result, renderdoc.as_deref(), ui)?;
 
-    serve_web_directory(Path::new("build/web"), args.port, ui)?;
+    let status = session
+        .child
+        .wait()
+        .map_err(|e| CustomError::ProcessError(t!("Failed to wait for process: {}", e)))?;
+    finish_session(session, status, ui);
 
     Ok(())
 }
 
-fn open_browser(port: u16) -> Result<(), CustomError> {
-    let url = format!("http://localhost:{}", port);
+// code, shaders and assets that need a rebuild; data files are hot reloaded instead
+fn needs_restart(path: &Path, rules: &WatchRules) -> bool {
+    if path.components().any(|c| c.as_os_str() == ".bonsai") {
+        return false;
+    }
 
-    #[cfg(target_os = "windows")]
-    Command::new("cmd").args(["/C", "start", &url]).spawn()?;
+    // written by the build itself
+    let is_odin = path.extension().is_some_and(|ext| ext == "odin");
+    if is_odin && path.with_extension("glsl").exists()
+        || path.file_name().is_some_and(|name| name == SCENE_REGISTRY_FILE)
+    {
+        return false;
+    }
 
-    #[cfg(target_os = "macos")]
-    Command::new("open").arg(&url).spawn()?;
+    is_odin || rules.stages_for(path).iter().any(|stage| stage != "data")
+}
 
-    #[cfg(target_os = "linux")]
-    Command::new("xdg-open").arg(&url).spawn()?;
+// atlas and font changes are hot reloaded into the page, anything else that needs a
+// desktop restart needs a web rebuild
+fn needs_web_rebuild(path: &Path, rules: &WatchRules) -> bool {
+    needs_restart(path, rules)
+        && !rules
+            .stages_for(path)
+            .iter()
+            .any(|stage| stage == "sprites" || stage == "fonts")
+}
 
-    // std::thread::sleep(std::time::Duration::from_secs(1));
+// source, shaders, assets and installed systems; dropping the debouncer stops watching
+fn watch_project(
+    paths: &ProjectPaths,
+    rules: &WatchRules,
+) -> Result<
+    (
+        Debouncer<RecommendedWatcher>,
+        mpsc::Receiver<DebounceEventResult>,
+    ),
+    CustomError,
+> {
+    let (tx, rx) = mpsc::channel();
+    let mut debouncer = new_debouncer(rules.debounce(), tx)
+        .map_err(|e| CustomError::ProcessError(t!("Failed to create file watcher: {}", e)))?;
+    let mut watched: Vec<PathBuf> = Vec::new();
+    for dir in [
+        paths.source.clone(),
+        paths.shaders.clone(),
+        paths.assets.clone(),
+        PathBuf::from(SYSTEMS_DIR),
+    ] {
+        if !dir.exists() || watched.iter().any(|w| dir.starts_with(w)) {
+            continue;
+        }
+        debouncer
+            .watcher()
+            .watch(&dir, RecursiveMode::Recursive)
+            .map_err(|e| {
+                CustomError::ProcessError(t!("Failed to watch {}: {}", dir.display(), e))
+            })?;
+        watched.push(dir);
+    }
+    Ok((debouncer, rx))
+}
 
-    Ok(())
+// drops events the build itself caused, once the debouncer has delivered them
+fn drain_events(rx: &mpsc::Receiver<DebounceEventResult>, rules: &WatchRules) {
+    drain_changes(rx, rules);
 }
 
-fn serve_web_directory(web_dir: &Path, port: u16, ui: &Ui) -> Result<(), CustomError> {
+// like drain_events, keeping the paths that need a restart
+fn drain_changes(rx: &mpsc::Receiver<DebounceEventResult>, rules: &WatchRules) -> Vec<PathBuf> {
+    thread::sleep(rules.debounce() + Duration::from_millis(50));
+    let mut changed = Vec::new();
+    while let Ok(result) = rx.try_recv() {
+        if let Ok(events) = result {
+            changed.extend(
+                events
+                    .into_iter()
+                    .map(|e| e.path)
+                    .filter(|path| needs_restart(path, rules)),
+            );
+        }
+    }
+    changed
+}
+
+// fonts and sounds are read by the running game, editing them doesn't need a recompile
+fn is_asset_refresh(path: &Path, rules: &WatchRules) -> bool {
+    let stages = rules.stages_for(path);
+    !stages.is_empty() && stages.iter().all(|stage| stage == "fonts" || stage == "audio")
+}
+
+/// Rebuilds and restarts the desktop game whenever code, shaders or assets change.
+/// A failed build keeps watching, the next change tries again.
+fn run_desktop_watched(
+    args: &RunArgs,
+    harness: &HarnessOptions,
+    renderdoc: Option<&Path>,
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    let manifest = load_manifest(Path::new("."))?;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let rules = WatchRules::new(&manifest.watch, &paths)?;
+    let (_debouncer, rx) = watch_project(&paths, &rules)?;
+
+    let mut build_result: Option<BuildResult> = None;
+    // set when only fonts and audio changed since the last launch
+    let mut asset_changes: Vec<PathBuf> = Vec::new();
+    loop {
+        let refreshed = match &build_result {
+            Some(result) if !asset_changes.is_empty() => refresh_for_run(args, result, &asset_changes, ui),
+            _ => false,
+        };
+        if !refreshed {
+            build_result = match build_for_run(args, ui) {
+                Ok(result) => Some(result),
+                Err(e) => {
+                    ui.error(&e.to_string());
+                    None
+                }
+            };
+        }
+        let mut game = match &build_result {
+            Some(result) => Some(launch_desktop(args, harness, result, renderdoc, ui)?),
+            None => None,
+        };
+        drain_events(&rx, &rules);
+        if game.is_none() {
+            ui.status(tr("Waiting for changes to rebuild..."));
+        }
+
+        // until something relevant changes; the game may exit on its own in the meantime
+        let mut changed = loop {
+            if let Some(session) = &mut game
+                && let Some(status) = session.child.try_wait()?
+            {
+                if let Some(session) = game.take() {
+                    finish_session(session, status, ui);
+                }
+                ui.status(tr("Game exited, waiting for changes to rebuild..."));
+            }
+
+            match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(Ok(events)) => {
+                    let changed: Vec<PathBuf> = events
+                        .into_iter()
+                        .map(|e| e.path)
+                        .filter(|path| needs_restart(path, &rules))
+                        .collect();
+                    if !changed.is_empty() {
+                        break changed;
+                    }
+                }
+                Ok(Err(_)) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        };
+        changed.extend(drain_changes(&rx, &rules));
+
+        ui.log(&t!("{} changed, rebuilding...", paths.logical(&changed[0])));
+        asset_changes = if changed.iter().all(|path| is_asset_refresh(path, &rules)) {
+            changed
+        } else {
+            Vec::new()
+        };
+        // killed by us, not a crash, so no crash log
+        if let Some(mut session) = game {
+            let _ = session.child.kill();
+            let _ = session.child.wait();
+            if let Some(capture_dir) = &session.capture_dir {
+                report_captures(capture_dir, ui);
+            }
+        }
+    }
+}
+
+/// Outcome of the latest watch-mode web build, polled by the error overlay.
+#[derive(Default)]
+pub struct WebBuildStatus {
+    // (successful builds so far, errors of the last build when it failed)
+    state: Mutex<(u64, Option<String>)>,
+}
+
+impl WebBuildStatus {
+    fn update(&self, error: Option<String>) {
+        if let Ok(mut state) = self.state.lock() {
+            if error.is_none() {
+                state.0 += 1;
+            }
+            state.1 = error;
+        }
+    }
+
+    fn snapshot(&self) -> (u64, Option<String>) {
+        self.state.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+}
+
+// one watch-mode web build; its errors go to `status` for the overlay instead of ending the run
+fn rebuild_web(config: &str, wait: bool, status: &WebBuildStatus, ui: &Ui) -> bool {
+    let errors = ErrorLog::default();
+    let build_ui = ui.capturing_errors(errors.clone());
+    let result = ProjectLock::acquire(wait, &build_ui)
+        .and_then(|_lock| build_web(config, false, &build_ui));
+
+    match result {
+        Ok(()) => {
+            status.update(None);
+            true
+        }
+        Err(e) => {
+            ui.error(&e.to_string());
+            let mut lines = errors.lock().map(|l| l.clone()).unwrap_or_default();
+            lines.push(e.to_string());
+            status.update(Some(lines.join("\n")));
+            false
+        }
+    }
+}
+
+/// Rebuilds the web target whenever code, shaders or non hot-reloadable assets change.
+fn watch_web_rebuilds(
+    config: &str,
+    wait: bool,
+    status: &WebBuildStatus,
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    let manifest = load_manifest(Path::new("."))?;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let rules = WatchRules::new(&manifest.watch, &paths)?;
+    let (_debouncer, rx) = watch_project(&paths, &rules)?;
+
+    loop {
+        let changed = match rx.recv() {
+            Ok(Ok(events)) => events
+                .iter()
+                .find(|e| needs_web_rebuild(&e.path, &rules))
+                .map(|e| e.path.clone()),
+            Ok(Err(_)) => None,
+            Err(_) => return Ok(()),
+        };
+        let Some(changed) = changed else {
+            continue;
+        };
+        drain_events(&rx, &rules);
+
+        ui.log(&t!("{} changed, rebuilding...", paths.logical(&changed)));
+        if rebuild_web(config, wait, status, ui) {
+            ui.success(tr("Web build updated, reloading the page..."));
+        } else {
+            ui.status(tr("Build errors are shown in the browser, waiting for changes..."));
+        }
+        drain_events(&rx, &rules);
+    }
+}
+
+fn run_web(args: &RunArgs, port: u16, web_dir: &Path, ui: &Ui) -> Result<(), CustomError> {
+    ui.status(tr("Building for web..."));
+
+    let build_status = args.watch.then(|| Arc::new(WebBuildStatus::default()));
+    match &build_status {
+        // a failing first build still serves the page, the overlay shows why
+        Some(status) => {
+            rebuild_web(&args.config, args.wait, status, ui);
+            fs::create_dir_all(web_dir)?;
+
+            let (config, wait, status, ui) =
+                (args.config.clone(), args.wait, status.clone(), ui.clone());
+            thread::spawn(move || {
+                if let Err(e) = watch_web_rebuilds(&config, wait, &status, &ui) {
+                    ui.error(&t!("Web rebuilds stopped: {}", e));
+                }
+            });
+        }
+        None => {
+            let _lock = ProjectLock::acquire(args.wait, ui)?;
+            build_web(&args.config, false, ui)?;
+        }
+    }
+
+    ui.status(tr("Starting web server..."));
+
+    let browser = args.browser.clone();
+    let browser_profile = args.browser_profile;
+    let isolated: Arc<Mutex<Option<IsolatedBrowser>>> = Arc::default();
+    let isolated_slot = isolated.clone();
+    let ui_browser = ui.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(500));
+        let url = format!("http://localhost:{}", port);
+        if browser_profile {
+            match open_isolated_browser(&url, browser.as_deref(), &ui_browser) {
+                Ok(session) => {
+                    if let Ok(mut slot) = isolated_slot.lock() {
+                        *slot = Some(session);
+                    }
+                    return;
+                }
+                Err(e) => ui_browser.message(&t!(
+                    "{} {}. Opening the page in the usual browser profile.",
+                    "[WARNING]".yellow(),
+                    e
+                )),
+            }
+        }
+        open_browser(&url, browser.as_deref(), &ui_browser);
+    });
+
+    let dev = DevServerOptions {
+        console_bridge: (!args.no_console_bridge).then_some(port + 1),
+        build_status,
+        memory_report: args.memory_report.then(|| Arc::new(MemoryReport::default())),
+    };
+    let served = serve_web_directory(web_dir, "0.0.0.0", port, None, dev, ui);
+
+    // the profile can only be deleted once the browser using it is gone
+    if let Some(session) = isolated.lock().ok().and_then(|mut slot| slot.take()) {
+        let program = session.program.clone();
+        session.close();
+        ui.log(&t!(
+            "Closed {} and deleted its temporary profile.",
+            program.display()
+        ));
+    }
+    served
+}
+
+/// TLS certificate and private key, both PEM encoded.
+pub struct TlsFiles {
+    pub certificate: Vec<u8>,
+    pub private_key: Vec<u8>,
+}
+
+/// What `run --web` adds on top of serving the build, none of it is used by `bonsai serve`.
+#[derive(Default)]
+pub struct DevServerOptions {
+    // websocket port the console bridge sends to
+    pub console_bridge: Option<u16>,
+    pub build_status: Option<Arc<WebBuildStatus>>,
+    pub memory_report: Option<Arc<MemoryReport>>,
+}
+
+pub fn serve_web_directory(
+    web_dir: &Path,
+    host: &str,
+    port: u16,
+    tls: Option<TlsFiles>,
+    dev: DevServerOptions,
+    ui: &Ui,
+) -> Result<(), CustomError> {
     if !web_dir.exists() {
-        return Err(CustomError::ValidationError(format!(
+        return Err(CustomError::ValidationError(t!(
             "Web build does not exist: {}",
             web_dir.display()
         )));
     }
 
-    let addr = format!("0.0.0.0:{}", port);
+    let addr = format!("{}:{}", host, port);
+    let scheme = if tls.is_some() { "https" } else { "http" };
     let root = web_dir.to_path_buf();
+    let DevServerOptions {
+        console_bridge,
+        build_status,
+        memory_report,
+    } = dev;
+    let report = memory_report.clone();
 
     let shutdown = Arc::new(AtomicBool::new(false));
     let shutdown_clone = shutdown.clone();
     let ui_clone = ui.clone();
 
-    ctrlc::set_handler(move || {
+    on_interrupt(move || {
         shutdown_clone.store(true, Ordering::SeqCst);
-        ui_clone.error("Received Ctrl+C. Shutting down server...");
-    })
-    .map_err(|e| CustomError::ProcessError(format!("Failed to set Ctrl+C handler: {}", e)))?;
-
-    ui.message(&format!(
-        "{} Serving web build at http://localhost:{}.",
+        ui_clone.error(tr("Received Ctrl+C. Shutting down server..."));
+    })?;
+
+    // a wildcard address isn't something a browser can open
+    let shown_host = match host {
+        "0.0.0.0" | "::" => "localhost",
+        _ => host,
+    };
+    ui.message(&t!(
+        "{} Serving web build at {}://{}:{}.",
         "[INFO]".green(),
+        scheme,
+        shown_host,
         port
     ));
-    ui.message("  (CTRL+C to stop the server)");
+    ui.message(tr("  (CTRL+C to stop the server)"));
 
-    ui.status("Server running...");
+    ui.status(tr("Server running..."));
 
-    let server = Server::new(&addr, move |request| {
+    let handler = move |request: &rouille::Request| {
         let url = request.url();
+        if url == BUILD_STATUS_URL
+            && let Some(status) = &build_status
+        {
+            let (generation, error) = status.snapshot();
+            return rouille::Response::json(&serde_json::json!({
+                "generation": generation,
+                "error": error,
+            }))
+            .with_additional_header("Cache-Control", "no-store");
+        }
+        if url == MEMORY_REPORT_URL
+            && let Some(report) = &report
+        {
+            let mut body = String::new();
+            if let Some(mut data) = request.data() {
+                let _ = data.read_to_string(&mut body);
+            }
+            return if report.record(&body) {
+                rouille::Response::empty_204()
+            } else {
+                rouille::Response::empty_400()
+            };
+        }
+
         let mut response = rouille::match_assets(request, &root);
 
-        if !response.is_success() && url == "/" {
+        let is_index = url == "/" || url == "/index.html";
+        let mut scripts = String::new();
+        if let Some(ws_port) = console_bridge {
+            scripts.push_str(&CONSOLE_BRIDGE_SCRIPT.replace("{port}", &ws_port.to_string()));
+        }
+        if report.is_some() {
+            scripts.push_str(&MEMORY_REPORT_SCRIPT.replace("{url}", MEMORY_REPORT_URL));
+        }
+        if let Some(status) = &build_status {
+            scripts.push_str(
+                &ERROR_OVERLAY_SCRIPT
+                    .replace("{generation}", &status.snapshot().0.to_string())
+                    .replace("{url}", BUILD_STATUS_URL),
+            );
+        }
+
+        if is_index && !scripts.is_empty() {
+            let html = fs::read_to_string(root.join("index.html"))
+                .ok()
+                .or_else(|| build_status.is_some().then(|| NO_BUILD_PAGE.to_string()));
+            if let Some(html) = html {
+                let html = match html.find("</head>") {
+                    Some(index) => format!("{}{}{}", &html[..index], scripts, &html[index..]),
+                    None => format!("{}{}", scripts, html),
+                };
+                response = rouille::Response::html(html);
+            }
+        } else if !response.is_success() && url == "/" {
             let index_path = root.join("index.html");
             if let Ok(file) = std::fs::File::open(index_path) {
                 response = rouille::Response::from_file("text/html", file);
@@ -349,20 +1151,30 @@ fn serve_web_directory(web_dir: &Path, port: u16, ui: &Ui) -> Result<(), CustomE
         }
 
         if response.is_success() {
+            for (name, value) in CROSS_ORIGIN_HEADERS {
+                response = response.with_additional_header(*name, *value);
+            }
             response = response
-                .with_additional_header("Cross-Origin-Opener-Policy", "same-origin")
-                .with_additional_header("Cross-Origin-Embedder-Policy", "require-corp")
                 .with_additional_header("Cache-Control", "no-cache, no-store, must-revalidate");
         }
 
         response
-    })
-    .map_err(|e| CustomError::ProcessError(format!("Failed to start server: {}", e)))?;
+    };
+
+    let server = match tls {
+        Some(tls) => Server::new_ssl(&addr, handler, tls.certificate, tls.private_key),
+        None => Server::new(&addr, handler),
+    }
+    .map_err(|e| CustomError::ProcessError(t!("Failed to start server: {}", e)))?;
 
     while !shutdown.load(Ordering::SeqCst) {
         server.poll();
         thread::sleep(std::time::Duration::from_millis(50));
     }
 
+    if let Some(report) = memory_report {
+        report.print_summary(ui);
+    }
+
     Ok(())
 }
@@ -0,0 +1,397 @@
+use crate::Ui;
+use crate::build::{build_desktop, build_web};
+use crate::commands::example::{copy_engine_project, engine_checkout};
+use crate::commands::shader::{ShaderTemplate, create_shader};
+use crate::error::CustomError;
+use crate::harness::{HarnessOptions, RunOutcome, run_headless};
+use crate::i18n::{t, tr};
+use crate::imports::package_name;
+use crate::manifest::{load_manifest, update_manifest};
+use crate::packer::atlas_pages;
+use crate::paths::ProjectPaths;
+use crate::stats::load_atlas_stats;
+use clap::Args;
+use image::{Rgba, RgbaImage};
+use rand_core::{OsRng, RngCore};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const PROJECT_NAME: &str = "selftest";
+const SELFTEST_CONFIG: &str = "debug";
+const SELFTEST_PREFIX: &str = "selftest";
+const ATLAS_DIR: &str = "bonsai/core/render/atlas";
+const SPRITE_METADATA: &str = "bonsai/generated/sprite.odin";
+const SYSTEMS_DIR: &str = "bonsai/systems";
+const WEB_OUTPUTS: &[&str] = &["index.html", "index.js", "index.wasm"];
+const RUN_FRAMES: u32 = 60;
+const RUN_TIMEOUT_SECS: u64 = 60;
+const SHADER_TEMPLATES: &[ShaderTemplate] = &[
+    ShaderTemplate::Sprite,
+    ShaderTemplate::PostProcess,
+    ShaderTemplate::Fullscreen,
+];
+
+#[derive(Args)]
+pub struct SelftestArgs {
+    /// Engine version the project is generated from
+    #[arg(long, short, default_value = "latest")]
+    pub version: String,
+    /// Download the engine again instead of using the cached copy
+    #[arg(long)]
+    pub refresh: bool,
+    /// Generate the project of an earlier run, every run prints its seed
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Also build for the web
+    #[arg(long)]
+    pub web: bool,
+    /// Run the desktop build headless for a few frames
+    #[arg(long)]
+    pub run: bool,
+    /// Keep the generated project, it is always kept when a check fails
+    #[arg(long)]
+    pub keep: bool,
+}
+
+// splitmix64, a seed has to generate the same project on every platform and version
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn range(&mut self, min: u32, max: u32) -> u32 {
+        min + (self.next() % (max - min + 1) as u64) as u32
+    }
+
+    fn color(&mut self) -> Rgba<u8> {
+        let value = self.next();
+        Rgba([value as u8, (value >> 8) as u8, (value >> 16) as u8, 255])
+    }
+}
+
+// what the generated project should produce
+struct Fixture {
+    // atlas key and SpriteName of every image
+    sprites: Vec<(String, String)>,
+    shaders: Vec<PathBuf>,
+    systems: Vec<String>,
+}
+
+pub fn selftest(args: &SelftestArgs, ui: Ui) -> Result<(), CustomError> {
+    let seed = args.seed.unwrap_or_else(|| OsRng.next_u64());
+    ui.log(&t!("Self-test seed {}, rerun it with --seed {}.", seed, seed));
+
+    let engine = engine_checkout(&args.version, args.refresh, &ui)?;
+    let project = tempfile::Builder::new()
+        .prefix("bonsai-selftest-")
+        .tempdir()?;
+
+    ui.status(tr("Generating the self-test project..."));
+    copy_engine_project(&engine, project.path(), PROJECT_NAME, &ui)?;
+    let fixture = generate_fixture(project.path(), &mut Rng(seed))?;
+    ui.log(&t!(
+        "Generated {} sprites, {} shaders and {} systems.",
+        fixture.sprites.len(),
+        fixture.shaders.len(),
+        fixture.systems.len()
+    ));
+
+    let result = run_pipeline(project.path(), &fixture, args, &ui);
+
+    // a failed run is only worth something when the project can be inspected or built again
+    if args.keep || result.is_err() {
+        let kept = project.keep();
+        ui.message(&t!("  Self-test project kept at {}", kept.display()));
+    }
+    result?;
+
+    ui.success(tr("Self-test passed."));
+    Ok(())
+}
+
+fn generate_fixture(project_dir: &Path, rng: &mut Rng) -> Result<Fixture, CustomError> {
+    let paths = ProjectPaths::new(&load_manifest(project_dir)?.paths)?;
+    let sprites = generate_sprites(&project_dir.join(paths.images()).join(SELFTEST_PREFIX), rng)?;
+
+    let mut shaders = Vec::new();
+    for index in 0..rng.range(1, 3) {
+        let name = format!("{}_shader_{}", SELFTEST_PREFIX, index);
+        let template = SHADER_TEMPLATES[rng.range(0, SHADER_TEMPLATES.len() as u32 - 1) as usize];
+        shaders.push(create_shader(project_dir, &paths, &name, template)?);
+    }
+
+    let mut systems = Vec::new();
+    for index in 0..rng.range(1, 3) {
+        let name = format!("{}_system_{}", SELFTEST_PREFIX, index);
+        generate_system(&project_dir.join(SYSTEMS_DIR).join(&name), &name, rng)?;
+        systems.push(name);
+    }
+
+    generate_game_file(project_dir, &paths, &shaders, &systems)?;
+
+    // paths relative to the project, the pipeline runs from inside it
+    let shaders = shaders
+        .iter()
+        .filter_map(|path| path.strip_prefix(project_dir).ok())
+        .map(Path::to_path_buf)
+        .collect();
+
+    Ok(Fixture {
+        sprites,
+        shaders,
+        systems,
+    })
+}
+
+// images of random sizes, some with transparent borders, and one animation strip
+fn generate_sprites(dir: &Path, rng: &mut Rng) -> Result<Vec<(String, String)>, CustomError> {
+    fs::create_dir_all(dir)?;
+    let mut sprites = Vec::new();
+
+    for index in 0..rng.range(6, 20) {
+        let (width, height) = (rng.range(4, 48), rng.range(4, 48));
+        let border = rng.range(0, width.min(height) / 4);
+        let (fill, corner) = (rng.color(), rng.color());
+        let image = RgbaImage::from_fn(width, height, |x, y| {
+            if x < border || y < border || x >= width - border || y >= height - border {
+                Rgba([0, 0, 0, 0])
+            } else if x == border && y == border {
+                corner
+            } else {
+                fill
+            }
+        });
+        let name = format!("{}_sprite_{}", SELFTEST_PREFIX, index);
+        save_image(&image, &dir.join(format!("{}.png", name)))?;
+        sprites.push((name.clone(), name));
+    }
+
+    let frames = rng.range(2, 8);
+    let (frame_width, height) = (rng.range(4, 24), rng.range(4, 24));
+    let colors: Vec<Rgba<u8>> = (0..frames).map(|_| rng.color()).collect();
+    let strip = RgbaImage::from_fn(frames * frame_width, height, |x, _| {
+        colors[(x / frame_width) as usize]
+    });
+    let name = format!("{}_strip", SELFTEST_PREFIX);
+    let key = format!("{}_{}x1", name, frames);
+    save_image(&strip, &dir.join(format!("{}.png", key)))?;
+    sprites.push((key, name));
+
+    Ok(sprites)
+}
+
+fn save_image(image: &RgbaImage, path: &Path) -> Result<(), CustomError> {
+    image.save(path).map_err(|e| {
+        CustomError::BuildError(t!("Failed to write {}: {}", path.display(), e))
+    })
+}
+
+// a package with a define from its system.toml, so the [build] settings reach odin
+fn generate_system(dir: &Path, name: &str, rng: &mut Rng) -> Result<(), CustomError> {
+    fs::create_dir_all(dir)?;
+    let define = format!("{}_VALUE", name.to_uppercase());
+    fs::write(
+        dir.join("system.toml"),
+        format!(
+            "[system]\nname = \"{}\"\nversion = \"{}\"\ndescription = \"Generated by bonsai selftest\"\n\n[build]\ndefines = {{ {} = {} }}\n",
+            name,
+            env!("CARGO_PKG_VERSION"),
+            define,
+            rng.range(1, 1000)
+        ),
+    )?;
+    fs::write(
+        dir.join(format!("{}.odin", name)),
+        format!(
+            "package {}\n\n// NOTE: Machine generated by bonsai selftest.\n\nVALUE :: #config({}, 0)\n\nvalue :: proc() -> int {{\n\treturn VALUE + {}\n}}\n",
+            name,
+            define,
+            rng.range(1, 1000)
+        ),
+    )?;
+    Ok(())
+}
+
+// odin only compiles imported packages, so the game package imports every generated one
+fn generate_game_file(
+    project_dir: &Path,
+    paths: &ProjectPaths,
+    shaders: &[PathBuf],
+    systems: &[String],
+) -> Result<(), CustomError> {
+    let game_dir = project_dir.join(paths.game());
+    let mut sources: Vec<PathBuf> = fs::read_dir(&game_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "odin"))
+        .collect();
+    sources.sort();
+    let package = sources.iter().find_map(|file| package_name(file)).ok_or_else(|| {
+        CustomError::ValidationError(t!(
+            "The engine template has no game package in {}",
+            game_dir.display()
+        ))
+    })?;
+
+    // shaders moved out of the game package would need their own collection
+    let shader_package = paths.shaders.strip_prefix(paths.game()).ok();
+    let mut imports = Vec::new();
+    let mut uses = Vec::new();
+    for system in systems {
+        imports.push(format!("import {} \"bonsai:systems/{}\"", system, system));
+        uses.push(format!("\ttotal += {}.value()", system));
+    }
+    if let Some(package) = shader_package {
+        let package = package.to_string_lossy().replace('\\', "/");
+        for shader in shaders {
+            let Some(name) = shader.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let import = if package.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}/{}", package, name)
+            };
+            imports.push(format!("import {} \"game:{}\"", name, import));
+            uses.push(format!("\t_ = {}.shader", name));
+        }
+    }
+
+    fs::write(
+        game_dir.join(format!("{}.odin", SELFTEST_PREFIX)),
+        format!(
+            "package {}\n\n// NOTE: Machine generated by bonsai selftest.\n\n{}\n\nselftest_total :: proc() -> int {{\n\ttotal := 0\n{}\n\treturn total\n}}\n",
+            package,
+            imports.join("\n"),
+            uses.join("\n")
+        ),
+    )?;
+    Ok(())
+}
+
+fn run_pipeline(
+    project_dir: &Path,
+    fixture: &Fixture,
+    args: &SelftestArgs,
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    update_manifest(Path::new("."), ui)?;
+
+    ui.status(tr("Building for desktop..."));
+    let build_result = build_desktop(SELFTEST_CONFIG, false, ui)?;
+
+    let mut failures = check_outputs(fixture);
+    if !build_result.executable_path.is_file() {
+        failures.push(t!(
+            "desktop build: {} was not written",
+            build_result.executable_path.display()
+        ));
+    }
+
+    if args.web {
+        ui.status(tr("Building for web..."));
+        build_web(SELFTEST_CONFIG, false, ui)?;
+        let web_dir = ProjectPaths::load()?.build_target("web");
+        for output in WEB_OUTPUTS {
+            if !web_dir.join(output).is_file() {
+                failures.push(t!("web build: {} was not written", output));
+            }
+        }
+    }
+
+    if args.run && build_result.executable_path.is_file() {
+        ui.status(tr("Running the desktop build..."));
+        let harness = HarnessOptions {
+            headless: true,
+            exit_after_frames: Some(RUN_FRAMES),
+            ..Default::default()
+        };
+        match run_headless(
+            &build_result.executable_path,
+            &harness,
+            Duration::from_secs(RUN_TIMEOUT_SECS),
+        )? {
+            RunOutcome::Passed => {}
+            RunOutcome::TimedOut => failures.push(t!(
+                "run: did not exit within {}s",
+                RUN_TIMEOUT_SECS
+            )),
+            RunOutcome::Crashed(status) => failures.push(t!("run: game exited with {}", status)),
+            RunOutcome::Desync => unreachable!("the self-test runs without a replay"),
+        }
+    }
+
+    for failure in &failures {
+        ui.error(failure);
+    }
+    if !failures.is_empty() {
+        return Err(CustomError::ValidationError(t!(
+            "{} self-test check(s) failed",
+            failures.len()
+        )));
+    }
+    Ok(())
+}
+
+// the atlas, generated metadata, shaders and systems the desktop build should have left behind
+fn check_outputs(fixture: &Fixture) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    match load_atlas_stats() {
+        Some(stats) => {
+            for (key, _) in &fixture.sprites {
+                if !stats.sprites.iter().any(|sprite| &sprite.name == key) {
+                    failures.push(t!("atlas: sprite '{}' was not packed", key));
+                }
+            }
+            let pages = atlas_pages(Path::new(ATLAS_DIR)).len();
+            if pages != stats.pages.len() {
+                failures.push(t!(
+                    "atlas: {} texture(s) written for {} page(s)",
+                    pages,
+                    stats.pages.len()
+                ));
+            }
+        }
+        None => failures.push(tr("atlas: no atlas statistics were written").to_string()),
+    }
+
+    let metadata = fs::read_to_string(SPRITE_METADATA).unwrap_or_default();
+    for (_, name) in &fixture.sprites {
+        if !metadata.contains(&format!("\t{},\n", name)) {
+            failures.push(t!("sprite metadata: SpriteName has no '{}'", name));
+        }
+    }
+
+    for shader in &fixture.shaders {
+        let output = shader.with_extension("odin");
+        if !output.is_file() {
+            failures.push(t!("shaders: {} was not compiled", shader.display()));
+        }
+    }
+
+    let registered = load_manifest(Path::new("."))
+        .map(|manifest| manifest.systems)
+        .unwrap_or_default();
+    for system in &fixture.systems {
+        if !registered.contains_key(system) {
+            failures.push(t!("systems: '{}' is not registered in bonsai.toml", system));
+        }
+    }
+
+    failures
+}
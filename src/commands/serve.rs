@@ -0,0 +1,66 @@
+use crate::Ui;
+use crate::commands::run::{DevServerOptions, TlsFiles, serve_web_directory};
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::load_manifest;
+use crate::paths::ProjectPaths;
+use clap::Args;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_PORT: u16 = 8080;
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Web build directory, or a project whose web build should be served
+    #[arg(default_value = ".")]
+    pub dir: String,
+    #[arg(long, short = 'p')]
+    pub port: Option<u16>,
+    /// Address to listen on, e.g. 0.0.0.0 for every interface
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: String,
+    #[arg(long, requires_all = ["cert", "key"])]
+    pub https: bool,
+    /// PEM certificate used with --https
+    #[arg(long, value_name = "PATH")]
+    pub cert: Option<PathBuf>,
+    /// PEM private key used with --https
+    #[arg(long, value_name = "PATH")]
+    pub key: Option<PathBuf>,
+}
+
+pub fn serve(args: &ServeArgs, ui: Ui) -> Result<(), CustomError> {
+    let dir = Path::new(&args.dir);
+    let (web_dir, port) = if dir.join("index.html").exists() {
+        (dir.to_path_buf(), args.port.unwrap_or(DEFAULT_PORT))
+    } else if dir.join("bonsai.toml").exists() {
+        let manifest = load_manifest(dir)?;
+        (
+            dir.join(ProjectPaths::new(&manifest.paths)?.build_target("web")),
+            args.port.or(manifest.web.port).unwrap_or(DEFAULT_PORT),
+        )
+    } else {
+        return Err(CustomError::ValidationError(t!(
+            "'{}' is neither a web build (missing index.html) nor a bonsai project",
+            args.dir
+        )));
+    };
+
+    if !web_dir.join("index.html").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "No web build in {}. Run 'bonsai build --web' first",
+            web_dir.display()
+        )));
+    }
+
+    let tls = match (&args.cert, &args.key) {
+        (Some(cert), Some(key)) if args.https => Some(TlsFiles {
+            certificate: fs::read(cert)?,
+            private_key: fs::read(key)?,
+        }),
+        _ => None,
+    };
+
+    serve_web_directory(&web_dir, &args.host, port, tls, DevServerOptions::default(), &ui)
+}
@@ -0,0 +1,326 @@
+use crate::Ui;
+use crate::build::compile_shaders_only;
+use crate::cancel::RebuildRunner;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::manifest::{load_manifest, register_shader};
+use crate::paths::ProjectPaths;
+use crate::watch::WatchRules;
+use crate::wsl::check_project_location;
+use clap::{Args, Subcommand, ValueEnum};
+use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+const CORE_SHADERS_DIR: &str = "bonsai/shaders";
+const SHADERS_INCLUDE_DIR: &str = "bonsai/shaders/include";
+
+const SHADER_HEADER_TEMPLATE: &str = r#"@header package {name}
+@header import sg "bonsai:libs/sokol/gfx"
+
+"#;
+
+const SPRITE_TEMPLATE: &str = r#"@vs vs
+layout(binding=0) uniform vs_params {
+    mat4 mvp;
+};
+
+in vec2 position;
+in vec4 color0;
+in vec2 texcoord0;
+
+out vec4 color;
+out vec2 uv;
+
+void main() {
+    gl_Position = mvp * vec4(position, 0.0, 1.0);
+    color = color0;
+    uv = texcoord0;
+}
+@end
+
+@fs fs
+layout(binding=0) uniform texture2D tex;
+layout(binding=0) uniform sampler smp;
+
+in vec4 color;
+in vec2 uv;
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = texture(sampler2D(tex, smp), uv) * color;
+}
+@end
+
+@program {name} vs fs
+"#;
+
+const POST_PROCESS_TEMPLATE: &str = r#"@vs vs
+out vec2 uv;
+
+// fullscreen triangle, no vertex buffer needed
+void main() {
+    vec2 pos = vec2(float((gl_VertexIndex << 1) & 2), float(gl_VertexIndex & 2));
+    uv = vec2(pos.x, 1.0 - pos.y);
+    gl_Position = vec4(pos * 2.0 - 1.0, 0.0, 1.0);
+}
+@end
+
+@fs fs
+layout(binding=0) uniform texture2D screen_tex;
+layout(binding=0) uniform sampler screen_smp;
+
+layout(binding=1) uniform fs_params {
+    float time;
+};
+
+in vec2 uv;
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = texture(sampler2D(screen_tex, screen_smp), uv);
+}
+@end
+
+@program {name} vs fs
+"#;
+
+const FULLSCREEN_TEMPLATE: &str = r#"@vs vs
+out vec2 uv;
+
+// fullscreen triangle, no vertex buffer needed
+void main() {
+    vec2 pos = vec2(float((gl_VertexIndex << 1) & 2), float(gl_VertexIndex & 2));
+    uv = pos;
+    gl_Position = vec4(pos * 2.0 - 1.0, 0.0, 1.0);
+}
+@end
+
+@fs fs
+layout(binding=1) uniform fs_params {
+    vec2 resolution;
+    float time;
+};
+
+in vec2 uv;
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = vec4(uv, 0.5 + 0.5 * sin(time), 1.0);
+}
+@end
+
+@program {name} vs fs
+"#;
+
+const ODIN_STUB_TEMPLATE: &str = r#"package {name}
+
+import sg "bonsai:libs/sokol/gfx"
+
+// {name}.odin is generated from {name}.glsl by sokol-shdc on every build
+
+shader :: proc() -> sg.Shader {
+	return sg.make_shader({name}_shader_desc(sg.query_backend()))
+}
+"#;
+
+#[derive(Args)]
+pub struct ShaderArgs {
+    #[command(subcommand)]
+    pub command: ShaderCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ShaderCommand {
+    /// Recompile shaders on save, without running the full build
+    Watch {
+        #[arg(default_value = ".")]
+        dir: String,
+        #[arg(long)]
+        web: bool,
+    },
+    /// Create a new shader from a template
+    New {
+        name: String,
+        #[arg(long, short, value_enum, default_value_t = ShaderTemplate::Sprite)]
+        template: ShaderTemplate,
+        #[arg(long, default_value = ".")]
+        dir: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ShaderTemplate {
+    Sprite,
+    PostProcess,
+    Fullscreen,
+}
+
+pub fn shader(args: &ShaderArgs, ui: Ui) -> Result<(), CustomError> {
+    match &args.command {
+        ShaderCommand::Watch { dir, web } => watch(dir, *web, &ui),
+        ShaderCommand::New {
+            name,
+            template,
+            dir,
+        } => new_shader(name, *template, dir, &ui),
+    }
+}
+
+fn new_shader(name: &str, template: ShaderTemplate, dir: &str, ui: &Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            dir
+        )));
+    }
+
+    // the name becomes an odin package and program identifier
+    let is_identifier = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !is_identifier {
+        return Err(CustomError::ValidationError(t!(
+            "Invalid shader name '{}'. Use snake_case, e.g. 'water_ripple'",
+            name
+        )));
+    }
+
+    let paths = ProjectPaths::new(&load_manifest(project_dir)?.paths)?;
+    let glsl_path = create_shader(project_dir, &paths, name, template)?;
+
+    ui.success(&t!("Created shader '{}' at {}", name, glsl_path.display()));
+    // shaders moved out of the game package need their own collection
+    if let Ok(package) = paths.shaders.strip_prefix(paths.game()) {
+        ui.message(&t!(
+            "  Import it with: import \"game:{}/{}\"",
+            package.to_string_lossy().replace('\\', "/"),
+            name
+        ));
+    }
+
+    Ok(())
+}
+
+/// Writes the `name` shader from `template` into its own directory under the project's shaders,
+/// with the Odin stub creating it, and registers it in `[shaders.programs]`. Returns the path of
+/// the `.glsl` file.
+pub fn create_shader(
+    project_dir: &Path,
+    paths: &ProjectPaths,
+    name: &str,
+    template: ShaderTemplate,
+) -> Result<PathBuf, CustomError> {
+    let shader_dir = project_dir.join(&paths.shaders).join(name);
+    if shader_dir.exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Shader '{}' already exists at {}",
+            name,
+            shader_dir.display()
+        )));
+    }
+
+    let body = match template {
+        ShaderTemplate::Sprite => SPRITE_TEMPLATE,
+        ShaderTemplate::PostProcess => POST_PROCESS_TEMPLATE,
+        ShaderTemplate::Fullscreen => FULLSCREEN_TEMPLATE,
+    };
+    let glsl = format!("{}{}", SHADER_HEADER_TEMPLATE, body).replace("{name}", name);
+    let stub = ODIN_STUB_TEMPLATE.replace("{name}", name);
+
+    fs::create_dir_all(&shader_dir)?;
+    let glsl_path = shader_dir.join(format!("{}.glsl", name));
+    fs::write(&glsl_path, glsl)?;
+    fs::write(shader_dir.join(format!("{}_shader.odin", name)), stub)?;
+
+    let relative_path = format!(
+        "{}/{}/{}.glsl",
+        paths.shaders.to_string_lossy().replace('\\', "/"),
+        name,
+        name
+    );
+    register_shader(project_dir, name, &relative_path)?;
+
+    Ok(glsl_path)
+}
+
+fn watch(dir: &str, is_web: bool, ui: &Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            dir
+        )));
+    }
+
+    check_project_location(project_dir, ui);
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    let manifest = load_manifest(Path::new("."))?;
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let rules = WatchRules::new(&manifest.watch, &paths)?;
+
+    let mut runner = RebuildRunner::default();
+    runner.restart(ui, move |ui| compile_shaders_only(is_web, false, ui));
+    let mut last_force = false;
+
+    let (tx, rx) = mpsc::channel();
+    let mut debouncer = new_debouncer(rules.debounce(), tx)
+        .map_err(|e| CustomError::ProcessError(t!("Failed to create file watcher: {}", e)))?;
+
+    for watch_dir in [PathBuf::from(CORE_SHADERS_DIR), paths.shaders.clone()] {
+        if watch_dir.exists() {
+            debouncer
+                .watcher()
+                .watch(&watch_dir, RecursiveMode::Recursive)
+                .map_err(|e| {
+                    CustomError::ProcessError(t!(
+                        "Failed to watch {}: {}",
+                        watch_dir.display(),
+                        e
+                    ))
+                })?;
+        }
+    }
+
+    ui.success(tr("Watching shaders for changes (Ctrl+C to stop)..."));
+
+    let include_dir = std::env::current_dir()?.join(SHADERS_INCLUDE_DIR);
+
+    while let Some(paths) = rules.next_batch(&rx) {
+        let changed: Vec<_> = paths
+            .iter()
+            .filter(|path| rules.stages_for(path).iter().any(|s| s == "shaders"))
+            .collect();
+
+        if changed.is_empty() {
+            continue;
+        }
+
+        // include files are pasted into every shader, so everything has to be rebuilt
+        // a forced rebuild that gets cancelled still has to happen
+        let force = changed.iter().any(|path| path.starts_with(&include_dir))
+            || (last_force && runner.is_running());
+        last_force = force;
+
+        runner.restart(ui, move |ui| {
+            compile_shaders_only(is_web, force, ui)?;
+            ui.success(tr("Shaders up to date."));
+            Ok(())
+        });
+    }
+
+    Ok(())
+}
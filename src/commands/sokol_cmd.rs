@@ -0,0 +1,116 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::git::{clone_repo_to_temp, describe_head};
+use crate::i18n::{t, tr};
+use crate::manifest::set_manifest_value;
+use crate::sokol::compile_sokol;
+use crate::wsl::check_project_location;
+use clap::{Args, Subcommand};
+use std::fs;
+use std::path::Path;
+
+const SOKOL_ODIN_URL: &str = "https://github.com/floooh/sokol-odin";
+const SOKOL_LIB_DIR: &str = "bonsai/libs/sokol";
+// sokol-odin keeps its C sources in sokol/c and one binding package per module
+const UPSTREAM_SOKOL_DIR: &str = "sokol";
+
+#[derive(Args)]
+pub struct SokolArgs {
+    #[command(subcommand)]
+    pub command: SokolCommand,
+}
+
+#[derive(Subcommand)]
+pub enum SokolCommand {
+    /// Fetch a sokol-odin release, refresh the bindings and recompile the libraries
+    Upgrade {
+        #[arg(default_value = ".")]
+        dir: String,
+        /// Tag, branch or 'latest'
+        #[arg(long, short, default_value = "latest")]
+        version: String,
+        /// Repository to fetch sokol-odin from
+        #[arg(long, default_value = SOKOL_ODIN_URL)]
+        url: String,
+    },
+}
+
+pub fn sokol(args: &SokolArgs, ui: Ui) -> Result<(), CustomError> {
+    match &args.command {
+        SokolCommand::Upgrade { dir, version, url } => upgrade(dir, version, url, &ui),
+    }
+}
+
+fn upgrade(dir: &str, version: &str, url: &str, ui: &Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            dir
+        )));
+    }
+
+    check_project_location(project_dir, ui);
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    ui.status(&t!("Fetching sokol-odin ({})...", version));
+    let temp_repo = clone_repo_to_temp(url, version, ui)?;
+    let upstream = temp_repo.path().join(UPSTREAM_SOKOL_DIR);
+    if !upstream.join("c").is_dir() {
+        return Err(CustomError::ValidationError(t!(
+            "'{}' does not look like sokol-odin (missing {}/c)",
+            url, UPSTREAM_SOKOL_DIR
+        )));
+    }
+
+    // pin 'latest' to the commit that was actually fetched
+    let resolved = if version == "latest" {
+        describe_head(temp_repo.path()).unwrap_or_else(|| version.to_string())
+    } else {
+        version.to_string()
+    };
+
+    let local = Path::new(SOKOL_LIB_DIR);
+    let mut updated = 0;
+    for entry in fs::read_dir(&upstream)?.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        let module = entry.file_name();
+        let is_sources = module == "c";
+        let dest_dir = local.join(&module);
+        fs::create_dir_all(&dest_dir)?;
+
+        for file in fs::read_dir(&path)?.filter_map(|e| e.ok()) {
+            let ext = file.path().extension().and_then(|e| e.to_str()).map(str::to_owned);
+            let wanted = match ext.as_deref() {
+                Some("c" | "h") => is_sources,
+                Some("odin") => !is_sources,
+                _ => false,
+            };
+            if wanted {
+                fs::copy(file.path(), dest_dir.join(file.file_name()))?;
+                updated += 1;
+            }
+        }
+    }
+    ui.log(&t!("Updated {} sokol source and binding files.", updated));
+
+    set_manifest_value(Path::new("."), "toolchain", "sokol", &resolved)?;
+
+    ui.status(tr("Recompiling sokol libraries..."));
+    compile_sokol(false, true, true, ui)?;
+    compile_sokol(false, false, false, ui)?;
+    ui.log(tr("Web libraries will be recompiled on the next web build."));
+
+    ui.success(&t!("Sokol upgraded to {}.", resolved));
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::paths::ProjectPaths;
+use crate::stats::{collect_report, print_report};
+use crate::usage::{collect_trends, print_trends};
+use clap::Args;
+use std::path::Path;
+
+#[derive(Args)]
+pub struct StatsArgs {
+    #[arg(default_value = ".")]
+    pub dir: String,
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn stats(args: &StatsArgs, ui: Ui) -> Result<(), CustomError> {
+    let trends = collect_trends();
+
+    // usage trends are global, so they are still shown outside a project
+    let project_dir = Path::new(&args.dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        if args.json {
+            let json = serde_json::to_string_pretty(&serde_json::json!({ "usage": trends }))
+                .map_err(|e| {
+                    CustomError::ValidationError(t!("Failed to serialize report: {}", e))
+                })?;
+            println!("{}", json);
+        } else {
+            print_trends(&trends);
+        }
+        return Ok(());
+    }
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    if ui.verbose {
+        ui.log(&t!("Collecting asset statistics in: '{}'", project_dir.display()));
+    }
+
+    let report = collect_report(&ProjectPaths::load()?);
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&serde_json::json!({
+            "assets": report,
+            "usage": trends,
+        }))
+        .map_err(|e| {
+            CustomError::ValidationError(t!("Failed to serialize report: {}", e))
+        })?;
+        println!("{}", json);
+    } else {
+        print_report(&report);
+        print_trends(&trends);
+    }
+
+    Ok(())
+}
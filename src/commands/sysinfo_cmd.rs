@@ -0,0 +1,26 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::sysinfo::{collect_system_info, render_system_info};
+use clap::Args;
+
+#[derive(Args)]
+pub struct SysinfoArgs {
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn sysinfo(args: &SysinfoArgs, _ui: Ui) -> Result<(), CustomError> {
+    let info = collect_system_info();
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&info).map_err(|e| {
+            CustomError::ValidationError(t!("Failed to serialize system info: {}", e))
+        })?;
+        println!("{}", json);
+    } else {
+        print!("{}", render_system_info(&info));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use crate::Ui;
+use crate::build::build_desktop;
+use crate::error::CustomError;
+use crate::golden::{
+    DEFAULT_CHANNEL_TOLERANCE, DEFAULT_MAX_DIFF_PERCENT, GOLDEN_DIR, GOLDEN_OUTPUT_DIR,
+    compare_images,
+};
+use crate::harness::{HarnessOptions, RunOutcome, run_headless};
+use crate::i18n::{t, tr};
+use crate::lock::ProjectLock;
+use crate::manifest::{TestOptions, load_manifest};
+use crate::wsl::check_project_location;
+use clap::Args;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DEFAULT_FRAMES: u32 = 300;
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Args)]
+pub struct TestArgs {
+    #[arg(default_value = ".")]
+    pub dir: String,
+    #[arg(long, short = 'c', default_value = "debug")]
+    pub config: String,
+    /// Replay files to run instead of the ones listed in [test] replays
+    #[arg(long, value_name = "FILE")]
+    pub replay: Vec<PathBuf>,
+    /// Frames to run each session before the game exits
+    #[arg(long)]
+    pub frames: Option<u32>,
+    /// Seconds before a session is considered hung
+    #[arg(long)]
+    pub timeout: Option<u64>,
+    /// Capture the [test] golden_scenes and compare them against tests/golden
+    #[arg(long)]
+    pub golden: bool,
+    /// Overwrite the golden images with the new captures
+    #[arg(long, requires = "golden")]
+    pub update_golden: bool,
+    /// Wait for another bonsai build in this project to finish instead of failing
+    #[arg(long)]
+    pub wait: bool,
+}
+
+pub fn test(args: &TestArgs, ui: Ui) -> Result<(), CustomError> {
+    let project_dir = Path::new(&args.dir);
+    if !project_dir.join("bonsai.toml").exists() {
+        return Err(CustomError::ValidationError(t!(
+            "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+            args.dir
+        )));
+    }
+
+    check_project_location(project_dir, &ui);
+
+    // replay paths given on the command line are relative to where bonsai was invoked
+    let cli_replays = args
+        .replay
+        .iter()
+        .map(std::path::absolute)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let current_dir = std::env::current_dir()?;
+    std::env::set_current_dir(project_dir)?;
+
+    let _cleanup_on_fail = scopeguard::guard(current_dir, |dir| {
+        let _ = std::env::set_current_dir(&dir);
+    });
+
+    let manifest = load_manifest(Path::new("."))?;
+    let frames = args
+        .frames
+        .or(manifest.test.frames)
+        .unwrap_or(DEFAULT_FRAMES);
+    let timeout = Duration::from_secs(
+        args.timeout
+            .or(manifest.test.timeout)
+            .unwrap_or(DEFAULT_TIMEOUT_SECS),
+    );
+
+    let replays: Vec<PathBuf> = if cli_replays.is_empty() {
+        manifest.test.replays.iter().map(std::path::absolute).collect::<Result<_, _>>()?
+    } else {
+        cli_replays
+    };
+
+    let _lock = ProjectLock::acquire(args.wait, &ui)?;
+
+    ui.status(tr("Building for desktop..."));
+    let build_result = build_desktop(&args.config, false, &ui)?;
+
+    if args.golden {
+        return run_golden(
+            args,
+            &manifest.test,
+            &build_result.executable_path,
+            frames,
+            timeout,
+            &ui,
+        );
+    }
+
+    // a plain smoke run first, then every known-good session
+    let mut sessions: Vec<Option<PathBuf>> = vec![None];
+    sessions.extend(replays.into_iter().map(Some));
+
+    let mut failures = 0;
+    for replay in sessions {
+        let name = replay
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "smoke".to_string());
+
+        if let Some(path) = &replay
+            && !path.exists()
+        {
+            return Err(CustomError::ValidationError(t!(
+                "Replay file not found: {}",
+                path.display()
+            )));
+        }
+
+        ui.status(&t!("Running {}...", name));
+        let options = HarnessOptions {
+            replay,
+            headless: true,
+            exit_after_frames: Some(frames),
+            ..Default::default()
+        };
+
+        let outcome = run_headless(&build_result.executable_path, &options, timeout)?;
+        if report_outcome(&name, outcome, timeout, &ui) {
+            ui.log(&t!("{}: passed", name));
+        } else {
+            failures += 1;
+        }
+    }
+
+    if failures > 0 {
+        return Err(CustomError::ProcessError(t!(
+            "{} test session(s) failed",
+            failures
+        )));
+    }
+
+    ui.success(tr("All test sessions passed."));
+    Ok(())
+}
+
+// returns whether the session exited cleanly
+fn report_outcome(name: &str, outcome: RunOutcome, timeout: Duration, ui: &Ui) -> bool {
+    match outcome {
+        RunOutcome::Passed => return true,
+        RunOutcome::Desync => ui.error(&t!("{}: replay desynced", name)),
+        RunOutcome::Crashed(status) => {
+            ui.error(&t!("{}: game exited with {}", name, status))
+        }
+        RunOutcome::TimedOut => ui.error(&t!(
+            "{}: did not exit within {}s",
+            name,
+            timeout.as_secs()
+        )),
+    }
+    false
+}
+
+fn run_golden(
+    args: &TestArgs,
+    options: &TestOptions,
+    executable: &Path,
+    frames: u32,
+    timeout: Duration,
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    if options.golden_scenes.is_empty() {
+        return Err(CustomError::ValidationError(
+            t!("No scenes to capture. Add them to [test] golden_scenes in bonsai.toml")
+        ));
+    }
+
+    let tolerance = options
+        .golden_tolerance
+        .unwrap_or(DEFAULT_CHANNEL_TOLERANCE);
+    let max_diff = options
+        .golden_max_diff_percent
+        .unwrap_or(DEFAULT_MAX_DIFF_PERCENT);
+
+    let output_dir = std::path::absolute(GOLDEN_OUTPUT_DIR)?;
+    if output_dir.exists() {
+        fs::remove_dir_all(&output_dir)?;
+    }
+    fs::create_dir_all(&output_dir)?;
+    fs::create_dir_all(GOLDEN_DIR)?;
+
+    let mut failures = 0;
+    for scene in &options.golden_scenes {
+        let capture = output_dir.join(format!("{}.png", scene));
+        let golden = Path::new(GOLDEN_DIR).join(format!("{}.png", scene));
+
+        ui.status(&t!("Capturing {}...", scene));
+        let harness = HarnessOptions {
+            headless: true,
+            exit_after_frames: Some(frames),
+            scene: Some(scene.clone()),
+            screenshot: Some(capture.clone()),
+            ..Default::default()
+        };
+
+        let outcome = run_headless(executable, &harness, timeout)?;
+        if !report_outcome(scene, outcome, timeout, ui) {
+            failures += 1;
+            continue;
+        }
+        if !capture.exists() {
+            failures += 1;
+            ui.error(&t!("{}: the game did not write a screenshot", scene));
+            continue;
+        }
+
+        if args.update_golden || !golden.exists() {
+            fs::copy(&capture, &golden)?;
+            ui.log(&t!("{}: golden image written to {}", scene, golden.display()));
+            continue;
+        }
+
+        let diff = compare_images(&golden, &capture, tolerance)?;
+        if diff.percent() <= max_diff {
+            ui.log(&t!("{}: matches golden image", scene));
+            continue;
+        }
+
+        failures += 1;
+        let diff_path = output_dir.join(format!("{}_diff.png", scene));
+        if let Some(image) = &diff.diff_image {
+            image.save(&diff_path).map_err(|e| {
+                CustomError::ValidationError(t!("Failed to write {}: {}", format!("{:?}", diff_path), e))
+            })?;
+        }
+        ui.error(&t!("{}: {}% of pixels differ ({} of {}), diff written to {}", scene, format!("{:.2}", diff.percent()), diff.differing_pixels, diff.total_pixels, diff_path.display()));
+    }
+
+    if failures > 0 {
+        return Err(CustomError::ProcessError(t!(
+            "{} golden scene(s) failed. Run 'bonsai test --golden --update-golden' if the change is intended",
+            failures
+        )));
+    }
+
+    ui.success(tr("All golden scenes match."));
+    Ok(())
+}
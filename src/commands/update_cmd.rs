@@ -0,0 +1,354 @@
+use crate::Ui;
+use crate::commands::install::install_dependencies;
+use crate::error::CustomError;
+use crate::git::{checkout, clone_repo_at_commit, resolve_version};
+use crate::hash::hash_file;
+use crate::i18n::{t, tr};
+use crate::lockfile::{LOCKFILE, LockedSystem, Lockfile, system_checksum};
+use crate::manifest::update_manifest;
+use crate::path_guard::{copy_dir_all, is_plain_name};
+use clap::Args;
+use colored::Colorize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+const SYSTEMS_DIR: &str = "bonsai/systems";
+const UTILS_DIR: &str = "utils";
+const MANIFEST_FILE: &str = "bonsai.toml";
+// generated by `update_manifest` when a system ships without one
+const SYSTEM_MANIFEST: &str = "system.toml";
+
+#[derive(Args)]
+pub struct UpdateArgs {
+    /// Systems to update (default: every system in bonsai.lock)
+    pub names: Vec<String>,
+    /// Move a single system to another tag or branch
+    #[arg(long, short, requires = "names")]
+    pub version: Option<String>,
+    /// Show what would change without touching any files
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Overwrite local edits that conflict with upstream changes
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Upstream changes to one installed directory, relative to the locked commit.
+#[derive(Default)]
+struct Plan {
+    added: Vec<String>,
+    modified: Vec<String>,
+    removed: Vec<String>,
+    // files edited since install that upstream left alone, kept as they are
+    local_edits: Vec<String>,
+    // files edited both locally and upstream
+    conflicts: Vec<String>,
+}
+
+impl Plan {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.modified.is_empty()
+            && self.removed.is_empty()
+            && self.conflicts.is_empty()
+    }
+
+    fn extend(&mut self, other: Plan) {
+        self.added.extend(other.added);
+        self.modified.extend(other.modified);
+        self.removed.extend(other.removed);
+        self.local_edits.extend(other.local_edits);
+        self.conflicts.extend(other.conflicts);
+    }
+}
+
+// relative path -> content hash of every file under `root`, empty when it doesn't exist
+fn file_hashes(root: &Path) -> Result<BTreeMap<String, String>, CustomError> {
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        files.insert(
+            relative.to_string_lossy().replace('\\', "/"),
+            hash_file(entry.path())?,
+        );
+    }
+    Ok(files)
+}
+
+/// Three-way comparison of the installed files against the locked (`old`) and the new
+/// upstream (`new`) checkouts. Paths in the plan are prefixed with `installed`.
+fn plan_directory(old: &Path, new: &Path, installed: &Path) -> Result<Plan, CustomError> {
+    let (old, new, current) = (file_hashes(old)?, file_hashes(new)?, file_hashes(installed)?);
+    let prefix = installed.to_string_lossy().replace('\\', "/");
+    let display = |path: &str| format!("{}/{}", prefix, path);
+
+    let mut plan = Plan::default();
+    let paths: BTreeSet<&String> = old.keys().chain(new.keys()).chain(current.keys()).collect();
+    for path in paths {
+        let (old, new, current) = (old.get(path), new.get(path), current.get(path));
+        if old == new {
+            if current != old && !(old.is_none() && path == SYSTEM_MANIFEST) {
+                plan.local_edits.push(display(path));
+            }
+        } else if current == new {
+            // already matches upstream
+        } else if current != old {
+            plan.conflicts.push(display(path));
+        } else if old.is_none() {
+            plan.added.push(display(path));
+        } else if new.is_none() {
+            plan.removed.push(display(path));
+        } else {
+            plan.modified.push(display(path));
+        }
+    }
+    Ok(plan)
+}
+
+// copies or deletes `path` (prefixed with `installed`) to match the upstream checkout at `new`
+fn apply_file(path: &str, new: &Path, installed: &Path) -> Result<(), CustomError> {
+    let relative = Path::new(path)
+        .strip_prefix(installed)
+        .map_err(|_| CustomError::ValidationError(t!("Unexpected path '{}'", path)))?;
+    let source = new.join(relative);
+    let target = installed.join(relative);
+
+    // a link in the new checkout isn't in the plan's hashes, so it's never followed here
+    if fs::symlink_metadata(&source).is_ok_and(|m| m.is_file()) {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&source, &target)?;
+    } else if target.exists() {
+        fs::remove_file(&target)?;
+        // drop directories the removal emptied, up to the installed root
+        let mut dir = target.parent();
+        while let Some(current) = dir {
+            if current == installed || fs::remove_dir(current).is_err() {
+                break;
+            }
+            dir = current.parent();
+        }
+    }
+    Ok(())
+}
+
+fn short(commit: &str) -> &str {
+    &commit[..commit.len().min(12)]
+}
+
+fn print_plan(plan: &Plan, force: bool, ui: &Ui) {
+    for path in &plan.added {
+        ui.message(&format!("  {} {}", "+".green(), path));
+    }
+    for path in &plan.modified {
+        ui.message(&format!("  {} {}", "~".yellow(), path));
+    }
+    for path in &plan.removed {
+        ui.message(&format!("  {} {}", "-".red(), path));
+    }
+    for path in &plan.local_edits {
+        ui.message(&t!("  {} {} (local edit, kept)", "=".cyan(), path));
+    }
+    for path in &plan.conflicts {
+        let note = if force { "overwritten" } else { "conflicts with upstream" };
+        ui.message(&t!("  {} {} (local edit, {})", "!".red().bold(), path, note));
+    }
+}
+
+enum Outcome {
+    UpToDate,
+    Updated,
+    Conflicted,
+}
+
+fn update_system(
+    name: &str,
+    locked: &LockedSystem,
+    version: &str,
+    args: &UpdateArgs,
+    lockfile: &mut Lockfile,
+    ui: &Ui,
+) -> Result<Outcome, CustomError> {
+    let installed = Path::new(SYSTEMS_DIR).join(name);
+    if !installed.is_dir() {
+        return Err(CustomError::ValidationError(t!(
+            "System '{}' is in {} but not installed. Run 'bonsai install' to restore it",
+            name, LOCKFILE
+        )));
+    }
+
+    ui.status(&t!("Checking '{}' for updates...", name));
+    let repo = clone_repo_at_commit(&locked.url, &locked.commit, ui)?;
+    let repo_path = repo.path();
+    let commit = resolve_version(repo_path, version).ok_or_else(|| {
+        CustomError::GitError(t!("Version/branch '{}' not found in {}", version, locked.url))
+    })?;
+
+    if commit == locked.commit && version == locked.version {
+        ui.log(&t!("{} is up to date ({}).", name, short(&commit)));
+        return Ok(Outcome::UpToDate);
+    }
+
+    // the locked tree is copied aside before checking out the new one over it
+    let old_tree = TempDir::new()?;
+    let pairs = [
+        (repo_path.join(SYSTEMS_DIR).join(name), installed.clone()),
+        (repo_path.join(UTILS_DIR), Path::new(UTILS_DIR).join(name)),
+    ];
+    for (index, (upstream, _)) in pairs.iter().enumerate() {
+        if upstream.exists() {
+            copy_dir_all(upstream, &old_tree.path().join(index.to_string()))?;
+        }
+    }
+    checkout(repo_path, &commit).map_err(|e| {
+        CustomError::GitError(t!("Could not check out {} of {}: {}", short(&commit), locked.url, e))
+    })?;
+
+    if !pairs[0].0.exists() {
+        return Err(CustomError::ValidationError(t!(
+            "{} of {} does not contain 'bonsai/systems/{}'",
+            short(&commit),
+            locked.url,
+            name
+        )));
+    }
+
+    let mut plan = Plan::default();
+    for (index, (upstream, target)) in pairs.iter().enumerate() {
+        plan.extend(plan_directory(
+            &old_tree.path().join(index.to_string()),
+            upstream,
+            target,
+        )?);
+    }
+
+    let from = if version == locked.version {
+        short(&locked.commit).to_string()
+    } else {
+        format!("{} {}", locked.version, short(&locked.commit))
+    };
+    ui.message(&format!(
+        "{} {}: {} -> {} {}",
+        "[UPDATE]".green().bold(),
+        name,
+        from,
+        version,
+        short(&commit)
+    ));
+    if plan.is_empty() {
+        ui.message(tr("  (no file changes)"));
+    }
+    print_plan(&plan, args.force, ui);
+
+    if !plan.conflicts.is_empty() && !args.force {
+        return Ok(Outcome::Conflicted);
+    }
+    if args.dry_run {
+        return Ok(Outcome::Updated);
+    }
+
+    for path in plan
+        .added
+        .iter()
+        .chain(&plan.modified)
+        .chain(&plan.removed)
+        .chain(&plan.conflicts)
+    {
+        let (upstream, target) = pairs
+            .iter()
+            .find(|(_, target)| Path::new(path).starts_with(target))
+            .ok_or_else(|| CustomError::ValidationError(t!("Unexpected path '{}'", path)))?;
+        apply_file(path, upstream, target)?;
+    }
+
+    install_dependencies(name, &pairs[0].0, Some(repo_path), ui)?;
+    update_manifest(Path::new("."), ui)?;
+
+    // local edits stay out of the checksum, so the build keeps warning about them
+    let checksum_dir = if plan.local_edits.is_empty() {
+        &installed
+    } else {
+        &pairs[0].0
+    };
+    lockfile.systems.insert(
+        name.to_string(),
+        LockedSystem {
+            url: locked.url.clone(),
+            version: version.to_string(),
+            commit,
+            checksum: system_checksum(checksum_dir)?,
+        },
+    );
+    lockfile.save()?;
+    Ok(Outcome::Updated)
+}
+
+pub fn update(args: &UpdateArgs, ui: Ui) -> Result<(), CustomError> {
+    if !Path::new(MANIFEST_FILE).exists() {
+        return Err(CustomError::ValidationError(
+            t!("Bonsai.toml manifest not found. Are you in a bonsai project?")
+        ));
+    }
+    if args.version.is_some() && args.names.len() != 1 {
+        return Err(CustomError::ValidationError(
+            t!("--version needs exactly one system name")
+        ));
+    }
+
+    let mut lockfile = Lockfile::load()?;
+    let names: Vec<String> = if args.names.is_empty() {
+        lockfile.systems.keys().cloned().collect()
+    } else {
+        args.names.clone()
+    };
+    if names.is_empty() {
+        ui.success(&t!("No systems in {}, nothing to update.", LOCKFILE));
+        return Ok(());
+    }
+
+    let (mut updated, mut conflicted) = (0, Vec::new());
+    for name in &names {
+        if !is_plain_name(name) {
+            return Err(CustomError::ValidationError(t!("Invalid system name '{}'", name)));
+        }
+        let Some(locked) = lockfile.systems.get(name).cloned() else {
+            return Err(CustomError::ValidationError(t!(
+                "System '{}' is not in {}. Only systems installed from git can be updated",
+                name, LOCKFILE
+            )));
+        };
+        let version = args.version.as_deref().unwrap_or(&locked.version);
+        match update_system(name, &locked, version, args, &mut lockfile, &ui)? {
+            Outcome::UpToDate => {}
+            Outcome::Updated => updated += 1,
+            Outcome::Conflicted => conflicted.push(name.as_str()),
+        }
+    }
+
+    if !conflicted.is_empty() {
+        return Err(CustomError::ValidationError(t!(
+            "Local edits conflict with upstream changes in: {}. Nothing was changed in them, \
+             re-run with --force to overwrite the edits",
+            conflicted.join(", ")
+        )));
+    }
+
+    if args.dry_run {
+        ui.success(&t!(
+            "Dry run: {} of {} systems would be updated, no files changed.",
+            updated,
+            names.len()
+        ));
+    } else {
+        ui.success(&t!("{} of {} systems updated.", updated, names.len()));
+    }
+    Ok(())
+}
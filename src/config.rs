@@ -0,0 +1,101 @@
+use crate::error::CustomError;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use toml_edit::{DocumentMut, table, value};
+
+const GLOBAL_DIR_NAME: &str = ".bonsai";
+const CONFIG_FILE: &str = "config.toml";
+const BUNDLE_CACHE_DIR: &str = "cache/bundle";
+
+#[derive(Debug, Deserialize, Default)]
+pub struct GlobalConfig {
+    #[serde(default)]
+    pub toolchain: ToolchainConfig,
+    #[serde(default)]
+    pub web: WebConfig,
+    #[serde(default)]
+    pub stats: StatsConfig,
+    #[serde(default)]
+    pub provenance: ProvenanceConfig,
+    #[serde(default)]
+    pub ui: UiConfig,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ToolchainConfig {
+    pub emsdk: Option<String>,
+    pub shdc_base_url: Option<String>,
+    // sha256 the downloaded sokol-shdc has to match
+    pub shdc_sha256: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct WebConfig {
+    pub browser: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct StatsConfig {
+    pub record_usage: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ProvenanceConfig {
+    // file written by `bonsai provenance keygen`
+    pub signing_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct UiConfig {
+    // "en" or "pl", BONSAI_LANG and the system locale are used otherwise
+    pub language: Option<String>,
+    // same as passing --plain to every command
+    pub plain: Option<bool>,
+}
+
+pub fn global_dir() -> Result<PathBuf, CustomError> {
+    let home = dirs::home_dir()
+        .ok_or_else(|| CustomError::ValidationError("Could not find home directory".into()))?;
+
+    Ok(home.join(GLOBAL_DIR_NAME))
+}
+
+/// Where `bonsai bundle use` unpacks the repositories and libraries of an offline bundle.
+pub fn bundle_cache_dir() -> Result<PathBuf, CustomError> {
+    Ok(global_dir()?.join(BUNDLE_CACHE_DIR))
+}
+
+pub fn load_global_config() -> Result<GlobalConfig, CustomError> {
+    let config_path = global_dir()?.join(CONFIG_FILE);
+    if !config_path.exists() {
+        return Ok(GlobalConfig::default());
+    }
+
+    let content = fs::read_to_string(&config_path)?;
+    toml_edit::de::from_str(&content).map_err(|e| {
+        CustomError::ValidationError(format!("Invalid global config {:?}: {}", config_path, e))
+    })
+}
+
+pub fn set_global_value(section: &str, key: &str, new_value: &str) -> Result<(), CustomError> {
+    let dir = global_dir()?;
+    fs::create_dir_all(&dir)?;
+
+    let config_path = dir.join(CONFIG_FILE);
+    let content = if config_path.exists() {
+        fs::read_to_string(&config_path)?
+    } else {
+        String::new()
+    };
+
+    let mut doc = content.parse::<DocumentMut>()?;
+    if doc.get(section).is_none() {
+        doc[section] = table();
+    }
+    doc[section][key] = value(new_value);
+
+    fs::write(&config_path, doc.to_string())?;
+
+    Ok(())
+}
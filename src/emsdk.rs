@@ -0,0 +1,130 @@
+use crate::Ui;
+use crate::build::run_with_prefix;
+use crate::config::{global_dir, load_global_config, set_global_value};
+use crate::error::CustomError;
+use crate::git::run_clone;
+use crate::i18n::{t, tr};
+use colored::Colorize;
+use std::env;
+use std::path::{Path, PathBuf};
+
+const EMSDK_REPO_URL: &str = "https://github.com/emscripten-core/emsdk.git";
+const TOOLCHAINS_DIR: &str = "toolchains";
+const EMSCRIPTEN_DIR: &str = "upstream/emscripten";
+
+fn is_emsdk_dir(path: &Path) -> bool {
+    path.join("emsdk_env.sh").exists() || path.join("emsdk_env.bat").exists()
+}
+
+pub fn find_emsdk() -> Option<PathBuf> {
+    if let Ok(path) = env::var("EMSDK") {
+        let path = PathBuf::from(path);
+        if path.exists() {
+            return Some(path);
+        }
+    }
+
+    if let Ok(config) = load_global_config()
+        && let Some(path) = config.toolchain.emsdk.map(PathBuf::from)
+        && is_emsdk_dir(&path)
+    {
+        return Some(path);
+    }
+
+    if let Ok(dir) = global_dir() {
+        let managed = dir.join(TOOLCHAINS_DIR).join("emsdk");
+        if is_emsdk_dir(&managed) {
+            return Some(managed);
+        }
+    }
+
+    if let Ok(home) = env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
+        let home_path = PathBuf::from(home);
+
+        let common_paths = ["repos/emsdk", "emsdk", "tools/emsdk", ".emsdk"]; // a little bit hacky, but might help someone
+
+        for sub_path in common_paths {
+            let attempt = home_path.join(sub_path);
+            if is_emsdk_dir(&attempt) {
+                return Some(attempt);
+            }
+        }
+    }
+
+    None
+}
+
+pub fn resolve_emsdk(ui: &Ui) -> Result<PathBuf, CustomError> {
+    if let Some(path) = find_emsdk() {
+        return Ok(path);
+    }
+
+    let install_dir = global_dir()?.join(TOOLCHAINS_DIR).join("emsdk");
+
+    let question = format!(
+        "{} Emscripten SDK not found. Install it into '{}'?",
+        "[WARNING]".yellow(),
+        install_dir.display()
+    );
+    if !ui.confirm(&question) {
+        return Err(CustomError::BuildError(
+            t!("Could not find Emscripten SDK.\n\
+            Please install it (https://emscripten.org/docs/getting_started/downloads.html)\n\
+            and set the 'EMSDK' environment variable to its installation folder")
+        ));
+    }
+
+    install_emsdk(&install_dir, ui)?;
+
+    set_global_value("toolchain", "emsdk", &install_dir.to_string_lossy())?;
+    ui.log(&t!(
+        "Saved Emscripten SDK path to the global config: {}",
+        install_dir.display()
+    ));
+
+    Ok(install_dir)
+}
+
+fn install_emsdk(install_dir: &Path, ui: &Ui) -> Result<(), CustomError> {
+    if !install_dir.join("emsdk.py").exists() {
+        ui.status(tr("Downloading Emscripten SDK..."));
+        std::fs::create_dir_all(install_dir)?;
+        run_clone(&["clone", "--depth", "1"], EMSDK_REPO_URL, install_dir, "latest")?;
+    }
+
+    let script = install_dir.join(if cfg!(windows) { "emsdk.bat" } else { "emsdk" });
+    let script_str = script
+        .to_str()
+        .ok_or(CustomError::ValidationError(t!("Invalid UTF-8 path")))?;
+
+    ui.status(tr("Installing latest Emscripten toolchain (this may take a while)..."));
+    run_with_prefix(
+        script_str,
+        &["install", "latest"],
+        "[EMSDK]",
+        colored::Color::Magenta,
+        ui,
+    )?;
+
+    ui.status(tr("Activating Emscripten toolchain..."));
+    run_with_prefix(
+        script_str,
+        &["activate", "latest"],
+        "[EMSDK]",
+        colored::Color::Magenta,
+        ui,
+    )?;
+
+    ui.log(tr("Emscripten SDK installed."));
+    Ok(())
+}
+
+pub fn emscripten_tool(emsdk_path: &Path, tool: &str) -> PathBuf {
+    let name = if cfg!(windows) {
+        format!("{}.bat", tool)
+    } else {
+        tool.to_string()
+    };
+
+    emsdk_path.join(EMSCRIPTEN_DIR).join(name)
+}
@@ -1,17 +1,20 @@
+use crate::i18n::tr;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum CustomError {
-    #[error("Git operation failed: {0}")]
+    #[error("{}: {}", tr("Git operation failed"), .0)]
     GitError(String),
-    #[error("I/O operation failed: {0}")]
+    #[error("{}: {}", tr("I/O operation failed"), .0)]
     IoError(#[from] std::io::Error),
-    #[error("TOML parsing failed: {0}")]
+    #[error("{}: {}", tr("TOML parsing failed"), .0)]
     TomlError(#[from] toml_edit::TomlError),
-    #[error("Validation error: {0}")]
+    #[error("{}: {}", tr("Validation error"), .0)]
     ValidationError(String),
-    #[error("Build failed: {0}")]
+    #[error("{}: {}", tr("Build failed"), .0)]
     BuildError(String),
-    #[error("Process execution failed: {0}")]
+    #[error("{}: {}", tr("Process execution failed"), .0)]
     ProcessError(String),
+    #[error("{}", tr("Build cancelled"))]
+    Cancelled,
 }
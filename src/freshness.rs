@@ -0,0 +1,344 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::hash::{hash_file, to_hex};
+use crate::i18n::{t, tr};
+use crate::manifest::Freshness;
+use crate::rebuild_log::{self, Reason};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+/// Content hashes of what every output was built from and of the output itself.
+pub const HASH_STORE: &str = ".bonsai/cache/hashes.json";
+// one file per stage holding the settings it was last built with
+pub const SETTINGS_DIR: &str = ".bonsai/cache/settings";
+// written after the first build of a checkout, until then committed outputs may look fresh
+const CHECKOUT_MARKER: &str = ".bonsai/cache/checkout";
+const PROBE_FILE: &str = ".bonsai/cache/mtime_probe";
+// more than this between the clock and a new file's mtime means the server keeps its own time
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(2);
+
+// bind mounts from a container host, network shares and VM shared folders
+const UNRELIABLE_FILESYSTEMS: &[&str] = &[
+    "fuse.grpcfuse",
+    "fakeowner",
+    "osxfs",
+    "virtiofs",
+    "9p",
+    "vboxsf",
+    "prl_fs",
+    "vmhgfs",
+    "fuse.vmhgfs-fuse",
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "fuse.sshfs",
+    "fuse.rclone",
+];
+
+static USE_HASHES: OnceLock<bool> = OnceLock::new();
+static STORE: Mutex<Option<BTreeMap<String, CacheEntry>>> = Mutex::new(None);
+
+// an output as [`mark_fresh`] left it
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+struct CacheEntry {
+    // fingerprint of the input paths and their content hashes
+    inputs: String,
+    output: String,
+}
+
+// filesystem type of the mount holding `path`, from /proc/self/mountinfo
+fn filesystem_type(path: &Path) -> Option<String> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").ok()?;
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            let (mount, fs_info) = line.split_once(" - ")?;
+            let mount_point = mount.split(' ').nth(4)?.replace("\\040", " ");
+            let fs_type = fs_info.split(' ').next()?;
+            path.starts_with(&mount_point)
+                .then(|| (mount_point.len(), fs_type.to_string()))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, fs_type)| fs_type)
+}
+
+fn clock_skew() -> Option<Duration> {
+    let probe = Path::new(PROBE_FILE);
+    fs::create_dir_all(probe.parent()?).ok()?;
+    let before = SystemTime::now();
+    fs::write(probe, b"").ok()?;
+    let modified = fs::metadata(probe).and_then(|m| m.modified()).ok();
+    let _ = fs::remove_file(probe);
+    let modified = modified?;
+    Some(
+        modified
+            .duration_since(before)
+            .or_else(|_| before.duration_since(modified))
+            .unwrap_or_default(),
+    )
+}
+
+// why modification times can't be trusted in the project directory, if they can't
+fn unreliable_reason() -> Option<String> {
+    let root = std::env::current_dir().ok()?.canonicalize().ok()?;
+
+    // canonical Windows paths are \\?\C:\... locally and \\?\UNC\server\share on a share
+    let unc = root.to_string_lossy().to_lowercase();
+    if unc.starts_with(r"\\?\unc\") || unc.starts_with(r"\\") && !unc.starts_with(r"\\?\") {
+        return Some("the project is on a network share".to_string());
+    }
+
+    if let Some(fs_type) = filesystem_type(&root)
+        && UNRELIABLE_FILESYSTEMS.contains(&fs_type.as_str())
+    {
+        let in_container = Path::new("/.dockerenv").exists() || Path::new("/run/.containerenv").exists();
+        return Some(if in_container {
+            format!("the project is a container bind mount ({})", fs_type)
+        } else {
+            format!("the project is on a {} mount", fs_type)
+        });
+    }
+
+    if let Some(skew) = clock_skew()
+        && skew > MAX_CLOCK_SKEW
+    {
+        return Some(format!(
+            "new files are stamped {}s away from the system clock",
+            skew.as_secs()
+        ));
+    }
+
+    if !Path::new(CHECKOUT_MARKER).exists() {
+        return Some("this checkout has not been built yet".to_string());
+    }
+
+    None
+}
+
+/// Decides once per run whether up-to-date checks compare modification times or content
+/// hashes, and logs the decision when hashes are used. Call from the project root.
+pub fn init(setting: Freshness, ui: &Ui) {
+    USE_HASHES.get_or_init(|| {
+        let use_hashes = match setting {
+            Freshness::Mtime => false,
+            Freshness::Hash => true,
+            Freshness::Auto => match unreliable_reason() {
+                Some(reason) => {
+                    ui.log(&t!(
+                        "Modification times are unreliable ({}), using content hashes for up-to-date checks.",
+                        reason
+                    ));
+                    true
+                }
+                None => false,
+            },
+        };
+
+        if let Some(parent) = Path::new(CHECKOUT_MARKER).parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(CHECKOUT_MARKER, b"");
+        use_hashes
+    });
+}
+
+pub fn uses_hashes() -> bool {
+    USE_HASHES.get().copied().unwrap_or(false)
+}
+
+// the content hash of every input, sorted by path
+fn input_hashes(inputs: &[PathBuf]) -> Result<Vec<(PathBuf, String)>, CustomError> {
+    let mut sorted: Vec<&PathBuf> = inputs.iter().collect();
+    sorted.sort();
+    sorted
+        .into_iter()
+        .map(|input| Ok((input.clone(), hash_file(input)?)))
+        .collect()
+}
+
+fn inputs_fingerprint(hashes: &[(PathBuf, String)]) -> String {
+    let mut hasher = Sha256::new();
+    for (input, hash) in hashes {
+        hasher.update(input.to_string_lossy().as_bytes());
+        hasher.update(hash.as_bytes());
+    }
+    to_hex(&hasher.finalize())
+}
+
+fn with_store<T>(f: impl FnOnce(&mut BTreeMap<String, CacheEntry>) -> T) -> T {
+    let mut guard = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    let store = guard.get_or_insert_with(|| {
+        fs::read_to_string(HASH_STORE)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    });
+    f(store)
+}
+
+fn store_key(output: &Path) -> String {
+    output.to_string_lossy().replace('\\', "/")
+}
+
+// how `output` and its inputs compare with what [`mark_fresh`] recorded
+#[derive(PartialEq)]
+enum Cached {
+    Same,
+    OutputChanged,
+    Different,
+}
+
+fn compare_cache(output: &Path, hashes: &[(PathBuf, String)]) -> Result<Cached, CustomError> {
+    let Some(entry) = with_store(|store| store.get(&store_key(output)).cloned()) else {
+        return Ok(Cached::Different);
+    };
+    if entry.inputs != inputs_fingerprint(hashes) {
+        return Ok(Cached::Different);
+    }
+    Ok(if entry.output == hash_file(output)? {
+        Cached::Same
+    } else {
+        Cached::OutputChanged
+    })
+}
+
+// so the next run takes the modification time shortcut again
+fn touch(output: &Path) {
+    let _ = fs::File::options()
+        .write(true)
+        .open(output)
+        .and_then(|f| f.set_modified(SystemTime::now()));
+}
+
+/// Whether `output` is up to date with `inputs`: the inputs and the output hash the same as
+/// when [`mark_fresh`] recorded them. Without hashes, no input being newer than the output is
+/// enough, and newer inputs are hashed before rebuilding, so a checkout or a restored cache
+/// that only moved timestamps rebuilds nothing.
+/// The reasons for a stale result are kept in the rebuild log.
+pub fn is_fresh(inputs: &[PathBuf], output: &Path) -> Result<bool, CustomError> {
+    if !output.exists() {
+        rebuild_log::stale(output, vec![Reason::Missing]);
+        return Ok(false);
+    }
+
+    if uses_hashes() {
+        let hashes = input_hashes(inputs)?;
+        let cached = compare_cache(output, &hashes)?;
+        match cached {
+            Cached::Same => rebuild_log::up_to_date(output),
+            Cached::OutputChanged => rebuild_log::stale(output, vec![Reason::OutputChanged]),
+            Cached::Different => rebuild_log::stale_by_hash(output, inputs, &hashes.into_iter().collect()),
+        }
+        return Ok(cached == Cached::Same);
+    }
+
+    let output_time = fs::metadata(output)?.modified()?;
+    let mut newer = Vec::new();
+    for input in inputs {
+        if fs::metadata(input)?.modified()? > output_time {
+            newer.push(input.clone());
+        }
+    }
+    if newer.is_empty() {
+        rebuild_log::up_to_date(output);
+        return Ok(true);
+    }
+    match compare_cache(output, &input_hashes(inputs)?)? {
+        Cached::Same => {
+            touch(output);
+            rebuild_log::up_to_date(output);
+            return Ok(true);
+        }
+        Cached::OutputChanged => rebuild_log::stale(output, vec![Reason::OutputChanged]),
+        Cached::Different => rebuild_log::stale_by_mtime(output, inputs, &newer),
+    }
+    Ok(false)
+}
+
+/// Records why `output` is rebuilt without asking [`is_fresh`], e.g. for `--clean`.
+pub fn mark_forced(output: &Path, why: &str) {
+    rebuild_log::stale(output, vec![Reason::Forced { why: why.to_string() }]);
+}
+
+/// Records the inputs `output` was just built from, and the content hashes of both.
+pub fn mark_fresh(inputs: &[PathBuf], output: &Path) -> Result<(), CustomError> {
+    let hashes = input_hashes(inputs)?;
+    let entry = CacheEntry {
+        inputs: inputs_fingerprint(&hashes),
+        output: hash_file(output)?,
+    };
+    rebuild_log::rebuilt(output, inputs, &hashes.into_iter().collect());
+    // stages run in parallel, so the file is written under the lock and swapped in whole
+    with_store(|store| {
+        store.insert(store_key(output), entry);
+        let json = serde_json::to_string_pretty(store).map_err(|e| {
+            CustomError::ValidationError(t!("Failed to serialize hash store: {}", e))
+        })?;
+        let path = Path::new(HASH_STORE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let staged = path.with_extension("tmp");
+        fs::write(&staged, json)?;
+        fs::rename(&staged, path)?;
+        Ok(())
+    })
+}
+
+/// Forgets every recorded hash (`--clean-cache`). Until outputs are rebuilt, only modification
+/// times can show them up to date.
+pub fn clear_cache(ui: &Ui) -> Result<(), CustomError> {
+    let mut store = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    *store = Some(BTreeMap::new());
+    if Path::new(HASH_STORE).exists() {
+        fs::remove_file(HASH_STORE)?;
+        ui.log(tr("Cleaned the content hash cache."));
+    }
+    Ok(())
+}
+
+/// Writes `contents` to `path` unless it already holds exactly that, so regenerated files keep
+/// their modification time and don't make everything built from them look stale.
+pub fn write_if_changed(path: &Path, contents: impl AsRef<[u8]>) -> Result<bool, CustomError> {
+    let contents = contents.as_ref();
+    if fs::read(path).is_ok_and(|current| current == contents) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(true)
+}
+
+/// Path of a stamp holding the settings `stage` is built with, rewritten only when they change.
+/// As one of the stage's [`is_fresh`] inputs it invalidates the stage exactly when they do.
+pub fn settings_stamp(stage: &str, settings: &str) -> Result<PathBuf, CustomError> {
+    let stamp = Path::new(SETTINGS_DIR).join(format!("{}.json", stage));
+    write_if_changed(&stamp, settings)?;
+    Ok(stamp)
+}
+
+/// Whether a copy at `dest` still matches `src`, for staged asset copies.
+pub fn is_same_copy(src: &Path, dest: &Path, src_modified: Option<SystemTime>) -> bool {
+    let Ok(dest_meta) = fs::metadata(dest) else {
+        return false;
+    };
+
+    if uses_hashes() {
+        return fs::metadata(src).is_ok_and(|m| m.len() == dest_meta.len())
+            && matches!((hash_file(src), hash_file(dest)), (Ok(a), Ok(b)) if a == b);
+    }
+
+    match (src_modified, dest_meta.modified().ok()) {
+        (Some(src_time), Some(dest_time)) => src_time <= dest_time,
+        _ => false,
+    }
+}
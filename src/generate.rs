@@ -0,0 +1,164 @@
+use crate::Ui;
+use crate::assets::SCENE_REGISTRY_FILE;
+use crate::build::{build_assets, compile_shaders_only, core_shaders};
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::Manifest;
+use crate::paths::ProjectPaths;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+const CORE_SHADER_DIR: &str = "bonsai/shaders";
+pub const GENERATED_KINDS: &[(&str, &[&str])] = &[
+    ("sprites", &["bonsai/generated/sprite.odin"]),
+    ("fonts", &["bonsai/generated/font.odin", "bonsai/generated/font_atlas.odin"]),
+    ("audio", &["bonsai/generated/audio.odin"]),
+    // plus the scene registry inside the scenes directory
+    ("scenes", &["bonsai/generated/scene.odin"]),
+    ("maps", &["bonsai/generated/map.odin"]),
+    ("app", &["bonsai/generated/app.odin"]),
+    ("shaders", &[]),
+];
+
+// sokol-shdc writes an .odin file next to every compiled .glsl
+pub fn shader_outputs(paths: &ProjectPaths) -> Vec<PathBuf> {
+    let mut outputs: Vec<PathBuf> = core_shaders().iter().map(|s| s.with_extension("odin")).collect();
+    outputs.extend(
+        [PathBuf::from(CORE_SHADER_DIR), paths.shaders.clone()]
+            .iter()
+            .flat_map(|dir| WalkDir::new(dir).into_iter().filter_map(|e| e.ok()))
+            .map(|e| e.into_path())
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("odin"))
+            .filter(|p| p.with_extension("glsl").exists()),
+    );
+    outputs.sort();
+    outputs.dedup();
+    outputs
+}
+
+pub fn is_tracked(path: &Path) -> bool {
+    Command::new("git")
+        .args(["ls-files", "--error-unmatch"])
+        .arg(path)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn in_git_repo() -> bool {
+    Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn vetted_kinds(manifest: &Manifest) -> Result<Vec<&'static str>, CustomError> {
+    let all: Vec<&str> = GENERATED_KINDS.iter().map(|(kind, _)| *kind).collect();
+    if manifest.generate.vet.is_empty() {
+        return Ok(all);
+    }
+
+    manifest
+        .generate
+        .vet
+        .iter()
+        .map(|kind| {
+            all.iter().find(|k| *k == kind).copied().ok_or_else(|| {
+                CustomError::ValidationError(t!(
+                    "Unknown [generate] vet entry '{}'. Expected one of: {}",
+                    kind,
+                    all.join(", ")
+                ))
+            })
+        })
+        .collect()
+}
+
+// committed files when the project is under git, otherwise whatever is on disk
+fn vetted_files(manifest: &Manifest) -> Result<Vec<PathBuf>, CustomError> {
+    let kinds = vetted_kinds(manifest)?;
+    let project_paths = ProjectPaths::new(&manifest.paths)?;
+    let mut files = Vec::new();
+
+    for (kind, paths) in GENERATED_KINDS {
+        if !kinds.contains(kind) {
+            continue;
+        }
+        if *kind == "shaders" {
+            files.extend(shader_outputs(&project_paths));
+        } else {
+            files.extend(paths.iter().map(PathBuf::from));
+        }
+        if *kind == "scenes" {
+            files.push(project_paths.scenes().join(SCENE_REGISTRY_FILE));
+        }
+    }
+
+    let use_git = in_git_repo();
+    files.retain(|path| {
+        if use_git {
+            is_tracked(path)
+        } else {
+            path.exists()
+        }
+    });
+
+    Ok(files)
+}
+
+fn regenerate(manifest: &Manifest, shaders: bool, ui: &Ui) -> Result<(), CustomError> {
+    build_assets(manifest, ui)?;
+    if shaders {
+        compile_shaders_only(false, true, ui)?;
+    }
+    Ok(())
+}
+
+/// Regenerates every generated file and returns the vetted ones whose contents changed.
+/// Without `write`, the previous contents are restored afterwards.
+pub fn vet_generated(
+    manifest: &Manifest,
+    write: bool,
+    ui: &Ui,
+) -> Result<Vec<PathBuf>, CustomError> {
+    let files = vetted_files(manifest)?;
+    let shaders = write || vetted_kinds(manifest)?.contains(&"shaders");
+    let snapshot: BTreeMap<PathBuf, Option<Vec<u8>>> = files
+        .iter()
+        .map(|path| (path.clone(), fs::read(path).ok()))
+        .collect();
+
+    let restore = || {
+        for (path, content) in &snapshot {
+            match content {
+                Some(bytes) => {
+                    let _ = fs::write(path, bytes);
+                }
+                None => {
+                    let _ = fs::remove_file(path);
+                }
+            }
+        }
+    };
+
+    if let Err(e) = regenerate(manifest, shaders, ui) {
+        restore();
+        return Err(e);
+    }
+
+    let stale = snapshot
+        .iter()
+        .filter(|(path, before)| fs::read(path).ok() != **before)
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    if !write {
+        restore();
+    }
+
+    Ok(stale)
+}
@@ -1,8 +1,14 @@
 use crate::Ui;
+use crate::config::bundle_cache_dir;
 use crate::error::CustomError;
-use std::path::Path;
+use crate::i18n::{t, tr};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tempfile::TempDir;
+use url::Url;
+
+// bare repositories an offline bundle brought, under the bundle cache
+pub const BUNDLE_REPOS_DIR: &str = "repos";
 
 pub fn clone_repo(
     full_url: &str,
@@ -10,20 +16,71 @@ pub fn clone_repo(
     version: &str,
     ui: &Ui,
 ) -> Result<(), CustomError> {
-    let mut args = vec!["clone", "--depth", "1"];
+    ui.status(tr("Initializing template repository..."));
+
+    run_clone(&["clone", "--depth", "1"], full_url, destination, version)?;
+
+    ui.log(tr("Download complete."));
+    Ok(())
+}
+
+pub fn clone_repo_sparse(
+    full_url: &str,
+    destination: &Path,
+    version: &str,
+    paths: &[String],
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    ui.status(tr("Initializing template repository (sparse)..."));
+
+    run_clone(
+        &["clone", "--depth", "1", "--filter=blob:none", "--sparse"],
+        full_url,
+        destination,
+        version,
+    )?;
 
-    ui.status("Initializing template repository...");
+    ui.status(&t!("Checking out: {}...", paths.join(", ")));
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(destination)
+        .args(["sparse-checkout", "set"])
+        .args(paths)
+        .output()
+        .map_err(CustomError::IoError)?;
+
+    if !output.status.success() {
+        return Err(CustomError::GitError(t!(
+            "Sparse checkout failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    ui.log(tr("Download complete."));
+    Ok(())
+}
+
+pub fn run_clone(
+    base_args: &[&str],
+    full_url: &str,
+    destination: &Path,
+    version: &str,
+) -> Result<(), CustomError> {
+    let mut args = base_args.to_vec();
 
     if version != "latest" {
         args.push("--branch");
         args.push(version);
     }
 
-    args.push(full_url);
+    // a mirror primed by `bonsai bundle use` stands in for the network
+    let mirror = bundled_mirror(full_url).and_then(|path| Url::from_file_path(path).ok());
+    args.push(mirror.as_ref().map_or(full_url, |url| url.as_str()));
     args.push(
         destination
             .to_str()
-            .ok_or_else(|| CustomError::ValidationError("Invalid destination path".to_string()))?,
+            .ok_or_else(|| CustomError::ValidationError(t!("Invalid destination path")))?,
     );
 
     let output = Command::new("git")
@@ -32,25 +89,58 @@ pub fn clone_repo(
         .map_err(|e| CustomError::IoError(e))?;
 
     if output.status.success() {
-        ui.log("Download complete.");
+        if mirror.is_some() {
+            // later fetches and provenance should still name the real repository
+            let _ = Command::new("git")
+                .arg("-C")
+                .arg(destination)
+                .args(["remote", "set-url", "origin", full_url])
+                .output();
+        }
         Ok(())
     } else {
         let error_msg = String::from_utf8_lossy(&output.stderr);
 
         if error_msg.to_lowercase().contains("remote branch") || error_msg.contains("not found") {
-            return Err(CustomError::GitError(format!(
+            return Err(CustomError::GitError(t!(
                 "Version/branch '{}' not found",
                 version
             )));
         }
 
-        Err(CustomError::GitError(format!(
+        Err(CustomError::GitError(t!(
             "Git clone failed: {}",
             error_msg
         )))
     }
 }
 
+/// Clones every branch and tag of `full_url` into the bare repository `destination`.
+pub fn mirror_repo(full_url: &str, destination: &Path, ui: &Ui) -> Result<(), CustomError> {
+    ui.status(&t!("Mirroring {}...", full_url));
+    run_clone(&["clone", "--mirror"], full_url, destination, "latest")
+}
+
+/// The directory name of `full_url`'s mirror, the same for its https and ssh forms.
+pub fn mirror_name(full_url: &str) -> String {
+    let url = full_url.trim_end_matches('/').trim_end_matches(".git");
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.strip_prefix("git@").unwrap_or(url);
+    let name: String = url
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+    format!("{}.git", name)
+}
+
+fn bundled_mirror(full_url: &str) -> Option<PathBuf> {
+    let mirror = bundle_cache_dir()
+        .ok()?
+        .join(BUNDLE_REPOS_DIR)
+        .join(mirror_name(full_url));
+    mirror.is_dir().then_some(mirror)
+}
+
 pub fn clone_repo_to_temp(full_url: &str, version: &str, ui: &Ui) -> Result<TempDir, CustomError> {
     let temp_dir = TempDir::new().map_err(|e| CustomError::IoError(e))?;
 
@@ -58,3 +148,192 @@ pub fn clone_repo_to_temp(full_url: &str, version: &str, ui: &Ui) -> Result<Temp
 
     Ok(temp_dir)
 }
+
+/// Clones `full_url` with its history and checks out `commit`, which `--branch` can't name.
+pub fn clone_repo_at_commit(full_url: &str, commit: &str, ui: &Ui) -> Result<TempDir, CustomError> {
+    let temp_dir = TempDir::new().map_err(CustomError::IoError)?;
+    ui.status(&t!("Fetching commit {}...", &commit[..commit.len().min(12)]));
+
+    run_clone(&["clone", "--no-checkout"], full_url, temp_dir.path(), "latest")?;
+    checkout(temp_dir.path(), commit).map_err(|e| {
+        CustomError::GitError(t!("Commit {} not found in {}: {}", commit, full_url, e))
+    })?;
+
+    ui.log(tr("Download complete."));
+    Ok(temp_dir)
+}
+
+/// Checks out `rev` in the clone at `dir`, the error is git's message.
+pub fn checkout(dir: &Path, rev: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["checkout", "--quiet", rev])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Commit a system version names in the full clone at `dir`: "latest" is the remote's default
+/// branch, anything else a tag or a branch.
+pub fn resolve_version(dir: &Path, version: &str) -> Option<String> {
+    let candidates = if version == "latest" {
+        vec!["origin/HEAD".to_string()]
+    } else {
+        vec![format!("refs/tags/{}", version), format!("origin/{}", version)]
+    };
+    candidates.iter().find_map(|rev| {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["rev-parse", "--verify", "--quiet"])
+            .arg(format!("{}^{{commit}}", rev))
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    })
+}
+
+/// Full hash of the commit the checkout at `dir` is on.
+pub fn head_commit(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// URL of the `origin` remote of the checkout at `dir`.
+pub fn remote_url(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["config", "--get", "remote.origin.url"])
+        .output()
+        .ok()?;
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !url.is_empty()).then_some(url)
+}
+
+/// Commit the working tree at `dir` is on, with a `-dirty` suffix for uncommitted changes.
+pub fn describe_head(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let dirty = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["status", "--porcelain"])
+        .output()
+        .map(|o| !o.stdout.is_empty())
+        .unwrap_or(false);
+
+    Some(if dirty {
+        format!("{}-dirty", commit)
+    } else {
+        commit
+    })
+}
+
+/// Nearest tag of the checkout at `dir`, or the short commit hash when it has none.
+pub fn describe_tag(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["describe", "--tags", "--always"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Files with uncommitted changes in the checkout at `dir`, as `git status --porcelain` lists them.
+pub fn uncommitted_changes(dir: &Path) -> Result<Vec<String>, CustomError> {
+    let stdout = run_git(dir, &["status", "--porcelain"])?;
+    Ok(stdout.lines().map(|line| line.to_string()).collect())
+}
+
+pub fn tag_exists(dir: &Path, tag: &str) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("refs/tags/{}", tag))
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Commits `paths` alone, returning false when none of them changed. Missing and ignored
+/// paths are skipped.
+pub fn commit_paths(dir: &Path, paths: &[&str], message: &str) -> Result<bool, CustomError> {
+    let paths: Vec<&str> = paths
+        .iter()
+        .copied()
+        .filter(|path| dir.join(path).exists() && run_git(dir, &["check-ignore", "--quiet", path]).is_err())
+        .collect();
+    if paths.is_empty() {
+        return Ok(false);
+    }
+
+    let mut add = vec!["add", "--all", "--"];
+    add.extend_from_slice(&paths);
+    run_git(dir, &add)?;
+
+    let mut staged = vec!["diff", "--cached", "--name-only", "--"];
+    staged.extend_from_slice(&paths);
+    if run_git(dir, &staged)?.trim().is_empty() {
+        return Ok(false);
+    }
+
+    let mut commit = vec!["commit", "--quiet", "-m", message, "--"];
+    commit.extend_from_slice(&paths);
+    run_git(dir, &commit)?;
+    Ok(true)
+}
+
+/// Creates the annotated tag `tag` on HEAD.
+pub fn create_tag(dir: &Path, tag: &str, message: &str) -> Result<(), CustomError> {
+    run_git(dir, &["tag", "--annotate", tag, "-m", message])?;
+    Ok(())
+}
+
+// stdout of a git command that has to succeed, its stderr otherwise
+fn run_git(dir: &Path, args: &[&str]) -> Result<String, CustomError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| CustomError::GitError(t!("Failed to run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(CustomError::GitError(t!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,75 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use image::{Rgba, RgbaImage};
+use std::path::Path;
+
+pub const GOLDEN_DIR: &str = "tests/golden";
+pub const GOLDEN_OUTPUT_DIR: &str = ".bonsai/test/golden";
+pub const DEFAULT_CHANNEL_TOLERANCE: u8 = 8;
+pub const DEFAULT_MAX_DIFF_PERCENT: f32 = 0.1;
+
+pub struct GoldenDiff {
+    pub differing_pixels: u64,
+    pub total_pixels: u64,
+    pub diff_image: Option<RgbaImage>,
+}
+
+impl GoldenDiff {
+    pub fn percent(&self) -> f32 {
+        if self.total_pixels == 0 {
+            return 0.0;
+        }
+        self.differing_pixels as f32 / self.total_pixels as f32 * 100.0
+    }
+}
+
+fn load_png(path: &Path) -> Result<RgbaImage, CustomError> {
+    image::open(path)
+        .map(|img| img.to_rgba8())
+        .map_err(|e| CustomError::ValidationError(t!("Failed to read {}: {}", format!("{:?}", path), e)))
+}
+
+// differing pixels are painted red over a faded copy of the golden image
+pub fn compare_images(
+    golden_path: &Path,
+    actual_path: &Path,
+    channel_tolerance: u8,
+) -> Result<GoldenDiff, CustomError> {
+    let golden = load_png(golden_path)?;
+    let actual = load_png(actual_path)?;
+
+    if golden.dimensions() != actual.dimensions() {
+        let total = u64::from(golden.width()) * u64::from(golden.height());
+        return Ok(GoldenDiff {
+            differing_pixels: total,
+            total_pixels: total,
+            diff_image: Some(actual),
+        });
+    }
+
+    let mut diff_image = RgbaImage::new(golden.width(), golden.height());
+    let mut differing_pixels = 0;
+
+    for ((x, y, expected), got) in golden.enumerate_pixels().zip(actual.pixels()) {
+        let differs = expected
+            .0
+            .iter()
+            .zip(got.0.iter())
+            .any(|(a, b)| a.abs_diff(*b) > channel_tolerance);
+
+        let pixel = if differs {
+            differing_pixels += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let [r, g, b, _] = expected.0;
+            Rgba([r / 4, g / 4, b / 4, 255])
+        };
+        diff_image.put_pixel(x, y, pixel);
+    }
+
+    Ok(GoldenDiff {
+        differing_pixels,
+        total_pixels: u64::from(golden.width()) * u64::from(golden.height()),
+        diff_image: (differing_pixels > 0).then_some(diff_image),
+    })
+}
@@ -0,0 +1,100 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// environment protocol understood by the framework runtime
+const RECORD_ENV: &str = "BONSAI_RECORD";
+const REPLAY_ENV: &str = "BONSAI_REPLAY";
+const HEADLESS_ENV: &str = "BONSAI_HEADLESS";
+const EXIT_AFTER_FRAMES_ENV: &str = "BONSAI_EXIT_AFTER_FRAMES";
+const SCENE_ENV: &str = "BONSAI_SCENE";
+const SCREENSHOT_ENV: &str = "BONSAI_SCREENSHOT";
+const FRAME_STATS_ENV: &str = "BONSAI_FRAME_STATS";
+const CAPTURE_FRAME_ENV: &str = "BONSAI_CAPTURE_FRAME";
+/// Exit code the game uses when a replay diverges from the recorded session.
+const REPLAY_DESYNC_EXIT_CODE: i32 = 3;
+
+#[derive(Default, Clone)]
+pub struct HarnessOptions {
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub headless: bool,
+    pub exit_after_frames: Option<u32>,
+    pub scene: Option<String>,
+    pub screenshot: Option<PathBuf>,
+    pub frame_stats: Option<PathBuf>,
+    pub capture_frame: Option<u32>,
+}
+
+pub enum RunOutcome {
+    Passed,
+    Desync,
+    Crashed(ExitStatus),
+    TimedOut,
+}
+
+impl HarnessOptions {
+    pub fn apply(&self, command: &mut Command) {
+        if let Some(record) = &self.record {
+            command.env(RECORD_ENV, record);
+        }
+        if let Some(replay) = &self.replay {
+            command.env(REPLAY_ENV, replay);
+        }
+        if self.headless {
+            command.env(HEADLESS_ENV, "1");
+        }
+        if let Some(frames) = self.exit_after_frames {
+            command.env(EXIT_AFTER_FRAMES_ENV, frames.to_string());
+        }
+        if let Some(scene) = &self.scene {
+            command.env(SCENE_ENV, scene);
+        }
+        if let Some(screenshot) = &self.screenshot {
+            command.env(SCREENSHOT_ENV, screenshot);
+        }
+        if let Some(frame_stats) = &self.frame_stats {
+            command.env(FRAME_STATS_ENV, frame_stats);
+        }
+        if let Some(frame) = self.capture_frame {
+            command.env(CAPTURE_FRAME_ENV, frame.to_string());
+        }
+    }
+}
+
+pub fn run_headless(
+    executable: &Path,
+    options: &HarnessOptions,
+    timeout: Duration,
+) -> Result<RunOutcome, CustomError> {
+    let mut command = Command::new(executable);
+    options.apply(&mut command);
+
+    let mut child = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| CustomError::ProcessError(t!("Failed to start game: {}", e)))?;
+
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(match status.code() {
+                Some(0) => RunOutcome::Passed,
+                Some(REPLAY_DESYNC_EXIT_CODE) if options.replay.is_some() => RunOutcome::Desync,
+                _ => RunOutcome::Crashed(status),
+            });
+        }
+
+        if started.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(RunOutcome::TimedOut);
+        }
+
+        thread::sleep(Duration::from_millis(50));
+    }
+}
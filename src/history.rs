@@ -0,0 +1,140 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::generate::shader_outputs;
+use crate::git::describe_head;
+use crate::hash::hash_file;
+use crate::i18n::t;
+use crate::paths::ProjectPaths;
+use crate::staging::{AssetFilter, forget_staged, link_dir, stage_files};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const HISTORY_DIR: &str = ".bonsai/builds";
+pub const DEFAULT_KEEP_BUILDS: usize = 5;
+const BUILD_INFO_FILE: &str = "build.json";
+const LATEST_LINK: &str = "latest";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BuildInfo {
+    pub id: String,
+    pub target: String,
+    pub config: String,
+    pub created: String,
+    pub commit: Option<String>,
+    #[serde(default)]
+    pub shaders: BTreeMap<String, String>,
+}
+
+pub struct BuildEntry {
+    pub info: BuildInfo,
+    pub path: PathBuf,
+}
+
+pub fn new_build_id(target: &str) -> String {
+    format!("{}-{}", target, Local::now().format("%Y%m%d-%H%M%S"))
+}
+
+/// Copies a finished build into `.bonsai/builds/<target>/<id>` and prunes old snapshots.
+pub fn snapshot_build(
+    output_dir: &Path,
+    id: &str,
+    target: &str,
+    config: &str,
+    keep: usize,
+    ui: &Ui,
+) -> Result<bool, CustomError> {
+    if keep == 0 || !output_dir.exists() {
+        return Ok(false);
+    }
+
+    let target_dir = Path::new(HISTORY_DIR).join(target);
+    let snapshot_dir = target_dir.join(id);
+
+    stage_files(output_dir, &snapshot_dir, &AssetFilter::new(&[])?)?;
+
+    let info = BuildInfo {
+        id: id.to_string(),
+        target: target.to_string(),
+        config: config.to_string(),
+        created: Local::now().to_rfc3339(),
+        commit: describe_head(Path::new(".")),
+        shaders: hash_shader_outputs(&ProjectPaths::load()?),
+    };
+    let json = serde_json::to_string_pretty(&info)
+        .map_err(|e| CustomError::BuildError(t!("Failed to serialize build info: {}", e)))?;
+    fs::write(snapshot_dir.join(BUILD_INFO_FILE), json)?;
+
+    link_dir(&snapshot_dir, &target_dir.join(LATEST_LINK))?;
+
+    let mut builds = list_builds(Some(target));
+    if builds.len() > keep {
+        builds.truncate(builds.len() - keep);
+        for old in builds {
+            fs::remove_dir_all(&old.path)?;
+            forget_staged(&old.path);
+            if ui.verbose {
+                ui.log(&t!("Removed old build {}", old.info.id));
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+// shader outputs are compiled into the binary, so record their hashes for diffs
+fn hash_shader_outputs(paths: &ProjectPaths) -> BTreeMap<String, String> {
+    shader_outputs(paths)
+        .iter()
+        .filter_map(|path| {
+            let hash = hash_file(path).ok()?;
+            Some((path.to_string_lossy().replace('\\', "/"), hash))
+        })
+        .collect()
+}
+
+/// Recorded builds, oldest first.
+pub fn list_builds(target: Option<&str>) -> Vec<BuildEntry> {
+    let history = Path::new(HISTORY_DIR);
+    let Ok(targets) = fs::read_dir(history) else {
+        return Vec::new();
+    };
+
+    let mut builds: Vec<BuildEntry> = targets
+        .filter_map(|e| e.ok())
+        .filter(|e| target.is_none_or(|t| e.file_name().to_str() == Some(t)))
+        .filter_map(|e| fs::read_dir(e.path()).ok())
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name() != LATEST_LINK)
+        .filter_map(|e| {
+            let content = fs::read_to_string(e.path().join(BUILD_INFO_FILE)).ok()?;
+            let info: BuildInfo = serde_json::from_str(&content).ok()?;
+            Some(BuildEntry {
+                info,
+                path: e.path(),
+            })
+        })
+        .collect();
+
+    builds.sort_by(|a, b| a.info.created.cmp(&b.info.created));
+    builds
+}
+
+// accepts a build id or "latest"
+pub fn find_build(id: &str) -> Result<BuildEntry, CustomError> {
+    let builds = list_builds(None);
+    if id == LATEST_LINK {
+        return builds
+            .into_iter()
+            .last()
+            .ok_or_else(|| CustomError::ValidationError(t!("No recorded builds")));
+    }
+
+    builds
+        .into_iter()
+        .find(|b| b.info.id == id)
+        .ok_or_else(|| CustomError::ValidationError(t!("Build '{}' not found", id)))
+}
@@ -0,0 +1,80 @@
+use crate::error::CustomError;
+use clap::ValueEnum;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// cross-origin isolation, required for SharedArrayBuffer and wasm threads
+pub const CROSS_ORIGIN_HEADERS: &[(&str, &str)] = &[
+    ("Cross-Origin-Opener-Policy", "same-origin"),
+    ("Cross-Origin-Embedder-Policy", "require-corp"),
+];
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum HostProvider {
+    /// `_headers` (Cloudflare Pages, also read by Netlify)
+    Cloudflare,
+    /// `netlify.toml`
+    Netlify,
+    /// `vercel.json`
+    Vercel,
+    /// `.htaccess` (Apache, most shared hosting)
+    Apache,
+}
+
+impl HostProvider {
+    fn file_name(self) -> &'static str {
+        match self {
+            HostProvider::Cloudflare => "_headers",
+            HostProvider::Netlify => "netlify.toml",
+            HostProvider::Vercel => "vercel.json",
+            HostProvider::Apache => ".htaccess",
+        }
+    }
+
+    fn render(self) -> String {
+        let mut out = String::new();
+        match self {
+            HostProvider::Cloudflare => {
+                out.push_str("/*\n");
+                for (name, value) in CROSS_ORIGIN_HEADERS {
+                    out.push_str(&format!("  {}: {}\n", name, value));
+                }
+            }
+            HostProvider::Netlify => {
+                out.push_str("[[headers]]\n  for = \"/*\"\n  [headers.values]\n");
+                for (name, value) in CROSS_ORIGIN_HEADERS {
+                    out.push_str(&format!("    {} = \"{}\"\n", name, value));
+                }
+            }
+            HostProvider::Vercel => {
+                let headers: Vec<_> = CROSS_ORIGIN_HEADERS
+                    .iter()
+                    .map(|(name, value)| serde_json::json!({ "key": name, "value": value }))
+                    .collect();
+                let config = serde_json::json!({
+                    "headers": [{ "source": "/(.*)", "headers": headers }]
+                });
+                out.push_str(&serde_json::to_string_pretty(&config).unwrap_or_default());
+                out.push('\n');
+            }
+            HostProvider::Apache => {
+                out.push_str("<IfModule mod_headers.c>\n");
+                for (name, value) in CROSS_ORIGIN_HEADERS {
+                    out.push_str(&format!("  Header set {} \"{}\"\n", name, value));
+                }
+                out.push_str("</IfModule>\n");
+                // older Apache installs serve .wasm as octet-stream, which breaks streaming compilation
+                out.push_str("AddType application/wasm .wasm\n");
+            }
+        }
+        out
+    }
+}
+
+/// Writes the host config for `provider` into `out_dir`, so the deployed build gets the same
+/// headers as `bonsai run --web`.
+pub fn write_host_config(out_dir: &Path, provider: HostProvider) -> Result<PathBuf, CustomError> {
+    let path = out_dir.join(provider.file_name());
+    fs::write(&path, provider.render())?;
+    Ok(path)
+}
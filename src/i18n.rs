@@ -0,0 +1,1716 @@
+use crate::config::load_global_config;
+use colored::Colorize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Overrides `[ui] language` and the system locale, e.g. `BONSAI_LANG=pl`.
+pub const LANGUAGE_ENV: &str = "BONSAI_LANG";
+
+/// Languages the CLI speaks, as (code, name).
+pub const LANGUAGES: [(&str, &str); 2] = [("en", "English"), ("pl", "Polski")];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Language {
+    English,
+    Polish,
+}
+
+impl Language {
+    fn from_code(code: &str) -> Option<Self> {
+        // "pl", "pl_PL.UTF-8", "pl-PL"
+        let code = code.split(['_', '-', '.', '@']).next().unwrap_or("").to_lowercase();
+        match code.as_str() {
+            "en" => Some(Language::English),
+            "pl" => Some(Language::Polish),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Polish => "pl",
+        }
+    }
+
+    fn catalog(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => &[],
+            Language::Polish => POLISH,
+        }
+    }
+}
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+static CATALOG: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+
+// BONSAI_LANG, then [ui] language in ~/.bonsai/config.toml, then the system locale.
+// Loading the config must not translate anything, the language isn't known yet.
+fn detect() -> Language {
+    let explicit = std::env::var(LANGUAGE_ENV)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(|v| (v, LANGUAGE_ENV.to_string()))
+        .or_else(|| {
+            load_global_config()
+                .ok()
+                .and_then(|c| c.ui.language)
+                .map(|v| (v, "[ui] language".to_string()))
+        });
+    if let Some((value, source)) = explicit {
+        return Language::from_code(&value).unwrap_or_else(|| {
+            eprintln!(
+                "{} {} '{}' isn't a supported language ({}), using English.",
+                "[WARNING]".yellow(),
+                source,
+                value,
+                LANGUAGES.map(|(code, _)| code).join(", ")
+            );
+            Language::English
+        });
+    }
+
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        .and_then(|v| Language::from_code(&v))
+        .unwrap_or(Language::English)
+}
+
+pub fn language() -> Language {
+    *LANGUAGE.get_or_init(detect)
+}
+
+/// `text` in the user's language, `text` itself when it has no translation.
+pub fn tr(text: &'static str) -> &'static str {
+    CATALOG
+        .get_or_init(|| language().catalog().iter().copied().collect())
+        .get(text)
+        .copied()
+        .unwrap_or(text)
+}
+
+/// Fills the `{}` placeholders of a translated `text` in order, `{0}`, `{1}`... by position
+/// for translations that need another word order.
+pub fn fill(text: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut next = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find(['{', '}']) {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        // "{{" and "}}" are literal braces, as in format!
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            out.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        if rest.starts_with('}') {
+            out.push('}');
+            rest = &rest[1..];
+            continue;
+        }
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let index = match &rest[1..end] {
+            "" => {
+                next += 1;
+                Some(next - 1)
+            }
+            n => n.parse::<usize>().ok(),
+        };
+        match index.and_then(|i| args.get(i)) {
+            Some(arg) => out.push_str(&arg.to_string()),
+            None => out.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Whether a `[Y/N]` answer means yes, in English or the user's language.
+pub fn is_yes(answer: &str) -> bool {
+    let answer = answer.trim().to_lowercase();
+    let local: &[&str] = match language() {
+        Language::English => &[],
+        Language::Polish => &["t", "tak"],
+    };
+    answer == "y" || answer == "yes" || local.contains(&answer.as_str())
+}
+
+/// `format!` for user-facing messages: the literal is looked up in the catalog first.
+macro_rules! t {
+    ($text:literal) => {
+        $crate::i18n::tr($text).to_string()
+    };
+    ($text:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill($crate::i18n::tr($text), &[$(&$arg as &dyn std::fmt::Display),+])
+    };
+}
+pub(crate) use t;
+
+// keys are the English messages exactly as written at their call sites
+const POLISH: &[(&str, &str)] = &[
+    (
+        "Failed to pack font at build time: {}",
+        "Nie udało się spakować czcionki podczas kompilacji: {}",
+    ),
+    (
+        "Analyzing {} to find true pixel size...",
+        "Analizowanie {} w poszukiwaniu rzeczywistego rozmiaru pikseli...",
+    ),
+    (
+        "Failed to parse font for size detection.",
+        "Nie udało się odczytać czcionki do wykrycia rozmiaru.",
+    ),
+    (
+        "Invalid [app] orientation '{}' (expected any, landscape or portrait)",
+        "Nieprawidłowa orientacja [app] '{}' (oczekiwano any, landscape lub portrait)",
+    ),
+    ("App icon not found: {}", "Nie znaleziono ikony aplikacji: {}"),
+    (
+        "Invalid [app] save_dir_name '{}' (expected a single directory name)",
+        "Nieprawidłowe [app] save_dir_name '{}' (oczekiwano nazwy jednego katalogu)",
+    ),
+    ("No frame times recorded in {}", "Brak zapisanych czasów klatek w {}"),
+    ("Invalid bench baseline: {}", "Nieprawidłowy punkt odniesienia benchmarku: {}"),
+    ("Failed to serialize baseline: {}", "Nie udało się zapisać punktu odniesienia: {}"),
+    (
+        "{} Could not start browser '{}'. Falling back to the system default.",
+        "{} Nie udało się uruchomić przeglądarki '{}'. Używam domyślnej przeglądarki systemu.",
+    ),
+    (
+        "{} No browser opener available (tried --browser, $BROWSER and the system defaults). Open {} manually.",
+        "{} Brak programu do otwarcia przeglądarki (sprawdzono --browser, $BROWSER i domyślne ustawienia systemu). Otwórz {} ręcznie.",
+    ),
+    ("Browser '{}' not found", "Nie znaleziono przeglądarki '{}'"),
+    (
+        "No Chrome, Chromium, Edge, Brave or Firefox found for a separate profile, pass its path with --browser",
+        "Nie znaleziono Chrome, Chromium, Edge, Brave ani Firefoksa dla osobnego profilu, podaj ścieżkę przez --browser",
+    ),
+    (
+        "'{}' is not a Chromium based browser or Firefox, which a separate profile needs",
+        "'{}' nie jest przeglądarką opartą na Chromium ani Firefoksem, a tego wymaga osobny profil",
+    ),
+    ("Failed to start {}: {}", "Nie udało się uruchomić {}: {}"),
+    (
+        "{} {}. Removing the outputs of every build stage.",
+        "{} {}. Usuwanie wyników wszystkich etapów kompilacji.",
+    ),
+    ("Running pre-build tasks...", "Wykonywanie zadań przed kompilacją..."),
+    (
+        "Unknown manifest section [target.{}]. Expected one of: {}",
+        "Nieznana sekcja manifestu [target.{}]. Oczekiwano jednej z: {}",
+    ),
+    ("Native library not found: {}", "Nie znaleziono biblioteki natywnej: {}"),
+    ("Runtime library not found: {}", "Nie znaleziono biblioteki uruchomieniowej: {}"),
+    (
+        "Unknown shader target '{}' in [shaders] (expected windows, macos, linux or web)",
+        "Nieznany cel shaderów '{}' w [shaders] (oczekiwano windows, macos, linux lub web)",
+    ),
+    (
+        "Unknown shader language '{}' in [shaders] slang",
+        "Nieznany język shaderów '{}' w [shaders] slang",
+    ),
+    ("Compiling shader: {}", "Kompilowanie shadera: {}"),
+    ("Failed to start sokol-shdc: {}", "Nie udało się uruchomić sokol-shdc: {}"),
+    ("Shader compilation failed for {}", "Kompilacja shadera {} nie powiodła się"),
+    (
+        "Shader registered in [shaders.programs] not found: {}",
+        "Nie znaleziono shadera zarejestrowanego w [shaders.programs]: {}",
+    ),
+    ("Failed to serialize manifest: {}", "Nie udało się zserializować manifestu: {}"),
+    (
+        "{} Ignoring the system.toml of '{}': {}",
+        "{} Pomijanie system.toml systemu '{}': {}",
+    ),
+    ("{} unresolved package import(s)", "{} nierozwiązanych importów pakietów"),
+    ("Copying assets...", "Kopiowanie zasobów..."),
+    ("Copying runtime files...", "Kopiowanie plików uruchomieniowych..."),
+    (
+        "Could not find 'odin' to get root path",
+        "Nie znaleziono 'odin', aby ustalić ścieżkę główną",
+    ),
+    (
+        "{} Core preload bundle is over the {} MB budget, move assets into [web.packages].",
+        "{} Główny pakiet preload przekracza budżet {} MB, przenieś zasoby do [web.packages].",
+    ),
+    (
+        "{} Web package '{}' matches no files.",
+        "{} Pakiet webowy '{}' nie obejmuje żadnych plików.",
+    ),
+    ("Packaging '{}'...", "Pakowanie '{}'..."),
+    (
+        "  + Adding {} external libraries from manifest.",
+        "  + Dodawanie {} zewnętrznych bibliotek z manifestu.",
+    ),
+    ("External library not found: {}", "Nie znaleziono zewnętrznej biblioteki: {}"),
+    ("Linking with Emscripten...", "Linkowanie przez Emscripten..."),
+    ("Web build created in {}.", "Wersja webowa utworzona w {}."),
+    (
+        "Failed to run Emscripten command: {}",
+        "Nie udało się uruchomić polecenia Emscripten: {}",
+    ),
+    ("Emscripten command failed: {}", "Polecenie Emscripten nie powiodło się: {}"),
+    ("Scanning for utility scripts...", "Wyszukiwanie skryptów narzędziowych..."),
+    ("Invalid UTF-8 path", "Nieprawidłowa ścieżka UTF-8"),
+    ("{} Running Rust script: {}", "{} Uruchamianie skryptu Rust: {}"),
+    ("Failed to compile rust script: {}", "Nie udało się skompilować skryptu Rust: {}"),
+    ("Failed to compile {}", "Nie udało się skompilować {}"),
+    ("Rust script {} failed", "Skrypt Rust {} nie powiódł się"),
+    (
+        "Odin compiler not found in PATH. Please install it from https://odin-lang.org/docs/install",
+        "Nie znaleziono kompilatora Odin w PATH. Zainstaluj go ze strony https://odin-lang.org/docs/install",
+    ),
+    (
+        "{} Running script with arguments: {}...",
+        "{} Uruchamianie skryptu z argumentami: {}...",
+    ),
+    ("Failed to wait for {}: {}", "Nie udało się poczekać na {}: {}"),
+    ("{} failed", "{} nie powiodło się"),
+    ("Not a wasm module: {}", "To nie jest moduł wasm: {}"),
+    (
+        "Unknown build graph format for '{}' (expected a .dot, .gv or .json path)",
+        "Nieznany format grafu kompilacji dla '{}' (oczekiwano ścieżki .dot, .gv lub .json)",
+    ),
+    (
+        "Failed to serialize build graph: {}",
+        "Nie udało się zserializować grafu kompilacji: {}",
+    ),
+    (
+        "Could not find Emscripten SDK.\n\
+            Please install it (https://emscripten.org/docs/getting_started/downloads.html)\n\
+            and set the 'EMSDK' environment variable to its installation folder",
+        "Nie znaleziono Emscripten SDK.\nZainstaluj je (https://emscripten.org/docs/getting_started/downloads.html)\ni ustaw zmienną środowiskową 'EMSDK' na jego katalog instalacyjny",
+    ),
+    ("Downloading Emscripten SDK...", "Pobieranie Emscripten SDK..."),
+    (
+        "Installing latest Emscripten toolchain (this may take a while)...",
+        "Instalowanie najnowszego toolchaina Emscripten (to może chwilę potrwać)...",
+    ),
+    ("Activating Emscripten toolchain...", "Aktywowanie toolchaina Emscripten..."),
+    ("Git operation failed", "Operacja git nie powiodła się"),
+    ("I/O operation failed", "Operacja wejścia/wyjścia nie powiodła się"),
+    ("TOML parsing failed", "Błąd parsowania TOML"),
+    ("Validation error", "Błąd walidacji"),
+    ("Build failed", "Kompilacja nie powiodła się"),
+    ("Process execution failed", "Uruchomienie procesu nie powiodło się"),
+    ("Build cancelled", "Kompilacja przerwana"),
+    (
+        "Failed to serialize hash store: {}",
+        "Nie udało się zserializować magazynu skrótów: {}",
+    ),
+    (
+        "Unknown [generate] vet entry '{}'. Expected one of: {}",
+        "Nieznany wpis vet '{}' w [generate]. Oczekiwano jednego z: {}",
+    ),
+    ("Initializing template repository...", "Inicjalizowanie repozytorium szablonu..."),
+    (
+        "Initializing template repository (sparse)...",
+        "Inicjalizowanie repozytorium szablonu (sparse)...",
+    ),
+    ("Checking out: {}...", "Pobieranie wersji: {}..."),
+    ("Sparse checkout failed: {}", "Sparse checkout nie powiódł się: {}"),
+    ("Invalid destination path", "Nieprawidłowa ścieżka docelowa"),
+    ("Version/branch '{}' not found", "Nie znaleziono wersji/gałęzi '{}'"),
+    ("Git clone failed: {}", "Klonowanie git nie powiodło się: {}"),
+    ("Fetching commit {}...", "Pobieranie commita {}..."),
+    ("Commit {} not found in {}: {}", "Nie znaleziono commita {} w {}: {}"),
+    ("Failed to read {}: {}", "Nie udało się odczytać {}: {}"),
+    ("Failed to start game: {}", "Nie udało się uruchomić gry: {}"),
+    (
+        "Failed to serialize build info: {}",
+        "Nie udało się zserializować informacji o kompilacji: {}",
+    ),
+    ("No recorded builds", "Brak zapisanych kompilacji"),
+    (
+        "No recorded builds. Run 'bonsai build' to create one.",
+        "Brak zapisanych kompilacji. Uruchom 'bonsai build', aby utworzyć pierwszą.",
+    ),
+    ("Build '{}' not found", "Nie znaleziono kompilacji '{}'"),
+    (
+        "Invalid [deploy.itch] project '{}' (expected \"user/game\")",
+        "Nieprawidłowy projekt [deploy.itch] '{}' (oczekiwano \"user/game\")",
+    ),
+    (
+        "Unknown [deploy.itch] target '{}' (expected one of: {})",
+        "Nieznany cel [deploy.itch] '{}' (oczekiwano jednego z: {})",
+    ),
+    (
+        "Invalid [deploy.itch.channels] {} channel '{}'",
+        "Nieprawidłowy kanał {} '{}' w [deploy.itch.channels]",
+    ),
+    ("Failed to untrack {}", "Nie udało się usunąć {} ze śledzenia"),
+    (
+        "Another build is running (pid {}). Use --wait to wait for it to finish",
+        "Trwa inna kompilacja (pid {}). Użyj --wait, aby poczekać na jej zakończenie",
+    ),
+    (
+        "Waiting for another build to finish (pid {})...",
+        "Oczekiwanie na zakończenie innej kompilacji (pid {})...",
+    ),
+    ("Invalid {}: {}", "Nieprawidłowy {}: {}"),
+    ("Failed to serialize {}: {}", "Nie udało się zserializować {}: {}"),
+    (
+        "{} System '{}' differs from commit {} in {}.",
+        "{} System '{}' różni się od commita {} zapisanego w {}.",
+    ),
+    (
+        "Systems in {} are not installed: {}. Run 'bonsai install' to restore them",
+        "Systemy z {} nie są zainstalowane: {}. Uruchom 'bonsai install', aby je przywrócić",
+    ),
+    ("Invalid manifest: {}", "Nieprawidłowy manifest: {}"),
+    ("Bonsai manifest not found at {}", "Nie znaleziono manifestu bonsai w {}"),
+    ("Manifest [systems] is not a table.", "[systems] w manifeście nie jest tabelą."),
+    (
+        "  + Auto-generating manifest for system: '{}'",
+        "  + Automatyczne generowanie manifestu systemu: '{}'",
+    ),
+    ("  + Discovered new local system: '{}'", "  + Wykryto nowy lokalny system: '{}'"),
+    (
+        "  ! Editable system '{}' points to missing path '{}'",
+        "  ! Edytowalny system '{}' wskazuje na nieistniejącą ścieżkę '{}'",
+    ),
+    (
+        "  + Relinking editable system: '{}'",
+        "  + Ponowne linkowanie edytowalnego systemu: '{}'",
+    ),
+    ("  - Pruning missing system: '{}'", "  - Usuwanie brakującego systemu: '{}'"),
+    (
+        "  - Removing unsafe system path (outside source): '{}'",
+        "  - Usuwanie niebezpiecznej ścieżki systemu (poza source): '{}'",
+    ),
+    (
+        "  ! Error verifying system path '{}': {}",
+        "  ! Błąd weryfikacji ścieżki systemu '{}': {}",
+    ),
+    (
+        "Manifest [shaders.programs] is not a table.",
+        "[shaders.programs] w manifeście nie jest tabelą.",
+    ),
+    (
+        "Manifest [target.{}] {} is not an array.",
+        "[target.{}] {} w manifeście nie jest tablicą.",
+    ),
+    ("Root not found: {}", "Nie znaleziono katalogu głównego: {}"),
+    ("System path not found: {}", "Nie znaleziono ścieżki systemu: {}"),
+    (
+        "Unterminated merge conflict starting at line {}",
+        "Niezakończony konflikt scalania od linii {}",
+    ),
+    (
+        "Nested merge conflict in the hunk starting at line {}",
+        "Zagnieżdżony konflikt scalania we fragmencie od linii {}",
+    ),
+    (
+        "Merge conflict at line {} is outside [systems], resolve it by hand first",
+        "Konflikt scalania w linii {} jest poza [systems], najpierw rozwiąż go ręcznie",
+    ),
+    ("Invalid choice for system '{}'", "Nieprawidłowy wybór dla systemu '{}'"),
+    (
+        "{} The page reported no wasm memory, was it opened in a browser?",
+        "{} Strona nie zgłosiła pamięci wasm, czy została otwarta w przeglądarce?",
+    ),
+    (
+        "{} Wasm heap: {}{} peak over {} page load(s)",
+        "{} Sterta wasm: {}{} szczytowo w ciągu {} załadowań strony",
+    ),
+    ("{} JS heap: {} peak", "{} Sterta JS: {} szczytowo"),
+    (
+        "{} The heap never grew past its initial size.",
+        "{} Sterta nigdy nie przekroczyła rozmiaru początkowego.",
+    ),
+    ("{} {} growth event(s):", "{} Zdarzenia wzrostu sterty ({}):"),
+    ("  ... and {} more", "  ... i {} więcej"),
+    (
+        "{} {} growth(s) failed, the page ran out of memory:",
+        "{} Nieudane wzrosty sterty ({}), stronie zabrakło pamięci:",
+    ),
+    (
+        "{} Set initial_memory_mb = {} under [web] in bonsai.toml to start at the peak.",
+        "{} Ustaw initial_memory_mb = {} w sekcji [web] pliku bonsai.toml, aby zacząć od wartości szczytowej.",
+    ),
+    ("Failed to run vcvarsall.bat: {}", "Nie udało się uruchomić vcvarsall.bat: {}"),
+    (
+        "vcvarsall.bat failed for architecture '{}':\n{}",
+        "vcvarsall.bat nie powiódł się dla architektury '{}':\n{}",
+    ),
+    ("Locating Visual Studio Build Tools...", "Wyszukiwanie Visual Studio Build Tools..."),
+    (
+        "The 'cl' command (MSVC compiler) was not found and no Visual Studio installation \
+                with C++ build tools could be located.\n\
+                Install 'Desktop development with C++' from https://visualstudio.microsoft.com/downloads \
+                or run this tool from the 'Visual Studio Developer Command Prompt'",
+        "Nie znaleziono polecenia 'cl' (kompilator MSVC) ani instalacji Visual Studio z narzędziami C++.\nZainstaluj 'Desktop development with C++' ze strony https://visualstudio.microsoft.com/downloads lub uruchom to narzędzie z 'Visual Studio Developer Command Prompt'",
+    ),
+    (
+        "Invalid [assets.atlas.sprites] pattern '{}': {}",
+        "Nieprawidłowy wzorzec [assets.atlas.sprites] '{}': {}",
+    ),
+    ("Using cached font: {}", "Użycie czcionki z pamięci podręcznej: {}"),
+    ("Packing pixel font: {} ({}px)...", "Pakowanie czcionki pikselowej: {} ({}px)..."),
+    ("Packing MSDF Vector font: {}...", "Pakowanie czcionki wektorowej MSDF: {}..."),
+    ("Failed to read font", "Nie udało się odczytać czcionki"),
+    (
+        "Failed to parse font with fontdue",
+        "Nie udało się sparsować czcionki przez fontdue",
+    ),
+    (
+        "{} Pixel font '{}' contains blurred/anti-aliased pixels at size {}px. Are you sure this is its native size?",
+        "{} Czcionka pikselowa '{}' zawiera rozmyte/wygładzone piksele w rozmiarze {}px. Czy na pewno to jej natywny rozmiar?",
+    ),
+    ("Failed to parse font", "Nie udało się sparsować czcionki"),
+    ("Failed to export MSDF atlas: {}", "Nie udało się wyeksportować atlasu MSDF: {}"),
+    (
+        "Failed to encode MSDF PNG to memory",
+        "Nie udało się zakodować PNG atlasu MSDF w pamięci",
+    ),
+    ("Failed to save font atlas: {}", "Nie udało się zapisać atlasu czcionki: {}"),
+    ("Packing texture atlas...", "Pakowanie atlasu tekstur..."),
+    (
+        "Sprite '{}' doesn't fit into an empty {}x{} atlas page. Raise [assets.atlas] max_size",
+        "Sprite '{}' nie mieści się na pustej stronie atlasu {}x{}. Zwiększ [assets.atlas] max_size",
+    ),
+    ("No images to pack in {}", "Brak obrazów do spakowania w {}"),
+    ("Failed to load {}: {}", "Nie udało się wczytać {}: {}"),
+    ("Tileset {} has a zero tile size", "Tileset {} ma zerowy rozmiar kafla"),
+    ("Failed to save atlas", "Nie udało się zapisać atlasu"),
+    ("Failed to encode PNG to memory", "Nie udało się zakodować PNG w pamięci"),
+    (
+        "Failed to serialize [assets.atlas] settings: {}",
+        "Nie udało się zserializować ustawień [assets.atlas]: {}",
+    ),
+    (
+        "Invalid [paths] {} '{}' (expected a directory other than the project root)",
+        "Nieprawidłowa ścieżka [paths] {} '{}' (oczekiwano katalogu innego niż katalog główny projektu)",
+    ),
+    (
+        "[paths] build '{}' contains the {} directory '{}'",
+        "[paths] build '{}' zawiera katalog {} '{}'",
+    ),
+    (
+        "Unknown build config '{}'. Add [profile.{}] to bonsai.toml or use one of: {}",
+        "Nieznana konfiguracja kompilacji '{}'. Dodaj [profile.{}] do bonsai.toml lub użyj jednej z: {}",
+    ),
+    ("[profile.{}] inherits in a cycle: {}", "[profile.{}] dziedziczy w cyklu: {}"),
+    (
+        "[profile.{}] inherits unknown profile '{}'",
+        "[profile.{}] dziedziczy po nieznanym profilu '{}'",
+    ),
+    (
+        "Invalid {} (expected 64 hex characters)",
+        "Nieprawidłowy {} (oczekiwano 64 znaków szesnastkowych)",
+    ),
+    ("Invalid public key: {}", "Nieprawidłowy klucz publiczny: {}"),
+    (
+        "Could not read signing key {}: {}",
+        "Nie udało się odczytać klucza podpisującego {}: {}",
+    ),
+    ("Failed to serialize provenance: {}", "Nie udało się zserializować provenance: {}"),
+    ("Could not read {}: {}", "Nie udało się odczytać {}: {}"),
+    (
+        "Failed to serialize build marker: {}",
+        "Nie udało się zserializować znacznika kompilacji: {}",
+    ),
+    ("Unreadable {}: {}", "Nie można odczytać {}: {}"),
+    (
+        "RenderDoc does not support macOS, use Xcode's Metal frame capture instead",
+        "RenderDoc nie obsługuje macOS, użyj zamiast tego przechwytywania klatek Metal w Xcode",
+    ),
+    (
+        "RenderDoc not found. Install it from https://renderdoc.org or set {} to its directory",
+        "Nie znaleziono RenderDoc. Zainstaluj go ze strony https://renderdoc.org lub ustaw {} na jego katalog",
+    ),
+    ("Could not find local data directory", "Nie znaleziono lokalnego katalogu danych"),
+    (
+        "No prebuilt sokol-shdc for {} {}. Build it from https://github.com/floooh/sokol-tools and set [toolchain] shdc in bonsai.toml",
+        "Brak gotowego sokol-shdc dla {} {}. Zbuduj go z https://github.com/floooh/sokol-tools i ustaw [toolchain] shdc w bonsai.toml",
+    ),
+    ("Failed to download sokol-shdc: {}", "Nie udało się pobrać sokol-shdc: {}"),
+    (
+        "Incomplete sokol-shdc download ({} of {} bytes)",
+        "Niepełne pobieranie sokol-shdc ({} z {} bajtów)",
+    ),
+    ("Downloaded sokol-shdc binary is empty", "Pobrany plik sokol-shdc jest pusty"),
+    (
+        "Failed to open sokol-shdc mirror file {}: {}",
+        "Nie udało się otworzyć pliku sokol-shdc z mirrora {}: {}",
+    ),
+    ("Downloading sokol-shdc for {}...", "Pobieranie sokol-shdc dla {}..."),
+    ("  Source: {}", "  Źródło: {}"),
+    (
+        "sokol-shdc set in [toolchain] shdc was not found: {}",
+        "Nie znaleziono sokol-shdc ustawionego w [toolchain] shdc: {}",
+    ),
+    ("Critical error resolving paths: {}", "Krytyczny błąd ustalania ścieżek: {}"),
+    (
+        "{} sokol-shdc at {} does not match its recorded checksum. Reinstalling...",
+        "{} sokol-shdc w {} nie zgadza się z zapisaną sumą kontrolną. Ponowna instalacja...",
+    ),
+    ("Failed to install sokol-shdc: {}", "Nie udało się zainstalować sokol-shdc: {}"),
+    (
+        "Make sure you have internet access and file write permissions, or point {} / [toolchain] shdc_base_url at a mirror.",
+        "Upewnij się, że masz dostęp do internetu i prawa zapisu plików, albo wskaż mirror przez {} / [toolchain] shdc_base_url.",
+    ),
+    (
+        "Unknown [toolchain] c_compiler '{}'. Expected one of: msvc, clang-cl, zig",
+        "Nieznany [toolchain] c_compiler '{}'. Oczekiwano jednego z: msvc, clang-cl, zig",
+    ),
+    (
+        "The '{}' command selected by [toolchain] c_compiler was not found in PATH",
+        "Nie znaleziono w PATH polecenia '{}' wybranego przez [toolchain] c_compiler",
+    ),
+    ("Sokol directory not found: {}", "Nie znaleziono katalogu sokol: {}"),
+    ("Unsupported OS: {}", "Nieobsługiwany system operacyjny: {}"),
+    ("Compiling sokol for {} [{}]...", "Kompilowanie sokol dla {} [{}]..."),
+    (
+        "Sokol compilation skipped (already compiled).",
+        "Pominięto kompilację sokol (już skompilowany).",
+    ),
+    ("Cleaning sokol artifacts...", "Czyszczenie artefaktów sokol..."),
+    ("Failed to create directory {}: {}", "Nie udało się utworzyć katalogu {}: {}"),
+    ("Building Windows DLLs...", "Budowanie bibliotek DLL dla Windows..."),
+    (
+        "Unsupported architecture for sokol: {}",
+        "Nieobsługiwana architektura dla sokol: {}",
+    ),
+    ("Compiling sokol (WASM, {})...", "Kompilowanie sokol (WASM, {})..."),
+    ("Cleaning WASM artifacts...", "Czyszczenie artefaktów WASM..."),
+    ("Failed to run emcc: {}", "Nie udało się uruchomić emcc: {}"),
+    (
+        "WASM compilation failed for {}:\n{}",
+        "Kompilacja WASM nie powiodła się dla {}:\n{}",
+    ),
+    ("Failed to run emar: {}", "Nie udało się uruchomić emar: {}"),
+    (
+        "WASM archiving failed for {}:\n{}",
+        "Archiwizacja WASM nie powiodła się dla {}:\n{}",
+    ),
+    (
+        "The 'emcc' command was not found.\n\
+        Please install the Emscripten SDK and run 'emsdk_env' to add it to your PATH",
+        "Nie znaleziono polecenia 'emcc'.\nZainstaluj Emscripten SDK i uruchom 'emsdk_env', aby dodać je do PATH",
+    ),
+    ("Failed to run {}: {}", "Nie udało się uruchomić {}: {}"),
+    ("{} compilation failed for {}:\n{}", "Kompilacja {} nie powiodła się dla {}:\n{}"),
+    ("{} failed for {}:\n{}", "{} nie powiodło się dla {}:\n{}"),
+    ("Failed to run zig cc: {}", "Nie udało się uruchomić zig cc: {}"),
+    (
+        "zig cc compilation failed for {}:\n{}",
+        "Kompilacja zig cc nie powiodła się dla {}:\n{}",
+    ),
+    ("Failed to run zig ar: {}", "Nie udało się uruchomić zig ar: {}"),
+    ("zig ar failed for {}:\n{}", "zig ar nie powiódł się dla {}:\n{}"),
+    ("unsupported macOS arch: {}", "nieobsługiwana architektura macOS: {}"),
+    ("Clang failed: {}", "Clang nie powiódł się: {}"),
+    ("Compilation failed for {}:\n{}", "Kompilacja nie powiodła się dla {}:\n{}"),
+    ("Ar failed: {}", "Ar nie powiódł się: {}"),
+    ("Archive failed for {}", "Archiwizacja nie powiodła się dla {}"),
+    ("Invalid exclude pattern '{}': {}", "Nieprawidłowy wzorzec wykluczenia '{}': {}"),
+    ("Invalid exclude patterns: {}", "Nieprawidłowe wzorce wykluczeń: {}"),
+    ("Failed to copy {}: {}", "Nie udało się skopiować {}: {}"),
+    ("Failed to create junction {} -> {}", "Nie udało się utworzyć junction {} -> {}"),
+    (
+        "Failed to serialize atlas stats: {}",
+        "Nie udało się zserializować statystyk atlasu: {}",
+    ),
+    (
+        "Systems '{}' and '{}' set different values for define {}",
+        "Systemy '{}' i '{}' ustawiają różne wartości dla define {}",
+    ),
+    (
+        "Invalid [tools] {} version '{}' (expected e.g. \"1.3\" or \"*\")",
+        "Nieprawidłowa wersja [tools] {} '{}' (oczekiwano np. \"1.3\" lub \"*\")",
+    ),
+    (
+        "{}: {}. Install it, or set a path in bonsai.toml with [tools] {} = {{ path = \"...\" }}",
+        "{}: {}. Zainstaluj go albo ustaw ścieżkę w bonsai.toml przez [tools] {} = {{ path = \"...\" }}",
+    ),
+    (
+        "Missing or outdated tools from [tools]:\n{}\nInstall them, or set a path with name = {{ version = \"...\", path = \"...\" }}",
+        "Brakujące lub przestarzałe narzędzia z [tools]:\n{}\nZainstaluj je albo ustaw ścieżkę przez name = {{ version = \"...\", path = \"...\" }}",
+    ),
+    ("[Y/N]", "[T/N]"),
+    ("Choose [1-{}]: ", "Wybierz [1-{}]: "),
+    (
+        "Failed to serialize update manifest: {}",
+        "Nie udało się zserializować manifestu aktualizacji: {}",
+    ),
+    (
+        "Invalid {} version '{}' (expected letters, digits and . - + _)",
+        "Nieprawidłowa wersja {} '{}' (oczekiwano liter, cyfr oraz . - + _)",
+    ),
+    (
+        "Engine version mismatch: {} says '{}' but [toolchain] engine is '{}'. Update bonsai.toml after upgrading the engine",
+        "Niezgodność wersji silnika: {} podaje '{}', a [toolchain] engine to '{}'. Zaktualizuj bonsai.toml po aktualizacji silnika",
+    ),
+    ("Failed to serialize versions: {}", "Nie udało się zserializować wersji: {}"),
+    ("Invalid [watch] pattern '{}': {}", "Nieprawidłowy wzorzec [watch] '{}': {}"),
+    ("Invalid [watch] patterns: {}", "Nieprawidłowe wzorce [watch]: {}"),
+    (
+        "Unknown [watch] stage '{}' for '{}'. Expected one of: {}",
+        "Nieznany etap [watch] '{}' dla '{}'. Oczekiwano jednego z: {}",
+    ),
+    ("Invalid [web] {} pattern '{}': {}", "Nieprawidłowy wzorzec [web] {} '{}': {}"),
+    (
+        "{} matches both [web] preload and [web] embed",
+        "{} pasuje jednocześnie do [web] preload i [web] embed",
+    ),
+    (
+        "{} matches both [web] {} and [web.packages] {}",
+        "{} pasuje jednocześnie do [web] {} i [web.packages] {}",
+    ),
+    (
+        "[web] {} pattern '{}' matches no asset files",
+        "Wzorzec [web] {} '{}' nie pasuje do żadnych plików zasobów",
+    ),
+    (
+        "Invalid [web.packages] name '{}' (expected lowercase letters, digits and underscores)",
+        "Nieprawidłowa nazwa [web.packages] '{}' (oczekiwano małych liter, cyfr i podkreśleń)",
+    ),
+    (
+        "Invalid [web.packages] {} pattern '{}': {}",
+        "Nieprawidłowy wzorzec [web.packages] {} '{}': {}",
+    ),
+    (
+        "Invalid [web.packages] {} patterns: {}",
+        "Nieprawidłowe wzorce [web.packages] {}: {}",
+    ),
+    (
+        "{} matches both [web.packages] {} and {}",
+        "{} pasuje jednocześnie do [web.packages] {} i {}",
+    ),
+    (
+        "Failed to serialize web packages: {}",
+        "Nie udało się zserializować pakietów webowych: {}",
+    ),
+    (
+        "{} The project lives on a Windows drive ({}) but bonsai runs inside WSL. \
+            File access across the boundary is very slow and file watching may be unreliable.",
+        "{} Projekt znajduje się na dysku Windows ({}), a bonsai działa w WSL. Dostęp do plików przez tę granicę jest bardzo wolny, a obserwowanie plików może być zawodne.",
+    ),
+    (
+        "  Move the project into the Linux filesystem (e.g. ~/projects), or run the Windows build of bonsai from '{}'.",
+        "  Przenieś projekt do systemu plików Linuksa (np. ~/projects) albo uruchom wersję bonsai dla Windows z '{}'.",
+    ),
+    (
+        "  Move the project into the Linux filesystem (e.g. ~/projects), or run the Windows build of bonsai.",
+        "  Przenieś projekt do systemu plików Linuksa (np. ~/projects) albo uruchom wersję bonsai dla Windows.",
+    ),
+    (
+        "{} The project lives inside a WSL filesystem ({}) but bonsai runs on Windows. \
+            File access across the boundary is very slow; run bonsai from inside WSL instead.",
+        "{} Projekt znajduje się w systemie plików WSL ({}), a bonsai działa w Windows. Dostęp do plików przez tę granicę jest bardzo wolny, uruchom bonsai wewnątrz WSL.",
+    ),
+    (
+        "Not a bonsai project: '{}'. (Missing bonsai.toml)",
+        "To nie jest projekt bonsai: '{}'. (Brak bonsai.toml)",
+    ),
+    ("Assets built successfully.", "Zasoby zbudowane pomyślnie."),
+    ("Assets cleaned.", "Zasoby wyczyszczone."),
+    ("Failed to create file watcher: {}", "Nie udało się utworzyć obserwatora plików: {}"),
+    ("Failed to watch {}: {}", "Nie udało się obserwować {}: {}"),
+    (
+        "Watching assets for changes (Ctrl+C to stop)...",
+        "Obserwowanie zmian zasobów (Ctrl+C, aby zakończyć)...",
+    ),
+    ("Assets up to date.", "Zasoby są aktualne."),
+    (
+        "No benchmark scenes. Add them to [bench] scenes in bonsai.toml",
+        "Brak scen benchmarku. Dodaj je do [bench] scenes w bonsai.toml",
+    ),
+    ("Building for desktop (release)...", "Budowanie wersji desktopowej (release)..."),
+    ("Benchmarking {}...", "Benchmark {}..."),
+    ("{}: did not exit within {}s", "{}: nie zakończył się w ciągu {}s"),
+    ("{}: game exited with {}", "{}: gra zakończyła się z {}"),
+    ("{}: regressed: {}", "{}: regresja: {}"),
+    ("Benchmark baseline saved.", "Zapisano punkt odniesienia benchmarku."),
+    (
+        "{} benchmark scene(s) failed or regressed by more than {}%",
+        "{} scen benchmarku nie powiodło się lub pogorszyło o więcej niż {}%",
+    ),
+    ("No benchmark regressions.", "Brak regresji w benchmarkach."),
+    ("Directory '{}' does not exist", "Katalog '{}' nie istnieje"),
+    (
+        "{} No signing key configured, the provenance is unsigned.",
+        "{} Nie skonfigurowano klucza podpisującego, provenance nie jest podpisane.",
+    ),
+    ("Build completed successfully.", "Kompilacja zakończona pomyślnie."),
+    ("Removed {} recorded builds.", "Usunięto {} zapisanych kompilacji."),
+    ("Project cleaned.", "Projekt wyczyszczony."),
+    (
+        "No [deploy.itch] section in bonsai.toml. Add one with project = \"user/game\"",
+        "Brak sekcji [deploy.itch] w bonsai.toml. Dodaj ją z project = \"user/game\"",
+    ),
+    (
+        "[profile.{}] link_assets would push a link instead of the assets, deploy another config",
+        "[profile.{}] link_assets wysłałby link zamiast zasobów, wdróż inną konfigurację",
+    ),
+    ("Pushing {} to {}:{}...", "Wysyłanie {} do {}:{}..."),
+    (
+        "Dry run finished, nothing was pushed.",
+        "Próba zakończona, nic nie zostało wysłane.",
+    ),
+    ("Deployed to {}.", "Wdrożono do {}."),
+    (
+        "No trigger given and no [docs.triggers] in bonsai.toml",
+        "Nie podano wyzwalacza i brak [docs.triggers] w bonsai.toml",
+    ),
+    ("Scanning for odin files in: {}", "Wyszukiwanie plików odin w: {}"),
+    ("Failed to read file: {}", "Nie udało się odczytać pliku: {}"),
+    ("Writing package pages...", "Zapisywanie stron pakietów..."),
+    (
+        "Docs generated successfully. Scanned {} files, created {} pages.",
+        "Dokumentacja wygenerowana pomyślnie. Przeskanowano plików: {}, utworzono stron: {}.",
+    ),
+    (
+        "Failed to serialize doctor report: {}",
+        "Nie udało się zserializować raportu doctor: {}",
+    ),
+    (
+        "{} of {} tools from [tools] are missing or outdated",
+        "Brakujące lub przestarzałe narzędzia z [tools]: {} z {}",
+    ),
+    (
+        "Unknown example '{}'. Available examples: {}",
+        "Nieznany przykład '{}'. Dostępne przykłady: {}",
+    ),
+    (
+        "This engine version has no bundled examples. Try '--version latest --refresh'",
+        "Ta wersja silnika nie zawiera przykładów. Spróbuj '--version latest --refresh'",
+    ),
+    (
+        "Creating sandbox for example '{}'...",
+        "Tworzenie piaskownicy dla przykładu '{}'...",
+    ),
+    (
+        "Nothing recorded yet, run 'bonsai build' first",
+        "Nic jeszcze nie zapisano, najpierw uruchom 'bonsai build'",
+    ),
+    (
+        "Failed to serialize rebuild record: {}",
+        "Nie udało się zserializować zapisu przebudowy: {}",
+    ),
+    (
+        "No rebuild record for '{}'. Run 'bonsai explain-build' to list the tracked outputs",
+        "Brak zapisu przebudowy dla '{}'. Uruchom 'bonsai explain-build', aby wyświetlić śledzone wyniki",
+    ),
+    ("'{}' matches several outputs: {}", "'{}' pasuje do kilku wyników: {}"),
+    ("Regenerating generated files...", "Ponowne generowanie plików..."),
+    ("Generated files are up to date.", "Wygenerowane pliki są aktualne."),
+    (
+        "{} generated file(s) are out of date. Run 'bonsai generate --write' and commit the result",
+        "Nieaktualne wygenerowane pliki: {}. Uruchom 'bonsai generate --write' i zatwierdź wynik",
+    ),
+    ("Generated files refreshed.", "Wygenerowane pliki odświeżone."),
+    ("Failed to serialize info: {}", "Nie udało się zserializować informacji: {}"),
+    ("'{}' does not exist", "'{}' nie istnieje"),
+    (
+        "No version information found at '{}'. Expected a bonsai project or build",
+        "Nie znaleziono informacji o wersji w '{}'. Oczekiwano projektu lub kompilacji bonsai",
+    ),
+    (
+        "Could not determine project name from the current directory",
+        "Nie udało się ustalić nazwy projektu z bieżącego katalogu",
+    ),
+    ("Project name cannot be empty", "Nazwa projektu nie może być pusta"),
+    (
+        "Project name contains invalid characters: {}",
+        "Nazwa projektu zawiera niedozwolone znaki: {}",
+    ),
+    ("'{}' exists but is not a directory", "'{}' istnieje, ale nie jest katalogiem"),
+    (
+        "Destination '{}' already exists and is not empty (use --here from inside it to merge)",
+        "Katalog docelowy '{}' już istnieje i nie jest pusty (użyj --here wewnątrz niego, aby scalić)",
+    ),
+    ("Cleaning up partial installation...", "Usuwanie częściowej instalacji..."),
+    ("Project '{}' initialized successfully.", "Projekt '{}' zainicjalizowany pomyślnie."),
+    ("Merging template files...", "Scalanie plików szablonu..."),
+    ("  ~ Skipped existing file: '{}'", "  ~ Pominięto istniejący plik: '{}'"),
+    (
+        "  {} file(s) already existed and were left untouched (use --force to overwrite).",
+        "  Plików, które już istniały i pozostały nietknięte: {} (użyj --force, aby nadpisać).",
+    ),
+    (
+        "Project '{}' initialized successfully ({} files added).",
+        "Projekt '{}' zainicjalizowany pomyślnie (dodano plików: {}).",
+    ),
+    ("Invalid template path: {}", "Nieprawidłowa ścieżka szablonu: {}"),
+    (
+        "Bonsai.toml manifest not found. Are you in a bonsai project?",
+        "Nie znaleziono manifestu bonsai.toml. Czy jesteś w projekcie bonsai?",
+    ),
+    ("Invalid system name.", "Nieprawidłowa nazwa systemu."),
+    (
+        "source/systems directory not found. Are you in a bonsai project?",
+        "Nie znaleziono katalogu source/systems. Czy jesteś w projekcie bonsai?",
+    ),
+    ("System '{}' is already installed at {}", "System '{}' jest już zainstalowany w {}"),
+    ("Installing system '{}'...", "Instalowanie systemu '{}'..."),
+    ("Installed {} successfully.", "Zainstalowano {} pomyślnie."),
+    (
+        "Nothing to install: no system URL given and no {}",
+        "Nic do zainstalowania: nie podano URL systemu i brak {}",
+    ),
+    (
+        "{} of {} locked systems installed, the rest were present.",
+        "Zainstalowano {} z {} zablokowanych systemów, pozostałe były obecne.",
+    ),
+    (
+        "Dependency '{}' in {} needs a git URL (name = \"user/repo\" or {{ git = \"...\" }})",
+        "Zależność '{}' w {} wymaga URL git (name = \"user/repo\" lub {{ git = \"...\" }})",
+    ),
+    ("Invalid dependency name '{}' in {}", "Nieprawidłowa nazwa zależności '{}' w {}"),
+    (
+        "Version conflict for system '{}':\n  - {}  ({})\n  + {}  ({})",
+        "Konflikt wersji systemu '{}':\n  - {}  ({})\n  + {}  ({})",
+    ),
+    ("Dependency cycle between systems: {}", "Cykl zależności między systemami: {}"),
+    (
+        "Resolving dependency '{}' of '{}'...",
+        "Rozwiązywanie zależności '{}' systemu '{}'...",
+    ),
+    (
+        "Could not read the checked out commit of {}",
+        "Nie udało się odczytać pobranego commita {}",
+    ),
+    (
+        "Not a valid Bonsai system: {} has no bonsai.toml",
+        "To nie jest prawidłowy system Bonsai: {} nie ma bonsai.toml",
+    ),
+    (
+        "The repository {} does not contain 'bonsai/systems/{}'. Structure mismatch.",
+        "Repozytorium {} nie zawiera 'bonsai/systems/{}'. Niezgodna struktura.",
+    ),
+    ("Copying system files of '{}'...", "Kopiowanie plików systemu '{}'..."),
+    (
+        "Found utilities. Installing to 'utils/{}'",
+        "Znaleziono narzędzia. Instalowanie do 'utils/{}'",
+    ),
+    ("Updating manifest...", "Aktualizowanie manifestu..."),
+    (
+        "{} System '{}' at commit {} does not match the checksum in {}.",
+        "{} System '{}' w commicie {} nie zgadza się z sumą kontrolną w {}.",
+    ),
+    ("System directory '{}' not found.", "Nie znaleziono katalogu systemu '{}'."),
+    ("Could not determine system name", "Nie udało się ustalić nazwy systemu"),
+    ("Linking system '{}'...", "Linkowanie systemu '{}'..."),
+    ("Linked {} from {} (editable).", "Podlinkowano {} z {} (edytowalny)."),
+    ("Invalid git SSH format", "Nieprawidłowy format git SSH"),
+    (
+        "Git SSH URL must contain a separator (: or /)",
+        "URL git SSH musi zawierać separator (: lub /)",
+    ),
+    ("Invalid URL: {}", "Nieprawidłowy URL: {}"),
+    ("URL has no path", "URL nie ma ścieżki"),
+    ("URL has no segments", "URL nie ma segmentów"),
+    (
+        "Could not determine system name from URL",
+        "Nie udało się ustalić nazwy systemu z URL",
+    ),
+    (
+        "The {} SDK can't be downloaded automatically. Download it from the partner site and pass it with --sdk <zip or directory>",
+        "SDK {} nie może zostać pobrane automatycznie. Pobierz je ze strony partnera i przekaż przez --sdk <zip lub katalog>",
+    ),
+    (
+        "The {} SDK is distributed under its own license: {}",
+        "SDK {} jest rozpowszechniane na własnej licencji: {}",
+    ),
+    ("Do you accept the SDK license?", "Czy akceptujesz licencję SDK?"),
+    ("SDK license not accepted", "Licencja SDK nie została zaakceptowana"),
+    (
+        "No {} redistributables found in '{}'",
+        "Nie znaleziono plików redystrybucyjnych {} w '{}'",
+    ),
+    ("Integrated {} ({} files in {}).", "Zintegrowano {} ({} plików w {})."),
+    ("SDK not found: {}", "Nie znaleziono SDK: {}"),
+    ("Extracting SDK...", "Rozpakowywanie SDK..."),
+    ("Invalid SDK archive: {}", "Nieprawidłowe archiwum SDK: {}"),
+    ("Failed to extract SDK: {}", "Nie udało się rozpakować SDK: {}"),
+    ("Downloading {}...", "Pobieranie {}..."),
+    ("Failed to download SDK: {}", "Nie udało się pobrać SDK: {}"),
+    ("Linting project...", "Lintowanie projektu..."),
+    ("{} lint issue(s) found", "Znalezione problemy lint: {}"),
+    ("No lint issues found.", "Nie znaleziono problemów lint."),
+    ("Manifest is clean.", "Manifest jest poprawny."),
+    (
+        "Manifest needs fixing: {} conflict hunk(s), {} duplicate and {} conflicting system(s). Run 'bonsai manifest fix'",
+        "Manifest wymaga naprawy: fragmenty konfliktów: {}, zduplikowane systemy: {}, sprzeczne systemy: {}. Uruchom 'bonsai manifest fix'",
+    ),
+    (
+        "Manifest is clean, nothing to fix.",
+        "Manifest jest poprawny, nie ma nic do naprawy.",
+    ),
+    (
+        "  ~ Merged {} conflict hunk(s) in [systems]",
+        "  ~ Scalono fragmenty konfliktów w [systems]: {}",
+    ),
+    (
+        "  - Removed duplicate system entry: '{}'",
+        "  - Usunięto zduplikowany wpis systemu: '{}'",
+    ),
+    ("  ~ Kept {} = {}", "  ~ Zachowano {} = {}"),
+    ("Normalized manifest formatting.", "Znormalizowano formatowanie manifestu."),
+    ("Fixed manifest.", "Naprawiono manifest."),
+    (
+        "{} already exists, pass --force to replace it",
+        "{} już istnieje, użyj --force, aby go zastąpić",
+    ),
+    ("Public key: {}", "Klucz publiczny: {}"),
+    (
+        "  Share {} with whoever verifies builds. In CI, put the contents of {} in {} instead of the file.",
+        "  Udostępnij {} osobom weryfikującym kompilacje. W CI umieść zawartość {} w {} zamiast pliku.",
+    ),
+    ("Wrote signing key to {}.", "Zapisano klucz podpisujący do {}."),
+    ("  builder: {}", "  kompilujący: {}"),
+    ("{} The provenance is not signed.", "{} Provenance nie jest podpisane."),
+    ("  {} signed by {}", "  {} podpisane przez {}"),
+    ("  {} bad signature from {}", "  {} nieprawidłowy podpis od {}"),
+    ("  - signed by {}, not checked{}", "  - podpisane przez {}, niesprawdzone{}"),
+    ("  {} {} does not match its digest", "  {} {} nie zgadza się ze swoim skrótem"),
+    ("  {} {} is missing", "  {} brak {}"),
+    ("  {} {} is not covered by the provenance", "  {} {} nie jest objęty provenance"),
+    ("{} verification failed: {}", "{} weryfikacja nie powiodła się: {}"),
+    ("{} files match the provenance{}.", "Pliki zgodne z provenance: {}{}."),
+    ("Unlinking system '{}'...", "Odlinkowywanie systemu '{}'..."),
+    ("Unlinked system '{}'", "Odlinkowano system '{}'"),
+    ("System '{}' not found.", "Nie znaleziono systemu '{}'."),
+    ("Operation cancelled.", "Operacja anulowana."),
+    ("Removing system '{}'...", "Usuwanie systemu '{}'..."),
+    ("Removing system '{}' utility...", "Usuwanie narzędzi systemu '{}'..."),
+    ("Removed system '{}'", "Usunięto system '{}'"),
+    (
+        "Not a bonsai project directory: '{}'. (Missing bonsai.toml)",
+        "To nie jest katalog projektu bonsai: '{}'. (Brak bonsai.toml)",
+    ),
+    ("Replay file not found: {}", "Nie znaleziono pliku nagrania: {}"),
+    ("Running project in: {}...", "Uruchamianie projektu w: {}..."),
+    ("Watch directory missing: {}", "Brak obserwowanego katalogu: {}"),
+    (
+        "Hot reloader waiting for asset changes...",
+        "Hot reloader czeka na zmiany zasobów...",
+    ),
+    ("Data reload failed: {}", "Przeładowanie danych nie powiodło się: {}"),
+    ("Repacking atlas...", "Ponowne pakowanie atlasu..."),
+    ("Packer failed: {}", "Pakowanie nie powiodło się: {}"),
+    ("Repacking font: {}...", "Ponowne pakowanie czcionki: {}..."),
+    ("font packing failed: {}", "pakowanie czcionki nie powiodło się: {}"),
+    (
+        "{} WebSocket hot-reload server running at ws://localhost:{}",
+        "{} Serwer hot-reload WebSocket działa pod ws://localhost:{}",
+    ),
+    ("Building for desktop...", "Budowanie wersji desktopowej..."),
+    ("Executable not found at: {}", "Nie znaleziono pliku wykonywalnego w: {}"),
+    ("Running desktop build...", "Uruchamianie wersji desktopowej..."),
+    ("Game exited with code: {}", "Gra zakończyła się z kodem: {}"),
+    ("Failed to write crash log: {}", "Nie udało się zapisać logu awarii: {}"),
+    ("Failed to wait for process: {}", "Nie udało się poczekać na proces: {}"),
+    ("Waiting for changes to rebuild...", "Oczekiwanie na zmiany do przebudowania..."),
+    (
+        "Game exited, waiting for changes to rebuild...",
+        "Gra zakończyła działanie, oczekiwanie na zmiany do przebudowania...",
+    ),
+    (
+        "Web build updated, reloading the page...",
+        "Wersja webowa zaktualizowana, przeładowywanie strony...",
+    ),
+    (
+        "Build errors are shown in the browser, waiting for changes...",
+        "Błędy kompilacji są widoczne w przeglądarce, oczekiwanie na zmiany...",
+    ),
+    ("Building for web...", "Budowanie wersji webowej..."),
+    ("Web rebuilds stopped: {}", "Przebudowy wersji webowej zatrzymane: {}"),
+    ("Starting web server...", "Uruchamianie serwera WWW..."),
+    (
+        "{} {}. Opening the page in the usual browser profile.",
+        "{} {}. Otwieranie strony w zwykłym profilu przeglądarki.",
+    ),
+    ("Web build does not exist: {}", "Wersja webowa nie istnieje: {}"),
+    ("Received Ctrl+C. Shutting down server...", "Otrzymano Ctrl+C. Zamykanie serwera..."),
+    ("Failed to set Ctrl+C handler: {}", "Nie udało się ustawić obsługi Ctrl+C: {}"),
+    (
+        "{} Serving web build at {}://{}:{}.",
+        "{} Udostępnianie wersji webowej pod {}://{}:{}.",
+    ),
+    ("  (CTRL+C to stop the server)", "  (CTRL+C, aby zatrzymać serwer)"),
+    ("Server running...", "Serwer działa..."),
+    ("Failed to start server: {}", "Nie udało się uruchomić serwera: {}"),
+    (
+        "'{}' is neither a web build (missing index.html) nor a bonsai project",
+        "'{}' nie jest ani wersją webową (brak index.html), ani projektem bonsai",
+    ),
+    (
+        "No web build in {}. Run 'bonsai build --web' first",
+        "Brak wersji webowej w {}. Najpierw uruchom 'bonsai build --web'",
+    ),
+    (
+        "Invalid shader name '{}'. Use snake_case, e.g. 'water_ripple'",
+        "Nieprawidłowa nazwa shadera '{}'. Użyj snake_case, np. 'water_ripple'",
+    ),
+    ("Shader '{}' already exists at {}", "Shader '{}' już istnieje w {}"),
+    ("Created shader '{}' at {}", "Utworzono shader '{}' w {}"),
+    (
+        "  Import it with: import \"game:{}/{}\"",
+        "  Zaimportuj go przez: import \"game:{}/{}\"",
+    ),
+    (
+        "Watching shaders for changes (Ctrl+C to stop)...",
+        "Obserwowanie zmian shaderów (Ctrl+C, aby zakończyć)...",
+    ),
+    ("Shaders up to date.", "Shadery są aktualne."),
+    ("Fetching sokol-odin ({})...", "Pobieranie sokol-odin ({})..."),
+    (
+        "'{}' does not look like sokol-odin (missing {}/c)",
+        "'{}' nie wygląda na sokol-odin (brak {}/c)",
+    ),
+    ("Recompiling sokol libraries...", "Ponowne kompilowanie bibliotek sokol..."),
+    ("Sokol upgraded to {}.", "Zaktualizowano sokol do {}."),
+    ("Failed to serialize report: {}", "Nie udało się zserializować raportu: {}"),
+    (
+        "Failed to serialize system info: {}",
+        "Nie udało się zserializować informacji o systemie: {}",
+    ),
+    ("Running {}...", "Uruchamianie {}..."),
+    ("{} test session(s) failed", "Nieudane sesje testowe: {}"),
+    ("All test sessions passed.", "Wszystkie sesje testowe zakończone powodzeniem."),
+    ("{}: replay desynced", "{}: nagranie się rozsynchronizowało"),
+    (
+        "No scenes to capture. Add them to [test] golden_scenes in bonsai.toml",
+        "Brak scen do przechwycenia. Dodaj je do [test] golden_scenes w bonsai.toml",
+    ),
+    ("Capturing {}...", "Przechwytywanie {}..."),
+    ("{}: the game did not write a screenshot", "{}: gra nie zapisała zrzutu ekranu"),
+    ("Failed to write {}: {}", "Nie udało się zapisać {}: {}"),
+    (
+        "{}: {}% of pixels differ ({} of {}), diff written to {}",
+        "{}: różni się {}% pikseli ({} z {}), różnica zapisana do {}",
+    ),
+    (
+        "{} golden scene(s) failed. Run 'bonsai test --golden --update-golden' if the change is intended",
+        "Nieudane sceny wzorcowe: {}. Uruchom 'bonsai test --golden --update-golden', jeśli zmiana jest zamierzona",
+    ),
+    ("All golden scenes match.", "Wszystkie sceny wzorcowe są zgodne."),
+    ("Unexpected path '{}'", "Nieoczekiwana ścieżka '{}'"),
+    ("  {} {} (local edit, kept)", "  {} {} (lokalna zmiana, zachowana)"),
+    ("  {} {} (local edit, {})", "  {} {} (lokalna zmiana, {})"),
+    (
+        "System '{}' is in {} but not installed. Run 'bonsai install' to restore it",
+        "System '{}' jest w {}, ale nie jest zainstalowany. Uruchom 'bonsai install', aby go przywrócić",
+    ),
+    ("Checking '{}' for updates...", "Sprawdzanie aktualizacji '{}'..."),
+    ("Version/branch '{}' not found in {}", "Nie znaleziono wersji/gałęzi '{}' w {}"),
+    ("Could not check out {} of {}: {}", "Nie udało się pobrać {} z {}: {}"),
+    (
+        "{} of {} does not contain 'bonsai/systems/{}'",
+        "{} z {} nie zawiera 'bonsai/systems/{}'",
+    ),
+    ("  (no file changes)", "  (brak zmian w plikach)"),
+    (
+        "--version needs exactly one system name",
+        "--version wymaga dokładnie jednej nazwy systemu",
+    ),
+    (
+        "No systems in {}, nothing to update.",
+        "Brak systemów w {}, nie ma czego aktualizować.",
+    ),
+    (
+        "System '{}' is not in {}. Only systems installed from git can be updated",
+        "Systemu '{}' nie ma w {}. Aktualizować można tylko systemy zainstalowane z git",
+    ),
+    (
+        "Local edits conflict with upstream changes in: {}. Nothing was changed in them, \
+             re-run with --force to overwrite the edits",
+        "Lokalne zmiany kolidują ze zmianami upstream w: {}. Nic w nich nie zmieniono, uruchom ponownie z --force, aby nadpisać zmiany",
+    ),
+    (
+        "Dry run: {} of {} systems would be updated, no files changed.",
+        "Próba: zaktualizowano by {} z {} systemów, żadne pliki nie zostały zmienione.",
+    ),
+    ("{} of {} systems updated.", "Zaktualizowano {} z {} systemów."),
+    ("up to date", "aktualny"),
+    ("unfinished", "niedokończony"),
+    ("rebuilt", "przebudowany"),
+    ("  Up to date when last checked at {}.", "  Aktualny przy ostatnim sprawdzeniu o {}."),
+    ("  Last rebuilt at {} because:", "  Ostatnio przebudowany o {}, ponieważ:"),
+    (
+        "  It hasn't been rebuilt since the rebuild log was started.",
+        "  Nie był przebudowywany od rozpoczęcia dziennika przebudów.",
+    ),
+    (
+        "  A rebuild started at {} and didn't finish (it failed or was stopped) because:",
+        "  Przebudowa rozpoczęta o {} nie została ukończona (nie powiodła się lub została przerwana), ponieważ:",
+    ),
+    ("  Rebuilt at {} because:", "  Przebudowany o {}, ponieważ:"),
+    ("  - ... and {} more", "  - ... i {} więcej"),
+    ("  - the output didn't exist", "  - wynik nie istniał"),
+    ("  - it was forced: {}", "  - wymuszono: {}"),
+    (
+        "  - {} was modified after the output was built",
+        "  - {} zmieniono po zbudowaniu wyniku",
+    ),
+    (
+        "  - {} changed (its content hash differs)",
+        "  - {} się zmienił (skrót zawartości jest inny)",
+    ),
+    ("  - {} is a new input", "  - {} jest nowym wejściem"),
+    ("  - {} is no longer an input", "  - {} nie jest już wejściem"),
+    (
+        "  - nothing recorded which inputs it was built from (first build with content hashes)",
+        "  - nie zapisano, z jakich wejść został zbudowany (pierwsza kompilacja ze skrótami zawartości)",
+    ),
+    ("  - the {} settings changed", "  - zmieniły się ustawienia etapu {}"),
+    (
+        "      (the previous settings weren't recorded)",
+        "      (poprzednie ustawienia nie zostały zapisane)",
+    ),
+    ("{} asset steps failed", "Nieudane kroki zasobów: {}"),
+    (
+        "  - the output was changed or replaced after it was built",
+        "  - wynik został zmieniony lub podmieniony po zbudowaniu",
+    ),
+    (
+        "Failed to serialize manifest history: {}",
+        "Nie udało się zserializować historii manifestu: {}",
+    ),
+    (" (undone)", " (cofnięte)"),
+    ("at line {}:", "w linii {}:"),
+    ("bonsai hasn't changed the manifest yet.", "bonsai nie zmienił jeszcze manifestu."),
+    ("No manifest change left to undo", "Nie ma już zmian manifestu do cofnięcia"),
+    (
+        "bonsai.toml was edited after the change from {} ({}). Run 'bonsai manifest undo --force' to restore the manifest from before it, losing the later edits",
+        "bonsai.toml został zmieniony po zmianie z {} ({}). Uruchom 'bonsai manifest undo --force', aby przywrócić manifest sprzed niej, tracąc późniejsze zmiany",
+    ),
+    ("Reverted the change '{}' made at {}.", "Cofnięto zmianę '{}' z {}."),
+    (
+        "bonsai.toml has no [profile], [assets.atlas], [web] or [toolchain] settings to export",
+        "bonsai.toml nie ma ustawień [profile], [assets.atlas], [web] ani [toolchain] do wyeksportowania",
+    ),
+    ("Invalid preset: {}", "Nieprawidłowy preset: {}"),
+    (
+        "Preset '{}' has no [profile], [assets.atlas], [web] or [toolchain] settings to import",
+        "Preset '{}' nie ma ustawień [profile], [assets.atlas], [web] ani [toolchain] do zaimportowania",
+    ),
+    ("[{}] in bonsai.toml isn't a table", "[{}] w bonsai.toml nie jest tabelą"),
+    ("The preset doesn't fit this project: {}", "Preset nie pasuje do tego projektu: {}"),
+    ("Exported preset '{}' to {}.", "Wyeksportowano preset '{}' do {}."),
+    ("Could not read preset {}: {}", "Nie udało się odczytać presetu {}: {}"),
+    (
+        "bonsai.toml already matches preset '{}'.",
+        "bonsai.toml jest już zgodny z presetem '{}'.",
+    ),
+    ("Preset '{}' would change {} setting(s).", "Preset '{}' zmieniłby ustawienia: {}."),
+    (
+        "Imported preset '{}', {} setting(s) changed.",
+        "Zaimportowano preset '{}', zmienione ustawienia: {}.",
+    ),
+    (
+        "Run 'bonsai manifest undo' to revert it.",
+        "Uruchom 'bonsai manifest undo', aby to cofnąć.",
+    ),
+    (
+        "{} Skipped link '{}': only links to files inside the copied directory are followed",
+        "{} Pominięto dowiązanie '{}': obsługiwane są tylko dowiązania do plików w kopiowanym katalogu",
+    ),
+    (
+        "{} Skipped '{}': it links outside the project",
+        "{} Pominięto '{}': wskazuje poza projekt",
+    ),
+    ("Invalid system name '{}'", "Nieprawidłowa nazwa systemu '{}'"),
+    ("[web] chunk_mb must be at least 1", "[web] chunk_mb musi wynosić co najmniej 1"),
+    ("Failed to serialize web chunks: {}", "Nie udało się zserializować fragmentów web: {}"),
+    (
+        "The web shell has no {} placeholder, [web] chunk_mb can't hold the game back",
+        "Szablon strony web nie ma znacznika {}, [web] chunk_mb nie może wstrzymać startu gry",
+    ),
+    (
+        "Invalid [assets.atlas.groups] name '{}' (expected lowercase letters, digits and underscores, not ending in _<number>)",
+        "Nieprawidłowa nazwa [assets.atlas.groups] '{}' (oczekiwano małych liter, cyfr i podkreśleń, bez końcówki _<liczba>)",
+    ),
+    (
+        "Invalid [assets.atlas.groups] {} pattern '{}': {}",
+        "Nieprawidłowy wzorzec [assets.atlas.groups] {} '{}': {}",
+    ),
+    (
+        "Invalid [assets.atlas.groups] {} patterns: {}",
+        "Nieprawidłowe wzorce [assets.atlas.groups] {}: {}",
+    ),
+    (
+        "{} matches both [assets.atlas.groups] {} and {}",
+        "{} pasuje jednocześnie do [assets.atlas.groups] {} i {}",
+    ),
+    (
+        "{} Atlas group '{}' matches no images.",
+        "{} Grupa atlasu '{}' nie pasuje do żadnego obrazu.",
+    ),
+    ("Generating the self-test project...", "Generowanie projektu autotestu..."),
+    ("  Self-test project kept at {}", "  Projekt autotestu zachowano w {}"),
+    ("Self-test passed.", "Autotest zakończony powodzeniem."),
+    (
+        "The engine template has no game package in {}",
+        "Szablon silnika nie ma pakietu gry w {}",
+    ),
+    ("Running the desktop build...", "Uruchamianie buildu desktopowego..."),
+    ("desktop build: {} was not written", "build desktopowy: nie zapisano {}"),
+    ("web build: {} was not written", "build web: nie zapisano {}"),
+    ("run: did not exit within {}s", "uruchomienie: gra nie zakończyła się w ciągu {}s"),
+    ("run: game exited with {}", "uruchomienie: gra zakończyła się z {}"),
+    ("{} self-test check(s) failed", "Nie powiodło się sprawdzeń autotestu: {}"),
+    ("atlas: sprite '{}' was not packed", "atlas: sprite '{}' nie został spakowany"),
+    (
+        "atlas: {} texture(s) written for {} page(s)",
+        "atlas: zapisano tekstur: {} dla stron: {}",
+    ),
+    ("atlas: no atlas statistics were written", "atlas: nie zapisano statystyk atlasu"),
+    (
+        "sprite metadata: SpriteName has no '{}'",
+        "metadane sprite'ów: SpriteName nie zawiera '{}'",
+    ),
+    ("shaders: {} was not compiled", "shadery: {} nie został skompilowany"),
+    (
+        "systems: '{}' is not registered in bonsai.toml",
+        "systemy: '{}' nie jest zarejestrowany w bonsai.toml",
+    ),
+    ("Invalid aseprite data for {}: {}", "Nieprawidłowe dane aseprite dla {}: {}"),
+    (
+        "Aseprite frame {}x{} at {},{} is outside the sheet exported from {}",
+        "Klatka Aseprite {}x{} w {},{} wychodzi poza arkusz wyeksportowany z {}",
+    ),
+    ("Exporting {}...", "Eksportowanie {}..."),
+    ("Failed to start aseprite: {}", "Nie udało się uruchomić aseprite: {}"),
+    ("aseprite failed to export {}: {}", "aseprite nie wyeksportował {}: {}"),
+    (
+        "Two Aseprite animations are named '{}'. Rename one of the files or tags",
+        "Dwie animacje Aseprite mają nazwę '{}'. Zmień nazwę jednego z plików lub tagów",
+    ),
+    ("Mirroring {}...", "Tworzenie kopii lustrzanej {}..."),
+    (
+        "The sokol-shdc in the bundle does not match its checksum",
+        "sokol-shdc w paczce nie zgadza się ze swoją sumą kontrolną",
+    ),
+    ("Adding sokol-shdc...", "Dodawanie sokol-shdc..."),
+    ("Failed to write the bundle: {}", "Nie udało się zapisać paczki: {}"),
+    ("Writing {}...", "Zapisywanie {}..."),
+    (
+        "Bundle written to {} ({} repositories, {}).",
+        "Paczka zapisana do {} (repozytoria: {}, {}).",
+    ),
+    (
+        "Prime a machine with 'bonsai bundle use {}', then create projects with 'bonsai init <name> --version {}'.",
+        "Przygotuj komputer poleceniem 'bonsai bundle use {}', a potem twórz projekty poleceniem 'bonsai init <nazwa> --version {}'.",
+    ),
+    ("Invalid bundle {}: {}", "Nieprawidłowa paczka {}: {}"),
+    (
+        "{} was made by bonsai {}, which writes a bundle format this version can't read",
+        "{} została utworzona przez bonsai {}, który zapisuje paczki w formacie nieczytelnym dla tej wersji",
+    ),
+    ("Unpacking {}...", "Rozpakowywanie {}..."),
+    ("The bundle {} has no sokol-shdc", "Paczka {} nie zawiera sokol-shdc"),
+    (
+        "{} The bundle was made on {}, its sokol-shdc and desktop libraries don't run on {}.",
+        "{} Paczka została utworzona na {}, jej sokol-shdc i biblioteki desktopowe nie działają na {}.",
+    ),
+    (
+        "Primed the cache with engine {} and {} repositories.",
+        "Wypełniono pamięć podręczną silnikiem {} i repozytoriami ({}).",
+    ),
+    (
+        "Create projects with 'bonsai init <name> --version {}'.",
+        "Twórz projekty poleceniem 'bonsai init <nazwa> --version {}'.",
+    ),
+    (
+        "Stopped after running longer than [limits] timeout_seconds ({}s)",
+        "Zatrzymano po przekroczeniu [limits] timeout_seconds ({}s)",
+    ),
+    ("[limits] {} must be greater than 0", "[limits] {} musi być większe od 0"),
+    (
+        "Maps cut the tileset image {} along different grids",
+        "Mapy dzielą obraz zestawu kafelków {} według różnych siatek",
+    ),
+    ("Invalid map {}: {}", "Nieprawidłowa mapa {}: {}"),
+    ("Failed to read {} used by {}: {}", "Nie udało się odczytać {} używanego przez {}: {}"),
+    (
+        "Map {} uses a tileset made of separate images. Use a tileset based on one image",
+        "Mapa {} używa zestawu kafelków z osobnych obrazów. Użyj zestawu opartego na jednym obrazie",
+    ),
+    (
+        "Map {} uses {} compression. Save it with zlib, gzip or no compression",
+        "Mapa {} używa kompresji {}. Zapisz ją z kompresją zlib, gzip lub bez kompresji",
+    ),
+    (
+        "Layer '{}' of {} uses a tileset without an image file",
+        "Warstwa '{}' w {} używa zestawu kafelków bez pliku obrazu",
+    ),
+    (
+        "Two maps are named '{}'. Rename one of the files or levels",
+        "Dwie mapy nazywają się '{}'. Zmień nazwę jednego z plików lub poziomów",
+    ),
+    (
+        "The sidecar of {} cuts it differently than the maps using it",
+        "Plik pomocniczy {} dzieli go inaczej niż mapy, które go używają",
+    ),
+    ("Map tileset image not found: {}", "Nie znaleziono obrazu zestawu kafelków mapy: {}"),
+    (
+        "Map tileset image {} is outside the project",
+        "Obraz zestawu kafelków mapy {} jest poza projektem",
+    ),
+    (
+        "Failed to serialize map tilesets: {}",
+        "Nie udało się zserializować zestawów kafelków map: {}",
+    ),
+    (
+        "[assets.fonts] '{}' matches no font in {}",
+        "[assets.fonts] '{}' nie pasuje do żadnej czcionki w {}",
+    ),
+    (
+        "[assets.fonts] {} has an invalid range '{}'",
+        "[assets.fonts] {} ma nieprawidłowy zakres '{}'",
+    ),
+    (
+        "[assets.fonts] {} is an msdf font and takes a single size",
+        "[assets.fonts] {} jest czcionką msdf i przyjmuje jeden rozmiar",
+    ),
+    (
+        "[assets.fonts] {} needs sizes, its file name doesn't end in a pixel size",
+        "[assets.fonts] {} wymaga sizes, nazwa jej pliku nie kończy się rozmiarem w pikselach",
+    ),
+    (
+        "[assets.fonts] {} sizes and distance_range must be greater than 0",
+        "[assets.fonts] {} sizes i distance_range muszą być większe od 0",
+    ),
+    ("Baking font: {} ({}px, {})...", "Wypiekanie czcionki: {} ({}px, {})..."),
+    (
+        "Failed to bake font {} at {}px: {}",
+        "Nie udało się wypiec czcionki {} w rozmiarze {}px: {}",
+    ),
+    (
+        "the glyphs don't fit into one {}x{} page, bake fewer characters or a smaller size",
+        "glify nie mieszczą się na jednej stronie {}x{}, wypiecz mniej znaków lub mniejszy rozmiar",
+    ),
+    ("Docs generation crashed", "Generowanie dokumentacji uległo awarii"),
+    (
+        "Dry run finished, packages are in {}. Nothing was committed, tagged or pushed.",
+        "Próba zakończona, paczki są w {}. Nic nie zostało zacommitowane, otagowane ani wypchnięte.",
+    ),
+    ("Failed to read changelog {}: {}", "Nie udało się odczytać dziennika zmian {}: {}"),
+    ("Failed to run git: {}", "Nie udało się uruchomić gita: {}"),
+    ("Nothing to package in {}", "Brak plików do spakowania w {}"),
+    ("Released {} {}.", "Wydano {} {}."),
+    ("Running tests...", "Uruchamianie testów..."),
+    (
+        "Tag {} already exists, bump [project] version first",
+        "Tag {} już istnieje, najpierw podbij wersję w [project]",
+    ),
+    (
+        "The working tree has uncommitted changes, commit or stash them first (or pass --allow-dirty):\n{}",
+        "Drzewo robocze ma niezacommitowane zmiany, najpierw je zacommituj lub schowaj (albo użyj --allow-dirty):\n{}",
+    ),
+    (
+        "Unknown [release] deploy '{}' (expected one of: {})",
+        "Nieznane wdrożenie '{}' w [release] (oczekiwano jednego z: {})",
+    ),
+    (
+        "Unknown [release] target '{}' (expected one of: {})",
+        "Nieznany cel '{}' w [release] (oczekiwano jednego z: {})",
+    ),
+    (
+        "[deploy.itch] pushes {} builds, but [release] targets don't build it",
+        "[deploy.itch] wypycha buildy {}, ale cele [release] go nie budują",
+    ),
+    (
+        "[profile.{}] link_assets would package a link instead of the assets, release another config",
+        "link_assets w [profile.{}] spakowałoby link zamiast zasobów, wydaj inną konfigurację",
+    ),
+    (
+        "[release] deploy lists itch, but bonsai.toml has no [deploy.itch] section",
+        "[release] deploy wymienia itch, ale bonsai.toml nie ma sekcji [deploy.itch]",
+    ),
+    ("git {} failed: {}", "git {} nie powiódł się: {}"),
+    ("{} already has a section for {}", "{} ma już sekcję dla {}"),
+    (
+        "{} has no '## Unreleased' section to release",
+        "{} nie ma sekcji '## Unreleased' do wydania",
+    ),
+    (
+        "Another build is running. Use --wait to wait for it to finish",
+        "Trwa inna kompilacja. Użyj --wait, aby poczekać na jej zakończenie",
+    ),
+    (
+        "Waiting for another build to finish...",
+        "Oczekiwanie na zakończenie innej kompilacji...",
+    ),
+    (
+        "Failed to serialize staged files: {}",
+        "Nie udało się zserializować listy skopiowanych plików: {}",
+    ),
+    ("Failed to download {}: {}", "Nie udało się pobrać {}: {}"),
+    ("{} does not hold a sha256 checksum", "{} nie zawiera sumy kontrolnej sha256"),
+    (
+        "{} / [toolchain] shdc_sha256 is not a sha256 checksum: {}",
+        "{} / [toolchain] shdc_sha256 nie jest sumą kontrolną sha256: {}",
+    ),
+    (
+        "No checksum for the sokol-shdc at {}. Publish {}.{} next to it, or set {} / [toolchain] shdc_sha256",
+        "Brak sumy kontrolnej dla sokol-shdc pod {}. Opublikuj obok niego {}.{} albo ustaw {} / [toolchain] shdc_sha256",
+    ),
+    (
+        "The sokol-shdc from {} does not match its checksum (expected {}, got {})",
+        "sokol-shdc z {} nie zgadza się ze swoją sumą kontrolną (oczekiwano {}, otrzymano {})",
+    ),
+    (
+        "Manifest needs fixing: [atlas] is deprecated and should be [assets.atlas]. Run 'bonsai manifest fix'",
+        "Manifest wymaga naprawy: [atlas] jest przestarzałe i powinno być [assets.atlas]. Uruchom 'bonsai manifest fix'",
+    ),
+    ("  ~ Moved [atlas] to [assets.atlas]", "  ~ Przeniesiono [atlas] do [assets.atlas]"),
+    (
+        "{} [atlas] in bonsai.toml is deprecated, run 'bonsai manifest fix' to move it to [assets.atlas].",
+        "{} [atlas] w bonsai.toml jest przestarzałe, uruchom 'bonsai manifest fix', aby przenieść je do [assets.atlas].",
+    ),
+    (
+        "bonsai.toml has both [atlas] and [assets.atlas], move the [atlas] settings into [assets.atlas] by hand",
+        "bonsai.toml ma zarówno [atlas], jak i [assets.atlas], przenieś ustawienia [atlas] do [assets.atlas] ręcznie",
+    ),
+    ("Atlas: {}, {} sprites", "Atlas: {}, sprite'y: {}"),
+    (
+        "  Fill: {}% ({} px used, {} px wasted)",
+        "  Wypełnienie: {}% (zajęte px: {}, zmarnowane px: {})",
+    ),
+    (
+        "  Transparent borders: {} px of untrimmed sprites, [assets.atlas] trim = true cuts them",
+        "  Przezroczyste krawędzie: {} px nieprzyciętych sprite'ów, [assets.atlas] trim = true je usuwa",
+    ),
+    ("  Largest sprites:", "  Największe sprite'y:"),
+    (
+        "Atlas: not packed yet (run a build first)",
+        "Atlas: jeszcze nie spakowany (najpierw uruchom kompilację)",
+    ),
+    ("Textures:", "Tekstury:"),
+    ("Targets:", "Cele:"),
+    ("  {}: {} of files, {} of texture memory", "  {}: pliki {}, pamięć tekstur {}"),
+    ("Audio", "Audio"),
+    ("Fonts", "Czcionki"),
+    ("{}: {} files, {}", "{}: pliki: {}, {}"),
+    ("Usage (last {} days):", "Użycie (ostatnie dni: {}):"),
+    ("  No commands recorded yet.", "  Nie zapisano jeszcze żadnych poleceń."),
+    (", {}% cache hits", ", trafienia w cache: {}%"),
+    (
+        "  {}: {} runs ({} failed), avg {}s{}",
+        "  {}: uruchomienia: {} (nieudane: {}), średnio {}s{}",
+    ),
+    ("  Slowest stages:", "  Najwolniejsze etapy:"),
+    ("    {}: avg {}s", "    {}: średnio {}s"),
+    ("before", "przed"),
+    ("after", "po"),
+    ("delta", "różnica"),
+    ("Total: {} -> {} ({})", "Razem: {} -> {} ({})"),
+    ("binary", "plik binarny"),
+    ("wasm section", "sekcja wasm"),
+    ("file", "plik"),
+    ("shader outputs", "wyjścia shaderów"),
+    ("added", "dodany"),
+    ("removed", "usunięty"),
+    ("changed", "zmieniony"),
+    ("engine {}, cli {}, project {}", "silnik {}, cli {}, projekt {}"),
+    ("Project:", "Projekt:"),
+    ("Current", "Obecne"),
+    ("Build ({})", "Kompilacja ({})"),
+    ("No builds found.", "Nie znaleziono kompilacji."),
+    (
+        "No fonts directory found, skipping build-time font packing.",
+        "Nie znaleziono katalogu fonts, pomijam pakowanie czcionek podczas kompilacji.",
+    ),
+    ("Size for font {} found: {}px", "Znaleziono rozmiar czcionki {}: {}px"),
+    ("No perfect pixel size found for {}.", "Nie znaleziono idealnego rozmiaru pikseli dla {}."),
+    (
+        "Recommendation: Rename your file to {}_{}.ttf/otf",
+        "Zalecenie: zmień nazwę pliku na {}_{}.ttf/otf",
+    ),
+    ("Removed {}", "Usunięto {}"),
+    ("Cleaned generated assets.", "Wyczyszczono wygenerowane zasoby."),
+    (
+        "Font {} has no glyph for {} of the {} baked characters",
+        "Czcionka {} nie ma glifów dla {} z {} wypiekanych znaków",
+    ),
+    ("Opened browser with '{}'.", "Otwarto przeglądarkę przez '{}'."),
+    ("Opened {} with a temporary profile in {}.", "Otwarto {} z tymczasowym profilem w {}."),
+    (
+        "Core shader compilation skipped for {} (already compiled).",
+        "Pominięto kompilację shadera rdzenia {} (już skompilowany).",
+    ),
+    ("Not vetting systems: {}.", "Bez sprawdzania systemów: {}."),
+    (
+        "Building without -strict-style for systems: {}.",
+        "Kompilacja bez -strict-style dla systemów: {}.",
+    ),
+    ("Profile '{}': {}.", "Profil '{}': {}."),
+    (
+        "Compile skipped, sources and compile settings are unchanged.",
+        "Pominięto kompilację, źródła i ustawienia kompilacji się nie zmieniły.",
+    ),
+    (
+        "Asset names changed, the game needs to be recompiled.",
+        "Nazwy zasobów się zmieniły, gra wymaga ponownej kompilacji.",
+    ),
+    (
+        "Linked build assets to the source assets directory.",
+        "Podlinkowano zasoby kompilacji do źródłowego katalogu zasobów.",
+    ),
+    ("Copied {} changed asset files.", "Skopiowano zmienione pliki zasobów: {}."),
+    ("Core preload bundle: {}.", "Pakiet wstępnie ładowanych plików rdzenia: {}."),
+    ("Embedded into the wasm: {} files, {}.", "Osadzono w wasm: pliki: {}, {}."),
+    ("Web package '{}': {} files, {}.", "Pakiet web '{}': pliki: {}, {}."),
+    (
+        "Link skipped, its inputs and link settings are unchanged.",
+        "Pominięto linkowanie, jego wejścia i ustawienia linkowania się nie zmieniły.",
+    ),
+    ("Split {} data bundles into {} chunks.", "Podzielono pakiety danych ({}) na części: {}."),
+    ("Cleaned build directory.", "Wyczyszczono katalog kompilacji."),
+    ("Cleaned shader output {}.", "Wyczyszczono wyjście shaderów {}."),
+    ("Cleaned shader outputs.", "Wyczyszczono wyjścia shaderów."),
+    ("Cleaned staged web files.", "Wyczyszczono przygotowane pliki web."),
+    (
+        "Change detected, cancelling the running rebuild...",
+        "Wykryto zmianę, przerywam trwającą przebudowę...",
+    ),
+    (
+        "Saved Emscripten SDK path to the global config: {}",
+        "Zapisano ścieżkę Emscripten SDK w globalnej konfiguracji: {}",
+    ),
+    ("Emscripten SDK installed.", "Zainstalowano Emscripten SDK."),
+    (
+        "Modification times are unreliable ({}), using content hashes for up-to-date checks.",
+        "Czasy modyfikacji są niewiarygodne ({}), do sprawdzania aktualności używam skrótów zawartości.",
+    ),
+    ("Cleaned the content hash cache.", "Wyczyszczono pamięć podręczną skrótów zawartości."),
+    ("Download complete.", "Pobieranie zakończone."),
+    ("Removed old build {}", "Usunięto starą kompilację {}"),
+    ("Pushed build {} to {}:{} as version {}.", "Wysłano kompilację {} do {}:{} jako wersję {}."),
+    ("Importing MSVC environment from: {}", "Importowanie środowiska MSVC z: {}"),
+    ("MSDF Font {} Atlas generated ({}x{})", "Wygenerowano atlas czcionki MSDF {} ({}x{})"),
+    ("Atlas is up to date. Skipping packing.", "Atlas jest aktualny. Pomijam pakowanie."),
+    (
+        "No images to pack in assets directory. Skipping packing.",
+        "Brak obrazów do spakowania w katalogu zasobów. Pomijam pakowanie.",
+    ),
+    ("Slicing tileset found: {}", "Cięcie znalezionego tilesetu: {}"),
+    ("Atlas generated at {} ({}x{})", "Wygenerowano atlas w {} ({}x{})"),
+    ("Building for {} ({}).", "Kompilacja dla {} ({})."),
+    ("Installed sokol-shdc to {}", "Zainstalowano sokol-shdc w {}"),
+    ("Using vendored sokol-shdc: {}", "Używam dołączonego sokol-shdc: {}"),
+    ("Cleaned sokol libraries.", "Wyczyszczono biblioteki sokol."),
+    ("Compiling sokol with '{}'.", "Kompilacja sokol przez '{}'."),
+    (
+        "Sokol sources changed since the libraries were compiled, recompiling.",
+        "Źródła sokol zmieniły się od kompilacji bibliotek, kompiluję ponownie.",
+    ),
+    (
+        "Sokol sources are newer than the compiled libraries, recompiling.",
+        "Źródła sokol są nowsze niż skompilowane biblioteki, kompiluję ponownie.",
+    ),
+    (
+        "Copied prebuilt sokol libraries from an offline bundle.",
+        "Skopiowano gotowe biblioteki sokol z pakietu offline.",
+    ),
+    ("Atlas {}: {} sprites, {}% filled", "Atlas {}: sprite'y: {}, wypełnienie {}%"),
+    ("Rebuilding asset stages: {}", "Przebudowa etapów zasobów: {}"),
+    (
+        "Set [assets.atlas] packer = \"{}\" and sort = \"{}\" in bonsai.toml to use the smallest layout.",
+        "Ustaw [assets.atlas] packer = \"{}\" i sort = \"{}\" w bonsai.toml, aby użyć najmniejszego układu.",
+    ),
+    ("size", "rozmiar"),
+    ("fill", "wypełn."),
+    (
+        "{}: no baseline yet. Run 'bonsai bench --save-baseline' to store one.",
+        "{}: brak punktu odniesienia. Uruchom 'bonsai bench --save-baseline', aby go zapisać.",
+    ),
+    (
+        "mean {}ms  p50 {}ms  p95 {}ms  p99 {}ms  max {}ms  ({} frames)",
+        "średnio {}ms  p50 {}ms  p95 {}ms  p99 {}ms  maks. {}ms  (klatki: {})",
+    ),
+    ("Building project in: '{}'", "Kompilacja projektu w: '{}'"),
+    ("Building for web ({}).", "Kompilacja dla web ({})."),
+    ("Wrote host config {}.", "Zapisano konfigurację hosta {}."),
+    ("Building for desktop ({}).", "Kompilacja dla desktop ({})."),
+    ("Wrote build graph to {}.", "Zapisano graf kompilacji do {}."),
+    ("Wrote update manifest for build {}.", "Zapisano manifest aktualizacji dla kompilacji {}."),
+    ("Wrote provenance for build {}.", "Zapisano pochodzenie kompilacji {}."),
+    ("Recorded build {}.", "Zapisano kompilację {}."),
+    ("Opening {}", "Otwieranie {}"),
+    ("Bundled {} sokol libraries.", "Dołączono biblioteki sokol: {}."),
+    ("Preset '{}' from {} (bonsai {})", "Preset '{}' z {} (bonsai {})"),
+    ("Preset '{}' (bonsai {})", "Preset '{}' (bonsai {})"),
+    ("Skipping entry: {}", "Pomijam wpis: {}"),
+    ("Wrote {} for mdBook.", "Zapisano {} dla mdBook."),
+    ("Wrote {} for Docusaurus.", "Zapisano {} dla Docusaurus."),
+    ("Generated package: {}", "Wygenerowano pakiet: {}"),
+    ("Tools:", "Narzędzia:"),
+    ("Running example '{}' from {}.", "Uruchamianie przykładu '{}' z {}."),
+    ("Updated {}.", "Zaktualizowano {}."),
+    ("stale: {}", "nieaktualny: {}"),
+    ("Initializing project '{}'.", "Inicjalizacja projektu '{}'."),
+    (
+        "Initializing project '{}' in the current directory.",
+        "Inicjalizacja projektu '{}' w bieżącym katalogu.",
+    ),
+    ("Installed dependencies: {}.", "Zainstalowano zależności: {}."),
+    ("Installed dependencies of '{}': {}.", "Zainstalowano zależności '{}': {}."),
+    ("required by {}", "wymagany przez {}"),
+    ("requested", "żądany"),
+    ("installed, {}", "zainstalowany, {}"),
+    ("installed", "zainstalowany"),
+    ("Skipping {} (not in this SDK).", "Pomijam {} (brak w tym SDK)."),
+    ("Keeping existing loader at {}.", "Zachowuję istniejący loader w {}."),
+    ("Generated loader at {}.", "Wygenerowano loader w {}."),
+    ("fixed", "naprawiono"),
+    (" (fixable with --fix)", " (do naprawienia przez --fix)"),
+    ("warning", "ostrzeżenie"),
+    (
+        "Set [provenance] signing_key in ~/.bonsai/config.toml.",
+        "Ustaw [provenance] signing_key w ~/.bonsai/config.toml.",
+    ),
+    ("Packaged {}.", "Spakowano {}."),
+    ("{} is up to date.", "{} jest aktualny."),
+    ("Wrote {}.", "Zapisano {}."),
+    ("Would release the Unreleased section of {}.", "Sekcja Unreleased z {} zostałaby wydana."),
+    ("Would tag the release commit as {}.", "Commit wydania zostałby oznaczony jako {}."),
+    ("Committed {}.", "Zatwierdzono {}."),
+    ("Tagged {}.", "Oznaczono {}."),
+    ("Docs are up to date.", "Dokumentacja jest aktualna."),
+    ("Reloaded {} data file(s).", "Przeładowano pliki danych: {}."),
+    (
+        "Font {} is baked by [assets.fonts], rebuild to see the change.",
+        "Czcionka {} jest wypiekana przez [assets.fonts], przebuduj, aby zobaczyć zmianę.",
+    ),
+    ("Refreshed assets in {} ms.", "Odświeżono zasoby w {} ms."),
+    ("Crash log written to {}", "Zapisano log awarii do {}"),
+    ("No frame was captured.", "Nie przechwycono żadnej klatki."),
+    ("{} changed, rebuilding...", "{} się zmienił, przebudowa..."),
+    (
+        "Closed {} and deleted its temporary profile.",
+        "Zamknięto {} i usunięto tymczasowy profil.",
+    ),
+    ("Capture saved: {}", "Zapisano przechwycenie: {}"),
+    (
+        "Self-test seed {}, rerun it with --seed {}.",
+        "Ziarno autotestu {}, powtórz je przez --seed {}.",
+    ),
+    (
+        "Generated {} sprites, {} shaders and {} systems.",
+        "Wygenerowano sprite'y: {}, shadery: {} i systemy: {}.",
+    ),
+    (
+        "Updated {} sokol source and binding files.",
+        "Zaktualizowano pliki źródłowe i bindingi sokol: {}.",
+    ),
+    (
+        "Web libraries will be recompiled on the next web build.",
+        "Biblioteki web zostaną przekompilowane przy następnej kompilacji web.",
+    ),
+    ("Collecting asset statistics in: '{}'", "Zbieranie statystyk zasobów w: '{}'"),
+    ("{}: passed", "{}: zaliczony"),
+    ("{}: golden image written to {}", "{}: obraz wzorcowy zapisany do {}"),
+    ("{}: matches golden image", "{}: zgodny z obrazem wzorcowym"),
+    ("{} is up to date ({}).", "{} jest aktualny ({})."),
+];
@@ -0,0 +1,266 @@
+use crate::manifest::Manifest;
+use crate::paths::ProjectPaths;
+use crate::system_config::SystemConfig;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+// shipped with the compiler, resolved from `odin root`
+const COMPILER_COLLECTIONS: &[&str] = &["base", "core", "vendor", "shared"];
+const SYSTEMS_DIR: &str = "bonsai/systems";
+const SYSTEMS_PREFIX: &str = "systems/";
+
+/// An import that odin would fail to resolve, with a suggestion where one exists.
+pub struct ImportIssue {
+    pub file: PathBuf,
+    pub line: usize,
+    pub message: String,
+}
+
+struct Import {
+    line: usize,
+    alias: Option<String>,
+    path: String,
+}
+
+fn parse_imports(source: &str, pattern: &Regex) -> Vec<Import> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let captures = pattern.captures(line.trim_start())?;
+            Some(Import {
+                line: index + 1,
+                alias: captures.get(1).map(|m| m.as_str().to_string()),
+                path: captures[2].to_string(),
+            })
+        })
+        .collect()
+}
+
+fn odin_files(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "odin"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn package_name(file: &Path) -> Option<String> {
+    fs::read_to_string(file)
+        .ok()?
+        .lines()
+        .map(str::trim)
+        .find_map(|l| l.strip_prefix("package "))
+        .map(|name| name.trim().to_string())
+}
+
+// every package in a directory must share one name, otherwise odin refuses it
+fn package_names(dir: &Path) -> BTreeMap<String, Vec<PathBuf>> {
+    let mut names: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for file in odin_files(dir) {
+        if let Some(name) = package_name(&file) {
+            names.entry(name).or_default().push(file);
+        }
+    }
+    names
+}
+
+// the collections compile_project passes to odin
+fn project_collections(paths: &ProjectPaths) -> Vec<(&'static str, PathBuf)> {
+    vec![("bonsai", PathBuf::from("bonsai")), ("game", paths.game())]
+}
+
+// import strings for project directories named like the missing package
+fn suggestions(missing: &str, collections: &[(&'static str, PathBuf)]) -> Vec<String> {
+    let Some(name) = missing.rsplit('/').next().filter(|n| !n.is_empty()) else {
+        return Vec::new();
+    };
+
+    let mut found = Vec::new();
+    for (collection, root) in collections {
+        for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+            if entry.file_type().is_dir()
+                && entry.file_name().to_str() == Some(name)
+                && !odin_files(entry.path()).is_empty()
+                && let Ok(relative) = entry.path().strip_prefix(root)
+            {
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                let import = format!("{}:{}", collection, relative);
+                if !found.contains(&import) {
+                    found.push(import);
+                }
+            }
+        }
+    }
+    found
+}
+
+fn missing_message(
+    import: &str,
+    collection: Option<&str>,
+    collections: &[(&'static str, PathBuf)],
+    manifest: &Manifest,
+) -> String {
+    let mut message = format!("Cannot find package \"{}\".", import);
+
+    let sub_path = import.split_once(':').map_or(import, |(_, path)| path);
+    if collection == Some("bonsai")
+        && let Some(system) = sub_path
+            .strip_prefix(SYSTEMS_PREFIX)
+            .and_then(|rest| rest.split('/').next())
+    {
+        if manifest.systems.contains_key(system) {
+            message.push_str(&format!(
+                " System '{}' is listed in bonsai.toml but missing from bonsai/systems, reinstall it with 'bonsai install'.",
+                system
+            ));
+        } else {
+            message.push_str(&format!(
+                " System '{}' is not installed, add it with 'bonsai install <url>'.",
+                system
+            ));
+        }
+        return message;
+    }
+
+    match suggestions(sub_path, collections).as_slice() {
+        [] => {}
+        [only] => message.push_str(&format!(" Did you mean \"{}\"?", only)),
+        many => message.push_str(&format!(
+            " Did you mean one of: {}?",
+            many.iter()
+                .map(|s| format!("\"{}\"", s))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+    message
+}
+
+/// Resolves every import in the game sources and installed systems against
+/// the collection paths odin is given, so failures can be reported before compiling.
+/// Files a system lists in its `[build] ignore` are skipped.
+pub fn check_imports(manifest: &Manifest, systems: &[SystemConfig]) -> Vec<ImportIssue> {
+    let pattern = Regex::new(r#"^import\s+(?:([A-Za-z_][A-Za-z0-9_]*)\s+)?"([^"]+)""#).unwrap();
+    // an invalid [paths] section fails the build with its own error
+    let Ok(paths) = ProjectPaths::new(&manifest.paths) else {
+        return Vec::new();
+    };
+    let project_collections = project_collections(&paths);
+    let collections: HashMap<&str, &Path> = project_collections
+        .iter()
+        .map(|(name, root)| (*name, root.as_path()))
+        .collect();
+    let mut packages: HashMap<PathBuf, BTreeMap<String, Vec<PathBuf>>> = HashMap::new();
+    let mut issues = Vec::new();
+
+    let mut scanned = vec![paths.source.clone(), PathBuf::from(SYSTEMS_DIR)];
+    if !paths.shaders.starts_with(&paths.source) {
+        scanned.push(paths.shaders.clone());
+    }
+    let files = scanned
+        .iter()
+        .flat_map(|dir| WalkDir::new(dir).follow_links(true).into_iter().filter_map(|e| e.ok()))
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "odin"))
+        .map(|e| e.into_path())
+        .filter(|file| !systems.iter().any(|s| s.is_ignored(file)));
+
+    for file in files {
+        let Ok(source) = fs::read_to_string(&file) else {
+            continue;
+        };
+
+        // package name -> (import string, line) for duplicate detection within the file
+        let mut seen: HashMap<String, (String, usize)> = HashMap::new();
+
+        for import in parse_imports(&source, &pattern) {
+            let mut issue = |message: String| {
+                issues.push(ImportIssue {
+                    file: file.clone(),
+                    line: import.line,
+                    message,
+                })
+            };
+
+            let (collection, dir) = match import.path.split_once(':') {
+                Some((collection, _)) if COMPILER_COLLECTIONS.contains(&collection) => continue,
+                Some((collection, sub_path)) => match collections.get(collection) {
+                    Some(root) => (Some(collection), root.join(sub_path)),
+                    None => {
+                        let mut available: Vec<&str> = project_collections.iter().map(|(c, _)| *c).collect();
+                        available.extend(COMPILER_COLLECTIONS);
+                        issue(format!(
+                            "Unknown collection '{}' in \"{}\". Available collections: {}.",
+                            collection,
+                            import.path,
+                            available.join(", ")
+                        ));
+                        continue;
+                    }
+                },
+                None => (None, file.parent().unwrap_or(Path::new(".")).join(&import.path)),
+            };
+
+            if !dir.is_dir() {
+                issue(missing_message(&import.path, collection, &project_collections, manifest));
+                continue;
+            }
+            if odin_files(&dir).is_empty() {
+                issue(format!(
+                    "\"{}\" points at {}, which has no .odin files.",
+                    import.path,
+                    dir.display()
+                ));
+                continue;
+            }
+
+            let names = packages.entry(dir.clone()).or_insert_with(|| package_names(&dir));
+            if names.len() > 1 {
+                issue(format!(
+                    "\"{}\" mixes packages {}; every file in a directory must declare the same package.",
+                    import.path,
+                    names
+                        .iter()
+                        .map(|(name, files)| format!(
+                            "'{}' ({})",
+                            name,
+                            files[0].file_name().unwrap_or_default().to_string_lossy()
+                        ))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+                continue;
+            }
+
+            let Some(name) = import.alias.clone().or_else(|| names.keys().next().cloned()) else {
+                continue;
+            };
+            if name == "_" {
+                continue;
+            }
+
+            match seen.get(&name) {
+                Some((previous, _)) if *previous == import.path => issue(format!(
+                    "\"{}\" is imported more than once.",
+                    import.path
+                )),
+                Some((previous, line)) => issue(format!(
+                    "\"{}\" and \"{}\" (line {}) are both named '{}'. Give one an alias, e.g. import {}_2 \"{}\".",
+                    import.path, previous, line, name, name, import.path
+                )),
+                None => {
+                    seen.insert(name, (import.path.clone(), import.line));
+                }
+            }
+        }
+    }
+
+    issues
+}
@@ -0,0 +1,138 @@
+use crate::Ui;
+use crate::build::run_with_prefix;
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::ItchOptions;
+use std::path::Path;
+
+/// Targets `bonsai deploy itch` can build and push.
+pub const DEPLOY_TARGETS: &[&str] = &["web", "desktop"];
+const DEFAULT_DEPLOY_CONFIG: &str = "release";
+
+// itch.io recognises these channel names and tags the uploads with their platform
+const WEB_CHANNEL: &str = "html5";
+const DESKTOP_CHANNEL: &str = if cfg!(target_os = "windows") {
+    "windows"
+} else if cfg!(target_os = "macos") {
+    "osx"
+} else {
+    "linux"
+};
+
+/// Checks `[deploy.itch]` before anything is built.
+pub fn validate_itch_options(options: &ItchOptions) -> Result<(), CustomError> {
+    let valid_project = options
+        .project
+        .split_once('/')
+        .is_some_and(|(user, game)| {
+            !user.is_empty() && !game.is_empty() && !game.contains('/') && !options.project.contains(':')
+        });
+    if !valid_project {
+        return Err(CustomError::ValidationError(t!(
+            "Invalid [deploy.itch] project '{}' (expected \"user/game\")",
+            options.project
+        )));
+    }
+
+    for target in options.targets.iter().chain(options.channels.keys()) {
+        if !DEPLOY_TARGETS.contains(&target.as_str()) {
+            return Err(CustomError::ValidationError(t!(
+                "Unknown [deploy.itch] target '{}' (expected one of: {})",
+                target,
+                DEPLOY_TARGETS.join(", ")
+            )));
+        }
+    }
+    for (target, channel) in &options.channels {
+        if channel.is_empty() || channel.contains(':') || channel.contains('/') {
+            return Err(CustomError::ValidationError(t!(
+                "Invalid [deploy.itch.channels] {} channel '{}'",
+                target, channel
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The targets to push: the command line's, else the manifest's, else all of them.
+pub fn deploy_targets(requested: &[String], options: &ItchOptions) -> Vec<String> {
+    let targets = if !requested.is_empty() {
+        requested
+    } else {
+        &options.targets
+    };
+    if targets.is_empty() {
+        return DEPLOY_TARGETS.iter().map(|t| t.to_string()).collect();
+    }
+    // in the canonical order, once each
+    DEPLOY_TARGETS
+        .iter()
+        .filter(|t| targets.iter().any(|r| r == *t))
+        .map(|t| t.to_string())
+        .collect()
+}
+
+pub fn deploy_config(options: &ItchOptions) -> &str {
+    options.config.as_deref().unwrap_or(DEFAULT_DEPLOY_CONFIG)
+}
+
+pub fn channel(options: &ItchOptions, target: &str) -> String {
+    if let Some(channel) = options.channels.get(target) {
+        return channel.clone();
+    }
+    match target {
+        "web" => WEB_CHANNEL.to_string(),
+        _ => DESKTOP_CHANNEL.to_string(),
+    }
+}
+
+pub fn page_url(project: &str) -> String {
+    match project.split_once('/') {
+        Some((user, game)) => format!("https://{}.itch.io/{}", user, game),
+        None => format!("https://itch.io/{}", project),
+    }
+}
+
+/// Arguments for `butler push`, which uploads only what changed since the channel's last build.
+pub fn push_args(dir: &Path, project: &str, channel: &str, version: &str) -> Vec<String> {
+    vec![
+        "push".to_string(),
+        dir.to_string_lossy().to_string(),
+        format!("{}:{}", project, channel),
+        "--userversion".to_string(),
+        version.to_string(),
+    ]
+}
+
+/// Runs `butler push`. butler uses the credentials from `butler login` or `BUTLER_API_KEY`.
+pub fn push(butler: &str, args: &[String], ui: &Ui) -> Result<(), CustomError> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    run_with_prefix(butler, &args, "[BUTLER]", colored::Color::Magenta, ui)
+}
+
+/// Pushes build `id` in `output_dir` to the channel of `target`. Without `butler`, a dry run,
+/// the butler command is printed instead.
+pub fn push_build(
+    butler: Option<&str>,
+    options: &ItchOptions,
+    target: &str,
+    output_dir: &Path,
+    id: &str,
+    version: &str,
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    let channel = channel(options, target);
+    let args = push_args(output_dir, &options.project, &channel, version);
+    match butler {
+        Some(butler) => {
+            ui.status(&t!("Pushing {} to {}:{}...", target, options.project, channel));
+            push(butler, &args, ui)?;
+            ui.log(&t!(
+                "Pushed build {} to {}:{} as version {}.",
+                id, options.project, channel, version
+            ));
+        }
+        None => ui.message(&format!("butler {}", args.join(" "))),
+    }
+    Ok(())
+}
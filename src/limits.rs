@@ -0,0 +1,305 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::LimitsOptions;
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::process::{Child, Command};
+use std::time::Duration;
+
+// process groups of the tools running right now, stopped by [`kill_running`]
+#[cfg(unix)]
+static RUNNING_GROUPS: std::sync::Mutex<Vec<i32>> = std::sync::Mutex::new(Vec::new());
+
+/// Checks the `[limits]` section, a limit of 0 would stop every tool right away.
+pub fn validate_limits(limits: &LimitsOptions) -> Result<(), CustomError> {
+    let keys = [
+        ("memory_mb", limits.memory_mb),
+        ("cpu_seconds", limits.cpu_seconds),
+        ("timeout_seconds", limits.timeout_seconds),
+    ];
+    for (key, limit) in keys {
+        if limit == Some(0) {
+            return Err(CustomError::ValidationError(t!(
+                "[limits] {} must be greater than 0",
+                key
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// How long a tool may run before it is stopped.
+pub fn timeout(limits: &LimitsOptions) -> Option<Duration> {
+    limits.timeout_seconds.map(Duration::from_secs)
+}
+
+/// A spawned tool together with every process it started, e.g. emcc behind its shell wrapper.
+/// Derefs to the tool's [`Child`].
+pub struct ProcessTree {
+    child: Child,
+    #[cfg(windows)]
+    job: Job,
+}
+
+impl ProcessTree {
+    /// Kills the tool and everything it started, then reaps the tool.
+    pub fn kill(&mut self) {
+        #[cfg(unix)]
+        // SAFETY: the tool leads its own process group, see `spawn_limited`
+        unsafe {
+            libc::killpg(self.child.id() as libc::pid_t, libc::SIGKILL);
+        }
+        #[cfg(windows)]
+        self.job.terminate();
+
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Deref for ProcessTree {
+    type Target = Child;
+
+    fn deref(&self) -> &Child {
+        &self.child
+    }
+}
+
+impl DerefMut for ProcessTree {
+    fn deref_mut(&mut self) -> &mut Child {
+        &mut self.child
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ProcessTree {
+    fn drop(&mut self) {
+        let group = self.child.id() as i32;
+        RUNNING_GROUPS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|g| *g != group);
+    }
+}
+
+/// Kills the process groups of every running tool. They don't see the terminal's Ctrl+C, so the
+/// interrupt handler calls this.
+pub fn kill_running() {
+    #[cfg(unix)]
+    for group in RUNNING_GROUPS.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+        // SAFETY: a plain syscall, a group that already exited just fails with ESRCH
+        unsafe {
+            libc::killpg(*group, libc::SIGKILL);
+        }
+    }
+}
+
+/// Spawns `command` under the memory and CPU caps of `limits`, which every process it starts
+/// in turn inherits: rlimits on Unix, a job object on Windows. The tool gets its own process
+/// group (Unix) or job (Windows), so [`ProcessTree::kill`] reaches whatever it started. On
+/// Windows the job also kills what is left once the tree is dropped.
+pub fn spawn_limited(command: &mut Command, limits: &LimitsOptions) -> io::Result<ProcessTree> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        set_rlimits(command, limits);
+        // a background process group reading the terminal would be stopped by SIGTTIN
+        command.process_group(0).stdin(std::process::Stdio::null());
+
+        // registered under the lock, so Ctrl+C can't slip in between spawn and registration
+        let mut groups = RUNNING_GROUPS.lock().unwrap_or_else(|e| e.into_inner());
+        let child = command.spawn()?;
+        groups.push(child.id() as i32);
+        Ok(ProcessTree { child })
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        use windows_sys::Win32::System::Threading::CREATE_SUSPENDED;
+
+        let job = Job::new(limits)?;
+        // started suspended, so nothing it starts can escape the job before it is assigned
+        command.creation_flags(CREATE_SUSPENDED);
+        let mut child = command.spawn()?;
+        if let Err(e) = job.assign(&child).and_then(|()| resume(&child)) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+        Ok(ProcessTree { child, job })
+    }
+}
+
+#[cfg(unix)]
+fn set_rlimits(command: &mut Command, limits: &LimitsOptions) {
+    use std::os::unix::process::CommandExt;
+
+    if limits.memory_mb.is_none() && limits.cpu_seconds.is_none() {
+        return;
+    }
+    let memory = limits.memory_mb.map(|mb| mb.saturating_mul(1024 * 1024));
+    let cpu = limits.cpu_seconds;
+
+    // only the soft limit is lowered, a hard limit below it stays in charge
+    let lower = |resource, value: u64| {
+        let mut limit = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        // SAFETY: plain syscalls on a stack value, fine between fork and exec
+        unsafe {
+            if libc::getrlimit(resource, &mut limit) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            limit.rlim_cur = (value as libc::rlim_t).min(limit.rlim_max);
+            if libc::setrlimit(resource, &limit) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    };
+
+    // SAFETY: the closure only calls getrlimit and setrlimit, which are async-signal-safe
+    unsafe {
+        command.pre_exec(move || {
+            // heap and private mappings, address space runtimes merely reserve isn't counted
+            if let Some(bytes) = memory {
+                lower(libc::RLIMIT_DATA, bytes)?;
+            }
+            // the process gets SIGXCPU once it used this much CPU time
+            if let Some(seconds) = cpu {
+                lower(libc::RLIMIT_CPU, seconds)?;
+            }
+            Ok(())
+        });
+    }
+}
+
+// a job object holding a tool and everything it starts, closed on drop
+#[cfg(windows)]
+struct Job(windows_sys::Win32::Foundation::HANDLE);
+
+// SAFETY: a job handle may be used from any thread
+#[cfg(windows)]
+unsafe impl Send for Job {}
+
+#[cfg(windows)]
+impl Job {
+    fn new(limits: &LimitsOptions) -> io::Result<Self> {
+        use windows_sys::Win32::System::JobObjects::{
+            CreateJobObjectW, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+            JOB_OBJECT_LIMIT_PROCESS_TIME, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JobObjectExtendedLimitInformation, SetInformationJobObject,
+        };
+
+        // SAFETY: the new handle is owned by the returned Job, and all-zero limit information is valid
+        unsafe {
+            let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if handle.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let job = Job(handle);
+
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            // whatever the tool leaves running dies with the handle, even if bonsai is killed
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            if let Some(mb) = limits.memory_mb {
+                info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+                info.ProcessMemoryLimit = mb.saturating_mul(1024 * 1024) as usize;
+            }
+            if let Some(seconds) = limits.cpu_seconds {
+                info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_TIME;
+                // user-mode time, in 100 nanosecond units
+                info.BasicLimitInformation.PerProcessUserTimeLimit =
+                    seconds.saturating_mul(10_000_000) as i64;
+            }
+            if SetInformationJobObject(
+                job.0,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const std::ffi::c_void,
+                size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            ) == 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(job)
+        }
+    }
+
+    fn assign(&self, child: &Child) -> io::Result<()> {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::System::JobObjects::AssignProcessToJobObject;
+
+        // SAFETY: both handles are open for the duration of the call
+        if unsafe { AssignProcessToJobObject(self.0, child.as_raw_handle() as _) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn terminate(&self) {
+        use windows_sys::Win32::System::JobObjects::TerminateJobObject;
+
+        // SAFETY: the handle is open until drop
+        unsafe {
+            TerminateJobObject(self.0, 1);
+        }
+    }
+}
+
+// resumes the only thread of a process started with CREATE_SUSPENDED
+#[cfg(windows)]
+fn resume(child: &Child) -> io::Result<()> {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, TH32CS_SNAPTHREAD, THREADENTRY32, Thread32First, Thread32Next,
+    };
+    use windows_sys::Win32::System::Threading::{OpenThread, ResumeThread, THREAD_SUSPEND_RESUME};
+
+    // SAFETY: the snapshot and thread handles are closed before returning, and the entry is
+    // a plain struct with its size set as Thread32First requires
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut entry: THREADENTRY32 = std::mem::zeroed();
+        entry.dwSize = size_of::<THREADENTRY32>() as u32;
+        let mut resumed = Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "the suspended process has no thread",
+        ));
+        let mut found = Thread32First(snapshot, &mut entry) != 0;
+        while found {
+            if entry.th32OwnerProcessID == child.id() {
+                let thread = OpenThread(THREAD_SUSPEND_RESUME, 0, entry.th32ThreadID);
+                resumed = if thread.is_null() || ResumeThread(thread) == u32::MAX {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(())
+                };
+                if !thread.is_null() {
+                    CloseHandle(thread);
+                }
+                break;
+            }
+            found = Thread32Next(snapshot, &mut entry) != 0;
+        }
+
+        CloseHandle(snapshot);
+        resumed
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Job {
+    fn drop(&mut self) {
+        // SAFETY: the handle is owned by this Job and closed once
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use crate::error::CustomError;
+use crate::generate::{is_tracked, shader_outputs};
+use crate::i18n::t;
+use crate::imports::check_imports;
+use crate::system_config::load_system_configs;
+use crate::manifest::{Manifest, System};
+use crate::paths::ProjectPaths;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+const ENGINE_DIR: &str = "bonsai";
+const SYSTEMS_DIR: &str = "bonsai/systems";
+const ODIN_KEYWORDS: &[&str] = &[
+    "asm", "auto_cast", "bit_field", "bit_set", "break", "case", "cast", "context", "continue",
+    "defer", "distinct", "do", "dynamic", "else", "enum", "fallthrough", "false", "for", "foreign",
+    "if", "import", "in", "map", "matrix", "nil", "not_in", "or_break", "or_continue", "or_else",
+    "or_return", "package", "proc", "return", "struct", "switch", "transmute", "true", "typeid",
+    "union", "using", "when", "where",
+];
+
+pub enum Fix {
+    CreateDir(PathBuf),
+    Rename { from: PathBuf, to: PathBuf },
+    RelativizeManifestPath { absolute: String, relative: String },
+    Untrack(PathBuf),
+}
+
+pub struct LintIssue {
+    pub rule: &'static str,
+    pub message: String,
+    pub fix: Option<Fix>,
+}
+
+impl LintIssue {
+    fn new(rule: &'static str, message: String, fix: Option<Fix>) -> Self {
+        Self { rule, message, fix }
+    }
+}
+
+pub fn is_odin_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+
+    valid_start
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !ODIN_KEYWORDS.contains(&name)
+}
+
+// mirrors the normalization done when generating enums from file names
+fn enum_name(stem: &str) -> String {
+    stem.replace(['-', ' '], "_")
+}
+
+fn to_identifier(stem: &str) -> String {
+    let mut name: String = enum_name(stem)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    if ODIN_KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
+
+fn check_required_dirs(paths: &ProjectPaths, issues: &mut Vec<LintIssue>) {
+    // (directory, whether --fix may create it)
+    let required = [
+        (PathBuf::from(ENGINE_DIR), false),
+        (paths.source.clone(), false),
+        (paths.game(), true),
+        (paths.assets.clone(), true),
+    ];
+    for (dir, creatable) in required {
+        if !dir.is_dir() {
+            let fix = creatable.then(|| Fix::CreateDir(dir.clone()));
+            issues.push(LintIssue::new(
+                "structure",
+                format!("Missing required directory '{}'", dir.display()),
+                fix,
+            ));
+        }
+    }
+}
+
+fn check_manifest_paths(manifest: &Manifest, issues: &mut Vec<LintIssue>) {
+    let root = Path::new(".").canonicalize().unwrap_or_default();
+
+    let mut paths: Vec<(&str, &str)> = Vec::new();
+    for (name, system) in &manifest.systems {
+        if let System::Path { path, .. } = system {
+            paths.push((name, path));
+        }
+    }
+    for lib in &manifest.build.web_libs {
+        paths.push(("build.web_libs", lib));
+    }
+    for path in manifest.shaders.programs.values() {
+        paths.push(("shaders.programs", path));
+    }
+    if let Some(shdc) = &manifest.toolchain.shdc {
+        paths.push(("toolchain.shdc", shdc));
+    }
+    if let Some(icon) = &manifest.app.icon {
+        paths.push(("app.icon", icon));
+    }
+
+    for (key, path) in paths {
+        if !Path::new(path).is_absolute() {
+            continue;
+        }
+
+        let fix = Path::new(path)
+            .strip_prefix(&root)
+            .ok()
+            .map(|relative| Fix::RelativizeManifestPath {
+                absolute: path.to_string(),
+                relative: relative.to_string_lossy().replace('\\', "/"),
+            });
+        issues.push(LintIssue::new(
+            "manifest",
+            format!("Absolute path in manifest ({}): {}", key, path),
+            fix,
+        ));
+    }
+}
+
+fn check_committed_shader_outputs(paths: &ProjectPaths, issues: &mut Vec<LintIssue>) {
+    for output in shader_outputs(paths) {
+        if is_tracked(&output) {
+            issues.push(LintIssue::new(
+                "generated",
+                format!("Generated shader output is committed: {}", output.display()),
+                Some(Fix::Untrack(output)),
+            ));
+        }
+    }
+}
+
+fn check_asset_names(paths: &ProjectPaths, issues: &mut Vec<LintIssue>) {
+    let mut candidates: Vec<(PathBuf, String)> = Vec::new();
+
+    let named_assets: [(PathBuf, &[&str]); 3] = [
+        (paths.images(), &["png"]),
+        (paths.audio(), &["wav"]),
+        (paths.fonts(), &["ttf", "otf"]),
+    ];
+    for (dir, extensions) in &named_assets {
+        for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let is_asset = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| extensions.contains(&e));
+            if let (true, Some(stem)) = (is_asset, path.file_stem().and_then(|s| s.to_str())) {
+                candidates.push((path.to_path_buf(), stem.to_string()));
+            }
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(paths.scenes()) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            if entry.path().is_dir()
+                && let Some(name) = entry.file_name().to_str()
+            {
+                candidates.push((entry.path(), name.to_string()));
+            }
+        }
+    }
+
+    for (path, stem) in candidates {
+        if is_odin_identifier(&enum_name(&stem)) {
+            continue;
+        }
+
+        let fixed = to_identifier(&stem);
+        let new_name = match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if path.is_file() => format!("{}.{}", fixed, ext),
+            _ => fixed,
+        };
+        let to = path.with_file_name(new_name);
+        let fix = (!to.exists()).then(|| Fix::Rename {
+            from: path.clone(),
+            to,
+        });
+
+        issues.push(LintIssue::new(
+            "naming",
+            format!(
+                "'{}' does not produce a valid Odin identifier",
+                path.display()
+            ),
+            fix,
+        ));
+    }
+}
+
+fn check_systems(paths: &ProjectPaths, issues: &mut Vec<LintIssue>) {
+    let game_collection = format!("-collection:game={}", paths.game().display());
+    let Ok(entries) = fs::read_dir(SYSTEMS_DIR) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        if !entry.path().is_dir() {
+            continue;
+        }
+
+        let output = Command::new("odin")
+            .arg("check")
+            .arg(entry.path())
+            .args([
+                "-no-entry-point",
+                "-collection:bonsai=./bonsai",
+                game_collection.as_str(),
+            ])
+            .output();
+
+        match output {
+            Ok(o) if !o.status.success() => {
+                let stderr = String::from_utf8_lossy(&o.stderr);
+                let first_error = stderr.lines().next().unwrap_or("").trim().to_string();
+                issues.push(LintIssue::new(
+                    "systems",
+                    format!(
+                        "System '{}' does not compile standalone: {}",
+                        entry.file_name().to_string_lossy(),
+                        first_error
+                    ),
+                    None,
+                ));
+            }
+            Ok(_) => {}
+            // no compiler, nothing to check against
+            Err(_) => return,
+        }
+    }
+}
+
+pub fn run_lints(manifest: &Manifest, check_systems_compile: bool) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    let paths = match ProjectPaths::new(&manifest.paths) {
+        Ok(paths) => paths,
+        Err(e) => {
+            issues.push(LintIssue::new("structure", e.to_string(), None));
+            return issues;
+        }
+    };
+
+    check_required_dirs(&paths, &mut issues);
+    check_manifest_paths(manifest, &mut issues);
+    check_committed_shader_outputs(&paths, &mut issues);
+    check_asset_names(&paths, &mut issues);
+    let systems = load_system_configs();
+    for system in &systems {
+        if let Some(error) = &system.error {
+            issues.push(LintIssue::new(
+                "systems",
+                format!("System '{}' has an invalid system.toml: {}", system.name, error),
+                None,
+            ));
+        }
+    }
+    issues.extend(check_imports(manifest, &systems).into_iter().map(|issue| {
+        LintIssue::new(
+            "imports",
+            format!("{}({}): {}", issue.file.display(), issue.line, issue.message),
+            None,
+        )
+    }));
+    if check_systems_compile {
+        check_systems(&paths, &mut issues);
+    }
+
+    issues
+}
+
+pub fn apply_fix(fix: &Fix, manifest_path: &Path) -> Result<(), CustomError> {
+    match fix {
+        Fix::CreateDir(dir) => fs::create_dir_all(dir)?,
+        Fix::Rename { from, to } => fs::rename(from, to)?,
+        Fix::RelativizeManifestPath { absolute, relative } => {
+            let content = fs::read_to_string(manifest_path)?;
+            let escaped = absolute.replace('\\', "\\\\");
+            fs::write(
+                manifest_path,
+                content
+                    .replace(&format!("\"{}\"", escaped), &format!("\"{}\"", relative))
+                    .replace(&format!("'{}'", absolute), &format!("'{}'", relative)),
+            )?;
+        }
+        Fix::Untrack(path) => {
+            let status = Command::new("git")
+                .args(["rm", "--cached", "--quiet"])
+                .arg(path)
+                .status()?;
+            if !status.success() {
+                return Err(CustomError::GitError(t!(
+                    "Failed to untrack {}",
+                    path.display()
+                )));
+            }
+
+            let ignore_line = path.to_string_lossy().replace('\\', "/");
+            let gitignore = fs::read_to_string(".gitignore").unwrap_or_default();
+            if !gitignore.lines().any(|l| l.trim() == ignore_line) {
+                let separator = if gitignore.is_empty() || gitignore.ends_with('\n') {
+                    ""
+                } else {
+                    "\n"
+                };
+                fs::write(
+                    ".gitignore",
+                    format!("{}{}{}\n", gitignore, separator, ignore_line),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
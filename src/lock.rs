@@ -0,0 +1,75 @@
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::ui::Ui;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+pub const LOCK_FILE: &str = ".bonsai/lock";
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Exclusive hold on the project's build directory, released on drop.
+/// Relies on the working directory being the project root.
+pub struct ProjectLock {
+    // the OS lock on this file is the lock; it goes away with the process, so it is never stale
+    _file: File,
+}
+
+impl ProjectLock {
+    /// Takes the lock, failing with the owner's pid if another bonsai process holds it.
+    /// With `wait`, blocks until the other process is done instead.
+    pub fn acquire(wait: bool, ui: &Ui) -> Result<Self, CustomError> {
+        let path = PathBuf::from(LOCK_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // the file is never removed, another process may be about to lock it
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        let mut announced = false;
+        loop {
+            match file.try_lock() {
+                Ok(()) => {
+                    // only for the messages of processes waiting on this one
+                    file.set_len(0)?;
+                    write!(file, "{}", std::process::id())?;
+                    return Ok(Self { _file: file });
+                }
+                Err(TryLockError::WouldBlock) => {}
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+
+            // the owner may not have written its pid yet, and Windows can't read a locked file
+            let owner = read_owner(&path);
+            if !wait {
+                return Err(CustomError::ProcessError(match owner {
+                    Some(pid) => t!(
+                        "Another build is running (pid {}). Use --wait to wait for it to finish",
+                        pid
+                    ),
+                    None => tr("Another build is running. Use --wait to wait for it to finish").to_string(),
+                }));
+            }
+            if !announced {
+                match owner {
+                    Some(pid) => ui.status(&t!("Waiting for another build to finish (pid {})...", pid)),
+                    None => ui.status(tr("Waiting for another build to finish...")),
+                }
+                announced = true;
+            }
+            thread::sleep(WAIT_POLL_INTERVAL);
+        }
+    }
+}
+
+fn read_owner(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
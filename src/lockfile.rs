@@ -0,0 +1,109 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::hash::{hash_file, to_hex};
+use crate::i18n::t;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+pub const LOCKFILE: &str = "bonsai.lock";
+const SYSTEMS_DIR: &str = "bonsai/systems";
+const LOCKFILE_HEADER: &str = "# Written by 'bonsai install', commit it to install the same systems everywhere.\n\n";
+
+/// Where an installed system came from, pinned to the commit that was checked out.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LockedSystem {
+    pub url: String,
+    // what was asked for: "latest", a tag or a branch
+    pub version: String,
+    pub commit: String,
+    // see `system_checksum`
+    pub checksum: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Lockfile {
+    #[serde(default)]
+    pub systems: BTreeMap<String, LockedSystem>,
+}
+
+impl Lockfile {
+    /// Reads `bonsai.lock` from the project root, empty when there is none yet.
+    pub fn load() -> Result<Self, CustomError> {
+        match fs::read_to_string(LOCKFILE) {
+            Ok(content) => toml_edit::de::from_str(&content).map_err(|e| {
+                CustomError::ValidationError(t!("Invalid {}: {}", LOCKFILE, e))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(CustomError::IoError(e)),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), CustomError> {
+        if self.systems.is_empty() {
+            if Path::new(LOCKFILE).exists() {
+                fs::remove_file(LOCKFILE)?;
+            }
+            return Ok(());
+        }
+
+        let content = toml_edit::ser::to_string_pretty(self).map_err(|e| {
+            CustomError::ValidationError(t!("Failed to serialize {}: {}", LOCKFILE, e))
+        })?;
+        fs::write(LOCKFILE, format!("{}{}", LOCKFILE_HEADER, content))?;
+        Ok(())
+    }
+}
+
+/// SHA-256 over the relative paths and contents of every file in an installed system.
+pub fn system_checksum(dir: &Path) -> Result<String, CustomError> {
+    let mut hasher = Sha256::new();
+    for entry in WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+        hasher.update(relative.to_string_lossy().replace('\\', "/").as_bytes());
+        hasher.update(hash_file(entry.path())?.as_bytes());
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Checks the installed systems against `bonsai.lock`. A missing system fails, one whose
+/// files changed since it was installed only warns, local edits are allowed.
+pub fn verify_locked_systems(ui: &Ui) -> Result<(), CustomError> {
+    let lockfile = Lockfile::load()?;
+    let mut missing = Vec::new();
+
+    for (name, locked) in &lockfile.systems {
+        let dir = Path::new(SYSTEMS_DIR).join(name);
+        if !dir.is_dir() {
+            missing.push(name.as_str());
+            continue;
+        }
+        if system_checksum(&dir)? != locked.checksum {
+            ui.message(&t!(
+                "{} System '{}' differs from commit {} in {}.",
+                "[WARNING]".yellow(),
+                name,
+                &locked.commit[..locked.commit.len().min(12)],
+                LOCKFILE
+            ));
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(CustomError::ValidationError(t!(
+            "Systems in {} are not installed: {}. Run 'bonsai install' to restore them",
+            LOCKFILE,
+            missing.join(", ")
+        )));
+    }
+    Ok(())
+}
@@ -1,24 +1,103 @@
 use crate::ui::Ui;
 use clap::{Parser, Subcommand};
+use std::time::Instant;
+use usage::record_usage;
 use colored::*;
 
+mod aseprite;
 mod assets;
+mod atlas_layout;
+mod bench;
+mod browser;
 mod build;
+mod build_diff;
+mod build_graph;
+mod cancel;
 mod commands;
+mod config;
+mod emsdk;
 mod error;
+mod freshness;
+mod generate;
 mod git;
+mod golden;
+mod harness;
+mod hash;
+mod history;
+mod host_config;
+mod i18n;
+mod imports;
+mod itch;
+mod limits;
+mod lock;
+mod lockfile;
+mod lint;
 mod manifest;
+mod manifest_fix;
+mod manifest_history;
+mod memory_report;
+mod msvc;
 mod packer;
+mod path_guard;
+mod paths;
+mod pixel_ops;
+mod preset;
+mod profile;
+mod provenance;
+mod rebuild_log;
+mod recovery;
+mod release;
+mod renderdoc;
 mod shdc;
 mod sokol;
+mod staging;
+mod stats;
+mod sysinfo;
+mod system_config;
+mod tilemap;
+mod tools;
 mod ui;
+mod update;
+mod usage;
+mod version;
+mod watch;
+mod web_bundle;
+mod web_chunks;
+mod web_packages;
+mod wsl;
+mod xml;
 
+use commands::assets_cmd::{self, AssetsArgs};
+use commands::bench::{self as bench_cmd, BenchArgs};
 use commands::build_cmd::{self, BuildArgs};
+use commands::builds::{self, BuildsArgs};
+use commands::bundle::{self, BundleArgs};
+use commands::clean::{self, CleanArgs};
+use commands::config_cmd::{self, ConfigArgs};
+use commands::deploy::{self, DeployArgs};
 use commands::docs::{self, DocsArgs};
+use commands::doctor::{self, DoctorArgs};
+use commands::example::{self, ExampleArgs};
+use commands::explain_build::{self, ExplainBuildArgs};
+use commands::generate_cmd::{self, GenerateArgs};
+use commands::info::{self, InfoArgs};
 use commands::init::{self, InitArgs};
 use commands::install::{self, InstallArgs};
+use commands::integrate::{self, IntegrateArgs};
+use commands::lint::{self as lint_cmd, LintArgs};
+use commands::manifest_cmd::{self, ManifestArgs};
+use commands::provenance_cmd::{self, ProvenanceArgs};
+use commands::release_cmd::{self, ReleaseArgs};
 use commands::remove::{self, RemoveArgs};
 use commands::run::{self, RunArgs};
+use commands::selftest::{self, SelftestArgs};
+use commands::serve::{self, ServeArgs};
+use commands::shader::{self, ShaderArgs};
+use commands::sokol_cmd::{self, SokolArgs};
+use commands::stats_cmd::{self, StatsArgs};
+use commands::sysinfo_cmd::{self, SysinfoArgs};
+use commands::test::{self, TestArgs};
+use commands::update_cmd::{self, UpdateArgs};
 
 #[derive(Parser)]
 #[command(
@@ -30,6 +109,11 @@ use commands::run::{self, RunArgs};
 struct Cli {
     #[arg(short, long)]
     verbose: bool,
+    #[arg(long, global = true, value_name = "stderr|PATH")]
+    progress: Option<String>,
+    /// Plain output for screen readers and log scrapers: no spinner, glyphs or colors
+    #[arg(long, global = true)]
+    plain: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -42,11 +126,36 @@ enum Commands {
     Install(InstallArgs),
     Remove(RemoveArgs),
     Docs(DocsArgs),
+    Shader(ShaderArgs),
+    Stats(StatsArgs),
+    Assets(AssetsArgs),
+    Builds(BuildsArgs),
+    Lint(LintArgs),
+    Generate(GenerateArgs),
+    Test(TestArgs),
+    Bench(BenchArgs),
+    Sokol(SokolArgs),
+    Integrate(IntegrateArgs),
+    Serve(ServeArgs),
+    Sysinfo(SysinfoArgs),
+    Example(ExampleArgs),
+    Info(InfoArgs),
+    Doctor(DoctorArgs),
+    Manifest(ManifestArgs),
+    Config(ConfigArgs),
+    Clean(CleanArgs),
+    Update(UpdateArgs),
+    Provenance(ProvenanceArgs),
+    Deploy(DeployArgs),
+    ExplainBuild(ExplainBuildArgs),
+    Selftest(SelftestArgs),
+    Bundle(BundleArgs),
+    Release(ReleaseArgs),
 }
 
-fn handle_result(res: Result<(), crate::error::CustomError>, context: &str) {
+fn handle_result(res: Result<(), crate::error::CustomError>, context: &str, ui: &Ui) {
     if let Err(e) = res {
-        eprintln!("{}: {}.", format!("[ERROR] ({})", context).red().bold(), e);
+        ui.report_failure(context, &e.to_string());
         std::process::exit(1);
     }
 }
@@ -56,14 +165,64 @@ fn main() {
 
     let cli = Cli::parse();
 
-    let ui = Ui::new(cli.verbose);
+    let plain = cli.plain
+        || config::load_global_config()
+            .ok()
+            .and_then(|c| c.ui.plain)
+            .unwrap_or(false);
+    let mut ui = Ui::new(cli.verbose, plain);
+    if let Some(target) = &cli.progress {
+        ui = match ui.with_progress(target) {
+            Ok(ui) => ui,
+            Err(e) => {
+                eprintln!("{}: {}.", "[ERROR] (progress)".red().bold(), e);
+                std::process::exit(1);
+            }
+        };
+    }
+
+    if let Err(e) = cancel::install_interrupt_handler() {
+        ui.error(&e.to_string());
+    }
+
+    let started = Instant::now();
+
+    let (res, context) = match &cli.command {
+        Commands::Init(args) => (init::init(args, ui.clone()), "init"),
+        Commands::Run(args) => (run::run(args, ui.clone()), "run"),
+        Commands::Build(args) => (build_cmd::build(args, ui.clone()), "build"),
+        Commands::Install(args) => (install::install(args, ui.clone()), "install"),
+        Commands::Remove(args) => (remove::remove(args, ui.clone()), "remove"),
+        Commands::Docs(args) => (docs::docs(args, ui.clone()), "docs"),
+        Commands::Shader(args) => (shader::shader(args, ui.clone()), "shader"),
+        Commands::Stats(args) => (stats_cmd::stats(args, ui.clone()), "stats"),
+        Commands::Assets(args) => (assets_cmd::assets(args, ui.clone()), "assets"),
+        Commands::Builds(args) => (builds::builds(args, ui.clone()), "builds"),
+        Commands::Lint(args) => (lint_cmd::lint(args, ui.clone()), "lint"),
+        Commands::Generate(args) => (generate_cmd::generate(args, ui.clone()), "generate"),
+        Commands::Test(args) => (test::test(args, ui.clone()), "test"),
+        Commands::Bench(args) => (bench_cmd::bench(args, ui.clone()), "bench"),
+        Commands::Sokol(args) => (sokol_cmd::sokol(args, ui.clone()), "sokol"),
+        Commands::Integrate(args) => (integrate::integrate(args, ui.clone()), "integrate"),
+        Commands::Serve(args) => (serve::serve(args, ui.clone()), "serve"),
+        Commands::Sysinfo(args) => (sysinfo_cmd::sysinfo(args, ui.clone()), "sysinfo"),
+        Commands::Example(args) => (example::example(args, ui.clone()), "example"),
+        Commands::Info(args) => (info::info(args, ui.clone()), "info"),
+        Commands::Doctor(args) => (doctor::doctor(args, ui.clone()), "doctor"),
+        Commands::Manifest(args) => (manifest_cmd::manifest(args, ui.clone()), "manifest"),
+        Commands::Config(args) => (config_cmd::config(args, ui.clone()), "config"),
+        Commands::Clean(args) => (clean::clean(args, ui.clone()), "clean"),
+        Commands::Update(args) => (update_cmd::update(args, ui.clone()), "update"),
+        Commands::Provenance(args) => (provenance_cmd::provenance(args, ui.clone()), "provenance"),
+        Commands::Deploy(args) => (deploy::deploy(args, ui.clone()), "deploy"),
+        Commands::ExplainBuild(args) => (explain_build::explain_build(args, ui.clone()), "explain-build"),
+        Commands::Selftest(args) => (selftest::selftest(args, ui.clone()), "selftest"),
+        Commands::Bundle(args) => (bundle::bundle(args, ui.clone()), "bundle"),
+        Commands::Release(args) => (release_cmd::release(args, ui.clone()), "release"),
+    };
 
-    match &cli.command {
-        Commands::Init(args) => handle_result(init::init(args, ui.clone()), "init"),
-        Commands::Run(args) => handle_result(run::run(args, ui.clone()), "run"),
-        Commands::Build(args) => handle_result(build_cmd::build(args, ui.clone()), "build"),
-        Commands::Install(args) => handle_result(install::install(args, ui.clone()), "install"),
-        Commands::Remove(args) => handle_result(remove::remove(args, ui.clone()), "remove"),
-        Commands::Docs(args) => handle_result(docs::docs(args, ui.clone()), "docs"),
+    if context != "stats" {
+        record_usage(context, started.elapsed(), res.is_ok(), &ui.metrics);
     }
+    handle_result(res, context, &ui);
 }
@@ -1,10 +1,15 @@
 use crate::Ui;
 use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest_history::record_change;
+use crate::path_guard::is_path_safe;
+use crate::staging::link_dir;
+use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
-use toml_edit::{DocumentMut, InlineTable, Value, table, value};
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Table, Value, table, value};
 
 const MANIFEST_FILE: &str = "bonsai.toml";
 const SYSTEM_MANIFEST: &str = "system.toml";
@@ -13,20 +18,78 @@ const SYSTEM_MANIFEST: &str = "system.toml";
 #[serde(untagged)]
 pub enum System {
     Version(String),
-    Path { path: String },
+    Path {
+        path: String,
+        // linked with `bonsai install --link`, points outside bonsai/systems
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        editable: bool,
+    },
     Git { url: String, tag: Option<String> },
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// "1.3" is a minimum version, "*" accepts any version
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ToolRequirement {
+    Version(String),
+    Detailed {
+        version: Option<String>,
+        path: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Manifest {
     pub project: ProjectInfo,
     #[serde(default)]
     pub build: BuildOptions,
     #[serde(default)]
     pub systems: BTreeMap<String, System>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profile: BTreeMap<String, ProfileOptions>,
+    // the old name of [assets.atlas], moved there by `load_manifest`
+    #[serde(default, rename = "atlas", skip_serializing)]
+    legacy_atlas: Option<AtlasOptions>,
+    // whether the settings came from a deprecated [atlas] section
+    #[serde(skip)]
+    pub uses_legacy_atlas: bool,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub web: WebOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub toolchain: ToolchainOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub shaders: ShaderOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub assets: AssetOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub app: AppOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub generate: GenerateOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub test: TestOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub bench: BenchOptions,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub target: BTreeMap<String, TargetOptions>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub update: UpdateOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub watch: WatchOptions,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, ToolRequirement>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub limits: LimitsOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub paths: PathsOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub docs: DocsOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub deploy: DeployOptions,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub release: ReleaseOptions,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct ProjectInfo {
     pub name: String,
     pub version: String,
@@ -36,6 +99,662 @@ pub struct ProjectInfo {
 pub struct BuildOptions {
     #[serde(default)]
     pub web_libs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_builds: Option<usize>,
+    #[serde(default, skip_serializing_if = "Freshness::is_auto")]
+    pub freshness: Freshness,
+}
+
+// how build stages decide their outputs are up to date
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Freshness {
+    // modification times, or content hashes where they are unreliable
+    #[default]
+    Auto,
+    Mtime,
+    Hash,
+}
+
+impl Freshness {
+    fn is_auto(&self) -> bool {
+        *self == Freshness::Auto
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inherits: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opt: Option<OdinOpt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bounds_check: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emcc_opt: Option<EmccOpt>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub defines: BTreeMap<String, DefineValue>,
+    #[serde(default)]
+    pub odin_flags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_assets: Option<bool>,
+}
+
+// odin's -o: levels
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum OdinOpt {
+    None,
+    Minimal,
+    Size,
+    Speed,
+    Aggressive,
+}
+
+impl OdinOpt {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OdinOpt::None => "none",
+            OdinOpt::Minimal => "minimal",
+            OdinOpt::Size => "size",
+            OdinOpt::Speed => "speed",
+            OdinOpt::Aggressive => "aggressive",
+        }
+    }
+}
+
+// emcc's -O levels, applied when linking web builds
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub enum EmccOpt {
+    O0,
+    O1,
+    O2,
+    O3,
+    Os,
+    Oz,
+}
+
+impl EmccOpt {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmccOpt::O0 => "O0",
+            EmccOpt::O1 => "O1",
+            EmccOpt::O2 => "O2",
+            EmccOpt::O3 => "O3",
+            EmccOpt::Os => "Os",
+            EmccOpt::Oz => "Oz",
+        }
+    }
+}
+
+// a `-define:NAME=value` constant, read in odin with #config
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum DefineValue {
+    Bool(bool),
+    Int(i64),
+    String(String),
+}
+
+impl DefineValue {
+    pub fn to_odin(&self) -> String {
+        match self {
+            DefineValue::Bool(b) => b.to_string(),
+            DefineValue::Int(i) => i.to_string(),
+            DefineValue::String(s) => format!("{:?}", s),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct AtlasOptions {
+    pub max_size: Option<u32>,
+    pub padding: Option<u32>,
+    pub uv_constants: Option<bool>,
+    pub packer: Option<AtlasPacker>,
+    pub sort: Option<AtlasSort>,
+    pub color_space: Option<ColorSpace>,
+    pub premultiply_alpha: Option<bool>,
+    pub extrude: Option<u32>,
+    // minimum extrusion of tiles, whatever extrude says
+    pub tile_extrude: Option<u32>,
+    pub bleed: Option<bool>,
+    // cut fully transparent borders off sprites before packing
+    pub trim: Option<bool>,
+    // let the packer turn sprites 90 degrees when they fit better that way
+    pub allow_rotation: Option<bool>,
+    // image path glob -> settings for the sprites it matches, e.g. "assets/images/ui/**"
+    #[serde(default)]
+    pub sprites: BTreeMap<String, SpriteOptions>,
+    // group name -> image path globs, each group is packed into its own atlas textures
+    #[serde(default)]
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct SpriteOptions {
+    pub extrude: Option<u32>,
+    pub bleed: Option<bool>,
+    pub trim: Option<bool>,
+}
+
+// what the atlas pixel values mean, emitted into sprite.odin for the texture format and blending
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    Linear,
+}
+
+// how sprites are placed in the atlas
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum AtlasPacker {
+    #[default]
+    Skyline,
+    // tracks every free rectangle, tightest for mixed sizes but slowest
+    Maxrects,
+    Guillotine,
+}
+
+// the order sprites are handed to the packer, largest first except for `name`
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum AtlasSort {
+    // file path order, tiles of a tileset row by row
+    #[default]
+    Name,
+    Area,
+    Height,
+    Width,
+    Perimeter,
+    MaxSide,
+}
+
+impl AtlasPacker {
+    pub const ALL: [AtlasPacker; 3] = [AtlasPacker::Skyline, AtlasPacker::Maxrects, AtlasPacker::Guillotine];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AtlasPacker::Skyline => "skyline",
+            AtlasPacker::Maxrects => "maxrects",
+            AtlasPacker::Guillotine => "guillotine",
+        }
+    }
+}
+
+impl AtlasSort {
+    pub const ALL: [AtlasSort; 6] = [
+        AtlasSort::Name,
+        AtlasSort::Area,
+        AtlasSort::Height,
+        AtlasSort::Width,
+        AtlasSort::Perimeter,
+        AtlasSort::MaxSide,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AtlasSort::Name => "name",
+            AtlasSort::Area => "area",
+            AtlasSort::Height => "height",
+            AtlasSort::Width => "width",
+            AtlasSort::Perimeter => "perimeter",
+            AtlasSort::MaxSide => "max_side",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct WebOptions {
+    pub port: Option<u16>,
+    pub initial_memory_mb: Option<u32>,
+    pub preload_budget_mb: Option<u32>,
+    // asset globs added to the core preload bundle, or embedded into the wasm
+    #[serde(default)]
+    pub preload: Vec<String>,
+    #[serde(default)]
+    pub embed: Vec<String>,
+    #[serde(default)]
+    pub packages: BTreeMap<String, Vec<String>>,
+    // splits the .data bundles into chunks of this many MB, fetched by a resumable loader
+    pub chunk_mb: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct ToolchainOptions {
+    pub c_compiler: Option<String>,
+    pub shdc: Option<String>,
+    pub sokol: Option<String>,
+    pub engine: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct ShaderOptions {
+    #[serde(default)]
+    pub slang: Vec<String>,
+    #[serde(default)]
+    pub targets: Vec<String>,
+    #[serde(default)]
+    pub programs: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct AssetOptions {
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub atlas: AtlasOptions,
+    // fonts baked into atlases at build time, by FontName
+    #[serde(default)]
+    pub fonts: BTreeMap<String, FontBakeOptions>,
+}
+
+// how a baked font stores its glyphs
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum FontMode {
+    // coverage bitmaps, sharp at the baked sizes only
+    #[default]
+    Bitmap,
+    // multi-channel signed distance fields, scaled to any size by the shader
+    Msdf,
+}
+
+impl FontMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FontMode::Bitmap => "bitmap",
+            FontMode::Msdf => "msdf",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FontBakeOptions {
+    pub mode: Option<FontMode>,
+    // pixel sizes, one atlas each; an msdf font takes a single size
+    #[serde(default)]
+    pub sizes: Vec<u32>,
+    // "32-126", "0x400-0x4ff" or "U+20AC" style codepoint ranges, plus single characters
+    #[serde(default)]
+    pub ranges: Vec<String>,
+    pub chars: Option<String>,
+    // false thresholds bitmap glyphs for pixel fonts
+    pub antialias: Option<bool>,
+    // distance field range of an msdf font, in pixels
+    pub distance_range: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct AppOptions {
+    pub title: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub vsync: Option<bool>,
+    pub icon: Option<String>,
+    pub orientation: Option<String>,
+    pub save_dir_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct GenerateOptions {
+    #[serde(default)]
+    pub vet: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct TestOptions {
+    pub frames: Option<u32>,
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub replays: Vec<String>,
+    #[serde(default)]
+    pub golden_scenes: Vec<String>,
+    pub golden_tolerance: Option<u8>,
+    pub golden_max_diff_percent: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct BenchOptions {
+    #[serde(default)]
+    pub scenes: Vec<String>,
+    pub frames: Option<u32>,
+    pub threshold_percent: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct TargetOptions {
+    #[serde(default)]
+    pub libs: Vec<String>,
+    #[serde(default)]
+    pub system_libs: Vec<String>,
+    #[serde(default)]
+    pub frameworks: Vec<String>,
+    #[serde(default)]
+    pub linker_flags: Vec<String>,
+    #[serde(default)]
+    pub runtime_libs: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct UpdateOptions {
+    pub base_url: Option<String>,
+    pub channel: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct PathsOptions {
+    pub assets: Option<String>,
+    pub source: Option<String>,
+    pub shaders: Option<String>,
+    pub build: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct WatchOptions {
+    pub debounce_ms: Option<u64>,
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[serde(default)]
+    pub stages: BTreeMap<String, Vec<String>>,
+}
+
+// caps for every compiler and script a build spawns, unset ones don't apply
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct LimitsOptions {
+    pub memory_mb: Option<u64>,
+    pub cpu_seconds: Option<u64>,
+    pub timeout_seconds: Option<u64>,
+}
+
+// which documentation set a docs trigger feeds
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DocsCategory {
+    Public,
+    // also part of the internal set, together with everything public
+    Internal,
+    Examples,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct DocsOptions {
+    pub dir: Option<String>,
+    pub target: Option<String>,
+    // trigger tag -> category, e.g. "@doc" = "public"
+    #[serde(default)]
+    pub triggers: BTreeMap<String, DocsCategory>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+pub struct DeployOptions {
+    pub itch: Option<ItchOptions>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ItchOptions {
+    // "user/game", as in https://user.itch.io/game
+    pub project: String,
+    // pushed when `bonsai deploy itch` gets no --target (default: web and desktop)
+    #[serde(default)]
+    pub targets: Vec<String>,
+    pub config: Option<String>,
+    // target -> butler channel, defaults to html5 for web and the host platform for desktop
+    #[serde(default)]
+    pub channels: BTreeMap<String, String>,
+}
+
+// the steps of `bonsai release`, in the order they run
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ReleaseOptions {
+    // built and packaged, default: web and desktop
+    #[serde(default)]
+    pub targets: Vec<String>,
+    pub config: Option<String>,
+    // `bonsai test` before anything is built, default: true
+    pub test: Option<bool>,
+    // `bonsai docs`, default: when [docs.triggers] is set
+    pub docs: Option<bool>,
+    // its "## Unreleased" section becomes the released version's
+    pub changelog: Option<String>,
+    // `{version}` is replaced, an empty tag skips tagging
+    pub tag: Option<String>,
+    // pushed once the release is tagged
+    #[serde(default)]
+    pub deploy: Vec<String>,
+}
+
+const MANIFEST_TEMPLATE_COMMENTS: &str = r#"
+# Optional configuration. Uncomment a section to override its defaults.
+# Add 'keep_builds = 5' to [build] to change how many snapshots per target
+# 'bonsai build' keeps in .bonsai/builds (0 disables them).
+# 'freshness = "hash"' compares file contents instead of modification times
+# (default: "auto", which switches to hashes on bind mounts and network shares).
+
+# [app]
+# title = "My Game"         # window title and web page title (default: project name)
+# width = 1280              # initial window size in pixels
+# height = 720
+# vsync = true
+# icon = "assets/images/icon.png"
+# orientation = "any"       # any, landscape or portrait
+# save_dir_name = "MyGame"  # folder for saves in the platform's app data directory (default: project name)
+
+# [profile.debug]           # build configs, picked with --config (built in: debug, release, profiling)
+# inherits = "release"      # start from another profile (default: debug, built-in profiles start from scratch)
+# debug = true              # keep debug symbols: odin -debug, emcc -g and the debug sokol libraries
+# opt = "none"              # odin -o: level: "none", "minimal", "size", "speed" or "aggressive"
+# bounds_check = true       # false passes -no-bounds-check
+# emcc_opt = "O2"           # web: emcc optimization level, "O0" to "O3", "Os" or "Oz"
+# defines = { LOG_LEVEL = 2 } # -define:NAME=value constants, read with #config
+# odin_flags = []           # extra flags passed to 'odin build' for this config
+# link_assets = false       # desktop: link build/desktop/assets to assets/ instead of copying
+
+# [profile.profiling]       # like release (-o:speed) but with debug symbols
+# defines = { TRACY_ENABLE = true }
+
+# [target.desktop]          # also [target.windows], [target.linux], [target.macos] and [target.web]
+# libs = ["libs/steam/libsteam_api.so"] # native libraries linked into the game
+# system_libs = ["X11"]     # linked as -lX11 (X11.lib on windows)
+# frameworks = ["CoreAudio"] # macos frameworks
+# linker_flags = []         # passed through to the linker as-is
+# runtime_libs = []         # shared libraries copied next to the desktop binary
+
+# [update]
+# base_url = "https://cdn.example.com/my-game/{version}/{platform}" # writes build/<target>/update.json on every build
+# channel = "stable"
+
+# [web]
+# port = 8080               # default port used by 'bonsai run --web'
+# initial_memory_mb = 64    # initial WASM heap size
+# preload_budget_mb = 32    # warn when the core preload bundle grows past this size
+# preload = ["assets/data/*.json"] # assets preloaded besides assets/audio and assets/fonts
+# embed = []                # assets embedded into the wasm, readable before any preloading
+# chunk_mb = 16             # split .data bundles into chunks fetched by a resumable loader
+# [web.packages]            # assets split out of the core bundle, fetched with bonsaiLoadPackage(name)
+# level1 = ["assets/levels/level1/**"]
+
+# [assets]
+# exclude = ["**/*.aseprite", "**/*.psd"] # files left out of desktop and web builds
+# [assets.atlas]
+# max_size = 2048           # maximum atlas page width/height in pixels
+# padding = 2               # padding between packed sprites in pixels
+# uv_constants = false      # also emit a compile-time SPRITE_DATA table in sprite.odin
+# packer = "skyline"        # "skyline", "maxrects" or "guillotine"
+# sort = "name"             # "name", "area", "height", "width", "perimeter" or "max_side"
+# color_space = "srgb"      # "srgb" or "linear", how the image colors are encoded
+# premultiply_alpha = false # multiply colors by alpha while packing
+# extrude = 0               # repeat sprite edge pixels outwards
+# tile_extrude = 1          # at least this much for tiles, stops seams when the camera moves sub-pixel
+# bleed = false             # give transparent pixels the color of their visible neighbours
+# trim = false              # cut transparent borders off sprites, sprite.odin records what was cut
+# allow_rotation = false    # let the packer turn sprites 90 degrees clockwise to fit them better
+# [assets.atlas.sprites."assets/images/ui/**"] # per-sprite overrides, the longest matching pattern wins
+# extrude = 2
+# bleed = true
+# [assets.atlas.groups]     # sprites packed into their own textures, atlas_<group>.png
+# ui = ["assets/images/ui/**"]
+# [assets.fonts.Roboto]     # bake assets/fonts/Roboto.ttf into atlases, the TTF is left out of builds
+# mode = "bitmap"           # bitmap or msdf
+# sizes = [16, 32]          # pixel sizes, one atlas each (msdf: one size, default 64)
+# ranges = ["32-126", "0x100-0x17f"] # codepoint ranges (default: 32-126)
+# chars = "€"               # extra characters
+
+# [toolchain]
+# c_compiler = "msvc"       # windows only: msvc, clang-cl or zig
+# shdc = "tools/sokol-shdc" # vendored sokol-shdc binary instead of the downloaded one
+# sokol = "v1.0.0"          # sokol-odin version, written by 'bonsai sokol upgrade'
+# engine = "v0.2.0"         # engine version embedded into builds, written by 'bonsai init'
+
+# [shaders]
+# targets = ["macos", "web"] # also compile shader languages for these platforms
+# slang = ["glsl430"]        # explicit sokol-shdc languages, overrides targets
+# programs = {}              # shaders registered by 'bonsai shader new', compiled even outside source/game/shaders
+
+# [generate]
+# vet = ["sprites", "fonts"] # committed generated files checked by 'bonsai generate --check' (default: all)
+
+# [test]
+# frames = 300              # frames each headless session runs before exiting
+# timeout = 60              # seconds before a session counts as hung
+# replays = ["tests/replays/intro.rec"] # known-good sessions replayed by 'bonsai test'
+# golden_scenes = ["main_menu"] # scenes captured by 'bonsai test --golden' and compared to tests/golden
+# golden_tolerance = 8      # per-channel difference (0-255) still counted as equal
+# golden_max_diff_percent = 0.1 # share of differing pixels allowed before a scene fails
+
+# [bench]
+# scenes = ["stress_test"]  # scenes measured by 'bonsai bench'
+# frames = 600              # frames recorded per scene
+# threshold_percent = 10    # allowed slowdown against tests/bench/baseline.json
+
+# [watch]
+# debounce_ms = 200         # changes within this window are batched into one rebuild
+# ignore = ["assets/**/*.psd"] # paths the watchers never react to
+# [watch.stages]            # paths mapped to the stages they rebuild
+# "assets/ui/**" = ["sprites"] # stages: sprites, fonts, audio, scenes, maps, app, shaders, data
+
+# [paths]                   # project layout, relative to this file
+# assets = "../art/assets"  # default: assets
+# source = "src"            # default: source (the game package is <source>/game)
+# shaders = "shaders"       # game shaders, default: <source>/game/shaders
+# build = "out"             # default: build
+
+# [docs]                    # used by 'bonsai docs' without a trigger argument
+# dir = "bonsai"            # directory scanned for doc comments
+# target = "docs"           # output directory
+# [docs.triggers]           # comment tags and the set they belong to: public, internal or examples
+# "@doc" = "public"
+# "@internal" = "internal"
+# "@example" = "examples"
+
+# [tools]                   # external tools checked before every build and by 'bonsai doctor'
+# aseprite = "1.3"          # minimum version
+# ffmpeg = "*"              # any version
+# butler = { version = "15.0", path = "tools/butler" }
+
+# [deploy.itch]             # used by 'bonsai deploy itch', which pushes builds with butler
+# project = "user/my-game"  # itch.io user and game, as in https://user.itch.io/my-game
+# targets = ["web", "desktop"] # targets pushed by default
+# config = "release"        # build config the pushed builds use
+# [deploy.itch.channels]    # default: html5 for web, windows, linux or osx for desktop
+# web = "html5"
+
+# [release]                 # used by 'bonsai release'
+# targets = ["web", "desktop"] # built and packaged into <build>/release
+# config = "release"        # build config
+# test = true               # run 'bonsai test' first
+# docs = true               # regenerate the docs (default: when [docs.triggers] is set)
+# changelog = "CHANGELOG.md" # its Unreleased section gets the version and date
+# tag = "v{version}"        # git tag, "" skips tagging
+# deploy = ["itch"]         # deploys run after tagging
+"#;
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+pub fn load_manifest(project_root: &Path) -> Result<Manifest, CustomError> {
+    let manifest_content = fs::read_to_string(project_root.join(MANIFEST_FILE))?;
+    parse_manifest(&manifest_content)
+}
+
+/// Parses manifest text, reading a deprecated `[atlas]` section as `[assets.atlas]`.
+pub fn parse_manifest(text: &str) -> Result<Manifest, CustomError> {
+    let mut manifest: Manifest = toml_edit::de::from_str(text)
+        .map_err(|e| CustomError::ValidationError(t!("Invalid manifest: {}", e)))?;
+
+    if let Some(atlas) = manifest.legacy_atlas.take() {
+        if !is_default(&manifest.assets.atlas) {
+            return Err(CustomError::ValidationError(t!(
+                "bonsai.toml has both [atlas] and [assets.atlas], move the [atlas] settings into [assets.atlas] by hand"
+            )));
+        }
+        manifest.assets.atlas = atlas;
+        manifest.uses_legacy_atlas = true;
+    }
+    Ok(manifest)
+}
+
+/// Warns about deprecated sections `manifest` was read from.
+pub fn warn_deprecated_sections(manifest: &Manifest, ui: &Ui) {
+    if manifest.uses_legacy_atlas {
+        ui.message(&t!(
+            "{} [atlas] in bonsai.toml is deprecated, run 'bonsai manifest fix' to move it to [assets.atlas].",
+            "[WARNING]".yellow()
+        ));
+    }
+}
+
+/// Moves a deprecated `[atlas]` section to `[assets.atlas]`, keeping its comments. Returns
+/// whether anything moved.
+pub fn migrate_legacy_sections(doc: &mut DocumentMut) -> Result<bool, CustomError> {
+    let Some(atlas) = doc.remove("atlas") else {
+        return Ok(false);
+    };
+    let Item::Table(mut atlas) = atlas else {
+        return Err(CustomError::ValidationError(t!("[{}] in bonsai.toml isn't a table", "atlas")));
+    };
+
+    let assets = doc.entry("assets").or_insert_with(|| {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        Item::Table(table)
+    });
+    let Item::Table(assets) = assets else {
+        return Err(CustomError::ValidationError(t!("[{}] in bonsai.toml isn't a table", "assets")));
+    };
+    if assets.contains_key("atlas") {
+        return Err(CustomError::ValidationError(t!(
+            "bonsai.toml has both [atlas] and [assets.atlas], move the [atlas] settings into [assets.atlas] by hand"
+        )));
+    }
+    // explicit again, so the header shows up as [assets.atlas] where [atlas] was
+    atlas.set_implicit(false);
+    assets.insert("atlas", Item::Table(atlas));
+    Ok(true)
+}
+
+/// Writes `after` over the manifest read as `before`, unless they match, and journals the
+/// change in [`crate::manifest_history::HISTORY_LOG`] so `bonsai manifest undo` can revert it.
+pub fn write_manifest(
+    project_root: &Path,
+    before: &str,
+    after: &str,
+    changes: Vec<String>,
+) -> Result<(), CustomError> {
+    if before == after {
+        return Ok(());
+    }
+    fs::write(project_root.join(MANIFEST_FILE), after)?;
+    record_change(project_root, before, after, changes)
 }
 
 pub fn update_manifest(project_root: &Path, ui: &Ui) -> Result<(), CustomError> {
@@ -47,10 +766,7 @@ pub fn update_manifest(project_root: &Path, ui: &Ui) -> Result<(), CustomError>
     }
 
     if !manifest_path.exists() {
-        return Err(CustomError::ValidationError(format!(
-            "Bonsai manifest not found at {:?}",
-            manifest_path
-        )));
+        return Err(CustomError::ValidationError(t!("Bonsai manifest not found at {}", format!("{:?}", manifest_path))));
     }
 
     let manifest_content = fs::read_to_string(&manifest_path)?;
@@ -61,8 +777,9 @@ pub fn update_manifest(project_root: &Path, ui: &Ui) -> Result<(), CustomError>
     }
 
     let deps = doc["systems"].as_table_mut().ok_or_else(|| {
-        CustomError::ValidationError("Manifest [systems] is not a table.".to_string())
+        CustomError::ValidationError(t!("Manifest [systems] is not a table."))
     })?;
+    let mut changes = Vec::new();
 
     for entry in fs::read_dir(&systems_path)? {
         let entry = entry?;
@@ -76,7 +793,7 @@ pub fn update_manifest(project_root: &Path, ui: &Ui) -> Result<(), CustomError>
 
             let sys_toml_path = path.join(SYSTEM_MANIFEST);
             if !sys_toml_path.exists() {
-                ui.message(&format!(
+                ui.message(&t!(
                     "  + Auto-generating manifest for system: '{}'",
                     system_name
                 ));
@@ -84,10 +801,11 @@ pub fn update_manifest(project_root: &Path, ui: &Ui) -> Result<(), CustomError>
             }
 
             if !deps.contains_key(system_name) {
-                ui.message(&format!(
+                ui.message(&t!(
                     "  + Discovered new local system: '{}'",
                     system_name
                 ));
+                changes.push(format!("Discovered new local system '{}'", system_name));
 
                 let mut t = InlineTable::new();
                 t.insert(
@@ -110,22 +828,47 @@ pub fn update_manifest(project_root: &Path, ui: &Ui) -> Result<(), CustomError>
                 if let Some(path_str) = path_val.as_str() {
                     let full_path = project_root_abs.join(path_str);
 
+                    // editable systems live outside bonsai/systems on purpose and are only
+                    // dropped by `bonsai remove`; relink them after a fresh clone
+                    let editable = inline_table
+                        .get("editable")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false);
+                    if editable {
+                        let link = systems_path.join(name);
+                        if !full_path.exists() {
+                            ui.message(&t!(
+                                "  ! Editable system '{}' points to missing path '{}'",
+                                name, path_str
+                            ));
+                        } else if !link.exists() {
+                            ui.message(&t!("  + Relinking editable system: '{}'", name));
+                            link_dir(&full_path, &link)?;
+                        }
+                        continue;
+                    }
+
                     if !full_path.exists() {
-                        ui.message(&format!("  - Pruning missing system: '{}'", name));
+                        ui.message(&t!("  - Pruning missing system: '{}'", name));
+                        changes.push(format!("Pruned system '{}', its path '{}' is missing", name, path_str));
                         to_remove.push(name.to_string());
                         continue;
                     }
 
                     match is_path_safe(&systems_root_abs, &full_path) {
                         Ok(false) => {
-                            ui.message(&format!(
+                            ui.message(&t!(
                                 "  - Removing unsafe system path (outside source): '{}'",
                                 name
                             ));
+                            changes.push(format!(
+                                "Removed system '{}', its path '{}' is outside bonsai/systems",
+                                name, path_str
+                            ));
                             to_remove.push(name.to_string());
                         }
                         Err(e) => {
-                            ui.message(&format!(
+                            ui.message(&t!(
                                 "  ! Error verifying system path '{}': {}",
                                 name, e
                             ));
@@ -141,16 +884,135 @@ pub fn update_manifest(project_root: &Path, ui: &Ui) -> Result<(), CustomError>
         deps.remove(&name);
     }
 
-    fs::write(&manifest_path, doc.to_string())?;
+    write_manifest(project_root, &manifest_content, &doc.to_string(), changes)
+}
 
-    Ok(())
+pub fn set_system_entry(
+    project_root: &Path,
+    name: &str,
+    path: &str,
+    editable: bool,
+) -> Result<(), CustomError> {
+    let manifest_path = project_root.join(MANIFEST_FILE);
+    let manifest_content = fs::read_to_string(&manifest_path)?;
+    let mut doc = manifest_content.parse::<DocumentMut>()?;
+
+    if doc.get("systems").is_none() {
+        doc["systems"] = table();
+    }
+    let systems = doc["systems"].as_table_mut().ok_or_else(|| {
+        CustomError::ValidationError(t!("Manifest [systems] is not a table."))
+    })?;
+
+    let mut t = InlineTable::new();
+    t.insert("path", Value::from(path));
+    if editable {
+        t.insert("editable", Value::from(true));
+    }
+    systems.insert(name, value(t));
+
+    let change = format!("Set system '{}' to path '{}'{}", name, path, if editable { " (editable)" } else { "" });
+    write_manifest(project_root, &manifest_content, &doc.to_string(), vec![change])
+}
+
+/// Drops a `[systems]` entry, used for editable systems that `update_manifest` never prunes.
+pub fn remove_system_entry(project_root: &Path, name: &str) -> Result<(), CustomError> {
+    let manifest_path = project_root.join(MANIFEST_FILE);
+    let manifest_content = fs::read_to_string(&manifest_path)?;
+    let mut doc = manifest_content.parse::<DocumentMut>()?;
+
+    if let Some(systems) = doc.get_mut("systems").and_then(|s| s.as_table_mut()) {
+        systems.remove(name);
+    }
+
+    let change = format!("Removed system '{}'", name);
+    write_manifest(project_root, &manifest_content, &doc.to_string(), vec![change])
+}
+
+pub fn register_shader(project_root: &Path, name: &str, path: &str) -> Result<(), CustomError> {
+    let manifest_path = project_root.join(MANIFEST_FILE);
+    let manifest_content = fs::read_to_string(&manifest_path)?;
+    let mut doc = manifest_content.parse::<DocumentMut>()?;
+
+    if doc.get("shaders").is_none() {
+        let mut shaders = Table::new();
+        shaders.set_implicit(true);
+        doc["shaders"] = Item::Table(shaders);
+    }
+    if doc["shaders"].get("programs").is_none() {
+        doc["shaders"]["programs"] = table();
+    }
+
+    let programs = doc["shaders"]["programs"].as_table_mut().ok_or_else(|| {
+        CustomError::ValidationError(t!("Manifest [shaders.programs] is not a table."))
+    })?;
+    programs.insert(name, value(path));
+
+    let change = format!("Registered shader '{}' at '{}'", name, path);
+    write_manifest(project_root, &manifest_content, &doc.to_string(), vec![change])
+}
+
+pub fn set_manifest_value(
+    project_root: &Path,
+    section: &str,
+    key: &str,
+    new_value: &str,
+) -> Result<(), CustomError> {
+    let manifest_path = project_root.join(MANIFEST_FILE);
+    let manifest_content = fs::read_to_string(&manifest_path)?;
+    let mut doc = manifest_content.parse::<DocumentMut>()?;
+
+    if doc.get(section).is_none() {
+        doc[section] = table();
+    }
+    doc[section][key] = value(new_value);
+
+    let change = format!("Set [{}] {} = \"{}\"", section, key, new_value);
+    write_manifest(project_root, &manifest_content, &doc.to_string(), vec![change])
+}
+
+/// Appends values to an array in `[target.<name>]`, skipping ones already present.
+pub fn add_target_entries(
+    project_root: &Path,
+    target: &str,
+    key: &str,
+    values: &[String],
+) -> Result<(), CustomError> {
+    let manifest_path = project_root.join(MANIFEST_FILE);
+    let manifest_content = fs::read_to_string(&manifest_path)?;
+    let mut doc = manifest_content.parse::<DocumentMut>()?;
+
+    if doc.get("target").is_none() {
+        let mut targets = Table::new();
+        targets.set_implicit(true);
+        doc["target"] = Item::Table(targets);
+    }
+    if doc["target"].get(target).is_none() {
+        doc["target"][target] = table();
+    }
+    if doc["target"][target].get(key).is_none() {
+        doc["target"][target][key] = value(Array::new());
+    }
+
+    let entries = doc["target"][target][key].as_array_mut().ok_or_else(|| {
+        CustomError::ValidationError(t!("Manifest [target.{}] {} is not an array.", target, key))
+    })?;
+    let mut changes = Vec::new();
+    for new_value in values {
+        if !entries.iter().any(|v| v.as_str() == Some(new_value)) {
+            entries.push(new_value.as_str());
+            changes.push(format!("Added '{}' to [target.{}] {}", new_value, target, key));
+        }
+    }
+
+    write_manifest(project_root, &manifest_content, &doc.to_string(), changes)
 }
 
 fn create_default_system_toml(path: &Path, name: &str) -> Result<(), CustomError> {
     let template = format!(
         r#"[system]
-name = {}
-version = {}
+name = "{}"
+version = "{}"
 description = "Auto-generated description for {}"
 
 [dependencies]
@@ -171,28 +1033,16 @@ pub fn create_manifest(destination: &Path, project_name: &str) -> Result<(), Cus
             name: project_name.to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
         },
-        build: BuildOptions { web_libs: vec![] },
-        systems: BTreeMap::new(),
+        ..Default::default()
     };
 
     let manifest_path = destination.join(MANIFEST_FILE);
-    let toml_content = toml_edit::ser::to_string_pretty(&manifest).map_err(|e| {
-        CustomError::ValidationError(format!("Failed to serialize manifest: {}", e))
+    let mut toml_content = toml_edit::ser::to_string_pretty(&manifest).map_err(|e| {
+        CustomError::ValidationError(t!("Failed to serialize manifest: {}", e))
     })?;
+    toml_content.push_str(MANIFEST_TEMPLATE_COMMENTS);
 
     fs::write(manifest_path, toml_content)?;
 
     Ok(())
 }
-
-fn is_path_safe(root: &Path, child: &Path) -> Result<bool, CustomError> {
-    let root_abs = root
-        .canonicalize()
-        .map_err(|e| CustomError::ValidationError(format!("Root not found: {}", e)))?;
-
-    let child_abs = child
-        .canonicalize()
-        .map_err(|e| CustomError::ValidationError(format!("System path not found: {}", e)))?;
-
-    Ok(child_abs.starts_with(&root_abs))
-}
@@ -0,0 +1,219 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::migrate_legacy_sections;
+use std::collections::BTreeMap;
+use toml_edit::{DocumentMut, Item};
+
+const SYSTEMS_HEADER: &str = "[systems]";
+
+/// A `[systems]` name with more than one distinct source after a merge.
+pub struct SystemConflict {
+    pub name: String,
+    // normalized `{ path = ... }` / `"1.2"` values, in file order
+    pub candidates: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct ManifestFix {
+    pub text: String,
+    pub conflict_markers: usize,
+    pub duplicates: Vec<String>,
+    pub resolved: Vec<(String, String)>,
+    // a deprecated [atlas] section was moved to [assets.atlas]
+    pub moved_atlas: bool,
+}
+
+impl ManifestFix {
+    pub fn is_clean(&self) -> bool {
+        self.conflict_markers == 0 && self.duplicates.is_empty() && self.resolved.is_empty() && !self.moved_atlas
+    }
+}
+
+// `[systems]` and `[[bin]]` style headers, without a trailing comment
+fn table_header(line: &str) -> Option<&str> {
+    let line = line.split('#').next().unwrap_or("").trim();
+    (line.starts_with('[') && !line.contains('=')).then_some(line)
+}
+
+// git writes `<<<<<<< ours`, `||||||| base` (diff3 only), `=======` and `>>>>>>> theirs`
+fn marker(line: &str) -> Option<&'static str> {
+    ["<<<<<<<", "|||||||", "=======", ">>>>>>>"]
+        .into_iter()
+        .find(|m| line.starts_with(m))
+}
+
+/// Replaces merge conflict hunks inside `[systems]` with both sides, leaving the duplicate
+/// entries for [`resolve_duplicates`]. Hunks anywhere else need a human.
+fn merge_conflict_hunks(text: &str) -> Result<(Vec<String>, usize), CustomError> {
+    let mut out = Vec::new();
+    let mut hunks = 0;
+    let mut section = String::new();
+    let mut lines = text.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        if marker(line) != Some("<<<<<<<") {
+            if let Some(header) = table_header(line) {
+                section = header.to_string();
+            }
+            out.push(line.to_string());
+            continue;
+        }
+
+        hunks += 1;
+        let mut side = Vec::new();
+        let mut in_base = false;
+        loop {
+            let Some((_, line)) = lines.next() else {
+                return Err(CustomError::ValidationError(t!(
+                    "Unterminated merge conflict starting at line {}",
+                    index + 1
+                )));
+            };
+            match marker(line) {
+                Some(">>>>>>>") => break,
+                Some("|||||||") => in_base = true,
+                Some("=======") => in_base = false,
+                Some(_) => {
+                    return Err(CustomError::ValidationError(t!(
+                        "Nested merge conflict in the hunk starting at line {}",
+                        index + 1
+                    )));
+                }
+                None if !in_base => side.push(line.to_string()),
+                None => {}
+            }
+        }
+
+        let only_entries = side.iter().all(|l| {
+            let l = l.trim();
+            l.is_empty() || l.starts_with('#') || l.contains('=') && table_header(l).is_none()
+        });
+        if section != SYSTEMS_HEADER || !only_entries {
+            return Err(CustomError::ValidationError(t!(
+                "Merge conflict at line {} is outside [systems], resolve it by hand first",
+                index + 1
+            )));
+        }
+        out.extend(side);
+    }
+
+    Ok((out, hunks))
+}
+
+// the key and a whitespace-normalized value of a `name = ...` line
+fn parse_entry(line: &str) -> Option<(String, String)> {
+    let doc = line.parse::<DocumentMut>().ok()?;
+    let (key, item) = doc.iter().next()?;
+    let value = match item {
+        Item::Value(value) => {
+            let mut value = value.clone();
+            if let Some(table) = value.as_inline_table_mut() {
+                table.fmt();
+            }
+            value.decor_mut().clear();
+            value.to_string()
+        }
+        _ => return None,
+    };
+    Some((key.to_string(), value))
+}
+
+/// Collapses repeated `[systems]` names. Identical entries are merged silently, diverging
+/// ones are settled by `choose`, which picks one of the candidates.
+fn resolve_duplicates(
+    lines: Vec<String>,
+    fix: &mut ManifestFix,
+    choose: &mut dyn FnMut(&SystemConflict) -> Result<usize, CustomError>,
+) -> Result<Vec<String>, CustomError> {
+    let mut section = String::new();
+    let mut entries: BTreeMap<String, Vec<(usize, String)>> = BTreeMap::new();
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(header) = table_header(line) {
+            section = header.to_string();
+            continue;
+        }
+        if section == SYSTEMS_HEADER
+            && let Some((name, value)) = parse_entry(line)
+        {
+            entries.entry(name).or_default().push((index, value));
+        }
+    }
+
+    let mut lines: Vec<Option<String>> = lines.into_iter().map(Some).collect();
+    for (name, occurrences) in entries {
+        if occurrences.len() < 2 {
+            continue;
+        }
+
+        let mut candidates: Vec<String> = Vec::new();
+        for (_, value) in &occurrences {
+            if !candidates.contains(value) {
+                candidates.push(value.clone());
+            }
+        }
+
+        let keep = if candidates.len() == 1 {
+            fix.duplicates.push(name.clone());
+            candidates[0].clone()
+        } else {
+            let conflict = SystemConflict {
+                name: name.clone(),
+                candidates,
+            };
+            let choice = choose(&conflict)?;
+            let value = conflict.candidates.get(choice).cloned().ok_or_else(|| {
+                CustomError::ValidationError(t!("Invalid choice for system '{}'", name))
+            })?;
+            fix.resolved.push((name.clone(), value.clone()));
+            value
+        };
+
+        // the chosen entry takes the place of the first occurrence
+        let (first, _) = occurrences[0];
+        lines[first] = Some(format!("{} = {}", name, keep));
+        for (index, _) in &occurrences[1..] {
+            lines[*index] = None;
+        }
+    }
+
+    Ok(lines.into_iter().flatten().collect())
+}
+
+/// Resolves what a git merge typically leaves in `bonsai.toml`: conflict hunks and duplicate
+/// names in `[systems]`. The result is parsed again, a deprecated `[atlas]` section moved to
+/// `[assets.atlas]` and the `[systems]` table normalized (sorted, one inline table per system),
+/// everything else keeps its formatting.
+pub fn fix_manifest(
+    text: &str,
+    choose: &mut dyn FnMut(&SystemConflict) -> Result<usize, CustomError>,
+) -> Result<ManifestFix, CustomError> {
+    let mut fix = ManifestFix::default();
+
+    let (lines, hunks) = merge_conflict_hunks(text)?;
+    fix.conflict_markers = hunks;
+    let lines = resolve_duplicates(lines, &mut fix, choose)?;
+
+    let mut merged = lines.join("\n");
+    if text.ends_with('\n') {
+        merged.push('\n');
+    }
+    let mut doc = merged.parse::<DocumentMut>()?;
+    fix.moved_atlas = migrate_legacy_sections(&mut doc)?;
+
+    if let Some(systems) = doc.get_mut("systems").and_then(|s| s.as_table_mut()) {
+        systems.sort_values();
+        for (mut key, item) in systems.iter_mut() {
+            key.fmt();
+            if let Some(table) = item.as_inline_table_mut() {
+                table.fmt();
+            }
+            if let Some(value) = item.as_value_mut() {
+                value.decor_mut().clear();
+                value.decor_mut().set_prefix(" ");
+            }
+        }
+    }
+
+    fix.text = doc.to_string();
+    Ok(fix)
+}
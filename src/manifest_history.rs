@@ -0,0 +1,100 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Journal of every change bonsai made to `bonsai.toml`, one JSON object per line.
+pub const HISTORY_LOG: &str = ".bonsai/history.log";
+// older entries are dropped, each one holds the whole manifest twice
+const MAX_ENTRIES: usize = 200;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ManifestChange {
+    pub time: String,
+    /// The bonsai command line that made the change.
+    pub command: String,
+    pub changes: Vec<String>,
+    /// First changed line, 1-based.
+    pub line: usize,
+    /// The changed lines as they were and as they became.
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    pub manifest_before: String,
+    pub manifest_after: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub undone: bool,
+}
+
+fn current_command() -> String {
+    std::iter::once("bonsai".to_string())
+        .chain(std::env::args().skip(1))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// where the texts start to differ, and the lines between their common head and tail
+fn snippets(before: &str, after: &str) -> (usize, Vec<String>, Vec<String>) {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let head = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let tail = old[head..]
+        .iter()
+        .rev()
+        .zip(new[head..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let cut = |lines: &[&str]| lines[head..lines.len() - tail].iter().map(|l| l.to_string()).collect();
+    (head + 1, cut(&old), cut(&new))
+}
+
+pub fn load_history(project_root: &Path) -> Vec<ManifestChange> {
+    fs::read_to_string(project_root.join(HISTORY_LOG))
+        .map(|log| {
+            log.lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn save_history(project_root: &Path, history: &[ManifestChange]) -> Result<(), CustomError> {
+    let path = project_root.join(HISTORY_LOG);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut log = String::new();
+    for change in &history[history.len().saturating_sub(MAX_ENTRIES)..] {
+        let line = serde_json::to_string(change).map_err(|e| {
+            CustomError::ValidationError(t!("Failed to serialize manifest history: {}", e))
+        })?;
+        log.push_str(&line);
+        log.push('\n');
+    }
+    fs::write(path, log)?;
+    Ok(())
+}
+
+/// Appends a change from `before` to `after` made by the running command, described by `changes`.
+pub fn record_change(
+    project_root: &Path,
+    before: &str,
+    after: &str,
+    changes: Vec<String>,
+) -> Result<(), CustomError> {
+    let (line, before_lines, after_lines) = snippets(before, after);
+    let mut history = load_history(project_root);
+    history.push(ManifestChange {
+        time: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        command: current_command(),
+        changes,
+        line,
+        before: before_lines,
+        after: after_lines,
+        manifest_before: before.to_string(),
+        manifest_after: after.to_string(),
+        undone: false,
+    });
+    save_history(project_root, &history)
+}
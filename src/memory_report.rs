@@ -0,0 +1,202 @@
+use crate::Ui;
+use crate::i18n::t;
+use colored::Colorize;
+use indicatif::HumanBytes;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+/// Where the injected script posts memory samples during `run --web --memory-report`.
+pub const MEMORY_REPORT_URL: &str = "/__bonsai/memory";
+const MIB: u64 = 1024 * 1024;
+// growth events listed one by one in the summary, the rest are only counted
+const LISTED_GROWTHS: usize = 10;
+
+// finds the module's memory when it is instantiated, reports every grow() on it and samples
+// the heap sizes every 2s, {url} is replaced
+pub const MEMORY_REPORT_SCRIPT: &str = r#"<script>
+(function () {
+  var start = performance.now(), memory = null;
+  function post(event) {
+    event.time = Math.round(performance.now() - start);
+    fetch("{url}", { method: "POST", body: JSON.stringify(event), keepalive: true }).catch(function () {});
+  }
+  function watch(instance) {
+    var exports = (instance && instance.exports) || {};
+    for (var name in exports) {
+      if (exports[name] instanceof WebAssembly.Memory) {
+        memory = exports[name];
+        post({ kind: "initial", wasm: memory.buffer.byteLength });
+        return;
+      }
+    }
+  }
+  ["instantiate", "instantiateStreaming"].forEach(function (name) {
+    var original = WebAssembly[name];
+    if (!original) return;
+    WebAssembly[name] = function () {
+      return original.apply(WebAssembly, arguments).then(function (result) {
+        watch(result.instance || result);
+        return result;
+      });
+    };
+  });
+  var grow = WebAssembly.Memory.prototype.grow;
+  WebAssembly.Memory.prototype.grow = function (pages) {
+    var before = this.buffer.byteLength, result;
+    try { result = grow.apply(this, arguments); }
+    catch (e) { post({ kind: "grow_failed", from: before, to: before + pages * 65536 }); throw e; }
+    memory = memory || this;
+    post({ kind: "grow", from: before, to: this.buffer.byteLength });
+    return result;
+  };
+  function sample() {
+    var heap = performance.memory;
+    post({ kind: "sample", wasm: memory ? memory.buffer.byteLength : null, js: heap ? heap.usedJSHeapSize : null });
+  }
+  setInterval(sample, 2000);
+  addEventListener("pagehide", sample);
+})();
+</script>
+"#;
+
+// `time` is milliseconds since the page loaded
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum MemoryEvent {
+    Initial { wasm: u64 },
+    Grow { time: u64, from: u64, to: u64 },
+    GrowFailed { time: u64, from: u64, to: u64 },
+    Sample { wasm: Option<u64>, js: Option<u64> },
+}
+
+struct Growth {
+    page_load: u32,
+    time: u64,
+    from: u64,
+    to: u64,
+}
+
+#[derive(Default)]
+struct Totals {
+    page_loads: u32,
+    initial_wasm: Option<u64>,
+    peak_wasm: u64,
+    // performance.memory is Chromium only
+    peak_js: Option<u64>,
+    growths: Vec<Growth>,
+    failed_growths: Vec<Growth>,
+}
+
+/// Wasm and JS heap sizes the page reported while the dev server ran, summarized when it
+/// stops to help pick `[web] initial_memory_mb`.
+#[derive(Default)]
+pub struct MemoryReport {
+    totals: Mutex<Totals>,
+}
+
+impl MemoryReport {
+    /// Records one JSON event posted by [`MEMORY_REPORT_SCRIPT`], false when it isn't one.
+    pub fn record(&self, body: &str) -> bool {
+        let Ok(event) = serde_json::from_str::<MemoryEvent>(body) else {
+            return false;
+        };
+        let Ok(mut totals) = self.totals.lock() else {
+            return false;
+        };
+
+        let page_load = totals.page_loads;
+        match event {
+            MemoryEvent::Initial { wasm } => {
+                totals.page_loads += 1;
+                totals.initial_wasm = Some(wasm);
+                totals.peak_wasm = totals.peak_wasm.max(wasm);
+            }
+            MemoryEvent::Grow { time, from, to } => {
+                totals.peak_wasm = totals.peak_wasm.max(to);
+                totals.growths.push(Growth { page_load, time, from, to });
+            }
+            MemoryEvent::GrowFailed { time, from, to } => {
+                totals.failed_growths.push(Growth { page_load, time, from, to });
+            }
+            MemoryEvent::Sample { wasm, js } => {
+                totals.peak_wasm = totals.peak_wasm.max(wasm.unwrap_or(0));
+                if let Some(js) = js {
+                    totals.peak_js = Some(totals.peak_js.unwrap_or(0).max(js));
+                }
+            }
+        }
+        true
+    }
+
+    pub fn print_summary(&self, ui: &Ui) {
+        let Ok(totals) = self.totals.lock() else {
+            return;
+        };
+        let prefix = "[MEMORY]".cyan().bold();
+
+        if totals.peak_wasm == 0 {
+            ui.message(&t!(
+                "{} The page reported no wasm memory, was it opened in a browser?",
+                prefix
+            ));
+            return;
+        }
+
+        let initial = totals
+            .initial_wasm
+            .map(|bytes| format!("{} initial, ", HumanBytes(bytes)))
+            .unwrap_or_default();
+        ui.message(&t!(
+            "{} Wasm heap: {}{} peak over {} page load(s)",
+            prefix,
+            initial,
+            HumanBytes(totals.peak_wasm),
+            totals.page_loads.max(1)
+        ));
+        if let Some(js) = totals.peak_js {
+            ui.message(&t!("{} JS heap: {} peak", prefix, HumanBytes(js)));
+        }
+
+        let describe = |growth: &Growth| {
+            format!(
+                "  load {} at {:.1}s: {} -> {}",
+                growth.page_load.max(1),
+                growth.time as f64 / 1000.0,
+                HumanBytes(growth.from),
+                HumanBytes(growth.to)
+            )
+        };
+
+        if totals.growths.is_empty() {
+            ui.message(&t!("{} The heap never grew past its initial size.", prefix));
+        } else {
+            ui.message(&t!("{} {} growth event(s):", prefix, totals.growths.len()));
+            for growth in totals.growths.iter().take(LISTED_GROWTHS) {
+                ui.message(&describe(growth));
+            }
+            if totals.growths.len() > LISTED_GROWTHS {
+                ui.message(&t!("  ... and {} more", totals.growths.len() - LISTED_GROWTHS));
+            }
+        }
+
+        if !totals.failed_growths.is_empty() {
+            ui.message(&t!(
+                "{} {} growth(s) failed, the page ran out of memory:",
+                "[WARNING]".yellow(),
+                totals.failed_growths.len()
+            ));
+            for growth in totals.failed_growths.iter().take(LISTED_GROWTHS) {
+                ui.message(&describe(growth));
+            }
+        }
+
+        // wasm memory never shrinks, so without growth there's nothing to say about lowering it
+        if !totals.growths.is_empty() {
+            ui.message(&t!(
+                "{} Set initial_memory_mb = {} under [web] in bonsai.toml to start at the peak.",
+                prefix,
+                totals.peak_wasm.div_ceil(MIB)
+            ));
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const VSWHERE_PATH: &str = "Microsoft Visual Studio/Installer/vswhere.exe";
+const VCVARS_PATH: &str = "VC/Auxiliary/Build/vcvarsall.bat";
+const VS7_REGISTRY_KEYS: &[&str] = &[
+    r"HKLM\SOFTWARE\WOW6432Node\Microsoft\VisualStudio\SxS\VS7",
+    r"HKLM\SOFTWARE\Microsoft\VisualStudio\SxS\VS7",
+];
+
+fn is_cl_available() -> bool {
+    Command::new("cl")
+        .arg("/?")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+fn find_with_vswhere() -> Option<PathBuf> {
+    let program_files = env::var("ProgramFiles(x86)")
+        .or_else(|_| env::var("ProgramFiles"))
+        .ok()?;
+    let vswhere = Path::new(&program_files).join(VSWHERE_PATH);
+
+    let output = Command::new(vswhere)
+        .args([
+            "-latest",
+            "-products",
+            "*",
+            "-requires",
+            "Microsoft.VisualStudio.Component.VC.Tools.x86.x64",
+            "-property",
+            "installationPath",
+        ])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| PathBuf::from(l.trim()))
+        .find(|p| p.join(VCVARS_PATH).exists())
+}
+
+fn find_with_registry() -> Option<PathBuf> {
+    let mut installs: Vec<(f32, PathBuf)> = Vec::new();
+
+    for key in VS7_REGISTRY_KEYS {
+        let output = match Command::new("reg").args(["query", key]).output() {
+            Ok(o) if o.status.success() => o,
+            _ => continue,
+        };
+
+        // lines look like: "    17.0    REG_SZ    C:\Program Files\Microsoft Visual Studio\2022\Community\"
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let parts: Vec<&str> = line.trim().splitn(3, "REG_SZ").collect();
+            if parts.len() != 2 {
+                continue;
+            }
+
+            if let Ok(version) = parts[0].trim().parse::<f32>() {
+                let path = PathBuf::from(parts[1].trim());
+                if path.join(VCVARS_PATH).exists() {
+                    installs.push((version, path));
+                }
+            }
+        }
+    }
+
+    installs
+        .into_iter()
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, path)| path)
+}
+
+fn import_vcvars(install_path: &Path, arch: &str) -> Result<HashMap<String, String>, CustomError> {
+    let vcvars = install_path.join(VCVARS_PATH);
+
+    let output = Command::new("cmd")
+        .arg("/C")
+        .arg(format!("call \"{}\" {} >nul && set", vcvars.display(), arch))
+        .output()
+        .map_err(|e| CustomError::ProcessError(t!("Failed to run vcvarsall.bat: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(CustomError::BuildError(t!(
+            "vcvarsall.bat failed for architecture '{}':\n{}",
+            arch,
+            String::from_utf8_lossy(&output.stdout)
+        )));
+    }
+
+    let vars = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+    Ok(vars)
+}
+
+pub fn msvc_environment(arch: &str, ui: &Ui) -> Result<HashMap<String, String>, CustomError> {
+    if is_cl_available() {
+        return Ok(HashMap::new());
+    }
+
+    ui.status(tr("Locating Visual Studio Build Tools..."));
+
+    let install_path = find_with_vswhere()
+        .or_else(find_with_registry)
+        .ok_or_else(|| {
+            CustomError::BuildError(
+                t!("The 'cl' command (MSVC compiler) was not found and no Visual Studio installation \
+                with C++ build tools could be located.\n\
+                Install 'Desktop development with C++' from https://visualstudio.microsoft.com/downloads \
+                or run this tool from the 'Visual Studio Developer Command Prompt'")
+            )
+        })?;
+
+    if ui.verbose {
+        ui.log(&t!(
+            "Importing MSVC environment from: {}",
+            install_path.display()
+        ));
+    }
+
+    import_vcvars(&install_path, arch)
+}
@@ -1,7 +1,17 @@
 use crate::Ui;
-use crate::assets::{detect_native_size, generate_empty_sprite_metadata, generate_sprite_metadata, generate_font_metadata};
+use crate::aseprite::{Animation, AsepriteExporter, is_aseprite};
+use crate::atlas_layout::{Layout, layout};
+use crate::assets::{detect_native_size, generate_empty_sprite_metadata, generate_sprite_metadata, generate_font_metadata, sprite_metadata_exists};
 use crate::error::CustomError;
-use std::collections::{BTreeSet, HashMap};
+use crate::freshness::{is_fresh, mark_fresh, settings_stamp};
+use crate::i18n::{t, tr};
+use crate::manifest::{AtlasOptions, AtlasPacker, AtlasSort, ColorSpace, FontMode, SpriteOptions, ToolRequirement};
+use crate::path_guard::is_contained;
+use crate::pixel_ops::{Trim, bleed, extrude, premultiply, trim, visible_bounds};
+use crate::stats::save_atlas_stats;
+use crate::tilemap::{MAPS_DIR_NAME, MapTileset, map_tilesets};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::{self};
 use std::path::{Path, PathBuf};
 use texture_packer::{TexturePacker, TexturePackerConfig, exporter::ImageExporter};
@@ -12,49 +22,339 @@ use ttf_parser::Face;
 use msdfgen::{Bitmap, FontExt, Framing, MsdfGeneratorConfig, Projection, Rgb, Vector2};
 use fontdue::FontSettings;
 use colored::Colorize;
+use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
 
 const ATLAS_NAME: &str = "atlas.png";
+// the pages of an atlas that needs more than one texture are atlas_0.png, atlas_1.png, ...
+const ATLAS_PAGE_PREFIX: &str = "atlas_";
 const IMAGES_DIR_NAME: &str = "images";
 const TILESETS_DIR_NAME: &str = "tilesets";
 const DEFAULT_TILE_SIZE: u32 = 16;
+const DEFAULT_TILE_EXTRUDE: u32 = 1;
+// `<image>.tiles.toml` next to an image slices it into tiles wherever it lives
+const TILESET_SIDECAR_EXTENSION: &str = "tiles.toml";
+const DEFAULT_ATLAS_SIZE: u32 = 2048;
+const DEFAULT_ATLAS_PADDING: u32 = 2;
+// a baked font gets a single page of its own
+const BAKED_FONT_MIN_SIZE: u32 = 128;
+const BAKED_FONT_MAX_SIZE: u32 = 4096;
+const BAKED_FONT_PADDING: u32 = 2;
 
 struct AtlasContext {
+    assets_dir: PathBuf,
     images_dir: PathBuf,
     tilesets_dir: PathBuf,
+    maps_dir: PathBuf,
     atlas_path: PathBuf,
     atlas_dir: PathBuf,
 }
 
-struct AtlasOutput {
-    width: u32,
-    height: u32,
-}
-
 pub struct HotReloadPayload {
     pub png_bytes: Vec<u8>,
+    // atlas pages after the first, fonts have a single texture
+    pub extra_pages: Vec<Vec<u8>>,
     pub metadata_bin: Vec<u8>,
 }
 
+/// Atlas size one packer and sort order reach on the current images.
+pub struct PackerComparison {
+    pub packer: AtlasPacker,
+    pub sort: AtlasSort,
+    // None when a sprite doesn't fit into an empty max_size page
+    pub layout: Option<Layout>,
+}
+
 pub struct GlyphMetrics {
     pub x_offset: f32,
     pub y_offset: f32,
     pub advance: f32,
 }
 
+// [assets.atlas] extrude and bleed, overridden by the [assets.atlas.sprites] patterns a sprite matches
+struct SpriteRules {
+    defaults: SpriteOptions,
+    // shortest pattern first, so longer (more specific) ones are applied last
+    patterns: Vec<(GlobMatcher, SpriteOptions)>,
+    tile_extrude: u32,
+    premultiply: bool,
+    srgb: bool,
+    groups: Vec<(String, GlobSet)>,
+}
+
+/// What processing did to the sprites, for their metadata and the atlas stats.
+#[derive(Default)]
+struct SpriteNotes {
+    extruded: BTreeMap<String, u32>,
+    trimmed: BTreeMap<String, Trim>,
+    // transparent pixels around the sprites left untrimmed
+    border_pixels: u64,
+    // sprite -> its [assets.atlas.groups] entry, ungrouped sprites aren't listed
+    groups: BTreeMap<String, String>,
+    // the frames and tags of Aseprite files
+    animations: Vec<Animation>,
+}
+
+impl SpriteNotes {
+    // a later image with the same name replaces the earlier one, its group too
+    fn set_group(&mut self, key: &str, group: Option<&str>) {
+        match group {
+            Some(group) => self.groups.insert(key.to_string(), group.to_string()),
+            None => self.groups.remove(key),
+        };
+    }
+}
+
+// the grid a tileset is cut along, from its sidecar
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct TilesetSidecar {
+    tile_width: Option<u32>,
+    tile_height: Option<u32>,
+    // pixels around the whole grid, and between neighbouring tiles
+    #[serde(default)]
+    margin: u32,
+    #[serde(default)]
+    spacing: u32,
+    extrude: Option<u32>,
+}
+
+impl TilesetSidecar {
+    // the grid of a tileset a map uses, a sidecar may only repeat it and set the extrusion
+    fn cut_like(sidecar: Option<TilesetSidecar>, grid: &MapTileset) -> Result<Self, CustomError> {
+        let extrude = sidecar.as_ref().and_then(|sidecar| sidecar.extrude);
+        if let Some(sidecar) = sidecar
+            && (sidecar.tile_width.is_some_and(|w| w != grid.tile_width)
+                || sidecar.tile_height.is_some_and(|h| h != grid.tile_height)
+                || sidecar.margin != grid.margin
+                || sidecar.spacing != grid.spacing)
+        {
+            return Err(CustomError::ValidationError(t!(
+                "The sidecar of {} cuts it differently than the maps using it",
+                grid.image.display()
+            )));
+        }
+        Ok(Self {
+            tile_width: Some(grid.tile_width),
+            tile_height: Some(grid.tile_height),
+            margin: grid.margin,
+            spacing: grid.spacing,
+            extrude,
+        })
+    }
+}
+
+// what happens to one sprite's pixels before it is packed
+struct SpriteProcessing {
+    extrude: u32,
+    bleed: bool,
+    trim: bool,
+}
+
+impl SpriteRules {
+    fn new(options: &AtlasOptions) -> Result<Self, CustomError> {
+        let mut patterns = Vec::new();
+        for (pattern, sprite) in &options.sprites {
+            let glob = Glob::new(pattern).map_err(|e| {
+                CustomError::ValidationError(t!("Invalid [assets.atlas.sprites] pattern '{}': {}", pattern, e))
+            })?;
+            patterns.push((pattern.len(), glob.compile_matcher(), sprite.clone()));
+        }
+        patterns.sort_by_key(|(len, _, _)| *len);
+
+        let mut groups = Vec::new();
+        for (name, globs) in &options.groups {
+            // atlas_<group>_<n>.png would be read as page n of another group
+            let is_identifier = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+                && !name
+                    .rsplit_once('_')
+                    .is_some_and(|(_, last)| last.chars().all(|c| c.is_ascii_digit()));
+            if !is_identifier {
+                return Err(CustomError::ValidationError(t!(
+                    "Invalid [assets.atlas.groups] name '{}' (expected lowercase letters, digits and underscores, not ending in _<number>)",
+                    name
+                )));
+            }
+            let mut builder = GlobSetBuilder::new();
+            for pattern in globs {
+                builder.add(Glob::new(pattern).map_err(|e| {
+                    CustomError::ValidationError(t!(
+                        "Invalid [assets.atlas.groups] {} pattern '{}': {}",
+                        name, pattern, e
+                    ))
+                })?);
+            }
+            let set = builder.build().map_err(|e| {
+                CustomError::ValidationError(t!("Invalid [assets.atlas.groups] {} patterns: {}", name, e))
+            })?;
+            groups.push((name.clone(), set));
+        }
+
+        Ok(Self {
+            defaults: SpriteOptions {
+                extrude: options.extrude,
+                bleed: options.bleed,
+                trim: options.trim,
+            },
+            patterns: patterns.into_iter().map(|(_, m, s)| (m, s)).collect(),
+            tile_extrude: options.tile_extrude.unwrap_or(DEFAULT_TILE_EXTRUDE),
+            premultiply: options.premultiply_alpha.unwrap_or(false),
+            srgb: options.color_space.unwrap_or_default() == ColorSpace::Srgb,
+            groups,
+        })
+    }
+
+    // the [assets.atlas.groups] entry an image belongs to, an image may only be in one
+    fn group(&self, logical: &str) -> Result<Option<&str>, CustomError> {
+        let mut owners = self.groups.iter().filter(|(_, set)| set.is_match(logical));
+        match (owners.next(), owners.next()) {
+            (Some((first, _)), Some((second, _))) => Err(CustomError::ValidationError(t!(
+                "{} matches both [assets.atlas.groups] {} and {}",
+                logical, first, second
+            ))),
+            (owner, _) => Ok(owner.map(|(name, _)| name.as_str())),
+        }
+    }
+
+    // `logical` is the image's path in the default layout, e.g. assets/images/ui/button.png
+    // a tileset sidecar's extrude replaces the one the patterns give, tiles are never trimmed
+    fn resolve(&self, logical: &str, tileset: Option<&TilesetSidecar>) -> SpriteProcessing {
+        let mut extrude = self.defaults.extrude;
+        let mut bleed = self.defaults.bleed;
+        let mut trim = self.defaults.trim;
+        for (_, sprite) in self.patterns.iter().filter(|(m, _)| m.is_match(logical)) {
+            extrude = sprite.extrude.or(extrude);
+            bleed = sprite.bleed.or(bleed);
+            trim = sprite.trim.or(trim);
+        }
+        let extrude = match tileset {
+            Some(TilesetSidecar { extrude: Some(amount), .. }) => *amount,
+            // tiles keep the pixels that stop seams between them
+            Some(_) => extrude.unwrap_or(0).max(self.tile_extrude),
+            None => extrude.unwrap_or(0),
+        };
+        SpriteProcessing {
+            extrude,
+            bleed: bleed.unwrap_or(false),
+            trim: tileset.is_none() && trim.unwrap_or(false),
+        }
+    }
+
+    fn apply(&self, img: RgbaImage, processing: &SpriteProcessing) -> RgbaImage {
+        let mut img = img;
+        if processing.bleed {
+            bleed(&mut img);
+        }
+        let mut img = extrude(&img, processing.extrude);
+        if self.premultiply {
+            premultiply(&mut img, self.srgb);
+        }
+        img
+    }
+}
+
 impl AtlasContext {
     fn new(assets_dir: &Path, atlas_dir: &Path) -> Self {
         let images_dir = assets_dir.join(IMAGES_DIR_NAME);
         let tilesets_dir = images_dir.join(TILESETS_DIR_NAME);
         let atlas_dir = PathBuf::from(atlas_dir);
-        let atlas_path = atlas_dir.join(ATLAS_NAME);
+        // the first page stands for the whole atlas in the freshness checks
+        let atlas_path = atlas_pages(&atlas_dir)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| atlas_dir.join(ATLAS_NAME));
 
         Self {
+            assets_dir: PathBuf::from(assets_dir),
             images_dir,
             tilesets_dir,
+            maps_dir: assets_dir.join(MAPS_DIR_NAME),
             atlas_path,
             atlas_dir,
         }
     }
+
+    // the tileset images the maps use, which have to be inside the project like the images
+    fn map_tilesets(&self) -> Result<Vec<MapTileset>, CustomError> {
+        let tilesets = map_tilesets(&self.maps_dir)?;
+        for tileset in &tilesets {
+            if !tileset.image.is_file() {
+                return Err(CustomError::ValidationError(t!(
+                    "Map tileset image not found: {}",
+                    tileset.image.display()
+                )));
+            }
+            if !is_contained(&tileset.image, &[Path::new("."), &self.assets_dir]) {
+                return Err(CustomError::ValidationError(t!(
+                    "Map tileset image {} is outside the project",
+                    tileset.image.display()
+                )));
+            }
+        }
+        Ok(tilesets)
+    }
+
+    // the images in assets/images plus the tileset images of the maps, in path order
+    fn image_files(&self, tilesets: &[MapTileset], ui: &Ui) -> Result<Vec<PathBuf>, CustomError> {
+        let mut files = get_sorted_image_files(&self.images_dir, ui)?;
+        files.extend(tilesets.iter().map(|tileset| tileset.image.clone()));
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+}
+
+/// The texture names of the atlas pages, in order: `atlas.png`, or `atlas_0.png`, `atlas_1.png`,
+/// ... for ungrouped sprites, and `atlas_<group>.png` or `atlas_<group>_0.png`, ... for each
+/// `[assets.atlas.groups]` entry.
+pub fn page_names(layout: &Layout) -> Vec<String> {
+    let mut seen: BTreeMap<Option<&str>, usize> = BTreeMap::new();
+    layout
+        .pages
+        .iter()
+        .map(|page| {
+            let group = page.group.as_deref();
+            let count = layout.pages.iter().filter(|p| p.group.as_deref() == group).count();
+            let index = seen.entry(group).or_default();
+            *index += 1;
+            match (group, count) {
+                (None, 1) => ATLAS_NAME.to_string(),
+                (None, _) => format!("{}{}.png", ATLAS_PAGE_PREFIX, *index - 1),
+                (Some(group), 1) => format!("{}{}.png", ATLAS_PAGE_PREFIX, group),
+                (Some(group), _) => format!("{}{}_{}.png", ATLAS_PAGE_PREFIX, group, *index - 1),
+            }
+        })
+        .collect()
+}
+
+// (group, page) of an atlas texture name, the group is empty for ungrouped sprites
+fn parse_page_name(name: &str) -> Option<(String, Option<usize>)> {
+    if name == ATLAS_NAME {
+        return Some((String::new(), None));
+    }
+    let rest = name.strip_prefix(ATLAS_PAGE_PREFIX)?.strip_suffix(".png")?;
+    if let Ok(index) = rest.parse() {
+        return Some((String::new(), Some(index)));
+    }
+    let paged = rest
+        .rsplit_once('_')
+        .and_then(|(group, index)| Some((group.to_string(), Some(index.parse().ok()?))));
+    Some(paged.unwrap_or_else(|| (rest.to_string(), None)))
+}
+
+/// The atlas textures in `atlas_dir`, in the order of [`page_names`].
+pub fn atlas_pages(atlas_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(atlas_dir) else {
+        return Vec::new();
+    };
+    let mut pages: Vec<((String, Option<usize>), PathBuf)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| Some((parse_page_name(&e.file_name().to_string_lossy())?, e.path())))
+        .collect();
+    pages.sort();
+    pages.into_iter().map(|(_, path)| path).collect()
 }
 
 // msdf font packing
@@ -62,33 +362,29 @@ pub fn pack_font(font_path: &Path, font_name: &str, is_pixel: bool, native_size:
     let bin_path = font_output_dir.join(format!("{}.bin", font_name));
     let font_atlas_path = font_output_dir.join(format!("{}.png", font_name));
 
-    if let Ok(source_meta) = std::fs::metadata(font_path) {
-        if let (Ok(bin_meta), Ok(png_meta)) = (std::fs::metadata(&bin_path), std::fs::metadata(&font_atlas_path)) {
-            if let (Ok(source_time), Ok(bin_time), Ok(png_time)) = (source_meta.modified(), bin_meta.modified(), png_meta.modified()) {
-                if bin_time > source_time && png_time > source_time {
-                    ui.status(&format!("Using cached font: {}", font_name));
-                }
+    let font_sources = [font_path.to_path_buf()];
+    if is_fresh(&font_sources, &bin_path)? && is_fresh(&font_sources, &font_atlas_path)? {
+        ui.status(&t!("Using cached font: {}", font_name));
 
-                let metadata_bin = std::fs::read(&bin_path).map_err(CustomError::IoError)?;
-                let png_bytes = std::fs::read(&font_atlas_path).map_err(CustomError::IoError)?;
+        let metadata_bin = std::fs::read(&bin_path).map_err(CustomError::IoError)?;
+        let png_bytes = std::fs::read(&font_atlas_path).map_err(CustomError::IoError)?;
 
-                return Ok(Some(HotReloadPayload {
-                    png_bytes,
-                    metadata_bin,
-                }));
-            }
-        }
+        return Ok(Some(HotReloadPayload {
+            png_bytes,
+            extra_pages: Vec::new(),
+            metadata_bin,
+        }));
     }
 
     if ui.verbose {
         if is_pixel {
-            ui.status(&format!("Packing pixel font: {} ({}px)...", font_name, native_size));
+            ui.status(&t!("Packing pixel font: {} ({}px)...", font_name, native_size));
         } else {
-            ui.status(&format!("Packing MSDF Vector font: {}...", font_name));
+            ui.status(&t!("Packing MSDF Vector font: {}...", font_name));
         }
     }
 
-    let font_bytes = std::fs::read(font_path).map_err(|_| CustomError::BuildError("Failed to read font".into()))?;
+    let font_bytes = std::fs::read(font_path).map_err(|_| CustomError::BuildError(t!("Failed to read font")))?;
 
     let config = TexturePackerConfig {
         max_width: 2048,
@@ -104,7 +400,7 @@ pub fn pack_font(font_path: &Path, font_name: &str, is_pixel: bool, native_size:
 
     if is_pixel {
         let font = fontdue::Font::from_bytes(font_bytes.clone(), FontSettings::default())
-            .map_err(|_| CustomError::BuildError("Failed to parse font with fontdue".into()))?;
+            .map_err(|_| CustomError::BuildError(t!("Failed to parse font with fontdue")))?;
 
         let mut blurry_warning_logged = false;
 
@@ -138,7 +434,7 @@ pub fn pack_font(font_path: &Path, font_name: &str, is_pixel: bool, native_size:
             if !blurry_warning_logged {
                 for &alpha in &bitmap {
                     if alpha > 0 && alpha < 255 {
-                        ui.message(&format!("{} Pixel font '{}' contains blurred/anti-aliased pixels at size {}px. Are you sure this is its native size?", 
+                        ui.message(&t!("{} Pixel font '{}' contains blurred/anti-aliased pixels at size {}px. Are you sure this is its native size?", 
                             "[WARNING]".red(), font_name, native_size));
                         detect_native_size(&font_bytes, &font_name, ui);
                         blurry_warning_logged = true;
@@ -167,7 +463,7 @@ pub fn pack_font(font_path: &Path, font_name: &str, is_pixel: bool, native_size:
         }
     } else {
         let face = Face::parse(&font_bytes, 0)
-            .map_err(|_| CustomError::BuildError("Failed to parse font".into()))?;
+            .map_err(|_| CustomError::BuildError(t!("Failed to parse font")))?;
 
         let px_size = 64.0;
         let scale = px_size / face.units_per_em() as f64;
@@ -246,11 +542,11 @@ pub fn pack_font(font_path: &Path, font_name: &str, is_pixel: bool, native_size:
     }
 
     let atlas_image = ImageExporter::export(&packer, None)
-        .map_err(|e| CustomError::BuildError(format!("Failed to export MSDF atlas: {}", e)))?;
+        .map_err(|e| CustomError::BuildError(t!("Failed to export MSDF atlas: {}", e)))?;
 
     let mut png_bytes: Vec<u8> = Vec::new();
     atlas_image.write_to(&mut Cursor::new(&mut png_bytes), ImageFormat::Png)
-        .map_err(|_| CustomError::BuildError("Failed to encode MSDF PNG to memory".into()))?;
+        .map_err(|_| CustomError::BuildError(t!("Failed to encode MSDF PNG to memory")))?;
 
     let metadata_bin = generate_font_metadata(&packer, atlas_image.width(), atlas_image.height(), is_pixel, native_size, &metrics_map)?;
 
@@ -258,14 +554,16 @@ pub fn pack_font(font_path: &Path, font_name: &str, is_pixel: bool, native_size:
     if let Some(parent) = bin_path.parent() {
         fs::create_dir_all(parent).map_err(CustomError::IoError)?;
     }
-    fs::write(bin_path, &metadata_bin).map_err(CustomError::IoError)?;
+    fs::write(&bin_path, &metadata_bin).map_err(CustomError::IoError)?;
 
     let font_atlas_path = font_output_dir.join(format!("{}.png", font_name));
     atlas_image.save(&font_atlas_path)
-        .map_err(|_| CustomError::BuildError(format!("Failed to save font atlas: {}", font_name)))?;
+        .map_err(|_| CustomError::BuildError(t!("Failed to save font atlas: {}", font_name)))?;
+    mark_fresh(&font_sources, &bin_path)?;
+    mark_fresh(&font_sources, &font_atlas_path)?;
 
     if ui.verbose {
-        ui.log(&format!(
+        ui.log(&t!(
             "MSDF Font {} Atlas generated ({}x{})",
             font_name,
             atlas_image.width(),
@@ -275,54 +573,328 @@ pub fn pack_font(font_path: &Path, font_name: &str, is_pixel: bool, native_size:
 
     Ok(Some(HotReloadPayload {
         png_bytes,
+        extra_pages: Vec::new(),
         metadata_bin,
     }))
 }
 
-pub fn pack_atlas(assets_dir: &Path, atlas_dir: &Path, ui: &Ui) -> Result<Option<HotReloadPayload>, CustomError> {
+/// A font baked by `[assets.fonts]` at one size: its atlas page and every glyph on it.
+#[derive(Serialize, Deserialize)]
+pub struct BakedFont {
+    pub width: u32,
+    pub height: u32,
+    pub line_height: f32,
+    pub ascent: f32,
+    pub descent: f32,
+    pub glyphs: Vec<BakedGlyph>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BakedGlyph {
+    pub codepoint: u32,
+    // x, y, width and height on the page, all 0 for glyphs without pixels like the space
+    pub rect: [u32; 4],
+    pub x_offset: f32,
+    pub y_offset: f32,
+    pub advance: f32,
+}
+
+/// Rasterizes `chars` of a font at `size` pixels into a single atlas page, as coverage bitmaps
+/// or MSDF. Characters the font has no glyph for are left out.
+pub fn bake_font(
+    font_bytes: &[u8],
+    chars: &BTreeSet<char>,
+    mode: FontMode,
+    size: u32,
+    antialias: bool,
+    distance_range: u32,
+) -> Result<(RgbaImage, BakedFont), String> {
+    let mut images: Vec<(String, RgbaImage)> = Vec::new();
+    let mut glyphs = Vec::new();
+    let (line_height, ascent, descent);
+
+    match mode {
+        FontMode::Bitmap => {
+            let px = size as f32;
+            let font = fontdue::Font::from_bytes(font_bytes, FontSettings { scale: px, ..Default::default() })
+                .map_err(|_| tr("Failed to parse font with fontdue").to_string())?;
+            let line = font.horizontal_line_metrics(px);
+            ascent = line.map_or(px, |l| l.ascent);
+            descent = line.map_or(0.0, |l| l.descent);
+            line_height = line.map_or(px, |l| l.new_line_size);
+
+            for &ch in chars {
+                if font.lookup_glyph_index(ch) == 0 {
+                    continue;
+                }
+                let (metrics, bitmap) = font.rasterize(ch, px);
+                glyphs.push(BakedGlyph {
+                    codepoint: ch as u32,
+                    rect: [0; 4],
+                    x_offset: metrics.xmin as f32,
+                    y_offset: metrics.ymin as f32,
+                    advance: metrics.advance_width,
+                });
+                if metrics.width == 0 || metrics.height == 0 {
+                    continue;
+                }
+
+                let mut img = RgbaImage::new(metrics.width as u32, metrics.height as u32);
+                for y in 0..metrics.height {
+                    for x in 0..metrics.width {
+                        let coverage = bitmap[y * metrics.width + x];
+                        let alpha = if antialias { coverage } else if coverage > 127 { 255 } else { 0 };
+                        img.put_pixel(x as u32, y as u32, Rgba([255, 255, 255, alpha]));
+                    }
+                }
+                image::imageops::flip_vertical_in_place(&mut img);
+                images.push(((ch as u32).to_string(), img));
+            }
+        }
+        FontMode::Msdf => {
+            let face = Face::parse(font_bytes, 0).map_err(|_| tr("Failed to parse font").to_string())?;
+            let scale = size as f64 / face.units_per_em() as f64;
+            ascent = (face.ascender() as f64 * scale) as f32;
+            descent = (face.descender() as f64 * scale) as f32;
+            line_height = ((face.ascender() - face.descender() + face.line_gap()) as f64 * scale) as f32;
+
+            let padding_px = distance_range as f64;
+            let padding_font_units = padding_px / scale;
+            for &ch in chars {
+                let Some(glyph_id) = face.glyph_index(ch) else {
+                    continue;
+                };
+                let advance = face.glyph_hor_advance(glyph_id).unwrap_or(0) as f64 * scale;
+                let mut shape = face.glyph_shape(glyph_id).unwrap_or_default();
+                let bounds = shape.get_bound();
+                if bounds.left >= bounds.right || bounds.bottom >= bounds.top {
+                    glyphs.push(BakedGlyph {
+                        codepoint: ch as u32,
+                        rect: [0; 4],
+                        x_offset: 0.0,
+                        y_offset: 0.0,
+                        advance: advance as f32,
+                    });
+                    continue;
+                }
+
+                let width_px = ((bounds.right - bounds.left) * scale).ceil() as u32 + distance_range * 2;
+                let height_px = ((bounds.top - bounds.bottom) * scale).ceil() as u32 + distance_range * 2;
+                glyphs.push(BakedGlyph {
+                    codepoint: ch as u32,
+                    rect: [0; 4],
+                    x_offset: (bounds.left * scale - padding_px) as f32,
+                    y_offset: (bounds.bottom * scale - padding_px) as f32,
+                    advance: advance as f32,
+                });
+
+                let translation = Vector2::new(-bounds.left + padding_font_units, -bounds.bottom + padding_font_units);
+                let framing = Framing {
+                    projection: Projection::new(Vector2::new(scale, scale), translation),
+                    range: padding_font_units,
+                };
+                let mut msdf_bitmap = Bitmap::<Rgb<f32>>::new(width_px, height_px);
+                shape.edge_coloring_simple(3.0, 0);
+                shape.generate_msdf(&mut msdf_bitmap, framing, MsdfGeneratorConfig::default());
+
+                let mut img = RgbaImage::new(width_px, height_px);
+                for y in 0..height_px {
+                    for x in 0..width_px {
+                        let pixel = msdf_bitmap.pixel(x, y);
+                        img.put_pixel(x, y, Rgba([
+                            (pixel.r * 255.0) as u8,
+                            (pixel.g * 255.0) as u8,
+                            (pixel.b * 255.0) as u8,
+                            255,
+                        ]));
+                    }
+                }
+                images.push(((ch as u32).to_string(), img));
+            }
+        }
+    }
+
+    let sizes: Vec<(String, u32, u32)> = images
+        .iter()
+        .map(|(key, img)| (key.clone(), img.width(), img.height()))
+        .collect();
+    // the smallest square page all glyphs fit on, a strip along the maximum width otherwise
+    let mut page_size = BAKED_FONT_MIN_SIZE;
+    let packed = loop {
+        let packed = layout(&sizes, AtlasPacker::default(), AtlasSort::default(), page_size, BAKED_FONT_PADDING, false)?;
+        if packed.pages.len() <= 1 || page_size >= BAKED_FONT_MAX_SIZE {
+            break packed;
+        }
+        page_size *= 2;
+    };
+    if packed.pages.len() > 1 {
+        return Err(t!(
+            "the glyphs don't fit into one {}x{} page, bake fewer characters or a smaller size",
+            BAKED_FONT_MAX_SIZE,
+            BAKED_FONT_MAX_SIZE
+        ));
+    }
+
+    let (width, height) = packed.pages.first().map_or((1, 1), |page| (page.width, page.height));
+    let mut page_image = RgbaImage::new(width, height);
+    let frames = packed.frames();
+    for (key, img) in &images {
+        if let Some((_, rect)) = frames.get(key.as_str()) {
+            image::imageops::replace(&mut page_image, img, rect.x as i64, rect.y as i64);
+        }
+    }
+    for glyph in &mut glyphs {
+        if let Some((_, rect)) = frames.get(glyph.codepoint.to_string().as_str()) {
+            glyph.rect = [rect.x, rect.y, rect.w, rect.h];
+        }
+    }
+
+    Ok((page_image, BakedFont {
+        width,
+        height,
+        line_height,
+        ascent,
+        descent,
+        glyphs,
+    }))
+}
+
+pub fn pack_atlas(
+    assets_dir: &Path,
+    atlas_dir: &Path,
+    options: &AtlasOptions,
+    tools: &BTreeMap<String, ToolRequirement>,
+    ui: &Ui,
+) -> Result<Option<HotReloadPayload>, CustomError> {
     let ctx = AtlasContext::new(assets_dir, atlas_dir);
+    let tilesets = ctx.map_tilesets()?;
 
-    if !should_repack(&ctx.images_dir, &ctx.atlas_path)? && ui.verbose {
-        ui.log("Atlas is up to date. Skipping packing.");
+    if !should_repack(&ctx, &tilesets, options)? && sprite_metadata_exists() {
+        if ui.verbose {
+            ui.log(tr("Atlas is up to date. Skipping packing."));
+        }
         return Ok(None);
     }
 
-    let sorted_files = get_sorted_image_files(&ctx.images_dir)?;
+    let sorted_files = ctx.image_files(&tilesets, ui)?;
     if sorted_files.is_empty() {
         generate_empty_sprite_metadata()?;
         if ui.verbose {
-            ui.log("No images to pack in assets directory. Skipping packing.");
+            ui.log(tr("No images to pack in assets directory. Skipping packing."));
         }
         return Ok(None);
     }
 
     if ui.verbose {
-        ui.status("Packing texture atlas...");
+        ui.status(tr("Packing texture atlas..."));
     }
 
-    let config = TexturePackerConfig {
-        max_width: 2048,
-        max_height: 2048,
-        allow_rotation: false,
-        texture_outlines: false,
-        border_padding: 2,
-        texture_padding: 2,
-        trim: false,
-        ..Default::default()
-    };
-    let mut packer = TexturePacker::new_skyline(config);
-    let mut extruded_sprites: BTreeSet<String> = BTreeSet::new();
-    process_images(&ctx, &sorted_files, &mut packer, &mut extruded_sprites, ui)?;
-    let (output, png_bytes) = write_atlas(&ctx, &packer, ui)?;
-    let metadata_bin = generate_sprite_metadata(&packer, output.width, output.height, &extruded_sprites)?;
+    let rules = SpriteRules::new(options)?;
+    let mut notes = SpriteNotes::default();
+    let sprites = process_images(&ctx, &sorted_files, &tilesets, &rules, &mut notes, &mut AsepriteExporter::new(tools), ui)?;
+    for group in options.groups.keys() {
+        if !notes.groups.values().any(|g| g == group) {
+            ui.message(&t!(
+                "{} Atlas group '{}' matches no images.",
+                "[WARNING]".yellow(),
+                group
+            ));
+        }
+    }
+    let layout = layout_sprites(&sprites, &notes.groups, options, options.packer.unwrap_or_default(), options.sort.unwrap_or_default())
+        .map_err(|key| {
+            let max_size = options.max_size.unwrap_or(DEFAULT_ATLAS_SIZE);
+            CustomError::BuildError(t!(
+                "Sprite '{}' doesn't fit into an empty {}x{} atlas page. Raise [assets.atlas] max_size",
+                key,
+                max_size,
+                max_size
+            ))
+        })?;
+    let mut pages = write_atlas(&ctx, &sprites, &layout, ui)?.into_iter();
+    let inputs = atlas_inputs(&ctx, &tilesets, options)?;
+    let first_page = atlas_pages(&ctx.atlas_dir).into_iter().next().unwrap_or(ctx.atlas_path);
+    mark_fresh(&inputs, &first_page)?;
+    let metadata_bin = generate_sprite_metadata(&layout, &notes.extruded, &notes.trimmed, &notes.animations, options)?;
+    save_atlas_stats(&layout, notes.border_pixels, ui)?;
 
     Ok(Some(HotReloadPayload {
-        png_bytes,
+        png_bytes: pages.next().unwrap_or_default(),
+        extra_pages: pages.collect(),
         metadata_bin,
     }))
 }
 
-fn get_sorted_image_files(dir: &Path) -> Result<Vec<PathBuf>, CustomError> {
+/// Packs the current images with every packer and sort order, without writing anything.
+pub fn compare_atlas_packers(
+    assets_dir: &Path,
+    options: &AtlasOptions,
+    tools: &BTreeMap<String, ToolRequirement>,
+    ui: &Ui,
+) -> Result<Vec<PackerComparison>, CustomError> {
+    let ctx = AtlasContext::new(assets_dir, Path::new(""));
+    let tilesets = ctx.map_tilesets()?;
+    let sorted_files = ctx.image_files(&tilesets, ui)?;
+    if sorted_files.is_empty() {
+        return Err(CustomError::ValidationError(t!(
+            "No images to pack in {}",
+            ctx.images_dir.display()
+        )));
+    }
+
+    let rules = SpriteRules::new(options)?;
+    let mut notes = SpriteNotes::default();
+    let sprites = process_images(&ctx, &sorted_files, &tilesets, &rules, &mut notes, &mut AsepriteExporter::new(tools), ui)?;
+    let mut comparisons = Vec::new();
+    for packer in AtlasPacker::ALL {
+        for sort in AtlasSort::ALL {
+            comparisons.push(PackerComparison {
+                packer,
+                sort,
+                layout: layout_sprites(&sprites, &notes.groups, options, packer, sort).ok(),
+            });
+        }
+    }
+    Ok(comparisons)
+}
+
+// ungrouped sprites first, then every [assets.atlas.groups] entry on pages of its own
+fn layout_sprites(
+    sprites: &[(String, RgbaImage)],
+    groups: &BTreeMap<String, String>,
+    options: &AtlasOptions,
+    packer: AtlasPacker,
+    sort: AtlasSort,
+) -> Result<Layout, String> {
+    let mut pages = Vec::new();
+    for group in std::iter::once(None).chain(options.groups.keys().map(Some)) {
+        let sizes: Vec<(String, u32, u32)> = sprites
+            .iter()
+            .filter(|(key, _)| groups.get(key) == group)
+            .map(|(key, img)| (key.clone(), img.width(), img.height()))
+            .collect();
+        if sizes.is_empty() {
+            continue;
+        }
+        let packed = layout(
+            &sizes,
+            packer,
+            sort,
+            options.max_size.unwrap_or(DEFAULT_ATLAS_SIZE),
+            options.padding.unwrap_or(DEFAULT_ATLAS_PADDING),
+            options.allow_rotation.unwrap_or(false),
+        )?;
+        pages.extend(packed.pages.into_iter().map(|mut page| {
+            page.group = group.cloned();
+            page
+        }));
+    }
+    Ok(Layout { pages })
+}
+
+// images linked from outside the project and the assets directory are left out, so a cloned
+// project can't pack files from elsewhere on the machine into its atlas
+fn get_sorted_image_files(dir: &Path, ui: &Ui) -> Result<Vec<PathBuf>, CustomError> {
     let mut paths: Vec<PathBuf> = Vec::new();
 
     if !dir.exists() {
@@ -339,7 +911,15 @@ fn get_sorted_image_files(dir: &Path) -> Result<Vec<PathBuf>, CustomError> {
         if path.file_name().and_then(|s| s.to_str()) == Some(ATLAS_NAME) {
             continue;
         }
-        if path.extension().and_then(|s| s.to_str()) == Some("png") {
+        if path.extension().and_then(|s| s.to_str()) == Some("png") || is_aseprite(path) {
+            if entry.path_is_symlink() && !is_contained(path, &[Path::new("."), dir]) {
+                ui.message(&t!(
+                    "{} Skipped '{}': it links outside the project",
+                    "[WARNING]".yellow(),
+                    path.display()
+                ));
+                continue;
+            }
             paths.push(path.to_path_buf());
         }
     }
@@ -351,10 +931,13 @@ fn get_sorted_image_files(dir: &Path) -> Result<Vec<PathBuf>, CustomError> {
 fn process_images(
     ctx: &AtlasContext,
     files: &[PathBuf],
-    packer: &mut TexturePacker<image::RgbaImage, String>,
-    extruded_sprites: &mut BTreeSet<String>,
+    map_tilesets: &[MapTileset],
+    rules: &SpriteRules,
+    notes: &mut SpriteNotes,
+    aseprite: &mut AsepriteExporter,
     ui: &Ui,
-) -> Result<(), CustomError> {
+) -> Result<Vec<(String, RgbaImage)>, CustomError> {
+    let mut sprites = Vec::new();
     for path in files {
         let file_name = path.file_name().and_then(|s| s.to_str()).unwrap();
         let file_stem = path
@@ -363,88 +946,129 @@ fn process_images(
             .unwrap()
             .to_string();
 
-        let mut img = image::open(path)
-            .map_err(|e| CustomError::ValidationError(format!("Failed to load {:?}: {}", path, e)))?
-            .to_rgba8();
+        let sidecar = load_tileset_sidecar(path)?;
+        let tileset = match (sidecar, map_tilesets.iter().find(|t| t.image == *path)) {
+            // a map is cut along the grid it was painted with
+            (sidecar, Some(grid)) => Some(TilesetSidecar::cut_like(sidecar, grid)?),
+            (Some(sidecar), None) => Some(sidecar),
+            (None, None) if path.starts_with(&ctx.tilesets_dir) => Some(TilesetSidecar::default()),
+            (None, None) => None,
+        };
+        let logical = Path::new("assets")
+            .join(path.strip_prefix(&ctx.assets_dir).unwrap_or(path))
+            .to_string_lossy()
+            .replace('\\', "/");
+        let processing = rules.resolve(&logical, tileset.as_ref());
+        let group = rules.group(&logical)?;
+
+        if is_aseprite(path) {
+            let sheet = aseprite.load(path, &logical, ui)?;
+            // a single frame keeps the file's name, like a png
+            let key = |index: usize| {
+                if sheet.frames.len() == 1 {
+                    file_stem.clone()
+                } else {
+                    format!("{}_{}", file_stem, index)
+                }
+            };
+            for (index, (frame, _)) in sheet.frames.iter().enumerate() {
+                let sprite = prepare_sprite(key(index), frame.clone(), &processing, true, group, rules, notes);
+                sprites.push(sprite);
+            }
+            notes.animations.extend(sheet.animations(&file_stem, key));
+            continue;
+        }
 
-        let is_tileset = path.starts_with(&ctx.tilesets_dir);
+        let img = image::open(path)
+            .map_err(|e| CustomError::ValidationError(t!("Failed to load {}: {}", format!("{:?}", path), e)))?
+            .to_rgba8();
 
-        if is_tileset {
+        if let Some(tileset) = &tileset {
             if ui.verbose {
-                ui.log(&format!("Slicing tileset found: {}", file_name));
+                ui.log(&t!("Slicing tileset found: {}", file_name));
             }
 
-            let (tile_w, tile_h) = parse_grid_size_from_name(&file_stem)
+            let (name_w, name_h) = parse_grid_size_from_name(&file_stem)
                 .unwrap_or((DEFAULT_TILE_SIZE, DEFAULT_TILE_SIZE));
+            let tile_w = tileset.tile_width.unwrap_or(name_w);
+            let tile_h = tileset.tile_height.unwrap_or(name_h);
+            if tile_w == 0 || tile_h == 0 {
+                return Err(CustomError::ValidationError(t!(
+                    "Tileset {} has a zero tile size",
+                    path.display()
+                )));
+            }
 
-            let cols = img.width() / tile_w;
-            let rows = img.height() / tile_h;
+            let step_w = tile_w + tileset.spacing;
+            let step_h = tile_h + tileset.spacing;
+            let grid_w = img.width().saturating_sub(tileset.margin * 2) + tileset.spacing;
+            let grid_h = img.height().saturating_sub(tileset.margin * 2) + tileset.spacing;
+            let cols = grid_w / step_w;
+            let rows = grid_h / step_h;
 
             for y in 0..rows {
                 for x in 0..cols {
-                    let sub_img =
-                        image::imageops::crop_imm(&img, x * tile_w, y * tile_h, tile_w, tile_h)
-                            .to_image();
+                    let sub_img = image::imageops::crop_imm(
+                        &img,
+                        tileset.margin + x * step_w,
+                        tileset.margin + y * step_h,
+                        tile_w,
+                        tile_h,
+                    )
+                    .to_image();
 
                     let mut final_tile = sub_img;
                     image::imageops::flip_vertical_in_place(&mut final_tile);
 
-                    let extruded_tile = extrude_tile(&final_tile);
-
                     let tile_index = x + (y * cols);
                     let key = format!("{}_{}", file_stem, tile_index);
 
-                    packer.pack_own(key.clone(), extruded_tile).map_err(|_| {
-                        CustomError::BuildError(format!(
-                            "Failed to pack tile '{}'. Atlas full?",
-                            key
-                        ))
-                    })?;
-
-                    extruded_sprites.insert(key);
+                    notes.extruded.insert(key.clone(), processing.extrude);
+                    notes.set_group(&key, group);
+                    sprites.push((key, rules.apply(final_tile, &processing)));
                 }
             }
         } else {
-            image::imageops::flip_vertical_in_place(&mut img);
-            packer.pack_own(file_stem.clone(), img).map_err(|_| {
-                CustomError::BuildError(format!(
-                    "Failed to pack sprite '{}'. Atlas full?",
-                    file_stem
-                ))
-            })?;
+            // animation strips are cut into frames by their size, so they keep their borders
+            let trimmable = parse_grid_size_from_name(&file_stem).is_none();
+            sprites.push(prepare_sprite(file_stem, img, &processing, trimmable, group, rules, notes));
         }
     }
 
-    Ok(())
+    Ok(sprites)
 }
 
-//HACK: extrude edges of tiles by one pixel to ensure not getting tile seams
-fn extrude_tile(img: &image::RgbaImage) -> image::RgbaImage {
-    let (w, h) = img.dimensions();
-    let mut new_img = image::RgbaImage::new(w + 2, h + 2);
-    image::imageops::overlay(&mut new_img, img, 1, 1);
-
-    for x in 0..w {
-        let top_pixel = *img.get_pixel(x, 0);
-        let bottom_pixel = *img.get_pixel(x, h - 1);
-        new_img.put_pixel(x + 1, 0, top_pixel);
-        new_img.put_pixel(x + 1, h + 1, bottom_pixel);
+// trims or measures the borders of a whole image or Aseprite frame, flips it and applies the rules
+fn prepare_sprite(
+    key: String,
+    mut img: RgbaImage,
+    processing: &SpriteProcessing,
+    trimmable: bool,
+    group: Option<&str>,
+    rules: &SpriteRules,
+    notes: &mut SpriteNotes,
+) -> (String, RgbaImage) {
+    if trimmable {
+        if processing.trim {
+            let (cropped, cut) = trim(&img);
+            if cut != Trim::default() {
+                notes.trimmed.insert(key.clone(), cut);
+            }
+            img = cropped;
+        } else {
+            let cut = visible_bounds(&img);
+            let (w, h) = img.dimensions();
+            let visible = (w - cut.left - cut.right) as u64 * (h - cut.top - cut.bottom) as u64;
+            notes.border_pixels += w as u64 * h as u64 - visible;
+        }
     }
-
-    for y in 0..h {
-        let left_pixel = *img.get_pixel(0, y);
-        let right_pixel = *img.get_pixel(w - 1, y);
-
-        new_img.put_pixel(0, y + 1, left_pixel);
-        new_img.put_pixel(w + 1, y + 1, right_pixel);
+    image::imageops::flip_vertical_in_place(&mut img);
+    if processing.extrude > 0 {
+        notes.extruded.insert(key.clone(), processing.extrude);
     }
-
-    new_img.put_pixel(0, 0, *img.get_pixel(0, 0));
-    new_img.put_pixel(w + 1, 0, *img.get_pixel(w - 1, 0));
-    new_img.put_pixel(0, h + 1, *img.get_pixel(0, h - 1));
-    new_img.put_pixel(w + 1, h + 1, *img.get_pixel(w - 1, h - 1));
-
-    new_img
+    notes.set_group(&key, group);
+    let img = rules.apply(img, processing);
+    (key, img)
 }
 
 fn parse_grid_size_from_name(name: &str) -> Option<(u32, u32)> {
@@ -459,51 +1083,80 @@ fn parse_grid_size_from_name(name: &str) -> Option<(u32, u32)> {
     None
 }
 
+// writes every page and removes the pages of a previous, larger or differently grouped atlas
 fn write_atlas(
     ctx: &AtlasContext,
-    packer: &TexturePacker<image::RgbaImage, String>,
+    sprites: &[(String, RgbaImage)],
+    layout: &Layout,
     ui: &Ui,
-) -> Result<(AtlasOutput, Vec<u8>), CustomError> {
-    let atlas_image = ImageExporter::export(packer, None)
-        .map_err(|e| CustomError::BuildError(format!("Failed to export atlas: {}", e)))?;
-
+) -> Result<Vec<Vec<u8>>, CustomError> {
     fs::create_dir_all(&ctx.atlas_dir)?;
 
-    atlas_image
-        .save(&ctx.atlas_path)
-        .map_err(|_| CustomError::BuildError("Failed to save atlas".to_string()))?;
-
-    let mut png_bytes: Vec<u8> = Vec::new();
-    atlas_image.write_to(&mut Cursor::new(&mut png_bytes), ImageFormat::Png)
-        .map_err(|_| CustomError::BuildError("Failed to encode PNG to memory".to_string()))?;
+    let mut written = Vec::new();
+    let mut pages = Vec::new();
+    for (page, name) in layout.pages.iter().zip(page_names(layout)) {
+        let mut atlas_image = RgbaImage::new(page.width, page.height);
+        for (key, img) in sprites {
+            let Some(rect) = page.frames.get(key) else {
+                continue;
+            };
+            let turned;
+            let img = if page.rotated.contains(key) {
+                turned = image::imageops::rotate90(img);
+                &turned
+            } else {
+                img
+            };
+            // a later sprite with the same name replaces the earlier one
+            if (rect.w, rect.h) == img.dimensions() {
+                image::imageops::replace(&mut atlas_image, img, rect.x as i64, rect.y as i64);
+            }
+        }
 
-    if ui.verbose {
-        ui.log(&format!(
-            "Atlas generated at {:?} ({}x{})",
-            ctx.atlas_path,
-            atlas_image.width(),
-            atlas_image.height()
-        ));
-    }
+        let path = ctx.atlas_dir.join(name);
+        atlas_image
+            .save(&path)
+            .map_err(|_| CustomError::BuildError(t!("Failed to save atlas")))?;
 
-    Ok((AtlasOutput {
-        width: atlas_image.width(),
-        height: atlas_image.height(),
-    }, png_bytes))
-}
+        let mut png_bytes: Vec<u8> = Vec::new();
+        atlas_image.write_to(&mut Cursor::new(&mut png_bytes), ImageFormat::Png)
+            .map_err(|_| CustomError::BuildError(t!("Failed to encode PNG to memory")))?;
 
-fn should_repack(source_dir: &Path, target_file: &Path) -> Result<bool, CustomError> {
-    if !target_file.exists() {
-        return Ok(true);
+        if ui.verbose {
+            ui.log(&t!(
+                "Atlas generated at {} ({}x{})",
+                path.display(),
+                atlas_image.width(),
+                atlas_image.height()
+            ));
+        }
+        written.push(path);
+        pages.push(png_bytes);
     }
 
-    if !source_dir.exists() {
-        return Ok(false);
+    for stale in atlas_pages(&ctx.atlas_dir) {
+        if !written.contains(&stale) {
+            fs::remove_file(&stale)?;
+        }
     }
 
-    let target_metadata = fs::metadata(target_file).map_err(CustomError::IoError)?;
-    let target_time = target_metadata.modified().map_err(CustomError::IoError)?;
+    Ok(pages)
+}
+
+fn load_tileset_sidecar(image: &Path) -> Result<Option<TilesetSidecar>, CustomError> {
+    let sidecar_path = image.with_extension(TILESET_SIDECAR_EXTENSION);
+    if !sidecar_path.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&sidecar_path)?;
+    toml_edit::de::from_str(&content)
+        .map(Some)
+        .map_err(|e| CustomError::ValidationError(t!("Invalid {}: {}", sidecar_path.display(), e)))
+}
 
+// every png and Aseprite file the atlas is packed from, and the tileset sidecars cutting them
+fn atlas_sources(source_dir: &Path) -> Result<Vec<PathBuf>, CustomError> {
+    let mut sources = Vec::new();
     for entry in WalkDir::new(source_dir) {
         let entry = entry.map_err(|e| CustomError::IoError(e.into()))?;
         let path = entry.path();
@@ -512,15 +1165,48 @@ fn should_repack(source_dir: &Path, target_file: &Path) -> Result<bool, CustomEr
             continue;
         }
 
-        if path.extension().map_or(false, |ext| ext == "png") {
-            let metadata = fs::metadata(path).map_err(CustomError::IoError)?;
-            let modified_time = metadata.modified().map_err(CustomError::IoError)?;
+        if path.extension().is_some_and(|ext| ext == "png")
+            || is_aseprite(path)
+            || path.to_string_lossy().ends_with(&format!(".{}", TILESET_SIDECAR_EXTENSION))
+        {
+            sources.push(path.to_path_buf());
+        }
+    }
+    Ok(sources)
+}
 
-            if modified_time > target_time {
-                return Ok(true);
-            }
+// the sources plus the [assets.atlas] settings, so changing the padding or size repacks too, and the
+// map tilesets with their grids, but not the maps themselves
+fn atlas_inputs(ctx: &AtlasContext, tilesets: &[MapTileset], options: &AtlasOptions) -> Result<Vec<PathBuf>, CustomError> {
+    let settings = serde_json::to_string(options).map_err(|e| {
+        CustomError::ValidationError(t!("Failed to serialize [assets.atlas] settings: {}", e))
+    })?;
+    let grids = serde_json::to_string(tilesets).map_err(|e| {
+        CustomError::ValidationError(t!("Failed to serialize map tilesets: {}", e))
+    })?;
+    let mut inputs = if ctx.images_dir.exists() {
+        atlas_sources(&ctx.images_dir)?
+    } else {
+        Vec::new()
+    };
+    for tileset in tilesets {
+        if !inputs.contains(&tileset.image) {
+            inputs.push(tileset.image.clone());
         }
     }
+    inputs.push(settings_stamp("atlas", &settings)?);
+    inputs.push(settings_stamp("map_tilesets", &grids)?);
+    Ok(inputs)
+}
+
+fn should_repack(ctx: &AtlasContext, tilesets: &[MapTileset], options: &AtlasOptions) -> Result<bool, CustomError> {
+    if !ctx.atlas_path.exists() {
+        return Ok(true);
+    }
+
+    if !ctx.images_dir.exists() && tilesets.is_empty() {
+        return Ok(false);
+    }
 
-    Ok(false)
+    Ok(!is_fresh(&atlas_inputs(ctx, tilesets, options)?, &ctx.atlas_path)?)
 }
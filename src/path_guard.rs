@@ -0,0 +1,91 @@
+use crate::Ui;
+use crate::error::CustomError;
+use crate::i18n::t;
+use colored::Colorize;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Whether `child` resolves (symlinks included) to somewhere inside `root`.
+pub fn is_path_safe(root: &Path, child: &Path) -> Result<bool, CustomError> {
+    let root_abs = root
+        .canonicalize()
+        .map_err(|e| CustomError::ValidationError(t!("Root not found: {}", e)))?;
+
+    let child_abs = child
+        .canonicalize()
+        .map_err(|e| CustomError::ValidationError(t!("System path not found: {}", e)))?;
+
+    Ok(child_abs.starts_with(&root_abs))
+}
+
+/// Whether `path` resolves to somewhere inside one of `roots`. A dangling link counts as
+/// leaving them.
+pub fn is_contained(path: &Path, roots: &[&Path]) -> bool {
+    let Ok(resolved) = path.canonicalize() else {
+        return false;
+    };
+    roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(root))
+}
+
+/// Whether `name` can be used as a single directory under `bonsai/systems` or `utils`.
+/// Names come from cloned manifests and lockfiles too, so `..`, absolute paths and drive
+/// prefixes are refused.
+pub fn is_plain_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+        && !name.contains(['/', '\\', ':'])
+}
+
+/// Copies the directory `src` into `dst`. A symlink is copied as the file it points to when
+/// that file is inside `src`; links leaving `src`, dangling ones and links to directories are
+/// skipped and returned, relative to `src`.
+pub fn copy_dir_all(src: &Path, dst: &Path) -> io::Result<Vec<PathBuf>> {
+    let root = src.canonicalize()?;
+    let mut skipped = Vec::new();
+    copy_contained(&root, src, Path::new(""), dst, &mut skipped)?;
+    Ok(skipped)
+}
+
+fn copy_contained(
+    root: &Path,
+    dir: &Path,
+    relative: &Path,
+    dst: &Path,
+    skipped: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let ty = entry.file_type()?;
+        if ty.is_dir() {
+            copy_contained(root, &entry.path(), &relative.join(&name), &dst.join(&name), skipped)?;
+        } else if ty.is_symlink() {
+            match entry.path().canonicalize() {
+                Ok(target) if target.starts_with(root) && target.is_file() => {
+                    fs::copy(&target, dst.join(&name))?;
+                }
+                _ => skipped.push(relative.join(&name)),
+            }
+        } else {
+            fs::copy(entry.path(), dst.join(&name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Warns about the links [`copy_dir_all`] left out of its copy at `dst`.
+pub fn report_skipped(dst: &Path, skipped: &[PathBuf], ui: &Ui) {
+    for path in skipped {
+        ui.message(&t!(
+            "{} Skipped link '{}': only links to files inside the copied directory are followed",
+            "[WARNING]".yellow(),
+            dst.join(path).display()
+        ));
+    }
+}
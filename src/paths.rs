@@ -0,0 +1,152 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::{PathsOptions, load_manifest};
+use crate::tilemap::MAPS_DIR_NAME;
+use std::path::{Component, Path, PathBuf};
+
+const DEFAULT_ASSETS_DIR: &str = "assets";
+const DEFAULT_SOURCE_DIR: &str = "source";
+const DEFAULT_BUILD_DIR: &str = "build";
+const GAME_PACKAGE: &str = "game";
+const GAME_SHADERS: &str = "shaders";
+
+/// Project directories from `[paths]`, relative to the project root (the working directory).
+/// Builds always use the default layout for their output, so game code keeps loading
+/// `assets/...` no matter where the sources live.
+#[derive(Debug, Clone)]
+pub struct ProjectPaths {
+    pub assets: PathBuf,
+    pub source: PathBuf,
+    pub shaders: PathBuf,
+    pub build: PathBuf,
+    // every spelling of a directory a path may arrive in, mapped to its default-layout name
+    roots: Vec<(PathBuf, &'static str)>,
+}
+
+fn normalize(path: &str) -> PathBuf {
+    Path::new(path)
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+fn configured(name: &str, value: Option<&str>, default: &str) -> Result<PathBuf, CustomError> {
+    let path = normalize(value.unwrap_or(default));
+    if path.as_os_str().is_empty() {
+        return Err(CustomError::ValidationError(t!(
+            "Invalid [paths] {} '{}' (expected a directory other than the project root)",
+            name,
+            value.unwrap_or(default)
+        )));
+    }
+    Ok(path)
+}
+
+impl ProjectPaths {
+    pub fn new(options: &PathsOptions) -> Result<Self, CustomError> {
+        let assets = configured("assets", options.assets.as_deref(), DEFAULT_ASSETS_DIR)?;
+        let source = configured("source", options.source.as_deref(), DEFAULT_SOURCE_DIR)?;
+        let shaders = match options.shaders.as_deref() {
+            Some(shaders) => configured("shaders", Some(shaders), "")?,
+            None => source.join(GAME_PACKAGE).join(GAME_SHADERS),
+        };
+        let build = configured("build", options.build.as_deref(), DEFAULT_BUILD_DIR)?;
+
+        // `bonsai build --clean` deletes the build directory
+        for (name, dir) in [("assets", &assets), ("source", &source), ("shaders", &shaders)] {
+            if dir.starts_with(&build) {
+                return Err(CustomError::ValidationError(t!(
+                    "[paths] build '{}' contains the {} directory '{}'",
+                    build.display(),
+                    name,
+                    dir.display()
+                )));
+            }
+        }
+
+        let cwd = std::env::current_dir()?;
+        let mut roots = Vec::new();
+        // most specific first, the default shaders directory lives inside the source directory
+        for (dir, logical) in [
+            (&shaders, "source/game/shaders"),
+            (&assets, DEFAULT_ASSETS_DIR),
+            (&build, DEFAULT_BUILD_DIR),
+            (&source, DEFAULT_SOURCE_DIR),
+        ] {
+            let absolute = cwd.join(dir);
+            if let Ok(canonical) = absolute.canonicalize() {
+                roots.push((canonical, logical));
+            }
+            roots.push((absolute, logical));
+            roots.push((dir.clone(), logical));
+        }
+        // anything else is named relative to the project root
+        if let Ok(canonical) = cwd.canonicalize() {
+            roots.push((canonical, ""));
+        }
+        roots.push((cwd, ""));
+
+        Ok(Self {
+            assets,
+            source,
+            shaders,
+            build,
+            roots,
+        })
+    }
+
+    /// Paths of the project in the working directory.
+    pub fn load() -> Result<Self, CustomError> {
+        Self::new(&load_manifest(Path::new("."))?.paths)
+    }
+
+    pub fn images(&self) -> PathBuf {
+        self.assets.join("images")
+    }
+
+    pub fn fonts(&self) -> PathBuf {
+        self.assets.join("fonts")
+    }
+
+    pub fn audio(&self) -> PathBuf {
+        self.assets.join("audio")
+    }
+
+    pub fn maps(&self) -> PathBuf {
+        self.assets.join(MAPS_DIR_NAME)
+    }
+
+    /// The `game` collection.
+    pub fn game(&self) -> PathBuf {
+        self.source.join(GAME_PACKAGE)
+    }
+
+    pub fn scenes(&self) -> PathBuf {
+        self.game().join("scenes")
+    }
+
+    pub fn build_target(&self, target: &str) -> PathBuf {
+        self.build.join(target)
+    }
+
+    /// The name `path` has in the default layout (e.g. `../art/images/a.png` becomes
+    /// `assets/images/a.png`), with forward slashes. Paths outside the configured directories
+    /// are returned relative to the project root when possible.
+    pub fn logical(&self, path: &Path) -> String {
+        let path = normalize(&path.to_string_lossy());
+        let logical = self
+            .roots
+            .iter()
+            .find_map(|(root, logical)| {
+                path.strip_prefix(root)
+                    .ok()
+                    .map(|rest| Path::new(logical).join(rest))
+            })
+            .unwrap_or(path);
+        logical
+            .to_string_lossy()
+            .replace('\\', "/")
+            .trim_end_matches('/')
+            .to_string()
+    }
+}
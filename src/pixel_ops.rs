@@ -0,0 +1,154 @@
+use image::{Rgba, RgbaImage};
+use std::collections::VecDeque;
+
+/// Surrounds `img` with `amount` copies of its edge pixels, so filtering at the sprite's edge
+/// samples its own colors instead of the neighbouring sprite or the transparent padding.
+pub fn extrude(img: &RgbaImage, amount: u32) -> RgbaImage {
+    let (w, h) = img.dimensions();
+    if amount == 0 || w == 0 || h == 0 {
+        return img.clone();
+    }
+    RgbaImage::from_fn(w + amount * 2, h + amount * 2, |x, y| {
+        let src_x = x.saturating_sub(amount).min(w - 1);
+        let src_y = y.saturating_sub(amount).min(h - 1);
+        *img.get_pixel(src_x, src_y)
+    })
+}
+
+/// Transparent pixels cut off each edge of a sprite by [`trim`].
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Trim {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+/// The fully transparent rows and columns around the visible pixels of `img`. An image
+/// without any has none.
+pub fn visible_bounds(img: &RgbaImage) -> Trim {
+    let (w, h) = img.dimensions();
+    let visible = |x: u32, y: u32| img.get_pixel(x, y)[3] > 0;
+    let (mut left, mut top, mut right, mut bottom) = (w, h, 0, 0);
+    for y in 0..h {
+        for x in 0..w {
+            if visible(x, y) {
+                left = left.min(x);
+                top = top.min(y);
+                right = right.max(x + 1);
+                bottom = bottom.max(y + 1);
+            }
+        }
+    }
+    if left >= right {
+        return Trim::default();
+    }
+    Trim {
+        left,
+        top,
+        right: w - right,
+        bottom: h - bottom,
+    }
+}
+
+/// Crops `img` to its visible pixels. An image without any stays as it is.
+pub fn trim(img: &RgbaImage) -> (RgbaImage, Trim) {
+    let cut = visible_bounds(img);
+    if cut == Trim::default() {
+        return (img.clone(), cut);
+    }
+    let (w, h) = img.dimensions();
+    let cropped = image::imageops::crop_imm(
+        img,
+        cut.left,
+        cut.top,
+        w - cut.left - cut.right,
+        h - cut.top - cut.bottom,
+    )
+    .to_image();
+    (cropped, cut)
+}
+
+/// Gives every fully transparent pixel the color of the nearest visible one, alpha untouched.
+/// Transparent pixels are usually black, which bilinear filtering blends into a dark halo.
+pub fn bleed(img: &mut RgbaImage) {
+    let (w, h) = img.dimensions();
+    let mut filled: Vec<bool> = img.pixels().map(|p| p[3] > 0).collect();
+    let mut queue: VecDeque<(u32, u32)> = (0..h)
+        .flat_map(|y| (0..w).map(move |x| (x, y)))
+        .filter(|&(x, y)| filled[(y * w + x) as usize])
+        .collect();
+    if queue.is_empty() {
+        return;
+    }
+
+    // breadth first, so each pixel takes the average of the filled pixels one step closer
+    while let Some((x, y)) = queue.pop_front() {
+        for (nx, ny) in neighbours(x, y, w, h) {
+            let index = (ny * w + nx) as usize;
+            if filled[index] {
+                continue;
+            }
+            let (mut sum, mut count) = ([0u32; 3], 0);
+            for (sx, sy) in neighbours(nx, ny, w, h) {
+                if filled[(sy * w + sx) as usize] {
+                    let p = img.get_pixel(sx, sy);
+                    for c in 0..3 {
+                        sum[c] += p[c] as u32;
+                    }
+                    count += 1;
+                }
+            }
+            let p = img.get_pixel_mut(nx, ny);
+            for c in 0..3 {
+                p[c] = (sum[c] / count) as u8;
+            }
+            filled[index] = true;
+            queue.push_back((nx, ny));
+        }
+    }
+}
+
+fn neighbours(x: u32, y: u32, w: u32, h: u32) -> impl Iterator<Item = (u32, u32)> {
+    [(-1i64, 0i64), (1, 0), (0, -1), (0, 1)]
+        .into_iter()
+        .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+        .filter(move |&(nx, ny)| nx >= 0 && ny >= 0 && nx < w as i64 && ny < h as i64)
+        .map(|(nx, ny)| (nx as u32, ny as u32))
+}
+
+/// Multiplies the color channels by alpha. sRGB colors are multiplied in linear space and
+/// encoded again, which is what an sRGB texture format decodes them to before blending.
+pub fn premultiply(img: &mut RgbaImage, srgb: bool) {
+    let table: Vec<f32> = (0..=255u8).map(|v| decode(v, srgb)).collect();
+    for Rgba(p) in img.pixels_mut() {
+        let alpha = p[3] as f32 / 255.0;
+        for c in &mut p[..3] {
+            let linear = table[*c as usize] * alpha;
+            *c = encode(linear, srgb);
+        }
+    }
+}
+
+// 0-255 to a linear 0-1 value
+fn decode(value: u8, srgb: bool) -> f32 {
+    let v = value as f32 / 255.0;
+    if !srgb {
+        v
+    } else if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn encode(value: f32, srgb: bool) -> u8 {
+    let v = if !srgb {
+        value
+    } else if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
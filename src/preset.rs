@@ -0,0 +1,175 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::{
+    AtlasOptions, Manifest, ProfileOptions, ToolchainOptions, WebOptions, migrate_legacy_sections,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use toml_edit::{DocumentMut, Item, Table, value};
+
+/// The bonsai.toml sections a preset carries, as dotted paths.
+pub const PRESET_SECTIONS: &[&str] = &["profile", "assets.atlas", "web", "toolchain"];
+
+#[derive(Deserialize)]
+pub struct PresetInfo {
+    pub name: String,
+    // project the preset was exported from
+    pub source: Option<String>,
+    pub bonsai: Option<String>,
+}
+
+// read to reject typos, settings a preset can't carry and empty presets, the sections are merged as toml
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PresetFile {
+    preset: PresetInfo,
+    #[serde(default)]
+    profile: BTreeMap<String, ProfileOptions>,
+    #[serde(default)]
+    assets: PresetAssets,
+    // presets exported before [atlas] moved under [assets]
+    #[serde(default)]
+    atlas: AtlasOptions,
+    #[serde(default)]
+    web: WebOptions,
+    #[serde(default)]
+    toolchain: ToolchainOptions,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct PresetAssets {
+    #[serde(default)]
+    atlas: AtlasOptions,
+}
+
+/// A preset file with the [`PRESET_SECTIONS`] of `manifest`, named `name`.
+pub fn export_preset(manifest: &str, name: &str) -> Result<String, CustomError> {
+    let mut source = manifest.parse::<DocumentMut>()?;
+    migrate_legacy_sections(&mut source)?;
+
+    let mut info = Table::new();
+    info["name"] = value(name);
+    if let Some(project) = source.get("project").and_then(|p| p.get("name")).and_then(|n| n.as_str()) {
+        info["source"] = value(project);
+    }
+    info["bonsai"] = value(env!("CARGO_PKG_VERSION"));
+    info.set_position(Some(-1));
+
+    let mut preset = DocumentMut::new();
+    preset.insert("preset", Item::Table(info));
+    let mut exported = 0;
+    for section in PRESET_SECTIONS {
+        if let Some(item) = section_item(source.as_table(), section) {
+            section_entry(preset.as_table_mut(), section)?.clone_from(item);
+            exported += 1;
+        }
+    }
+    if exported == 0 {
+        return Err(CustomError::ValidationError(t!(
+            "bonsai.toml has no [profile], [assets.atlas], [web] or [toolchain] settings to export"
+        )));
+    }
+    Ok(preset.to_string())
+}
+
+/// Checks `preset` and returns its `[preset]` header.
+pub fn read_preset(preset: &str) -> Result<PresetInfo, CustomError> {
+    let file: PresetFile = toml_edit::de::from_str(preset)
+        .map_err(|e| CustomError::ValidationError(t!("Invalid preset: {}", e)))?;
+    if file.profile.is_empty()
+        && file.assets.atlas == AtlasOptions::default()
+        && file.atlas == AtlasOptions::default()
+        && file.web == WebOptions::default()
+        && file.toolchain == ToolchainOptions::default()
+    {
+        return Err(CustomError::ValidationError(t!(
+            "Preset '{}' has no [profile], [assets.atlas], [web] or [toolchain] settings to import",
+            file.preset.name
+        )));
+    }
+    Ok(file.preset)
+}
+
+// new tables stay implicit, so [profile.release] doesn't bring an empty [profile] header along
+fn implicit_table() -> Item {
+    let mut table = Table::new();
+    table.set_implicit(true);
+    Item::Table(table)
+}
+
+// the item at a dotted `path` of `table`
+fn section_item<'a>(table: &'a Table, path: &str) -> Option<&'a Item> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (section_item(table, parent)?.as_table()?, key),
+        None => (table, path),
+    };
+    parent.get(key)
+}
+
+// the table at a dotted `path` of `table`, created implicit where it's missing
+fn section_entry<'a>(table: &'a mut Table, path: &str) -> Result<&'a mut Item, CustomError> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => match section_entry(table, parent)? {
+            Item::Table(parent) => (parent, key),
+            _ => return Err(CustomError::ValidationError(t!("[{}] in bonsai.toml isn't a table", parent))),
+        },
+        None => (table, path),
+    };
+    Ok(parent.entry(key).or_insert_with(implicit_table))
+}
+
+// the value as written, without its spacing and trailing comment
+fn repr(item: &Item) -> String {
+    match item.as_value() {
+        Some(value) => {
+            let mut value = value.clone();
+            value.decor_mut().clear();
+            value.to_string()
+        }
+        None => item.to_string().trim().to_string(),
+    }
+}
+
+// settings the preset doesn't mention are kept, so a project can add its own on top
+fn merge(target: &mut Table, source: &Table, path: &str, changes: &mut Vec<(String, String)>) {
+    for (key, item) in source.iter() {
+        let key_path = if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+        if let Item::Table(source_table) = item {
+            let entry = target.entry(key).or_insert_with(implicit_table);
+            if let Item::Table(target_table) = entry {
+                merge(target_table, source_table, &key_path, changes);
+                continue;
+            }
+        }
+        let new = repr(item);
+        if target.get(key).map(|old| repr(old) != new).unwrap_or(true) {
+            target.insert(key, item.clone());
+            changes.push((key_path, new));
+        }
+    }
+}
+
+/// Merges the settings of `preset` into `manifest`. Returns the new manifest and the
+/// settings it changed, as (dotted key, new value).
+pub fn apply_preset(manifest: &str, preset: &str) -> Result<(String, Vec<(String, String)>), CustomError> {
+    let mut doc = manifest.parse::<DocumentMut>()?;
+    migrate_legacy_sections(&mut doc)?;
+    let mut preset_doc = preset.parse::<DocumentMut>()?;
+    migrate_legacy_sections(&mut preset_doc)?;
+
+    let mut changes = Vec::new();
+    for section in PRESET_SECTIONS {
+        if let Some(Item::Table(source)) = section_item(preset_doc.as_table(), section) {
+            match section_entry(doc.as_table_mut(), section)? {
+                Item::Table(target) => merge(target, source, section, &mut changes),
+                _ => return Err(CustomError::ValidationError(t!("[{}] in bonsai.toml isn't a table", section))),
+            }
+        }
+    }
+
+    let merged = doc.to_string();
+    toml_edit::de::from_str::<Manifest>(&merged)
+        .map_err(|e| CustomError::ValidationError(t!("The preset doesn't fit this project: {}", e)))?;
+    Ok((merged, changes))
+}
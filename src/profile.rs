@@ -0,0 +1,173 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::{DefineValue, EmccOpt, Manifest, OdinOpt, ProfileOptions};
+use std::collections::BTreeMap;
+
+/// Profiles every project has, `[profile.<name>]` with one of these names adjusts it.
+pub const BUILTIN_PROFILES: [&str; 3] = ["debug", "release", "profiling"];
+
+// a profile without `inherits` starts from this one
+const BASE_PROFILE: &str = "debug";
+
+/// A build config with its `inherits` chain applied.
+#[derive(Debug, Clone)]
+pub struct BuildProfile {
+    pub name: String,
+    /// Keeps debug symbols: odin `-debug`, emcc `-g` and the debug sokol libraries.
+    pub debug: bool,
+    pub opt: OdinOpt,
+    pub bounds_check: bool,
+    pub emcc_opt: Option<EmccOpt>,
+    pub defines: BTreeMap<String, DefineValue>,
+    pub odin_flags: Vec<String>,
+    pub link_assets: bool,
+}
+
+impl BuildProfile {
+    /// Flags for `odin build`, the profile's own `odin_flags` after the rest.
+    pub fn odin_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.debug {
+            args.push("-debug".to_string());
+        }
+        if self.opt != OdinOpt::None || !self.debug {
+            args.push(format!("-o:{}", self.opt.as_str()));
+        }
+        if !self.bounds_check {
+            args.push("-no-bounds-check".to_string());
+        }
+        args.extend(
+            self.defines
+                .iter()
+                .map(|(name, value)| format!("-define:{}={}", name, value.to_odin())),
+        );
+        args.extend(self.odin_flags.iter().cloned());
+        args
+    }
+
+    /// Flags for the emcc link.
+    pub fn emcc_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(opt) = self.emcc_opt {
+            args.push(format!("-{}", opt.as_str()));
+        }
+        if self.debug {
+            args.push("-g".to_string());
+        }
+        args
+    }
+
+    // odin links the debug sokol libraries whenever ODIN_DEBUG is set
+    pub fn links_debug_libraries(&self) -> bool {
+        self.debug || self.odin_flags.iter().any(|f| f == "-debug")
+    }
+}
+
+fn builtin(name: &str) -> Option<ProfileOptions> {
+    let profile = match name {
+        "debug" => ProfileOptions {
+            debug: Some(true),
+            opt: Some(OdinOpt::None),
+            bounds_check: Some(true),
+            ..Default::default()
+        },
+        "release" => ProfileOptions {
+            debug: Some(false),
+            opt: Some(OdinOpt::Speed),
+            bounds_check: Some(false),
+            emcc_opt: Some(EmccOpt::O2),
+            ..Default::default()
+        },
+        // release speed, with symbols for a profiler
+        "profiling" => ProfileOptions {
+            inherits: Some("release".to_string()),
+            debug: Some(true),
+            ..Default::default()
+        },
+        _ => return None,
+    };
+    Some(profile)
+}
+
+// the manifest's table, on top of the built-in profile of the same name
+fn own_options(manifest: &Manifest, name: &str) -> Option<ProfileOptions> {
+    match (builtin(name), manifest.profile.get(name)) {
+        (Some(base), Some(own)) => Some(ProfileOptions {
+            inherits: own.inherits.clone().or(base.inherits.clone()),
+            ..merge(own, &base)
+        }),
+        (Some(base), None) => Some(base),
+        (None, own) => own.cloned(),
+    }
+}
+
+// fields set in `over` win, `defines` are merged and `odin_flags` appended
+fn merge(over: &ProfileOptions, under: &ProfileOptions) -> ProfileOptions {
+    let mut defines = under.defines.clone();
+    defines.extend(over.defines.clone());
+    ProfileOptions {
+        inherits: None,
+        debug: over.debug.or(under.debug),
+        opt: over.opt.or(under.opt),
+        bounds_check: over.bounds_check.or(under.bounds_check),
+        emcc_opt: over.emcc_opt.or(under.emcc_opt),
+        defines,
+        odin_flags: under.odin_flags.iter().chain(&over.odin_flags).cloned().collect(),
+        link_assets: over.link_assets.or(under.link_assets),
+    }
+}
+
+// built-in profiles are roots unless they say otherwise, the project's own start from debug
+fn parent_name(name: &str, options: &ProfileOptions) -> Option<String> {
+    options
+        .inherits
+        .clone()
+        .or_else(|| builtin(name).is_none().then(|| BASE_PROFILE.to_string()))
+}
+
+/// Resolves the build config `name` (`--config`) from the built-in profiles and `[profile.<name>]`.
+pub fn resolve_profile(manifest: &Manifest, name: &str) -> Result<BuildProfile, CustomError> {
+    let mut options = own_options(manifest, name).ok_or_else(|| {
+        let mut known: Vec<&str> = BUILTIN_PROFILES.to_vec();
+        known.extend(manifest.profile.keys().map(|k| k.as_str()).filter(|k| !BUILTIN_PROFILES.contains(k)));
+        CustomError::ValidationError(t!(
+            "Unknown build config '{}'. Add [profile.{}] to bonsai.toml or use one of: {}",
+            name,
+            name,
+            known.join(", ")
+        ))
+    })?;
+    let mut merged = options.clone();
+    let mut chain = vec![name.to_string()];
+
+    while let Some(next) = parent_name(chain.last().unwrap(), &options) {
+        if chain.contains(&next) {
+            chain.push(next);
+            return Err(CustomError::ValidationError(t!(
+                "[profile.{}] inherits in a cycle: {}",
+                name,
+                chain.join(" -> ")
+            )));
+        }
+        options = own_options(manifest, &next).ok_or_else(|| {
+            CustomError::ValidationError(t!(
+                "[profile.{}] inherits unknown profile '{}'",
+                chain.last().unwrap(),
+                next
+            ))
+        })?;
+        merged = merge(&merged, &options);
+        chain.push(next);
+    }
+
+    Ok(BuildProfile {
+        name: name.to_string(),
+        debug: merged.debug.unwrap_or(true),
+        opt: merged.opt.unwrap_or(OdinOpt::None),
+        bounds_check: merged.bounds_check.unwrap_or(true),
+        emcc_opt: merged.emcc_opt,
+        defines: merged.defines,
+        odin_flags: merged.odin_flags,
+        link_assets: merged.link_assets.unwrap_or(false),
+    })
+}
@@ -0,0 +1,376 @@
+use crate::config::load_global_config;
+use crate::error::CustomError;
+use crate::git::{describe_head, head_commit, remote_url};
+use crate::hash::{hash_file, to_hex};
+use crate::i18n::t;
+use crate::lockfile::{LOCKFILE, Lockfile, system_checksum};
+use crate::manifest::Manifest;
+use crate::paths::ProjectPaths;
+use crate::sysinfo::toolchain;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, Local};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Written into the build output by `bonsai build --provenance`.
+pub const PROVENANCE_FILE: &str = "provenance.intoto.json";
+// hex encoded 32 byte Ed25519 seed, takes precedence over `[provenance] signing_key`
+pub const SIGNING_KEY_ENV: &str = "BONSAI_SIGNING_KEY";
+const PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+const PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v1";
+const BUILD_TYPE: &str = "https://github.com/nihiL7331/bonsai/build/v1";
+const SYSTEMS_DIR: &str = "bonsai/systems";
+
+/// DSSE envelope around the in-toto statement, `payload` is the base64 encoded statement.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Envelope {
+    pub payload_type: String,
+    pub payload: String,
+    pub signatures: Vec<EnvelopeSignature>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EnvelopeSignature {
+    pub keyid: String,
+    pub sig: String,
+}
+
+/// What the build inputs were, beyond the outputs it hashes.
+pub struct BuildInvocation<'a> {
+    pub id: &'a str,
+    pub target: &'a str,
+    pub config: &'a str,
+    pub started: DateTime<Local>,
+}
+
+// DSSE pre-authentication encoding, what the signature actually covers
+fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut out = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    out.extend_from_slice(payload);
+    out
+}
+
+pub fn key_id(key: &VerifyingKey) -> String {
+    to_hex(&Sha256::digest(key.as_bytes()))
+}
+
+fn decode_key_bytes(text: &str, what: &str) -> Result<[u8; 32], CustomError> {
+    let text = text.trim();
+    let invalid = || {
+        CustomError::ValidationError(t!(
+            "Invalid {} (expected 64 hex characters)",
+            what
+        ))
+    };
+    if text.len() != 64 || !text.is_ascii() {
+        return Err(invalid());
+    }
+    let mut bytes = [0u8; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(bytes)
+}
+
+/// `text` is either a hex public key or the path of a file holding one.
+pub fn parse_public_key(text: &str) -> Result<VerifyingKey, CustomError> {
+    let text = match fs::read_to_string(text) {
+        Ok(content) => content,
+        Err(_) => text.to_string(),
+    };
+    VerifyingKey::from_bytes(&decode_key_bytes(&text, "public key")?)
+        .map_err(|e| CustomError::ValidationError(t!("Invalid public key: {}", e)))
+}
+
+pub fn read_signing_key(path: &Path) -> Result<SigningKey, CustomError> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        CustomError::ValidationError(t!("Could not read signing key {}: {}", path.display(), e))
+    })?;
+    Ok(SigningKey::from_bytes(&decode_key_bytes(&content, "signing key")?))
+}
+
+/// The key provenance is signed with: `BONSAI_SIGNING_KEY`, then `[provenance] signing_key`
+/// in `~/.bonsai/config.toml`. None leaves the provenance unsigned.
+pub fn configured_signing_key() -> Result<Option<SigningKey>, CustomError> {
+    if let Ok(seed) = env::var(SIGNING_KEY_ENV)
+        && !seed.trim().is_empty()
+    {
+        let bytes = decode_key_bytes(&seed, SIGNING_KEY_ENV)?;
+        return Ok(Some(SigningKey::from_bytes(&bytes)));
+    }
+    match load_global_config()?.provenance.signing_key {
+        Some(path) => read_signing_key(Path::new(&path)).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn digest(sha256: String) -> Value {
+    json!({ "sha256": sha256 })
+}
+
+// relative path -> sha256 of every file of the build output
+fn output_hashes(output_dir: &Path) -> Result<BTreeMap<String, String>, CustomError> {
+    let mut hashes = BTreeMap::new();
+    for entry in WalkDir::new(output_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let relative = entry.path().strip_prefix(output_dir).unwrap_or(entry.path());
+        let name = relative.to_string_lossy().replace('\\', "/");
+        if name == PROVENANCE_FILE {
+            continue;
+        }
+        hashes.insert(name, hash_file(entry.path())?);
+    }
+    Ok(hashes)
+}
+
+fn resolved_dependencies(
+    manifest: &Manifest,
+    paths: &ProjectPaths,
+) -> Result<Vec<Value>, CustomError> {
+    let mut dependencies = Vec::new();
+
+    if let Some(commit) = head_commit(Path::new(".")) {
+        let uri = remote_url(Path::new("."))
+            .map(|url| format!("git+{}", url))
+            .unwrap_or_else(|| format!("git+file:{}", manifest.project.name));
+        let dirty = describe_head(Path::new(".")).is_some_and(|d| d.ends_with("-dirty"));
+        dependencies.push(json!({
+            "uri": uri,
+            "digest": { "gitCommit": commit },
+            "annotations": { "dirty": dirty },
+        }));
+    }
+
+    for file in ["bonsai.toml", LOCKFILE] {
+        if Path::new(file).exists() {
+            dependencies.push(json!({ "name": file, "digest": digest(hash_file(Path::new(file))?) }));
+        }
+    }
+    for (name, dir) in [("source", &paths.source), ("assets", &paths.assets)] {
+        if dir.exists() {
+            dependencies.push(json!({
+                "name": format!("{}/", dir.to_string_lossy().replace('\\', "/")),
+                "annotations": { "role": name },
+                "digest": digest(system_checksum(dir)?),
+            }));
+        }
+    }
+
+    // what is installed is what was built, the lockfile only says where it came from
+    for (name, locked) in Lockfile::load()?.systems {
+        let dir = Path::new(SYSTEMS_DIR).join(&name);
+        if !dir.is_dir() {
+            continue;
+        }
+        dependencies.push(json!({
+            "uri": format!("git+{}", locked.url),
+            "name": name,
+            "digest": { "gitCommit": locked.commit, "sha256": system_checksum(&dir)? },
+        }));
+    }
+    Ok(dependencies)
+}
+
+// CI run the build came from, or the machine for local builds
+fn builder_id() -> String {
+    if let (Ok(server), Ok(repository), Ok(run)) = (
+        env::var("GITHUB_SERVER_URL"),
+        env::var("GITHUB_REPOSITORY"),
+        env::var("GITHUB_RUN_ID"),
+    ) {
+        return format!("{}/{}/actions/runs/{}", server, repository, run);
+    }
+    if let Ok(job) = env::var("CI_JOB_URL") {
+        return job;
+    }
+    let host = env::var("HOSTNAME")
+        .or_else(|_| env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    format!("local:{}", host)
+}
+
+/// Writes [`PROVENANCE_FILE`] into `output_dir`: an in-toto statement with a SLSA provenance
+/// predicate over every output file, wrapped in a DSSE envelope signed with `key` when given.
+/// Relies on the working directory being the project root.
+pub fn write_provenance(
+    output_dir: &Path,
+    invocation: &BuildInvocation,
+    manifest: &Manifest,
+    key: Option<&SigningKey>,
+) -> Result<(), CustomError> {
+    let paths = ProjectPaths::new(&manifest.paths)?;
+    let tools: BTreeMap<String, String> = toolchain()
+        .into_iter()
+        .filter_map(|(name, version)| Some((name, version?)))
+        .collect();
+
+    let statement = json!({
+        "_type": STATEMENT_TYPE,
+        "subject": output_hashes(output_dir)?
+            .into_iter()
+            .map(|(name, hash)| json!({ "name": name, "digest": digest(hash) }))
+            .collect::<Vec<_>>(),
+        "predicateType": PREDICATE_TYPE,
+        "predicate": {
+            "buildDefinition": {
+                "buildType": BUILD_TYPE,
+                "externalParameters": {
+                    "project": manifest.project.name,
+                    "version": manifest.project.version,
+                    "target": invocation.target,
+                    "config": invocation.config,
+                },
+                "internalParameters": {
+                    "bonsai": env!("CARGO_PKG_VERSION"),
+                    "tools": tools,
+                },
+                "resolvedDependencies": resolved_dependencies(manifest, &paths)?,
+            },
+            "runDetails": {
+                "builder": { "id": builder_id() },
+                "metadata": {
+                    "invocationId": invocation.id,
+                    "startedOn": invocation.started.to_rfc3339(),
+                    "finishedOn": Local::now().to_rfc3339(),
+                },
+            },
+        },
+    });
+
+    let payload = serde_json::to_vec(&statement).map_err(|e| {
+        CustomError::ValidationError(t!("Failed to serialize provenance: {}", e))
+    })?;
+    let signatures = key
+        .map(|key| EnvelopeSignature {
+            keyid: key_id(&key.verifying_key()),
+            sig: BASE64.encode(key.sign(&pae(PAYLOAD_TYPE, &payload)).to_bytes()),
+        })
+        .into_iter()
+        .collect();
+    let envelope = Envelope {
+        payload_type: PAYLOAD_TYPE.to_string(),
+        payload: BASE64.encode(&payload),
+        signatures,
+    };
+
+    let json = serde_json::to_string_pretty(&envelope).map_err(|e| {
+        CustomError::ValidationError(t!("Failed to serialize provenance: {}", e))
+    })?;
+    fs::write(output_dir.join(PROVENANCE_FILE), json)?;
+    Ok(())
+}
+
+/// Result of checking a build directory against its provenance.
+pub struct Verification {
+    pub statement: Value,
+    // key ids of the signatures, and whether `key` verified each
+    pub signatures: Vec<(String, Option<bool>)>,
+    pub mismatched: Vec<String>,
+    pub missing: Vec<String>,
+    // output files the provenance doesn't cover
+    pub unlisted: Vec<String>,
+}
+
+impl Verification {
+    pub fn is_valid(&self, require_signature: bool) -> bool {
+        let signed = self.signatures.iter().any(|(_, valid)| *valid == Some(true));
+        self.mismatched.is_empty()
+            && self.missing.is_empty()
+            && self.unlisted.is_empty()
+            && !self.signatures.iter().any(|(_, valid)| *valid == Some(false))
+            && (signed || !require_signature)
+    }
+}
+
+/// Checks the files in `dir` against the digests in its provenance, and the signatures
+/// against `key` when one is given.
+pub fn verify_provenance(dir: &Path, key: Option<&VerifyingKey>) -> Result<Verification, CustomError> {
+    let path = dir.join(PROVENANCE_FILE);
+    let content = fs::read_to_string(&path).map_err(|e| {
+        CustomError::ValidationError(t!("Could not read {}: {}", path.display(), e))
+    })?;
+    let invalid = |e: String| {
+        CustomError::ValidationError(t!("Invalid {}: {}", path.display(), e))
+    };
+
+    let envelope: Envelope = serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+    let payload = BASE64
+        .decode(&envelope.payload)
+        .map_err(|e| invalid(e.to_string()))?;
+    let statement: Value = serde_json::from_slice(&payload).map_err(|e| invalid(e.to_string()))?;
+
+    let signatures = envelope
+        .signatures
+        .iter()
+        .map(|signature| {
+            let valid = key.filter(|key| key_id(key) == signature.keyid).map(|key| {
+                BASE64
+                    .decode(&signature.sig)
+                    .ok()
+                    .and_then(|bytes| Signature::from_slice(&bytes).ok())
+                    .is_some_and(|sig| {
+                        key.verify(&pae(&envelope.payload_type, &payload), &sig).is_ok()
+                    })
+            });
+            (signature.keyid.clone(), valid)
+        })
+        .collect();
+
+    let listed: BTreeMap<String, String> = statement["subject"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|subject| {
+            Some((
+                subject["name"].as_str()?.to_string(),
+                subject["digest"]["sha256"].as_str()?.to_string(),
+            ))
+        })
+        .collect();
+    let actual = output_hashes(dir)?;
+
+    let mut verification = Verification {
+        statement,
+        signatures,
+        mismatched: Vec::new(),
+        missing: Vec::new(),
+        unlisted: Vec::new(),
+    };
+    for (name, hash) in &listed {
+        match actual.get(name) {
+            Some(actual_hash) if actual_hash == hash => {}
+            Some(_) => verification.mismatched.push(name.clone()),
+            None => verification.missing.push(name.clone()),
+        }
+    }
+    verification.unlisted = actual
+        .keys()
+        .filter(|name| !listed.contains_key(*name))
+        .cloned()
+        .collect();
+    Ok(verification)
+}
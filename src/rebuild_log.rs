@@ -0,0 +1,235 @@
+use crate::freshness::SETTINGS_DIR;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Why every output the incremental build tracks was last rebuilt, read by `bonsai explain-build`.
+pub const REBUILD_LOG: &str = ".bonsai/cache/rebuilds.json";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    UpToDate,
+    // stale, and the rebuild hasn't finished (yet)
+    Rebuilding,
+    Rebuilt,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Reason {
+    Missing,
+    Forced { why: String },
+    // modification times: the input is newer than the output
+    Newer { input: String },
+    // content hashes: the input differs from the one the output was built from
+    Changed { input: String },
+    Added { input: String },
+    Removed { input: String },
+    Settings { stage: String, before: Option<String>, after: String },
+    // the inputs are unchanged, but the output isn't what was built from them
+    OutputChanged,
+    // content hashes, but nothing recorded what the output was built from
+    Unrecorded,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct InputState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RebuildRecord {
+    pub status: Status,
+    pub checked: String,
+    pub rebuilt: Option<String>,
+    // why the latest rebuild happened, kept while later checks find the output up to date
+    pub reasons: Vec<Reason>,
+    // what the output was last built from
+    #[serde(default)]
+    pub inputs: BTreeMap<String, InputState>,
+}
+
+static LOG: Mutex<Option<BTreeMap<String, RebuildRecord>>> = Mutex::new(None);
+
+fn now() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+pub fn key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// The recorded outputs, by their path relative to the project root.
+pub fn load_log() -> BTreeMap<String, RebuildRecord> {
+    fs::read_to_string(REBUILD_LOG)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+// the log is only a diagnostic, failing to write it never fails a build
+fn update(output: &Path, f: impl FnOnce(Option<RebuildRecord>) -> RebuildRecord) {
+    let mut guard = LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let log = guard.get_or_insert_with(load_log);
+    let key = key(output);
+    let record = f(log.remove(&key));
+    log.insert(key, record);
+
+    if let Ok(json) = serde_json::to_string_pretty(log) {
+        if let Some(parent) = Path::new(REBUILD_LOG).parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(REBUILD_LOG, json);
+    }
+}
+
+fn settings_stage(input: &Path) -> Option<String> {
+    input
+        .strip_prefix(SETTINGS_DIR)
+        .ok()
+        .and_then(|p| p.file_stem())
+        .map(|stem| stem.to_string_lossy().to_string())
+}
+
+fn snapshot(inputs: &[PathBuf], hashes: Option<&BTreeMap<PathBuf, String>>) -> BTreeMap<String, InputState> {
+    inputs
+        .iter()
+        .map(|input| {
+            let state = InputState {
+                hash: hashes.and_then(|h| h.get(input).cloned()),
+                settings: settings_stage(input).and_then(|_| fs::read_to_string(input).ok()),
+            };
+            (key(input), state)
+        })
+        .collect()
+}
+
+fn settings_reason(input: &Path, previous: Option<&InputState>) -> Option<Reason> {
+    let stage = settings_stage(input)?;
+    Some(Reason::Settings {
+        stage,
+        before: previous.and_then(|p| p.settings.clone()),
+        after: fs::read_to_string(input).unwrap_or_default(),
+    })
+}
+
+/// Records that `output` was found up to date.
+pub fn up_to_date(output: &Path) {
+    update(output, |record| match record {
+        Some(record) => RebuildRecord {
+            status: Status::UpToDate,
+            checked: now(),
+            ..record
+        },
+        None => RebuildRecord {
+            status: Status::UpToDate,
+            checked: now(),
+            rebuilt: None,
+            reasons: Vec::new(),
+            inputs: BTreeMap::new(),
+        },
+    });
+}
+
+/// Records that `output` is about to be rebuilt for `reasons`.
+pub fn stale(output: &Path, reasons: Vec<Reason>) {
+    update(output, |record| RebuildRecord {
+        status: Status::Rebuilding,
+        checked: now(),
+        rebuilt: record.as_ref().and_then(|r| r.rebuilt.clone()),
+        reasons,
+        inputs: record.map(|r| r.inputs).unwrap_or_default(),
+    });
+}
+
+/// Records that `output` is rebuilt when modification times decide freshness:
+/// `newer` are the inputs modified after it.
+pub fn stale_by_mtime(output: &Path, inputs: &[PathBuf], newer: &[PathBuf]) {
+    let previous = load_inputs(output);
+    let mut reasons: Vec<Reason> = newer
+        .iter()
+        .map(|input| {
+            settings_reason(input, previous.get(&key(input))).unwrap_or(Reason::Newer { input: key(input) })
+        })
+        .collect();
+    reasons.extend(added_and_removed(inputs, &previous));
+    stale(output, reasons);
+}
+
+/// Records that `output` is rebuilt when content hashes decide freshness.
+pub fn stale_by_hash(output: &Path, inputs: &[PathBuf], hashes: &BTreeMap<PathBuf, String>) {
+    let previous = load_inputs(output);
+    if previous.is_empty() {
+        stale(output, vec![Reason::Unrecorded]);
+        return;
+    }
+    let mut reasons: Vec<Reason> = inputs
+        .iter()
+        .filter_map(|input| {
+            let before = previous.get(&key(input))?;
+            if let Some(reason) = settings_reason(input, Some(before)) {
+                let changed = matches!(&reason, Reason::Settings { before, after, .. } if before.as_ref() != Some(after));
+                return changed.then_some(reason);
+            }
+            // no hash when the previous build compared modification times
+            (before.hash.is_some() && before.hash.as_ref() != hashes.get(input))
+                .then(|| Reason::Changed { input: key(input) })
+        })
+        .collect();
+    reasons.extend(added_and_removed(inputs, &previous));
+    if reasons.is_empty() {
+        reasons.push(Reason::Unrecorded);
+    }
+    stale(output, reasons);
+}
+
+/// Records that `output` was rebuilt from `inputs` with these content hashes.
+pub fn rebuilt(output: &Path, inputs: &[PathBuf], hashes: &BTreeMap<PathBuf, String>) {
+    let inputs = snapshot(inputs, Some(hashes));
+    update(output, |record| RebuildRecord {
+        status: Status::Rebuilt,
+        checked: now(),
+        rebuilt: Some(now()),
+        // a rebuild nothing checked first was forced by the caller
+        reasons: match record {
+            Some(record) if record.status == Status::Rebuilding => record.reasons,
+            _ => vec![Reason::Forced {
+                why: "rebuilt without an up-to-date check".to_string(),
+            }],
+        },
+        inputs,
+    });
+}
+
+fn load_inputs(output: &Path) -> BTreeMap<String, InputState> {
+    let mut guard = LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let log = guard.get_or_insert_with(load_log);
+    log.get(&key(output)).map(|r| r.inputs.clone()).unwrap_or_default()
+}
+
+// only meaningful against a previous build's inputs
+fn added_and_removed(inputs: &[PathBuf], previous: &BTreeMap<String, InputState>) -> Vec<Reason> {
+    if previous.is_empty() {
+        return Vec::new();
+    }
+    let current: BTreeMap<String, ()> = inputs.iter().map(|i| (key(i), ())).collect();
+    let mut reasons: Vec<Reason> = current
+        .keys()
+        .filter(|input| !previous.contains_key(*input))
+        .map(|input| Reason::Added { input: input.clone() })
+        .collect();
+    reasons.extend(
+        previous
+            .keys()
+            .filter(|input| !current.contains_key(*input))
+            .map(|input| Reason::Removed { input: input.clone() }),
+    );
+    reasons
+}
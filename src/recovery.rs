@@ -0,0 +1,105 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Present while a build runs. Left behind by a build that panicked, failed or was killed.
+pub const INCOMPLETE_MARKER: &str = ".bonsai/incomplete";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    // still running, or the process died without a chance to say otherwise
+    Running,
+    Failed,
+    Cancelled,
+}
+
+/// What the marker records about the build that wrote it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IncompleteBuild {
+    pub pid: u32,
+    pub target: String,
+    pub stage: String,
+    pub started: String,
+    pub outcome: Outcome,
+}
+
+static CURRENT: Mutex<Option<IncompleteBuild>> = Mutex::new(None);
+
+// written next to the marker and renamed over it, so the marker itself is never half-written
+fn write_marker(build: &IncompleteBuild) -> Result<(), CustomError> {
+    let json = serde_json::to_string_pretty(build).map_err(|e| {
+        CustomError::ValidationError(t!("Failed to serialize build marker: {}", e))
+    })?;
+    let marker = Path::new(INCOMPLETE_MARKER);
+    if let Some(parent) = marker.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let staged = marker.with_extension("tmp");
+    fs::write(&staged, json)?;
+    fs::rename(&staged, marker)?;
+    Ok(())
+}
+
+fn update(f: impl FnOnce(&mut IncompleteBuild)) -> Result<(), CustomError> {
+    let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    match current.as_mut() {
+        Some(build) => {
+            f(build);
+            write_marker(build)
+        }
+        None => Ok(()),
+    }
+}
+
+/// The marker a previous build left, if any. `Err` when it exists but can't be read,
+/// in which case nothing is known about where that build stopped.
+pub fn incomplete_build() -> Option<Result<IncompleteBuild, CustomError>> {
+    let json = fs::read_to_string(INCOMPLETE_MARKER).ok()?;
+    Some(serde_json::from_str(&json).map_err(|e| {
+        CustomError::ValidationError(t!("Unreadable {}: {}", INCOMPLETE_MARKER, e))
+    }))
+}
+
+/// Writes the marker for a new `target` build. Call with the project lock held.
+pub fn begin(target: &str) -> Result<(), CustomError> {
+    let build = IncompleteBuild {
+        pid: std::process::id(),
+        target: target.to_string(),
+        stage: String::new(),
+        started: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        outcome: Outcome::Running,
+    };
+    write_marker(&build)?;
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = Some(build);
+    Ok(())
+}
+
+/// Records the stage the running build entered, so a cleanup knows what may be half-written.
+pub fn enter_stage(stage: &str) -> Result<(), CustomError> {
+    update(|build| build.stage = stage.to_string())
+}
+
+/// Records that the build returned an error. The marker stays, the outputs of the failed
+/// stage are still suspect.
+pub fn fail(error: &CustomError) {
+    let outcome = match error {
+        CustomError::Cancelled => Outcome::Cancelled,
+        _ => Outcome::Failed,
+    };
+    let _ = update(|build| build.outcome = outcome);
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Removes the marker of a build that completed.
+pub fn finish() -> Result<(), CustomError> {
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    if Path::new(INCOMPLETE_MARKER).exists() {
+        fs::remove_file(INCOMPLETE_MARKER)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use crate::Ui;
+use crate::build::{build_desktop, build_web};
+use crate::error::CustomError;
+use crate::freshness::{is_fresh, mark_fresh};
+use crate::hash::hash_file;
+use crate::history::{DEFAULT_KEEP_BUILDS, new_build_id, snapshot_build};
+use crate::i18n::t;
+use crate::itch::DEPLOY_TARGETS;
+use crate::manifest::{Manifest, ReleaseOptions};
+use crate::paths::ProjectPaths;
+use crate::update::write_update_manifest;
+use regex::Regex;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Deploys `[release] deploy` can run.
+pub const RELEASE_DEPLOYS: &[&str] = &["itch"];
+const DEFAULT_RELEASE_CONFIG: &str = "release";
+const DEFAULT_TAG: &str = "v{version}";
+// archives and their checksums, inside the build directory
+const RELEASE_DIR_NAME: &str = "release";
+const CHECKSUMS_FILE: &str = "SHA256SUMS";
+
+/// Checks `[release]` before anything runs.
+pub fn validate_release_options(manifest: &Manifest) -> Result<(), CustomError> {
+    let options = &manifest.release;
+    for target in &options.targets {
+        if !DEPLOY_TARGETS.contains(&target.as_str()) {
+            return Err(CustomError::ValidationError(t!(
+                "Unknown [release] target '{}' (expected one of: {})",
+                target,
+                DEPLOY_TARGETS.join(", ")
+            )));
+        }
+    }
+    for deploy in &options.deploy {
+        if !RELEASE_DEPLOYS.contains(&deploy.as_str()) {
+            return Err(CustomError::ValidationError(t!(
+                "Unknown [release] deploy '{}' (expected one of: {})",
+                deploy,
+                RELEASE_DEPLOYS.join(", ")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The targets to build and package, in the canonical order (default: all of them).
+pub fn release_targets(options: &ReleaseOptions) -> Vec<String> {
+    DEPLOY_TARGETS
+        .iter()
+        .filter(|t| options.targets.is_empty() || options.targets.iter().any(|r| r == *t))
+        .map(|t| t.to_string())
+        .collect()
+}
+
+pub fn release_config(options: &ReleaseOptions) -> &str {
+    options.config.as_deref().unwrap_or(DEFAULT_RELEASE_CONFIG)
+}
+
+/// The git tag of `version`, None when `[release] tag` is empty.
+pub fn release_tag(options: &ReleaseOptions, version: &str) -> Option<String> {
+    let tag = options.tag.as_deref().unwrap_or(DEFAULT_TAG);
+    (!tag.is_empty()).then(|| tag.replace("{version}", version))
+}
+
+/// Builds `target`, writes its update manifest and records the build in the history.
+/// Returns the output directory and the build id.
+pub fn build_recorded(
+    target: &str,
+    config: &str,
+    manifest: &Manifest,
+    paths: &ProjectPaths,
+    ui: &Ui,
+) -> Result<(PathBuf, String), CustomError> {
+    ui.log(&t!("Building for {} ({}).", target, config));
+    match target {
+        "web" => build_web(config, false, ui)?,
+        _ => {
+            build_desktop(config, false, ui)?;
+        }
+    }
+
+    let keep = manifest.build.keep_builds.unwrap_or(DEFAULT_KEEP_BUILDS);
+    let output_dir = paths.build_target(target);
+    let id = new_build_id(target);
+    write_update_manifest(&output_dir, target, &id, manifest)?;
+    if snapshot_build(&output_dir, &id, target, config, keep, ui)? {
+        ui.log(&t!("Recorded build {}.", id));
+    }
+    Ok((output_dir, id))
+}
+
+pub fn release_dir(paths: &ProjectPaths) -> PathBuf {
+    paths.build.join(RELEASE_DIR_NAME)
+}
+
+/// Zips the build in `output_dir` into `archive`, unless the archive already holds the same
+/// files. Returns whether it was written.
+pub fn package_build(output_dir: &Path, archive: &Path) -> Result<bool, CustomError> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(output_dir).sort_by_file_name() {
+        let entry = entry.map_err(|e| CustomError::IoError(e.into()))?;
+        if entry.file_type().is_file() {
+            files.push(entry.into_path());
+        }
+    }
+    if files.is_empty() {
+        return Err(CustomError::BuildError(t!(
+            "Nothing to package in {}",
+            output_dir.display()
+        )));
+    }
+    if is_fresh(&files, archive)? {
+        return Ok(false);
+    }
+
+    if let Some(parent) = archive.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let partial = archive.with_extension("partial");
+    let _remove_partial = scopeguard::guard(partial.clone(), |partial| {
+        let _ = fs::remove_file(partial);
+    });
+    let archive_error =
+        |e: zip::result::ZipError| CustomError::BuildError(t!("Failed to write {}: {}", archive.display(), e));
+
+    let mut zip = ZipWriter::new(File::create(&partial)?);
+    for file in &files {
+        let name = file
+            .strip_prefix(output_dir)
+            .unwrap_or(file)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let metadata = fs::metadata(file)?;
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(metadata.len() >= u32::MAX as u64);
+        // executables have to stay executable once unpacked
+        #[cfg(unix)]
+        let options = {
+            use std::os::unix::fs::PermissionsExt;
+            options.unix_permissions(metadata.permissions().mode())
+        };
+        zip.start_file(name, options).map_err(archive_error)?;
+        io::copy(&mut File::open(file)?, &mut zip)?;
+    }
+    zip.finish().map_err(archive_error)?;
+
+    fs::rename(&partial, archive)?;
+    mark_fresh(&files, archive)?;
+    Ok(true)
+}
+
+/// Writes `SHA256SUMS` next to the archives, in the format `sha256sum --check` reads.
+pub fn write_checksums(archives: &[PathBuf], dir: &Path) -> Result<PathBuf, CustomError> {
+    let mut checksums = String::new();
+    for archive in archives {
+        let name = archive.file_name().unwrap_or_default().to_string_lossy();
+        checksums.push_str(&format!("{}  {}\n", hash_file(archive)?, name));
+    }
+    let path = dir.join(CHECKSUMS_FILE);
+    fs::write(&path, checksums)?;
+    Ok(path)
+}
+
+/// The changelog at `path` with its "## Unreleased" section renamed to `version` and `date`,
+/// and an empty "## Unreleased" section above it for what comes next.
+pub fn release_changelog(path: &Path, version: &str, date: &str) -> Result<String, CustomError> {
+    let changelog = fs::read_to_string(path).map_err(|e| {
+        CustomError::ValidationError(t!("Failed to read changelog {}: {}", path.display(), e))
+    })?;
+
+    let released = Regex::new(&format!(r"(?m)^##\s+\[?{}\]?(\s|$)", regex::escape(version)))
+        .map_err(|e| CustomError::ValidationError(e.to_string()))?;
+    if released.is_match(&changelog) {
+        return Err(CustomError::ValidationError(t!(
+            "{} already has a section for {}",
+            path.display(),
+            version
+        )));
+    }
+
+    let unreleased = Regex::new(r"(?mi)^##\s+\[?unreleased\]?[ \t]*$")
+        .map_err(|e| CustomError::ValidationError(e.to_string()))?;
+    let Some(heading) = unreleased.find(&changelog) else {
+        return Err(CustomError::ValidationError(t!(
+            "{} has no '## Unreleased' section to release",
+            path.display()
+        )));
+    };
+
+    Ok(format!(
+        "{}{}\n\n## {} - {}{}",
+        &changelog[..heading.start()],
+        heading.as_str(),
+        version,
+        date,
+        &changelog[heading.end()..]
+    ))
+}
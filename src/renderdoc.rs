@@ -0,0 +1,97 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const CAPTURES_DIR: &str = ".bonsai/captures";
+const RENDERDOC_CMD: &str = if cfg!(windows) {
+    "renderdoccmd.exe"
+} else {
+    "renderdoccmd"
+};
+const INSTALL_DIRS: &[&str] = if cfg!(windows) {
+    &["C:\\Program Files\\RenderDoc", "C:\\Program Files (x86)\\RenderDoc"]
+} else {
+    &["/usr/bin", "/usr/local/bin", "/opt/renderdoc/bin"]
+};
+/// Environment variable pointing at the RenderDoc install, checked before the defaults.
+const RENDERDOC_ENV: &str = "RENDERDOC_PATH";
+
+fn find_renderdoccmd() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var(RENDERDOC_ENV) {
+        let dir = PathBuf::from(dir);
+        for candidate in [dir.join(RENDERDOC_CMD), dir.join("bin").join(RENDERDOC_CMD)] {
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+
+    if Command::new(RENDERDOC_CMD).arg("version").output().is_ok() {
+        return Some(PathBuf::from(RENDERDOC_CMD));
+    }
+
+    INSTALL_DIRS
+        .iter()
+        .map(|dir| Path::new(dir).join(RENDERDOC_CMD))
+        .find(|path| path.is_file())
+}
+
+/// A desktop launch wrapped by `renderdoccmd capture`, writing into its own capture directory.
+pub struct CaptureSession {
+    pub command: Command,
+    pub capture_dir: PathBuf,
+}
+
+/// Locates renderdoccmd, so a missing install is reported before building.
+pub fn find_renderdoc() -> Result<PathBuf, CustomError> {
+    if cfg!(target_os = "macos") {
+        return Err(CustomError::ValidationError(
+            t!("RenderDoc does not support macOS, use Xcode's Metal frame capture instead")
+        ));
+    }
+
+    find_renderdoccmd().ok_or_else(|| {
+        CustomError::ValidationError(t!(
+            "RenderDoc not found. Install it from https://renderdoc.org or set {} to its directory",
+            RENDERDOC_ENV
+        ))
+    })
+}
+
+pub fn capture_session(renderdoccmd: &Path, executable: &Path) -> Result<CaptureSession, CustomError> {
+    let capture_dir = std::path::absolute(CAPTURES_DIR)?
+        .join(Local::now().format("%Y%m%d-%H%M%S").to_string());
+    fs::create_dir_all(&capture_dir)?;
+
+    let mut command = Command::new(renderdoccmd);
+    command
+        .arg("capture")
+        .arg("--wait-for-exit")
+        .arg("--capture-file")
+        .arg(capture_dir.join("capture"))
+        .arg("--working-dir")
+        .arg(std::env::current_dir()?)
+        .arg(executable);
+
+    Ok(CaptureSession {
+        command,
+        capture_dir,
+    })
+}
+
+pub fn list_captures(capture_dir: &Path) -> Vec<PathBuf> {
+    let mut captures = fs::read_dir(capture_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "rdc"))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    captures.sort();
+    captures
+}
@@ -1,14 +1,28 @@
 use crate::Ui;
+use crate::config::load_global_config;
 use crate::error::CustomError;
-use std::{env, fs, fs::File, io, path::PathBuf};
+use crate::hash::{hash_file, to_hex};
+use crate::i18n::t;
+use crate::manifest::load_manifest;
+use colored::Colorize;
+use indicatif::ProgressBar;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::{env, fs, fs::File, path::PathBuf};
+use tempfile::NamedTempFile;
 use ureq::Agent;
 use ureq::tls::{RootCerts, TlsConfig};
 
 const SHDC_BASE_URL: &str = "https://raw.githubusercontent.com/floooh/sokol-tools-bin/master/bin";
+const SHDC_BASE_URL_ENV: &str = "BONSAI_SHDC_BASE_URL";
+const SHDC_SHA256_ENV: &str = "BONSAI_SHDC_SHA256";
+// a mirror publishes `sokol-shdc.sha256` beside the binary, `sha256sum` output or the bare hash
+const PUBLISHED_CHECKSUM_EXTENSION: &str = "sha256";
 
 fn get_install_dir() -> Result<PathBuf, CustomError> {
     let base_dir = dirs::data_local_dir().ok_or_else(|| {
-        CustomError::ValidationError("Could not find local data directory".into())
+        CustomError::ValidationError(t!("Could not find local data directory"))
     })?;
 
     let install_dir = base_dir.join("bonsai").join("bin");
@@ -24,78 +38,316 @@ fn get_executable_name() -> &'static str {
     }
 }
 
+fn get_base_url() -> String {
+    if let Ok(url) = env::var(SHDC_BASE_URL_ENV)
+        && !url.trim().is_empty()
+    {
+        return url.trim().trim_end_matches('/').to_string();
+    }
+
+    load_global_config()
+        .ok()
+        .and_then(|c| c.toolchain.shdc_base_url)
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| SHDC_BASE_URL.to_string())
+}
+
 pub fn get_shdc_url() -> Result<String, CustomError> {
     let os = env::consts::OS;
     let arch = env::consts::ARCH;
+    let base_url = get_base_url();
 
     match (os, arch) {
-        ("windows", _) => Ok(format!("{}/win32/sokol-shdc.exe", SHDC_BASE_URL)),
-        ("linux", _) => Ok(format!("{}/linux/sokol-shdc", SHDC_BASE_URL)),
-        ("macos", "aarch64") => Ok(format!("{}/osx_arm64/sokol-shdc", SHDC_BASE_URL)),
-        ("macos", _) => Ok(format!("{}/osx/sokol-shdc", SHDC_BASE_URL)),
-        _ => Err(CustomError::ValidationError(format!(
-            "Unsupported platform: {} {}",
+        ("windows", _) => Ok(format!("{}/win32/sokol-shdc.exe", base_url)),
+        ("linux", "x86_64") => Ok(format!("{}/linux/sokol-shdc", base_url)),
+        ("linux", "aarch64") => Ok(format!("{}/linux_arm64/sokol-shdc", base_url)),
+        ("macos", "aarch64") => Ok(format!("{}/osx_arm64/sokol-shdc", base_url)),
+        ("macos", _) => Ok(format!("{}/osx/sokol-shdc", base_url)),
+        _ => Err(CustomError::ValidationError(t!(
+            "No prebuilt sokol-shdc for {} {}. Build it from https://github.com/floooh/sokol-tools and set [toolchain] shdc in bonsai.toml",
             os, arch
         ))),
     }
 }
 
-fn install_shdc(ui: &Ui) -> Result<PathBuf, CustomError> {
-    let install_dir = get_install_dir()?;
-    fs::create_dir_all(&install_dir).map_err(|e| {
-        CustomError::IoError(std::io::Error::new(
-            e.kind(),
-            format!("Failed to create directory {:?}: {}", install_dir, e),
-        ))
-    })?;
+// the checksum pinned for this machine's sokol-shdc, if any
+fn pinned_checksum() -> Option<String> {
+    if let Ok(checksum) = env::var(SHDC_SHA256_ENV)
+        && !checksum.trim().is_empty()
+    {
+        return Some(checksum);
+    }
+    load_global_config().ok().and_then(|c| c.toolchain.shdc_sha256)
+}
 
-    let dest_path = install_dir.join(get_executable_name());
-    let url = get_shdc_url()?;
+fn parse_checksum(text: &str) -> Option<String> {
+    let checksum = text.split_whitespace().next()?.to_lowercase();
+    (checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit())).then_some(checksum)
+}
 
-    ui.message(&format!(
-        "Downloading sokol-shdc for {}...",
-        env::consts::OS
-    ));
-    ui.message(&format!("  Source: {}", url));
+fn is_remote(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
 
-    let agent = Agent::config_builder()
+fn agent() -> Agent {
+    Agent::config_builder()
         .tls_config(
             TlsConfig::builder()
                 .root_certs(RootCerts::PlatformVerifier)
                 .build(),
         )
         .build()
-        .new_agent();
+        .new_agent()
+}
 
-    let response = agent
-        .get(&url)
+// the checksum a mirror publishes next to `url`, None when it publishes none
+fn published_checksum(url: &str) -> Result<Option<String>, CustomError> {
+    let checksum_url = format!("{}.{}", url, PUBLISHED_CHECKSUM_EXTENSION);
+    let text = if is_remote(url) {
+        match agent().get(&checksum_url).header("User-Agent", "bonsai-cli").call() {
+            Ok(response) => response.into_body().read_to_string().map_err(|e| {
+                CustomError::BuildError(t!("Failed to download {}: {}", checksum_url, e))
+            })?,
+            Err(ureq::Error::StatusCode(404)) => return Ok(None),
+            Err(e) => {
+                return Err(CustomError::BuildError(t!(
+                    "Failed to download {}: {}",
+                    checksum_url,
+                    e
+                )));
+            }
+        }
+    } else {
+        let path = checksum_url.strip_prefix("file://").unwrap_or(&checksum_url);
+        match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(CustomError::IoError(e)),
+        }
+    };
+
+    parse_checksum(&text).map(Some).ok_or_else(|| {
+        CustomError::ValidationError(t!("{} does not hold a sha256 checksum", checksum_url))
+    })
+}
+
+/// The checksum the sokol-shdc at `url` has to match: the pinned one, else the one published
+/// beside it. Only the default GitHub download may come without one, every mirror needs one.
+fn expected_checksum(url: &str) -> Result<Option<String>, CustomError> {
+    if let Some(pinned) = pinned_checksum() {
+        return parse_checksum(&pinned).map(Some).ok_or_else(|| {
+            CustomError::ValidationError(t!(
+                "{} / [toolchain] shdc_sha256 is not a sha256 checksum: {}",
+                SHDC_SHA256_ENV,
+                pinned.trim()
+            ))
+        });
+    }
+    let published = published_checksum(url)?;
+    if published.is_none() && !url.starts_with(SHDC_BASE_URL) {
+        return Err(CustomError::ValidationError(t!(
+            "No checksum for the sokol-shdc at {}. Publish {}.{} next to it, or set {} / [toolchain] shdc_sha256",
+            url,
+            url,
+            PUBLISHED_CHECKSUM_EXTENSION,
+            SHDC_SHA256_ENV
+        )));
+    }
+    Ok(published)
+}
+
+fn checksum_path(binary_path: &Path) -> PathBuf {
+    binary_path.with_extension("sha256")
+}
+
+// binaries installed before checksums were recorded are trusted as-is
+fn is_checksum_valid(binary_path: &Path) -> bool {
+    let expected = match fs::read_to_string(checksum_path(binary_path)) {
+        Ok(c) => c,
+        Err(_) => return true,
+    };
+
+    match hash_file(binary_path) {
+        Ok(actual) => actual == expected.trim(),
+        Err(_) => false,
+    }
+}
+
+fn copy_hashed(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    bar: &ProgressBar,
+) -> Result<(u64, String), CustomError> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut written: u64 = 0;
+
+    loop {
+        let read = reader.read(&mut buffer).map_err(CustomError::IoError)?;
+        if read == 0 {
+            break;
+        }
+        writer
+            .write_all(&buffer[..read])
+            .map_err(CustomError::IoError)?;
+        hasher.update(&buffer[..read]);
+        written += read as u64;
+        bar.set_position(written);
+    }
+    bar.finish_and_clear();
+    writer.flush().map_err(CustomError::IoError)?;
+
+    Ok((written, to_hex(&hasher.finalize())))
+}
+
+fn download_shdc(url: &str, dest: &mut File, ui: &Ui) -> Result<String, CustomError> {
+    let response = agent()
+        .get(url)
         .header("User-Agent", "bonsai-cli")
         .call()
-        .map_err(|e| CustomError::BuildError(format!("Failed to download sokol-shdc: {}", e)))?;
+        .map_err(|e| CustomError::BuildError(t!("Failed to download sokol-shdc: {}", e)))?;
 
-    let mut dest_file = File::create(&dest_path).map_err(|e| CustomError::IoError(e))?;
+    let expected_len = response
+        .headers()
+        .get("Content-Length")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
 
     let mut reader = response.into_body().into_reader();
+    let bar = ui.progress_bar(expected_len, "Downloading sokol-shdc");
+    let (written, checksum) = copy_hashed(&mut reader, dest, &bar)?;
 
-    io::copy(&mut reader, &mut dest_file).map_err(|e| CustomError::IoError(e))?;
+    if let Some(expected) = expected_len
+        && expected != written
+    {
+        return Err(CustomError::BuildError(t!(
+            "Incomplete sokol-shdc download ({} of {} bytes)",
+            written, expected
+        )));
+    }
+    if written == 0 {
+        return Err(CustomError::BuildError(
+            t!("Downloaded sokol-shdc binary is empty")
+        ));
+    }
+
+    Ok(checksum)
+}
+
+// mirrors can also be plain directories (air-gapped caches, network shares)
+fn copy_local_shdc(source: &str, dest: &mut File, ui: &Ui) -> Result<String, CustomError> {
+    let source_path = Path::new(source.strip_prefix("file://").unwrap_or(source));
+    let mut source_file = File::open(source_path).map_err(|e| {
+        CustomError::BuildError(t!("Failed to open sokol-shdc mirror file {}: {}", format!("{:?}", source_path), e))
+    })?;
+
+    let len = source_file.metadata().ok().map(|m| m.len());
+    let bar = ui.progress_bar(len, "Copying sokol-shdc");
+    let (_, checksum) = copy_hashed(&mut source_file, dest, &bar)?;
+
+    Ok(checksum)
+}
+
+fn install_shdc(ui: &Ui) -> Result<PathBuf, CustomError> {
+    let url = get_shdc_url()?;
+
+    ui.message(&t!(
+        "Downloading sokol-shdc for {}...",
+        env::consts::OS
+    ));
+    ui.message(&t!("  Source: {}", url));
+
+    let expected = expected_checksum(&url)?;
+    install_from(&url, expected.as_deref(), ui)
+}
+
+/// Installs the sokol-shdc an offline bundle brought, which has to match the bundle's `checksum`.
+pub fn install_bundled_shdc(source: &Path, checksum: &str, ui: &Ui) -> Result<PathBuf, CustomError> {
+    if hash_file(source)? != checksum {
+        return Err(CustomError::ValidationError(t!(
+            "The sokol-shdc in the bundle does not match its checksum"
+        )));
+    }
+    install_from(&source.to_string_lossy(), Some(checksum), ui)
+}
+
+// `url` is a download or a local file, checked against `expected` before it replaces anything
+fn install_from(url: &str, expected: Option<&str>, ui: &Ui) -> Result<PathBuf, CustomError> {
+    let install_dir = get_install_dir()?;
+    fs::create_dir_all(&install_dir).map_err(|e| {
+        CustomError::IoError(std::io::Error::new(
+            e.kind(),
+            format!("Failed to create directory {:?}: {}", install_dir, e),
+        ))
+    })?;
+
+    let dest_path = install_dir.join(get_executable_name());
+
+    // download next to the destination so the final rename stays on the same filesystem
+    let mut temp_file = NamedTempFile::new_in(&install_dir).map_err(CustomError::IoError)?;
+
+    let checksum = if is_remote(url) {
+        download_shdc(url, temp_file.as_file_mut(), ui)?
+    } else {
+        copy_local_shdc(url, temp_file.as_file_mut(), ui)?
+    };
+    if let Some(expected) = expected
+        && checksum != expected
+    {
+        return Err(CustomError::ValidationError(t!(
+            "The sokol-shdc from {} does not match its checksum (expected {}, got {})",
+            url,
+            expected,
+            checksum
+        )));
+    }
 
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&dest_path)?.permissions();
+        let mut perms = temp_file.as_file().metadata()?.permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(&dest_path, perms)?;
+        fs::set_permissions(temp_file.path(), perms)?;
+    }
+
+    temp_file
+        .persist(&dest_path)
+        .map_err(|e| CustomError::IoError(e.error))?;
+
+    fs::write(checksum_path(&dest_path), &checksum)?;
+
+    if ui.verbose {
+        ui.log(&t!("sokol-shdc sha256: {}", checksum));
     }
 
-    ui.log(&format!("Installed sokol-shdc to {:?}", dest_path));
+    ui.log(&t!("Installed sokol-shdc to {}", dest_path.display()));
     Ok(dest_path)
 }
 
+fn get_vendored_shdc() -> Option<PathBuf> {
+    load_manifest(Path::new("."))
+        .ok()
+        .and_then(|m| m.toolchain.shdc)
+        .map(PathBuf::from)
+}
+
 pub fn get_or_install_shdc(ui: &Ui) -> PathBuf {
+    if let Some(vendored) = get_vendored_shdc() {
+        if vendored.is_file() {
+            if ui.verbose {
+                ui.log(&t!("Using vendored sokol-shdc: {}", vendored.display()));
+            }
+            return vendored;
+        }
+
+        ui.error(&t!("sokol-shdc set in [toolchain] shdc was not found: {}", format!("{:?}", vendored)));
+        std::process::exit(1);
+    }
+
     let install_dir = match get_install_dir() {
         Ok(d) => d,
         Err(e) => {
-            ui.error(&format!("Critical error resolving paths: {}", e));
+            ui.error(&t!("Critical error resolving paths: {}", e));
             std::process::exit(1);
         }
     };
@@ -103,15 +355,21 @@ pub fn get_or_install_shdc(ui: &Ui) -> PathBuf {
     let path = install_dir.join(get_executable_name());
 
     if path.exists() {
-        return path;
+        if is_checksum_valid(&path) {
+            return path;
+        }
+        ui.message(&t!("{} sokol-shdc at {} does not match its recorded checksum. Reinstalling...", "[WARNING]".yellow(), format!("{:?}", path)));
     }
 
     match install_shdc(ui) {
         Ok(p) => p,
         Err(e) => {
-            ui.error(&format!("Failed to install sokol-shdc: {}", e));
+            ui.error(&t!("Failed to install sokol-shdc: {}", e));
 
-            ui.error("Make sure you have internet access and file write permissions.");
+            ui.error(&t!(
+                "Make sure you have internet access and file write permissions, or point {} / [toolchain] shdc_base_url at a mirror.",
+                SHDC_BASE_URL_ENV
+            ));
             std::process::exit(1);
         }
     }
@@ -1,12 +1,25 @@
 use crate::Ui;
+use crate::config::bundle_cache_dir;
+use crate::emsdk::{emscripten_tool, find_emsdk};
 use crate::error::CustomError;
+use crate::hash::{hash_file, to_hex};
+use crate::i18n::{t, tr};
+use crate::manifest::load_manifest;
+use crate::msvc::msvc_environment;
 use rayon::prelude::*;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use sha2::{Digest, Sha256};
 use std::process::Command;
+use std::time::SystemTime;
 
 const SOKOL_LIB_DIR: &str = "bonsai/libs/sokol";
+const SOKOL_SRC_DIR: &str = "bonsai/libs/sokol/c";
+const SOKOL_STAMP_DIR: &str = ".bonsai/cache/sokol";
+// libraries an offline bundle brought, under the bundle cache, one directory per sources fingerprint
+pub const BUNDLE_SOKOL_DIR: &str = "sokol";
 
 const SOKOL_MODULES: &[&str] = &[
     "sokol_log",
@@ -19,6 +32,39 @@ const SOKOL_MODULES: &[&str] = &[
     "sokol_shape",
     "sokol_gl",
 ];
+// modules linked into the web build, in link order
+const SOKOL_WASM_LINKED: &[&str] = &["app", "glue", "gfx", "shape", "log", "gl", "audio"];
+const SOKOL_WASM_MODULES: &[&str] = &[
+    "log",
+    "gfx",
+    "app",
+    "glue",
+    "time",
+    "audio",
+    "debugtext",
+    "shape",
+    "gl",
+];
+
+fn profile_suffix(is_debug: bool) -> &'static str {
+    if is_debug { "debug" } else { "release" }
+}
+
+fn wasm_library(module: &str, is_debug: bool) -> PathBuf {
+    Path::new(SOKOL_LIB_DIR).join(module).join(format!(
+        "sokol_{}_wasm_gl_{}.a",
+        module,
+        profile_suffix(is_debug)
+    ))
+}
+
+/// Sokol archives linked into the web build for the given profile.
+pub fn wasm_libraries(is_debug: bool) -> Vec<String> {
+    SOKOL_WASM_LINKED
+        .iter()
+        .map(|module| wasm_library(module, is_debug).to_string_lossy().replace('\\', "/"))
+        .collect()
+}
 
 fn clean_dir(path: &Path) {
     if !path.exists() {
@@ -40,6 +86,103 @@ fn clean_dir(path: &Path) {
     }
 }
 
+/// Removes the compiled sokol libraries of every platform and profile, and their stamps.
+pub fn clean_sokol(ui: &Ui) -> Result<(), CustomError> {
+    let sokol_dir = Path::new(SOKOL_LIB_DIR);
+    let mut folders: Vec<&str> = SOKOL_MODULES
+        .iter()
+        .map(|module| module.strip_prefix("sokol_").unwrap_or(module))
+        .chain(SOKOL_WASM_MODULES.iter().copied())
+        .collect();
+    folders.sort();
+    folders.dedup();
+    for folder in folders {
+        clean_dir(&sokol_dir.join(folder));
+    }
+
+    let stamps = Path::new(SOKOL_STAMP_DIR);
+    if stamps.exists() {
+        fs::remove_dir_all(stamps)?;
+    }
+
+    ui.log(tr("Cleaned sokol libraries."));
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum WindowsCompiler {
+    Msvc,
+    ClangCl,
+    Zig,
+}
+
+struct WindowsToolchain {
+    compiler: WindowsCompiler,
+    env: HashMap<String, String>,
+}
+
+impl WindowsCompiler {
+    fn from_name(name: &str) -> Result<Self, CustomError> {
+        match name {
+            "msvc" | "cl" => Ok(Self::Msvc),
+            "clang-cl" => Ok(Self::ClangCl),
+            "zig" => Ok(Self::Zig),
+            _ => Err(CustomError::ValidationError(t!(
+                "Unknown [toolchain] c_compiler '{}'. Expected one of: msvc, clang-cl, zig",
+                name
+            ))),
+        }
+    }
+
+    fn check_command(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Msvc => ("cl", "/?"),
+            Self::ClangCl => ("clang-cl", "--version"),
+            Self::Zig => ("zig", "version"),
+        }
+    }
+}
+
+fn windows_toolchain(arch: &str, ui: &Ui) -> Result<WindowsToolchain, CustomError> {
+    let manifest = load_manifest(Path::new("."))?;
+    let compiler = match manifest.toolchain.c_compiler.as_deref() {
+        Some(name) => WindowsCompiler::from_name(name)?,
+        None => WindowsCompiler::Msvc,
+    };
+
+    if compiler == WindowsCompiler::Msvc {
+        return Ok(WindowsToolchain {
+            compiler,
+            env: msvc_environment(arch, ui)?,
+        });
+    }
+
+    let (program, arg) = compiler.check_command();
+    let check = Command::new(program)
+        .arg(arg)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+
+    if check.is_err() {
+        return Err(CustomError::BuildError(t!(
+            "The '{}' command selected by [toolchain] c_compiler was not found in PATH",
+            program
+        )));
+    }
+
+    // clang-cl still needs the windows sdk headers, so reuse the msvc environment when available
+    let env = if compiler == WindowsCompiler::ClangCl {
+        msvc_environment(arch, ui).unwrap_or_default()
+    } else {
+        HashMap::new()
+    };
+
+    ui.log(&t!("Compiling sokol with '{}'.", program));
+
+    Ok(WindowsToolchain { compiler, env })
+}
+
 pub fn compile_sokol(
     is_web_target: bool,
     is_debug: bool,
@@ -47,30 +190,13 @@ pub fn compile_sokol(
     ui: &Ui,
 ) -> Result<(), CustomError> {
     if is_web_target {
-        compile_sokol_wasm(clean, ui)?;
+        compile_sokol_wasm(is_debug, clean, ui)?;
         return Ok(());
     }
 
-    if cfg!(windows) {
-        let check = Command::new("cl")
-            .arg("/?")
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .status();
-
-        if check.is_err() {
-            return Err(CustomError::BuildError(
-                "The 'cl' command (MSVC compiler) was not found.\n\
-                For Sokol compilation on Windows, you must run this tool from the \
-                'Visual Studio Developer Command Prompt' or a terminal with Build Tools initialized"
-                    .to_string(),
-            ));
-        }
-    }
-
     let sokol_dir = Path::new(SOKOL_LIB_DIR);
     if !sokol_dir.exists() {
-        return Err(CustomError::BuildError(format!(
+        return Err(CustomError::BuildError(t!(
             "Sokol directory not found: {}",
             sokol_dir.display()
         )));
@@ -78,11 +204,7 @@ pub fn compile_sokol(
 
     let os = env::consts::OS;
     let profile = if is_debug { "Debug" } else { "Release" };
-    let arch = if cfg!(target_arch = "x86_64") {
-        "x64"
-    } else {
-        "arm64"
-    };
+    let arch = sokol_arch()?;
     let backends = match os {
         "windows" => vec![
             ("D3D11", "SOKOL_D3D11", "d3d11"),
@@ -93,29 +215,53 @@ pub fn compile_sokol(
             ("GL", "SOKOL_GLCORE", "gl"),
         ],
         "linux" => vec![("GL", "SOKOL_GLCORE", "gl")],
-        _ => return Err(CustomError::BuildError(format!("Unsupported OS: {}", os))),
+        _ => return Err(CustomError::BuildError(t!("Unsupported OS: {}", os))),
     };
 
-    ui.status(&format!("Compiling sokol for {} [{}]...", os, arch));
+    ui.status(&t!("Compiling sokol for {} [{}]...", os, arch));
+
+    // every library of the selected profile must exist, the other profile is irrelevant
+    let lib_ext = if os == "windows" { "lib" } else { "a" };
+    let libraries: Vec<PathBuf> = SOKOL_MODULES
+        .iter()
+        .flat_map(|module| {
+            let folder_name = module.strip_prefix("sokol_").unwrap_or(module);
+            backends.iter().map(move |(_, _, suffix)| {
+                sokol_dir.join(folder_name).join(format!(
+                    "{}_{}_{}_{}_{}.{}",
+                    module,
+                    os,
+                    arch,
+                    suffix,
+                    profile.to_lowercase(),
+                    lib_ext
+                ))
+            })
+        })
+        .collect();
 
-    let default_backend_suffix = backends[0].2;
-    let check_lib_name = format!(
-        "app/sokol_app_{}_{}_{}_{}.{}",
+    let stamp = Path::new(SOKOL_STAMP_DIR).join(format!(
+        "{}_{}_{}",
         os,
         arch,
-        default_backend_suffix,
-        profile.to_lowercase(),
-        if os == "windows" { "lib" } else { "a" }
-    );
-    let check_path = sokol_dir.join(&check_lib_name);
-
-    if !clean && check_path.exists() {
-        ui.status("Sokol compilation skipped (already compiled).");
+        profile.to_lowercase()
+    ));
+    let fingerprint = sources_fingerprint()?;
+
+    if !clean
+        && (is_up_to_date(&libraries, &stamp, &fingerprint, ui)?
+            || copy_prebuilt(&libraries, &stamp, &fingerprint, ui)?)
+    {
+        ui.metrics.cache(true);
+        ui.graph.record("sokol", true);
+        ui.status(tr("Sokol compilation skipped (already compiled)."));
         return Ok(());
     }
+    ui.metrics.cache(false);
+    ui.graph.record("sokol", false);
 
     if clean {
-        ui.status("Cleaning sokol artifacts...");
+        ui.status(tr("Cleaning sokol artifacts..."));
 
         for module in SOKOL_MODULES {
             let folder_name = module.strip_prefix("sokol_").unwrap_or(module);
@@ -125,6 +271,12 @@ pub fn compile_sokol(
         }
     }
 
+    let toolchain = if os == "windows" {
+        Some(windows_toolchain(arch, ui)?)
+    } else {
+        None
+    };
+
     let mut tasks = Vec::new();
     for module in SOKOL_MODULES {
         for (_, define, suffix) in &backends {
@@ -139,14 +291,11 @@ pub fn compile_sokol(
 
             let output_dir = sokol_dir.join(folder_name);
             fs::create_dir_all(&output_dir).map_err(|e| {
-                CustomError::ProcessError(format!(
-                    "Failed to create directory {:?}: {}",
-                    output_dir, e
-                ))
+                CustomError::ProcessError(t!("Failed to create directory {}: {}", format!("{:?}", output_dir), e))
             })?;
 
-            if os == "windows" {
-                build_windows(sokol_dir, module, define, suffix, arch, is_debug)?;
+            if let Some(toolchain) = &toolchain {
+                build_windows(sokol_dir, module, define, suffix, arch, is_debug, toolchain)?;
             } else {
                 build_unix(sokol_dir, module, define, suffix, arch, is_debug)?;
             }
@@ -155,65 +304,164 @@ pub fn compile_sokol(
 
     if os == "windows" {
         if ui.verbose {
-            ui.status("Building Windows DLLs...");
+            ui.status(tr("Building Windows DLLs..."));
         }
     }
 
+    write_stamp(&stamp, &fingerprint)?;
+
     Ok(())
 }
 
-fn compile_sokol_wasm(clean: bool, ui: &Ui) -> Result<(), CustomError> {
-    let sokol_dir = Path::new(SOKOL_LIB_DIR);
-    let check_path = sokol_dir.join("app/sokol_app_wasm_gl_release.a");
+// hash of every sokol C source and header, so engine upgrades invalidate the compiled libraries
+pub fn sources_fingerprint() -> Result<String, CustomError> {
+    let mut sources: Vec<PathBuf> = match fs::read_dir(SOKOL_SRC_DIR) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("c" | "h")))
+            .collect(),
+        Err(_) => return Ok(String::new()),
+    };
+    sources.sort();
 
-    if !clean && check_path.exists() {
-        ui.status("Sokol compilation skipped (already compiled).");
-        return Ok(());
+    let mut hasher = Sha256::new();
+    for source in &sources {
+        hasher.update(source.file_name().unwrap_or_default().as_encoded_bytes());
+        hasher.update(hash_file(source)?.as_bytes());
     }
+    Ok(to_hex(&hasher.finalize()))
+}
 
-    ui.status("Compiling sokol (WASM)...");
+fn newest_source_mtime() -> Option<SystemTime> {
+    fs::read_dir(SOKOL_SRC_DIR)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+}
 
-    let sokol_dir = Path::new(SOKOL_LIB_DIR);
+fn is_up_to_date(
+    libraries: &[PathBuf],
+    stamp: &Path,
+    fingerprint: &str,
+    ui: &Ui,
+) -> Result<bool, CustomError> {
+    if !libraries.iter().all(|lib| lib.exists()) {
+        return Ok(false);
+    }
 
-    let (compiler, archiver) = if cfg!(windows) {
-        ("emcc.bat", "emar.bat")
-    } else {
-        ("emcc", "emar")
+    if let Ok(recorded) = fs::read_to_string(stamp) {
+        if recorded.trim() != fingerprint {
+            ui.log(tr("Sokol sources changed since the libraries were compiled, recompiling."));
+            return Ok(false);
+        }
+        return Ok(true);
+    }
+
+    // libraries compiled before stamps existed: fall back to modification times once
+    let oldest_library = libraries
+        .iter()
+        .filter_map(|lib| fs::metadata(lib).ok()?.modified().ok())
+        .min();
+    if let (Some(source), Some(library)) = (newest_source_mtime(), oldest_library)
+        && source > library
+    {
+        ui.log(tr("Sokol sources are newer than the compiled libraries, recompiling."));
+        return Ok(false);
+    }
+
+    write_stamp(stamp, fingerprint)?;
+    Ok(true)
+}
+
+// libraries `bonsai bundle use` unpacked for these exact sources are copied instead of compiled
+fn copy_prebuilt(
+    libraries: &[PathBuf],
+    stamp: &Path,
+    fingerprint: &str,
+    ui: &Ui,
+) -> Result<bool, CustomError> {
+    if fingerprint.is_empty() {
+        return Ok(false);
+    }
+    let Ok(cache) = bundle_cache_dir() else {
+        return Ok(false);
     };
+    let prebuilt = cache.join(BUNDLE_SOKOL_DIR).join(fingerprint);
+    let sources: Vec<PathBuf> = libraries
+        .iter()
+        .map(|lib| prebuilt.join(lib.strip_prefix(SOKOL_LIB_DIR).unwrap_or(lib)))
+        .collect();
+    if !sources.iter().all(|source| source.is_file()) {
+        return Ok(false);
+    }
 
-    let check = Command::new(compiler)
-        .arg("--version")
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status();
+    for (source, library) in sources.iter().zip(libraries) {
+        if let Some(parent) = library.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(source, library)?;
+    }
+    write_stamp(stamp, fingerprint)?;
+    ui.log(tr("Copied prebuilt sokol libraries from an offline bundle."));
+    Ok(true)
+}
 
-    if check.is_err() {
-        return Err(CustomError::BuildError(
-            "The 'emcc' command was not found.\n\
-            Please install the Emscripten SDK and run 'emsdk_env' to add it to your PATH"
-                .to_string(),
-        ));
-    }
-
-    let modules = &[
-        "log",
-        "gfx",
-        "app",
-        "glue",
-        "time",
-        "audio",
-        "debugtext",
-        "shape",
-        "gl",
-    ];
-
-    let profiles = &[
-        ("Debug", "debug", "-g"),
-        ("Release", "release", "-O2 -DNDEBUG"),
-    ];
+fn write_stamp(stamp: &Path, fingerprint: &str) -> Result<(), CustomError> {
+    if let Some(parent) = stamp.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(stamp, fingerprint)?;
+    Ok(())
+}
+
+// arch segment of the sokol library names expected by the odin bindings
+fn sokol_arch() -> Result<&'static str, CustomError> {
+    match env::consts::ARCH {
+        "x86_64" => Ok("x64"),
+        "aarch64" => Ok("arm64"),
+        other => Err(CustomError::BuildError(t!(
+            "Unsupported architecture for sokol: {}",
+            other
+        ))),
+    }
+}
+
+fn compile_sokol_wasm(is_debug: bool, clean: bool, ui: &Ui) -> Result<(), CustomError> {
+    let libraries: Vec<PathBuf> = SOKOL_WASM_MODULES
+        .iter()
+        .map(|module| wasm_library(module, is_debug))
+        .collect();
+    let stamp = Path::new(SOKOL_STAMP_DIR).join(format!("wasm_{}", profile_suffix(is_debug)));
+    let fingerprint = sources_fingerprint()?;
+
+    if !clean
+        && (is_up_to_date(&libraries, &stamp, &fingerprint, ui)?
+            || copy_prebuilt(&libraries, &stamp, &fingerprint, ui)?)
+    {
+        ui.metrics.cache(true);
+        ui.graph.record("sokol", true);
+        ui.status(tr("Sokol compilation skipped (already compiled)."));
+        return Ok(());
+    }
+    ui.metrics.cache(false);
+    ui.graph.record("sokol", false);
+
+    ui.status(&t!(
+        "Compiling sokol (WASM, {})...",
+        profile_suffix(is_debug)
+    ));
+
+    let sokol_dir = Path::new(SOKOL_LIB_DIR);
+
+    let (compiler, archiver) = find_emscripten_tools()?;
+
+    let modules = SOKOL_WASM_MODULES;
+    let flags = if is_debug { "-g" } else { "-O2 -DNDEBUG" };
 
     if clean {
-        ui.status("Cleaning WASM artifacts...");
+        ui.status(tr("Cleaning WASM artifacts..."));
 
         for module in modules {
             let target_dir = sokol_dir.join(module);
@@ -222,30 +470,19 @@ fn compile_sokol_wasm(clean: bool, ui: &Ui) -> Result<(), CustomError> {
         }
     }
 
-    let mut tasks = Vec::new();
-    for module in modules {
-        for profile in profiles {
-            tasks.push((module, profile));
-        }
-    }
-
-    tasks.par_iter().try_for_each(
-        |(module, (_, prof_suffix, flags))| -> Result<(), CustomError> {
+    modules.par_iter().try_for_each(
+        |module| -> Result<(), CustomError> {
             let src_path = sokol_dir.join(format!("c/sokol_{}.c", module));
-            let obj_name = format!("sokol_{}_{}.o", module, prof_suffix);
+            let obj_name = format!("sokol_{}_{}.o", module, profile_suffix(is_debug));
             let obj_path = sokol_dir.join(&obj_name);
             let out_folder = sokol_dir.join(module);
-            let out_lib_name = format!("sokol_{}_wasm_gl_{}.a", module, prof_suffix);
-            let out_lib_path = out_folder.join(&out_lib_name);
+            let out_lib_path = wasm_library(module, is_debug);
 
             fs::create_dir_all(&out_folder).map_err(|e| {
-                CustomError::ProcessError(format!(
-                    "Failed to create directory {:?}: {}",
-                    out_folder, e
-                ))
+                CustomError::ProcessError(t!("Failed to create directory {}: {}", format!("{:?}", out_folder), e))
             })?;
 
-            let mut cmd = Command::new(compiler);
+            let mut cmd = Command::new(&compiler);
             cmd.arg("-c").arg("-DIMPL").arg("-DSOKOL_GLES3");
 
             for flag in flags.split_whitespace() {
@@ -256,23 +493,23 @@ fn compile_sokol_wasm(clean: bool, ui: &Ui) -> Result<(), CustomError> {
 
             let output = cmd
                 .output()
-                .map_err(|e| CustomError::ProcessError(format!("Failed to run emcc: {}", e)))?;
+                .map_err(|e| CustomError::ProcessError(t!("Failed to run emcc: {}", e)))?;
             if !output.status.success() {
-                return Err(CustomError::BuildError(format!(
+                return Err(CustomError::BuildError(t!(
                     "WASM compilation failed for {}:\n{}",
                     module,
                     String::from_utf8_lossy(&output.stderr)
                 )));
             }
 
-            let mut ar_cmd = Command::new(archiver);
+            let mut ar_cmd = Command::new(&archiver);
             ar_cmd.arg("rcs").arg(&out_lib_path).arg(&obj_path);
 
             let output = ar_cmd
                 .output()
-                .map_err(|e| CustomError::ProcessError(format!("Failed to run emar: {}", e)))?;
+                .map_err(|e| CustomError::ProcessError(t!("Failed to run emar: {}", e)))?;
             if !output.status.success() {
-                return Err(CustomError::BuildError(format!(
+                return Err(CustomError::BuildError(t!(
                     "WASM archiving failed for {}:\n{}",
                     module,
                     String::from_utf8_lossy(&output.stderr)
@@ -285,9 +522,41 @@ fn compile_sokol_wasm(clean: bool, ui: &Ui) -> Result<(), CustomError> {
         },
     )?;
 
+    write_stamp(&stamp, &fingerprint)?;
+
     Ok(())
 }
 
+fn find_emscripten_tools() -> Result<(PathBuf, PathBuf), CustomError> {
+    let (compiler, archiver) = if cfg!(windows) {
+        ("emcc.bat", "emar.bat")
+    } else {
+        ("emcc", "emar")
+    };
+
+    let check = Command::new(compiler)
+        .arg("--version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+
+    if check.is_ok() {
+        return Ok((PathBuf::from(compiler), PathBuf::from(archiver)));
+    }
+
+    if let Some(emsdk_path) = find_emsdk() {
+        let compiler = emscripten_tool(&emsdk_path, "emcc");
+        if compiler.exists() {
+            return Ok((compiler, emscripten_tool(&emsdk_path, "emar")));
+        }
+    }
+
+    Err(CustomError::BuildError(
+        t!("The 'emcc' command was not found.\n\
+        Please install the Emscripten SDK and run 'emsdk_env' to add it to your PATH")
+    ))
+}
+
 fn build_windows(
     root_dir: &Path,
     module: &str,
@@ -295,6 +564,7 @@ fn build_windows(
     suffix: &str,
     arch: &str,
     is_debug: bool,
+    toolchain: &WindowsToolchain,
 ) -> Result<(), CustomError> {
     let folder = module.strip_prefix("sokol_").unwrap_or(module);
     let profile_suffix = if is_debug { "debug" } else { "release" };
@@ -309,7 +579,18 @@ fn build_windows(
         folder, module, arch, suffix, profile_suffix
     ));
 
-    let mut cmd = Command::new("cl");
+    if toolchain.compiler == WindowsCompiler::Zig {
+        return build_windows_zig(module, define, arch, is_debug, &src, &obj, &lib);
+    }
+
+    let (compiler, archiver) = if toolchain.compiler == WindowsCompiler::ClangCl {
+        ("clang-cl", "llvm-lib")
+    } else {
+        ("cl", "lib")
+    };
+
+    let mut cmd = Command::new(compiler);
+    cmd.envs(&toolchain.env);
     cmd.args(&["/c", "/DIMPL", &format!("/D{}", define)]);
 
     if is_debug {
@@ -323,25 +604,28 @@ fn build_windows(
 
     let output = cmd
         .output()
-        .map_err(|e| CustomError::ProcessError(format!("Failed to run cl.exe: {}", e)))?;
+        .map_err(|e| CustomError::ProcessError(t!("Failed to run {}: {}", compiler, e)))?;
     if !output.status.success() {
-        return Err(CustomError::BuildError(format!(
-            "CL compilation failed for {}:\n{}",
+        return Err(CustomError::BuildError(t!(
+            "{} compilation failed for {}:\n{}",
+            compiler.to_uppercase(),
             module,
             String::from_utf8_lossy(&output.stdout)
         )));
     }
 
-    let mut cmd = Command::new("lib");
+    let mut cmd = Command::new(archiver);
+    cmd.envs(&toolchain.env);
     cmd.arg(format!("/OUT:{}", lib.display()));
     cmd.arg(&obj);
 
     let output = cmd
         .output()
-        .map_err(|e| CustomError::ProcessError(format!("Failed to run lib.exe: {}", e)))?;
+        .map_err(|e| CustomError::ProcessError(t!("Failed to run {}: {}", archiver, e)))?;
     if !output.status.success() {
-        return Err(CustomError::BuildError(format!(
-            "LIB failed for {}:\n{}",
+        return Err(CustomError::BuildError(t!(
+            "{} failed for {}:\n{}",
+            archiver.to_uppercase(),
             module,
             String::from_utf8_lossy(&output.stdout)
         )));
@@ -351,6 +635,62 @@ fn build_windows(
     Ok(())
 }
 
+fn build_windows_zig(
+    module: &str,
+    define: &str,
+    arch: &str,
+    is_debug: bool,
+    src: &Path,
+    obj: &Path,
+    lib: &Path,
+) -> Result<(), CustomError> {
+    // odin links against the MSVC ABI, while zig defaults to the GNU one
+    let target = match arch {
+        "arm64" => "aarch64-windows-msvc",
+        _ => "x86_64-windows-msvc",
+    };
+    let mut cmd = Command::new("zig");
+    cmd.args(["cc", "-target", target, "-c", "-x", "c", "-DIMPL", &format!("-D{}", define)]);
+    cmd.arg("-fno-sanitize=undefined");
+
+    if is_debug {
+        cmd.args(["-D_DEBUG", "-g"]);
+    } else {
+        cmd.args(["-O2", "-DNDEBUG"]);
+    }
+
+    cmd.arg(src).arg("-o").arg(obj);
+
+    let output = cmd
+        .output()
+        .map_err(|e| CustomError::ProcessError(t!("Failed to run zig cc: {}", e)))?;
+    if !output.status.success() {
+        return Err(CustomError::BuildError(t!(
+            "zig cc compilation failed for {}:\n{}",
+            module,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    let output = Command::new("zig")
+        .arg("ar")
+        .arg("rcs")
+        .arg(lib)
+        .arg(obj)
+        .output()
+        .map_err(|e| CustomError::ProcessError(t!("Failed to run zig ar: {}", e)))?;
+    if !output.status.success() {
+        return Err(CustomError::BuildError(t!(
+            "zig ar failed for {}:\n{}",
+            module,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    let _ = fs::remove_file(obj);
+    Ok(())
+}
+
 fn build_unix(
     root_dir: &Path,
     module: &str,
@@ -381,7 +721,7 @@ fn build_unix(
             "aarch64" => "arm64",
             "x86_64" => "x86_64",
             _ => {
-                return Err(CustomError::BuildError(format!(
+                return Err(CustomError::BuildError(t!(
                     "unsupported macOS arch: {}",
                     std::env::consts::ARCH
                 )));
@@ -405,9 +745,9 @@ fn build_unix(
 
     let output = cmd
         .output()
-        .map_err(|e| CustomError::ProcessError(format!("Clang failed: {}", e)))?;
+        .map_err(|e| CustomError::ProcessError(t!("Clang failed: {}", e)))?;
     if !output.status.success() {
-        return Err(CustomError::BuildError(format!(
+        return Err(CustomError::BuildError(t!(
             "Compilation failed for {}:\n{}",
             module,
             String::from_utf8_lossy(&output.stderr)
@@ -417,10 +757,10 @@ fn build_unix(
     let output = Command::new("ar")
         .args(&["rcs", &lib.to_string_lossy(), &obj.to_string_lossy()])
         .output()
-        .map_err(|e| CustomError::ProcessError(format!("Ar failed: {}", e)))?;
+        .map_err(|e| CustomError::ProcessError(t!("Ar failed: {}", e)))?;
 
     if !output.status.success() {
-        return Err(CustomError::BuildError(format!(
+        return Err(CustomError::BuildError(t!(
             "Archive failed for {}",
             module
         )));
@@ -0,0 +1,212 @@
+use crate::error::CustomError;
+use crate::freshness::{is_same_copy, write_if_changed};
+use crate::hash::to_hex;
+use crate::i18n::t;
+use crate::paths::ProjectPaths;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+// editor and OS droppings that never belong in a build
+const DEFAULT_EXCLUDES: &[&str] = &[".DS_Store", "**/.DS_Store", "Thumbs.db", "**/Thumbs.db", "*~"];
+// one list per destination directory of the files staging put there, the only ones it removes
+const STAGED_RECORDS_DIR: &str = ".bonsai/cache/staged";
+
+pub struct AssetFilter {
+    excludes: GlobSet,
+    paths: Option<ProjectPaths>,
+}
+
+impl AssetFilter {
+    pub fn new(patterns: &[String]) -> Result<Self, CustomError> {
+        let mut builder = GlobSetBuilder::new();
+
+        for pattern in DEFAULT_EXCLUDES.iter().copied().chain(patterns.iter().map(|p| p.as_str())) {
+            let glob = Glob::new(pattern).map_err(|e| {
+                CustomError::ValidationError(t!("Invalid exclude pattern '{}': {}", pattern, e))
+            })?;
+            builder.add(glob);
+        }
+
+        let excludes = builder
+            .build()
+            .map_err(|e| CustomError::ValidationError(t!("Invalid exclude patterns: {}", e)))?;
+
+        Ok(Self {
+            excludes,
+            paths: None,
+        })
+    }
+
+    // also match paths by their default-layout name, so `assets/...` patterns follow [paths]
+    pub fn with_paths(mut self, paths: &ProjectPaths) -> Self {
+        self.paths = Some(paths.clone());
+        self
+    }
+
+    // paths are matched relative to the project root, with forward slashes
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        let normalized = path.to_string_lossy().replace('\\', "/");
+        let normalized = normalized.trim_start_matches("./");
+        self.excludes.is_match(normalized)
+            || self
+                .paths
+                .as_ref()
+                .is_some_and(|paths| self.excludes.is_match(paths.logical(path)))
+    }
+}
+
+struct StagedFile {
+    src: PathBuf,
+    dest: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// Mirrors `src` (a file or directory) into `dest`, copying only new or changed files in parallel
+/// and removing files an earlier run staged that no longer exist in the source. Files anything
+/// else put into `dest` are left alone.
+pub fn stage_files(src: &Path, dest: &Path, filter: &AssetFilter) -> Result<usize, CustomError> {
+    if !src.exists() {
+        return Ok(0);
+    }
+
+    let mut files = Vec::new();
+    for entry in WalkDir::new(src).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() || filter.is_excluded(entry.path()) {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(src).unwrap_or(Path::new(""));
+        let dest_path = if relative.as_os_str().is_empty() {
+            dest.to_path_buf()
+        } else {
+            dest.join(relative)
+        };
+
+        // walkdir already has the metadata on most platforms, avoid a second stat
+        let modified = entry.metadata().ok().and_then(|m| m.modified().ok());
+
+        files.push(StagedFile {
+            src: entry.path().to_path_buf(),
+            dest: dest_path,
+            modified,
+        });
+    }
+
+    let parents: BTreeSet<&Path> = files.iter().filter_map(|f| f.dest.parent()).collect();
+    for parent in parents {
+        fs::create_dir_all(parent)?;
+    }
+
+    let copied = files
+        .par_iter()
+        .map(|file| -> Result<usize, CustomError> {
+            if !is_same_copy(&file.src, &file.dest, file.modified) {
+                fs::copy(&file.src, &file.dest).map_err(|e| {
+                    CustomError::BuildError(t!("Failed to copy {}: {}", format!("{:?}", file.src), e))
+                })?;
+                return Ok(1);
+            }
+            Ok(0)
+        })
+        .try_reduce(|| 0, |a, b| Ok(a + b))?;
+
+    if src.is_dir() {
+        let staged: BTreeSet<String> = files
+            .iter()
+            .filter_map(|f| f.dest.strip_prefix(dest).ok())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .collect();
+        let record = staged_record(dest);
+        let previous: HashSet<String> = fs::read_to_string(&record)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        for stale in previous.iter().filter(|p| !staged.contains(*p)) {
+            let _ = fs::remove_file(dest.join(stale));
+        }
+
+        let json = serde_json::to_string_pretty(&staged).map_err(|e| {
+            CustomError::ValidationError(t!("Failed to serialize staged files: {}", e))
+        })?;
+        write_if_changed(&record, json)?;
+    }
+
+    Ok(copied)
+}
+
+/// Drops the list of files staged into `dest`, once `dest` itself is deleted.
+pub fn forget_staged(dest: &Path) {
+    let _ = fs::remove_file(staged_record(dest));
+}
+
+// where the files staged into `dest` are listed, relative to `dest`
+fn staged_record(dest: &Path) -> PathBuf {
+    let key = dest.to_string_lossy().replace('\\', "/");
+    let hash = to_hex(&Sha256::digest(key.trim_start_matches("./").as_bytes()));
+    Path::new(STAGED_RECORDS_DIR).join(format!("{}.json", &hash[..16]))
+}
+
+fn is_link(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+}
+
+fn remove_link(path: &Path) -> std::io::Result<()> {
+    if cfg!(windows) {
+        fs::remove_dir(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Removes `path` if it is a directory link left behind by [`link_dir`], so it can be copied into again.
+pub fn unlink_dir(path: &Path) -> Result<(), CustomError> {
+    if is_link(path) {
+        remove_link(path)?;
+    }
+    Ok(())
+}
+
+/// Points `dest` at `src` with a symlink (a junction on Windows), replacing any copied directory.
+pub fn link_dir(src: &Path, dest: &Path) -> Result<(), CustomError> {
+    let target = src.canonicalize()?;
+
+    if is_link(dest) {
+        if fs::read_link(dest).ok().and_then(|p| p.canonicalize().ok()) == Some(target.clone()) {
+            return Ok(());
+        }
+        remove_link(dest)?;
+    } else if dest.exists() {
+        fs::remove_dir_all(dest)?;
+    }
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&target, dest)?;
+
+    // junctions don't need the symlink privilege that developer mode grants
+    #[cfg(windows)]
+    {
+        let status = std::process::Command::new("cmd")
+            .args(["/C", "mklink", "/J"])
+            .arg(dest)
+            .arg(&target)
+            .stdout(std::process::Stdio::null())
+            .status()?;
+        if !status.success() {
+            return Err(CustomError::BuildError(t!("Failed to create junction {} -> {}", format!("{:?}", dest), format!("{:?}", target))));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,360 @@
+use crate::Ui;
+use crate::atlas_layout::Layout;
+use crate::build::web_preload_paths;
+use crate::error::CustomError;
+use crate::i18n::{t, tr};
+use crate::packer::atlas_pages;
+use crate::paths::ProjectPaths;
+use indicatif::HumanBytes;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const ATLAS_STATS_PATH: &str = ".bonsai/cache/atlas_stats.json";
+const FONT_ATLAS_DIR: &str = ".bonsai/cache/fonts";
+const ATLAS_DIR: &str = "bonsai/core/render/atlas";
+// fonts baked by [assets.fonts]
+const BAKED_FONT_DIR: &str = "bonsai/core/render/atlas/fonts";
+const BYTES_PER_PIXEL: u64 = 4; // textures are uploaded as RGBA8
+const LARGEST_COUNT: usize = 5;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SpriteStats {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct PageStats {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AtlasStats {
+    // the first page, all of them are in `pages`
+    pub width: u32,
+    pub height: u32,
+    // empty in stats written before atlases had pages
+    #[serde(default)]
+    pub pages: Vec<PageStats>,
+    pub sprites: Vec<SpriteStats>,
+    // fully transparent pixels around the sprites packed without [assets.atlas] trim
+    #[serde(default)]
+    pub border_pixels: u64,
+}
+
+#[derive(Serialize)]
+pub struct AtlasReport {
+    pub width: u32,
+    pub height: u32,
+    pub pages: Vec<PageStats>,
+    pub sprite_count: usize,
+    pub used_pixels: u64,
+    pub wasted_pixels: u64,
+    pub fill_percent: f64,
+    pub border_pixels: u64,
+    pub largest_sprites: Vec<SpriteStats>,
+}
+
+#[derive(Serialize)]
+pub struct TextureReport {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: u64,
+}
+
+#[derive(Serialize)]
+pub struct TargetReport {
+    pub target: String,
+    pub file_bytes: u64,
+    pub texture_memory_bytes: u64,
+}
+
+#[derive(Serialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(Serialize, Default)]
+pub struct FileGroupReport {
+    pub count: usize,
+    pub total_bytes: u64,
+    pub largest: Vec<FileEntry>,
+}
+
+#[derive(Serialize)]
+pub struct AssetReport {
+    pub atlas: Option<AtlasReport>,
+    pub textures: Vec<TextureReport>,
+    pub targets: Vec<TargetReport>,
+    pub audio: FileGroupReport,
+    pub fonts: FileGroupReport,
+}
+
+impl AtlasStats {
+    fn used_pixels(&self) -> u64 {
+        self.sprites
+            .iter()
+            .map(|s| s.width as u64 * s.height as u64)
+            .sum()
+    }
+
+    fn page_sizes(&self) -> Vec<PageStats> {
+        if self.pages.is_empty() {
+            vec![PageStats {
+                width: self.width,
+                height: self.height,
+            }]
+        } else {
+            self.pages.clone()
+        }
+    }
+
+    fn total_pixels(&self) -> u64 {
+        self.page_sizes()
+            .iter()
+            .map(|p| p.width as u64 * p.height as u64)
+            .sum()
+    }
+
+    fn fill_percent(&self) -> f64 {
+        let total = self.total_pixels();
+        if total == 0 {
+            return 0.0;
+        }
+        self.used_pixels() as f64 / total as f64 * 100.0
+    }
+}
+
+// written after every atlas pack so reports don't need to repack
+pub fn save_atlas_stats(
+    layout: &Layout,
+    border_pixels: u64,
+    ui: &Ui,
+) -> Result<(), CustomError> {
+    let sprites: Vec<SpriteStats> = layout
+        .frames()
+        .into_iter()
+        .map(|(name, (_, frame))| SpriteStats {
+            name: name.to_string(),
+            width: frame.w,
+            height: frame.h,
+        })
+        .collect();
+    let pages: Vec<PageStats> = layout
+        .pages
+        .iter()
+        .map(|page| PageStats {
+            width: page.width,
+            height: page.height,
+        })
+        .collect();
+
+    let first = pages.first().copied().unwrap_or(PageStats { width: 0, height: 0 });
+    let stats = AtlasStats {
+        width: first.width,
+        height: first.height,
+        pages,
+        sprites,
+        border_pixels,
+    };
+
+    ui.log(&t!(
+        "Atlas {}: {} sprites, {}% filled",
+        page_list(&stats.page_sizes()),
+        stats.sprites.len(),
+        format!("{:.1}", stats.fill_percent())
+    ));
+
+    let stats_path = Path::new(ATLAS_STATS_PATH);
+    if let Some(parent) = stats_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&stats)
+        .map_err(|e| CustomError::BuildError(t!("Failed to serialize atlas stats: {}", e)))?;
+    fs::write(stats_path, json)?;
+
+    Ok(())
+}
+
+// "2048x2048", or "2048x2048 + 512x512" for an atlas with more pages
+fn page_list(pages: &[PageStats]) -> String {
+    pages
+        .iter()
+        .map(|p| format!("{}x{}", p.width, p.height))
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+pub fn load_atlas_stats() -> Option<AtlasStats> {
+    let content = fs::read_to_string(ATLAS_STATS_PATH).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn collect_files(path: &Path) -> Vec<FileEntry> {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| FileEntry {
+            path: e.path().to_string_lossy().replace('\\', "/"),
+            bytes: e.metadata().map(|m| m.len()).unwrap_or(0),
+        })
+        .collect()
+}
+
+fn summarize_files(path: &Path) -> FileGroupReport {
+    let mut files = collect_files(path);
+    files.sort_by_key(|f| std::cmp::Reverse(f.bytes));
+
+    FileGroupReport {
+        count: files.len(),
+        total_bytes: files.iter().map(|f| f.bytes).sum(),
+        largest: files.into_iter().take(LARGEST_COUNT).collect(),
+    }
+}
+
+fn texture_report(path: &Path) -> Option<TextureReport> {
+    let (width, height) = image::image_dimensions(path).ok()?;
+
+    Some(TextureReport {
+        path: path.to_string_lossy().replace('\\', "/"),
+        width,
+        height,
+        bytes: width as u64 * height as u64 * BYTES_PER_PIXEL,
+    })
+}
+
+pub fn collect_report(paths: &ProjectPaths) -> AssetReport {
+    let atlas = load_atlas_stats().map(|stats| {
+        let used_pixels = stats.used_pixels();
+        let total_pixels = stats.total_pixels();
+        let fill_percent = stats.fill_percent();
+
+        let mut largest = stats.sprites.clone();
+        largest.sort_by_key(|s| std::cmp::Reverse(s.width as u64 * s.height as u64));
+        largest.truncate(LARGEST_COUNT);
+
+        AtlasReport {
+            width: stats.width,
+            height: stats.height,
+            pages: stats.page_sizes(),
+            sprite_count: stats.sprites.len(),
+            used_pixels,
+            wasted_pixels: total_pixels.saturating_sub(used_pixels),
+            fill_percent,
+            border_pixels: stats.border_pixels,
+            largest_sprites: largest,
+        }
+    });
+
+    let mut texture_paths = atlas_pages(Path::new(ATLAS_DIR));
+    texture_paths.extend(
+        collect_files(Path::new(FONT_ATLAS_DIR))
+            .into_iter()
+            .chain(collect_files(Path::new(BAKED_FONT_DIR)))
+            .map(|f| PathBuf::from(f.path))
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("png")),
+    );
+    let textures: Vec<TextureReport> = texture_paths
+        .iter()
+        .filter_map(|p| texture_report(p))
+        .collect();
+    let texture_memory: u64 = textures.iter().map(|t| t.bytes).sum();
+
+    let desktop_bytes = collect_files(&paths.assets)
+        .iter()
+        .map(|f| f.bytes)
+        .sum();
+    let web_bytes = web_preload_paths(paths)
+        .iter()
+        .flat_map(|(src, _)| collect_files(src))
+        .map(|f| f.bytes)
+        .sum();
+
+    AssetReport {
+        atlas,
+        textures,
+        targets: vec![
+            TargetReport {
+                target: "desktop".to_string(),
+                file_bytes: desktop_bytes,
+                texture_memory_bytes: texture_memory,
+            },
+            TargetReport {
+                target: "web".to_string(),
+                file_bytes: web_bytes,
+                texture_memory_bytes: texture_memory,
+            },
+        ],
+        audio: summarize_files(&paths.audio()),
+        fonts: summarize_files(&paths.fonts()),
+    }
+}
+
+pub fn print_report(report: &AssetReport) {
+    match &report.atlas {
+        Some(atlas) => {
+            println!("{}", t!("Atlas: {}, {} sprites", page_list(&atlas.pages), atlas.sprite_count));
+            println!(
+                "{}",
+                t!(
+                    "  Fill: {}% ({} px used, {} px wasted)",
+                    format!("{:.1}", atlas.fill_percent),
+                    atlas.used_pixels,
+                    atlas.wasted_pixels
+                )
+            );
+            if atlas.border_pixels > 0 {
+                println!(
+                    "{}",
+                    t!(
+                        "  Transparent borders: {} px of untrimmed sprites, [assets.atlas] trim = true cuts them",
+                        atlas.border_pixels
+                    )
+                );
+            }
+            println!("{}", tr("  Largest sprites:"));
+            for sprite in &atlas.largest_sprites {
+                println!("    {} ({}x{})", sprite.name, sprite.width, sprite.height);
+            }
+        }
+        None => println!("{}", tr("Atlas: not packed yet (run a build first)")),
+    }
+
+    println!("{}", tr("Textures:"));
+    for texture in &report.textures {
+        println!(
+            "  {} ({}x{}, {})",
+            texture.path,
+            texture.width,
+            texture.height,
+            HumanBytes(texture.bytes)
+        );
+    }
+
+    println!("{}", tr("Targets:"));
+    for target in &report.targets {
+        println!(
+            "{}",
+            t!(
+                "  {}: {} of files, {} of texture memory",
+                target.target,
+                HumanBytes(target.file_bytes),
+                HumanBytes(target.texture_memory_bytes)
+            )
+        );
+    }
+
+    for (label, group) in [(tr("Audio"), &report.audio), (tr("Fonts"), &report.fonts)] {
+        println!("{}", t!("{}: {} files, {}", label, group.count, HumanBytes(group.total_bytes)));
+        for file in &group.largest {
+            println!("  {} ({})", file.path, HumanBytes(file.bytes));
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use crate::emsdk::{emscripten_tool, find_emsdk};
+use crate::error::CustomError;
+use crate::i18n::language;
+use crate::version::read_version_info;
+use chrono::Local;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+#[derive(Serialize)]
+pub struct SystemInfo {
+    pub bonsai: String,
+    pub os: String,
+    pub os_version: Option<String>,
+    pub arch: String,
+    // of bonsai's own messages, the report itself stays in English for bug reports
+    pub language: String,
+    pub cpu: Option<String>,
+    pub cpu_threads: usize,
+    pub memory_mb: Option<u64>,
+    pub gpus: Vec<String>,
+    pub backends: Vec<String>,
+    pub toolchain: BTreeMap<String, Option<String>>,
+}
+
+// first non-empty line of a command's output, None when it can't be run
+fn command_line(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    stdout
+        .lines()
+        .chain(stderr.lines())
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(str::to_string)
+}
+
+fn command_lines(program: &str, args: &[&str]) -> Vec<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn os_version() -> Option<String> {
+    match env::consts::OS {
+        "windows" => command_line("cmd", &["/C", "ver"]),
+        "macos" => command_line("sw_vers", &["-productVersion"]).map(|v| format!("macOS {}", v)),
+        _ => fs::read_to_string("/etc/os-release")
+            .ok()
+            .and_then(|release| {
+                release
+                    .lines()
+                    .find_map(|l| l.strip_prefix("PRETTY_NAME="))
+                    .map(|name| name.trim_matches('"').to_string())
+            })
+            .or_else(|| command_line("uname", &["-sr"])),
+    }
+}
+
+fn cpu_name() -> Option<String> {
+    match env::consts::OS {
+        "windows" => env::var("PROCESSOR_IDENTIFIER").ok(),
+        "macos" => command_line("sysctl", &["-n", "machdep.cpu.brand_string"]),
+        _ => fs::read_to_string("/proc/cpuinfo").ok().and_then(|info| {
+            info.lines()
+                .find(|l| l.starts_with("model name"))
+                .and_then(|l| l.split_once(':'))
+                .map(|(_, name)| name.trim().to_string())
+        }),
+    }
+}
+
+fn memory_mb() -> Option<u64> {
+    match env::consts::OS {
+        "macos" => command_line("sysctl", &["-n", "hw.memsize"])?
+            .parse::<u64>()
+            .ok()
+            .map(|bytes| bytes / 1024 / 1024),
+        "windows" => None,
+        _ => fs::read_to_string("/proc/meminfo").ok().and_then(|info| {
+            info.lines()
+                .find_map(|l| l.strip_prefix("MemTotal:"))
+                .and_then(|kb| kb.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+                .map(|kb| kb / 1024)
+        }),
+    }
+}
+
+fn gpus() -> Vec<String> {
+    match env::consts::OS {
+        "windows" => command_lines(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "Get-CimInstance Win32_VideoController | ForEach-Object { \"$($_.Name) (driver $($_.DriverVersion))\" }",
+            ],
+        ),
+        "macos" => command_lines("system_profiler", &["SPDisplaysDataType"])
+            .into_iter()
+            .filter_map(|l| l.strip_prefix("Chipset Model:").map(|m| m.trim().to_string()))
+            .collect(),
+        _ => {
+            let renderer = command_lines("glxinfo", &["-B"])
+                .into_iter()
+                .filter(|l| {
+                    l.starts_with("OpenGL renderer string") || l.starts_with("OpenGL version string")
+                })
+                .collect::<Vec<_>>();
+            if !renderer.is_empty() {
+                return renderer;
+            }
+            command_lines("lspci", &[])
+                .into_iter()
+                .filter(|l| l.contains("VGA") || l.contains("3D controller"))
+                .collect()
+        }
+    }
+}
+
+// graphics backends the sokol libraries are compiled for on this platform
+fn backends() -> Vec<String> {
+    let native: &[&str] = match env::consts::OS {
+        "windows" => &["d3d11", "gl"],
+        "macos" => &["metal", "gl"],
+        _ => &["gl"],
+    };
+    native
+        .iter()
+        .chain(["webgl2 (web)"].iter())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// `--version` lines of the compilers builds use, None for the ones that aren't installed.
+pub fn toolchain() -> BTreeMap<String, Option<String>> {
+    let mut tools = BTreeMap::new();
+    tools.insert("odin".to_string(), command_line("odin", &["version"]));
+    tools.insert("git".to_string(), command_line("git", &["--version"]));
+
+    let emcc = command_line("emcc", &["--version"]).or_else(|| {
+        find_emsdk().and_then(|emsdk| {
+            let tool = emscripten_tool(&emsdk, "emcc");
+            command_line(&tool.to_string_lossy(), &["--version"])
+        })
+    });
+    tools.insert("emcc".to_string(), emcc);
+
+    let c_compiler = match env::consts::OS {
+        "windows" => ("cl", command_line("cl", &[])),
+        "macos" => ("clang", command_line("clang", &["--version"])),
+        _ => ("cc", command_line("cc", &["--version"])),
+    };
+    tools.insert(c_compiler.0.to_string(), c_compiler.1);
+    tools
+}
+
+pub fn collect_system_info() -> SystemInfo {
+    SystemInfo {
+        bonsai: env!("CARGO_PKG_VERSION").to_string(),
+        os: env::consts::OS.to_string(),
+        os_version: os_version(),
+        arch: env::consts::ARCH.to_string(),
+        language: language().code().to_string(),
+        cpu: cpu_name(),
+        cpu_threads: std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        memory_mb: memory_mb(),
+        gpus: gpus(),
+        backends: backends(),
+        toolchain: toolchain(),
+    }
+}
+
+pub fn render_system_info(info: &SystemInfo) -> String {
+    let unknown = || "unknown".to_string();
+    let mut out = String::new();
+
+    let _ = writeln!(out, "bonsai:     {}", info.bonsai);
+    let _ = writeln!(
+        out,
+        "OS:         {} ({}, {})",
+        info.os_version.clone().unwrap_or_else(unknown),
+        info.os,
+        info.arch
+    );
+    let _ = writeln!(out, "Language:   {}", info.language);
+    let _ = writeln!(
+        out,
+        "CPU:        {} ({} threads)",
+        info.cpu.clone().unwrap_or_else(unknown),
+        info.cpu_threads
+    );
+    if let Some(memory) = info.memory_mb {
+        let _ = writeln!(out, "Memory:     {} MB", memory);
+    }
+    if info.gpus.is_empty() {
+        let _ = writeln!(out, "GPU:        unknown");
+    }
+    for gpu in &info.gpus {
+        let _ = writeln!(out, "GPU:        {}", gpu);
+    }
+    let _ = writeln!(out, "Backends:   {}", info.backends.join(", "));
+    for (tool, version) in &info.toolchain {
+        let _ = writeln!(
+            out,
+            "{:<11} {}",
+            format!("{}:", tool),
+            version.clone().unwrap_or_else(|| "not found".to_string())
+        );
+    }
+    out
+}
+
+pub const CRASH_LOG_DIR: &str = ".bonsai/logs";
+
+// writes a crash report with the environment at the top so playtester logs
+// can be attached to bug reports as-is
+pub fn write_crash_log(executable: &Path, status: &ExitStatus) -> Result<PathBuf, CustomError> {
+    let log_dir = Path::new(CRASH_LOG_DIR);
+    fs::create_dir_all(log_dir)?;
+
+    let timestamp = Local::now();
+    let path = log_dir.join(format!("crash-{}.log", timestamp.format("%Y%m%d-%H%M%S")));
+
+    let mut report = render_system_info(&collect_system_info());
+    let _ = writeln!(report);
+    let _ = writeln!(report, "Time:       {}", timestamp.to_rfc3339());
+    let _ = writeln!(report, "Executable: {}", executable.display());
+    if let Some(versions) = read_version_info(executable) {
+        let _ = writeln!(
+            report,
+            "Build:      engine {}, cli {}, project {}",
+            versions.engine, versions.cli, versions.project
+        );
+    }
+    let _ = writeln!(report, "Exit:       {}", status);
+
+    fs::write(&path, report)?;
+    Ok(path)
+}
@@ -0,0 +1,194 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::imports::package_name;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const SYSTEMS_DIR: &str = "bonsai/systems";
+const SYSTEM_MANIFEST: &str = "system.toml";
+
+// odin's -define takes a boolean, integer or string constant
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum DefineValue {
+    Bool(bool),
+    Int(i64),
+    String(String),
+}
+
+impl DefineValue {
+    fn to_odin(&self) -> String {
+        match self {
+            DefineValue::Bool(b) => b.to_string(),
+            DefineValue::Int(i) => i.to_string(),
+            DefineValue::String(s) => format!("{:?}", s),
+        }
+    }
+}
+
+/// The `[build]` section of a system's `system.toml`.
+#[derive(Debug, Deserialize, Default)]
+pub struct SystemBuildOptions {
+    #[serde(default)]
+    pub defines: BTreeMap<String, DefineValue>,
+    pub vet: Option<bool>,
+    pub strict_style: Option<bool>,
+    // glob patterns relative to the system directory, e.g. "examples/**"
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct SystemManifest {
+    #[serde(default)]
+    build: SystemBuildOptions,
+}
+
+pub struct SystemConfig {
+    pub name: String,
+    pub dir: PathBuf,
+    pub build: SystemBuildOptions,
+    ignore: GlobSet,
+    // set when system.toml could not be read, the system then builds with the project's rules
+    pub error: Option<String>,
+}
+
+impl SystemConfig {
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        path.strip_prefix(&self.dir)
+            .is_ok_and(|relative| self.ignore.is_match(relative))
+    }
+}
+
+fn load_system(name: String, dir: PathBuf) -> SystemConfig {
+    let parsed = fs::read_to_string(dir.join(SYSTEM_MANIFEST))
+        .ok()
+        .map(|content| toml_edit::de::from_str::<SystemManifest>(&content))
+        .transpose()
+        .map_err(|e| e.to_string())
+        .and_then(|manifest| {
+            let manifest = manifest.unwrap_or_default();
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &manifest.build.ignore {
+                builder.add(
+                    Glob::new(pattern)
+                        .map_err(|e| format!("invalid ignore pattern '{}': {}", pattern, e))?,
+                );
+            }
+            let ignore = builder.build().map_err(|e| e.to_string())?;
+            Ok((manifest.build, ignore))
+        });
+
+    match parsed {
+        Ok((build, ignore)) => SystemConfig {
+            name,
+            dir,
+            build,
+            ignore,
+            error: None,
+        },
+        Err(error) => SystemConfig {
+            name,
+            dir,
+            build: SystemBuildOptions::default(),
+            ignore: GlobSet::empty(),
+            error: Some(error),
+        },
+    }
+}
+
+/// Reads the `[build]` section of every installed system's `system.toml`.
+pub fn load_system_configs() -> Vec<SystemConfig> {
+    let Ok(entries) = fs::read_dir(SYSTEMS_DIR) else {
+        return Vec::new();
+    };
+    let mut systems: Vec<SystemConfig> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| load_system(e.file_name().to_string_lossy().to_string(), e.path()))
+        .collect();
+    systems.sort_by(|a, b| a.name.cmp(&b.name));
+    systems
+}
+
+/// Odin flags for the project build, merged from every system's `[build]` section.
+/// `vetted_roots` are the directories whose packages are vetted unless a system opts out.
+pub fn system_odin_flags(
+    systems: &[SystemConfig],
+    vetted_roots: &[PathBuf],
+) -> Result<SystemOdinFlags, CustomError> {
+    let mut defines: BTreeMap<&str, (&DefineValue, &str)> = BTreeMap::new();
+    for system in systems {
+        for (name, value) in &system.build.defines {
+            match defines.get(name.as_str()) {
+                Some((existing, owner)) if *existing != value => {
+                    return Err(CustomError::ValidationError(t!(
+                        "Systems '{}' and '{}' set different values for define {}",
+                        owner, system.name, name
+                    )));
+                }
+                _ => {
+                    defines.insert(name, (value, &system.name));
+                }
+            }
+        }
+    }
+
+    let unvetted: Vec<&SystemConfig> = systems
+        .iter()
+        .filter(|s| s.build.vet == Some(false))
+        .collect();
+
+    // odin vets packages by name, so list every package except the opted-out systems
+    let vet_packages = if unvetted.is_empty() {
+        None
+    } else {
+        let mut names = BTreeSet::new();
+        for root in vetted_roots {
+            for entry in WalkDir::new(root)
+                .follow_links(true)
+                .into_iter()
+                .filter_map(|e| e.ok())
+            {
+                let path = entry.path();
+                if !entry.file_type().is_file()
+                    || path.extension().is_none_or(|ext| ext != "odin")
+                    || unvetted.iter().any(|s| path.starts_with(&s.dir))
+                {
+                    continue;
+                }
+                if let Some(name) = package_name(path) {
+                    names.insert(name);
+                }
+            }
+        }
+        Some(names.into_iter().collect::<Vec<_>>().join(","))
+    };
+
+    Ok(SystemOdinFlags {
+        defines: defines
+            .iter()
+            .map(|(name, (value, _))| format!("-define:{}={}", name, value.to_odin()))
+            .collect(),
+        vet_packages,
+        unvetted: unvetted.iter().map(|s| s.name.clone()).collect(),
+        loose_style: systems
+            .iter()
+            .filter(|s| s.build.strict_style == Some(false))
+            .map(|s| s.name.clone())
+            .collect(),
+    })
+}
+
+pub struct SystemOdinFlags {
+    pub defines: Vec<String>,
+    // `-vet-packages` list, None when every package is vetted
+    pub vet_packages: Option<String>,
+    pub unvetted: Vec<String>,
+    // odin can't scope -strict-style to a package, any of these drops it for the whole build
+    pub loose_style: Vec<String>,
+}
@@ -0,0 +1,745 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::xml::{self, Element};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const MAPS_DIR_NAME: &str = "maps";
+const TILED_EXTENSION: &str = "tmx";
+const LDTK_EXTENSION: &str = "ldtk";
+
+// the top bits of a Tiled gid flip the tile, the hexagonal 120 degree rotation is ignored
+const TILED_FLIP_X: u32 = 0x8000_0000;
+const TILED_FLIP_Y: u32 = 0x4000_0000;
+const TILED_FLIP_DIAGONAL: u32 = 0x2000_0000;
+const TILED_GID_MASK: u32 = 0x0fff_ffff;
+
+/// Whether `path` is a Tiled or LDtk map the map stage generates code for.
+pub fn is_map(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext == TILED_EXTENSION || ext == LDTK_EXTENSION)
+}
+
+/// A tileset image and the grid a map cuts it along. The atlas packs it as `<stem>_<index>`
+/// tiles, numbered row by row like Tiled and LDtk number them.
+#[derive(Clone, PartialEq, Serialize)]
+pub struct MapTileset {
+    pub image: PathBuf,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    // pixels around the whole grid, and between neighbouring tiles
+    pub margin: u32,
+    pub spacing: u32,
+}
+
+impl MapTileset {
+    /// The sprite key of tile `index`.
+    pub fn tile_key(&self, index: u32) -> String {
+        let stem = self
+            .image
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        format!("{}_{}", stem, index)
+    }
+}
+
+/// One Tiled map or LDtk level.
+pub struct TileMap {
+    pub name: String,
+    // in pixels
+    pub width: f32,
+    pub height: f32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    // in draw order, the bottom layer first
+    pub layers: Vec<MapLayer>,
+    pub tilesets: Vec<MapTileset>,
+}
+
+pub struct MapLayer {
+    pub name: String,
+    pub visible: bool,
+    // in pixels
+    pub offset: (f32, f32),
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub content: LayerContent,
+}
+
+pub enum LayerContent {
+    Tiles(Vec<MapTile>),
+    Objects(Vec<MapObject>),
+}
+
+/// A placed tile, `x` and `y` count cells from the layer's top left corner.
+pub struct MapTile {
+    pub sprite: String,
+    pub x: i32,
+    pub y: i32,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub flip_diagonal: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ObjectShape {
+    Rectangle,
+    Ellipse,
+    Point,
+    Polygon,
+    Polyline,
+}
+
+impl ObjectShape {
+    pub fn odin_name(self) -> &'static str {
+        match self {
+            ObjectShape::Rectangle => "rectangle",
+            ObjectShape::Ellipse => "ellipse",
+            ObjectShape::Point => "point",
+            ObjectShape::Polygon => "polygon",
+            ObjectShape::Polyline => "polyline",
+        }
+    }
+}
+
+/// A Tiled object or LDtk entity. The position is its top left corner in pixels.
+pub struct MapObject {
+    pub name: String,
+    // the Tiled class (type before Tiled 1.9) or the LDtk entity identifier
+    pub class: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    // degrees clockwise
+    pub rotation: f32,
+    pub sprite: Option<String>,
+    pub shape: ObjectShape,
+    // polygon and polyline points, relative to the position
+    pub points: Vec<(f32, f32)>,
+    // custom properties or LDtk fields, every value written as text
+    pub properties: Vec<(String, String)>,
+}
+
+// Tiled and LDtk name files relative to the file naming them
+fn resolve(base: &Path, relative: &str) -> PathBuf {
+    let mut path = base.parent().unwrap_or(Path::new("")).to_path_buf();
+    for component in Path::new(&relative.replace('\\', "/")).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(path.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                path.pop();
+            }
+            other => path.push(other),
+        }
+    }
+    path
+}
+
+fn map_files(maps_dir: &Path) -> Result<Vec<PathBuf>, CustomError> {
+    let mut files = Vec::new();
+    if !maps_dir.exists() {
+        return Ok(files);
+    }
+    for entry in WalkDir::new(maps_dir) {
+        let entry = entry.map_err(|e| CustomError::IoError(e.into()))?;
+        if entry.file_type().is_file() && is_map(entry.path()) {
+            files.push(entry.into_path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Every map in `maps_dir`: a Tiled `.tmx` file is one map named after the file, every level
+/// of an LDtk project is one named `<file>_<level>`.
+pub fn load_maps(maps_dir: &Path) -> Result<Vec<TileMap>, CustomError> {
+    let mut maps = Vec::new();
+    for path in map_files(maps_dir)? {
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        if path.extension().is_some_and(|ext| ext == TILED_EXTENSION) {
+            maps.push(load_tiled(&path, stem)?);
+        } else {
+            maps.extend(load_ldtk(&path, stem)?);
+        }
+    }
+    Ok(maps)
+}
+
+/// The tileset images of every map, each with the one grid all maps cut it along.
+pub fn map_tilesets(maps_dir: &Path) -> Result<Vec<MapTileset>, CustomError> {
+    let mut tilesets: Vec<MapTileset> = Vec::new();
+    for map in load_maps(maps_dir)? {
+        for tileset in map.tilesets {
+            match tilesets.iter().find(|t| t.image == tileset.image) {
+                Some(known) if *known != tileset => {
+                    return Err(CustomError::ValidationError(t!(
+                        "Maps cut the tileset image {} along different grids",
+                        tileset.image.display()
+                    )));
+                }
+                Some(_) => {}
+                None => tilesets.push(tileset),
+            }
+        }
+    }
+    Ok(tilesets)
+}
+
+fn invalid_map(path: &Path, error: impl std::fmt::Display) -> CustomError {
+    CustomError::BuildError(t!("Invalid map {}: {}", path.display(), error))
+}
+
+// a file a map refers to, missing ones are named together with the map
+fn read_referenced(file: &Path, map_path: &Path) -> Result<String, CustomError> {
+    fs::read_to_string(file).map_err(|e| {
+        CustomError::BuildError(t!("Failed to read {} used by {}: {}", file.display(), map_path.display(), e))
+    })
+}
+
+fn read_xml(path: &Path, map_path: &Path) -> Result<Element, CustomError> {
+    let text = read_referenced(path, map_path)?;
+    xml::parse(&text).map_err(|e| invalid_map(path, e))
+}
+
+fn number<T: std::str::FromStr>(element: &Element, name: &str, path: &Path) -> Result<Option<T>, CustomError> {
+    element
+        .attribute(name)
+        .map(|value| {
+            value.trim().parse().map_err(|_| {
+                invalid_map(path, format!("'{}' of <{}> is not a number: {}", name, element.name, value))
+            })
+        })
+        .transpose()
+}
+
+// a tileset with its first gid, None for a collection of images the atlas can't cut
+struct TiledTileset {
+    first_gid: u32,
+    grid: Option<MapTileset>,
+}
+
+fn tiled_tileset(element: &Element, file: &Path, map_path: &Path) -> Result<Option<MapTileset>, CustomError> {
+    let Some(image) = element.child("image").and_then(|image| image.attribute("source")) else {
+        return Ok(None);
+    };
+    let size = |name: &str| -> Result<u32, CustomError> {
+        number(element, name, map_path)?.ok_or_else(|| {
+            invalid_map(map_path, format!("<tileset> in {} has no {}", file.display(), name))
+        })
+    };
+    Ok(Some(MapTileset {
+        image: resolve(file, image),
+        tile_width: size("tilewidth")?,
+        tile_height: size("tileheight")?,
+        margin: number(element, "margin", map_path)?.unwrap_or(0),
+        spacing: number(element, "spacing", map_path)?.unwrap_or(0),
+    }))
+}
+
+struct TiledContext<'a> {
+    path: &'a Path,
+    tilesets: Vec<TiledTileset>,
+    tile_width: u32,
+    tile_height: u32,
+}
+
+impl TiledContext<'_> {
+    // the sprite of a gid without its flip bits, None for an empty cell
+    fn sprite(&self, gid: u32) -> Result<Option<String>, CustomError> {
+        let gid = gid & TILED_GID_MASK;
+        if gid == 0 {
+            return Ok(None);
+        }
+        let Some(tileset) = self.tilesets.iter().rev().find(|t| t.first_gid <= gid) else {
+            return Err(invalid_map(self.path, format!("tile {} belongs to no tileset", gid)));
+        };
+        match &tileset.grid {
+            Some(grid) => Ok(Some(grid.tile_key(gid - tileset.first_gid))),
+            None => Err(CustomError::BuildError(t!(
+                "Map {} uses a tileset made of separate images. Use a tileset based on one image",
+                self.path.display()
+            ))),
+        }
+    }
+
+    // `content` is the <data> element or one of its chunks
+    fn tiles(&self, data: &Element, content: &Element, width: u32, origin: (i32, i32), tiles: &mut Vec<MapTile>) -> Result<(), CustomError> {
+        let gids = tiled_gids(data, content, self.path)?;
+        let width = width.max(1);
+        for (index, gid) in gids.into_iter().enumerate() {
+            if let Some(sprite) = self.sprite(gid)? {
+                let index = index as u32;
+                tiles.push(MapTile {
+                    sprite,
+                    x: origin.0 + (index % width) as i32,
+                    y: origin.1 + (index / width) as i32,
+                    flip_x: gid & TILED_FLIP_X != 0,
+                    flip_y: gid & TILED_FLIP_Y != 0,
+                    flip_diagonal: gid & TILED_FLIP_DIAGONAL != 0,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // layers inside groups are flattened to `<group>/<layer>`, with the group's offset added
+    fn layers(&self, parent: &Element, prefix: &str, offset: (f32, f32), visible: bool, layers: &mut Vec<MapLayer>) -> Result<(), CustomError> {
+        for element in &parent.children {
+            let name = format!("{}{}", prefix, element.attribute("name").unwrap_or_default());
+            let offset = (
+                offset.0 + number(element, "offsetx", self.path)?.unwrap_or(0.0),
+                offset.1 + number(element, "offsety", self.path)?.unwrap_or(0.0),
+            );
+            let visible = visible && element.attribute("visible") != Some("0");
+
+            let content = match element.name.as_str() {
+                "group" => {
+                    self.layers(element, &format!("{}/", name), offset, visible, layers)?;
+                    continue;
+                }
+                "layer" => {
+                    let mut tiles = Vec::new();
+                    if let Some(data) = element.child("data") {
+                        if data.child("chunk").is_some() {
+                            // infinite maps store their tiles in chunks
+                            for chunk in data.children_named("chunk") {
+                                let origin = (
+                                    number(chunk, "x", self.path)?.unwrap_or(0),
+                                    number(chunk, "y", self.path)?.unwrap_or(0),
+                                );
+                                let width = number(chunk, "width", self.path)?.unwrap_or(0);
+                                self.tiles(data, chunk, width, origin, &mut tiles)?;
+                            }
+                        } else {
+                            let width = number(element, "width", self.path)?.unwrap_or(0);
+                            self.tiles(data, data, width, (0, 0), &mut tiles)?;
+                        }
+                    }
+                    LayerContent::Tiles(tiles)
+                }
+                "objectgroup" => {
+                    let mut objects = Vec::new();
+                    for object in element.children_named("object") {
+                        objects.push(self.object(object)?);
+                    }
+                    LayerContent::Objects(objects)
+                }
+                // image layers aren't tiles, games draw those pictures themselves
+                _ => continue,
+            };
+            layers.push(MapLayer {
+                name,
+                visible,
+                offset,
+                tile_width: self.tile_width,
+                tile_height: self.tile_height,
+                content,
+            });
+        }
+        Ok(())
+    }
+
+    fn object(&self, object: &Element) -> Result<MapObject, CustomError> {
+        let x = number(object, "x", self.path)?.unwrap_or(0.0);
+        let mut y = number(object, "y", self.path)?.unwrap_or(0.0);
+        let width = number(object, "width", self.path)?.unwrap_or(0.0);
+        let height = number(object, "height", self.path)?.unwrap_or(0.0);
+        let sprite = match number::<u32>(object, "gid", self.path)? {
+            Some(gid) => {
+                // tile objects are placed by their bottom left corner
+                y -= height;
+                self.sprite(gid)?
+            }
+            None => None,
+        };
+
+        let mut shape = ObjectShape::Rectangle;
+        let mut points = Vec::new();
+        if object.child("ellipse").is_some() {
+            shape = ObjectShape::Ellipse;
+        } else if object.child("point").is_some() {
+            shape = ObjectShape::Point;
+        } else if let Some((kind, element)) = object
+            .child("polygon")
+            .map(|e| (ObjectShape::Polygon, e))
+            .or_else(|| object.child("polyline").map(|e| (ObjectShape::Polyline, e)))
+        {
+            shape = kind;
+            for point in element.attribute("points").unwrap_or_default().split_whitespace() {
+                let parsed = point
+                    .split_once(',')
+                    .and_then(|(px, py)| Some((px.parse().ok()?, py.parse().ok()?)));
+                match parsed {
+                    Some(point) => points.push(point),
+                    None => return Err(invalid_map(self.path, format!("invalid point '{}'", point))),
+                }
+            }
+        }
+
+        let properties = object
+            .child("properties")
+            .map(|properties| {
+                properties
+                    .children_named("property")
+                    .map(|property| {
+                        let value = property
+                            .attribute("value")
+                            .map(str::to_string)
+                            .unwrap_or_else(|| property.text.clone());
+                        (property.attribute("name").unwrap_or_default().to_string(), value)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(MapObject {
+            name: object.attribute("name").unwrap_or_default().to_string(),
+            class: object
+                .attribute("class")
+                .or_else(|| object.attribute("type"))
+                .unwrap_or_default()
+                .to_string(),
+            x,
+            y,
+            width,
+            height,
+            rotation: number(object, "rotation", self.path)?.unwrap_or(0.0),
+            sprite,
+            shape,
+            points,
+            properties,
+        })
+    }
+}
+
+// the raw gids in `content`, written in any encoding but zstd compression the <data> element names
+fn tiled_gids(data: &Element, content: &Element, path: &Path) -> Result<Vec<u32>, CustomError> {
+    match data.attribute("encoding") {
+        None => content
+            .children_named("tile")
+            .map(|tile| Ok(number(tile, "gid", path)?.unwrap_or(0)))
+            .collect(),
+        Some("csv") => content
+            .text
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| invalid_map(path, format!("invalid tile '{}'", value)))
+            })
+            .collect(),
+        Some("base64") => {
+            let compressed: String = content.text.split_whitespace().collect();
+            let compressed = BASE64
+                .decode(compressed)
+                .map_err(|e| invalid_map(path, e))?;
+            let mut bytes = Vec::new();
+            match data.attribute("compression") {
+                None => bytes = compressed,
+                Some("zlib") => {
+                    ZlibDecoder::new(compressed.as_slice())
+                        .read_to_end(&mut bytes)
+                        .map_err(|e| invalid_map(path, e))?;
+                }
+                Some("gzip") => {
+                    GzDecoder::new(compressed.as_slice())
+                        .read_to_end(&mut bytes)
+                        .map_err(|e| invalid_map(path, e))?;
+                }
+                Some(other) => {
+                    return Err(CustomError::BuildError(t!(
+                        "Map {} uses {} compression. Save it with zlib, gzip or no compression",
+                        path.display(),
+                        other
+                    )));
+                }
+            }
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect())
+        }
+        Some(other) => Err(invalid_map(path, format!("unknown encoding '{}'", other))),
+    }
+}
+
+fn load_tiled(path: &Path, name: &str) -> Result<TileMap, CustomError> {
+    let root = read_xml(path, path)?;
+    if root.name != "map" {
+        return Err(invalid_map(path, "the root element isn't <map>"));
+    }
+
+    let mut tilesets = Vec::new();
+    for element in root.children_named("tileset") {
+        let first_gid = number(element, "firstgid", path)?.unwrap_or(1);
+        let grid = match element.attribute("source") {
+            Some(source) => {
+                let file = resolve(path, source);
+                let external = read_xml(&file, path)?;
+                tiled_tileset(&external, &file, path)?
+            }
+            None => tiled_tileset(element, path, path)?,
+        };
+        tilesets.push(TiledTileset { first_gid, grid });
+    }
+    tilesets.sort_by_key(|t| t.first_gid);
+
+    let tile_width = number(&root, "tilewidth", path)?.unwrap_or(0);
+    let tile_height = number(&root, "tileheight", path)?.unwrap_or(0);
+    let ctx = TiledContext {
+        path,
+        tilesets,
+        tile_width,
+        tile_height,
+    };
+    let mut layers = Vec::new();
+    ctx.layers(&root, "", (0.0, 0.0), true, &mut layers)?;
+
+    let width: u32 = number(&root, "width", path)?.unwrap_or(0);
+    let height: u32 = number(&root, "height", path)?.unwrap_or(0);
+    Ok(TileMap {
+        name: name.to_string(),
+        width: (width * tile_width) as f32,
+        height: (height * tile_height) as f32,
+        tile_width,
+        tile_height,
+        layers,
+        tilesets: ctx.tilesets.into_iter().filter_map(|t| t.grid).collect(),
+    })
+}
+
+// the parts of an LDtk project the map stage reads
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkProject {
+    default_grid_size: Option<u32>,
+    defs: LdtkDefs,
+    levels: Vec<LdtkLevel>,
+}
+
+#[derive(Deserialize)]
+struct LdtkDefs {
+    tilesets: Vec<LdtkTileset>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkTileset {
+    uid: i64,
+    // None for the tilesets LDtk embeds itself
+    rel_path: Option<String>,
+    tile_grid_size: u32,
+    #[serde(default)]
+    spacing: u32,
+    #[serde(default)]
+    padding: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkLevel {
+    identifier: String,
+    px_wid: f32,
+    px_hei: f32,
+    // None when the level is saved in a file of its own
+    layer_instances: Option<Vec<LdtkLayer>>,
+    external_rel_path: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkLayer {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__type")]
+    kind: String,
+    #[serde(rename = "__gridSize")]
+    grid_size: u32,
+    #[serde(rename = "__pxTotalOffsetX", default)]
+    offset_x: f32,
+    #[serde(rename = "__pxTotalOffsetY", default)]
+    offset_y: f32,
+    #[serde(rename = "__tilesetDefUid")]
+    tileset: Option<i64>,
+    #[serde(default = "visible_by_default")]
+    visible: bool,
+    #[serde(default)]
+    grid_tiles: Vec<LdtkTile>,
+    #[serde(default)]
+    auto_layer_tiles: Vec<LdtkTile>,
+    #[serde(default)]
+    entity_instances: Vec<LdtkEntity>,
+}
+
+fn visible_by_default() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct LdtkTile {
+    px: [i32; 2],
+    t: u32,
+    // bit 0 flips horizontally, bit 1 vertically
+    #[serde(default)]
+    f: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LdtkEntity {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    px: [f32; 2],
+    #[serde(rename = "__pivot", default)]
+    pivot: [f32; 2],
+    width: f32,
+    height: f32,
+    #[serde(default)]
+    field_instances: Vec<LdtkField>,
+}
+
+#[derive(Deserialize)]
+struct LdtkField {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__value")]
+    value: serde_json::Value,
+}
+
+fn load_ldtk(path: &Path, stem: &str) -> Result<Vec<TileMap>, CustomError> {
+    let project: LdtkProject = serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| invalid_map(path, e))?;
+
+    let tilesets: Vec<(i64, MapTileset)> = project
+        .defs
+        .tilesets
+        .iter()
+        .filter_map(|tileset| {
+            let image = tileset.rel_path.as_deref()?;
+            Some((
+                tileset.uid,
+                MapTileset {
+                    image: resolve(path, image),
+                    tile_width: tileset.tile_grid_size,
+                    tile_height: tileset.tile_grid_size,
+                    margin: tileset.padding,
+                    spacing: tileset.spacing,
+                },
+            ))
+        })
+        .collect();
+    let grid_size = project.default_grid_size.unwrap_or(16);
+
+    let mut maps = Vec::new();
+    for level in project.levels {
+        let instances = match (level.layer_instances, &level.external_rel_path) {
+            (Some(instances), _) => instances,
+            (None, Some(external)) => {
+                let file = resolve(path, external);
+                let level: LdtkLevel = serde_json::from_str(&read_referenced(&file, path)?)
+                    .map_err(|e| invalid_map(&file, e))?;
+                level.layer_instances.unwrap_or_default()
+            }
+            (None, None) => Vec::new(),
+        };
+
+        let mut layers = Vec::new();
+        let mut used = Vec::new();
+        // LDtk lists the top layer first
+        for layer in instances.into_iter().rev() {
+            let content = if layer.kind == "Entities" {
+                LayerContent::Objects(layer.entity_instances.into_iter().map(ldtk_object).collect())
+            } else {
+                let tiles: Vec<&LdtkTile> = layer.grid_tiles.iter().chain(&layer.auto_layer_tiles).collect();
+                if tiles.is_empty() {
+                    continue;
+                }
+                let Some((_, tileset)) = tilesets.iter().find(|(uid, _)| Some(*uid) == layer.tileset) else {
+                    return Err(CustomError::BuildError(t!(
+                        "Layer '{}' of {} uses a tileset without an image file",
+                        layer.identifier,
+                        path.display()
+                    )));
+                };
+                if !used.contains(tileset) {
+                    used.push(tileset.clone());
+                }
+                let grid = layer.grid_size.max(1) as i32;
+                LayerContent::Tiles(
+                    tiles
+                        .into_iter()
+                        .map(|tile| MapTile {
+                            sprite: tileset.tile_key(tile.t),
+                            x: tile.px[0].div_euclid(grid),
+                            y: tile.px[1].div_euclid(grid),
+                            flip_x: tile.f & 1 != 0,
+                            flip_y: tile.f & 2 != 0,
+                            flip_diagonal: false,
+                        })
+                        .collect(),
+                )
+            };
+            layers.push(MapLayer {
+                name: layer.identifier,
+                visible: layer.visible,
+                offset: (layer.offset_x, layer.offset_y),
+                tile_width: layer.grid_size,
+                tile_height: layer.grid_size,
+                content,
+            });
+        }
+
+        maps.push(TileMap {
+            name: format!("{}_{}", stem, level.identifier),
+            width: level.px_wid,
+            height: level.px_hei,
+            tile_width: grid_size,
+            tile_height: grid_size,
+            layers,
+            tilesets: used,
+        });
+    }
+    Ok(maps)
+}
+
+fn ldtk_object(entity: LdtkEntity) -> MapObject {
+    let properties = entity
+        .field_instances
+        .into_iter()
+        .map(|field| {
+            let value = match field.value {
+                serde_json::Value::String(text) => text,
+                serde_json::Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            (field.identifier, value)
+        })
+        .collect();
+    MapObject {
+        name: String::new(),
+        class: entity.identifier,
+        // LDtk places entities by their pivot
+        x: entity.px[0] - entity.pivot[0] * entity.width,
+        y: entity.px[1] - entity.pivot[1] * entity.height,
+        width: entity.width,
+        height: entity.height,
+        rotation: 0.0,
+        sprite: None,
+        shape: ObjectShape::Rectangle,
+        points: Vec::new(),
+        properties,
+    }
+}
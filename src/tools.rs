@@ -0,0 +1,174 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::ToolRequirement;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::process::Command;
+
+struct KnownTool {
+    name: &'static str,
+    version_args: &'static [&'static str],
+}
+
+// tools whose version flag isn't the usual `--version`
+const KNOWN_TOOLS: &[KnownTool] = &[
+    KnownTool {
+        name: "aseprite",
+        version_args: &["--version"],
+    },
+    KnownTool {
+        name: "ffmpeg",
+        version_args: &["-version"],
+    },
+    KnownTool {
+        name: "butler",
+        version_args: &["--version"],
+    },
+];
+
+#[derive(Debug, Serialize)]
+pub struct ToolCheck {
+    pub name: String,
+    pub program: String,
+    pub required: Option<String>,
+    pub found: Option<String>,
+    pub ok: bool,
+}
+
+impl ToolCheck {
+    fn problem(&self) -> Option<String> {
+        if self.ok {
+            return None;
+        }
+        Some(match (&self.found, &self.required) {
+            (None, _) => format!("'{}' not found", self.program),
+            (Some(found), Some(required)) => format!("found {}, need {} or newer", found, required),
+            (Some(found), None) => format!("could not read a version from {}", found),
+        })
+    }
+}
+
+fn parse_version(text: &str) -> Option<Vec<u64>> {
+    let re = Regex::new(r"(\d+)\.(\d+)(?:\.(\d+))?").unwrap();
+    let caps = re.captures(text)?;
+    Some(
+        caps.iter()
+            .skip(1)
+            .flatten()
+            .filter_map(|m| m.as_str().parse().ok())
+            .collect(),
+    )
+}
+
+fn at_least(found: &[u64], required: &[u64]) -> bool {
+    let len = found.len().max(required.len());
+    let pad = |v: &[u64]| (0..len).map(|i| v.get(i).copied().unwrap_or(0)).collect::<Vec<_>>();
+    pad(found) >= pad(required)
+}
+
+// first line mentioning a version, from stdout or stderr (butler prints to stderr)
+fn tool_version(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<&str> = stdout.lines().chain(stderr.lines()).map(str::trim).collect();
+    lines
+        .iter()
+        .find(|l| parse_version(l).is_some())
+        .or_else(|| lines.iter().find(|l| !l.is_empty()))
+        .map(|l| l.to_string())
+}
+
+fn check_tool(name: &str, requirement: &ToolRequirement) -> Result<ToolCheck, CustomError> {
+    let (version, path) = match requirement {
+        ToolRequirement::Version(version) => (Some(version.as_str()), None),
+        ToolRequirement::Detailed { version, path } => (version.as_deref(), path.as_deref()),
+    };
+    let required = version.filter(|v| !v.is_empty() && *v != "*");
+
+    let required_parts = match required {
+        Some(v) => Some(parse_version(v).or_else(|| v.parse().ok().map(|major| vec![major])).ok_or_else(
+            || {
+                CustomError::ValidationError(t!(
+                    "Invalid [tools] {} version '{}' (expected e.g. \"1.3\" or \"*\")",
+                    name, v
+                ))
+            },
+        )?),
+        None => None,
+    };
+
+    let program = path.unwrap_or(name).to_string();
+    let args = KNOWN_TOOLS
+        .iter()
+        .find(|t| t.name == name)
+        .map(|t| t.version_args)
+        .unwrap_or(&["--version"]);
+    let found = tool_version(&program, args);
+
+    let ok = match (&found, &required_parts) {
+        (None, _) => false,
+        (Some(_), None) => true,
+        (Some(found), Some(required)) => parse_version(found).is_some_and(|v| at_least(&v, required)),
+    };
+
+    Ok(ToolCheck {
+        name: name.to_string(),
+        program,
+        required: required.map(str::to_string),
+        found,
+        ok,
+    })
+}
+
+pub fn check_tools(tools: &BTreeMap<String, ToolRequirement>) -> Result<Vec<ToolCheck>, CustomError> {
+    tools
+        .iter()
+        .map(|(name, requirement)| check_tool(name, requirement))
+        .collect()
+}
+
+pub fn render_tool_report(checks: &[ToolCheck]) -> String {
+    let mut out = String::new();
+    for check in checks {
+        let status = match check.problem() {
+            None => format!("ok ({})", check.found.as_deref().unwrap_or("")),
+            Some(problem) => problem,
+        };
+        let _ = writeln!(out, "{:<11} {}", format!("{}:", check.name), status);
+    }
+    out
+}
+
+/// Checks one tool, the `[tools]` entry when there is one, and returns the program to run.
+/// Tools without an entry only need to be on the PATH.
+pub fn require_tool(tools: &BTreeMap<String, ToolRequirement>, name: &str) -> Result<String, CustomError> {
+    let any_version = ToolRequirement::Version("*".to_string());
+    let check = check_tool(name, tools.get(name).unwrap_or(&any_version))?;
+    match check.problem() {
+        None => Ok(check.program),
+        Some(problem) => Err(CustomError::ValidationError(t!(
+            "{}: {}. Install it, or set a path in bonsai.toml with [tools] {} = {{ path = \"...\" }}",
+            name, problem, name
+        ))),
+    }
+}
+
+/// Checks every `[tools]` entry at once, so a missing tool is reported before any stage runs
+/// instead of halfway through the pipeline.
+pub fn require_tools(tools: &BTreeMap<String, ToolRequirement>) -> Result<(), CustomError> {
+    let problems: Vec<String> = check_tools(tools)?
+        .iter()
+        .filter_map(|c| c.problem().map(|p| format!("  {}: {}", c.name, p)))
+        .collect();
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(CustomError::ValidationError(t!(
+        "Missing or outdated tools from [tools]:\n{}\nInstall them, or set a path with name = {{ version = \"...\", path = \"...\" }}",
+        problems.join("\n")
+    )))
+}
@@ -1,20 +1,222 @@
+use crate::build_graph::BuildGraph;
+use crate::cancel::CancelToken;
+use crate::i18n::{is_yes, t, tr};
+use crate::manifest::LimitsOptions;
+use crate::usage::Metrics;
 use chrono::Local;
 use colored::*;
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::fs::OpenOptions;
 use std::io;
 use std::io::Write;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+type ProgressSink = Arc<Mutex<Box<dyn Write + Send>>>;
+/// Error lines collected while a [`Ui::capturing_errors`] copy is in use.
+pub type ErrorLog = Arc<Mutex<Vec<String>>>;
+
+/// Draws the human-readable output, [`TerminalBackend`] or [`PlainBackend`] (`--plain`).
+trait Backend: Send + Sync {
+    fn stage(&self, id: &str);
+    fn status(&self, msg: &str);
+    fn log(&self, msg: &str);
+    fn success(&self, msg: &str);
+    fn error(&self, msg: &str);
+    fn message(&self, text: &str);
+    fn failure(&self, context: &str, msg: &str);
+    fn progress_bar(&self, total: Option<u64>, msg: &str) -> ProgressBar;
+    // runs `ask` with the prefix for the prompt's first line
+    fn prompt(&self, ask: &mut dyn FnMut(&str));
+    fn mark(&self, ok: bool) -> String;
+}
+
+fn backend(verbose: bool, plain: bool, human_stderr: bool) -> Arc<dyn Backend> {
+    if plain {
+        Arc::new(PlainBackend {
+            human_stderr,
+            stage: Mutex::new(None),
+        })
+    } else {
+        Arc::new(TerminalBackend::new(verbose, human_stderr))
+    }
+}
+
 #[derive(Clone)]
 pub struct Ui {
-    spinner: ProgressBar,
-    multiprogress: MultiProgress,
+    backend: Arc<dyn Backend>,
+    progress: Option<ProgressSink>,
+    errors: Option<ErrorLog>,
+    plain: bool,
+    pub metrics: Arc<Metrics>,
+    pub graph: Arc<BuildGraph>,
+    pub cancel: CancelToken,
+    // `[limits]` of the project being built, for the tools it spawns
+    pub limits: LimitsOptions,
     pub verbose: bool,
 }
 
 impl Ui {
-    pub fn new(verbose: bool) -> Self {
-        let pb = if verbose {
+    pub fn new(verbose: bool, plain: bool) -> Self {
+        if plain {
+            colored::control::set_override(false);
+        }
+
+        Self {
+            backend: backend(verbose, plain, true),
+            progress: None,
+            errors: None,
+            plain,
+            metrics: Arc::new(Metrics::default()),
+            graph: Arc::new(BuildGraph::default()),
+            cancel: CancelToken::default(),
+            limits: LimitsOptions::default(),
+            verbose,
+        }
+    }
+
+    // a copy of this ui whose builds stop when `token` is cancelled
+    pub fn with_cancel(&self, token: CancelToken) -> Self {
+        let mut ui = self.clone();
+        ui.cancel = token;
+        ui
+    }
+
+    // a copy of this ui whose spawned tools run under `limits`
+    pub fn with_limits(&self, limits: LimitsOptions) -> Self {
+        let mut ui = self.clone();
+        ui.limits = limits;
+        ui
+    }
+
+    // a copy of this ui that also keeps every error line, without colors, in `log`
+    pub fn capturing_errors(&self, log: ErrorLog) -> Self {
+        let mut ui = self.clone();
+        ui.errors = Some(log);
+        ui
+    }
+
+    // machine-readable progress for editors: "stderr" or a file/named pipe path, one JSON object per line
+    pub fn with_progress(mut self, target: &str) -> io::Result<Self> {
+        let sink: Box<dyn Write + Send> = if target == "stderr" {
+            // stderr belongs to the events now, keep every human-readable line on stdout
+            self.backend = backend(self.verbose, self.plain, false);
+            Box::new(io::stderr())
+        } else {
+            Box::new(OpenOptions::new().create(true).append(true).open(target)?)
+        };
+        self.progress = Some(Arc::new(Mutex::new(sink)));
+        Ok(self)
+    }
+
+    fn emit(&self, event: serde_json::Value) {
+        if let Some(sink) = &self.progress
+            && let Ok(mut sink) = sink.lock()
+        {
+            let _ = writeln!(sink, "{}", event);
+            let _ = sink.flush();
+        }
+    }
+
+    pub fn report_failure(&self, context: &str, msg: &str) {
+        self.emit(serde_json::json!({ "event": "failed", "context": context, "message": msg }));
+        self.backend.failure(context, msg);
+    }
+
+    pub fn stage(&self, id: &str, percent: u8) {
+        self.metrics.enter_stage(id);
+        self.emit(serde_json::json!({ "event": "stage", "stage": id, "percent": percent }));
+        self.backend.stage(id);
+    }
+
+    pub fn status(&self, msg: &str) {
+        self.backend.status(msg);
+    }
+
+    pub fn log(&self, msg: &str) {
+        self.backend.log(msg);
+    }
+
+    pub fn success(&self, msg: &str) {
+        self.emit(serde_json::json!({ "event": "success", "message": msg }));
+        self.backend.success(msg);
+    }
+
+    pub fn error(&self, msg: &str) {
+        self.emit(serde_json::json!({ "event": "error", "message": msg }));
+        if let Some(errors) = &self.errors
+            && let Ok(mut errors) = errors.lock()
+        {
+            errors.push(strip_ansi(msg));
+        }
+        self.backend.error(msg);
+    }
+
+    pub fn message(&self, text: &str) {
+        self.backend.message(text);
+    }
+
+    /// A check mark or cross for lists of results, plain words with `--plain`.
+    pub fn mark(&self, ok: bool) -> String {
+        self.backend.mark(ok)
+    }
+
+    pub fn progress_bar(&self, total: Option<u64>, msg: &str) -> ProgressBar {
+        self.backend.progress_bar(total, msg)
+    }
+
+    pub fn confirm(&self, prompt_text: &str) -> bool {
+        let mut answer = false;
+        self.backend.prompt(&mut |prefix| {
+            print!("{}{} {}: ", prefix, prompt_text, tr("[Y/N]"));
+            io::stdout().flush().unwrap_or(());
+            let mut input = String::new();
+            io::stdin().read_line(&mut input).unwrap_or(0);
+            answer = is_yes(&input);
+        });
+        answer
+    }
+
+    /// Numbered prompt, returns the index of the picked option or `None` for an invalid answer.
+    pub fn choose(&self, prompt_text: &str, options: &[String]) -> Option<usize> {
+        let mut picked = None;
+        self.backend.prompt(&mut |prefix| {
+            println!("{}{}", prefix, prompt_text);
+            for (i, option) in options.iter().enumerate() {
+                println!("  {}) {}", i + 1, option);
+            }
+            print!("{}", t!("Choose [1-{}]: ", options.len()));
+            io::stdout().flush().unwrap_or(());
+            let mut input = String::new();
+            io::stdin().read_line(&mut input).unwrap_or(0);
+            picked = input
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|n| (1..=options.len()).contains(n))
+                .map(|n| n - 1);
+        });
+        picked
+    }
+}
+
+fn timestamp() -> String {
+    let now = Local::now();
+    format!("{}", now.format("[%H:%M:%S]").to_string().bright_yellow())
+}
+
+/// Spinner, colors and timestamps.
+struct TerminalBackend {
+    spinner: ProgressBar,
+    multiprogress: MultiProgress,
+    human_stderr: bool,
+    verbose: bool,
+}
+
+impl TerminalBackend {
+    fn new(verbose: bool, human_stderr: bool) -> Self {
+        // with --progress stderr the spinner would fight the events for the stream
+        let spinner = if verbose || !human_stderr {
             ProgressBar::hidden()
         } else {
             let p = ProgressBar::new_spinner();
@@ -27,21 +229,26 @@ impl Ui {
             p.enable_steady_tick(Duration::from_millis(80));
             p
         };
+        let multiprogress = if human_stderr {
+            MultiProgress::new()
+        } else {
+            MultiProgress::with_draw_target(ProgressDrawTarget::stdout())
+        };
 
         Self {
-            spinner: pb,
-            multiprogress: MultiProgress::new(),
+            spinner,
+            multiprogress,
+            human_stderr,
             verbose,
         }
     }
+}
 
-    fn timestamp(&self) -> String {
-        let now = Local::now();
-        format!("{}", now.format("[%H:%M:%S]").to_string().bright_yellow())
-    }
+impl Backend for TerminalBackend {
+    fn stage(&self, _id: &str) {}
 
-    pub fn status(&self, msg: &str) {
-        let time = self.timestamp();
+    fn status(&self, msg: &str) {
+        let time = timestamp();
         if self.verbose {
             println!("{} {} {}", time, "[INFO]".blue().bold(), msg);
         } else {
@@ -50,8 +257,8 @@ impl Ui {
         }
     }
 
-    pub fn log(&self, msg: &str) {
-        let time = self.timestamp();
+    fn log(&self, msg: &str) {
+        let time = timestamp();
         if self.verbose {
             println!("{} {} {}", time, "[LOG]".yellow().bold(), msg);
         } else {
@@ -61,8 +268,8 @@ impl Ui {
         }
     }
 
-    pub fn success(&self, msg: &str) {
-        let time = self.timestamp();
+    fn success(&self, msg: &str) {
+        let time = timestamp();
         if self.spinner.is_finished() || self.verbose {
             println!("{} {} {}", time, "[SUCCESS]".green(), msg);
         } else {
@@ -72,9 +279,11 @@ impl Ui {
         }
     }
 
-    pub fn error(&self, msg: &str) {
-        let time = self.timestamp();
-        if self.spinner.is_finished() || self.verbose {
+    fn error(&self, msg: &str) {
+        let time = timestamp();
+        if !self.human_stderr {
+            println!("{} {} {}", time, "[ERROR]".red().bold(), msg);
+        } else if self.spinner.is_finished() || self.verbose {
             eprintln!("{} {} {}", time, "[ERROR]".red().bold(), msg);
         } else {
             self.spinner.disable_steady_tick();
@@ -83,8 +292,8 @@ impl Ui {
         }
     }
 
-    pub fn message(&self, text: &str) {
-        let time = self.timestamp();
+    fn message(&self, text: &str) {
+        let time = timestamp();
         if self.verbose {
             println!("{} {}", time, text);
         } else {
@@ -92,15 +301,146 @@ impl Ui {
         }
     }
 
-    pub fn confirm(&self, prompt_text: &str) -> bool {
-        let time = self.timestamp();
-        self.multiprogress.suspend(|| {
-            print!("{} {} [Y/N]: ", time, prompt_text);
-            io::stdout().flush().unwrap_or(());
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap_or(0);
-            let clean = input.trim().to_lowercase();
-            clean == "y" || clean == "yes"
-        })
+    fn failure(&self, context: &str, msg: &str) {
+        let line = format!("{}: {}.", format!("[ERROR] ({})", context).red().bold(), msg);
+        if self.human_stderr {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    fn progress_bar(&self, total: Option<u64>, msg: &str) -> ProgressBar {
+        let bar = match total {
+            Some(len) => {
+                let p = ProgressBar::new(len);
+                p.set_style(
+                    ProgressStyle::default_bar()
+                        .template("{msg} [{bar:30.green}] {bytes}/{total_bytes} ({bytes_per_sec})")
+                        .unwrap()
+                        .progress_chars("=> "),
+                );
+                p
+            }
+            None => {
+                let p = ProgressBar::new_spinner();
+                p.set_style(
+                    ProgressStyle::default_spinner()
+                        .template("{spinner:.green} {msg} {bytes} ({bytes_per_sec})")
+                        .unwrap(),
+                );
+                p
+            }
+        };
+        bar.set_message(msg.to_string());
+
+        self.multiprogress.add(bar)
+    }
+
+    fn prompt(&self, ask: &mut dyn FnMut(&str)) {
+        let prefix = format!("{} ", timestamp());
+        self.multiprogress.suspend(|| ask(&prefix));
+    }
+
+    fn mark(&self, ok: bool) -> String {
+        if ok {
+            "✔".green().to_string()
+        } else {
+            "✘".red().to_string()
+        }
+    }
+}
+
+/// One line per event with a fixed `WORD:` prefix and no spinner, glyphs, colors or timestamps,
+/// for screen readers and log scrapers. Stages get explicit START/OK/FAIL lines.
+struct PlainBackend {
+    human_stderr: bool,
+    // the stage that printed START and hasn't been closed yet
+    stage: Mutex<Option<String>>,
+}
+
+impl PlainBackend {
+    fn close_stage(&self, result: &str) {
+        if let Ok(mut stage) = self.stage.lock()
+            && let Some(id) = stage.take()
+        {
+            println!("{}: {}", result, id);
+        }
+    }
+
+    fn error_line(&self, line: String) {
+        if self.human_stderr {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+impl Backend for PlainBackend {
+    fn stage(&self, id: &str) {
+        self.close_stage("OK");
+        println!("START: {}", id);
+        if let Ok(mut stage) = self.stage.lock() {
+            *stage = Some(id.to_string());
+        }
+    }
+
+    fn status(&self, msg: &str) {
+        println!("INFO: {}", msg);
+    }
+
+    fn log(&self, msg: &str) {
+        println!("LOG: {}", msg);
+    }
+
+    fn success(&self, msg: &str) {
+        self.close_stage("OK");
+        println!("SUCCESS: {}", msg);
+    }
+
+    fn error(&self, msg: &str) {
+        self.close_stage("FAIL");
+        self.error_line(format!("ERROR: {}", msg));
+    }
+
+    fn message(&self, text: &str) {
+        println!("NOTE: {}", text);
+    }
+
+    fn failure(&self, context: &str, msg: &str) {
+        self.close_stage("FAIL");
+        self.error_line(format!("ERROR: {}: {}.", context, msg));
+    }
+
+    fn progress_bar(&self, _total: Option<u64>, msg: &str) -> ProgressBar {
+        println!("INFO: {}...", msg);
+        ProgressBar::hidden()
+    }
+
+    fn prompt(&self, ask: &mut dyn FnMut(&str)) {
+        ask("PROMPT: ");
+    }
+
+    fn mark(&self, ok: bool) -> String {
+        if ok { "OK".to_string() } else { "FAIL".to_string() }
+    }
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequences end with a letter, e.g. "\x1b[31m"
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
     }
+    out
 }
@@ -0,0 +1,103 @@
+use crate::error::CustomError;
+use crate::git::describe_head;
+use crate::hash::hash_file;
+use crate::i18n::t;
+use crate::manifest::Manifest;
+use chrono::Local;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+pub const UPDATE_MANIFEST_FILE: &str = "update.json";
+
+#[derive(Serialize)]
+struct UpdateArtifact {
+    path: String,
+    size: u64,
+    sha256: String,
+    url: String,
+}
+
+#[derive(Serialize)]
+struct UpdateManifest {
+    build_id: String,
+    version: String,
+    channel: String,
+    platform: String,
+    created: String,
+    commit: Option<String>,
+    artifacts: Vec<UpdateArtifact>,
+}
+
+pub fn platform_name(target: &str) -> String {
+    if target == "web" {
+        return target.to_string();
+    }
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Writes `update.json` into the build output when `[update] base_url` is set.
+pub fn write_update_manifest(
+    output_dir: &Path,
+    target: &str,
+    build_id: &str,
+    manifest: &Manifest,
+) -> Result<bool, CustomError> {
+    let Some(base_url) = &manifest.update.base_url else {
+        return Ok(false);
+    };
+
+    let version = &manifest.project.version;
+    let platform = platform_name(target);
+    let base_url = base_url
+        .replace("{version}", version)
+        .replace("{platform}", &platform)
+        .replace("{build_id}", build_id);
+    let base_url = base_url.trim_end_matches('/');
+
+    let mut artifacts = Vec::new();
+    for entry in WalkDir::new(output_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let relative = entry
+            .path()
+            .strip_prefix(output_dir)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+        if relative == UPDATE_MANIFEST_FILE {
+            continue;
+        }
+
+        artifacts.push(UpdateArtifact {
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            sha256: hash_file(entry.path())?,
+            url: format!("{}/{}", base_url, relative),
+            path: relative,
+        });
+    }
+
+    let update = UpdateManifest {
+        build_id: build_id.to_string(),
+        version: version.clone(),
+        channel: manifest
+            .update
+            .channel
+            .clone()
+            .unwrap_or_else(|| "stable".to_string()),
+        platform,
+        created: Local::now().to_rfc3339(),
+        commit: describe_head(Path::new(".")),
+        artifacts,
+    };
+
+    let json = serde_json::to_string_pretty(&update)
+        .map_err(|e| CustomError::BuildError(t!("Failed to serialize update manifest: {}", e)))?;
+    fs::write(output_dir.join(UPDATE_MANIFEST_FILE), json)?;
+
+    Ok(true)
+}
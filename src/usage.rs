@@ -0,0 +1,241 @@
+use crate::config::{global_dir, load_global_config};
+use crate::i18n::{t, tr};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const USAGE_FILE: &str = "usage.jsonl";
+const MAX_RECORDS: usize = 2000;
+const TREND_WINDOW_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Stage timings and cache counters collected while a command runs. Never leaves the machine.
+#[derive(Default)]
+pub struct Metrics {
+    stages: Mutex<Vec<(String, Instant)>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+impl Metrics {
+    pub fn enter_stage(&self, id: &str) {
+        if let Ok(mut stages) = self.stages.lock() {
+            stages.push((id.to_string(), Instant::now()));
+        }
+    }
+
+    pub fn cache(&self, hit: bool) {
+        let counter = if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn stage_durations(&self, end: Instant) -> BTreeMap<String, u64> {
+        let mut durations = BTreeMap::new();
+        let Ok(stages) = self.stages.lock() else {
+            return durations;
+        };
+
+        for (i, (id, start)) in stages.iter().enumerate() {
+            let stop = stages.get(i + 1).map(|(_, t)| *t).unwrap_or(end);
+            *durations.entry(id.clone()).or_insert(0) += stop.duration_since(*start).as_millis() as u64;
+        }
+        durations
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub timestamp: u64,
+    pub command: String,
+    pub duration_ms: u64,
+    pub success: bool,
+    #[serde(default)]
+    pub stages: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub cache_hits: u64,
+    #[serde(default)]
+    pub cache_misses: u64,
+}
+
+#[derive(Serialize)]
+pub struct CommandTrend {
+    pub command: String,
+    pub runs: usize,
+    pub failures: usize,
+    pub average_ms: u64,
+    pub cache_hit_rate: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct StageTrend {
+    pub stage: String,
+    pub average_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct UsageTrends {
+    pub window_days: u64,
+    pub commands: Vec<CommandTrend>,
+    pub slowest_stages: Vec<StageTrend>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn is_enabled() -> bool {
+    load_global_config()
+        .ok()
+        .and_then(|c| c.stats.record_usage)
+        .unwrap_or(true)
+}
+
+// failures here must never break the command that was just run
+pub fn record_usage(command: &str, duration: Duration, success: bool, metrics: &Metrics) {
+    if !is_enabled() {
+        return;
+    }
+    let Ok(dir) = global_dir() else {
+        return;
+    };
+    if fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+
+    let record = UsageRecord {
+        timestamp: now_secs(),
+        command: command.to_string(),
+        duration_ms: duration.as_millis() as u64,
+        success,
+        stages: metrics.stage_durations(Instant::now()),
+        cache_hits: metrics.cache_hits.load(Ordering::Relaxed),
+        cache_misses: metrics.cache_misses.load(Ordering::Relaxed),
+    };
+    let Ok(line) = serde_json::to_string(&record) else {
+        return;
+    };
+
+    let path = dir.join(USAGE_FILE);
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{}", line);
+    }
+
+    // keep the file bounded
+    if let Ok(content) = fs::read_to_string(&path) {
+        let lines: Vec<&str> = content.lines().collect();
+        if lines.len() > MAX_RECORDS {
+            let kept = lines[lines.len() - MAX_RECORDS..].join("\n");
+            let _ = fs::write(&path, kept + "\n");
+        }
+    }
+}
+
+fn load_records() -> Vec<UsageRecord> {
+    let Ok(dir) = global_dir() else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(dir.join(USAGE_FILE)) else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+pub fn collect_trends() -> UsageTrends {
+    let since = now_secs().saturating_sub(TREND_WINDOW_SECS);
+    let records: Vec<UsageRecord> = load_records()
+        .into_iter()
+        .filter(|r| r.timestamp >= since)
+        .collect();
+
+    let mut by_command: BTreeMap<&str, Vec<&UsageRecord>> = BTreeMap::new();
+    for record in &records {
+        by_command.entry(&record.command).or_default().push(record);
+    }
+
+    let commands = by_command
+        .into_iter()
+        .map(|(command, runs)| {
+            let hits: u64 = runs.iter().map(|r| r.cache_hits).sum();
+            let lookups = hits + runs.iter().map(|r| r.cache_misses).sum::<u64>();
+
+            CommandTrend {
+                command: command.to_string(),
+                runs: runs.len(),
+                failures: runs.iter().filter(|r| !r.success).count(),
+                average_ms: runs.iter().map(|r| r.duration_ms).sum::<u64>() / runs.len() as u64,
+                cache_hit_rate: (lookups > 0).then(|| hits as f64 / lookups as f64 * 100.0),
+            }
+        })
+        .collect();
+
+    let mut stage_totals: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+    for record in &records {
+        for (stage, ms) in &record.stages {
+            let entry = stage_totals.entry(stage).or_insert((0, 0));
+            entry.0 += ms;
+            entry.1 += 1;
+        }
+    }
+    let mut slowest_stages: Vec<StageTrend> = stage_totals
+        .into_iter()
+        .map(|(stage, (total, count))| StageTrend {
+            stage: stage.to_string(),
+            average_ms: total / count,
+        })
+        .collect();
+    slowest_stages.sort_by_key(|s| std::cmp::Reverse(s.average_ms));
+
+    UsageTrends {
+        window_days: TREND_WINDOW_SECS / (24 * 60 * 60),
+        commands,
+        slowest_stages,
+    }
+}
+
+pub fn print_trends(trends: &UsageTrends) {
+    println!("{}", t!("Usage (last {} days):", trends.window_days));
+    if trends.commands.is_empty() {
+        println!("{}", tr("  No commands recorded yet."));
+        return;
+    }
+
+    for command in &trends.commands {
+        let hit_rate = command
+            .cache_hit_rate
+            .map(|r| t!(", {}% cache hits", format!("{:.0}", r)))
+            .unwrap_or_default();
+        println!(
+            "{}",
+            t!(
+                "  {}: {} runs ({} failed), avg {}s{}",
+                command.command,
+                command.runs,
+                command.failures,
+                format!("{:.1}", command.average_ms as f64 / 1000.0),
+                hit_rate
+            )
+        );
+    }
+
+    if !trends.slowest_stages.is_empty() {
+        println!("{}", tr("  Slowest stages:"));
+        for stage in &trends.slowest_stages {
+            let average = format!("{:.1}", stage.average_ms as f64 / 1000.0);
+            println!("{}", t!("    {}: avg {}s", stage.stage, average));
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::Manifest;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Written into the engine sources by releases, preferred over `[toolchain] engine`.
+const ENGINE_VERSION_FILE: &str = "bonsai/VERSION";
+/// Stored next to every build's executable or index.html.
+pub const VERSION_FILE: &str = "version.json";
+pub const VERSION_META_NAME: &str = "bonsai-version";
+const UNKNOWN: &str = "unknown";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VersionInfo {
+    pub engine: String,
+    pub cli: String,
+    pub project: String,
+}
+
+// versions end up in Odin strings, HTML attributes and JS, keep them to plain version characters
+fn validate(label: &str, version: &str) -> Result<(), CustomError> {
+    let valid = !version.is_empty()
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(CustomError::ValidationError(t!(
+            "Invalid {} version '{}' (expected letters, digits and . - + _)",
+            label, version
+        )))
+    }
+}
+
+/// Versions embedded into builds. Relies on the working directory being the project root.
+pub fn resolve_versions(manifest: &Manifest) -> Result<VersionInfo, CustomError> {
+    let file_version = fs::read_to_string(ENGINE_VERSION_FILE)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    let manifest_version = manifest.toolchain.engine.clone();
+
+    if let (Some(file), Some(recorded)) = (&file_version, &manifest_version)
+        && file != recorded
+    {
+        return Err(CustomError::ValidationError(t!(
+            "Engine version mismatch: {} says '{}' but [toolchain] engine is '{}'. Update bonsai.toml after upgrading the engine",
+            ENGINE_VERSION_FILE, file, recorded
+        )));
+    }
+
+    let engine = file_version
+        .or(manifest_version)
+        .unwrap_or_else(|| UNKNOWN.to_string());
+    validate("engine", &engine)?;
+    validate("project", &manifest.project.version)?;
+
+    Ok(VersionInfo {
+        engine,
+        cli: env!("CARGO_PKG_VERSION").to_string(),
+        project: manifest.project.version.clone(),
+    })
+}
+
+pub fn write_version_file(output_dir: &Path, info: &VersionInfo) -> Result<(), CustomError> {
+    let json = serde_json::to_string_pretty(info)
+        .map_err(|e| CustomError::ValidationError(t!("Failed to serialize versions: {}", e)))?;
+    fs::write(output_dir.join(VERSION_FILE), json)?;
+    Ok(())
+}
+
+/// `<meta>` tag and JS global injected into the web shell's `<head>`.
+pub fn web_version_tags(info: &VersionInfo) -> String {
+    format!(
+        "<meta name=\"{}\" content=\"engine={};cli={};project={}\">\n<script>window.BONSAI_VERSION = {{ engine: \"{}\", cli: \"{}\", project: \"{}\" }};</script>\n",
+        VERSION_META_NAME,
+        info.engine,
+        info.cli,
+        info.project,
+        info.engine,
+        info.cli,
+        info.project
+    )
+}
+
+fn parse_meta(html: &str) -> Option<VersionInfo> {
+    let marker = format!("name=\"{}\" content=\"", VERSION_META_NAME);
+    let start = html.find(&marker)? + marker.len();
+    let content = &html[start..start + html[start..].find('"')?];
+
+    let field = |key: &str| {
+        content
+            .split(';')
+            .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+            .unwrap_or(UNKNOWN)
+            .to_string()
+    };
+    Some(VersionInfo {
+        engine: field("engine"),
+        cli: field("cli"),
+        project: field("project"),
+    })
+}
+
+/// Reads the versions back from a build directory, an executable or a web build's index.html.
+pub fn read_version_info(path: &Path) -> Option<VersionInfo> {
+    let dir = if path.is_dir() { path } else { path.parent()? };
+
+    if let Ok(json) = fs::read_to_string(dir.join(VERSION_FILE))
+        && let Ok(info) = serde_json::from_str(&json)
+    {
+        return Some(info);
+    }
+
+    let html = if path.is_file() && path.extension().is_some_and(|ext| ext == "html") {
+        path.to_path_buf()
+    } else {
+        dir.join("index.html")
+    };
+    parse_meta(&fs::read_to_string(html).ok()?)
+}
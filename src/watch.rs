@@ -0,0 +1,155 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::WatchOptions;
+use crate::paths::ProjectPaths;
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use std::collections::BTreeSet;
+use notify_debouncer_mini::DebounceEventResult;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+const DEFAULT_DEBOUNCE_MS: u64 = 200;
+
+/// Pipeline stages a changed file can trigger.
+pub const WATCH_STAGES: &[&str] = &["sprites", "fonts", "audio", "scenes", "maps", "app", "shaders", "data"];
+
+/// Stages that make up the asset pipeline, in the order they run.
+pub const ASSET_STAGES: &[&str] = &["sprites", "fonts", "audio", "scenes", "maps", "app"];
+
+// used for paths that none of the [watch] stages patterns match
+const DEFAULT_STAGES: &[(&str, &[&str])] = &[
+    ("assets/images/**", &["sprites"]),
+    ("assets/fonts/**", &["fonts"]),
+    ("bonsai/core/render/**/*.{ttf,otf}", &["fonts"]),
+    ("assets/audio/**", &["audio"]),
+    ("assets/maps/**", &["maps"]),
+    ("assets/data/**", &["data"]),
+    ("assets/locale/**", &["data"]),
+    // the scene list comes from the directories, edits inside a scene don't change it
+    ("source/game/scenes/*", &["scenes"]),
+    ("**/*.{glsl,vert,frag}", &["shaders"]),
+];
+
+// outputs written by the pipeline itself, watching them would rebuild forever
+const DEFAULT_IGNORE: &[&str] = &[
+    ".bonsai/**",
+    "build/**",
+    "bonsai/generated/**",
+    "bonsai/core/render/atlas/**",
+    "source/game/scenes/generated_registry.odin",
+    "**/.DS_Store",
+    "**/*~",
+    "**/*.swp",
+];
+
+// `*` stays within one path component, `**` crosses directories
+fn glob(pattern: &str) -> Result<Glob, CustomError> {
+    GlobBuilder::new(pattern).literal_separator(true).build().map_err(|e| {
+        CustomError::ValidationError(t!("Invalid [watch] pattern '{}': {}", pattern, e))
+    })
+}
+
+fn glob_set<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Result<GlobSet, CustomError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(glob(pattern)?);
+    }
+    builder
+        .build()
+        .map_err(|e| CustomError::ValidationError(t!("Invalid [watch] patterns: {}", e)))
+}
+
+/// Decides which watcher events matter and which pipeline stages they rebuild.
+/// Paths are matched by their default-layout name (see [`ProjectPaths::logical`]), so patterns
+/// like `assets/images/**` keep working when `[paths]` moves a directory.
+pub struct WatchRules {
+    paths: ProjectPaths,
+    debounce: Duration,
+    ignore: GlobSet,
+    stages: Vec<(GlobSet, Vec<String>)>,
+}
+
+impl WatchRules {
+    /// Builds the rules from `[watch]`; relies on the working directory being the project root.
+    pub fn new(options: &WatchOptions, paths: &ProjectPaths) -> Result<Self, CustomError> {
+        let mut stages = Vec::new();
+        for (pattern, names) in &options.stages {
+            for name in names {
+                if !WATCH_STAGES.contains(&name.as_str()) {
+                    return Err(CustomError::ValidationError(t!(
+                        "Unknown [watch] stage '{}' for '{}'. Expected one of: {}",
+                        name,
+                        pattern,
+                        WATCH_STAGES.join(", ")
+                    )));
+                }
+            }
+            stages.push((glob_set([pattern.as_str()])?, names.clone()));
+        }
+        for (pattern, names) in DEFAULT_STAGES {
+            stages.push((
+                glob_set([*pattern])?,
+                names.iter().map(|s| s.to_string()).collect(),
+            ));
+        }
+
+        Ok(Self {
+            paths: paths.clone(),
+            debounce: Duration::from_millis(options.debounce_ms.unwrap_or(DEFAULT_DEBOUNCE_MS)),
+            ignore: glob_set(
+                DEFAULT_IGNORE
+                    .iter()
+                    .copied()
+                    .chain(options.ignore.iter().map(|p| p.as_str())),
+            )?,
+            stages,
+        })
+    }
+
+    pub fn debounce(&self) -> Duration {
+        self.debounce
+    }
+
+    /// Stages rebuilt by a change to `path`, empty when the path is ignored.
+    /// The first [watch] stages pattern that matches wins, then the built-in mapping.
+    pub fn stages_for(&self, path: &Path) -> Vec<String> {
+        // notify reports canonical paths, which may differ from the working directory (symlinks)
+        let relative = self.paths.logical(path);
+        if self.ignore.is_match(&relative) {
+            return Vec::new();
+        }
+        self.stages
+            .iter()
+            .find(|(patterns, _)| patterns.is_match(&relative))
+            .map(|(_, names)| names.clone())
+            .unwrap_or_default()
+    }
+
+    /// Blocks for the next batch of changed paths, None once the watcher is gone.
+    /// The debouncer reports paths as each one settles, so batches that follow
+    /// within one debounce interval (an editor saving many files) are merged.
+    pub fn next_batch(&self, rx: &Receiver<DebounceEventResult>) -> Option<Vec<PathBuf>> {
+        let mut paths = BTreeSet::new();
+        loop {
+            let result = if paths.is_empty() {
+                rx.recv().ok()?
+            } else {
+                match rx.recv_timeout(self.debounce) {
+                    Ok(result) => result,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            };
+            if let Ok(events) = result {
+                paths.extend(events.into_iter().map(|e| e.path));
+            }
+        }
+        Some(paths.into_iter().collect())
+    }
+
+    /// Union of the stages for a batch of changed paths, so a batch triggers one rebuild.
+    pub fn batch_stages<'a>(&self, paths: impl IntoIterator<Item = &'a Path>) -> BTreeSet<String> {
+        paths.into_iter().flat_map(|p| self.stages_for(p)).collect()
+    }
+}
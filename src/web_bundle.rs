@@ -0,0 +1,178 @@
+use crate::error::CustomError;
+use crate::freshness::is_same_copy;
+use crate::i18n::t;
+use crate::manifest::WebOptions;
+use crate::paths::ProjectPaths;
+use crate::staging::AssetFilter;
+use globset::{Glob, GlobMatcher};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const WEB_EXTRA_PRELOAD_STAGE_DIR: &str = ".bonsai/cache/web_preload_extra";
+pub const WEB_EMBED_STAGE_DIR: &str = ".bonsai/cache/web_embed";
+
+/// Asset files a `[web] preload` or `embed` list put into the build, staged under their
+/// `assets/...` paths.
+pub struct BundledFiles {
+    pub stage_dir: PathBuf,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl BundledFiles {
+    /// The emcc flag mounting the staged files at `assets/`, None when nothing was staged.
+    pub fn emcc_flag(&self, flag: &str, to_emcc_path: impl Fn(&Path) -> String) -> Option<String> {
+        if self.files == 0 {
+            return None;
+        }
+        Some(format!(
+            "{} {}@assets",
+            flag,
+            to_emcc_path(&self.stage_dir.join("assets"))
+        ))
+    }
+
+    pub fn staged_files(&self) -> Vec<PathBuf> {
+        WalkDir::new(&self.stage_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect()
+    }
+}
+
+struct Rule {
+    list: &'static str,
+    pattern: String,
+    matcher: GlobMatcher,
+    matched: bool,
+}
+
+fn rules(list: &'static str, patterns: &[String]) -> Result<Vec<Rule>, CustomError> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            let glob = Glob::new(pattern).map_err(|e| {
+                CustomError::ValidationError(t!("Invalid [web] {} pattern '{}': {}", list, pattern, e))
+            })?;
+            Ok(Rule {
+                list,
+                pattern: pattern.clone(),
+                matcher: glob.compile_matcher(),
+                matched: false,
+            })
+        })
+        .collect()
+}
+
+/// Stages the files `[web] preload` and `[web] embed` select, as (preload, embed).
+/// Every pattern has to match a file, and a file may only be preloaded, embedded or in a
+/// `[web.packages]` entry, so a typo or overlap fails the build before anything is linked.
+/// Files under `assets/audio` and `assets/fonts` are always preloaded and skipped here.
+pub fn stage_web_bundles(
+    web: &WebOptions,
+    paths: &ProjectPaths,
+    filter: &AssetFilter,
+) -> Result<(BundledFiles, BundledFiles), CustomError> {
+    let mut preload_rules = rules("preload", &web.preload)?;
+    let mut embed_rules = rules("embed", &web.embed)?;
+    let packages = web
+        .packages
+        .iter()
+        .map(|(name, patterns)| Ok((name, rules("packages", patterns)?)))
+        .collect::<Result<Vec<_>, CustomError>>()?;
+    let default_preload = [paths.audio(), paths.fonts()];
+
+    let mut preload = Vec::new();
+    let mut embed = Vec::new();
+    for entry in WalkDir::new(&paths.assets).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() || filter.is_excluded(entry.path()) {
+            continue;
+        }
+        let logical = paths.logical(entry.path());
+        let matched = |rules: &mut [Rule]| {
+            let mut any = false;
+            for rule in rules.iter_mut().filter(|r| r.matcher.is_match(&logical)) {
+                rule.matched = true;
+                any = true;
+            }
+            any
+        };
+        let preloaded = matched(&mut preload_rules);
+        let embedded = matched(&mut embed_rules);
+        if !preloaded && !embedded {
+            continue;
+        }
+
+        if preloaded && embedded {
+            return Err(CustomError::ValidationError(t!(
+                "{} matches both [web] preload and [web] embed",
+                logical
+            )));
+        }
+        if let Some((name, _)) = packages
+            .iter()
+            .find(|(_, rules)| rules.iter().any(|r| r.matcher.is_match(&logical)))
+        {
+            return Err(CustomError::ValidationError(t!(
+                "{} matches both [web] {} and [web.packages] {}",
+                logical,
+                if preloaded { "preload" } else { "embed" },
+                name
+            )));
+        }
+
+        let is_default = default_preload.iter().any(|dir| entry.path().starts_with(dir));
+        if embedded {
+            embed.push((entry.into_path(), logical));
+        } else if !is_default {
+            preload.push((entry.into_path(), logical));
+        }
+    }
+
+    if let Some(rule) = preload_rules.iter().chain(&embed_rules).find(|r| !r.matched) {
+        return Err(CustomError::ValidationError(t!(
+            "[web] {} pattern '{}' matches no asset files",
+            rule.list, rule.pattern
+        )));
+    }
+
+    Ok((
+        stage(Path::new(WEB_EXTRA_PRELOAD_STAGE_DIR), &preload)?,
+        stage(Path::new(WEB_EMBED_STAGE_DIR), &embed)?,
+    ))
+}
+
+// copies only new or changed files, so the link stays up to date when nothing changed
+fn stage(stage_dir: &Path, files: &[(PathBuf, String)]) -> Result<BundledFiles, CustomError> {
+    let mut bundled = BundledFiles {
+        stage_dir: stage_dir.to_path_buf(),
+        files: 0,
+        bytes: 0,
+    };
+
+    let mut staged = HashSet::new();
+    for (src, logical) in files {
+        let dest = stage_dir.join(logical);
+        let modified = fs::metadata(src).and_then(|m| m.modified()).ok();
+        if !is_same_copy(src, &dest, modified) {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(src, &dest)?;
+        }
+        bundled.files += 1;
+        bundled.bytes += fs::metadata(&dest)?.len();
+        staged.insert(dest);
+    }
+
+    for path in bundled.staged_files() {
+        if !staged.contains(&path) {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(bundled)
+}
@@ -0,0 +1,309 @@
+use crate::error::CustomError;
+use crate::hash::hash_file;
+use crate::i18n::t;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+pub const WEB_CHUNKS_MANIFEST: &str = "chunks.json";
+/// The bundle emcc writes next to index.html for `--preload-file`.
+pub const WEB_CORE_DATA: &str = "index.data";
+const SCRIPT_PLACEHOLDER: &str = "{{{ SCRIPT }}}";
+const HELD_SCRIPT_ID: &str = "bonsai-main-script";
+
+/// A `.data` bundle split into `<name>.0`, `<name>.1`, ... next to where it was.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChunkedData {
+    // sha256 of the whole bundle, names the browser cache keeping its finished chunks
+    pub hash: String,
+    pub bytes: u64,
+    // size of every chunk, in order
+    pub chunks: Vec<u64>,
+}
+
+fn chunk_name(data: &str, index: usize) -> String {
+    format!("{}.{}", data, index)
+}
+
+fn chunk_bytes(chunk_mb: u32) -> Result<u64, CustomError> {
+    if chunk_mb == 0 {
+        return Err(CustomError::ValidationError(t!("[web] chunk_mb must be at least 1")));
+    }
+    Ok(chunk_mb as u64 * 1024 * 1024)
+}
+
+fn read_chunks_manifest(out_dir: &Path) -> BTreeMap<String, ChunkedData> {
+    fs::read_to_string(out_dir.join(WEB_CHUNKS_MANIFEST))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+// streamed, a bundle worth chunking may not fit in memory twice
+fn split(path: &Path, data: &str, chunk_bytes: u64) -> Result<ChunkedData, CustomError> {
+    let hash = hash_file(path)?;
+    let mut file = File::open(path)?;
+    let mut chunks = Vec::new();
+    loop {
+        let chunk_path = path.with_file_name(chunk_name(data, chunks.len()));
+        let written = io::copy(&mut (&mut file).take(chunk_bytes), &mut File::create(&chunk_path)?)?;
+        if written == 0 {
+            fs::remove_file(&chunk_path)?;
+            break;
+        }
+        chunks.push(written);
+        if written < chunk_bytes {
+            break;
+        }
+    }
+    drop(file);
+    fs::remove_file(path)?;
+
+    Ok(ChunkedData {
+        hash,
+        bytes: chunks.iter().sum(),
+        chunks,
+    })
+}
+
+/// Splits the `data` bundles in `out_dir` into chunks of at most `chunk_mb` MB and lists them
+/// in `chunks.json`. A bundle that is gone already was split by an earlier build whose link
+/// was reused, so its previous chunks are kept. Chunks no longer listed are removed, all of
+/// them when `chunk_mb` is None.
+pub fn chunk_web_data(
+    out_dir: &Path,
+    data: &[String],
+    chunk_mb: Option<u32>,
+) -> Result<BTreeMap<String, ChunkedData>, CustomError> {
+    let previous = read_chunks_manifest(out_dir);
+    let mut chunked = BTreeMap::new();
+
+    if let Some(chunk_mb) = chunk_mb {
+        let chunk_bytes = chunk_bytes(chunk_mb)?;
+        for name in data {
+            let path = out_dir.join(name);
+            if path.exists() {
+                chunked.insert(name.clone(), split(&path, name, chunk_bytes)?);
+            } else if let Some(entry) = previous.get(name)
+                && (0..entry.chunks.len()).all(|i| out_dir.join(chunk_name(name, i)).exists())
+            {
+                chunked.insert(name.clone(), entry.clone());
+            }
+        }
+    }
+
+    for (name, entry) in &previous {
+        let kept = chunked.get(name).map_or(0, |e| e.chunks.len());
+        for index in kept..entry.chunks.len() {
+            let stale = out_dir.join(chunk_name(name, index));
+            if stale.exists() {
+                fs::remove_file(stale)?;
+            }
+        }
+    }
+
+    let manifest_path = out_dir.join(WEB_CHUNKS_MANIFEST);
+    if chunked.is_empty() {
+        if manifest_path.exists() {
+            fs::remove_file(manifest_path)?;
+        }
+    } else {
+        let json = serde_json::to_string_pretty(&chunked).map_err(|e| {
+            CustomError::ValidationError(t!("Failed to serialize web chunks: {}", e))
+        })?;
+        fs::write(manifest_path, json)?;
+    }
+    Ok(chunked)
+}
+
+/// Removes the chunks `chunks.json` lists and the manifest itself.
+pub fn clean_web_chunks(out_dir: &Path) -> Result<(), CustomError> {
+    chunk_web_data(out_dir, &[], None)?;
+    Ok(())
+}
+
+/// Prepares the web shell for chunked bundles: emcc's script placeholder is wrapped in a
+/// `<template>`, so `index.js` only starts once the loader has fetched the core bundle, and
+/// the loader goes at the end of the body.
+pub fn prepare_chunked_shell(shell: &mut String, chunk_mb: u32) -> Result<(), CustomError> {
+    chunk_bytes(chunk_mb)?;
+    if !shell.contains(SCRIPT_PLACEHOLDER) {
+        return Err(CustomError::ValidationError(t!(
+            "The web shell has no {} placeholder, [web] chunk_mb can't hold the game back",
+            SCRIPT_PLACEHOLDER
+        )));
+    }
+    *shell = shell.replacen(
+        SCRIPT_PLACEHOLDER,
+        &format!(r#"<template id="{}">{}</template>"#, HELD_SCRIPT_ID, SCRIPT_PLACEHOLDER),
+        1,
+    );
+    if let Some(body_end) = shell.rfind("</body>") {
+        shell.insert_str(body_end, &chunk_loader_script());
+    }
+    Ok(())
+}
+
+/// The loader fetching chunked bundles: chunk by chunk, retrying dropped connections with a
+/// Range request from where they stopped, and keeping finished chunks in the Cache Storage so
+/// a reload continues the download. The data reaches Emscripten through
+/// `Module.getPreloadedPackage`.
+///
+/// Progress is reported per bundle, named without `.data` (`index` is the core bundle):
+/// `window.bonsaiProgress(name)` returns `{ loaded, total }`, and a `window.bonsaiOnProgress`
+/// function, when the page or game sets one, is called with `(name, loaded, total)`.
+fn chunk_loader_script() -> String {
+    format!(
+        r#"<script>
+(function () {{
+  var CORE = "{core}";
+  var MAX_ATTEMPTS = 6;
+  var progress = {{}};
+  var buffers = {{}};
+  var loading = {{}};
+  var manifest = fetch("{manifest}", {{ cache: "no-cache" }})
+    .then(function (response) {{ return response.ok ? response.json() : {{}}; }})
+    .catch(function () {{ return {{}}; }});
+
+  function report(data, loaded, total) {{
+    var name = data.replace(/\.data$/, "");
+    progress[name] = {{ loaded: loaded, total: total }};
+    if (typeof window.bonsaiOnProgress === "function") window.bonsaiOnProgress(name, loaded, total);
+    if (data === CORE && window.Module && Module.setStatus) {{
+      Module.setStatus("Downloading data... (" + loaded + "/" + total + ")");
+    }}
+  }}
+
+  window.bonsaiProgress = function (name) {{
+    return progress[name] || null;
+  }};
+
+  async function openCache(data, hash) {{
+    if (!window.caches) return null;
+    try {{
+      var prefix = "bonsai-" + data + "-";
+      var keys = await caches.keys();
+      await Promise.all(keys.filter(function (key) {{
+        return key.indexOf(prefix) === 0 && key !== prefix + hash;
+      }}).map(function (key) {{ return caches.delete(key); }}));
+      return await caches.open(prefix + hash);
+    }} catch (error) {{
+      return null;
+    }}
+  }}
+
+  // a cut off chunk continues with a Range request, unless the server ignores it
+  async function fetchChunk(url, size, onBytes) {{
+    var parts = [];
+    var received = 0;
+    for (var attempt = 1; ; attempt++) {{
+      try {{
+        var headers = received > 0 ? {{ Range: "bytes=" + received + "-" }} : {{}};
+        var response = await fetch(url, {{ headers: headers, cache: "no-cache" }});
+        if (!response.ok) throw new Error(url + ": HTTP " + response.status);
+        if (received > 0 && response.status !== 206) {{
+          onBytes(-received);
+          parts = [];
+          received = 0;
+        }}
+        var reader = response.body.getReader();
+        for (;;) {{
+          var read = await reader.read();
+          if (read.done) break;
+          parts.push(read.value);
+          received += read.value.length;
+          onBytes(read.value.length);
+        }}
+        if (received === size) return await new Blob(parts).arrayBuffer();
+        if (received > size) {{
+          onBytes(-received);
+          parts = [];
+          received = 0;
+        }}
+        throw new Error(url + ": received " + received + " of " + size + " bytes");
+      }} catch (error) {{
+        if (attempt >= MAX_ATTEMPTS) throw error;
+        await new Promise(function (resolve) {{ setTimeout(resolve, 500 * attempt * attempt); }});
+      }}
+    }}
+  }}
+
+  async function fetchData(data, entry) {{
+    var cache = await openCache(data, entry.hash);
+    var bundle = new Uint8Array(entry.bytes);
+    var offset = 0;
+    var loaded = 0;
+    report(data, 0, entry.bytes);
+    for (var i = 0; i < entry.chunks.length; i++) {{
+      var url = data + "." + i;
+      var size = entry.chunks[i];
+      var cached = cache ? await cache.match(url) : null;
+      var buffer;
+      if (cached) {{
+        buffer = await cached.arrayBuffer();
+        loaded += size;
+        report(data, loaded, entry.bytes);
+      }} else {{
+        buffer = await fetchChunk(url, size, function (bytes) {{
+          loaded += bytes;
+          report(data, loaded, entry.bytes);
+        }});
+        if (cache) await cache.put(url, new Response(buffer)).catch(function () {{}});
+      }}
+      bundle.set(new Uint8Array(buffer), offset);
+      offset += size;
+    }}
+    return bundle.buffer;
+  }}
+
+  // resolves once the chunks of `data` are fetched, right away for bundles that aren't chunked
+  window.bonsaiFetchChunked = function (data) {{
+    if (!loading[data]) {{
+      loading[data] = manifest.then(function (chunks) {{
+        if (!chunks[data]) return;
+        return fetchData(data, chunks[data]).then(function (buffer) {{
+          buffers[data] = buffer;
+        }});
+      }});
+      loading[data].catch(function () {{ delete loading[data]; }});
+    }}
+    return loading[data];
+  }};
+
+  var module = window.Module || (window.Module = {{}});
+  module.getPreloadedPackage = function (remoteName) {{
+    for (var data in buffers) {{
+      if (remoteName.slice(-data.length) === data) {{
+        var buffer = buffers[data];
+        delete buffers[data];
+        return buffer;
+      }}
+    }}
+    return null;
+  }};
+
+  window.bonsaiFetchChunked(CORE).then(function () {{
+    var held = document.getElementById("{held}");
+    held.content.querySelectorAll("script").forEach(function (original) {{
+      var script = document.createElement("script");
+      for (var i = 0; i < original.attributes.length; i++) {{
+        script.setAttribute(original.attributes[i].name, original.attributes[i].value);
+      }}
+      script.async = false;
+      script.text = original.text;
+      document.body.appendChild(script);
+    }});
+  }}, function (error) {{
+    if (window.Module && Module.setStatus) Module.setStatus("Download failed: " + error.message);
+    console.error(error);
+  }});
+}})();
+</script>
+"#,
+        core = WEB_CORE_DATA,
+        manifest = WEB_CHUNKS_MANIFEST,
+        held = HELD_SCRIPT_ID,
+    )
+}
@@ -0,0 +1,208 @@
+use crate::error::CustomError;
+use crate::i18n::t;
+use crate::manifest::WebOptions;
+use crate::paths::ProjectPaths;
+use crate::staging::AssetFilter;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const WEB_PACKAGES_STAGE_DIR: &str = ".bonsai/cache/web_packages";
+pub const WEB_PACKAGES_MANIFEST: &str = "packages.json";
+// runtime methods the file_packager loaders call once the main module is already running
+pub const WEB_PACKAGE_EMCC_FLAGS: &str = "-sFORCE_FILESYSTEM=1 \
+-sEXPORTED_RUNTIME_METHODS=FS_createPath,FS_createDataFile,FS_createPreloadedFile,addRunDependency,removeRunDependency";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WebPackage {
+    pub name: String,
+    // loader script and data file, relative to the build directory; None when nothing matched
+    pub js: Option<String>,
+    pub data: Option<String>,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+pub struct StagedPackage {
+    pub package: WebPackage,
+    pub stage_dir: PathBuf,
+}
+
+// package names end up as JS keys and Odin strings, keep them boring
+pub fn web_package_names(web: &WebOptions) -> Result<Vec<String>, CustomError> {
+    for name in web.packages.keys() {
+        let is_identifier = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !is_identifier {
+            return Err(CustomError::ValidationError(t!(
+                "Invalid [web.packages] name '{}' (expected lowercase letters, digits and underscores)",
+                name
+            )));
+        }
+    }
+    Ok(web.packages.keys().cloned().collect())
+}
+
+// every package glob, excluded from the core preload bundle and the copied assets
+pub fn web_package_patterns(web: &WebOptions) -> Vec<String> {
+    web.packages.values().flatten().cloned().collect()
+}
+
+fn build_globset(name: &str, patterns: &[String]) -> Result<GlobSet, CustomError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| {
+            CustomError::ValidationError(t!(
+                "Invalid [web.packages] {} pattern '{}': {}",
+                name, pattern, e
+            ))
+        })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| {
+        CustomError::ValidationError(t!("Invalid [web.packages] {} patterns: {}", name, e))
+    })
+}
+
+/// Copies the asset files matched by each `[web.packages]` entry into its own stage directory,
+/// keeping their `assets/...` paths. A file may only belong to one package.
+pub fn stage_web_packages(
+    web: &WebOptions,
+    paths: &ProjectPaths,
+    filter: &AssetFilter,
+) -> Result<Vec<StagedPackage>, CustomError> {
+    let names = web_package_names(web)?;
+    let sets = names
+        .iter()
+        .map(|name| build_globset(name, &web.packages[name]))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut staged: Vec<StagedPackage> = names
+        .iter()
+        .map(|name| StagedPackage {
+            package: WebPackage {
+                name: name.clone(),
+                js: None,
+                data: None,
+                files: 0,
+                bytes: 0,
+            },
+            stage_dir: Path::new(WEB_PACKAGES_STAGE_DIR).join(name),
+        })
+        .collect();
+
+    for package in &staged {
+        if package.stage_dir.exists() {
+            fs::remove_dir_all(&package.stage_dir)?;
+        }
+    }
+
+    for entry in WalkDir::new(&paths.assets).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() || filter.is_excluded(entry.path()) {
+            continue;
+        }
+
+        let normalized = paths.logical(entry.path());
+        let owners: Vec<usize> = sets
+            .iter()
+            .enumerate()
+            .filter(|(_, set)| set.is_match(&normalized))
+            .map(|(i, _)| i)
+            .collect();
+
+        let owner = match owners.as_slice() {
+            [] => continue,
+            [owner] => *owner,
+            [first, second, ..] => {
+                return Err(CustomError::ValidationError(t!(
+                    "{} matches both [web.packages] {} and {}",
+                    normalized, names[*first], names[*second]
+                )));
+            }
+        };
+
+        let package = &mut staged[owner];
+        let dest = package.stage_dir.join(&normalized);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        package.package.bytes += fs::copy(entry.path(), &dest)?;
+        package.package.files += 1;
+    }
+
+    for package in &mut staged {
+        if package.package.files > 0 {
+            package.package.js = Some(format!("{}.js", package.package.name));
+            package.package.data = Some(format!("{}.data", package.package.name));
+        }
+    }
+
+    Ok(staged)
+}
+
+pub fn write_web_packages_manifest(out_dir: &Path, packages: &[WebPackage]) -> Result<(), CustomError> {
+    let json = serde_json::to_string_pretty(packages).map_err(|e| {
+        CustomError::ValidationError(t!("Failed to serialize web packages: {}", e))
+    })?;
+    fs::write(out_dir.join(WEB_PACKAGES_MANIFEST), json)?;
+    Ok(())
+}
+
+/// The `window.BONSAI_PACKAGES` table and the `bonsaiLoadPackage(name)` loader injected into the
+/// web shell. The promise resolves once the package's files are mounted in the virtual filesystem.
+pub fn web_package_script(packages: &[WebPackage]) -> Result<String, CustomError> {
+    let table: serde_json::Map<String, serde_json::Value> = packages
+        .iter()
+        .map(|p| {
+            (
+                p.name.clone(),
+                serde_json::json!({ "js": p.js, "data": p.data, "bytes": p.bytes }),
+            )
+        })
+        .collect();
+    let table = serde_json::to_string(&table).map_err(|e| {
+        CustomError::ValidationError(t!("Failed to serialize web packages: {}", e))
+    })?;
+
+    // file_packager removes the "datafile_<path>" run dependency once the data is in the FS
+    Ok(format!(
+        r#"<script>
+window.BONSAI_PACKAGES = {};
+window.bonsaiLoadPackage = function (name) {{
+  var pkg = window.BONSAI_PACKAGES[name];
+  if (!pkg) return Promise.reject(new Error("Unknown web package: " + name));
+  if (!pkg.js) return Promise.resolve();
+  if (!pkg.promise) {{
+    pkg.promise = new Promise(function (resolve, reject) {{
+      var remove = Module.removeRunDependency;
+      Module.removeRunDependency = function (id) {{
+        remove(id);
+        if (id.slice(-pkg.data.length) === pkg.data) resolve();
+      }};
+      var script = document.createElement("script");
+      script.src = pkg.js;
+      script.onerror = function () {{
+        pkg.promise = null;
+        reject(new Error("Failed to fetch web package: " + name));
+      }};
+      // with [web] chunk_mb the data is fetched in chunks first, the loader then takes it from memory
+      var fetched = window.bonsaiFetchChunked ? window.bonsaiFetchChunked(pkg.data) : Promise.resolve();
+      fetched.then(function () {{
+        document.body.appendChild(script);
+      }}, function (error) {{
+        pkg.promise = null;
+        reject(error);
+      }});
+    }});
+  }}
+  return pkg.promise;
+}};
+</script>
+"#,
+        table
+    ))
+}
@@ -0,0 +1,96 @@
+use crate::Ui;
+use crate::i18n::{t, tr};
+use colored::Colorize;
+use std::env;
+use std::fs;
+use std::path::{Component, Path};
+use std::process::{Command, Stdio};
+
+const WSL_SHARE_PREFIXES: &[&str] = &[
+    r"\\wsl$\",
+    r"\\wsl.localhost\",
+    r"\\?\unc\wsl$\",
+    r"\\?\unc\wsl.localhost\",
+];
+
+pub fn is_wsl() -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+
+    if env::var_os("WSL_DISTRO_NAME").is_some() || env::var_os("WSL_INTEROP").is_some() {
+        return true;
+    }
+
+    fs::read_to_string("/proc/sys/kernel/osrelease")
+        .map(|release| release.to_lowercase().contains("microsoft"))
+        .unwrap_or(false)
+}
+
+// drvfs mounts look like /mnt/c/...
+fn is_windows_mount(path: &Path) -> bool {
+    let mut components = path.components();
+
+    matches!(
+        (components.next(), components.next(), components.next()),
+        (Some(Component::RootDir), Some(Component::Normal(mnt)), Some(Component::Normal(drive)))
+            if mnt == "mnt"
+                && drive.len() == 1
+                && drive.to_str().is_some_and(|d| d.chars().all(|c| c.is_ascii_alphabetic()))
+    )
+}
+
+fn is_wsl_share(path: &Path) -> bool {
+    let normalized = path.to_string_lossy().to_lowercase().replace('/', "\\");
+
+    WSL_SHARE_PREFIXES
+        .iter()
+        .any(|prefix| normalized.starts_with(prefix))
+}
+
+pub fn to_windows_path(path: &Path) -> Option<String> {
+    let output = Command::new("wslpath")
+        .arg("-w")
+        .arg(path)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn check_project_location(project_dir: &Path, ui: &Ui) {
+    let project_abs = project_dir
+        .canonicalize()
+        .unwrap_or_else(|_| project_dir.to_path_buf());
+
+    if is_wsl() && is_windows_mount(&project_abs) {
+        ui.message(&t!(
+            "{} The project lives on a Windows drive ({}) but bonsai runs inside WSL. \
+            File access across the boundary is very slow and file watching may be unreliable.",
+            "[WARNING]".yellow(),
+            project_abs.display()
+        ));
+
+        match to_windows_path(&project_abs) {
+            Some(windows_path) => ui.message(&t!(
+                "  Move the project into the Linux filesystem (e.g. ~/projects), or run the Windows build of bonsai from '{}'.",
+                windows_path
+            )),
+            None => ui.message(tr(
+                "  Move the project into the Linux filesystem (e.g. ~/projects), or run the Windows build of bonsai.",
+            )),
+        }
+    } else if cfg!(windows) && is_wsl_share(&project_abs) {
+        ui.message(&t!(
+            "{} The project lives inside a WSL filesystem ({}) but bonsai runs on Windows. \
+            File access across the boundary is very slow; run bonsai from inside WSL instead.",
+            "[WARNING]".yellow(),
+            project_abs.display()
+        ));
+    }
+}